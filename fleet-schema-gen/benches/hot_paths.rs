@@ -0,0 +1,151 @@
+//! Benchmarks for the paths the language server runs on every keystroke.
+//!
+//! - lint: a team file with 500 policies
+//! - completion: `path:` completion over a 10,000-file workspace index
+//! - hover: context resolution deep in a large document
+//!
+//! `cargo bench` measures every benchmark (`cargo bench -- hover` runs the
+//! ones whose name contains `hover`); under `cargo test --benches` each
+//! runs once as a smoke test. Results are the median and fastest time per
+//! iteration over a number of samples.
+
+use fleet_schema_gen::linter::Linter;
+use fleet_schema_gen::lsp::completion::complete_at_with_index;
+use fleet_schema_gen::lsp::hover::hover_in_file;
+use fleet_schema_gen::lsp::workspace::FileIndex;
+use std::fmt::Write;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tower_lsp::lsp_types::Position;
+
+const POLICIES: usize = 500;
+const WORKSPACE_FILES: usize = 10_000;
+const PLATFORMS: &[&str] = &["darwin", "windows", "linux", "chrome"];
+
+/// Time spent measuring each benchmark
+const MEASUREMENT_TIME: Duration = Duration::from_secs(3);
+const SAMPLES: u32 = 50;
+
+struct Runner {
+    measure: bool,
+    filter: Option<String>,
+}
+
+impl Runner {
+    fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        Self {
+            measure: args.iter().any(|arg| arg == "--bench"),
+            filter: args.into_iter().find(|arg| !arg.starts_with('-')),
+        }
+    }
+
+    fn bench<T>(&self, name: &str, mut routine: impl FnMut() -> T) {
+        if self.filter.as_deref().is_some_and(|filter| !name.contains(filter)) {
+            return;
+        }
+        if !self.measure {
+            black_box(routine());
+            println!("{:<40} ok", name);
+            return;
+        }
+
+        // Size samples so the whole run takes about MEASUREMENT_TIME
+        let start = Instant::now();
+        black_box(routine());
+        let once = start.elapsed().max(Duration::from_nanos(1));
+        let per_sample = (MEASUREMENT_TIME / SAMPLES).as_nanos() / once.as_nanos();
+        let iterations = per_sample.clamp(1, 1_000_000) as u32;
+
+        let mut samples: Vec<Duration> = (0..SAMPLES)
+            .map(|_| {
+                let start = Instant::now();
+                for _ in 0..iterations {
+                    black_box(routine());
+                }
+                start.elapsed() / iterations
+            })
+            .collect();
+        samples.sort();
+        println!(
+            "{:<40} median {:>12?}   fastest {:>12?}   ({} x {} iterations)",
+            name,
+            samples[samples.len() / 2],
+            samples[0],
+            SAMPLES,
+            iterations
+        );
+    }
+}
+
+/// A team file with `POLICIES` inline policies spread across platforms
+fn large_team_file() -> String {
+    let mut out = String::from("name: Workstations\npolicies:\n");
+    for idx in 0..POLICIES {
+        let platform = PLATFORMS[idx % PLATFORMS.len()];
+        let _ = write!(
+            out,
+            "  - name: Policy {idx}\n    description: Checks setting {idx}\n    \
+             query: SELECT 1 FROM os_version WHERE major >= {idx} AND name LIKE '%{platform}%';\n    \
+             platform: {platform}\n    critical: {}\n    resolution: Update the device.\n",
+            idx % 7 == 0
+        );
+    }
+    out.push_str("queries:\n  - name: Uptime\n    query: SELECT * FROM uptime;\n    interval: 3600\n");
+    out
+}
+
+/// Lay out `WORKSPACE_FILES` lib and team files under `root`
+fn populate_workspace(root: &Path) {
+    let kinds = ["policies", "queries", "scripts", "software", "configuration-profiles"];
+    for idx in 0..WORKSPACE_FILES {
+        let platform = PLATFORMS[idx % PLATFORMS.len()];
+        let kind = kinds[(idx / PLATFORMS.len()) % kinds.len()];
+        let file = match kind {
+            "scripts" => format!("lib/{platform}/scripts/group-{}/script-{idx}.sh", idx % 20),
+            "configuration-profiles" => format!("lib/{platform}/configuration-profiles/profile-{idx}.mobileconfig"),
+            _ => format!("lib/{platform}/{kind}/group-{}/{kind}-{idx}.yml", idx % 20),
+        };
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+    fs::create_dir_all(root.join("teams")).unwrap();
+}
+
+fn main() {
+    let runner = Runner::from_args();
+
+    let linter = Linter::new();
+    let team = large_team_file();
+    let team_path = Path::new("teams/workstations.yml");
+    runner.bench("lint/team_file_500_policies", || linter.lint_content(&team, team_path).unwrap());
+
+    let workspace = tempfile::tempdir().unwrap();
+    populate_workspace(workspace.path());
+    let current_file = workspace.path().join("teams/workstations.yml");
+    let source = "name: Workstations\npolicies:\n  - path: ../lib/darwin/pol\n";
+    let position = Position::new(2, "  - path: ../lib/darwin/pol".len() as u32);
+
+    let index = FileIndex::new(workspace.path());
+    runner.bench("completion/path_10k_files_warm_index", || {
+        complete_at_with_index(source, position, Some(&current_file), Some(&index))
+    });
+    runner.bench("completion/path_10k_files_cold_index", || {
+        index.invalidate();
+        complete_at_with_index(source, position, Some(&current_file), Some(&index))
+    });
+
+    // Near the end, so resolving the enclosing keys walks the whole file
+    let lines: Vec<&str> = team.lines().collect();
+    let last_platform = lines.iter().rposition(|line| line.trim_start().starts_with("platform:")).unwrap();
+    let last_query = lines.iter().rposition(|line| line.trim_start().starts_with("query:")).unwrap();
+    runner.bench("hover/field_500_policies", || {
+        hover_in_file(&team, Position::new(last_platform as u32, 6), Some(team_path))
+    });
+    runner.bench("hover/osquery_table_500_policies", || {
+        hover_in_file(&team, Position::new(last_query as u32, 27), Some(team_path))
+    });
+}
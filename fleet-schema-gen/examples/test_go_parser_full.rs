@@ -69,11 +69,11 @@ async fn main() -> anyhow::Result<()> {
             println!("  Fields: {}", go_struct.fields.len());
 
             for field in go_struct.fields.iter().take(5) {
-                println!("    - {} : {} (json: {:?}, omitempty: {})",
+                println!("    - {} : {} (json: {:?}, yaml: {:?})",
                     field.name,
                     field.go_type,
                     field.json_tag,
-                    field.omitempty
+                    field.yaml_tag
                 );
                 total_fields += 1;
             }
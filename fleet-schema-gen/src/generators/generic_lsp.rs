@@ -0,0 +1,124 @@
+//! Ready-to-paste LSP client configuration for editors without a dedicated
+//! generator (Kate, Sublime Text's LSP package, Helix, Lapce).
+//!
+//! Each snippet starts `fleet-schema-gen lsp` for YAML files; the README
+//! collects them with the path each one goes to.
+
+use anyhow::Result;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+pub fn generate(output_dir: &Path) -> Result<()> {
+    tracing::info!("=== Generating Generic LSP Client Configuration ===");
+
+    fs::create_dir_all(output_dir)?;
+
+    let kate = serde_json::to_string_pretty(&kate_settings())?;
+    fs::write(output_dir.join("kate-lspclient.json"), &kate)?;
+    tracing::debug!("  ✓ kate-lspclient.json");
+
+    // Enabled: pasted into user settings on purpose, unlike the package's copy
+    let sublime = serde_json::to_string_pretty(&super::sublime::lsp_client_settings(true))?;
+    fs::write(output_dir.join("LSP.sublime-settings"), &sublime)?;
+    tracing::debug!("  ✓ LSP.sublime-settings");
+
+    fs::write(output_dir.join("helix-languages.toml"), HELIX_LANGUAGES)?;
+    tracing::debug!("  ✓ helix-languages.toml");
+
+    fs::write(output_dir.join("README.md"), readme(&kate, &sublime))?;
+    tracing::debug!("  ✓ README.md");
+
+    tracing::info!("✓ LSP client configuration generated at: {}", output_dir.display());
+
+    Ok(())
+}
+
+/// Kate's LSP client settings (`servers` keyed by language).
+fn kate_settings() -> serde_json::Value {
+    json!({
+        "servers": {
+            "yaml": {
+                "command": ["fleet-schema-gen", "lsp"],
+                "root": "",
+                "rootIndicationFileNames": ["default.yml", ".fleetlint.toml"],
+                "highlightingModeRegex": "^YAML$"
+            }
+        }
+    })
+}
+
+const HELIX_LANGUAGES: &str = r#"[language-server.fleet-lsp]
+command = "fleet-schema-gen"
+args = ["lsp"]
+
+[[language]]
+name = "yaml"
+language-servers = ["fleet-lsp", "yaml-language-server"]
+"#;
+
+fn readme(kate: &str, sublime: &str) -> String {
+    format!(
+        r#"# Fleet GitOps - LSP Client Configuration
+
+Snippets that start `fleet-schema-gen lsp` for YAML files. The binary must be
+on `PATH`; otherwise replace `fleet-schema-gen` with its full path.
+
+## Kate
+
+**Settings → Configure Kate → LSP Client → User Server Settings**
+(`~/.config/kate/lspclient/settings.json`). This replaces Kate's default YAML
+server; the root is the nearest directory with `default.yml` or
+`.fleetlint.toml`.
+
+```json
+{kate}
+```
+
+## Sublime Text (LSP package)
+
+**Preferences → Package Settings → LSP → Settings**. To limit the server to
+Fleet repositories, set `"enabled": false` here and enable `fleet-lsp` in the
+project's settings (see the `sublime` generator).
+
+```json
+{sublime}
+```
+
+## Helix
+
+`~/.config/helix/languages.toml` (or `.helix/languages.toml` in the repo):
+
+```toml
+{helix}```
+
+## Lapce
+
+Lapce starts language servers through plugins (volts) only. Use a plugin that
+accepts a custom server command and point it at `fleet-schema-gen` with the
+argument `lsp`.
+"#,
+        kate = kate,
+        sublime = sublime,
+        helix = HELIX_LANGUAGES,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_snippets() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        generate(temp_dir.path()).unwrap();
+
+        let kate: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(temp_dir.path().join("kate-lspclient.json")).unwrap()).unwrap();
+        assert_eq!(kate["servers"]["yaml"]["command"], json!(["fleet-schema-gen", "lsp"]));
+
+        let readme = fs::read_to_string(temp_dir.path().join("README.md")).unwrap();
+        assert!(readme.contains("[language-server.fleet-lsp]"));
+        assert!(readme.contains("\"enabled\": true"));
+    }
+}
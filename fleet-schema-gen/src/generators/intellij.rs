@@ -5,7 +5,7 @@ use serde_json::json;
 use crate::schema::types::FleetSchema;
 
 pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n=== Generating IntelliJ IDEA Configuration ===");
+    tracing::info!("=== Generating IntelliJ IDEA Configuration ===");
 
     fs::create_dir_all(output_dir)?;
 
@@ -27,13 +27,144 @@ pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
     // 6. Generate README
     generate_readme(output_dir)?;
 
-    println!("✓ IntelliJ IDEA configuration generated at: {}", output_dir.display());
+    tracing::info!("✓ IntelliJ IDEA configuration generated at: {}", output_dir.display());
+
+    Ok(())
+}
+
+/// Scaffold a minimal IntelliJ plugin that runs `fleet-schema-gen lsp`
+/// through LSP4IJ, so JetBrains IDEs get the linter's rules, hover docs and
+/// quick fixes on top of JSON Schema validation.
+///
+/// Build it with `gradle buildPlugin` (no wrapper is generated) and install
+/// the zip from disk.
+pub fn generate_plugin(output_dir: &Path) -> Result<()> {
+    tracing::info!("=== Generating IntelliJ Plugin Scaffold ===");
+
+    let resources_dir = output_dir.join("src/main/resources/META-INF");
+    let source_dir = output_dir.join("src/main/kotlin/com/fleetdm/gitops");
+    fs::create_dir_all(&resources_dir)?;
+    fs::create_dir_all(&source_dir)?;
+
+    let settings_gradle = r#"rootProject.name = "fleet-gitops-lsp"
+"#;
+    fs::write(output_dir.join("settings.gradle.kts"), settings_gradle)?;
+
+    let build_gradle = r#"plugins {
+    id("org.jetbrains.kotlin.jvm") version "2.0.21"
+    id("org.jetbrains.intellij.platform") version "2.2.1"
+}
+
+group = "com.fleetdm.gitops"
+version = "0.1.0"
+
+repositories {
+    mavenCentral()
+    intellijPlatform {
+        defaultRepositories()
+    }
+}
+
+dependencies {
+    intellijPlatform {
+        intellijIdeaCommunity("2024.3")
+        // Language server client
+        plugin("com.redhat.devtools.lsp4ij:0.9.0")
+        bundledPlugin("org.jetbrains.plugins.yaml")
+    }
+}
+
+kotlin {
+    jvmToolchain(17)
+}
+"#;
+    fs::write(output_dir.join("build.gradle.kts"), build_gradle)?;
+    tracing::debug!("    ✓ Gradle build");
+
+    let plugin_xml = r#"<idea-plugin>
+  <id>com.fleetdm.gitops.lsp</id>
+  <name>Fleet GitOps</name>
+  <vendor>Generated by fleet-schema-gen</vendor>
+  <description>Fleet GitOps YAML validation, hover docs and quick fixes via fleet-schema-gen lsp.</description>
+
+  <depends>com.intellij.modules.platform</depends>
+  <depends>org.jetbrains.plugins.yaml</depends>
+  <depends>com.redhat.devtools.lsp4ij</depends>
+
+  <extensions defaultExtensionNs="com.redhat.devtools.lsp4ij">
+    <server id="fleetLsp"
+            name="Fleet GitOps"
+            factoryClass="com.fleetdm.gitops.FleetLanguageServerFactory">
+      <description><![CDATA[Runs <code>fleet-schema-gen lsp</code>.]]></description>
+    </server>
+    <!-- Every YAML file; the server only reports Fleet GitOps findings -->
+    <languageMapping language="yaml" serverId="fleetLsp" languageId="yaml"/>
+  </extensions>
+</idea-plugin>
+"#;
+    fs::write(resources_dir.join("plugin.xml"), plugin_xml)?;
+    tracing::debug!("    ✓ plugin.xml");
+
+    let factory = r#"package com.fleetdm.gitops
+
+import com.intellij.execution.configurations.GeneralCommandLine
+import com.intellij.openapi.project.Project
+import com.redhat.devtools.lsp4ij.LanguageServerFactory
+import com.redhat.devtools.lsp4ij.server.OSProcessStreamConnectionProvider
+import com.redhat.devtools.lsp4ij.server.StreamConnectionProvider
+
+/** Starts `fleet-schema-gen lsp` in the project directory. */
+class FleetLanguageServerFactory : LanguageServerFactory {
+    override fun createConnectionProvider(project: Project): StreamConnectionProvider =
+        FleetLanguageServer(project)
+}
+
+class FleetLanguageServer(project: Project) : OSProcessStreamConnectionProvider() {
+    init {
+        // FLEET_SCHEMA_GEN overrides the binary when it isn't on PATH
+        val binary = System.getenv("FLEET_SCHEMA_GEN") ?: "fleet-schema-gen"
+        commandLine = GeneralCommandLine(binary, "lsp").withWorkDirectory(project.basePath)
+    }
+}
+"#;
+    fs::write(source_dir.join("FleetLanguageServer.kt"), factory)?;
+    tracing::debug!("    ✓ FleetLanguageServer.kt");
+
+    let readme = r#"# Fleet GitOps - IntelliJ Plugin
+
+Minimal plugin that connects JetBrains IDEs to `fleet-schema-gen lsp` via
+[LSP4IJ](https://plugins.jetbrains.com/plugin/23257-lsp4ij). Unlike the JSON
+schemas alone, this reports the linter's rules (query syntax, references,
+platform checks), shows hover docs and offers quick fixes.
+
+## Build and install
+
+No Gradle wrapper is included; build with Gradle 8.5 or newer:
+
+```bash
+gradle buildPlugin
+```
+
+(`gradle wrapper` adds a `./gradlew` to pin the Gradle version.)
+
+Then **Settings → Plugins → ⚙ → Install Plugin from Disk** and pick
+`build/distributions/fleet-gitops-lsp-0.1.0.zip`. LSP4IJ is installed as a
+dependency.
+
+`fleet-schema-gen` must be on `PATH`, or set `FLEET_SCHEMA_GEN` to the binary.
+The server attaches to YAML files; disable it for other projects under
+**Languages & Frameworks → Language Servers**.
+"#;
+    fs::write(output_dir.join("README.md"), readme)?;
+    tracing::debug!("    ✓ README.md");
+
+    tracing::info!("✓ IntelliJ plugin scaffold generated at: {}", output_dir.display());
 
     Ok(())
 }
 
 fn generate_schemas(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating JSON schemas for IntelliJ...");
+    tracing::info!("  → Generating JSON schemas for IntelliJ...");
 
     let schemas_dir = output_dir.join("schemas");
     fs::create_dir_all(&schemas_dir)?;
@@ -57,14 +188,14 @@ fn generate_schemas(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
         let json = serde_json::to_string_pretty(&output_schema)?;
         fs::write(schemas_dir.join(filename), json)?;
 
-        println!("    ✓ {}", filename);
+        tracing::debug!("    ✓ {}", filename);
     }
 
     Ok(())
 }
 
 fn generate_schema_mappings(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating schema mappings...");
+    tracing::info!("  → Generating schema mappings...");
 
     // IntelliJ schema mappings are configured in .idea/jsonSchemas.xml
     let mappings = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -172,13 +303,13 @@ fn generate_schema_mappings(output_dir: &Path) -> Result<()> {
     fs::create_dir_all(&idea_dir)?;
     fs::write(idea_dir.join("jsonSchemas.xml"), mappings)?;
 
-    println!("    ✓ jsonSchemas.xml");
+    tracing::debug!("    ✓ jsonSchemas.xml");
 
     Ok(())
 }
 
 fn generate_live_templates(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating live templates (snippets)...");
+    tracing::info!("  → Generating live templates (snippets)...");
 
     // IntelliJ Live Templates are stored in XML format
     let templates = r#"<templateSet group="Fleet GitOps">
@@ -238,13 +369,13 @@ fn generate_live_templates(output_dir: &Path) -> Result<()> {
     fs::create_dir_all(&templates_dir)?;
     fs::write(templates_dir.join("Fleet-GitOps.xml"), templates)?;
 
-    println!("    ✓ Fleet-GitOps.xml (live templates)");
+    tracing::debug!("    ✓ Fleet-GitOps.xml (live templates)");
 
     Ok(())
 }
 
 fn generate_file_templates(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating file templates...");
+    tracing::info!("  → Generating file templates...");
 
     let file_templates_dir = output_dir.join("fileTemplates");
     fs::create_dir_all(&file_templates_dir)?;
@@ -262,7 +393,7 @@ spec:
 "#;
 
     fs::write(file_templates_dir.join("Fleet Policy.yml"), policy_template)?;
-    println!("    ✓ Fleet Policy.yml");
+    tracing::debug!("    ✓ Fleet Policy.yml");
 
     // Query file template
     let query_template = r#"---
@@ -276,7 +407,7 @@ spec:
 "#;
 
     fs::write(file_templates_dir.join("Fleet Query.yml"), query_template)?;
-    println!("    ✓ Fleet Query.yml");
+    tracing::debug!("    ✓ Fleet Query.yml");
 
     // Team file template
     let team_template = r#"---
@@ -302,13 +433,13 @@ spec:
 "#;
 
     fs::write(file_templates_dir.join("Fleet Team.yml"), team_template)?;
-    println!("    ✓ Fleet Team.yml");
+    tracing::debug!("    ✓ Fleet Team.yml");
 
     Ok(())
 }
 
 fn generate_project_settings(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating project settings...");
+    tracing::info!("  → Generating project settings...");
 
     let idea_dir = output_dir.join(".idea");
     fs::create_dir_all(&idea_dir)?;
@@ -326,7 +457,7 @@ fn generate_project_settings(output_dir: &Path) -> Result<()> {
 "#;
 
     fs::write(idea_dir.join("misc.xml"), misc_xml)?;
-    println!("    ✓ misc.xml");
+    tracing::debug!("    ✓ misc.xml");
 
     // Generate modules.xml
     let modules_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -340,7 +471,7 @@ fn generate_project_settings(output_dir: &Path) -> Result<()> {
 "#;
 
     fs::write(idea_dir.join("modules.xml"), modules_xml)?;
-    println!("    ✓ modules.xml");
+    tracing::debug!("    ✓ modules.xml");
 
     // Generate fleet-gitops.iml
     let iml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -357,13 +488,13 @@ fn generate_project_settings(output_dir: &Path) -> Result<()> {
 "#;
 
     fs::write(idea_dir.join("fleet-gitops.iml"), iml)?;
-    println!("    ✓ fleet-gitops.iml");
+    tracing::debug!("    ✓ fleet-gitops.iml");
 
     Ok(())
 }
 
 fn generate_readme(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating README...");
+    tracing::info!("  → Generating README...");
 
     let readme = r#"# Fleet GitOps - IntelliJ IDEA Configuration
 
@@ -377,6 +508,9 @@ Auto-generated IntelliJ IDEA configuration for Fleet GitOps YAML editing.
 - ✅ File templates for creating new Fleet configurations
 - ✅ Project structure recognition
 
+For the linter's rules, hover docs and quick fixes, also build the plugin in
+`plugin/` (`fleet-schema-gen generate --editor intellij-plugin`).
+
 ## Installation
 
 ### Method 1: Copy to Project
@@ -550,7 +684,39 @@ fleet-schema-gen generate --editor intellij --output .
 "#;
 
     fs::write(output_dir.join("README.md"), readme)?;
-    println!("    ✓ README.md");
+    tracing::debug!("    ✓ README.md");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_plugin() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        generate_plugin(temp_dir.path()).unwrap();
+
+        let files: Vec<String> = crate::linter::walk::tree_files(temp_dir.path())
+            .unwrap()
+            .iter()
+            .map(|path| path.strip_prefix(temp_dir.path()).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                "README.md",
+                "build.gradle.kts",
+                "settings.gradle.kts",
+                "src/main/kotlin/com/fleetdm/gitops/FleetLanguageServer.kt",
+                "src/main/resources/META-INF/plugin.xml",
+            ]
+        );
+
+        // The README only uses what was generated
+        let readme = fs::read_to_string(temp_dir.path().join("README.md")).unwrap();
+        assert!(readme.contains("gradle buildPlugin"));
+        assert!(!readme.contains("./gradlew buildPlugin"));
+    }
+}
@@ -0,0 +1,26 @@
+//! Full schema for the language server.
+//!
+//! Writes the merged [`FleetSchema`] as is, so hover and completion docs
+//! (`lsp::schema`) come from the same sources as the editor schemas.
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use crate::schema::types::FleetSchema;
+
+/// File name the LSP looks for in a versioned schema directory.
+pub const SCHEMA_FILE: &str = "fleet-schema.json";
+
+pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
+    tracing::info!("=== Generating LSP Schema ===");
+
+    fs::create_dir_all(output_dir)?;
+
+    let json = serde_json::to_string_pretty(schema)?;
+    fs::write(output_dir.join(SCHEMA_FILE), json + "\n")?;
+
+    tracing::debug!("  ✓ {}", SCHEMA_FILE);
+    tracing::info!("✓ LSP schema generated at: {}", output_dir.display());
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+//! Markdown reference of every documented Fleet GitOps field.
+//!
+//! Uses the same field docs as LSP hover (`lsp::schema`), so the page
+//! matches what editors show. The output has no timestamps and fields keep
+//! schema order, so references for two Fleet versions diff cleanly.
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use std::fs;
+use std::path::Path;
+use crate::lsp::schema::{docs_from_schema, FieldDoc};
+use crate::schema::types::FleetSchema;
+
+/// Name of the generated page.
+pub const REFERENCE_FILE: &str = "fleet-gitops-reference.md";
+
+pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
+    tracing::info!("=== Generating Markdown Reference ===");
+
+    fs::create_dir_all(output_dir)?;
+    fs::write(output_dir.join(REFERENCE_FILE), render(schema))?;
+
+    tracing::debug!("  ✓ {}", REFERENCE_FILE);
+    tracing::info!("✓ Markdown reference generated at: {}", output_dir.display());
+
+    Ok(())
+}
+
+/// The reference page for a schema.
+pub fn render(schema: &FleetSchema) -> String {
+    let docs = docs_from_schema(schema);
+
+    // Top-level key -> fields under it, in schema order
+    let mut sections: IndexMap<&str, Vec<(&str, &FieldDoc)>> = IndexMap::new();
+    for (path, doc) in &docs {
+        let section = path.split('.').next().unwrap_or(path);
+        sections.entry(section).or_default().push((path, doc));
+    }
+
+    let mut md = String::from("# Fleet GitOps YAML reference\n\n");
+    md.push_str(&format!(
+        "Fleet {}. Generated by `fleet-schema-gen generate --editor docs` from: {}.\n\n",
+        schema.metadata.fleet_version,
+        schema.metadata.sources.join(", ")
+    ));
+
+    for section in sections.keys() {
+        md.push_str(&format!("- [`{}`](#{})\n", section, anchor(section)));
+    }
+
+    for (section, fields) in &sections {
+        md.push_str(&format!("\n## {}\n", section));
+        for (path, doc) in fields {
+            md.push_str(&render_field(path, doc));
+        }
+    }
+
+    md
+}
+
+fn render_field(path: &str, doc: &FieldDoc) -> String {
+    let mut md = format!("\n### `{}`\n\n{}\n\n", path, doc.description);
+
+    md.push_str(&format!("- **Type:** `{}`\n", doc.field_type));
+    md.push_str(&format!("- **Required:** {}\n", if doc.required { "yes" } else { "no" }));
+    if let Some(values) = &doc.valid_values {
+        let values: Vec<String> = values.iter().map(|v| format!("`{}`", v)).collect();
+        md.push_str(&format!("- **Valid values:** {}\n", values.join(", ")));
+    }
+    if let Some(url) = &doc.docs_url {
+        md.push_str(&format!("- **Fleet docs:** <{}>\n", url));
+    }
+
+    if let Some(example) = &doc.example {
+        md.push_str(&format!("\n```yaml\n{}\n```\n", example));
+    }
+
+    md
+}
+
+/// GitHub-style heading anchor.
+fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_' || *c == ' ')
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_reference() {
+        let md = render(&crate::linter::bundled::fleet_schema());
+
+        assert!(md.starts_with("# Fleet GitOps YAML reference\n\nFleet 4.74.0."));
+        assert!(md.contains("- [`policies`](#policies)\n"));
+        assert!(md.contains("- [`software_lib`](#software_lib)\n"));
+        assert!(md.contains(
+            "\n### `policies.platform`\n\nThe operating system(s) this policy applies to. The query must use tables available on this platform.\n\n\
+             - **Type:** `string`\n- **Required:** no\n- **Valid values:** `darwin`, `windows`, `linux`, `chrome`\n"
+        ));
+        assert!(md.contains("### `policies.name`\n") && md.contains("- **Required:** yes\n"));
+        assert!(md.contains("\n```yaml\nplatform: darwin\n```\n"));
+    }
+}
@@ -4,3 +4,6 @@ pub mod sublime;
 pub mod sublime_lsp;
 pub mod intellij;
 pub mod neovim;
+pub mod lsp;
+pub mod markdown;
+pub mod generic_lsp;
@@ -5,7 +5,7 @@ use serde_json::json;
 use crate::schema::types::FleetSchema;
 
 pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n=== Generating Neovim Configuration ===");
+    tracing::info!("=== Generating Neovim Configuration ===");
 
     fs::create_dir_all(output_dir)?;
 
@@ -33,13 +33,13 @@ pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
     // 8. Generate README
     generate_readme(output_dir)?;
 
-    println!("✓ Neovim configuration generated at: {}", output_dir.display());
+    tracing::info!("✓ Neovim configuration generated at: {}", output_dir.display());
 
     Ok(())
 }
 
 fn generate_schemas(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating JSON schemas...");
+    tracing::info!("  → Generating JSON schemas...");
 
     let schemas_dir = output_dir.join("schemas");
     fs::create_dir_all(&schemas_dir)?;
@@ -60,14 +60,14 @@ fn generate_schemas(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
         let json = serde_json::to_string_pretty(&output_schema)?;
         fs::write(schemas_dir.join(filename), json)?;
 
-        println!("    ✓ {}", filename);
+        tracing::debug!("    ✓ {}", filename);
     }
 
     Ok(())
 }
 
 fn generate_lsp_config(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating LSP configuration (nvim-lspconfig)...");
+    tracing::info!("  → Generating LSP configuration (nvim-lspconfig)...");
 
     // Lua configuration for yamlls with JSON schemas
     let lsp_config = r#"-- Fleet GitOps YAML LSP Configuration
@@ -108,13 +108,13 @@ lspconfig.yamlls.setup {
 "#;
 
     fs::write(output_dir.join("lspconfig.lua"), lsp_config)?;
-    println!("    ✓ lspconfig.lua");
+    tracing::debug!("    ✓ lspconfig.lua");
 
     Ok(())
 }
 
 fn generate_schemastore_config(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating schemastore configuration...");
+    tracing::info!("  → Generating schemastore configuration...");
 
     // Configuration for vim-yaml-schemastore or b0o/schemastore.nvim
     let schemastore_config = r#"-- Fleet GitOps Schema Store Configuration
@@ -146,13 +146,13 @@ require('lspconfig').yamlls.setup {
 "#;
 
     fs::write(output_dir.join("schemastore.lua"), schemastore_config)?;
-    println!("    ✓ schemastore.lua");
+    tracing::debug!("    ✓ schemastore.lua");
 
     Ok(())
 }
 
 fn generate_luasnip_snippets(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating LuaSnip snippets...");
+    tracing::info!("  → Generating LuaSnip snippets...");
 
     let snippets_dir = output_dir.join("luasnip");
     fs::create_dir_all(&snippets_dir)?;
@@ -213,13 +213,13 @@ return {
 "#;
 
     fs::write(snippets_dir.join("yaml.lua"), luasnip)?;
-    println!("    ✓ yaml.lua (LuaSnip)");
+    tracing::debug!("    ✓ yaml.lua (LuaSnip)");
 
     Ok(())
 }
 
 fn generate_ultisnips_snippets(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating UltiSnips snippets...");
+    tracing::info!("  → Generating UltiSnips snippets...");
 
     let snippets_dir = output_dir.join("UltiSnips");
     fs::create_dir_all(&snippets_dir)?;
@@ -291,13 +291,13 @@ endsnippet
 "#;
 
     fs::write(snippets_dir.join("yaml.snippets"), ultisnips)?;
-    println!("    ✓ yaml.snippets (UltiSnips)");
+    tracing::debug!("    ✓ yaml.snippets (UltiSnips)");
 
     Ok(())
 }
 
 fn generate_snipmate_snippets(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating SnipMate snippets...");
+    tracing::info!("  → Generating SnipMate snippets...");
 
     let snippets_dir = output_dir.join("snippets");
     fs::create_dir_all(&snippets_dir)?;
@@ -326,13 +326,13 @@ snippet fleet-label
 "#;
 
     fs::write(snippets_dir.join("yaml.snippets"), snipmate)?;
-    println!("    ✓ yaml.snippets (SnipMate)");
+    tracing::debug!("    ✓ yaml.snippets (SnipMate)");
 
     Ok(())
 }
 
 fn generate_coc_config(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating coc.nvim configuration...");
+    tracing::info!("  → Generating coc.nvim configuration...");
 
     // coc-settings.json for coc.nvim users
     let coc_settings = json!({
@@ -352,13 +352,13 @@ fn generate_coc_config(output_dir: &Path) -> Result<()> {
     let coc_json = serde_json::to_string_pretty(&coc_settings)?;
     fs::write(output_dir.join("coc-settings.json"), coc_json)?;
 
-    println!("    ✓ coc-settings.json");
+    tracing::debug!("    ✓ coc-settings.json");
 
     Ok(())
 }
 
 fn generate_readme(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating README...");
+    tracing::info!("  → Generating README...");
 
     let readme = r#"# Fleet GitOps - Neovim Configuration
 
@@ -799,7 +799,7 @@ fleet-schema-gen generate --editor neovim --output .
 "#;
 
     fs::write(output_dir.join("README.md"), readme)?;
-    println!("    ✓ README.md");
+    tracing::debug!("    ✓ README.md");
 
     Ok(())
 }
@@ -1,45 +1,91 @@
+//! Strict validation schemas.
+//!
+//! Unlike the editor schemas, which stay permissive so new Fleet fields
+//! don't light up as errors, the strict schemas reject anything unknown:
+//!
+//! - objects with known properties get `additionalProperties: false`
+//!   (free-form maps such as agent `config` are left alone)
+//! - well-known fields get enums, integer minimums and patterns
+//!   (`interval` ≥ 1, `minimum_version` as `14.4.1`, `deadline` as a date)
+//!
+//! Constraints already present in the input, e.g. enums from
+//! `schema-defs/`, are kept as they are.
+
 use anyhow::Result;
 use std::path::Path;
 use std::fs;
-use crate::schema::types::{FleetSchema, SchemaDefinition, AdditionalProperties};
+use crate::schema::types::{AdditionalProperties, FleetSchema, SchemaDefinition, SchemaType};
+
+/// `14`, `14.4` or `14.4.1`.
+pub const VERSION_PATTERN: &str = r"^\d+(\.\d+){0,2}$";
+
+/// `2025-01-31`.
+pub const DATE_PATTERN: &str = r"^\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])$";
+
+/// Comma-separated list of osquery platforms (`darwin,linux`).
+pub const PLATFORM_PATTERN: &str = r"^(darwin|windows|linux|chrome)(,\s*(darwin|windows|linux|chrome))*$";
+
+/// Lowercase hex SHA-256 digest.
+pub const SHA256_PATTERN: &str = r"^[a-f0-9]{64}$";
 
 pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n=== Generating Strict Validation Schema ===");
+    tracing::info!("=== Generating Strict Validation Schema ===");
 
     fs::create_dir_all(output_dir)?;
 
-    // Create strict schema with additionalProperties: false
-    let mut strict_schema = schema.default_schema.clone();
+    for (file_name, strict_schema) in strict_schemas(schema) {
+        let json = serde_json::to_string_pretty(&strict_schema)?;
+        fs::write(output_dir.join(file_name), json)?;
+        tracing::debug!("  ✓ {}", file_name);
+    }
 
-    // Enable strict mode recursively
-    make_strict(&mut strict_schema);
+    tracing::info!("✓ Strict schema generated at: {}", output_dir.display());
 
-    // Update to Draft 2020-12
-    strict_schema.schema = Some("https://json-schema.org/draft/2020-12/schema".to_string());
-    strict_schema.title = Some("Fleet GitOps Default Configuration (Strict)".to_string());
-    strict_schema.description = Some(
-        "Strict JSON Schema for Fleet default.yml files with no additional properties allowed".to_string()
-    );
+    Ok(())
+}
 
-    let output_path = output_dir.join("fleet-gitops-default.strict.schema.json");
-    let json = serde_json::to_string_pretty(&strict_schema)?;
-    fs::write(&output_path, json)?;
+/// Strict variant of each schema, keyed by output file name.
+pub fn strict_schemas(schema: &FleetSchema) -> Vec<(&'static str, SchemaDefinition)> {
+    let schemas = [
+        ("fleet-gitops-default.strict.schema.json", &schema.default_schema, "Fleet GitOps Default Configuration", "default.yml files"),
+        ("fleet-gitops-team.strict.schema.json", &schema.team_schema, "Fleet GitOps Team Configuration", "team YAML files"),
+        ("fleet-gitops-policy.strict.schema.json", &schema.policy_schema, "Fleet Policy", "policy definitions"),
+        ("fleet-gitops-query.strict.schema.json", &schema.query_schema, "Fleet Query", "query definitions"),
+        ("fleet-gitops-label.strict.schema.json", &schema.label_schema, "Fleet Label", "label definitions"),
+    ];
 
-    println!("  ✓ fleet-gitops-default.strict.schema.json");
-    println!("✓ Strict schema generated at: {}", output_dir.display());
+    schemas
+        .into_iter()
+        .map(|(file_name, base, title, files)| {
+            let mut strict_schema = base.clone();
+            make_strict(&mut strict_schema);
 
-    Ok(())
+            // Update to Draft 2020-12
+            strict_schema.schema = Some("https://json-schema.org/draft/2020-12/schema".to_string());
+            strict_schema.title = Some(format!("{} (Strict)", title));
+            strict_schema.description = Some(format!(
+                "Strict JSON Schema for Fleet {} with no additional properties allowed",
+                files
+            ));
+            (file_name, strict_schema)
+        })
+        .collect()
 }
 
 fn make_strict(schema: &mut SchemaDefinition) {
-    // Set additionalProperties to false for this level
-    if schema.type_.as_ref().map(|t| matches!(t, crate::schema::types::SchemaType::Single(s) if s == "object")).unwrap_or(false) {
+    // Closed objects: only the properties the schema knows about. Objects
+    // without properties are maps and keep accepting any key.
+    if has_type(schema, "object")
+        && schema.properties.is_some()
+        && !matches!(schema.additional_properties, Some(AdditionalProperties::Schema(_)))
+    {
         schema.additional_properties = Some(AdditionalProperties::Boolean(false));
     }
 
-    // Recursively apply to properties
+    // Recursively apply to properties, adding per-field constraints
     if let Some(props) = &mut schema.properties {
-        for prop in props.values_mut() {
+        for (name, prop) in props.iter_mut() {
+            constrain_field(name, prop);
             make_strict(prop);
         }
     }
@@ -51,10 +97,13 @@ fn make_strict(schema: &mut SchemaDefinition) {
         }
     }
 
-    // Apply to array items
+    // Apply to array items and map values
     if let Some(items) = &mut schema.items {
         make_strict(items);
     }
+    if let Some(AdditionalProperties::Schema(values)) = &mut schema.additional_properties {
+        make_strict(values);
+    }
 
     // Apply to oneOf/anyOf
     if let Some(one_of) = &mut schema.one_of {
@@ -70,17 +119,79 @@ fn make_strict(schema: &mut SchemaDefinition) {
     }
 }
 
+/// Add the enum, minimum or pattern Fleet enforces for a field named `name`,
+/// unless the schema already constrains it.
+fn constrain_field(name: &str, schema: &mut SchemaDefinition) {
+    if has_type(schema, "integer") && schema.minimum.is_none() {
+        schema.minimum = match name {
+            "interval" | "host_expiry_window" => Some(1),
+            "deadline_days" | "grace_period_days" | "query_report_cap" => Some(0),
+            _ => None,
+        };
+    }
+
+    if !has_type(schema, "string") || schema.enum_.is_some() || schema.pattern.is_some() {
+        return;
+    }
+
+    let values: &[&str] = match name {
+        "logging" => &["snapshot", "differential", "differential_ignore_removals"],
+        "label_membership_type" => &["dynamic", "manual", "host_vitals"],
+        "mode" => &["voluntary", "forced"],
+        _ => &[],
+    };
+    if !values.is_empty() {
+        schema.enum_ = Some(values.iter().map(|v| serde_json::json!(v)).collect());
+        return;
+    }
+
+    schema.pattern = match name {
+        "minimum_version" => Some(VERSION_PATTERN),
+        "deadline" => Some(DATE_PATTERN),
+        "platform" => Some(PLATFORM_PATTERN),
+        "hash_sha256" => Some(SHA256_PATTERN),
+        _ => None,
+    }
+    .map(str::to_string);
+}
+
+fn has_type(schema: &SchemaDefinition, type_: &str) -> bool {
+    match &schema.type_ {
+        Some(SchemaType::Single(t)) => t == type_,
+        Some(SchemaType::Multiple(types)) => types.iter().any(|t| t == type_),
+        None => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::types::SchemaType;
+    use indexmap::IndexMap;
+    use std::path::PathBuf;
+
+    fn field(type_: &str) -> SchemaDefinition {
+        SchemaDefinition {
+            type_: Some(SchemaType::Single(type_.to_string())),
+            ..Default::default()
+        }
+    }
+
+    fn object(properties: &[(&str, SchemaDefinition)]) -> SchemaDefinition {
+        SchemaDefinition {
+            properties: Some(properties.iter().map(|(k, v)| (k.to_string(), v.clone())).collect::<IndexMap<_, _>>()),
+            ..field("object")
+        }
+    }
+
+    fn strict_field(name: &str, schema: SchemaDefinition) -> SchemaDefinition {
+        let mut parent = object(&[(name, schema)]);
+        make_strict(&mut parent);
+        parent.properties.unwrap().swap_remove(name).unwrap()
+    }
 
     #[test]
     fn test_make_strict() {
-        let mut schema = SchemaDefinition {
-            type_: Some(SchemaType::Single("object".to_string())),
-            ..Default::default()
-        };
+        let mut schema = object(&[("name", field("string"))]);
 
         make_strict(&mut schema);
 
@@ -89,4 +200,115 @@ mod tests {
             Some(AdditionalProperties::Boolean(false))
         ));
     }
+
+    #[test]
+    fn test_additional_properties_closed_recursively() {
+        let mut schema = object(&[
+            ("controls", object(&[("scripts", field("array"))])),
+            ("teams", SchemaDefinition { items: Some(Box::new(object(&[("name", field("string"))]))), ..field("array") }),
+        ]);
+        // Explicitly permissive objects with known properties are closed too
+        schema.additional_properties = Some(AdditionalProperties::Boolean(true));
+
+        make_strict(&mut schema);
+
+        let props = schema.properties.as_ref().unwrap();
+        assert!(matches!(schema.additional_properties, Some(AdditionalProperties::Boolean(false))));
+        assert!(matches!(props["controls"].additional_properties, Some(AdditionalProperties::Boolean(false))));
+        assert!(matches!(
+            props["teams"].items.as_ref().unwrap().additional_properties,
+            Some(AdditionalProperties::Boolean(false))
+        ));
+    }
+
+    #[test]
+    fn test_maps_stay_open() {
+        let config = strict_field("config", field("object"));
+        assert!(config.additional_properties.is_none());
+
+        let values = SchemaDefinition {
+            additional_properties: Some(AdditionalProperties::Schema(Box::new(field("string")))),
+            ..field("object")
+        };
+        let additional_queries = strict_field("additional_queries", values);
+        assert!(matches!(additional_queries.additional_properties, Some(AdditionalProperties::Schema(_))));
+    }
+
+    #[test]
+    fn test_enum_constraints() {
+        let logging = strict_field("logging", field("string"));
+        assert_eq!(
+            logging.enum_,
+            Some(vec![
+                serde_json::json!("snapshot"),
+                serde_json::json!("differential"),
+                serde_json::json!("differential_ignore_removals")
+            ])
+        );
+        assert_eq!(strict_field("label_membership_type", field("string")).enum_.unwrap().len(), 3);
+
+        // Enums from schema-defs win
+        let existing = SchemaDefinition { enum_: Some(vec![serde_json::json!("darwin")]), ..field("string") };
+        let platform = strict_field("platform", existing);
+        assert_eq!(platform.enum_, Some(vec![serde_json::json!("darwin")]));
+        assert!(platform.pattern.is_none());
+    }
+
+    #[test]
+    fn test_integer_minimums() {
+        assert_eq!(strict_field("interval", field("integer")).minimum, Some(1));
+        assert_eq!(strict_field("host_expiry_window", field("integer")).minimum, Some(1));
+        let nullable = SchemaDefinition {
+            type_: Some(SchemaType::Multiple(vec!["integer".to_string(), "null".to_string()])),
+            ..Default::default()
+        };
+        assert_eq!(strict_field("deadline_days", nullable).minimum, Some(0));
+
+        // Only integers, and only known fields
+        assert_eq!(strict_field("interval", field("string")).minimum, None);
+        assert_eq!(strict_field("count", field("integer")).minimum, None);
+    }
+
+    #[test]
+    fn test_pattern_constraints() {
+        let version = regex::Regex::new(VERSION_PATTERN).unwrap();
+        assert!(version.is_match("14") && version.is_match("14.4") && version.is_match("14.4.1"));
+        assert!(!version.is_match("14.4.1.2") && !version.is_match("latest") && !version.is_match("v14"));
+
+        let date = regex::Regex::new(DATE_PATTERN).unwrap();
+        assert!(date.is_match("2025-01-31"));
+        assert!(!date.is_match("2025-13-01") && !date.is_match("31-01-2025") && !date.is_match("2025-1-31"));
+
+        let platform = regex::Regex::new(PLATFORM_PATTERN).unwrap();
+        assert!(platform.is_match("darwin") && platform.is_match("darwin,linux") && platform.is_match("darwin, windows"));
+        assert!(!platform.is_match("macos") && !platform.is_match("darwin,"));
+
+        assert_eq!(strict_field("minimum_version", field("string")).pattern.as_deref(), Some(VERSION_PATTERN));
+        assert_eq!(strict_field("deadline", field("string")).pattern.as_deref(), Some(DATE_PATTERN));
+        assert_eq!(strict_field("hash_sha256", field("string")).pattern.as_deref(), Some(SHA256_PATTERN));
+
+        let existing = SchemaDefinition { pattern: Some("^x$".to_string()), ..field("string") };
+        assert_eq!(strict_field("deadline", existing).pattern.as_deref(), Some("^x$"));
+    }
+
+    /// Compares each strict schema for `testdata/strict/input.json` with the
+    /// checked-in output. Run with `UPDATE_GOLDEN=1` to accept changes.
+    #[test]
+    fn test_golden_files() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/strict");
+        let input: FleetSchema = serde_json::from_str(&fs::read_to_string(dir.join("input.json")).unwrap()).unwrap();
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+
+        for (file_name, strict_schema) in strict_schemas(&input) {
+            let actual = serde_json::to_string_pretty(&strict_schema).unwrap() + "\n";
+            let golden = dir.join(file_name);
+            if update {
+                fs::write(&golden, &actual).unwrap();
+                continue;
+            }
+            let expected = fs::read_to_string(&golden)
+                .unwrap_or_else(|_| panic!("missing {}; run with UPDATE_GOLDEN=1", golden.display()));
+            assert_eq!(actual, expected, "{} changed; run with UPDATE_GOLDEN=1 to accept", file_name);
+        }
+    }
 }
@@ -5,7 +5,7 @@ use serde_json::json;
 use crate::schema::types::FleetSchema;
 
 pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n=== Generating Sublime Text Package ===");
+    tracing::info!("=== Generating Sublime Text Package ===");
 
     fs::create_dir_all(output_dir)?;
 
@@ -27,13 +27,16 @@ pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
     // 6. Generate package metadata
     generate_package_metadata(output_dir)?;
 
-    println!("✓ Sublime Text package generated at: {}", output_dir.display());
+    // 7. Generate LSP client settings for the Fleet language server
+    generate_lsp_client_settings(output_dir)?;
+
+    tracing::info!("✓ Sublime Text package generated at: {}", output_dir.display());
 
     Ok(())
 }
 
 fn generate_schemas(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating JSON schemas for LSP-json...");
+    tracing::info!("  → Generating JSON schemas for LSP-json...");
 
     let schemas_dir = output_dir.join("schemas");
     fs::create_dir_all(&schemas_dir)?;
@@ -54,14 +57,14 @@ fn generate_schemas(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
         let json = serde_json::to_string_pretty(&output_schema)?;
         fs::write(schemas_dir.join(filename), json)?;
 
-        println!("    ✓ {}", filename);
+        tracing::debug!("    ✓ {}", filename);
     }
 
     Ok(())
 }
 
 fn generate_completions(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating Sublime completions...");
+    tracing::info!("  → Generating Sublime completions...");
 
     let completions_dir = output_dir.join("completions");
     fs::create_dir_all(&completions_dir)?;
@@ -121,7 +124,7 @@ fn generate_policy_completions(
     let json_str = serde_json::to_string_pretty(&output)?;
     fs::write(&path, json_str)?;
 
-    println!("    ✓ fleet-policies.sublime-completions");
+    tracing::debug!("    ✓ fleet-policies.sublime-completions");
 
     Ok(())
 }
@@ -156,7 +159,7 @@ fn generate_query_completions(
     let json_str = serde_json::to_string_pretty(&output)?;
     fs::write(&path, json_str)?;
 
-    println!("    ✓ fleet-queries.sublime-completions");
+    tracing::debug!("    ✓ fleet-queries.sublime-completions");
 
     Ok(())
 }
@@ -191,13 +194,13 @@ fn generate_label_completions(
     let json_str = serde_json::to_string_pretty(&output)?;
     fs::write(&path, json_str)?;
 
-    println!("    ✓ fleet-labels.sublime-completions");
+    tracing::debug!("    ✓ fleet-labels.sublime-completions");
 
     Ok(())
 }
 
 fn generate_snippets(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating Sublime snippets...");
+    tracing::info!("  → Generating Sublime snippets...");
 
     let snippets_dir = output_dir.join("snippets");
     fs::create_dir_all(&snippets_dir)?;
@@ -217,7 +220,7 @@ fn generate_snippets(output_dir: &Path) -> Result<()> {
 </snippet>"#;
 
     fs::write(snippets_dir.join("fleet-policy.sublime-snippet"), policy_snippet)?;
-    println!("    ✓ fleet-policy.sublime-snippet");
+    tracing::debug!("    ✓ fleet-policy.sublime-snippet");
 
     // Query snippet
     let query_snippet = r#"<snippet>
@@ -234,7 +237,7 @@ fn generate_snippets(output_dir: &Path) -> Result<()> {
 </snippet>"#;
 
     fs::write(snippets_dir.join("fleet-query.sublime-snippet"), query_snippet)?;
-    println!("    ✓ fleet-query.sublime-snippet");
+    tracing::debug!("    ✓ fleet-query.sublime-snippet");
 
     // Label snippet
     let label_snippet = r#"<snippet>
@@ -249,13 +252,13 @@ fn generate_snippets(output_dir: &Path) -> Result<()> {
 </snippet>"#;
 
     fs::write(snippets_dir.join("fleet-label.sublime-snippet"), label_snippet)?;
-    println!("    ✓ fleet-label.sublime-snippet");
+    tracing::debug!("    ✓ fleet-label.sublime-snippet");
 
     Ok(())
 }
 
 fn generate_syntax(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating syntax highlighting...");
+    tracing::info!("  → Generating syntax highlighting...");
 
     // Generate a simple syntax file for Fleet YAML
     let syntax = json!({
@@ -288,17 +291,21 @@ fn generate_syntax(output_dir: &Path) -> Result<()> {
     let json_str = serde_json::to_string_pretty(&syntax)?;
     fs::write(&path, json_str)?;
 
-    println!("    ✓ Fleet-GitOps.sublime-syntax");
+    tracing::debug!("    ✓ Fleet-GitOps.sublime-syntax");
 
     Ok(())
 }
 
 fn generate_project_settings(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating project settings...");
+    tracing::info!("  → Generating project settings...");
 
     let settings = json!({
         "settings": {
             "LSP": {
+                // Enabled per project; see LSP.sublime-settings
+                "fleet-lsp": {
+                    "enabled": true
+                },
                 "LSP-json": {
                     "settings": {
                         "json.schemas": [
@@ -333,13 +340,13 @@ fn generate_project_settings(output_dir: &Path) -> Result<()> {
     let json_str = serde_json::to_string_pretty(&settings)?;
     fs::write(&path, json_str)?;
 
-    println!("    ✓ fleet-gitops.sublime-project");
+    tracing::debug!("    ✓ fleet-gitops.sublime-project");
 
     Ok(())
 }
 
 fn generate_package_metadata(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating package metadata...");
+    tracing::info!("  → Generating package metadata...");
 
     // Create README for Sublime package
     let readme = r#"# Fleet GitOps - Sublime Text Package
@@ -348,6 +355,7 @@ Auto-generated Sublime Text package for Fleet GitOps YAML editing.
 
 ## Features
 
+- Fleet language server (`fleet-schema-gen lsp`): lint diagnostics, hover docs, quick fixes
 - JSON Schema validation via LSP-json
 - Auto-completion for Fleet fields
 - Code snippets for policies, queries, and labels
@@ -363,8 +371,11 @@ Auto-generated Sublime Text package for Fleet GitOps YAML editing.
 ### Setup
 
 1. Copy this package to your Sublime Text `Packages/User/` directory
-2. Open your Fleet GitOps project
-3. Enable LSP-json for YAML files in LSP settings
+2. Merge `LSP.sublime-settings` into Preferences > Package Settings > LSP > Settings
+3. Open your Fleet GitOps project via `fleet-gitops.sublime-project`, which enables `fleet-lsp`
+4. Enable LSP-json for YAML files in LSP settings
+
+`fleet-schema-gen` must be on your `PATH` (or adjust `command` in the LSP settings).
 
 ## Usage
 
@@ -388,7 +399,7 @@ This package was automatically generated. Do not edit manually.
 "#;
 
     fs::write(output_dir.join("README.md"), readme)?;
-    println!("    ✓ README.md");
+    tracing::debug!("    ✓ README.md");
 
     // Create package.json metadata
     let package_json = json!({
@@ -403,7 +414,59 @@ This package was automatically generated. Do not edit manually.
     let json_str = serde_json::to_string_pretty(&package_json)?;
     fs::write(&path, json_str)?;
 
-    println!("    ✓ package.json");
+    tracing::debug!("    ✓ package.json");
+
+    Ok(())
+}
+
+/// `LSP.sublime-settings` with a `fleet-lsp` client. With `enabled: false`
+/// the client only runs in projects that turn it on.
+pub fn lsp_client_settings(enabled: bool) -> serde_json::Value {
+    json!({
+        "clients": {
+            "fleet-lsp": {
+                "enabled": enabled,
+                "command": ["fleet-schema-gen", "lsp"],
+                "selector": "source.yaml | source.yaml.fleet",
+                "schemes": ["file"]
+            }
+        }
+    })
+}
+
+fn generate_lsp_client_settings(output_dir: &Path) -> Result<()> {
+    tracing::info!("  → Generating LSP client settings...");
+
+    // Off by default so other YAML projects are left alone; the project
+    // file turns it on for Fleet GitOps repositories
+    let settings = lsp_client_settings(false);
+
+    let path = output_dir.join("LSP.sublime-settings");
+    let json_str = serde_json::to_string_pretty(&settings)?;
+    fs::write(&path, json_str)?;
+
+    tracing::debug!("    ✓ LSP.sublime-settings");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lsp_client_enabled_per_project() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        generate(&crate::linter::bundled::fleet_schema(), temp_dir.path()).unwrap();
+
+        let read = |name: &str| -> serde_json::Value {
+            serde_json::from_str(&fs::read_to_string(temp_dir.path().join(name)).unwrap()).unwrap()
+        };
+        let client = &read("LSP.sublime-settings")["clients"]["fleet-lsp"];
+        assert_eq!(client["command"], json!(["fleet-schema-gen", "lsp"]));
+        assert_eq!(client["enabled"], json!(false));
+
+        let project = read("fleet-gitops.sublime-project");
+        assert_eq!(project["settings"]["LSP"]["fleet-lsp"]["enabled"], json!(true));
+    }
+}
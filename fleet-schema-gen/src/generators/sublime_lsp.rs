@@ -17,7 +17,7 @@ use std::path::Path;
 /// This generates the configuration needed to use `fleet-schema-gen lsp`
 /// as a language server in Sublime Text via the LSP package.
 pub fn generate(output_dir: &Path) -> Result<()> {
-    println!("\n=== Generating Sublime Text LSP Configuration ===");
+    tracing::info!("=== Generating Sublime Text LSP Configuration ===");
 
     fs::create_dir_all(output_dir)?;
 
@@ -33,14 +33,14 @@ pub fn generate(output_dir: &Path) -> Result<()> {
     // 4. Generate helper script for binary installation
     generate_install_script(output_dir)?;
 
-    println!("✓ Sublime Text LSP configuration generated at: {}", output_dir.display());
+    tracing::info!("✓ Sublime Text LSP configuration generated at: {}", output_dir.display());
 
     Ok(())
 }
 
 /// Generate LSP settings for Sublime Text.
 fn generate_lsp_settings(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating LSP client settings...");
+    tracing::info!("  → Generating LSP client settings...");
 
     // LSP.sublime-settings for user configuration
     let lsp_settings = r#"{
@@ -91,7 +91,7 @@ fn generate_lsp_settings(output_dir: &Path) -> Result<()> {
 "#;
 
     fs::write(output_dir.join("LSP.sublime-settings"), lsp_settings)?;
-    println!("    ✓ LSP.sublime-settings");
+    tracing::debug!("    ✓ LSP.sublime-settings");
 
     // Generate a more targeted configuration for Fleet files only
     let fleet_lsp_settings = r#"{
@@ -122,14 +122,14 @@ fn generate_lsp_settings(output_dir: &Path) -> Result<()> {
 "#;
 
     fs::write(output_dir.join("Fleet-LSP.sublime-settings"), fleet_lsp_settings)?;
-    println!("    ✓ Fleet-LSP.sublime-settings");
+    tracing::debug!("    ✓ Fleet-LSP.sublime-settings");
 
     Ok(())
 }
 
 /// Generate syntax/file association settings.
 fn generate_syntax_settings(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating file association settings...");
+    tracing::info!("  → Generating file association settings...");
 
     // YAML.sublime-settings to ensure YAML files use correct syntax
     let yaml_settings = r#"{
@@ -144,14 +144,14 @@ fn generate_syntax_settings(output_dir: &Path) -> Result<()> {
 "#;
 
     fs::write(output_dir.join("YAML.sublime-settings"), yaml_settings)?;
-    println!("    ✓ YAML.sublime-settings");
+    tracing::debug!("    ✓ YAML.sublime-settings");
 
     Ok(())
 }
 
 /// Generate installation README.
 fn generate_readme(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating installation guide...");
+    tracing::info!("  → Generating installation guide...");
 
     let readme = r#"# Fleet GitOps - Sublime Text LSP Integration
 
@@ -294,14 +294,14 @@ Generated by `fleet-schema-gen generate --editor sublime-lsp`
 "#;
 
     fs::write(output_dir.join("README.md"), readme)?;
-    println!("    ✓ README.md");
+    tracing::debug!("    ✓ README.md");
 
     Ok(())
 }
 
 /// Generate helper installation script.
 fn generate_install_script(output_dir: &Path) -> Result<()> {
-    println!("\n  → Generating installation helper...");
+    tracing::info!("  → Generating installation helper...");
 
     // Bash install script
     let install_sh = r#"#!/bin/bash
@@ -388,7 +388,7 @@ echo
         fs::set_permissions(output_dir.join("install.sh"), perms)?;
     }
 
-    println!("    ✓ install.sh");
+    tracing::debug!("    ✓ install.sh");
 
     Ok(())
 }
@@ -5,7 +5,7 @@ use serde_json::json;
 use crate::schema::types::FleetSchema;
 
 pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
-    println!("\n=== Generating VSCode Schemas ===");
+    tracing::info!("=== Generating VSCode Schemas ===");
 
     // Create .vscode directory structure
     let vscode_dir = output_dir.join(".vscode");
@@ -25,7 +25,7 @@ pub fn generate(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
     // Generate metadata file
     generate_metadata(schema, &schema_dir)?;
 
-    println!("✓ VSCode schemas generated in: {}/.vscode/", output_dir.display());
+    tracing::info!("✓ VSCode schemas generated in: {}/.vscode/", output_dir.display());
 
     Ok(())
 }
@@ -41,7 +41,7 @@ fn generate_schema_file(
     let json = serde_json::to_string_pretty(&output_schema)?;
     fs::write(path, json)?;
 
-    println!("  ✓ {}", path.file_name().unwrap().to_str().unwrap());
+    tracing::debug!("  ✓ {}", path.file_name().unwrap().to_str().unwrap());
 
     Ok(())
 }
@@ -68,7 +68,7 @@ fn generate_settings_file(vscode_dir: &Path) -> Result<()> {
     let json = serde_json::to_string_pretty(&settings)?;
     fs::write(&settings_path, json)?;
 
-    println!("  ✓ .vscode/settings.json");
+    tracing::debug!("  ✓ .vscode/settings.json");
 
     Ok(())
 }
@@ -86,7 +86,7 @@ fn generate_metadata(schema: &FleetSchema, output_dir: &Path) -> Result<()> {
     let json = serde_json::to_string_pretty(&metadata)?;
     fs::write(&metadata_path, json)?;
 
-    println!("  ✓ metadata.json");
+    tracing::debug!("  ✓ metadata.json");
 
     Ok(())
 }
@@ -130,7 +130,7 @@ pub fn generate_snippets(output_dir: &Path) -> Result<()> {
     let json = serde_json::to_string_pretty(&snippets)?;
     fs::write(&snippets_path, json)?;
 
-    println!("  ✓ fleet-gitops.code-snippets");
+    tracing::debug!("  ✓ fleet-gitops.code-snippets");
 
     Ok(())
 }
@@ -0,0 +1,454 @@
+//! CIS benchmark catalog and policy annotations.
+//!
+//! Policies can be mapped to benchmark controls with a structured comment
+//! inside (or directly above) the policy item:
+//!
+//! ```yaml
+//! policies:
+//!   # cis: 2.2.1
+//!   - name: macOS - Firewall enabled
+//!     query: SELECT 1 FROM alf WHERE global_state >= 1;
+//!     platform: darwin
+//! ```
+//!
+//! Multiple IDs can be separated by commas (`# cis: 2.3.3.2, 2.3.3.5`).
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A single control from a bundled benchmark.
+pub struct BenchmarkControl {
+    pub id: &'static str,
+    pub title: &'static str,
+}
+
+/// Benchmarks bundled with the linter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Benchmark {
+    #[serde(rename = "cis-macos")]
+    CisMacos,
+    #[serde(rename = "cis-windows")]
+    CisWindows,
+}
+
+impl Benchmark {
+    /// All bundled benchmarks.
+    pub const ALL: [Benchmark; 2] = [Benchmark::CisMacos, Benchmark::CisWindows];
+
+    /// Parse a benchmark name as used on the command line.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "cis-macos" | "macos" | "darwin" => Some(Benchmark::CisMacos),
+            "cis-windows" | "windows" => Some(Benchmark::CisWindows),
+            _ => None,
+        }
+    }
+
+    /// Benchmark that applies to a Fleet platform value.
+    pub fn for_platform(platform: &str) -> Option<Self> {
+        match platform.trim() {
+            "darwin" => Some(Benchmark::CisMacos),
+            "windows" => Some(Benchmark::CisWindows),
+            _ => None,
+        }
+    }
+
+    /// Human-readable benchmark name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Benchmark::CisMacos => "CIS Apple macOS 14 Sonoma",
+            Benchmark::CisWindows => "CIS Microsoft Windows 11 Enterprise",
+        }
+    }
+
+    /// Controls in this benchmark, keyed by ID.
+    pub fn controls(&self) -> &'static HashMap<&'static str, BenchmarkControl> {
+        match self {
+            Benchmark::CisMacos => &CIS_MACOS_CONTROLS,
+            Benchmark::CisWindows => &CIS_WINDOWS_CONTROLS,
+        }
+    }
+
+    /// Look up a control by ID.
+    pub fn control(&self, id: &str) -> Option<&'static BenchmarkControl> {
+        self.controls().get(id)
+    }
+}
+
+/// Build a catalog map from `(id, title)` pairs.
+fn catalog(entries: &[(&'static str, &'static str)]) -> HashMap<&'static str, BenchmarkControl> {
+    entries
+        .iter()
+        .map(|(id, title)| (*id, BenchmarkControl { id, title }))
+        .collect()
+}
+
+/// Automated controls from the CIS Apple macOS 14 Sonoma Benchmark.
+pub static CIS_MACOS_CONTROLS: Lazy<HashMap<&'static str, BenchmarkControl>> = Lazy::new(|| {
+    catalog(&[
+        // ========================================================================
+        // 1 Install Updates, Patches and Additional Security Software
+        // ========================================================================
+        ("1.1", "Ensure All Apple-provided Software Is Current"),
+        ("1.2", "Ensure Auto Update Is Enabled"),
+        ("1.3", "Ensure Download New Updates When Available Is Enabled"),
+        ("1.4", "Ensure Install of macOS Updates Is Enabled"),
+        ("1.5", "Ensure Install Application Updates from the App Store Is Enabled"),
+        ("1.6", "Ensure Install Security Responses and System Files Is Enabled"),
+        ("1.7", "Ensure Software Update Deferment Is Less Than or Equal to 30 Days"),
+        // ========================================================================
+        // 2 System Settings
+        // ========================================================================
+        ("2.2.1", "Ensure Firewall Is Enabled"),
+        ("2.2.2", "Ensure Firewall Stealth Mode Is Enabled"),
+        ("2.3.1.1", "Ensure AirDrop Is Disabled"),
+        ("2.3.1.2", "Ensure AirPlay Receiver Is Disabled"),
+        ("2.3.2.1", "Ensure Set Time and Date Automatically Is Enabled"),
+        ("2.3.3.1", "Ensure DVD or CD Sharing Is Disabled"),
+        ("2.3.3.2", "Ensure Screen Sharing Is Disabled"),
+        ("2.3.3.3", "Ensure File Sharing Is Disabled"),
+        ("2.3.3.4", "Ensure Printer Sharing Is Disabled"),
+        ("2.3.3.5", "Ensure Remote Login Is Disabled"),
+        ("2.3.3.6", "Ensure Remote Management Is Disabled"),
+        ("2.3.3.7", "Ensure Remote Apple Events Is Disabled"),
+        ("2.3.3.8", "Ensure Internet Sharing Is Disabled"),
+        ("2.3.3.9", "Ensure Content Caching Is Disabled"),
+        ("2.3.3.10", "Ensure Media Sharing Is Disabled"),
+        ("2.3.3.11", "Ensure Bluetooth Sharing Is Disabled"),
+        ("2.3.4.1", "Ensure Backup Automatically is Enabled If Time Machine Is Enabled"),
+        ("2.3.4.2", "Ensure Time Machine Volumes Are Encrypted If Time Machine Is Enabled"),
+        ("2.6.1.1", "Ensure Location Services Is Enabled"),
+        ("2.6.2", "Ensure Sending Diagnostic and Usage Data to Apple Is Disabled"),
+        ("2.6.5", "Ensure Gatekeeper Is Enabled"),
+        ("2.6.6", "Ensure FileVault Is Enabled"),
+        ("2.6.7", "Ensure an Administrator Password Is Required to Access System-Wide Preferences"),
+        ("2.10.1", "Ensure Screen Saver Is Enabled for a Period of 20 Minutes or Less"),
+        ("2.10.2", "Ensure a Password Is Required to Wake the Computer"),
+        ("2.11.1", "Ensure Users' Accounts Do Not Have a Password Hint"),
+        ("2.12.1", "Ensure Guest Account Is Disabled"),
+        ("2.12.2", "Ensure Guest Access to Shared Folders Is Disabled"),
+        ("2.12.3", "Ensure Automatic Login Is Disabled"),
+        // ========================================================================
+        // 3 Logging and Auditing
+        // ========================================================================
+        ("3.1", "Ensure Security Auditing Is Enabled"),
+        ("3.3", "Ensure install.log Is Retained for 365 or More Days"),
+        ("3.4", "Ensure Security Auditing Retention Is Enabled"),
+        // ========================================================================
+        // 4 Network Configurations
+        // ========================================================================
+        ("4.1", "Ensure Bonjour Advertising Services Is Disabled"),
+        ("4.2", "Ensure HTTP Server Is Disabled"),
+        ("4.3", "Ensure NFS Server Is Disabled"),
+        // ========================================================================
+        // 5 System Access, Authentication and Authorization
+        // ========================================================================
+        ("5.1.1", "Ensure Home Folders Are Secure"),
+        ("5.1.2", "Ensure System Integrity Protection Status (SIP) Is Enabled"),
+        ("5.1.3", "Ensure Apple Mobile File Integrity (AMFI) Is Enabled"),
+        ("5.1.4", "Ensure Signed System Volume (SSV) Is Enabled"),
+        ("5.2.1", "Ensure Password Account Lockout Threshold Is Configured"),
+        ("5.2.2", "Ensure Password Minimum Length Is Configured"),
+        ("5.4", "Ensure the Sudo Timeout Period Is Set to Zero"),
+        ("5.6", "Ensure the \"root\" Account Is Disabled"),
+        ("5.8", "Ensure a Login Window Banner Exists"),
+        // ========================================================================
+        // 6 Applications
+        // ========================================================================
+        ("6.1.1", "Ensure Show All Filename Extensions Setting is Enabled"),
+        ("6.3.1", "Ensure Automatic Opening of Safe Files in Safari Is Disabled"),
+    ])
+});
+
+/// Automated controls from the CIS Microsoft Windows 11 Enterprise Benchmark.
+pub static CIS_WINDOWS_CONTROLS: Lazy<HashMap<&'static str, BenchmarkControl>> = Lazy::new(|| {
+    catalog(&[
+        // ========================================================================
+        // 1 Account Policies
+        // ========================================================================
+        ("1.1.1", "Ensure 'Enforce password history' is set to '24 or more password(s)'"),
+        ("1.1.2", "Ensure 'Maximum password age' is set to '365 or fewer days, but not 0'"),
+        ("1.1.3", "Ensure 'Minimum password age' is set to '1 or more day(s)'"),
+        ("1.1.4", "Ensure 'Minimum password length' is set to '14 or more character(s)'"),
+        ("1.1.5", "Ensure 'Password must meet complexity requirements' is set to 'Enabled'"),
+        ("1.1.7", "Ensure 'Store passwords using reversible encryption' is set to 'Disabled'"),
+        ("1.2.1", "Ensure 'Account lockout duration' is set to '15 or more minute(s)'"),
+        ("1.2.2", "Ensure 'Account lockout threshold' is set to '5 or fewer invalid logon attempt(s), but not 0'"),
+        ("1.2.4", "Ensure 'Reset account lockout counter after' is set to '15 or more minute(s)'"),
+        // ========================================================================
+        // 2 Local Policies
+        // ========================================================================
+        ("2.3.1.1", "Ensure 'Accounts: Block Microsoft accounts' is set to 'Users can't add or log on with Microsoft accounts'"),
+        ("2.3.1.2", "Ensure 'Accounts: Guest account status' is set to 'Disabled'"),
+        ("2.3.1.3", "Ensure 'Accounts: Limit local account use of blank passwords to console logon only' is set to 'Enabled'"),
+        ("2.3.7.1", "Ensure 'Interactive logon: Do not require CTRL+ALT+DEL' is set to 'Disabled'"),
+        ("2.3.7.2", "Ensure 'Interactive logon: Don't display last signed-in' is set to 'Enabled'"),
+        ("2.3.7.4", "Ensure 'Interactive logon: Machine inactivity limit' is set to '900 or fewer second(s), but not 0'"),
+        ("2.3.17.1", "Ensure 'User Account Control: Admin Approval Mode for the Built-in Administrator account' is set to 'Enabled'"),
+        // ========================================================================
+        // 9 Windows Defender Firewall with Advanced Security
+        // ========================================================================
+        ("9.1.1", "Ensure 'Windows Firewall: Domain: Firewall state' is set to 'On (recommended)'"),
+        ("9.2.1", "Ensure 'Windows Firewall: Private: Firewall state' is set to 'On (recommended)'"),
+        ("9.3.1", "Ensure 'Windows Firewall: Public: Firewall state' is set to 'On (recommended)'"),
+        // ========================================================================
+        // 17 Advanced Audit Policy Configuration
+        // ========================================================================
+        ("17.1.1", "Ensure 'Audit Credential Validation' is set to 'Success and Failure'"),
+        ("17.2.1", "Ensure 'Audit Application Group Management' is set to 'Success and Failure'"),
+        ("17.2.2", "Ensure 'Audit Security Group Management' is set to include 'Success'"),
+        ("17.2.3", "Ensure 'Audit User Account Management' is set to 'Success and Failure'"),
+        ("17.5.1", "Ensure 'Audit Account Lockout' is set to include 'Failure'"),
+        ("17.5.4", "Ensure 'Audit Logon' is set to 'Success and Failure'"),
+        // ========================================================================
+        // 18 Administrative Templates (Computer)
+        // ========================================================================
+        ("18.10.9.1.1", "Ensure 'BitLocker: Allow access to BitLocker-protected fixed data drives from earlier versions of Windows' is set to 'Disabled'"),
+        ("18.10.43.6.1", "Ensure 'Microsoft Defender Antivirus: Turn on behavior monitoring' is set to 'Enabled'"),
+        ("18.10.43.16", "Ensure 'Turn off Microsoft Defender AntiVirus' is set to 'Disabled'"),
+        ("18.10.92.1.1", "Ensure 'Windows Update: No auto-restart with logged on users for scheduled automatic updates installations' is set to 'Disabled'"),
+    ])
+});
+
+/// A `# cis:` annotation attached to a policy in a YAML document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkAnnotation {
+    /// Name of the annotated policy (if it has one).
+    pub policy_name: Option<String>,
+
+    /// Platform of the annotated policy (if specified).
+    pub platform: Option<String>,
+
+    /// Control IDs listed in the annotation.
+    pub ids: Vec<String>,
+
+    /// Line of the annotation comment (1-indexed).
+    pub line: usize,
+}
+
+impl BenchmarkAnnotation {
+    /// Benchmarks an annotation applies to.
+    ///
+    /// Uses the policy platform when set; otherwise any benchmark whose
+    /// catalog contains the ID.
+    pub fn benchmarks_for(&self, id: &str) -> Vec<Benchmark> {
+        if let Some(benchmark) = self.platform.as_deref().and_then(Benchmark::for_platform) {
+            return vec![benchmark];
+        }
+
+        Benchmark::ALL
+            .iter()
+            .copied()
+            .filter(|b| b.control(id).is_some())
+            .collect()
+    }
+}
+
+/// Parse the control IDs from a `# cis: ...` comment line.
+fn parse_annotation_comment(trimmed: &str) -> Option<Vec<String>> {
+    let comment = trimmed.strip_prefix('#')?.trim();
+    let (key, value) = comment.split_once(':')?;
+    if !key.trim().eq_ignore_ascii_case("cis") {
+        return None;
+    }
+
+    let ids: Vec<String> = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+
+    if ids.is_empty() {
+        None
+    } else {
+        Some(ids)
+    }
+}
+
+/// Extract `# cis:` annotations from policies in a YAML document.
+///
+/// A comment inside a policy item applies to that policy; a comment directly
+/// above a list item applies to the item that follows it.
+pub fn extract_annotations(source: &str) -> Vec<BenchmarkAnnotation> {
+    let mut annotations = Vec::new();
+
+    // Lib files are a bare list of policies; team files nest them under `policies:`
+    let is_bare_list = source
+        .lines()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && !l.starts_with('#') && l != &"---")
+        .map(|l| l.starts_with('-'))
+        .unwrap_or(false);
+
+    let mut in_policies = is_bare_list;
+    let mut item_indent: Option<usize> = None;
+    let mut current: Option<BenchmarkAnnotation> = None;
+    let mut pending: Vec<(Vec<String>, usize)> = Vec::new();
+
+    fn flush(current: &mut Option<BenchmarkAnnotation>, annotations: &mut Vec<BenchmarkAnnotation>) {
+        if let Some(annotation) = current.take() {
+            if !annotation.ids.is_empty() {
+                annotations.push(annotation);
+            }
+        }
+    }
+
+    for (idx, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        if trimmed.starts_with('#') {
+            if in_policies {
+                if let Some(ids) = parse_annotation_comment(trimmed) {
+                    pending.push((ids, idx + 1));
+                }
+            }
+            continue;
+        }
+
+        // Top-level key switches section
+        if indent == 0 && !trimmed.starts_with('-') {
+            flush(&mut current, &mut annotations);
+            pending.clear();
+            item_indent = None;
+            in_policies = trimmed.starts_with("policies:");
+            continue;
+        }
+
+        if !in_policies {
+            continue;
+        }
+
+        let is_item_start = trimmed.starts_with('-') && item_indent.map(|i| indent <= i).unwrap_or(true);
+
+        if is_item_start {
+            flush(&mut current, &mut annotations);
+            item_indent = Some(indent);
+
+            let mut annotation = BenchmarkAnnotation {
+                policy_name: None,
+                platform: None,
+                ids: Vec::new(),
+                line: idx + 1,
+            };
+            for (ids, line) in pending.drain(..) {
+                annotation.ids.extend(ids);
+                annotation.line = line;
+            }
+            current = Some(annotation);
+        } else if let Some(annotation) = current.as_mut() {
+            // Comments inside the item body belong to the current policy
+            for (ids, line) in pending.drain(..) {
+                if annotation.ids.is_empty() {
+                    annotation.line = line;
+                }
+                annotation.ids.extend(ids);
+            }
+        }
+
+        if let Some(annotation) = current.as_mut() {
+            let field = trimmed.trim_start_matches('-').trim();
+            if let Some(value) = field.strip_prefix("name:") {
+                if annotation.policy_name.is_none() {
+                    annotation.policy_name = Some(unquote(value));
+                }
+            } else if let Some(value) = field.strip_prefix("platform:") {
+                if annotation.platform.is_none() {
+                    annotation.platform = Some(unquote(value));
+                }
+            }
+        }
+    }
+
+    // Trailing comments after the last line of an item belong to that item
+    if let Some(annotation) = current.as_mut() {
+        for (ids, line) in pending.drain(..) {
+            if annotation.ids.is_empty() {
+                annotation.line = line;
+            }
+            annotation.ids.extend(ids);
+        }
+    }
+    flush(&mut current, &mut annotations);
+
+    annotations
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches('"').trim_matches('\'').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_annotation_comment() {
+        assert_eq!(parse_annotation_comment("# cis: 2.2.1"), Some(vec!["2.2.1".to_string()]));
+        assert_eq!(
+            parse_annotation_comment("# CIS: 2.3.3.2, 2.3.3.5"),
+            Some(vec!["2.3.3.2".to_string(), "2.3.3.5".to_string()])
+        );
+        assert_eq!(parse_annotation_comment("# just a comment"), None);
+        assert_eq!(parse_annotation_comment("# cis:"), None);
+    }
+
+    #[test]
+    fn test_extract_annotations_team_file() {
+        let source = r#"name: Workstations
+policies:
+  # cis: 2.2.1
+  - name: Firewall enabled
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+    platform: darwin
+  - name: FileVault enabled
+    # cis: 2.6.6
+    query: SELECT 1 FROM filevault_status WHERE status = 'FileVault is On.';
+    platform: darwin
+  - name: No annotation
+    query: SELECT 1;
+queries:
+  # cis: 9.9.9
+  - name: Not a policy
+    query: SELECT 1;
+"#;
+
+        let annotations = extract_annotations(source);
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0].policy_name.as_deref(), Some("Firewall enabled"));
+        assert_eq!(annotations[0].ids, vec!["2.2.1"]);
+        assert_eq!(annotations[0].line, 3);
+        assert_eq!(annotations[0].platform.as_deref(), Some("darwin"));
+        assert_eq!(annotations[1].policy_name.as_deref(), Some("FileVault enabled"));
+        assert_eq!(annotations[1].ids, vec!["2.6.6"]);
+        assert_eq!(annotations[1].line, 8);
+    }
+
+    #[test]
+    fn test_extract_annotations_lib_file() {
+        let source = r#"# cis: 9.1.1, 9.2.1
+- name: Windows firewall
+  query: SELECT 1 FROM windows_security_center WHERE firewall = 'Good';
+  platform: windows
+"#;
+
+        let annotations = extract_annotations(source);
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].ids, vec!["9.1.1", "9.2.1"]);
+        assert_eq!(annotations[0].benchmarks_for("9.1.1"), vec![Benchmark::CisWindows]);
+    }
+
+    #[test]
+    fn test_catalog_lookup() {
+        assert!(Benchmark::CisMacos.control("2.2.1").is_some());
+        assert!(Benchmark::CisWindows.control("9.1.1").is_some());
+        assert!(Benchmark::CisMacos.control("99.1").is_none());
+        assert_eq!(Benchmark::for_platform("darwin"), Some(Benchmark::CisMacos));
+        assert_eq!(Benchmark::parse("windows"), Some(Benchmark::CisWindows));
+    }
+}
@@ -0,0 +1,116 @@
+//! Schema snapshot embedded in the binary.
+//!
+//! `validate` and the `strict-schema` lint rule normally use schemas written
+//! by `fleet-schema-gen generate`, which needs network access to Fleet's
+//! sources. In air-gapped environments the snapshot below is used instead,
+//! with a warning when the repository pins a different Fleet version. The
+//! LSP derives its field docs from the embedded [`FleetSchema`]; osquery
+//! table data (`osquery.rs`) is compiled in already.
+//!
+//! Refresh the snapshots with
+//! `fleet-schema-gen generate --editor strict --output bundled` and
+//! `fleet-schema-gen generate --editor lsp --output bundled`.
+
+use super::validate::{SchemaKind, SchemaValidator};
+use crate::schema::types::FleetSchema;
+use anyhow::Result;
+use serde_json::Value;
+
+/// Fleet version the embedded snapshot was generated for.
+pub const SNAPSHOT_VERSION: &str = "4.74.0";
+
+/// Strict schema for `default.yml`.
+pub const STRICT_DEFAULT_SCHEMA: &str = include_str!("../../bundled/fleet-gitops-default.strict.schema.json");
+
+/// Strict schema for team files.
+pub const STRICT_TEAM_SCHEMA: &str = include_str!("../../bundled/fleet-gitops-team.strict.schema.json");
+
+/// Strict schema for a policy in a lib file.
+pub const STRICT_POLICY_SCHEMA: &str = include_str!("../../bundled/fleet-gitops-policy.strict.schema.json");
+
+/// Strict schema for a query in a lib file.
+pub const STRICT_QUERY_SCHEMA: &str = include_str!("../../bundled/fleet-gitops-query.strict.schema.json");
+
+/// Strict schema for a label in a lib file.
+pub const STRICT_LABEL_SCHEMA: &str = include_str!("../../bundled/fleet-gitops-label.strict.schema.json");
+
+/// Full generated schema (descriptions, enums, examples) for the LSP.
+pub const FLEET_SCHEMA: &str = include_str!("../../bundled/fleet-schema.json");
+
+/// The embedded strict schema for files of `kind` as JSON.
+pub fn strict_schema(kind: SchemaKind) -> Value {
+    let schema = match kind {
+        SchemaKind::Default => STRICT_DEFAULT_SCHEMA,
+        SchemaKind::Team => STRICT_TEAM_SCHEMA,
+        SchemaKind::Policies => STRICT_POLICY_SCHEMA,
+        SchemaKind::Queries => STRICT_QUERY_SCHEMA,
+        SchemaKind::Labels => STRICT_LABEL_SCHEMA,
+    };
+    serde_json::from_str(schema).expect("bundled schema is valid JSON")
+}
+
+/// Validator for the embedded strict schema of `kind`. Lists are checked
+/// item by item by [`super::validate::SchemaSource::validator`].
+pub fn strict_validator(kind: SchemaKind) -> Result<SchemaValidator> {
+    SchemaValidator::new(&strict_schema(kind))
+}
+
+/// Warning for checking files of a repository pinned to `fleet_version`
+/// against the snapshot, `None` when the snapshot is for that version.
+pub fn version_mismatch(fleet_version: &str) -> Option<String> {
+    if super::osquery::compare_versions(fleet_version, SNAPSHOT_VERSION).is_eq() {
+        return None;
+    }
+    Some(format!(
+        "The bundled schemas are for Fleet {}, not {}: fields added or removed in between are reported wrongly. Run `fleet-schema-gen generate --fleet-version {} --editor strict`",
+        SNAPSHOT_VERSION, fleet_version, fleet_version
+    ))
+}
+
+/// The embedded full schema.
+pub fn fleet_schema() -> FleetSchema {
+    serde_json::from_str(FLEET_SCHEMA).expect("bundled schema is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_bundled_schema_validates() {
+        let validator = strict_validator(SchemaKind::Default).unwrap();
+
+        let result = validator.validate("policies: []\nnot_a_fleet_key: true\n", Path::new("default.yml"));
+        assert!(!result.valid);
+        assert!(result.violations.iter().any(|v| v.message.contains("not_a_fleet_key")));
+        assert!(result.violations.iter().all(|v| !v.message.contains("policies")));
+    }
+
+    #[test]
+    fn test_bundled_schema_by_kind() {
+        use super::super::validate::SchemaSource;
+
+        // Team files have `name`, which default.yml doesn't
+        let team = SchemaSource::Bundled.validator(Some(SchemaKind::Team)).unwrap().unwrap();
+        assert!(team.validate("name: Workstations\npolicies: []\n", Path::new("teams/a.yml")).valid);
+        let default = SchemaSource::Bundled.validator(Some(SchemaKind::Default)).unwrap().unwrap();
+        assert!(!default.validate("name: Workstations\npolicies: []\n", Path::new("default.yml")).valid);
+
+        // Lib files are lists of items
+        let policies = SchemaSource::Bundled.validator(Some(SchemaKind::Policies)).unwrap().unwrap();
+        let source = "- name: FileVault\n  query: SELECT 1;\n- name: Firewall\n  querry: SELECT 1;\n";
+        let result = policies.validate(source, Path::new("lib/macos.policies.yml"));
+        assert!(!result.violations.is_empty());
+        assert!(result.violations.iter().all(|v| v.pointer.as_deref() == Some("/1") && v.line == Some(3)));
+        assert!(result.violations.iter().any(|v| v.message.contains("querry")));
+
+        assert!(SchemaSource::Bundled.validator(None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_version_mismatch() {
+        assert!(version_mismatch(SNAPSHOT_VERSION).is_none());
+        assert!(version_mismatch("4.58.0").unwrap().contains("not 4.58.0"));
+    }
+}
@@ -0,0 +1,384 @@
+//! CI pipeline definitions for a Fleet GitOps repository (`ci-init`).
+//!
+//! Generates a GitHub Actions workflow and/or a GitLab CI include that
+//! install a pinned fleet-schema-gen release (cached between runs) and run
+//! `lint`, `validate` and an informational `migrate --dry-run`. Trigger
+//! paths, validate targets and the Fleet version come from the detected
+//! layout and `.fleetlint.toml`; with a pinned Fleet version the generated
+//! schemas are cached too.
+
+use super::config::{FleetLintConfig, CONFIG_FILE_NAME};
+use super::init::detect_workspace;
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Release archives are downloaded from this repository.
+const RELEASES_URL: &str = "https://github.com/headmin/fleet-editor-extensions/releases/download";
+
+/// CI system to generate for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiProvider {
+    Github,
+    Gitlab,
+}
+
+impl std::str::FromStr for CiProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "github" => Ok(CiProvider::Github),
+            "gitlab" => Ok(CiProvider::Gitlab),
+            _ => anyhow::bail!("Unknown CI provider: {} (expected github or gitlab)", s),
+        }
+    }
+}
+
+impl CiProvider {
+    /// Where the pipeline is written, relative to the repository root.
+    pub fn file(self) -> &'static str {
+        match self {
+            CiProvider::Github => ".github/workflows/fleet-gitops.yml",
+            CiProvider::Gitlab => ".gitlab/fleet-gitops.gitlab-ci.yml",
+        }
+    }
+}
+
+/// What the pipeline needs to know about the repository.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CiLayout {
+    /// Paths that trigger the pipeline (`default.yml`, `teams/**`).
+    pub trigger_paths: Vec<String>,
+    /// Files and directories passed to `validate`.
+    pub validate_targets: Vec<String>,
+    /// Fleet version pinned in `.fleetlint.toml`.
+    pub fleet_version: Option<String>,
+    /// Directory of generated schemas (`[schema] versions_dir`).
+    pub versions_dir: String,
+}
+
+/// Layout of the repository at `root`.
+pub fn detect_layout(root: &Path) -> CiLayout {
+    let detected = detect_workspace(root);
+    let config = FleetLintConfig::discover(root).ok().flatten().map(|(_, config)| config);
+
+    let mut trigger_paths = detected.root_yaml_files.clone();
+    let mut validate_targets = detected.root_yaml_files.clone();
+    if detected.has_teams_dir {
+        trigger_paths.push("teams/**".to_string());
+        validate_targets.push("teams".to_string());
+    }
+    if detected.has_lib_dir {
+        trigger_paths.push("lib/**".to_string());
+    }
+    if trigger_paths.is_empty() {
+        trigger_paths.extend(["**/*.yml".to_string(), "**/*.yaml".to_string()]);
+    }
+    if validate_targets.is_empty() {
+        validate_targets.push(".".to_string());
+    }
+    if root.join(CONFIG_FILE_NAME).is_file() {
+        trigger_paths.push(CONFIG_FILE_NAME.to_string());
+    }
+
+    CiLayout {
+        trigger_paths,
+        validate_targets,
+        fleet_version: config.as_ref().and_then(|c| c.fleet_version.clone()),
+        versions_dir: config.map(|c| c.schema.versions_dir).unwrap_or_else(|| "schemas".to_string()),
+    }
+}
+
+/// GitHub Actions workflow for `layout`, installing fleet-schema-gen `version`.
+pub fn github_workflow(layout: &CiLayout, version: &str) -> String {
+    let paths: String = layout.trigger_paths.iter().map(|p| format!("      - \"{}\"\n", p)).collect();
+
+    let mut yaml = format!(
+        r#"# Generated by `fleet-schema-gen ci-init`
+name: Fleet GitOps
+
+on:
+  pull_request:
+    paths:
+{paths}  push:
+    branches: [main]
+    paths:
+{paths}
+permissions:
+  contents: read
+  security-events: write
+
+env:
+  FLEET_SCHEMA_GEN_VERSION: "{version}"
+
+jobs:
+  fleet-gitops:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+
+      - name: Cache fleet-schema-gen
+        id: cache-bin
+        uses: actions/cache@v4
+        with:
+          path: ~/.local/bin/fleet-schema-gen
+          key: fleet-schema-gen-${{{{ env.FLEET_SCHEMA_GEN_VERSION }}}}-linux-x64
+
+      - name: Install fleet-schema-gen
+        if: steps.cache-bin.outputs.cache-hit != 'true'
+        run: |
+          mkdir -p ~/.local/bin
+          {install}
+
+      - run: echo "$HOME/.local/bin" >> "$GITHUB_PATH"
+"#,
+        paths = paths,
+        version = version,
+        install = install_command(),
+    );
+
+    if let Some(fleet_version) = &layout.fleet_version {
+        let dir = format!("{}/{}", layout.versions_dir, fleet_version);
+        yaml.push_str(&format!(
+            r#"
+      - name: Cache Fleet {fleet_version} schemas
+        id: cache-schemas
+        uses: actions/cache@v4
+        with:
+          path: {dir}
+          key: fleet-schemas-{fleet_version}-${{{{ env.FLEET_SCHEMA_GEN_VERSION }}}}
+
+      - name: Generate Fleet {fleet_version} schemas
+        if: steps.cache-schemas.outputs.cache-hit != 'true'
+        env:
+          GITHUB_TOKEN: ${{{{ github.token }}}}
+        run: {generate}
+"#,
+            fleet_version = fleet_version,
+            dir = dir,
+            generate = generate_command(fleet_version, &dir),
+        ));
+    }
+
+    yaml.push_str(&format!(
+        r#"
+      - name: Lint
+        run: fleet-schema-gen lint .
+
+      - name: Validate
+        run: fleet-schema-gen validate {targets} --format sarif > fleet-validate.sarif
+
+      - name: Upload validation results
+        if: always()
+        uses: github/codeql-action/upload-sarif@v3
+        with:
+          sarif_file: fleet-validate.sarif
+          category: fleet-gitops
+
+      - name: Check pending migrations
+        continue-on-error: true
+        run: {migrate}
+"#,
+        targets = layout.validate_targets.join(" "),
+        migrate = migrate_command(layout),
+    ));
+
+    yaml
+}
+
+/// GitLab CI include for `layout`, installing fleet-schema-gen `version`.
+pub fn gitlab_ci(layout: &CiLayout, version: &str) -> String {
+    let paths: String = layout.trigger_paths.iter().map(|p| format!("        - \"{}\"\n", p)).collect();
+
+    let mut cache = String::from(
+        r#"  cache:
+    - key: fleet-schema-gen-$FLEET_SCHEMA_GEN_VERSION
+      paths:
+        - .fleet-schema-gen/
+"#,
+    );
+    let mut generate = String::new();
+    if let Some(fleet_version) = &layout.fleet_version {
+        let dir = format!("{}/{}", layout.versions_dir, fleet_version);
+        cache.push_str(&format!(
+            "    - key: fleet-schemas-{}-$FLEET_SCHEMA_GEN_VERSION\n      paths:\n        - {}/\n",
+            fleet_version, dir
+        ));
+        generate = format!(
+            "    - test -d {dir} || {generate}\n",
+            dir = dir,
+            generate = generate_command(fleet_version, &dir)
+        );
+    }
+
+    format!(
+        r#"# Generated by `fleet-schema-gen ci-init`
+# Include from .gitlab-ci.yml:
+#   include:
+#     - local: {file}
+
+variables:
+  FLEET_SCHEMA_GEN_VERSION: "{version}"
+
+.fleet-gitops-rules:
+  rules:
+    - if: $CI_PIPELINE_SOURCE == "merge_request_event" || $CI_COMMIT_BRANCH == $CI_DEFAULT_BRANCH
+      changes:
+{paths}
+fleet-gitops:
+  stage: test
+  image: debian:stable-slim
+  extends: .fleet-gitops-rules
+{cache}  before_script:
+    - apt-get update -qq && apt-get install -y -qq curl ca-certificates > /dev/null
+    - mkdir -p .fleet-schema-gen
+    - test -x .fleet-schema-gen/fleet-schema-gen || {install}
+    - export PATH="$PWD/.fleet-schema-gen:$PATH"
+{generate}  script:
+    - fleet-schema-gen lint .
+    - fleet-schema-gen validate {targets}
+
+fleet-gitops-migrations:
+  stage: test
+  image: debian:stable-slim
+  extends: .fleet-gitops-rules
+  needs: [fleet-gitops]
+  allow_failure: true
+{cache}  before_script:
+    - apt-get update -qq && apt-get install -y -qq curl ca-certificates > /dev/null
+    - mkdir -p .fleet-schema-gen
+    - test -x .fleet-schema-gen/fleet-schema-gen || {install}
+    - export PATH="$PWD/.fleet-schema-gen:$PATH"
+  script:
+    - {migrate}
+"#,
+        file = CiProvider::Gitlab.file(),
+        version = version,
+        paths = paths,
+        cache = cache,
+        install = install_command().replace("~/.local/bin", ".fleet-schema-gen"),
+        generate = generate,
+        targets = layout.validate_targets.join(" "),
+        migrate = migrate_command(layout),
+    )
+}
+
+fn install_command() -> String {
+    format!(
+        "curl -fsSL \"{}/v${{FLEET_SCHEMA_GEN_VERSION}}/fleet-schema-gen-${{FLEET_SCHEMA_GEN_VERSION}}-linux-x64.tar.gz\" | tar -xz -C ~/.local/bin fleet-schema-gen",
+        RELEASES_URL
+    )
+}
+
+fn generate_command(fleet_version: &str, dir: &str) -> String {
+    format!(
+        "fleet-schema-gen generate --fleet-version {} --editor strict --output {}",
+        fleet_version, dir
+    )
+}
+
+fn migrate_command(layout: &CiLayout) -> String {
+    match &layout.fleet_version {
+        Some(version) => format!("fleet-schema-gen migrate . --dry-run --to {}", version),
+        None => "fleet-schema-gen migrate . --dry-run".to_string(),
+    }
+}
+
+/// Options for [`ci_init`].
+#[derive(Debug, Clone)]
+pub struct CiInitOptions {
+    pub providers: Vec<CiProvider>,
+    /// Overwrite existing pipeline files.
+    pub force: bool,
+    /// Print to stdout instead of writing files.
+    pub print: bool,
+}
+
+/// Write (or print) pipeline definitions for the repository at `root`.
+pub fn ci_init(root: &Path, options: CiInitOptions) -> anyhow::Result<Vec<PathBuf>> {
+    let layout = detect_layout(root);
+    let version = env!("CARGO_PKG_VERSION");
+
+    let mut written = Vec::new();
+    for provider in options.providers {
+        let content = match provider {
+            CiProvider::Github => github_workflow(&layout, version),
+            CiProvider::Gitlab => gitlab_ci(&layout, version),
+        };
+        if options.print {
+            println!("{}", content);
+            continue;
+        }
+
+        let path = root.join(provider.file());
+        if path.exists() && !options.force {
+            anyhow::bail!("{} already exists\nUse --force to overwrite.", path.display());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+        println!("{} Created {}", "✓".green(), provider.file().bold());
+        written.push(path);
+    }
+
+    if written.iter().any(|p| p.ends_with(CiProvider::Gitlab.file())) {
+        println!(
+            "  Include it from .gitlab-ci.yml: {}",
+            format!("include: [{{ local: {} }}]", CiProvider::Gitlab.file()).dimmed()
+        );
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_layout() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(root.join("default.yml"), "policies: []\n").unwrap();
+        fs::write(root.join(CONFIG_FILE_NAME), "fleet_version = \"4.58.0\"\n").unwrap();
+
+        let layout = detect_layout(root);
+        assert_eq!(layout.trigger_paths, vec!["default.yml", "teams/**", "lib/**", CONFIG_FILE_NAME]);
+        assert_eq!(layout.validate_targets, vec!["default.yml", "teams"]);
+        assert_eq!(layout.fleet_version.as_deref(), Some("4.58.0"));
+        assert_eq!(layout.versions_dir, "schemas");
+    }
+
+    #[test]
+    fn test_pipelines_parse_and_use_layout() {
+        let layout = CiLayout {
+            trigger_paths: vec!["default.yml".to_string(), "teams/**".to_string()],
+            validate_targets: vec!["default.yml".to_string(), "teams".to_string()],
+            fleet_version: Some("4.58.0".to_string()),
+            versions_dir: "schemas".to_string(),
+        };
+
+        let github = github_workflow(&layout, "0.5.0");
+        let workflow: serde_yaml::Value = serde_yaml::from_str(&github).unwrap();
+        assert_eq!(workflow["on"]["pull_request"]["paths"][1], "teams/**");
+        assert_eq!(workflow["env"]["FLEET_SCHEMA_GEN_VERSION"], "0.5.0");
+        assert!(github.contains("key: fleet-schema-gen-${{ env.FLEET_SCHEMA_GEN_VERSION }}-linux-x64"));
+        assert!(github.contains("path: schemas/4.58.0"));
+        assert!(github.contains("fleet-schema-gen validate default.yml teams --format sarif"));
+        assert!(github.contains("migrate . --dry-run --to 4.58.0"));
+
+        let gitlab = gitlab_ci(&layout, "0.5.0");
+        let pipeline: serde_yaml::Value = serde_yaml::from_str(&gitlab).unwrap();
+        assert_eq!(pipeline["fleet-gitops"]["cache"][1]["paths"][0], "schemas/4.58.0/");
+        assert_eq!(pipeline["fleet-gitops-migrations"]["allow_failure"], true);
+        assert_eq!(pipeline[".fleet-gitops-rules"]["rules"][0]["changes"][0], "default.yml");
+
+        let unpinned = CiLayout { fleet_version: None, ..layout };
+        assert!(!github_workflow(&unpinned, "0.5.0").contains("cache-schemas"));
+        assert!(!gitlab_ci(&unpinned, "0.5.0").contains("fleet-schemas-"));
+    }
+}
@@ -2,7 +2,15 @@
 //!
 //! Supports `.fleetlint.toml` configuration files that allow teams
 //! to customize linting behavior and share settings via version control.
-
+//!
+//! Configs are discovered like `.gitignore`: every `.fleetlint.toml` from
+//! the linted path up to the repository root is loaded and merged, nearest
+//! last, so a monorepo can keep org-wide settings at the root and override
+//! them per GitOps tree. A config can also `extends` other config files,
+//! and policy packs installed next to it (see [`super::packs`]) apply
+//! beneath it.
+
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -14,6 +22,18 @@ pub const CONFIG_FILE_NAME: &str = ".fleetlint.toml";
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct FleetLintConfig {
+    /// Stop config discovery at this file (like `.editorconfig`).
+    pub root: bool,
+
+    /// Built-in preset this config builds on (see [`super::presets`]).
+    pub preset: Option<String>,
+
+    /// Fleet version the repository targets (e.g. `"4.58.0"`).
+    ///
+    /// Selects the generated schema and deprecation set used for
+    /// validation. Defaults to the latest known version.
+    pub fleet_version: Option<String>,
+
     /// Rule configuration.
     pub rules: RulesConfig,
 
@@ -25,6 +45,15 @@ pub struct FleetLintConfig {
 
     /// Schema validation options.
     pub schema: SchemaConfig,
+
+    /// Declarative rules (`[[custom_rules]]`, see [`super::custom_rules`]).
+    pub custom_rules: Vec<super::custom_rules::CustomRule>,
+
+    /// Local usage metrics (see [`super::metrics`]).
+    pub metrics: MetricsConfig,
+
+    /// Git branch, commit and pull request settings for `migrate --git`.
+    pub migrate: MigrateConfig,
 }
 
 /// Rule enable/disable configuration.
@@ -41,6 +70,11 @@ pub struct RulesConfig {
     #[serde(default)]
     pub warn: Vec<String>,
 
+    /// Opt-in rules to enable (by name).
+    /// Example: `enabled = ["policy-resolution"]`
+    #[serde(default)]
+    pub enabled: Vec<String>,
+
     /// Additional custom rule configurations.
     #[serde(flatten)]
     pub custom: std::collections::HashMap<String, toml::Value>,
@@ -64,6 +98,19 @@ pub struct ThresholdsConfig {
 
     /// Whether to warn on trailing semicolons (default: true).
     pub warn_trailing_semicolon: bool,
+
+    /// Scheduled query load budget per team, in table reads per host per
+    /// hour (default: 600; see [`super::schedule`]).
+    pub max_schedule_load: f64,
+
+    /// Policies a file may define inline; 0 disables (default: 100).
+    pub max_policies_per_file: usize,
+
+    /// Queries a file may define inline; 0 disables (default: 100).
+    pub max_queries_per_file: usize,
+
+    /// Lines a file may have; 0 disables (default: 2000).
+    pub max_lines_per_file: usize,
 }
 
 impl Default for ThresholdsConfig {
@@ -74,6 +121,10 @@ impl Default for ThresholdsConfig {
             warn_select_star: true,
             warn_trailing_semicolon: true,
             max_query_length: 10000,
+            max_schedule_load: 600.0,
+            max_policies_per_file: 100,
+            max_queries_per_file: 100,
+            max_lines_per_file: 2000,
         }
     }
 }
@@ -94,6 +145,13 @@ pub struct FilesConfig {
 
     /// Root directory for file resolution (relative to config file).
     pub root: Option<String>,
+
+    /// Skip files ignored by `.gitignore`/`.ignore` (default: true).
+    pub gitignore: bool,
+
+    /// Skip files larger than this many bytes in directory scans
+    /// (default: 1 MiB, 0 disables the limit).
+    pub max_file_size: u64,
 }
 
 impl Default for FilesConfig {
@@ -102,6 +160,8 @@ impl Default for FilesConfig {
             include: default_include_patterns(),
             exclude: default_exclude_patterns(),
             root: None,
+            gitignore: true,
+            max_file_size: super::walk::DEFAULT_MAX_FILE_SIZE,
         }
     }
 }
@@ -116,6 +176,7 @@ fn default_exclude_patterns() -> Vec<String> {
         "**/target/**".to_string(),
         "**/.git/**".to_string(),
         "**/dist/**".to_string(),
+        "**/.fleet-migrate/**".to_string(),
     ]
 }
 
@@ -131,6 +192,14 @@ pub struct SchemaConfig {
 
     /// Whether to require explicit platform specification (default: false).
     pub require_platform: bool,
+
+    /// Directory of generated schemas, one subdirectory per Fleet version
+    /// (default: `schemas`, e.g. `schemas/4.58.0/`).
+    pub versions_dir: String,
+
+    /// Directory of local schema enhancements; the LSP reloads hover docs
+    /// when its files change (default: `schema-defs`).
+    pub defs_dir: String,
 }
 
 impl Default for SchemaConfig {
@@ -139,17 +208,118 @@ impl Default for SchemaConfig {
             validate: true,
             allow_unknown_fields: true,
             require_platform: false,
+            versions_dir: "schemas".to_string(),
+            defs_dir: "schema-defs".to_string(),
+        }
+    }
+}
+
+/// Local usage metrics; off unless enabled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Record rule findings and completion latency (default: false).
+    pub enabled: bool,
+
+    /// Metrics file (default: `~/.cache/fleet-schema-gen/metrics.json`).
+    pub file: Option<String>,
+}
+
+/// How `migrate --git` names branches and commits and publishes them.
+///
+/// Templates use `{from}`, `{to}` and `{date}` (YYYY-MM-DD); commit and
+/// pull request templates also `{files}` and `{branch}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MigrateConfig {
+    /// Branch name template (default: `fleet-migrate-{from}-to-{to}`).
+    pub branch: String,
+
+    /// Commit message template; the first line is the subject.
+    pub commit_message: String,
+
+    /// Sign the commit with git's configured signing setup (`git commit -S`).
+    pub sign: bool,
+
+    /// Key to sign with instead of `user.signingkey`.
+    pub signing_key: Option<String>,
+
+    /// Push the branch after committing (default: false).
+    pub push: bool,
+
+    /// Remote to push to (default: `origin`).
+    pub remote: String,
+
+    /// Open a pull request with the GitHub CLI (`gh`) if it is installed;
+    /// implies `push`.
+    pub pull_request: bool,
+
+    /// Pull request title template (default: the commit subject).
+    pub pr_title: Option<String>,
+
+    /// Pull request body template (default: the commit message body).
+    pub pr_body: Option<String>,
+
+    /// Retention of the backups taken before each migration.
+    pub backups: BackupRetention,
+}
+
+/// How many migration backups `.fleet-migrate/backups/` keeps; older ones
+/// are pruned after each migration and by `migrate backups prune`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupRetention {
+    /// Number of most recent backups to keep (default: 10).
+    pub keep: Option<usize>,
+
+    /// Delete backups older than this many days (default: no limit).
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self { keep: Some(10), max_age_days: None }
+    }
+}
+
+impl Default for MigrateConfig {
+    fn default() -> Self {
+        Self {
+            branch: "fleet-migrate-{from}-to-{to}".to_string(),
+            commit_message: "Migrate Fleet GitOps from {from} to {to}\n\n\
+                             Automated migration of {files} file(s).\n\n\
+                             Migration performed by fleet-schema-gen"
+                .to_string(),
+            sign: false,
+            signing_key: None,
+            push: false,
+            remote: "origin".to_string(),
+            pull_request: false,
+            pr_title: None,
+            pr_body: None,
+            backups: BackupRetention::default(),
         }
     }
 }
 
 impl FleetLintConfig {
-    /// Load configuration from a file.
+    /// Load configuration from a file, resolving `extends` and `preset`.
     pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| ConfigError::ReadError(path.to_path_buf(), e.to_string()))?;
+        toml::Value::Table(load_table(path, &mut Vec::new())?)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::ParseError(e.to_string()))
+    }
 
-        Self::from_str(&content)
+    /// Apply a preset on top of this configuration (`lint --preset`).
+    ///
+    /// The preset wins over settings it mentions; everything else is kept.
+    pub fn with_preset(self, name: &str) -> Result<Self, ConfigError> {
+        let mut table = toml::Table::try_from(&self).map_err(|e| ConfigError::ParseError(e.to_string()))?;
+        merge_tables(&mut table, preset_table(name)?);
+        table.insert("preset".to_string(), toml::Value::String(name.to_string()));
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::ParseError(e.to_string()))
     }
 
     /// Parse configuration from a TOML string.
@@ -159,29 +329,62 @@ impl FleetLintConfig {
 
     /// Find and load configuration by searching up from a starting path.
     ///
-    /// Searches for `.fleetlint.toml` starting from `start_path` and
-    /// walking up to parent directories until found or root is reached.
+    /// Returns the nearest config file and the merged configuration (see
+    /// [`Self::discover`]). Invalid configs are logged and ignored.
     pub fn find_and_load(start_path: &Path) -> Option<(PathBuf, Self)> {
-        let mut current = if start_path.is_file() {
-            start_path.parent()?.to_path_buf()
+        match Self::discover(start_path) {
+            Ok(Some((mut paths, config))) => Some((paths.pop()?, config)),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("{}; using default configuration", e);
+                None
+            }
+        }
+    }
+
+    /// Discover and merge every config from `start_path` up to the repository root.
+    ///
+    /// The search stops at the first directory containing `.git`, or at a
+    /// config with `root = true`. Returns the config files outermost first,
+    /// with the merged configuration (nearer files win).
+    pub fn discover(start_path: &Path) -> Result<Option<(Vec<PathBuf>, Self)>, ConfigError> {
+        let start = if start_path.is_file() {
+            start_path.parent().unwrap_or(Path::new("."))
         } else {
-            start_path.to_path_buf()
+            start_path
         };
-
-        loop {
-            let config_path = current.join(CONFIG_FILE_NAME);
-            if config_path.exists() {
-                match Self::from_file(&config_path) {
-                    Ok(config) => return Some((config_path, config)),
-                    Err(_) => return None, // Config exists but is invalid
+        let start = std::path::absolute(start).unwrap_or_else(|_| start.to_path_buf());
+
+        let mut layers = Vec::new();
+        for dir in start.ancestors() {
+            let config_path = dir.join(CONFIG_FILE_NAME);
+            if config_path.is_file() {
+                let table = load_table(&config_path, &mut Vec::new())?;
+                let is_root = table.get("root").and_then(|v| v.as_bool()).unwrap_or(false);
+                layers.push((config_path, table));
+                if is_root {
+                    break;
                 }
             }
-
-            match current.parent() {
-                Some(parent) => current = parent.to_path_buf(),
-                None => return None,
+            if dir.join(".git").exists() {
+                break;
             }
         }
+
+        if layers.is_empty() {
+            return Ok(None);
+        }
+
+        let mut merged = toml::Table::new();
+        let mut paths = Vec::new();
+        for (path, table) in layers.into_iter().rev() {
+            merge_tables(&mut merged, table);
+            paths.push(path);
+        }
+        let config = toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::ParseError(e.to_string()))?;
+        Ok(Some((paths, config)))
     }
 
     /// Check if a rule is disabled.
@@ -199,11 +402,32 @@ impl FleetLintConfig {
         self.rules.disabled.iter().map(|s| s.as_str()).collect()
     }
 
+    /// Check if an opt-in rule has been enabled.
+    pub fn is_rule_enabled(&self, rule_name: &str) -> bool {
+        self.rules.enabled.iter().any(|r| r == rule_name)
+    }
+
+    /// Get the set of enabled opt-in rules.
+    pub fn enabled_rules(&self) -> HashSet<&str> {
+        self.rules.enabled.iter().map(|s| s.as_str()).collect()
+    }
+
     /// Get the set of warning-only rules.
     pub fn warning_rules(&self) -> HashSet<&str> {
         self.rules.warn.iter().map(|s| s.as_str()).collect()
     }
 
+    /// Get rule-specific options from a `[rules.<rule-name>]` table.
+    ///
+    /// Returns the default options when the table is missing or invalid.
+    pub fn rule_options<T: DeserializeOwned + Default>(&self, rule_name: &str) -> T {
+        self.rules
+            .custom
+            .get(rule_name)
+            .and_then(|value| value.clone().try_into().ok())
+            .unwrap_or_default()
+    }
+
     /// Check if a file path should be linted based on include/exclude patterns.
     pub fn should_lint_file(&self, file_path: &Path) -> bool {
         let path_str = file_path.to_string_lossy();
@@ -240,6 +464,21 @@ impl FleetLintConfig {
     pub fn default_with_comments() -> String {
         r#"# Fleet Linter Configuration
 # Place this file at the root of your GitOps repository as `.fleetlint.toml`
+#
+# Configs are merged from the repository root down to the linted path, so a
+# nested `.fleetlint.toml` only needs the settings it overrides.
+# Set `root = true` to ignore configs in parent directories.
+# root = true
+
+# Built-in preset to start from: fleet-recommended, cis-strict or minimal
+# preset = "fleet-recommended"
+
+# Shared configs to build on (paths relative to this file, or preset names)
+# extends = "../fleet-org/.fleetlint.toml"
+
+# Fleet version this repository targets (default: latest)
+# Overridden by `--fleet-version` on the command line.
+# fleet_version = "4.58.0"
 
 # Rule Configuration
 [rules]
@@ -252,11 +491,86 @@ impl FleetLintConfig {
 #   - interval-validation: Warns about extreme interval values
 #   - duplicate-names: Detects duplicate policy/query/label names
 #   - query-syntax: Validates SQL query syntax
+#   - sql-dialect: Detects MySQL/Postgres syntax in queries that SQLite doesn't support
+#   - constant-result: Detects policy queries that always pass or always fail
+#   - event-tables: Checks queries on evented tables have a time window, join conditions and differential logging
+#   - benchmark-ids: Validates CIS benchmark IDs in policy annotations
+#   - osquery-version: Validates min_osquery_version against tables and columns with a tracked introduction version
+#   - chrome-tables: Validates ChromeOS queries only use tables supported by fleetd for Chrome
+#   - windows-profiles: Validates SyncML structure, OMA-URIs, and data formats of Windows XML profiles
+#   - fleet-variables: Validates $FLEET_VAR_* references in profiles and scripts
+#   - webhook-settings: Validates webhook URLs and required fields of enabled webhooks
+#   - enroll-secrets: Detects literal, short, or repeated enroll secrets
+#   - host-settings: Validates host_expiry_settings and features flags
+#   - deprecated-fields: Detects fields deprecated by the targeted Fleet version
+#   - contradictory-override: Detects conflicting global/team settings (repository lint)
+#   - redundant-override: Detects team settings that repeat the global value (repository lint)
+#   - cross-team-policies: Detects policies duplicated or conflicting across teams (repository lint)
+#   - calendar-events: Checks calendar-enabled policies have a Google Calendar integration (repository lint)
+#   - shared-enroll-secrets: Detects enroll secrets shared between teams (repository lint)
 disabled = []
 
 # Rules to downgrade from error to warning
 warn = []
 
+# Opt-in rules to enable (off by default)
+# Available opt-in rules:
+#   - policy-resolution: Policies should include a resolution
+#   - critical-policy-platform: Critical policies should target a specific platform
+#   - policy-select-star: Policy queries should use SELECT 1 rather than SELECT *
+#   - policy-description: Policies should have a non-empty description
+#   - common-misspellings: Flags common misspellings in policy descriptions and resolutions
+#   - localization: Checks translated policy fields exist for every locale
+#   - prefer-path-references: Team files should reference lib files instead of defining items inline
+#   - naming-conventions: Team files, policies, queries and lib files follow naming conventions
+#   - strict-schema: Files match the strict schema of their kind (generated or bundled)
+enabled = []
+
+# Project dictionary for the common-misspellings rule
+# [rules.common-misspellings]
+# words = ["fleetd"]                     # words to accept
+# corrections = { fleetdm = "Fleet" }    # additional misspellings
+
+# Locales for the localization rule (translations like `resolution_de`)
+# [rules.localization]
+# locales = ["de", "fr"]                 # default: locales used by any policy
+# fields = ["resolution"]                # translated fields
+
+# Policies allowed a result that doesn't depend on the host (constant-result rule)
+# [rules.constant-result]
+# allow = ["Always on *"]                # policy names (globs)
+# min_confidence = "certain"             # "likely" (default) or "certain"
+
+# Inline definitions allowed in team files (prefer-path-references rule)
+# [rules.prefer-path-references]
+# kinds = ["policies", "queries"]        # default: policies, queries, labels, software
+# allow = ["Emergency *"]                # item names allowed inline (globs)
+# allow_files = ["default.yml"]          # files allowed inline items (globs)
+
+# Naming conventions checked (naming-conventions rule), all on by default
+# [rules.naming-conventions]
+# team_files = true                      # team file names match team_file_pattern
+# team_file_pattern = "^[a-z0-9-]+\\.yml$"
+# policy_names = true                    # "Platform - Description"
+# query_names = true                     # snake_case
+# lib_suffixes = true                    # .policies.yml / .queries.yml
+
+# Webhook destinations (webhook-settings rule)
+# [rules.webhook-settings]
+# allow_private = false                  # allow localhost/private addresses
+# allowed_hosts = ["hooks.corp.example"] # hosts accepted regardless
+
+# Minimum enroll secret length (enroll-secrets rule)
+# [rules.enroll-secrets]
+# min_length = 32
+
+# Software package URL checks (`fleet-schema-gen lint --check-urls`)
+# [rules.check-urls]
+# concurrency = 8                        # requests in flight
+# timeout_secs = 15
+# cache_ttl_hours = 24                   # 0 disables the cache
+# require_hash = true                    # packages must declare hash_sha256
+
 # Threshold Configuration
 [thresholds]
 # Minimum query interval in seconds (default: 60)
@@ -274,6 +588,16 @@ warn_select_star = true
 # Warn on trailing semicolons in queries (default: true)
 warn_trailing_semicolon = true
 
+# Scheduled query load budget per team, in table reads per host per hour
+# (tables touched x runs per hour, global queries included; default: 600)
+max_schedule_load = 600
+
+# Largest single files before splitting into lib files is suggested
+# (items defined inline and lines; 0 disables)
+max_policies_per_file = 100
+max_queries_per_file = 100
+max_lines_per_file = 2000
+
 # File Patterns
 [files]
 # Glob patterns to include
@@ -285,11 +609,18 @@ exclude = [
     "**/target/**",
     "**/.git/**",
     "**/dist/**",
+    "**/.fleet-migrate/**",
 ]
 
 # Optional: Root directory for path resolution (relative to this config file)
 # root = "."
 
+# Skip files ignored by .gitignore/.ignore (`lint --no-ignore` overrides)
+gitignore = true
+
+# Skip files larger than this many bytes (0 = no limit)
+max_file_size = 1048576
+
 # Schema Validation
 [schema]
 # Validate against Fleet's schema (default: true)
@@ -300,13 +631,177 @@ allow_unknown_fields = true
 
 # Require explicit platform specification (default: false)
 require_platform = false
+
+# Generated schemas per Fleet version, written with
+# `fleet-schema-gen generate --fleet-version 4.58.0 --output schemas/4.58.0`
+# versions_dir = "schemas"
+
+# Local schema enhancements; the LSP reloads hover docs when they change
+# defs_dir = "schema-defs"
+
+[metrics]
+# Record which rules fire and completion latency in a local file
+# (`fleet-schema-gen metrics report`); nothing is sent anywhere
+enabled = false
+
+[migrate]
+# Branch and commit templates for `migrate --git`; placeholders:
+# {from}, {to}, {date}, and in commit/PR templates {files} and {branch}
+# branch = "fleet-migrate-{from}-to-{to}"
+# commit_message = "Migrate Fleet GitOps from {from} to {to}"
+# Sign the commit (git commit -S), optionally with a specific key
+# sign = false
+# Push the branch, and open a pull request with gh if installed
+# push = false
+# pull_request = false
+
+[migrate.backups]
+# Backups taken before each migration (.fleet-migrate/backups/)
+keep = 10
+# max_age_days = 30
 "#
         .to_string()
     }
 }
 
+/// Parse a built-in preset.
+fn preset_table(name: &str) -> Result<toml::Table, ConfigError> {
+    let preset = super::presets::get(name).ok_or_else(|| {
+        ConfigError::UnknownPreset(name.to_string(), super::presets::names().join(", "))
+    })?;
+    toml::from_str(preset.config).map_err(|e| ConfigError::ParseError(format!("preset {}: {}", name, e)))
+}
+
+/// Read a config file as a TOML table with its `extends` chain merged in.
+///
+/// `extends` is a path (or list of paths) relative to the file, or a preset
+/// name. Installed packs come first, then the `preset`, then extended
+/// configs in order, then the file itself on top.
+fn load_table(path: &Path, chain: &mut Vec<PathBuf>) -> Result<toml::Table, ConfigError> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if chain.contains(&canonical) {
+        return Err(ConfigError::ExtendsError(path.to_path_buf(), "circular extends".to_string()));
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| ConfigError::ReadError(path.to_path_buf(), e.to_string()))?;
+    let mut table: toml::Table = toml::from_str(&content)
+        .map_err(|e| ConfigError::ParseError(format!("{}: {}", path.display(), e)))?;
+
+    let extends = match table.remove("extends") {
+        None => Vec::new(),
+        Some(toml::Value::String(s)) => vec![s],
+        Some(toml::Value::Array(items)) => items
+            .into_iter()
+            .map(|item| match item {
+                toml::Value::String(s) => Ok(s),
+                other => Err(ConfigError::ExtendsError(path.to_path_buf(), format!("expected a string, got {}", other))),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(other) => {
+            return Err(ConfigError::ExtendsError(path.to_path_buf(), format!("expected a string or list, got {}", other)));
+        }
+    };
+    let preset = table.get("preset").and_then(|v| v.as_str());
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let packs: Vec<PathBuf> = super::packs::installed(base_dir)
+        .iter()
+        .filter_map(|pack| pack.config_file())
+        .collect();
+    if extends.is_empty() && preset.is_none() && packs.is_empty() {
+        return Ok(table);
+    }
+
+    chain.push(canonical);
+    let mut merged = toml::Table::new();
+    for pack_config in packs {
+        merge_tables(&mut merged, load_table(&pack_config, chain)?);
+    }
+    if let Some(name) = preset {
+        merge_tables(&mut merged, preset_table(name)?);
+    }
+    for target in extends {
+        let target_path = base_dir.join(&target);
+        if target_path.is_file() {
+            merge_tables(&mut merged, load_table(&target_path, chain)?);
+        } else if super::presets::get(&target).is_some() {
+            merge_tables(&mut merged, preset_table(&target)?);
+        } else {
+            return Err(ConfigError::ExtendsError(
+                path.to_path_buf(),
+                format!("{} is neither a file nor a preset ({})", target, super::presets::names().join(", ")),
+            ));
+        }
+    }
+    chain.pop();
+
+    merge_tables(&mut merged, table);
+    Ok(merged)
+}
+
+/// Merge `overlay` into `base`, with `overlay` winning.
+///
+/// Tables merge recursively and other values are replaced. The `[rules]`
+/// lists are the exception: they accumulate, and a rule keeps the
+/// classification (disabled, warn, enabled) from the config that mentions
+/// it last. `custom_rules` accumulate too, keyed by `id`.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(mut table)) => {
+                if key == "rules" {
+                    merge_rule_lists(base_table, &mut table);
+                }
+                merge_tables(base_table, table);
+            }
+            (Some(toml::Value::Array(base_rules)), toml::Value::Array(rules)) if key == "custom_rules" => {
+                merge_custom_rules(base_rules, rules);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Custom rules accumulate; a rule with the same `id` replaces the earlier one.
+fn merge_custom_rules(base: &mut Vec<toml::Value>, overlay: Vec<toml::Value>) {
+    let id = |rule: &toml::Value| rule.get("id").and_then(|id| id.as_str()).map(str::to_string);
+    for rule in overlay {
+        match base.iter_mut().find(|existing| id(existing).is_some() && id(existing) == id(&rule)) {
+            Some(existing) => *existing = rule,
+            None => base.push(rule),
+        }
+    }
+}
+
+const RULE_LISTS: [&str; 3] = ["disabled", "warn", "enabled"];
+
+fn merge_rule_lists(base: &mut toml::Table, overlay: &mut toml::Table) {
+    let names = |table: &toml::Table, list: &str| -> Vec<toml::Value> {
+        table.get(list).and_then(|v| v.as_array()).cloned().unwrap_or_default()
+    };
+    let mentioned: Vec<toml::Value> = RULE_LISTS.iter().flat_map(|list| names(overlay, list)).collect();
+
+    for list in RULE_LISTS {
+        let mut merged: Vec<toml::Value> = names(base, list)
+            .into_iter()
+            .filter(|rule| !mentioned.contains(rule))
+            .collect();
+        for rule in names(overlay, list) {
+            if !merged.contains(&rule) {
+                merged.push(rule);
+            }
+        }
+        overlay.remove(list);
+        if !merged.is_empty() || base.contains_key(list) {
+            base.insert(list.to_string(), toml::Value::Array(merged));
+        }
+    }
+}
+
 /// Simple glob pattern matching.
-fn matches_glob(pattern: &str, path: &str) -> bool {
+pub(crate) fn matches_glob(pattern: &str, path: &str) -> bool {
     // Convert glob pattern to regex
     let mut regex_pattern = String::new();
     let mut chars = pattern.chars().peekable();
@@ -369,6 +864,10 @@ pub enum ConfigError {
     ParseError(String),
     /// Failed to write config file.
     WriteError(PathBuf, String),
+    /// Invalid `extends` in a config file.
+    ExtendsError(PathBuf, String),
+    /// Unknown preset name, with the available names.
+    UnknownPreset(String, String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -383,6 +882,12 @@ impl std::fmt::Display for ConfigError {
             ConfigError::WriteError(path, msg) => {
                 write!(f, "Failed to write config file {}: {}", path.display(), msg)
             }
+            ConfigError::ExtendsError(path, msg) => {
+                write!(f, "Invalid extends in {}: {}", path.display(), msg)
+            }
+            ConfigError::UnknownPreset(name, available) => {
+                write!(f, "Unknown preset: {} (available: {})", name, available)
+            }
         }
     }
 }
@@ -438,6 +943,37 @@ disabled = ["query-syntax", "security"]
         assert!(!config.is_rule_disabled("required-fields"));
     }
 
+    #[test]
+    fn test_is_rule_enabled() {
+        let toml = r#"
+[rules]
+enabled = ["policy-resolution"]
+"#;
+        let config = FleetLintConfig::from_str(toml).unwrap();
+
+        assert!(config.is_rule_enabled("policy-resolution"));
+        assert!(!config.is_rule_enabled("policy-description"));
+    }
+
+    #[test]
+    fn test_rule_options() {
+        use crate::linter::misspellings::MisspellingOptions;
+
+        let toml = r#"
+[rules]
+enabled = ["common-misspellings"]
+
+[rules.common-misspellings]
+words = ["fleetd"]
+"#;
+        let config = FleetLintConfig::from_str(toml).unwrap();
+        let options: MisspellingOptions = config.rule_options("common-misspellings");
+        assert_eq!(options.words, vec!["fleetd"]);
+
+        let missing: MisspellingOptions = FleetLintConfig::default().rule_options("common-misspellings");
+        assert!(missing.words.is_empty());
+    }
+
     #[test]
     fn test_is_rule_warning() {
         let toml = r#"
@@ -503,4 +1039,95 @@ min_interval = 120
         // Rules should be empty
         assert!(config.rules.disabled.is_empty());
     }
+
+    #[test]
+    fn test_discover_merges_nested_configs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("trees/a/teams")).unwrap();
+        std::fs::write(
+            root.join(CONFIG_FILE_NAME),
+            "[rules]\ndisabled = [\"security\", \"query-syntax\"]\n[thresholds]\nmin_interval = 300\nmax_interval = 3600\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("trees/a").join(CONFIG_FILE_NAME),
+            "[rules]\nwarn = [\"security\"]\n[thresholds]\nmin_interval = 120\n",
+        )
+        .unwrap();
+
+        let (paths, config) = FleetLintConfig::discover(&root.join("trees/a/teams")).unwrap().unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[1].ends_with("trees/a/.fleetlint.toml"));
+        assert_eq!(config.rules.disabled, vec!["query-syntax"]);
+        assert_eq!(config.rules.warn, vec!["security"]);
+        assert_eq!(config.thresholds.min_interval, 120);
+        assert_eq!(config.thresholds.max_interval, 3600);
+
+        // `root = true` stops the search
+        std::fs::write(root.join("trees/a").join(CONFIG_FILE_NAME), "root = true\n").unwrap();
+        let (paths, config) = FleetLintConfig::discover(&root.join("trees/a")).unwrap().unwrap();
+        assert_eq!(paths.len(), 1);
+        assert!(config.rules.disabled.is_empty());
+    }
+
+    #[test]
+    fn test_extends() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("shared")).unwrap();
+        std::fs::write(
+            root.join("shared/org.toml"),
+            "fleet_version = \"4.58.0\"\n[rules]\nenabled = [\"policy-resolution\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join(CONFIG_FILE_NAME),
+            "extends = \"shared/org.toml\"\n[rules]\ndisabled = [\"security\"]\n",
+        )
+        .unwrap();
+
+        let config = FleetLintConfig::from_file(&root.join(CONFIG_FILE_NAME)).unwrap();
+        assert_eq!(config.fleet_version.as_deref(), Some("4.58.0"));
+        assert_eq!(config.rules.enabled, vec!["policy-resolution"]);
+        assert_eq!(config.rules.disabled, vec!["security"]);
+
+        std::fs::write(root.join("shared/org.toml"), "extends = \"../.fleetlint.toml\"\n").unwrap();
+        let err = FleetLintConfig::from_file(&root.join(CONFIG_FILE_NAME)).unwrap_err();
+        assert!(err.to_string().contains("circular extends"));
+
+        std::fs::write(root.join(CONFIG_FILE_NAME), "extends = \"missing.toml\"\n").unwrap();
+        assert!(FleetLintConfig::from_file(&root.join(CONFIG_FILE_NAME)).is_err());
+    }
+
+    #[test]
+    fn test_presets() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "preset = \"fleet-recommended\"\n[rules]\ndisabled = [\"policy-description\"]\n").unwrap();
+
+        let config = FleetLintConfig::from_file(&path).unwrap();
+        assert_eq!(config.preset.as_deref(), Some("fleet-recommended"));
+        assert!(config.is_rule_enabled("policy-resolution"));
+        assert!(!config.is_rule_enabled("policy-description"));
+        assert!(config.is_rule_disabled("policy-description"));
+        assert_eq!(config.thresholds.min_interval, 300);
+
+        std::fs::write(&path, "extends = \"minimal\"\n").unwrap();
+        assert!(FleetLintConfig::from_file(&path).unwrap().is_rule_warning("duplicate-names"));
+
+        std::fs::write(&path, "preset = \"nope\"\n").unwrap();
+        assert!(FleetLintConfig::from_file(&path).unwrap_err().to_string().contains("Unknown preset: nope"));
+
+        // `lint --preset` wins over the settings the preset mentions
+        let config = FleetLintConfig::from_str("[rules]\ndisabled = [\"common-misspellings\", \"security\"]\n[thresholds]\nmin_interval = 60\n")
+            .unwrap()
+            .with_preset("cis-strict")
+            .unwrap();
+        assert!(config.is_rule_enabled("common-misspellings"));
+        assert!(config.is_rule_disabled("security"));
+        assert_eq!(config.thresholds.min_interval, 900);
+        assert_eq!(config.rule_options::<toml::Table>("enroll-secrets")["min_length"].as_integer(), Some(48));
+    }
 }
@@ -0,0 +1,331 @@
+//! Policy queries whose result doesn't depend on the host.
+//!
+//! A policy passes when its query returns rows and fails when it returns
+//! none, so `SELECT 1;` passes everywhere and `... WHERE 1 = 0` fails
+//! everywhere: the policy reports compliance without checking anything.
+//! Only the outer statement is looked at, on the [`super::sql`] tokens:
+//! subqueries and joins are taken to depend on the host.
+
+use super::sql::{tokenize, Token, TokenKind};
+
+/// What a constant policy always reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    AlwaysPasses,
+    AlwaysFails,
+}
+
+/// How sure the classification is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// Holds as long as a table has rows
+    #[default]
+    Likely,
+    /// Holds on every host
+    Certain,
+}
+
+/// Why a query's result is constant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstantResult {
+    pub outcome: Outcome,
+    pub confidence: Confidence,
+    /// "has no FROM or WHERE clause"
+    pub reason: String,
+}
+
+/// Tables with exactly one row on every host.
+const SINGLE_ROW_TABLES: &[&str] = &["os_version", "system_info", "osquery_info", "uptime", "time", "platform_info"];
+
+/// Aggregates that make a query without GROUP BY return exactly one row.
+const AGGREGATES: &[&str] = &["count", "sum", "total", "avg", "group_concat", "min", "max"];
+
+/// Clauses of the outer statement.
+const CLAUSES: &[&str] = &["FROM", "WHERE", "GROUP", "HAVING", "WINDOW", "ORDER", "LIMIT"];
+
+/// Value of a constant expression, when it is one: `None` means it
+/// depends on the row.
+fn constant(tokens: &[&Token]) -> Option<bool> {
+    let terms = split(tokens, "OR");
+    if terms.len() > 1 {
+        let values: Vec<Option<bool>> = terms.iter().map(|term| constant(term)).collect();
+        return if values.contains(&Some(true)) {
+            Some(true)
+        } else if values.iter().all(|value| *value == Some(false)) {
+            Some(false)
+        } else {
+            None
+        };
+    }
+    let factors = split(tokens, "AND");
+    if factors.len() > 1 {
+        let values: Vec<Option<bool>> = factors.iter().map(|factor| constant(factor)).collect();
+        return if values.contains(&Some(false)) {
+            Some(false)
+        } else if values.iter().all(|value| *value == Some(true)) {
+            Some(true)
+        } else {
+            None
+        };
+    }
+
+    match tokens {
+        [] => None,
+        [first, rest @ ..] if first.is_keyword("NOT") => constant(rest).map(|value| !value),
+        [_, inner @ .., _] if wrapped(tokens) => constant(inner),
+        [value] => literal(value).map(|value| value.truthy()),
+        [left, op, right] => {
+            let (left, right) = (literal(left)?, literal(right)?);
+            if op.is_keyword("IS") {
+                return Some(left == right);
+            }
+            // Comparisons with NULL are NULL, which doesn't match
+            if left == Literal::Null || right == Literal::Null {
+                return Some(false);
+            }
+            match op.text {
+                "=" | "==" => Some(left == right),
+                "!=" | "<>" => Some(left != right),
+                "<" => left.compare(&right).map(|o| o.is_lt()),
+                "<=" => left.compare(&right).map(|o| o.is_le()),
+                ">" => left.compare(&right).map(|o| o.is_gt()),
+                ">=" => left.compare(&right).map(|o| o.is_ge()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A literal operand.
+#[derive(Debug, PartialEq)]
+enum Literal {
+    Number(f64),
+    Text(String),
+    Null,
+}
+
+impl Literal {
+    fn truthy(&self) -> bool {
+        match self {
+            Literal::Number(n) => *n != 0.0,
+            Literal::Text(text) => text.trim().parse::<f64>().is_ok_and(|n| n != 0.0),
+            Literal::Null => false,
+        }
+    }
+
+    fn compare(&self, other: &Literal) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Literal::Number(a), Literal::Number(b)) => a.partial_cmp(b),
+            (Literal::Text(a), Literal::Text(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+fn literal(token: &Token) -> Option<Literal> {
+    match token.kind {
+        TokenKind::Number => token.text.replace('_', "").parse().ok().map(Literal::Number),
+        TokenKind::String if token.text.starts_with('\'') => {
+            Some(Literal::Text(token.text[1..token.text.len() - 1].replace("''", "'")))
+        }
+        TokenKind::Keyword if token.is_keyword("NULL") => Some(Literal::Null),
+        TokenKind::Identifier if token.text.eq_ignore_ascii_case("TRUE") => Some(Literal::Number(1.0)),
+        TokenKind::Identifier if token.text.eq_ignore_ascii_case("FALSE") => Some(Literal::Number(0.0)),
+        _ => None,
+    }
+}
+
+/// Whether `tokens` are one parenthesized expression.
+fn wrapped(tokens: &[&Token]) -> bool {
+    let mut depth = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.is("(") {
+            depth += 1;
+        } else if token.is(")") {
+            depth -= 1;
+            if depth == 0 && idx + 1 < tokens.len() {
+                return false;
+            }
+        } else if depth == 0 {
+            return false;
+        }
+    }
+    depth == 0 && tokens.len() >= 2
+}
+
+/// Split `tokens` on the keyword (or punctuation) `at` outside parentheses.
+/// A `BETWEEN ... AND` isn't split.
+fn split<'a, 'b>(tokens: &'b [&'a Token<'a>], at: &str) -> Vec<&'b [&'a Token<'a>]> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start, mut between) = (0, 0, false);
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.is("(") {
+            depth += 1;
+        } else if token.is(")") {
+            depth -= 1;
+        } else if depth == 0 && token.is_keyword("BETWEEN") {
+            between = true;
+        } else if depth == 0 && (token.is_keyword(at) || token.is(at)) {
+            if at == "AND" && between {
+                between = false;
+                continue;
+            }
+            parts.push(&tokens[start..idx]);
+            start = idx + 1;
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+/// Whether the result of `sql` as a policy query is the same on every host.
+pub fn constant_result(sql: &str) -> Option<ConstantResult> {
+    let all = tokenize(sql);
+    let mut tokens: Vec<&Token> =
+        all.iter().filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment)).collect();
+    while tokens.last().is_some_and(|token| token.is(";")) {
+        tokens.pop();
+    }
+    // One plain SELECT: CTEs, compound selects and several statements vary
+    let statements = split(&tokens, ";");
+    if statements.len() != 1 || !tokens.first()?.is_keyword("SELECT") {
+        return None;
+    }
+    if split(&tokens, "UNION").len() > 1 || split(&tokens, "INTERSECT").len() > 1 || split(&tokens, "EXCEPT").len() > 1 {
+        return None;
+    }
+
+    // Outer clauses, by keyword
+    let mut depth = 0;
+    let mut starts: Vec<(&str, usize)> = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.is("(") {
+            depth += 1;
+        } else if token.is(")") {
+            depth -= 1;
+        } else if depth == 0 {
+            if let Some(clause) = CLAUSES.iter().find(|clause| token.is_keyword(clause)) {
+                starts.push((clause, idx));
+            }
+        }
+    }
+    let clause = |name: &str| -> Option<&[&Token]> {
+        let position = starts.iter().position(|(clause, _)| *clause == name)?;
+        let start = starts[position].1 + 1;
+        let end = starts.get(position + 1).map_or(tokens.len(), |(_, idx)| *idx);
+        Some(&tokens[start..end])
+    };
+    let columns = &tokens[1..starts.first().map_or(tokens.len(), |(_, idx)| *idx)];
+    let text = |part: &[&Token]| match (part.first(), part.last()) {
+        (Some(first), Some(last)) => sql[first.offset..last.offset + last.text.len()].to_string(),
+        _ => String::new(),
+    };
+    let certain = |outcome, reason: String| Some(ConstantResult { outcome, confidence: Confidence::Certain, reason });
+
+    if let Some(limit) = clause("LIMIT") {
+        if limit.first().and_then(|count| literal(count)) == Some(Literal::Number(0.0)) {
+            return certain(Outcome::AlwaysFails, "has LIMIT 0".to_string());
+        }
+    }
+    let filter = clause("WHERE");
+    let filter_value = filter.and_then(constant);
+    if let (Some(filter), Some(false)) = (filter, filter_value) {
+        return certain(Outcome::AlwaysFails, format!("WHERE {} is always false", text(filter)));
+    }
+    let filters = filter.is_some() && filter_value != Some(true);
+    if let Some(having) = clause("HAVING") {
+        if constant(having) == Some(false) {
+            return certain(Outcome::AlwaysFails, format!("HAVING {} is always false", text(having)));
+        }
+        return None;
+    }
+
+    // An aggregate without GROUP BY returns one row, even when nothing matches
+    if clause("GROUP").is_none() {
+        let aggregate = columns.windows(2).enumerate().find(|(idx, pair)| {
+            let [name, open] = pair else { return false };
+            let is_call = name.kind == TokenKind::Identifier && open.is("(");
+            let nested = columns[..*idx].iter().filter(|t| t.is("(")).count() > columns[..*idx].iter().filter(|t| t.is(")")).count();
+            // min() and max() with several arguments are scalar
+            let scalar = name.text.eq_ignore_ascii_case("min") || name.text.eq_ignore_ascii_case("max");
+            let arguments = columns[idx + 2..].iter().take_while(|t| !t.is(")")).filter(|t| t.is(",")).count();
+            is_call
+                && !nested
+                && AGGREGATES.iter().any(|aggregate| name.text.eq_ignore_ascii_case(aggregate))
+                && !(scalar && arguments > 0)
+        });
+        if let Some((_, pair)) = aggregate {
+            return certain(
+                Outcome::AlwaysPasses,
+                format!("selects {}() without GROUP BY, which returns a row even when nothing matches", pair[0].text.to_lowercase()),
+            );
+        }
+    }
+
+    if filters {
+        return None;
+    }
+    let Some(from) = clause("FROM") else {
+        let reason = match filter {
+            Some(filter) => format!("has no FROM clause and WHERE {} is always true", text(filter)),
+            None => "has no FROM or WHERE clause".to_string(),
+        };
+        return certain(Outcome::AlwaysPasses, reason);
+    };
+
+    // A single table, unfiltered: passes wherever it has a row
+    let table = match from {
+        [table] => *table,
+        [table, alias] if alias.kind == TokenKind::Identifier => *table,
+        [table, alias, _] if alias.is_keyword("AS") => *table,
+        _ => return None,
+    };
+    if table.kind != TokenKind::Identifier {
+        return None;
+    }
+    let (confidence, reason) = if SINGLE_ROW_TABLES.iter().any(|name| table.text.eq_ignore_ascii_case(name)) {
+        (Confidence::Certain, format!("reads {} without filtering it, and it always has one row", table.text))
+    } else {
+        (Confidence::Likely, format!("reads {} without filtering it, so it passes wherever the table has a row", table.text))
+    };
+    Some(ConstantResult { outcome: Outcome::AlwaysPasses, confidence, reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(sql: &str) -> Option<(Outcome, Confidence)> {
+        constant_result(sql).map(|result| (result.outcome, result.confidence))
+    }
+
+    #[test]
+    fn test_constant_result() {
+        use Confidence::*;
+        use Outcome::*;
+
+        assert_eq!(classify("SELECT 1;"), Some((AlwaysPasses, Certain)));
+        assert_eq!(constant_result("select 1").unwrap().reason, "has no FROM or WHERE clause");
+        assert_eq!(classify("SELECT 1 WHERE 1 = 1 OR 'a' = 'b'"), Some((AlwaysPasses, Certain)));
+        assert_eq!(classify("SELECT 1 FROM alf WHERE global_state >= 1 AND 1=0"), Some((AlwaysFails, Certain)));
+        assert_eq!(constant_result("SELECT 1 FROM alf WHERE (1=0);").unwrap().reason, "WHERE (1=0) is always false");
+        assert_eq!(classify("SELECT 1 FROM alf WHERE NOT 1 LIMIT 1"), Some((AlwaysFails, Certain)));
+        assert_eq!(classify("SELECT 1 FROM alf LIMIT 0"), Some((AlwaysFails, Certain)));
+        assert_eq!(classify("SELECT COUNT(*) FROM apps WHERE name = 'Chrome'"), Some((AlwaysPasses, Certain)));
+        assert_eq!(classify("SELECT 1 FROM os_version;"), Some((AlwaysPasses, Certain)));
+        assert_eq!(classify("SELECT 1 FROM alf WHERE 1=1"), Some((AlwaysPasses, Likely)));
+
+        // Depend on the host
+        assert_eq!(classify("SELECT 1 FROM alf WHERE global_state >= 1;"), None);
+        assert_eq!(classify("SELECT 1 WHERE EXISTS (SELECT 1 FROM alf WHERE global_state = 1)"), None);
+        assert_eq!(classify("SELECT 1 FROM (SELECT COUNT(*) AS n FROM apps) WHERE n > 0"), None);
+        assert_eq!(classify("SELECT COUNT(*) FROM apps GROUP BY name HAVING COUNT(*) > 1"), None);
+        assert_eq!(classify("SELECT 1 FROM alf WHERE (global_state) = (1)"), None);
+        assert_eq!(classify("SELECT 1 FROM alf WHERE NULL = NULL"), Some((AlwaysFails, Certain)));
+        assert_eq!(classify("SELECT max(a, b) FROM t WHERE a BETWEEN 1 AND 2"), None);
+        assert_eq!(classify("SELECT 1 FROM a JOIN b ON a.id = b.id"), None);
+        assert_eq!(classify("SELECT 1 FROM a UNION SELECT 1 FROM b"), None);
+    }
+}
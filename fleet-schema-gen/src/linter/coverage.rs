@@ -0,0 +1,295 @@
+//! CIS benchmark coverage reporting.
+//!
+//! Collects `# cis:` annotations from each team's policies (including
+//! policies pulled in via `path:` references) and reports which controls
+//! of the bundled benchmarks are covered.
+
+use super::benchmarks::{extract_annotations, Benchmark};
+use super::fleet_config::{FleetConfig, PolicyOrPath};
+use super::repo::{team_files, team_name};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Coverage for every team found in a GitOps repository.
+#[derive(Debug, Serialize)]
+pub struct CoverageReport {
+    pub teams: Vec<TeamCoverage>,
+}
+
+/// Coverage for a single team (or the global `default.yml`).
+#[derive(Debug, Serialize)]
+pub struct TeamCoverage {
+    pub team: String,
+    pub file: PathBuf,
+    pub benchmarks: Vec<BenchmarkCoverage>,
+    /// Annotated IDs that don't exist in any bundled catalog.
+    pub unknown_ids: Vec<String>,
+}
+
+/// Coverage of one benchmark for a team.
+#[derive(Debug, Serialize)]
+pub struct BenchmarkCoverage {
+    pub benchmark: Benchmark,
+    pub name: &'static str,
+    pub total: usize,
+    pub covered: Vec<CoveredControl>,
+    pub uncovered: Vec<String>,
+}
+
+/// A covered control and the policies covering it.
+#[derive(Debug, Serialize)]
+pub struct CoveredControl {
+    pub id: String,
+    pub title: &'static str,
+    pub policies: Vec<String>,
+}
+
+impl BenchmarkCoverage {
+    /// Percentage of controls covered.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.covered.len() as f64 * 100.0 / self.total as f64
+    }
+}
+
+/// Build a coverage report for a GitOps repository root.
+///
+/// Looks at `default.yml` and `teams/*.yml`. Only the given benchmarks are
+/// reported (all bundled benchmarks when empty).
+pub fn coverage_report(root: &Path, benchmarks: &[Benchmark]) -> Result<CoverageReport> {
+    let benchmarks = if benchmarks.is_empty() {
+        Benchmark::ALL.to_vec()
+    } else {
+        benchmarks.to_vec()
+    };
+
+    let team_files = team_files(root)?;
+
+    let mut teams = Vec::new();
+    for file in team_files {
+        teams.push(team_coverage(&file, &benchmarks)?);
+    }
+
+    Ok(CoverageReport { teams })
+}
+
+/// Compute coverage for a single team file.
+pub fn team_coverage(file: &Path, benchmarks: &[Benchmark]) -> Result<TeamCoverage> {
+    let source = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+
+    let config: FleetConfig = serde_yaml::from_str(&source)
+        .with_context(|| format!("Failed to parse {}", file.display()))?;
+
+    let team = team_name(&config, file);
+
+    // Annotations from the team file plus any referenced policy files
+    let mut annotations = extract_annotations(&source);
+    if let Some(policies) = &config.policies {
+        let base_dir = file.parent().unwrap_or(Path::new("."));
+        for policy in policies {
+            if let PolicyOrPath::Path { path } = policy {
+                let resolved = base_dir.join(path);
+                if let Ok(content) = fs::read_to_string(&resolved) {
+                    annotations.extend(extract_annotations(&content));
+                }
+            }
+        }
+    }
+
+    let mut covered: Vec<BTreeMap<String, Vec<String>>> = vec![BTreeMap::new(); benchmarks.len()];
+    let mut unknown_ids = Vec::new();
+
+    for annotation in &annotations {
+        let policy_name = annotation.policy_name.clone().unwrap_or_else(|| "unnamed".to_string());
+
+        for id in &annotation.ids {
+            let matched = annotation.benchmarks_for(id);
+            let known = matched.iter().any(|b| b.control(id).is_some());
+            if !known {
+                if !unknown_ids.contains(id) {
+                    unknown_ids.push(id.clone());
+                }
+                continue;
+            }
+
+            for (idx, benchmark) in benchmarks.iter().enumerate() {
+                if matched.contains(benchmark) && benchmark.control(id).is_some() {
+                    covered[idx]
+                        .entry(id.clone())
+                        .or_default()
+                        .push(policy_name.clone());
+                }
+            }
+        }
+    }
+
+    let benchmarks = benchmarks
+        .iter()
+        .zip(covered)
+        .map(|(benchmark, covered)| {
+            let covered_ids: HashSet<&str> = covered.keys().map(|k| k.as_str()).collect();
+
+            let mut uncovered: Vec<String> = benchmark
+                .controls()
+                .values()
+                .filter(|control| !covered_ids.contains(control.id))
+                .map(|control| control.id.to_string())
+                .collect();
+            uncovered.sort_by(|a, b| compare_control_ids(a, b));
+
+            let mut covered: Vec<CoveredControl> = covered
+                .into_iter()
+                .map(|(id, policies)| CoveredControl {
+                    title: benchmark.control(&id).map(|c| c.title).unwrap_or_default(),
+                    id,
+                    policies,
+                })
+                .collect();
+            covered.sort_by(|a, b| compare_control_ids(&a.id, &b.id));
+
+            BenchmarkCoverage {
+                benchmark: *benchmark,
+                name: benchmark.display_name(),
+                total: benchmark.controls().len(),
+                covered,
+                uncovered,
+            }
+        })
+        .collect();
+
+    Ok(TeamCoverage {
+        team,
+        file: file.to_path_buf(),
+        benchmarks,
+        unknown_ids,
+    })
+}
+
+/// Order control IDs numerically by section (`2.10.1` after `2.3.1`).
+fn compare_control_ids(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |id: &str| -> Vec<u32> { id.split('.').map(|p| p.parse().unwrap_or(0)).collect() };
+    parse(a).cmp(&parse(b))
+}
+
+impl CoverageReport {
+    /// Print the report to stdout.
+    pub fn print(&self, show_uncovered: bool) {
+        for team in &self.teams {
+            println!("\n{} {} ({})",
+                "Team:".bold(),
+                team.team.cyan(),
+                team.file.display().to_string().dimmed()
+            );
+
+            for coverage in &team.benchmarks {
+                let summary = format!(
+                    "{}/{} ({:.0}%)",
+                    coverage.covered.len(),
+                    coverage.total,
+                    coverage.percent()
+                );
+                println!("  {} {}", coverage.name.bold(), summary.yellow());
+
+                for control in &coverage.covered {
+                    println!("    {} {} {}",
+                        "✓".green(),
+                        control.id.green(),
+                        control.policies.join(", ").dimmed()
+                    );
+                }
+
+                if show_uncovered {
+                    for id in &coverage.uncovered {
+                        let title = coverage.benchmark.control(id).map(|c| c.title).unwrap_or_default();
+                        println!("    {} {} {}", "✗".red(), id.red(), title.dimmed());
+                    }
+                }
+            }
+
+            if !team.unknown_ids.is_empty() {
+                println!("  {} Unknown control IDs: {}",
+                    "⚠".yellow(),
+                    team.unknown_ids.join(", ").yellow()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_compare_control_ids() {
+        let mut ids = vec!["2.10.1", "2.3.1.1", "1.1", "2.2.1"];
+        ids.sort_by(|a, b| compare_control_ids(a, b));
+        assert_eq!(ids, vec!["1.1", "2.2.1", "2.3.1.1", "2.10.1"]);
+    }
+
+    #[test]
+    fn test_coverage_report_follows_path_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+
+        fs::write(
+            root.join("lib/macos-policies.yml"),
+            r#"# cis: 2.6.6
+- name: FileVault enabled
+  query: SELECT 1 FROM filevault_status WHERE status = 'FileVault is On.';
+  platform: darwin
+"#,
+        )
+        .unwrap();
+
+        fs::write(
+            root.join("teams/workstations.yml"),
+            r#"name: Workstations
+policies:
+  - path: ../lib/macos-policies.yml
+  # cis: 2.2.1, 42.42
+  - name: Firewall enabled
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+    platform: darwin
+"#,
+        )
+        .unwrap();
+
+        let report = coverage_report(root, &[Benchmark::CisMacos]).unwrap();
+        assert_eq!(report.teams.len(), 1);
+
+        let team = &report.teams[0];
+        assert_eq!(team.team, "Workstations");
+        assert_eq!(team.unknown_ids, vec!["42.42"]);
+
+        let macos = &team.benchmarks[0];
+        let covered: Vec<&str> = macos.covered.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(covered, vec!["2.2.1", "2.6.6"]);
+        assert_eq!(macos.uncovered.len(), macos.total - 2);
+    }
+
+    #[test]
+    fn test_coverage_report_requires_team_files() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(coverage_report(temp_dir.path(), &[]).is_err());
+    }
+
+    #[test]
+    fn test_coverage_report_rejects_broken_team_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("default.yml"), "policies:\n  - path: [unclosed\n").unwrap();
+        let error = coverage_report(temp_dir.path(), &[]).unwrap_err();
+        assert!(error.to_string().starts_with("Failed to parse"), "{}", error);
+    }
+}
@@ -0,0 +1,237 @@
+//! Declarative lint rules from `.fleetlint.toml` and policy packs.
+//!
+//! Organizations encode conventions ("every policy has a resolution",
+//! "query names start with the team prefix") without writing Rust:
+//!
+//! ```toml
+//! [[custom_rules]]
+//! id = "acme/policy-owner"
+//! section = "policies"
+//! field = "description"
+//! pattern = "Owner: @"
+//! message = "Policy descriptions must name an owner"
+//! ```
+//!
+//! Each rule checks one field of the items of a section. All of them run
+//! as the `custom-rules` rule; an individual rule can still be listed by
+//! `id` in `rules.disabled` or `rules.warn`.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use super::error::Severity;
+
+/// A declarative rule on one field of a section's items.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomRule {
+    /// Identifier, conventionally `<org>/<name>`; used in messages and in
+    /// `rules.disabled`/`rules.warn`.
+    pub id: String,
+
+    /// Section whose items are checked: `policies`, `queries` or `labels`.
+    pub section: String,
+
+    /// Field of each item to check.
+    pub field: String,
+
+    /// The field must be present and non-empty.
+    pub required: bool,
+
+    /// Regex string values must match.
+    pub pattern: Option<String>,
+
+    /// Allowed values.
+    pub values: Option<Vec<String>>,
+
+    /// Maximum length of string values.
+    pub max_length: Option<usize>,
+
+    /// `error`, `warning` (default) or `info`.
+    pub severity: Option<String>,
+
+    /// Message replacing the generated one.
+    pub message: Option<String>,
+
+    /// Help shown with the finding.
+    pub help: Option<String>,
+}
+
+/// Sections custom rules can target.
+pub const SECTIONS: [&str; 3] = ["policies", "queries", "labels"];
+
+/// A violation of a custom rule by one item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Name of the offending item, if it has one.
+    pub item: Option<String>,
+    pub message: String,
+}
+
+impl CustomRule {
+    /// Problems with the rule definition itself.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("custom rule without an id".to_string());
+        }
+        if !SECTIONS.contains(&self.section.as_str()) {
+            return Err(format!(
+                "custom rule {}: section must be one of {}, got '{}'",
+                self.id,
+                SECTIONS.join(", "),
+                self.section
+            ));
+        }
+        if self.field.is_empty() {
+            return Err(format!("custom rule {}: missing field", self.id));
+        }
+        if let Some(pattern) = &self.pattern {
+            Regex::new(pattern).map_err(|e| format!("custom rule {}: invalid pattern: {}", self.id, e))?;
+        }
+        if let Some(severity) = &self.severity {
+            if !matches!(severity.as_str(), "error" | "warning" | "info") {
+                return Err(format!("custom rule {}: severity must be error, warning or info", self.id));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self.severity.as_deref() {
+            Some("error") => Severity::Error,
+            Some("info") => Severity::Info,
+            _ => Severity::Warning,
+        }
+    }
+
+    /// Check the items of the rule's section (inline definitions only;
+    /// `path:` references are checked in the file they point to).
+    pub fn check(&self, items: &[serde_yaml::Value]) -> Vec<Violation> {
+        let pattern = self.pattern.as_deref().and_then(|p| Regex::new(p).ok());
+        let kind = item_kind(&self.section);
+
+        let mut violations = Vec::new();
+        for item in items.iter().filter(|item| item.get("path").is_none()) {
+            let name = item.get("name").and_then(|n| n.as_str()).map(str::to_string);
+            let subject = format!("{} '{}'", kind, name.as_deref().unwrap_or("unnamed"));
+            let value = item.get(self.field.as_str()).filter(|v| !v.is_null());
+            let text = value.map(scalar_text);
+
+            let problem = match text.as_deref() {
+                None | Some("") if self.required => Some(format!("{} is missing `{}`", subject, self.field)),
+                None | Some("") => None,
+                Some(text) => {
+                    if let Some(re) = pattern.as_ref().filter(|re| !re.is_match(text)) {
+                        Some(format!("`{}` of {} does not match `{}`", self.field, subject, re.as_str()))
+                    } else if let Some(values) = self.values.as_ref().filter(|v| !v.iter().any(|v| v == text)) {
+                        Some(format!("`{}` of {} must be one of: {}", self.field, subject, values.join(", ")))
+                    } else {
+                        self.max_length.filter(|max| text.chars().count() > *max).map(|max| {
+                            format!("`{}` of {} is longer than {} characters", self.field, subject, max)
+                        })
+                    }
+                }
+            };
+
+            if let Some(problem) = problem {
+                let message = match &self.message {
+                    Some(message) => format!("{}: {} ({})", subject, message, self.id),
+                    None => format!("{} ({})", problem, self.id),
+                };
+                violations.push(Violation { item: name, message });
+            }
+        }
+        violations
+    }
+}
+
+/// `policies` -> `Policy`.
+fn item_kind(section: &str) -> &'static str {
+    match section {
+        "queries" => "Query",
+        "labels" => "Label",
+        _ => "Policy",
+    }
+}
+
+fn scalar_text(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.trim().to_string(),
+        serde_yaml::Value::Bool(b) => b.to_string(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(yaml: &str) -> Vec<serde_yaml::Value> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_custom_rule_checks() {
+        let policies = items(
+            "- name: Disk encryption\n  description: 'Owner: @secops'\n  platform: darwin\n\
+             - name: Firewall\n  platform: beos\n\
+             - path: ../lib/policies.yml\n",
+        );
+
+        let owner = CustomRule {
+            id: "acme/owner".to_string(),
+            section: "policies".to_string(),
+            field: "description".to_string(),
+            required: true,
+            pattern: Some("Owner: @".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            owner.check(&policies),
+            vec![Violation {
+                item: Some("Firewall".to_string()),
+                message: "Policy 'Firewall' is missing `description` (acme/owner)".to_string(),
+            }]
+        );
+
+        let platforms = CustomRule {
+            id: "acme/platforms".to_string(),
+            section: "policies".to_string(),
+            field: "platform".to_string(),
+            values: Some(vec!["darwin".to_string(), "windows".to_string()]),
+            message: Some("only macOS and Windows are managed".to_string()),
+            severity: Some("error".to_string()),
+            ..Default::default()
+        };
+        let violations = platforms.check(&policies);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].message, "Policy 'Firewall': only macOS and Windows are managed (acme/platforms)");
+        assert_eq!(platforms.severity(), Severity::Error);
+
+        let short_names = CustomRule {
+            id: "acme/short-names".to_string(),
+            section: "policies".to_string(),
+            field: "name".to_string(),
+            max_length: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            short_names.check(&policies)[0].message,
+            "`name` of Policy 'Disk encryption' is longer than 10 characters (acme/short-names)"
+        );
+    }
+
+    #[test]
+    fn test_custom_rule_validation() {
+        let rule = CustomRule {
+            id: "acme/x".to_string(),
+            section: "policies".to_string(),
+            field: "name".to_string(),
+            ..Default::default()
+        };
+        assert!(rule.validate().is_ok());
+        assert!(CustomRule { section: "software".to_string(), ..rule.clone() }.validate().is_err());
+        assert!(CustomRule { pattern: Some("(".to_string()), ..rule.clone() }.validate().is_err());
+        assert!(CustomRule { severity: Some("fatal".to_string()), ..rule }.validate().is_err());
+    }
+}
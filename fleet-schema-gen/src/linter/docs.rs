@@ -0,0 +1,446 @@
+//! Human-readable documentation for a GitOps repository.
+//!
+//! Renders one page per team (policies, queries, software) plus an index,
+//! as Markdown or standalone HTML, so compliance reviewers can see what is
+//! enforced without reading YAML.
+
+use super::repo::{GitopsRepo, Team};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Output format for generated docs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocsFormat {
+    Markdown,
+    Html,
+}
+
+impl DocsFormat {
+    /// Parse a format name (`markdown`/`md`, `html`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "markdown" | "md" => Some(DocsFormat::Markdown),
+            "html" => Some(DocsFormat::Html),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            DocsFormat::Markdown => "md",
+            DocsFormat::Html => "html",
+        }
+    }
+}
+
+/// A documentation page, independent of output format.
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// File name without extension.
+    pub slug: String,
+    pub title: String,
+    pub blocks: Vec<Block>,
+}
+
+/// Content blocks rendered by each output format.
+#[derive(Debug, Clone)]
+pub enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    /// Label/value pairs (platform, criticality, ...).
+    Fields(Vec<(String, String)>),
+    Code(String),
+    /// Header row and rows; cells may link as `(text, Some(slug))`.
+    Table(Vec<String>, Vec<Vec<(String, Option<String>)>>),
+}
+
+/// Build the pages for a repository: an index plus one page per team.
+pub fn build_pages(repo: &GitopsRepo) -> Vec<Page> {
+    let mut pages = Vec::new();
+    let mut index_rows = Vec::new();
+    let mut used_slugs: Vec<String> = vec!["index".to_string()];
+
+    for team in &repo.teams {
+        let mut slug = slugify(&team.name);
+        if used_slugs.contains(&slug) {
+            slug = format!("{}-{}", slug, used_slugs.len());
+        }
+        used_slugs.push(slug.clone());
+
+        let critical = team
+            .policies
+            .iter()
+            .filter(|p| p.item.critical == Some(true))
+            .count();
+
+        index_rows.push(vec![
+            (team.name.clone(), Some(slug.clone())),
+            (team.policies.len().to_string(), None),
+            (critical.to_string(), None),
+            (team.queries.len().to_string(), None),
+            (team.software.len().to_string(), None),
+        ]);
+
+        pages.push(team_page(team, slug, &repo.root));
+    }
+
+    let index = Page {
+        slug: "index".to_string(),
+        title: "Fleet GitOps configuration".to_string(),
+        blocks: vec![
+            Block::Heading(1, "Fleet GitOps configuration".to_string()),
+            Block::Paragraph(format!(
+                "Generated from {} on {}.",
+                repo.root.display(),
+                chrono::Local::now().format("%Y-%m-%d")
+            )),
+            Block::Table(
+                ["Team", "Policies", "Critical", "Queries", "Software"]
+                    .iter()
+                    .map(|h| h.to_string())
+                    .collect(),
+                index_rows,
+            ),
+        ],
+    };
+
+    pages.insert(0, index);
+    pages
+}
+
+/// Build the page for a single team.
+fn team_page(team: &Team, slug: String, root: &Path) -> Page {
+    let relative = |file: &Path| {
+        file.strip_prefix(root)
+            .unwrap_or(file)
+            .display()
+            .to_string()
+    };
+
+    let mut blocks = vec![
+        Block::Heading(1, team.name.clone()),
+        Block::Paragraph(format!("Defined in `{}`.", relative(&team.file))),
+    ];
+
+    blocks.push(Block::Heading(2, format!("Policies ({})", team.policies.len())));
+    if team.policies.is_empty() {
+        blocks.push(Block::Paragraph("No policies.".to_string()));
+    }
+    for sourced in &team.policies {
+        let policy = &sourced.item;
+        blocks.push(Block::Heading(3, policy.name.clone().unwrap_or_else(|| "Unnamed policy".to_string())));
+        if let Some(description) = &policy.description {
+            blocks.push(Block::Paragraph(description.trim().to_string()));
+        }
+
+        let mut fields = vec![
+            ("Platform".to_string(), policy.platform.clone().unwrap_or_else(|| "all".to_string())),
+            ("Critical".to_string(), if policy.critical == Some(true) { "yes" } else { "no" }.to_string()),
+        ];
+        if let Some(resolution) = &policy.resolution {
+            fields.push(("Resolution".to_string(), resolution.trim().to_string()));
+        }
+        fields.push(("Source".to_string(), format!("`{}`", relative(&sourced.file))));
+        blocks.push(Block::Fields(fields));
+
+        if let Some(query) = &policy.query {
+            blocks.push(Block::Code(query.trim().to_string()));
+        }
+    }
+
+    blocks.push(Block::Heading(2, format!("Queries ({})", team.queries.len())));
+    if team.queries.is_empty() {
+        blocks.push(Block::Paragraph("No queries.".to_string()));
+    }
+    for sourced in &team.queries {
+        let query = &sourced.item;
+        blocks.push(Block::Heading(3, query.name.clone().unwrap_or_else(|| "Unnamed query".to_string())));
+        if let Some(description) = &query.description {
+            blocks.push(Block::Paragraph(description.trim().to_string()));
+        }
+
+        let mut fields = vec![
+            ("Platform".to_string(), query.platform.clone().unwrap_or_else(|| "all".to_string())),
+        ];
+        if let Some(interval) = query.interval {
+            fields.push(("Interval".to_string(), format!("{}s", interval)));
+        }
+        if let Some(logging) = &query.logging {
+            fields.push(("Logging".to_string(), logging.clone()));
+        }
+        fields.push(("Source".to_string(), format!("`{}`", relative(&sourced.file))));
+        blocks.push(Block::Fields(fields));
+
+        if let Some(sql) = &query.query {
+            blocks.push(Block::Code(sql.trim().to_string()));
+        }
+    }
+
+    blocks.push(Block::Heading(2, format!("Software ({})", team.software.len())));
+    if team.software.is_empty() {
+        blocks.push(Block::Paragraph("No software.".to_string()));
+    } else {
+        let rows = team
+            .software
+            .iter()
+            .map(|software| {
+                let url = software
+                    .package
+                    .as_ref()
+                    .and_then(|p| p.url.clone())
+                    .unwrap_or_default();
+                let self_service = match software.self_service() {
+                    Some(true) => "yes",
+                    _ => "no",
+                };
+                let definition = software
+                    .file
+                    .as_deref()
+                    .map(relative)
+                    .unwrap_or_default();
+                vec![
+                    (software.name.clone(), None),
+                    (software.source.key().to_string(), None),
+                    (self_service.to_string(), None),
+                    (definition, None),
+                    (url, None),
+                ]
+            })
+            .collect();
+
+        blocks.push(Block::Table(
+            ["Name", "Source", "Self-service", "Definition", "URL"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            rows,
+        ));
+    }
+
+    if !team.unresolved.is_empty() {
+        blocks.push(Block::Heading(2, "Unresolved references".to_string()));
+        blocks.push(Block::Paragraph(
+            team.unresolved
+                .iter()
+                .map(|p| format!("`{}`", p))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+
+    Page {
+        slug,
+        title: team.name.clone(),
+        blocks,
+    }
+}
+
+/// Render a page as Markdown.
+pub fn render_markdown(page: &Page) -> String {
+    let mut out = String::new();
+
+    for block in &page.blocks {
+        match block {
+            Block::Heading(level, text) => {
+                out.push_str(&format!("{} {}\n\n", "#".repeat(*level), text));
+            }
+            Block::Paragraph(text) => out.push_str(&format!("{}\n\n", text)),
+            Block::Fields(fields) => {
+                for (label, value) in fields {
+                    out.push_str(&format!("- **{}:** {}\n", label, value.replace('\n', " ")));
+                }
+                out.push('\n');
+            }
+            Block::Code(code) => out.push_str(&format!("```sql\n{}\n```\n\n", code)),
+            Block::Table(headers, rows) => {
+                out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                for row in rows {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|(text, link)| {
+                            let text = text.replace('|', "\\|");
+                            match link {
+                                Some(slug) => format!("[{}]({}.md)", text, slug),
+                                None => text,
+                            }
+                        })
+                        .collect();
+                    out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                }
+                out.push('\n');
+            }
+        }
+    }
+
+    out
+}
+
+/// Render a page as a standalone HTML document.
+pub fn render_html(page: &Page) -> String {
+    let mut body = String::new();
+
+    for block in &page.blocks {
+        match block {
+            Block::Heading(level, text) => {
+                body.push_str(&format!("<h{0}>{1}</h{0}>\n", level, inline_html(text)));
+            }
+            Block::Paragraph(text) => body.push_str(&format!("<p>{}</p>\n", inline_html(text))),
+            Block::Fields(fields) => {
+                body.push_str("<ul>\n");
+                for (label, value) in fields {
+                    body.push_str(&format!(
+                        "  <li><strong>{}:</strong> {}</li>\n",
+                        escape_html(label),
+                        inline_html(value)
+                    ));
+                }
+                body.push_str("</ul>\n");
+            }
+            Block::Code(code) => {
+                body.push_str(&format!("<pre><code class=\"language-sql\">{}</code></pre>\n", escape_html(code)));
+            }
+            Block::Table(headers, rows) => {
+                body.push_str("<table>\n  <tr>");
+                for header in headers {
+                    body.push_str(&format!("<th>{}</th>", escape_html(header)));
+                }
+                body.push_str("</tr>\n");
+                for row in rows {
+                    body.push_str("  <tr>");
+                    for (text, link) in row {
+                        let cell = match link {
+                            Some(slug) => format!("<a href=\"{}.html\">{}</a>", slug, escape_html(text)),
+                            None => escape_html(text),
+                        };
+                        body.push_str(&format!("<td>{}</td>", cell));
+                    }
+                    body.push_str("</tr>\n");
+                }
+                body.push_str("</table>\n");
+            }
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(&page.title),
+        HTML_STYLE,
+        body
+    )
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }
+pre { background: #f5f5f5; padding: 8px; overflow-x: auto; }
+";
+
+/// Write all pages to an output directory, returning the written files.
+pub fn write_site(repo: &GitopsRepo, output: &Path, format: DocsFormat) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+
+    let mut written = Vec::new();
+    for page in build_pages(repo) {
+        let content = match format {
+            DocsFormat::Markdown => render_markdown(&page),
+            DocsFormat::Html => render_html(&page),
+        };
+
+        let path = output.join(format!("{}.{}", page.slug, format.extension()));
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Escape text for HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape text for HTML, rendering `code` spans.
+fn inline_html(text: &str) -> String {
+    escape_html(text)
+        .split('`')
+        .enumerate()
+        .map(|(i, part)| if i % 2 == 1 { format!("<code>{}</code>", part) } else { part.to_string() })
+        .collect()
+}
+
+/// Turn a team name into a file name.
+pub(crate) fn slugify(name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        "team".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::repo::load_repo;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("💻 Workstations (Canary)"), "workstations-canary");
+        assert_eq!(slugify("Global"), "global");
+        assert_eq!(slugify("🔥"), "team");
+    }
+
+    #[test]
+    fn test_write_site() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("repo");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("default.yml"),
+            r#"policies:
+  - name: Firewall enabled
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+    platform: darwin
+    critical: true
+    resolution: Turn on the <firewall>.
+"#,
+        )
+        .unwrap();
+
+        let repo = load_repo(&root).unwrap();
+        let output = temp_dir.path().join("site");
+
+        let written = write_site(&repo, &output, DocsFormat::Markdown).unwrap();
+        assert_eq!(written.len(), 2);
+
+        let index = fs::read_to_string(output.join("index.md")).unwrap();
+        assert!(index.contains("| [Global](global.md) | 1 | 1 | 0 | 0 |"));
+
+        let team = fs::read_to_string(output.join("global.md")).unwrap();
+        assert!(team.contains("### Firewall enabled"));
+        assert!(team.contains("- **Critical:** yes"));
+        assert!(team.contains("```sql\nSELECT 1 FROM alf WHERE global_state >= 1;\n```"));
+
+        write_site(&repo, &output, DocsFormat::Html).unwrap();
+        let html = fs::read_to_string(output.join("global.html")).unwrap();
+        assert!(html.contains("Turn on the &lt;firewall&gt;."));
+        assert!(html.contains("<h3>Firewall enabled</h3>"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Merging items that share a name.
+//!
+//! The `duplicate-names` rule reports the second definition; editors offer
+//! to delete it, or to merge it into the first: fields only the duplicate
+//! has are copied over, and fields both have with different values are
+//! reported as conflicts, keeping the first definition's value.
+
+use super::sort::{indent, is_trivia, list_items, ListItem};
+
+/// How to merge a duplicate into the first definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePlan {
+    pub first: ListItem,
+    pub duplicate: ListItem,
+    /// Line to insert the duplicate's extra fields before (0-indexed)
+    pub insert_line: usize,
+    /// The extra fields, newline-terminated (empty if there are none)
+    pub insert_text: String,
+    /// Fields both define differently, in the duplicate's order
+    pub conflicts: Vec<String>,
+}
+
+/// An item-level field: its key and lines, with the dash of the item's
+/// first line turned into a space
+struct Field {
+    key: String,
+    lines: Vec<String>,
+}
+
+fn fields(lines: &[&str], item: &ListItem) -> Vec<Field> {
+    let mut fields: Vec<Field> = Vec::new();
+    for (idx, line) in lines.iter().enumerate().take(item.end_line).skip(item.dash_line) {
+        let mut line = line.to_string();
+        if idx == item.dash_line {
+            line.replace_range(item.indent..item.indent + 1, " ");
+        }
+        let at_item_level = !is_trivia(&line) && indent(&line) == item.indent + 2;
+        match line.trim_start().split_once(':') {
+            Some((key, _)) if at_item_level => fields.push(Field { key: key.to_string(), lines: vec![line] }),
+            _ => {
+                if let Some(field) = fields.last_mut() {
+                    field.lines.push(line);
+                }
+            }
+        }
+    }
+    // Trailing comments and blank lines inside an item aren't part of a value
+    for field in &mut fields {
+        while field.lines.len() > 1 && field.lines.last().is_some_and(|line| is_trivia(line)) {
+            field.lines.pop();
+        }
+    }
+    fields
+}
+
+/// The item containing `line` (0-indexed) and an earlier item of the same
+/// list with the same name, if it is a duplicate.
+pub fn find_duplicate(source: &str, line: usize) -> Option<(ListItem, ListItem)> {
+    let items = list_items(source);
+    let duplicate = items.iter().find(|item| (item.dash_line..item.end_line).contains(&line))?;
+    let name = duplicate.name.as_ref()?;
+    let first = items
+        .iter()
+        .find(|item| item.section == duplicate.section && item.name.as_ref() == Some(name))?;
+    (first != duplicate).then(|| (first.clone(), duplicate.clone()))
+}
+
+/// Plan merging `duplicate` into `first`.
+pub fn merge_plan(source: &str, first: ListItem, duplicate: ListItem) -> MergePlan {
+    let lines: Vec<&str> = source.lines().collect();
+    let existing = fields(&lines, &first);
+    let mut insert_text = String::new();
+    let mut conflicts = Vec::new();
+
+    let normalize = |field: &Field| field.lines.iter().map(|line| line.trim_end().to_string()).collect::<Vec<_>>();
+    for field in fields(&lines, &duplicate) {
+        match existing.iter().find(|existing| existing.key == field.key) {
+            Some(existing) => {
+                if normalize(existing) != normalize(&field) {
+                    conflicts.push(field.key);
+                }
+            }
+            None => {
+                for line in &field.lines {
+                    insert_text.push_str(line);
+                    insert_text.push('\n');
+                }
+            }
+        }
+    }
+
+    MergePlan { insert_line: first.end_line, first, duplicate, insert_text, conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+policies:
+  - name: Firewall
+    query: SELECT 1 FROM alf;
+  - name: Disk
+    query: SELECT 1;
+  # Copied from the macOS team
+  - name: Firewall
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+    critical: true
+    labels_include_any:
+      - Laptops
+queries:
+  - name: Firewall
+";
+
+    #[test]
+    fn test_find_duplicate() {
+        let (first, duplicate) = find_duplicate(SOURCE, 7).unwrap();
+        assert_eq!((first.dash_line, duplicate.start_line, duplicate.end_line), (1, 5, 11));
+        // The first definition and a query with the same name aren't duplicates
+        assert!(find_duplicate(SOURCE, 1).is_none());
+        assert!(find_duplicate(SOURCE, 12).is_none());
+    }
+
+    #[test]
+    fn test_merge_plan() {
+        let (first, duplicate) = find_duplicate(SOURCE, 6).unwrap();
+        let plan = merge_plan(SOURCE, first, duplicate);
+        assert_eq!(plan.insert_line, 3);
+        assert_eq!(plan.insert_text, "    critical: true\n    labels_include_any:\n      - Laptops\n");
+        assert_eq!(plan.conflicts, vec!["query"]);
+    }
+}
@@ -0,0 +1,334 @@
+//! Effective settings per team.
+//!
+//! Fleet applies the global `default.yml` plus team overrides: some team
+//! settings fall back to the global value when omitted (features, host
+//! expiry, agent options), while controls are per team and the global
+//! controls apply to hosts in "No team". This module resolves the value each
+//! team actually ends up with, and where it came from.
+
+use super::repo::{GitopsRepo, Team};
+use colored::Colorize;
+use serde::Serialize;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A setting that can be set globally and/or per team.
+#[derive(Debug, Clone, Copy)]
+pub struct SettingSpec {
+    /// Display key, e.g. `features.enable_host_users`.
+    pub key: &'static str,
+    /// Dotted path in `default.yml`.
+    pub global_path: &'static str,
+    /// Dotted path in a team file.
+    pub team_path: &'static str,
+    /// Teams fall back to the global value when the setting is omitted.
+    pub inherited: bool,
+    /// Whether turning the setting off weakens the security baseline.
+    pub security: bool,
+}
+
+/// Settings modeled for inheritance.
+pub const SETTINGS: &[SettingSpec] = &[
+    SettingSpec {
+        key: "features.enable_host_users",
+        global_path: "org_settings.features.enable_host_users",
+        team_path: "team_settings.features.enable_host_users",
+        inherited: true,
+        security: false,
+    },
+    SettingSpec {
+        key: "features.enable_software_inventory",
+        global_path: "org_settings.features.enable_software_inventory",
+        team_path: "team_settings.features.enable_software_inventory",
+        inherited: true,
+        security: false,
+    },
+    SettingSpec {
+        key: "host_expiry_settings.host_expiry_enabled",
+        global_path: "org_settings.host_expiry_settings.host_expiry_enabled",
+        team_path: "team_settings.host_expiry_settings.host_expiry_enabled",
+        inherited: true,
+        security: false,
+    },
+    SettingSpec {
+        key: "host_expiry_settings.host_expiry_window",
+        global_path: "org_settings.host_expiry_settings.host_expiry_window",
+        team_path: "team_settings.host_expiry_settings.host_expiry_window",
+        inherited: true,
+        security: false,
+    },
+    SettingSpec {
+        key: "agent_options",
+        global_path: "agent_options",
+        team_path: "agent_options",
+        inherited: true,
+        security: false,
+    },
+    SettingSpec {
+        key: "controls.enable_disk_encryption",
+        global_path: "controls.enable_disk_encryption",
+        team_path: "controls.enable_disk_encryption",
+        inherited: false,
+        security: true,
+    },
+    SettingSpec {
+        key: "controls.windows_require_bitlocker_pin",
+        global_path: "controls.windows_require_bitlocker_pin",
+        team_path: "controls.windows_require_bitlocker_pin",
+        inherited: false,
+        security: true,
+    },
+    SettingSpec {
+        key: "controls.macos_setup.enable_end_user_authentication",
+        global_path: "controls.macos_setup.enable_end_user_authentication",
+        team_path: "controls.macos_setup.enable_end_user_authentication",
+        inherited: false,
+        security: true,
+    },
+    SettingSpec {
+        key: "controls.macos_updates.minimum_version",
+        global_path: "controls.macos_updates.minimum_version",
+        team_path: "controls.macos_updates.minimum_version",
+        inherited: false,
+        security: false,
+    },
+    SettingSpec {
+        key: "controls.windows_updates.deadline_days",
+        global_path: "controls.windows_updates.deadline_days",
+        team_path: "controls.windows_updates.deadline_days",
+        inherited: false,
+        security: false,
+    },
+];
+
+/// A `features` flag found in the generated schemas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureSpec {
+    pub name: String,
+    /// Oldest generated version listing the flag; `None` when the oldest
+    /// generated schema already has it, so when it was added is unknown.
+    pub since: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Known `features` keys (org and team settings), read from the strict
+/// default schemas generated in `versions_dir` (`schemas/4.58.0/`).
+///
+/// Empty when no version has been generated.
+pub fn versioned_features(versions_dir: &Path) -> Vec<FeatureSpec> {
+    use super::validate::{generated_file, generated_versions, SchemaKind};
+
+    let mut features: Vec<FeatureSpec> = Vec::new();
+    let mut oldest = true;
+    for (version, dir) in generated_versions(versions_dir) {
+        let Some(schema) = generated_file(&dir, SchemaKind::Default.file_name(), "strict")
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        else {
+            continue;
+        };
+        let newer = !std::mem::replace(&mut oldest, false);
+        let Some(flags) = schema
+            .pointer("/properties/org_settings/properties/features/properties")
+            .and_then(|flags| flags.as_object())
+        else {
+            continue;
+        };
+        for (name, flag) in flags {
+            if features.iter().any(|f| &f.name == name) {
+                continue;
+            }
+            features.push(FeatureSpec {
+                name: name.clone(),
+                since: newer.then(|| version.clone()),
+                description: flag.get("description").and_then(|d| d.as_str()).map(str::to_string),
+            });
+        }
+    }
+    features
+}
+
+/// Where an effective value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Origin {
+    /// `default.yml`
+    Global,
+    /// The team's own file (or `no-team.yml`)
+    Team,
+}
+
+/// The value a team ends up with for one setting.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveSetting {
+    pub value: Value,
+    pub origin: Origin,
+    /// Global value replaced by a team override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<Value>,
+}
+
+/// Effective settings for one team.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveConfig {
+    pub team: String,
+    /// Team file (`None` for "No team" without a `no-team.yml`).
+    pub file: Option<PathBuf>,
+    pub settings: BTreeMap<&'static str, EffectiveSetting>,
+}
+
+/// Look up a dotted path in a YAML value.
+pub fn get_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |node, key| node.get(key))
+}
+
+/// The global `default.yml` team, if present.
+pub fn global_team(repo: &GitopsRepo) -> Option<&Team> {
+    repo.teams.iter().find(|t| is_file_stem(&t.file, "default"))
+}
+
+/// The `teams/no-team.yml` team, if present.
+pub fn no_team(repo: &GitopsRepo) -> Option<&Team> {
+    repo.teams.iter().find(|t| is_file_stem(&t.file, "no-team"))
+}
+
+fn is_file_stem(file: &Path, stem: &str) -> bool {
+    file.file_stem().and_then(|s| s.to_str()) == Some(stem)
+}
+
+/// A team file as a YAML value (including fields not modeled by `FleetConfig`).
+pub fn team_value(team: &Team) -> Value {
+    std::fs::read_to_string(&team.file)
+        .ok()
+        .and_then(|content| serde_yaml::from_str(&content).ok())
+        .unwrap_or(Value::Null)
+}
+
+/// Compute effective settings for every team, including "No team".
+pub fn effective_configs(repo: &GitopsRepo) -> Vec<EffectiveConfig> {
+    let global = global_team(repo).map(team_value).unwrap_or(Value::Null);
+    let no_team_file = no_team(repo);
+
+    let mut configs: Vec<EffectiveConfig> = repo
+        .teams
+        .iter()
+        .filter(|t| !is_file_stem(&t.file, "default") && !is_file_stem(&t.file, "no-team"))
+        .map(|team| EffectiveConfig {
+            team: team.name.clone(),
+            file: Some(team.file.clone()),
+            settings: resolve(&global, &team_value(team), false),
+        })
+        .collect();
+
+    // "No team" hosts get the global controls unless no-team.yml sets them
+    if global_team(repo).is_some() || no_team_file.is_some() {
+        let overrides = no_team_file.map(team_value).unwrap_or(Value::Null);
+        configs.push(EffectiveConfig {
+            team: "No team".to_string(),
+            file: no_team_file.map(|t| t.file.clone()),
+            settings: resolve(&global, &overrides, true),
+        });
+    }
+
+    configs
+}
+
+fn resolve(global: &Value, team: &Value, no_team: bool) -> BTreeMap<&'static str, EffectiveSetting> {
+    SETTINGS
+        .iter()
+        .filter_map(|spec| {
+            let global_value = get_path(global, spec.global_path).cloned();
+            match get_path(team, spec.team_path) {
+                Some(value) => Some(EffectiveSetting {
+                    value: value.clone(),
+                    origin: Origin::Team,
+                    overrides: global_value,
+                }),
+                None if spec.inherited || no_team => global_value.map(|value| EffectiveSetting {
+                    value,
+                    origin: Origin::Global,
+                    overrides: None,
+                }),
+                None => None,
+            }
+            .map(|setting| (spec.key, setting))
+        })
+        .collect()
+}
+
+/// Print effective settings as text.
+pub fn print(configs: &[EffectiveConfig]) {
+    for config in configs {
+        let file = config
+            .file
+            .as_deref()
+            .map(|f| format!(" ({})", f.display()))
+            .unwrap_or_default();
+        println!("{}{}", config.team.bold(), file.dimmed());
+
+        if config.settings.is_empty() {
+            println!("  {}", "(all defaults)".dimmed());
+        }
+        for (key, setting) in &config.settings {
+            let value = serde_yaml::to_string(&setting.value)
+                .unwrap_or_default()
+                .trim()
+                .replace('\n', " ");
+            let origin = match (setting.origin, &setting.overrides) {
+                (Origin::Global, _) => "inherited from global".dimmed(),
+                (Origin::Team, Some(_)) => "overrides global".yellow(),
+                (Origin::Team, None) => "team".normal(),
+            };
+            println!("  {} = {}  {}", key, value.cyan(), origin);
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::repo::load_repo;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_effective_configs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::write(
+            root.join("default.yml"),
+            "org_settings:\n  features:\n    enable_host_users: false\ncontrols:\n  enable_disk_encryption: true\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("teams/servers.yml"),
+            "name: Servers\nteam_settings:\n  features:\n    enable_software_inventory: false\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("teams/laptops.yml"),
+            "name: Laptops\ncontrols:\n  enable_disk_encryption: false\n",
+        )
+        .unwrap();
+
+        let configs = effective_configs(&load_repo(root).unwrap());
+        let names: Vec<&str> = configs.iter().map(|c| c.team.as_str()).collect();
+        assert_eq!(names, vec!["Laptops", "Servers", "No team"]);
+
+        let laptops = &configs[0].settings;
+        let encryption = &laptops["controls.enable_disk_encryption"];
+        assert_eq!(encryption.value, Value::Bool(false));
+        assert_eq!(encryption.origin, Origin::Team);
+        assert_eq!(encryption.overrides, Some(Value::Bool(true)));
+        assert_eq!(laptops["features.enable_host_users"].origin, Origin::Global);
+
+        // Controls aren't inherited by teams, but apply to "No team"
+        let servers = &configs[1].settings;
+        assert!(!servers.contains_key("controls.enable_disk_encryption"));
+        assert_eq!(servers["features.enable_software_inventory"].origin, Origin::Team);
+        assert_eq!(configs[2].settings["controls.enable_disk_encryption"].origin, Origin::Global);
+    }
+}
@@ -1,14 +1,66 @@
 use super::config::FleetLintConfig;
 use super::error::{LintError, LintReport, Severity};
+use super::file_role::FileRole;
+use super::metrics::Recorder;
 use super::fleet_config::{FleetConfig, Policy, PolicyOrPath, Query, QueryOrPath, Label, LabelOrPath, SoftwarePackage, AgentOptionsLib};
 use super::rules::RuleSet;
-use anyhow::{Context, Result};
+use super::walk::WalkOptions;
+use std::error::Error;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Why files could not be linted (as opposed to the findings in a
+/// [`LintReport`])
+#[derive(Debug)]
+pub enum LintEngineError {
+    /// A file or directory could not be read
+    Read { path: PathBuf, source: io::Error },
+    /// A file is not valid YAML
+    Parse { path: PathBuf, source: serde_yaml::Error },
+    /// A directory to lint is not a directory
+    NotADirectory(PathBuf),
+    /// A repository root has neither `default.yml` nor `teams/*.yml`
+    NoTeamFiles(PathBuf),
+}
+
+impl fmt::Display for LintEngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintEngineError::Read { path, .. } => write!(f, "Failed to read {}", path.display()),
+            LintEngineError::Parse { path, .. } => write!(f, "Failed to parse YAML: {}", path.display()),
+            LintEngineError::NotADirectory(path) => write!(f, "Not a directory: {}", path.display()),
+            LintEngineError::NoTeamFiles(root) => {
+                write!(f, "No default.yml or teams/*.yml found in {}", root.display())
+            }
+        }
+    }
+}
+
+impl Error for LintEngineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LintEngineError::Read { source, .. } => Some(source),
+            LintEngineError::Parse { source, .. } => Some(source),
+            LintEngineError::NotADirectory(_) | LintEngineError::NoTeamFiles(_) => None,
+        }
+    }
+}
+
+type Result<T, E = LintEngineError> = std::result::Result<T, E>;
+
+/// Read a file, naming it in the error
+pub(crate) fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|source| LintEngineError::Read { path: path.to_path_buf(), source })
+}
 
 pub struct Linter {
     rules: RuleSet,
     config: Option<FleetLintConfig>,
+    /// Set when `[metrics] enabled = true`.
+    metrics: Option<Arc<Recorder>>,
 }
 
 impl Linter {
@@ -16,27 +68,30 @@ impl Linter {
         Self {
             rules: RuleSet::default_rules(),
             config: None,
+            metrics: None,
         }
     }
 
     pub fn with_rules(rules: RuleSet) -> Self {
-        Self { rules, config: None }
+        Self { rules, config: None, metrics: None }
     }
 
     /// Create a linter with configuration.
     pub fn with_config(config: FleetLintConfig) -> Self {
+        let mut rules = RuleSet::default_rules();
+        rules.configure(&config);
         Self {
-            rules: RuleSet::default_rules(),
+            rules,
+            metrics: Recorder::from_config(&config.metrics).map(Arc::new),
             config: Some(config),
         }
     }
 
     /// Create a linter by searching for configuration from a path.
     pub fn from_path(start_path: &Path) -> Self {
-        let config = FleetLintConfig::find_and_load(start_path).map(|(_, c)| c);
-        Self {
-            rules: RuleSet::default_rules(),
-            config,
+        match FleetLintConfig::find_and_load(start_path) {
+            Some((_, config)) => Self::with_config(config),
+            None => Self::new(),
         }
     }
 
@@ -47,14 +102,31 @@ impl Linter {
 
     /// Set the configuration.
     pub fn set_config(&mut self, config: FleetLintConfig) {
+        self.rules.configure(&config);
+        let metrics_path = super::metrics::path_for(&config.metrics).filter(|_| config.metrics.enabled);
+        if self.metrics.as_ref().map(|m| m.path()) != metrics_path.as_deref() {
+            self.metrics = metrics_path.map(|path| Arc::new(Recorder::new(path)));
+        }
         self.config = Some(config);
     }
 
+    /// The metrics recorder, if metrics are enabled.
+    pub fn metrics(&self) -> Option<&Arc<Recorder>> {
+        self.metrics.as_ref()
+    }
+
+    /// Write recorded metrics now (before `process::exit`, which skips drops).
+    pub fn flush_metrics(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.flush();
+        }
+    }
+
     /// Lint a single file
     pub fn lint_file(&self, file_path: &Path) -> Result<LintReport> {
+        tracing::debug!(file = %file_path.display(), "linting file");
         // Read file
-        let source = fs::read_to_string(file_path)
-            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        let source = read_file(file_path)?;
 
         self.lint_content(&source, file_path)
     }
@@ -64,45 +136,27 @@ impl Linter {
     /// This method is useful when the file content is already available,
     /// such as in an LSP server where the client sends document content.
     pub fn lint_content(&self, content: &str, file_path: &Path) -> Result<LintReport> {
-        // Try to parse as FleetConfig first (team files with policies:, queries:, etc.)
-        // If that fails, try to parse as a lib file (array of policies/queries directly)
-        let fleet_config: FleetConfig = match serde_yaml::from_str(content) {
-            Ok(config) => config,
-            Err(_) => {
-                // Try parsing as a lib file (array of policies or queries)
-                if let Ok(policies) = serde_yaml::from_str::<Vec<Policy>>(content) {
-                    FleetConfig {
-                        policies: Some(policies.into_iter().map(PolicyOrPath::Policy).collect()),
-                        ..Default::default()
-                    }
-                } else if let Ok(queries) = serde_yaml::from_str::<Vec<Query>>(content) {
-                    FleetConfig {
-                        queries: Some(queries.into_iter().map(QueryOrPath::Query).collect()),
-                        ..Default::default()
-                    }
-                } else if let Ok(labels) = serde_yaml::from_str::<Vec<Label>>(content) {
-                    FleetConfig {
-                        labels: Some(labels.into_iter().map(LabelOrPath::Label).collect()),
-                        ..Default::default()
-                    }
-                } else if let Ok(_software) = serde_yaml::from_str::<SoftwarePackage>(content) {
-                    // Software package lib file (single object with url, icon, scripts)
-                    // We don't lint these yet, but we recognize them
-                    FleetConfig::default()
-                } else if let Ok(_agent_options) = serde_yaml::from_str::<AgentOptionsLib>(content) {
-                    // Agent options lib file (single object with config, update_channels)
-                    // We don't lint these yet, but we recognize them
-                    FleetConfig::default()
-                } else {
-                    // Last resort: try parsing as generic YAML to give a better error
-                    let _: serde_yaml::Value = serde_yaml::from_str(content)
-                        .with_context(|| format!("Failed to parse YAML: {}", file_path.display()))?;
-                    // If it parsed as generic YAML but not our types, return empty config
-                    // (the file might be a software definition or other type we don't lint yet)
-                    FleetConfig::default()
-                }
+        // Windows profiles are SyncML, not YAML
+        if file_path.extension().is_some_and(|ext| ext == "xml") {
+            let mut report = LintReport::new();
+            let variables = super::fleet_vars::check_file(
+                content,
+                Some(super::fleet_vars::VarContext::WindowsProfile),
+                file_path,
+            );
+            for error in super::profiles::validate_windows_profile(content, file_path)
+                .into_iter()
+                .chain(variables)
+            {
+                report.add(error);
             }
-        };
+            return Ok(report);
+        }
+
+        let fleet_config = parse_fleet_config(content, file_path)?;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_file();
+        }
 
         // Run all rules
         let mut report = LintReport::new();
@@ -114,6 +168,9 @@ impl Linter {
         let warning_rules = self.config.as_ref()
             .map(|c| c.warning_rules())
             .unwrap_or_default();
+        let enabled_rules = self.config.as_ref()
+            .map(|c| c.enabled_rules())
+            .unwrap_or_default();
 
         for rule in self.rules.rules() {
             // Skip disabled rules
@@ -121,15 +178,24 @@ impl Linter {
                 continue;
             }
 
-            let errors = rule.check(&fleet_config, file_path, content);
+            // Skip opt-in rules unless explicitly enabled
+            if !rule.default_enabled() && !enabled_rules.contains(rule.name()) {
+                continue;
+            }
+
+            let mut errors = rule.check(&fleet_config, file_path, content);
 
             // Downgrade to warnings if configured
-            let should_warn = warning_rules.contains(rule.name());
-
-            for mut error in errors {
-                if should_warn && error.severity == Severity::Error {
+            if warning_rules.contains(rule.name()) {
+                for error in errors.iter_mut().filter(|e| e.severity == Severity::Error) {
                     error.severity = Severity::Warning;
                 }
+            }
+            if let Some(metrics) = &self.metrics {
+                metrics.record_findings(rule.name(), &errors);
+            }
+
+            for error in errors {
                 report.add(error);
             }
         }
@@ -137,6 +203,46 @@ impl Linter {
         Ok(report)
     }
 
+    /// Run workspace (cross-file) rules against a GitOps repository root.
+    ///
+    /// Errors are located in the team files they concern.
+    pub fn lint_repo(&self, root: &Path) -> Result<Vec<LintError>> {
+        let repo = super::repo::load_repo(root)?;
+        tracing::debug!(root = %root.display(), teams = repo.teams.len(), "running repository rules");
+
+        let disabled_rules = self.config.as_ref()
+            .map(|c| c.disabled_rules())
+            .unwrap_or_default();
+        let warning_rules = self.config.as_ref()
+            .map(|c| c.warning_rules())
+            .unwrap_or_default();
+        let enabled_rules = self.config.as_ref()
+            .map(|c| c.enabled_rules())
+            .unwrap_or_default();
+
+        let mut errors = Vec::new();
+        for rule in self.rules.workspace_rules() {
+            if disabled_rules.contains(rule.name())
+                || (!rule.default_enabled() && !enabled_rules.contains(rule.name()))
+            {
+                continue;
+            }
+
+            let mut found = rule.check(&repo);
+            if warning_rules.contains(rule.name()) {
+                for error in found.iter_mut().filter(|e| e.severity == Severity::Error) {
+                    error.severity = Severity::Warning;
+                }
+            }
+            if let Some(metrics) = &self.metrics {
+                metrics.record_findings(rule.name(), &found);
+            }
+            errors.extend(found);
+        }
+
+        Ok(errors)
+    }
+
     /// Lint multiple files
     pub fn lint_files(&self, files: &[&Path]) -> Result<Vec<(String, LintReport)>> {
         let mut results = Vec::new();
@@ -162,11 +268,9 @@ impl Linter {
     }
 
     /// Lint a directory recursively
-    pub fn lint_directory(&self, dir: &Path, pattern: Option<&str>) -> Result<Vec<(String, LintReport)>> {
-        let pattern = pattern.unwrap_or("**/*.{yml,yaml}");
-
+    pub fn lint_directory(&self, dir: &Path) -> Result<Vec<(String, LintReport)>> {
         // Find all YAML files
-        let yaml_files = find_yaml_files(dir, pattern)?;
+        let yaml_files = find_yaml_files(dir, &WalkOptions::from_config(self.config.as_ref()))?;
 
         // Lint each file
         let file_refs: Vec<&Path> = yaml_files.iter().map(|p| p.as_path()).collect();
@@ -180,41 +284,76 @@ impl Default for Linter {
     }
 }
 
-/// Find YAML files in directory
-fn find_yaml_files(dir: &Path, pattern: &str) -> Result<Vec<std::path::PathBuf>> {
-    let mut files = Vec::new();
-
-    // Simple recursive search for YAML files
-    fn visit_dirs(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> Result<()> {
-        if dir.is_dir() {
-            for entry in fs::read_dir(dir)? {
-                let entry = entry?;
-                let path = entry.path();
-
-                if path.is_dir() {
-                    // Skip hidden directories and common ignores
-                    if let Some(name) = path.file_name() {
-                        let name_str = name.to_string_lossy();
-                        if name_str.starts_with('.')
-                            || name_str == "node_modules"
-                            || name_str == "target"
-                            || name_str == "dist" {
-                            continue;
-                        }
-                    }
-                    visit_dirs(&path, files)?;
-                } else if let Some(ext) = path.extension() {
-                    if ext == "yml" || ext == "yaml" {
-                        files.push(path);
-                    }
-                }
-            }
+/// Parse file content into a `FleetConfig`.
+///
+/// Accepts team/default files as well as lib files (bare lists of policies,
+/// queries, or labels). Other recognized lib files yield an empty config.
+/// A lib file's [`FileRole`] decides how it is read first, so a list of
+/// queries in `lib/queries/` isn't taken for policies.
+pub fn parse_fleet_config(content: &str, file_path: &Path) -> Result<FleetConfig> {
+    // Try to parse as FleetConfig first (team files with policies:, queries:, etc.)
+    // If that fails, try to parse as a lib file (array of policies/queries directly)
+    if let Ok(config) = serde_yaml::from_str::<FleetConfig>(content) {
+        return Ok(config);
+    }
+
+    let fallback = [
+        FileRole::Policies,
+        FileRole::Queries,
+        FileRole::Labels,
+        FileRole::Software,
+        FileRole::AgentOptions,
+    ];
+    for role in FileRole::detect(file_path).into_iter().chain(fallback) {
+        if let Some(config) = parse_lib_file(content, role) {
+            return Ok(config);
         }
-        Ok(())
     }
 
-    visit_dirs(dir, &mut files)?;
-    Ok(files)
+    // Last resort: try parsing as generic YAML to give a better error
+    let _: serde_yaml::Value = serde_yaml::from_str(content)
+        .map_err(|source| LintEngineError::Parse { path: file_path.to_path_buf(), source })?;
+    // If it parsed as generic YAML but not our types, return empty config
+    // (the file might be a software definition or other type we don't lint yet)
+    Ok(FleetConfig::default())
+}
+
+/// Parse a lib file as holding `role` content.
+fn parse_lib_file(content: &str, role: FileRole) -> Option<FleetConfig> {
+    match role {
+        FileRole::Policies => serde_yaml::from_str::<Vec<Policy>>(content).ok().map(|policies| FleetConfig {
+            policies: Some(policies.into_iter().map(PolicyOrPath::Policy).collect()),
+            ..Default::default()
+        }),
+        FileRole::Queries => serde_yaml::from_str::<Vec<Query>>(content).ok().map(|queries| FleetConfig {
+            queries: Some(queries.into_iter().map(QueryOrPath::Query).collect()),
+            ..Default::default()
+        }),
+        FileRole::Labels => serde_yaml::from_str::<Vec<Label>>(content).ok().map(|labels| FleetConfig {
+            labels: Some(labels.into_iter().map(LabelOrPath::Label).collect()),
+            ..Default::default()
+        }),
+        // Software package lib file (single object with url, icon, scripts)
+        // We don't lint these yet, but we recognize them
+        FileRole::Software => serde_yaml::from_str::<SoftwarePackage>(content).ok().map(|_| FleetConfig::default()),
+        // Agent options lib file (single object with config, update_channels)
+        // We don't lint these yet, but we recognize them
+        FileRole::AgentOptions => {
+            serde_yaml::from_str::<AgentOptionsLib>(content).ok().map(|_| FleetConfig::default())
+        }
+        FileRole::Script => None,
+    }
+}
+
+/// Find YAML files in directory (see [`super::walk`] for what is skipped).
+pub fn find_yaml_files(dir: &Path, options: &WalkOptions) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Err(LintEngineError::NotADirectory(dir.to_path_buf()));
+    }
+    Ok(super::walk::files(dir, options)
+        .into_iter()
+        .filter(|path| super::walk::is_yaml(path))
+        .collect())
 }
 
 #[cfg(test)]
@@ -297,4 +436,76 @@ policies:
         assert!(report.has_errors());
         assert!(report.errors.iter().any(|e| e.message.contains("not available on platform")));
     }
+
+    #[test]
+    fn test_opt_in_rules_require_config() {
+        let yaml = r#"
+policies:
+  - name: "Firewall"
+    query: "SELECT 1 FROM alf WHERE global_state >= 1;"
+    platform: darwin
+"#;
+        let path = Path::new("default.yml");
+
+        let report = Linter::new().lint_content(yaml, path).unwrap();
+        assert!(!report.warnings.iter().any(|e| e.message.contains("has no resolution")));
+
+        let config = FleetLintConfig::from_str("[rules]\nenabled = [\"policy-resolution\"]\n").unwrap();
+        let report = Linter::with_config(config).lint_content(yaml, path).unwrap();
+        assert!(report.warnings.iter().any(|e| e.message.contains("has no resolution")));
+    }
+
+    #[test]
+    fn test_metrics_record_rule_findings() {
+        let yaml = "policies:\n  - name: Firewall\n    query: SELECT 1 FROM alf;\n    platform: darwin\n";
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("metrics.json");
+        let config = FleetLintConfig::from_str(&format!(
+            "[rules]\nenabled = [\"policy-resolution\"]\n[metrics]\nenabled = true\nfile = {:?}\n",
+            file.to_str().unwrap()
+        ))
+        .unwrap();
+
+        let linter = Linter::with_config(config);
+        linter.lint_content(yaml, Path::new("default.yml")).unwrap();
+        linter.flush_metrics();
+
+        let metrics = super::super::metrics::Metrics::load(&file).unwrap();
+        assert_eq!(metrics.files_linted, 1);
+        assert_eq!(metrics.rules["policy-resolution"].warnings, 1);
+        assert!(Linter::new().metrics().is_none());
+    }
+
+    #[test]
+    fn test_parse_lib_file_uses_file_role() {
+        let yaml = "- name: Uptime\n  query: SELECT * FROM uptime;\n  platform: darwin\n";
+
+        let config = parse_fleet_config(yaml, Path::new("lib/macos/queries/uptime.yml")).unwrap();
+        assert_eq!(config.queries.map(|q| q.len()), Some(1));
+        assert!(config.policies.is_none());
+
+        // Without a convention the structure decides
+        let config = parse_fleet_config(yaml, Path::new("lib/uptime.yml")).unwrap();
+        assert_eq!(config.policies.map(|p| p.len()), Some(1));
+    }
+
+    #[test]
+    fn test_engine_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let linter = Linter::new();
+
+        let err = linter.lint_file(&dir.path().join("missing.yml")).unwrap_err();
+        assert!(matches!(&err, LintEngineError::Read { source, .. } if source.kind() == io::ErrorKind::NotFound));
+
+        let err = linter.lint_content("policies: [", Path::new("default.yml")).unwrap_err();
+        assert!(matches!(err, LintEngineError::Parse { .. }));
+        assert_eq!(err.to_string(), "Failed to parse YAML: default.yml");
+        assert!(err.source().is_some());
+
+        let err = linter.lint_directory(&dir.path().join("missing")).unwrap_err();
+        assert!(matches!(err, LintEngineError::NotADirectory(_)));
+
+        let err = linter.lint_repo(dir.path()).unwrap_err();
+        assert!(matches!(err, LintEngineError::NoTeamFiles(_)));
+    }
 }
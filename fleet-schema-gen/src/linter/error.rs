@@ -1,9 +1,11 @@
 use annotate_snippets::{Level, Renderer, Snippet};
 use colored::*;
+use serde::Serialize;
 use std::fmt;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     Error,
     Warning,
@@ -20,7 +22,7 @@ impl fmt::Display for Severity {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct LintError {
     pub severity: Severity,
     pub message: String,
@@ -30,6 +32,22 @@ pub struct LintError {
     pub context: Option<String>,
     pub help: Option<String>,
     pub suggestion: Option<String>,
+    /// Whether the suggestion is a safe replacement for `context`, applied
+    /// by `lint --fix` and "Fix all" in editors.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fixable: bool,
+    /// Other locations involved (the first of two duplicates, the line in a
+    /// referenced file), shown as notes and as LSP related information.
+    pub related: Vec<RelatedLocation>,
+}
+
+/// A location related to a finding, usually in another file.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedLocation {
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
 }
 
 impl LintError {
@@ -43,6 +61,8 @@ impl LintError {
             context: None,
             help: None,
             suggestion: None,
+            fixable: false,
+            related: Vec::new(),
         }
     }
 
@@ -56,6 +76,8 @@ impl LintError {
             context: None,
             help: None,
             suggestion: None,
+            fixable: false,
+            related: Vec::new(),
         }
     }
 
@@ -69,6 +91,8 @@ impl LintError {
             context: None,
             help: None,
             suggestion: None,
+            fixable: false,
+            related: Vec::new(),
         }
     }
 
@@ -93,6 +117,30 @@ impl LintError {
         self
     }
 
+    /// Suggest a replacement for the located `context` that is always
+    /// safe to apply without review.
+    pub fn with_fix(mut self, replacement: impl Into<String>) -> Self {
+        self.suggestion = Some(replacement.into());
+        self.fixable = true;
+        self
+    }
+
+    /// Add a related location (1-indexed line and column, when known).
+    pub fn with_related(
+        mut self,
+        file: impl Into<PathBuf>,
+        location: Option<(usize, usize)>,
+        message: impl Into<String>,
+    ) -> Self {
+        self.related.push(RelatedLocation {
+            file: file.into(),
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            message: message.into(),
+        });
+        self
+    }
+
     /// Format error with rich annotations
     pub fn format(&self, source: Option<&str>) -> String {
         let mut output = String::new();
@@ -132,6 +180,16 @@ impl LintError {
             output.push_str(&format!("  {} {}\n", "suggestion:".cyan().bold(), suggestion));
         }
 
+        // Related locations
+        for related in &self.related {
+            let location = match (related.line, related.column) {
+                (Some(line), Some(col)) => format!("{}:{}:{}", related.file.display(), line, col),
+                (Some(line), None) => format!("{}:{}", related.file.display(), line),
+                _ => related.file.display().to_string(),
+            };
+            output.push_str(&format!("  {} {}: {}\n", "note:".blue().bold(), location, related.message));
+        }
+
         output
     }
 
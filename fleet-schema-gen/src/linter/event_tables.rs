@@ -0,0 +1,213 @@
+//! Queries on osquery's evented tables.
+//!
+//! Evented tables (`process_events`, `socket_events`, ...) buffer every
+//! event since they were last read, so a query without a time window
+//! returns the whole buffer, and joining one without a join condition
+//! multiplies each event by every row of the other table. Scheduled
+//! queries on them should log differentially, as each run only sees new
+//! events anyway.
+
+use super::sql::{tokenize, Token, TokenKind};
+
+/// Whether `table` is an evented table.
+pub fn is_event_table(table: &str) -> bool {
+    let table = table.to_ascii_lowercase();
+    // Table metadata, not events
+    table.ends_with("_events") && table != "osquery_events"
+}
+
+/// Logging types that only report changes.
+pub const DIFFERENTIAL_LOGGING: &[&str] = &["differential", "differential_ignore_removals"];
+
+/// A problem with how a query reads an evented table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventTableIssue {
+    pub table: String,
+    pub message: String,
+    pub help: String,
+}
+
+/// A table in a FROM clause.
+struct Source<'a> {
+    table: &'a str,
+    alias: &'a str,
+    /// Joined with ON or USING (the first table counts as joined)
+    joined: bool,
+}
+
+fn is_name(token: &Token) -> bool {
+    token.kind == TokenKind::Identifier
+}
+
+/// Tables of every FROM clause in `tokens`, subqueries included.
+fn sources<'a>(tokens: &[&Token<'a>]) -> Vec<Vec<Source<'a>>> {
+    let mut clauses = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        if !token.is_keyword("FROM") {
+            continue;
+        }
+        let mut clause: Vec<Source> = Vec::new();
+        let mut depth = 0;
+        let mut expect_table = true;
+        for (offset, token) in tokens[idx + 1..].iter().enumerate() {
+            let at = idx + 1 + offset;
+            if token.is("(") {
+                depth += 1;
+                expect_table = false;
+                continue;
+            }
+            if token.is(")") {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+                continue;
+            }
+            if depth > 0 {
+                continue;
+            }
+            if token.is(",") || token.is_keyword("JOIN") {
+                expect_table = true;
+            } else if token.is_keyword("ON") || token.is_keyword("USING") {
+                if let Some(last) = clause.last_mut() {
+                    last.joined = true;
+                }
+            } else if token.kind == TokenKind::Keyword
+                && ["WHERE", "GROUP", "HAVING", "WINDOW", "ORDER", "LIMIT", "UNION", "INTERSECT", "EXCEPT"]
+                    .iter()
+                    .any(|keyword| token.is_keyword(keyword))
+                || token.is(";")
+            {
+                break;
+            } else if expect_table && is_name(token) {
+                // `table [AS] alias`
+                let alias = match (tokens.get(at + 1), tokens.get(at + 2)) {
+                    (Some(next), Some(alias)) if next.is_keyword("AS") && is_name(alias) => alias.text,
+                    (Some(alias), _) if is_name(alias) => alias.text,
+                    _ => token.text,
+                };
+                clause.push(Source { table: token.text, alias, joined: clause.is_empty() });
+                expect_table = false;
+            }
+        }
+        clauses.push(clause);
+    }
+    clauses
+}
+
+/// Whether `tokens` bound the `time` column of `source` from below
+/// (`time > x`, `time >= x`, `x < time`, `time BETWEEN x AND y`).
+fn has_time_window(tokens: &[&Token], source: &Source) -> bool {
+    (0..tokens.len()).any(|idx| {
+        if !tokens[idx].text.eq_ignore_ascii_case("time") || !is_name(tokens[idx]) {
+            return false;
+        }
+        // Unqualified, or qualified with the table or its alias
+        let qualifier = (idx >= 2 && tokens[idx - 1].is(".")).then(|| tokens[idx - 2].text);
+        if qualifier.is_some_and(|q| !q.eq_ignore_ascii_case(source.alias) && !q.eq_ignore_ascii_case(source.table)) {
+            return false;
+        }
+        let before = if qualifier.is_some() { idx.checked_sub(3) } else { idx.checked_sub(1) };
+        let after = tokens.get(idx + 1);
+        after.is_some_and(|t| t.is(">") || t.is(">=") || t.is_keyword("BETWEEN"))
+            || before.is_some_and(|before| tokens[before].is("<") || tokens[before].is("<="))
+    })
+}
+
+/// Whether a WHERE clause compares a column of `alias` with one of another table.
+fn has_join_predicate(tokens: &[&Token], alias: &str) -> bool {
+    let qualified = |idx: usize| -> Option<&str> {
+        (idx + 2 < tokens.len() && is_name(tokens[idx]) && tokens[idx + 1].is(".")).then(|| tokens[idx].text)
+    };
+    (0..tokens.len()).any(|idx| {
+        if !(tokens[idx].is("=") || tokens[idx].is("==")) || idx < 3 {
+            return false;
+        }
+        match (qualified(idx - 3), qualified(idx + 1)) {
+            (Some(left), Some(right)) => {
+                (left.eq_ignore_ascii_case(alias) || right.eq_ignore_ascii_case(alias))
+                    && !left.eq_ignore_ascii_case(right)
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Problems with how `sql` reads evented tables.
+pub fn event_table_issues(sql: &str) -> Vec<EventTableIssue> {
+    let all = tokenize(sql);
+    let tokens: Vec<&Token> =
+        all.iter().filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment)).collect();
+    let mut issues = Vec::new();
+
+    for clause in sources(&tokens) {
+        for (idx, source) in clause.iter().enumerate() {
+            if !is_event_table(source.table) {
+                continue;
+            }
+            let table = source.table.to_lowercase();
+            if !has_time_window(&tokens, source) {
+                issues.push(EventTableIssue {
+                    message: format!("reads {} without a time window", table),
+                    help: format!(
+                        "Constrain it with a predicate like `{}.time > strftime('%s', 'now') - 3600`, or the query returns every buffered event",
+                        source.alias
+                    ),
+                    table: table.clone(),
+                });
+            }
+
+            // Joined without a condition on either side of the join
+            let unjoined = |source: &Source| !source.joined && !has_join_predicate(&tokens, source.alias);
+            let other = if idx == 0 {
+                clause.get(1).filter(|next| unjoined(next))
+            } else {
+                Some(&clause[idx - 1]).filter(|_| unjoined(source))
+            };
+            if let Some(other) = other {
+                issues.push(EventTableIssue {
+                    message: format!("joins {} with {} without a join condition", table, other.table.to_lowercase()),
+                    help: "Every event is returned once per row of the other table; join them with ON or USING".to_string(),
+                    table,
+                });
+            }
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(sql: &str) -> Vec<String> {
+        event_table_issues(sql).into_iter().map(|issue| issue.message).collect()
+    }
+
+    #[test]
+    fn test_event_table_issues() {
+        assert!(is_event_table("process_events"));
+        assert!(!is_event_table("osquery_events"));
+        assert!(!is_event_table("processes"));
+
+        assert_eq!(messages("SELECT * FROM process_events;"), vec!["reads process_events without a time window"]);
+        assert!(messages("SELECT * FROM process_events WHERE time > strftime('%s', 'now') - 60").is_empty());
+        assert!(messages("SELECT * FROM process_events pe WHERE strftime('%s','now') - 60 < pe.time").is_empty());
+        assert!(messages("SELECT * FROM socket_events AS s WHERE s.time BETWEEN 1 AND 2").is_empty());
+        assert_eq!(
+            messages("SELECT * FROM processes p, socket_events s WHERE p.time > 1"),
+            vec!["reads socket_events without a time window", "joins socket_events with processes without a join condition"]
+        );
+        assert!(messages("SELECT * FROM processes p, socket_events s WHERE p.pid = s.pid AND s.time > 1").is_empty());
+        assert!(messages("SELECT * FROM process_events e JOIN users u USING (uid) WHERE e.time >= 1").is_empty());
+        assert_eq!(
+            messages("SELECT * FROM process_events e CROSS JOIN users WHERE e.time >= 1"),
+            vec!["joins process_events with users without a join condition"]
+        );
+        // Subqueries are checked too
+        assert_eq!(
+            messages("SELECT 1 FROM (SELECT path FROM process_events) WHERE path LIKE '/tmp/%'"),
+            vec!["reads process_events without a time window"]
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Flattened policy and query tables for audit evidence collection.
+//!
+//! Every policy and query of every team becomes one row (name, team,
+//! platform, criticality, interval, query hash, defining file), written as
+//! CSV or as an XLSX workbook. Terraform and Pulumi exports are in
+//! [`super::iac`].
+
+use super::repo::GitopsRepo;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+
+/// Output format for exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    Terraform,
+    Pulumi,
+}
+
+impl ExportFormat {
+    /// Parse a format name (`csv`, `xlsx`, `terraform`, `pulumi`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "xlsx" => Some(ExportFormat::Xlsx),
+            "terraform" | "tf" => Some(ExportFormat::Terraform),
+            "pulumi" => Some(ExportFormat::Pulumi),
+            _ => None,
+        }
+    }
+}
+
+/// One exported policy or query.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportRow {
+    /// `policy` or `query`
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub name: String,
+    pub team: String,
+    pub platform: String,
+    pub critical: Option<bool>,
+    pub interval: Option<i64>,
+    /// SHA-256 of the trimmed query SQL.
+    pub query_hash: String,
+    /// Defining file, relative to the repository root.
+    pub file: String,
+}
+
+/// Column headers, matching the serialized `ExportRow` fields.
+pub const EXPORT_HEADERS: [&str; 8] = [
+    "type", "name", "team", "platform", "critical", "interval", "query_hash", "file",
+];
+
+/// Flatten every team's policies and queries into rows.
+pub fn export_rows(repo: &GitopsRepo) -> Vec<ExportRow> {
+    let relative = |file: &Path| {
+        file.strip_prefix(&repo.root)
+            .unwrap_or(file)
+            .display()
+            .to_string()
+    };
+
+    let mut rows = Vec::new();
+    for team in &repo.teams {
+        for sourced in &team.policies {
+            let policy = &sourced.item;
+            rows.push(ExportRow {
+                kind: "policy",
+                name: policy.name.clone().unwrap_or_default(),
+                team: team.name.clone(),
+                platform: policy.platform.clone().unwrap_or_default(),
+                critical: Some(policy.critical.unwrap_or(false)),
+                interval: None,
+                query_hash: policy.query.as_deref().map(query_hash).unwrap_or_default(),
+                file: relative(&sourced.file),
+            });
+        }
+
+        for sourced in &team.queries {
+            let query = &sourced.item;
+            rows.push(ExportRow {
+                kind: "query",
+                name: query.name.clone().unwrap_or_default(),
+                team: team.name.clone(),
+                platform: query.platform.clone().unwrap_or_default(),
+                critical: None,
+                interval: query.interval,
+                query_hash: query.query.as_deref().map(query_hash).unwrap_or_default(),
+                file: relative(&sourced.file),
+            });
+        }
+    }
+
+    rows
+}
+
+/// SHA-256 (hex) of a query, ignoring leading/trailing whitespace.
+pub fn query_hash(sql: &str) -> String {
+    Sha256::digest(sql.trim().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Write rows as CSV.
+pub fn write_csv<W: Write>(rows: &[ExportRow], writer: W) -> Result<()> {
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    for row in rows {
+        csv_writer.serialize(row)?;
+    }
+    if rows.is_empty() {
+        csv_writer.write_record(EXPORT_HEADERS)?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+/// Write rows as an XLSX workbook with a single sheet.
+pub fn write_xlsx(rows: &[ExportRow], path: &Path) -> Result<()> {
+    use rust_xlsxwriter::{Format, Workbook};
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Policies and queries")?;
+
+    let header = Format::new().set_bold();
+    for (col, title) in EXPORT_HEADERS.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *title, &header)?;
+    }
+
+    for (idx, row) in rows.iter().enumerate() {
+        let r = idx as u32 + 1;
+        sheet.write_string(r, 0, row.kind)?;
+        sheet.write_string(r, 1, &row.name)?;
+        sheet.write_string(r, 2, &row.team)?;
+        sheet.write_string(r, 3, &row.platform)?;
+        if let Some(critical) = row.critical {
+            sheet.write_boolean(r, 4, critical)?;
+        }
+        if let Some(interval) = row.interval {
+            sheet.write_number(r, 5, interval as f64)?;
+        }
+        sheet.write_string(r, 6, &row.query_hash)?;
+        sheet.write_string(r, 7, &row.file)?;
+    }
+
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofit();
+
+    workbook
+        .save(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::repo::load_repo;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn sample_repo() -> (TempDir, GitopsRepo) {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::write(
+            root.join("teams/servers.yml"),
+            r#"name: Servers
+policies:
+  - name: SSH root login disabled
+    query: SELECT 1 FROM ssh_configs;
+    platform: linux
+    critical: true
+queries:
+  - name: Uptime
+    query: SELECT * FROM uptime;
+    interval: 3600
+"#,
+        )
+        .unwrap();
+
+        let repo = load_repo(root).unwrap();
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_query_hash_ignores_surrounding_whitespace() {
+        assert_eq!(query_hash("SELECT 1;"), query_hash("  SELECT 1;\n"));
+        assert_eq!(query_hash("SELECT 1;").len(), 64);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let (_temp_dir, repo) = sample_repo();
+        let rows = export_rows(&repo);
+
+        let mut out = Vec::new();
+        write_csv(&rows, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], EXPORT_HEADERS.join(","));
+        assert!(lines[1].starts_with("policy,SSH root login disabled,Servers,linux,true,,"));
+        assert!(lines[1].ends_with(",teams/servers.yml"));
+        assert!(lines[2].starts_with("query,Uptime,Servers,,,3600,"));
+    }
+
+    #[test]
+    fn test_write_xlsx() {
+        let (temp_dir, repo) = sample_repo();
+        let path = temp_dir.path().join("export.xlsx");
+
+        write_xlsx(&export_rows(&repo), &path).unwrap();
+        assert!(fs::metadata(&path).unwrap().len() > 0);
+    }
+}
@@ -0,0 +1,145 @@
+//! What a lib file contains, from its name and location.
+//!
+//! Lib files are bare lists (`- name: ...`) or single objects without a
+//! top-level key, so their content alone is ambiguous, and an empty new
+//! file has no content at all. Fleet GitOps repositories name them by
+//! convention instead:
+//!
+//! - `*.policies.yml`, `*.queries.yml`, `*.labels.yml`
+//! - `lib/**/policies/`, `lib/**/queries/`, `lib/**/labels/`
+//! - `lib/**/software/` for package definitions
+//! - `agent-options.yml` for agent options
+//! - `lib/**/scripts/` for scripts
+//!
+//! Completion, hover and validation use the role to interpret such files.
+//! Only the part of the path below the repository root counts, so a
+//! checkout in `~/policies/` doesn't make every file a policy file.
+
+use std::path::Path;
+
+/// Kind of content a file is expected to hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileRole {
+    /// List of policies.
+    Policies,
+    /// List of queries (reports).
+    Queries,
+    /// List of labels.
+    Labels,
+    /// A single software package definition.
+    Software,
+    /// Agent options (`config:`, `update_channels:`).
+    AgentOptions,
+    /// A script referenced from `controls.scripts` or a package.
+    Script,
+}
+
+impl FileRole {
+    /// Role of the file at `path`, or `None` for team/default files and
+    /// files that don't follow a convention.
+    ///
+    /// The file name wins over directories, and nearer directories win over
+    /// farther ones (`lib/policies/queries/a.yml` holds queries).
+    pub fn detect(path: &Path) -> Option<FileRole> {
+        let file_name = path.file_name()?.to_str()?.to_lowercase();
+        if let Some(role) = Self::from_file_name(&file_name) {
+            return Some(role);
+        }
+
+        relative_to_root(path)
+            .parent()?
+            .components()
+            .rev()
+            .filter_map(|c| c.as_os_str().to_str())
+            .find_map(|dir| Self::from_directory(&dir.to_lowercase()))
+    }
+
+    fn from_file_name(name: &str) -> Option<FileRole> {
+        let stem = name
+            .strip_suffix(".yml")
+            .or_else(|| name.strip_suffix(".yaml"))?;
+        let kind = stem.rsplit('.').next()?;
+        match kind {
+            "policies" => Some(FileRole::Policies),
+            "queries" | "reports" => Some(FileRole::Queries),
+            "labels" => Some(FileRole::Labels),
+            "software" | "package" => Some(FileRole::Software),
+            "agent-options" | "agent_options" => Some(FileRole::AgentOptions),
+            _ => None,
+        }
+    }
+
+    fn from_directory(dir: &str) -> Option<FileRole> {
+        match dir {
+            "policies" => Some(FileRole::Policies),
+            "queries" | "reports" => Some(FileRole::Queries),
+            "labels" => Some(FileRole::Labels),
+            "software" => Some(FileRole::Software),
+            "scripts" => Some(FileRole::Script),
+            _ => None,
+        }
+    }
+
+    /// The team file section this role's items belong to (`policies`,
+    /// `software_lib`, ...), as used for field documentation lookups.
+    pub fn section(self) -> &'static str {
+        match self {
+            FileRole::Policies => "policies",
+            FileRole::Queries => "queries",
+            FileRole::Labels => "labels",
+            FileRole::Software => "software_lib",
+            FileRole::AgentOptions => "agent_options",
+            FileRole::Script => "scripts",
+        }
+    }
+}
+
+/// `path` below the nearest directory above it that holds `default.yml`,
+/// `teams/` or `.git`; `path` itself when there is none.
+pub fn relative_to_root(path: &Path) -> &Path {
+    path.ancestors()
+        .skip(1)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .find(|dir| dir.join("default.yml").is_file() || dir.join("teams").is_dir() || dir.join(".git").exists())
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let role = |p: &str| FileRole::detect(Path::new(p));
+        assert_eq!(role("lib/windows/policies/foo.policies.yml"), Some(FileRole::Policies));
+        assert_eq!(role("lib/disk-encryption.policies.yaml"), Some(FileRole::Policies));
+        assert_eq!(role("lib/macos/queries/uptime.yml"), Some(FileRole::Queries));
+        assert_eq!(role("lib/all.queries.yml"), Some(FileRole::Queries));
+        assert_eq!(role("lib/labels/arm.yml"), Some(FileRole::Labels));
+        assert_eq!(role("lib/macos/software/firefox.yml"), Some(FileRole::Software));
+        assert_eq!(role("lib/agent-options.yml"), Some(FileRole::AgentOptions));
+        assert_eq!(role("lib/macos/scripts/install.sh"), Some(FileRole::Script));
+
+        // File name beats directory, nearest directory beats farther ones
+        assert_eq!(role("lib/policies/extra.queries.yml"), Some(FileRole::Queries));
+        assert_eq!(role("lib/policies/queries/a.yml"), Some(FileRole::Queries));
+
+        assert_eq!(role("teams/workstations.yml"), None);
+        assert_eq!(role("default.yml"), None);
+        assert_eq!(role("lib/misc.yml"), None);
+    }
+
+    #[test]
+    fn test_detect_ignores_directories_above_the_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("policies/gitops");
+        std::fs::create_dir_all(root.join("lib/queries")).unwrap();
+        std::fs::write(root.join("default.yml"), "").unwrap();
+
+        assert_eq!(relative_to_root(&root.join("lib/misc.yml")), Path::new("lib/misc.yml"));
+        assert_eq!(FileRole::detect(&root.join("lib/misc.yml")), None);
+        assert_eq!(FileRole::detect(&root.join("teams/workstations.yml")), None);
+        assert_eq!(FileRole::detect(&root.join("lib/queries/uptime.yml")), Some(FileRole::Queries));
+    }
+}
@@ -0,0 +1,130 @@
+//! Size of a single YAML file.
+//!
+//! A team file with hundreds of inline policies is hard to review and to
+//! split between owners. The `file-size` rule warns once a file defines
+//! more items or lines than `thresholds.max_*_per_file` allow, and
+//! `lint -v` prints the breakdown below for such files, so it is clear what
+//! to move to lib files.
+
+use std::path::Path;
+
+use super::config::ThresholdsConfig;
+use super::fleet_config::{FleetConfig, LabelOrPath, PolicyOrPath, QueryOrPath};
+
+/// Items of one kind: defined in the file, and referenced with `- path:`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemCount {
+    pub inline: usize,
+    pub referenced: usize,
+}
+
+impl ItemCount {
+    fn count<T>(items: Option<&Vec<T>>, is_path: impl Fn(&T) -> bool) -> Self {
+        let items = items.map_or(&[][..], |items| items.as_slice());
+        let referenced = items.iter().filter(|item| is_path(item)).count();
+        Self { inline: items.len() - referenced, referenced }
+    }
+}
+
+/// What a file holds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileStats {
+    pub lines: usize,
+    pub policies: ItemCount,
+    pub queries: ItemCount,
+    pub labels: ItemCount,
+    pub packages: ItemCount,
+}
+
+/// A threshold a file is over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exceeded {
+    /// "policies", "queries" or "lines"
+    pub what: &'static str,
+    pub count: usize,
+    pub max: usize,
+    /// The `[thresholds]` setting
+    pub setting: &'static str,
+}
+
+impl FileStats {
+    pub fn new(config: &FleetConfig, source: &str) -> Self {
+        let packages = config
+            .software
+            .as_ref()
+            .and_then(|software| software.get("packages"))
+            .and_then(|packages| packages.as_sequence());
+        Self {
+            lines: source.lines().count(),
+            policies: ItemCount::count(config.policies.as_ref(), |p| matches!(p, PolicyOrPath::Path { .. })),
+            queries: ItemCount::count(config.queries.as_ref(), |q| matches!(q, QueryOrPath::Path { .. })),
+            labels: ItemCount::count(config.labels.as_ref(), |l| matches!(l, LabelOrPath::Path { .. })),
+            packages: ItemCount::count(packages, |p| p.get("path").is_some()),
+        }
+    }
+
+    /// Stats of the file at `path`, if it can be read and parsed.
+    pub fn for_file(path: &Path) -> Option<Self> {
+        let source = std::fs::read_to_string(path).ok()?;
+        let config = super::engine::parse_fleet_config(&source, path).ok()?;
+        Some(Self::new(&config, &source))
+    }
+
+    /// Thresholds the file is over; a threshold of 0 is off. Only items
+    /// defined in the file count, as `- path:` references are one line each.
+    pub fn exceeded(&self, thresholds: &ThresholdsConfig) -> Vec<Exceeded> {
+        [
+            ("policies", self.policies.inline, thresholds.max_policies_per_file, "max_policies_per_file"),
+            ("queries", self.queries.inline, thresholds.max_queries_per_file, "max_queries_per_file"),
+            ("lines", self.lines, thresholds.max_lines_per_file, "max_lines_per_file"),
+        ]
+        .into_iter()
+        .filter(|&(_, count, max, _)| max > 0 && count > max)
+        .map(|(what, count, max, setting)| Exceeded { what, count, max, setting })
+        .collect()
+    }
+
+    /// Breakdown for `lint -v`, in the style of `stats`.
+    pub fn render(&self, file: &Path, thresholds: &ThresholdsConfig) -> String {
+        let limit = |max: usize| if max > 0 { format!(" (max {})", max) } else { String::new() };
+        let items = |count: ItemCount| format!("{} inline, {} path references", count.inline, count.referenced);
+        format!(
+            "{}\n  lines: {}{}\n  policies: {}{}\n  queries: {}{}\n  labels: {}\n  software packages: {}\n",
+            file.display(),
+            self.lines,
+            limit(thresholds.max_lines_per_file),
+            items(self.policies),
+            limit(thresholds.max_policies_per_file),
+            items(self.queries),
+            limit(thresholds.max_queries_per_file),
+            items(self.labels),
+            items(self.packages),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_stats() {
+        let source = "policies:\n  - path: ../lib/a.yml\n  - name: A\n  - name: B\nqueries:\n  - name: Q\nsoftware:\n  packages:\n    - url: https://example.com/a.pkg\n";
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let stats = FileStats::new(&config, source);
+        assert_eq!(stats.lines, 9);
+        assert_eq!(stats.policies, ItemCount { inline: 2, referenced: 1 });
+        assert_eq!(stats.queries, ItemCount { inline: 1, referenced: 0 });
+        assert_eq!(stats.packages, ItemCount { inline: 1, referenced: 0 });
+
+        let thresholds = ThresholdsConfig { max_policies_per_file: 1, max_lines_per_file: 0, ..Default::default() };
+        let exceeded = stats.exceeded(&thresholds);
+        assert_eq!(
+            exceeded,
+            vec![Exceeded { what: "policies", count: 2, max: 1, setting: "max_policies_per_file" }]
+        );
+        let rendered = stats.render(Path::new("teams/a.yml"), &thresholds);
+        assert!(rendered.starts_with("teams/a.yml\n  lines: 9\n"));
+        assert!(rendered.contains("  policies: 2 inline, 1 path references (max 1)\n"));
+    }
+}
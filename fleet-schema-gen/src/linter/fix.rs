@@ -0,0 +1,171 @@
+//! Applying safe fixes.
+//!
+//! A finding is fixable when its rule marked the suggestion as a safe
+//! replacement for the located text ([`LintError::with_fix`]): a YAML 1.1
+//! boolean, a platform spelled in another case. `lint --fix` and the
+//! language server's "Fix all" both go through [`Linter::fix_content`], so
+//! they change files the same way.
+
+use anyhow::Result;
+use std::path::Path;
+
+use super::error::{LintError, LintReport};
+use super::Linter;
+
+/// Fixes can uncover others (a file that failed to parse); stop after this
+/// many rounds even if something is still fixable.
+const MAX_PASSES: usize = 5;
+
+/// A replacement of `original` at a 1-indexed line and byte column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub line: usize,
+    pub column: usize,
+    pub original: String,
+    pub replacement: String,
+}
+
+impl Fix {
+    /// The fix for a fixable, located finding.
+    pub fn from_error(error: &LintError) -> Option<Self> {
+        if !error.fixable {
+            return None;
+        }
+        let original = error.context.clone()?;
+        let replacement = error.suggestion.clone()?;
+        // Fixes never move lines, so several can be applied in one pass
+        if original.contains('\n') || replacement.contains('\n') || original == replacement {
+            return None;
+        }
+        Some(Self { line: error.line?, column: error.column?, original, replacement })
+    }
+
+    /// Byte range of `original` in `source`, if it is still there.
+    pub fn byte_range(&self, source: &str) -> Option<std::ops::Range<usize>> {
+        let line_start: usize = source.split_inclusive('\n').take(self.line.checked_sub(1)?).map(str::len).sum();
+        let start = line_start + self.column.checked_sub(1)?;
+        let end = start + self.original.len();
+        (source.get(start..end) == Some(self.original.as_str())).then_some(start..end)
+    }
+}
+
+/// Fixes for every fixable finding in a report, in document order.
+pub fn report_fixes(report: &LintReport) -> Vec<Fix> {
+    let mut fixes: Vec<Fix> = report
+        .errors
+        .iter()
+        .chain(&report.warnings)
+        .chain(&report.infos)
+        .filter_map(Fix::from_error)
+        .collect();
+    fixes.sort_by_key(|fix| (fix.line, fix.column));
+    fixes.dedup();
+    fixes
+}
+
+/// Fixes that apply to `source` without overlapping, in document order.
+pub fn applicable(source: &str, fixes: Vec<Fix>) -> Vec<(std::ops::Range<usize>, Fix)> {
+    let mut ranges: Vec<_> = fixes.into_iter().filter_map(|fix| Some((fix.byte_range(source)?, fix))).collect();
+    ranges.sort_by_key(|(range, _)| range.start);
+    let mut end = 0;
+    ranges.retain(|(range, _)| {
+        let keep = range.start >= end;
+        if keep {
+            end = range.end;
+        }
+        keep
+    });
+    ranges
+}
+
+/// `source` with `fixes` applied, and how many were.
+pub fn apply_fixes(source: &str, fixes: Vec<Fix>) -> (String, usize) {
+    let ranges = applicable(source, fixes);
+    let mut out = source.to_string();
+    for (range, fix) in ranges.iter().rev() {
+        out.replace_range(range.clone(), &fix.replacement);
+    }
+    (out, ranges.len())
+}
+
+/// Content after fixing, and how many problems were fixed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixResult {
+    pub content: String,
+    pub fixed: usize,
+}
+
+impl FixResult {
+    pub fn changed(&self) -> bool {
+        self.fixed > 0
+    }
+}
+
+impl Linter {
+    /// Apply every safe fix to `content`, linting again until nothing more
+    /// can be fixed.
+    pub fn fix_content(&self, content: &str, file_path: &Path) -> Result<FixResult> {
+        let mut content = content.to_string();
+        let mut fixed = 0;
+        for _ in 0..MAX_PASSES {
+            let fixes = report_fixes(&self.lint_content(&content, file_path)?);
+            let (next, applied) = apply_fixes(&content, fixes);
+            if applied == 0 {
+                break;
+            }
+            content = next;
+            fixed += applied;
+        }
+        Ok(FixResult { content, fixed })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_fixes() {
+        let source = "a: yes\nb: off\n";
+        let fix = |line, column: usize, original: &str, replacement: &str| Fix {
+            line,
+            column,
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+        };
+        let (fixed, count) = apply_fixes(
+            source,
+            vec![fix(1, 4, "yes", "true"), fix(2, 4, "off", "false"), fix(2, 4, "of", "x"), fix(1, 1, "b", "c")],
+        );
+        // Overlapping and stale fixes are skipped
+        assert_eq!(fixed, "a: true\nb: false\n");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_fix_content() {
+        let linter = Linter::new();
+        let source = "policies:\n  - name: Disk\n    query: SELECT 1 FROM disk_encryption;\n    platform: macOS\n    critical: yes\n    resolution: Encrypt\n  - name: Other\n    query: SELECT 1;\n    platform: freebsd\n    resolution: None\n";
+        let result = linter.fix_content(source, Path::new("team.yml")).unwrap();
+        assert!(result.changed());
+        assert_eq!(result.fixed, 2);
+        assert!(result.content.contains("platform: darwin\n    critical: true\n"));
+        // A guess is not applied
+        assert!(result.content.contains("platform: freebsd"));
+
+        let again = linter.fix_content(&result.content, Path::new("team.yml")).unwrap();
+        assert!(!again.changed());
+        assert_eq!(again.content, result.content);
+    }
+
+    #[test]
+    fn test_fix_content_min_osquery_version() {
+        let linter = Linter::new();
+        let source = "queries:\n  - name: Notes\n    description: |\n      min_osquery_version: 4.0 is too old\n    query: SELECT 1;\n  - name: Updates\n    query: SELECT * FROM windows_update_history;\n    min_osquery_version: \"4.0\"\n";
+        let result = linter.fix_content(source, Path::new("team.yml")).unwrap();
+        // The description is left alone and the query's own value is raised
+        assert!(result.content.contains("      min_osquery_version: 4.0 is too old\n"));
+        assert!(!result.content.contains("min_osquery_version: \"4.0\""));
+        assert!(result.content.contains("min_osquery_version: \"4."));
+    }
+}
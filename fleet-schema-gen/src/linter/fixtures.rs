@@ -0,0 +1,286 @@
+//! Record policy test harness fixtures from the local osquery.
+//!
+//! `fixtures record` runs `SELECT * FROM <table>` through `osqueryi` for
+//! each table and writes the rows to `<table>.json` in a fixture directory
+//! (see [`super::harness`]). osqueryi prints every value as a string, so
+//! values are converted back to numbers from the table's column types, and
+//! this host's hostnames and usernames are replaced with placeholders
+//! before anything is written.
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A table written to the fixture directory.
+#[derive(Debug)]
+pub struct RecordedTable {
+    pub table: String,
+    pub path: PathBuf,
+    pub rows: usize,
+}
+
+/// Replaces hostnames and usernames in text with placeholders.
+///
+/// Matching is ASCII case-insensitive and only at word boundaries, so a
+/// user `al` doesn't redact `/usr/local`. The same value always gets the
+/// same placeholder, which keeps joins and comparisons across tables intact.
+#[derive(Debug, Default)]
+pub struct Redactor {
+    /// (lowercase value, placeholder), longest value first
+    replacements: Vec<(String, String)>,
+    pub hostnames: usize,
+    pub usernames: usize,
+}
+
+impl Redactor {
+    pub fn new(hostnames: &[String], usernames: &[String]) -> Self {
+        let mut redactor = Redactor::default();
+        for hostname in hostnames.iter().map(|h| h.trim()).filter(|h| !h.is_empty()) {
+            // `alices-mbp.corp.example.com` is also written as `alices-mbp`
+            let short = hostname.split('.').next().unwrap_or(hostname);
+            let placeholder = match redactor.placeholder_for(short) {
+                Some(existing) => existing,
+                None => {
+                    redactor.hostnames += 1;
+                    let placeholder = format!("host-{}", redactor.hostnames);
+                    redactor.add(short, &placeholder);
+                    placeholder
+                }
+            };
+            if short != hostname {
+                redactor.add(hostname, &format!("{}.example", placeholder));
+            }
+        }
+        for username in usernames.iter().map(|u| u.trim()).filter(|u| !u.is_empty()) {
+            if redactor.placeholder_for(username).is_none() {
+                redactor.usernames += 1;
+                let placeholder = format!("user-{}", redactor.usernames);
+                redactor.add(username, &placeholder);
+            }
+        }
+        redactor.replacements.sort_by_key(|(value, _)| std::cmp::Reverse(value.len()));
+        redactor
+    }
+
+    fn placeholder_for(&self, value: &str) -> Option<String> {
+        let value = value.to_ascii_lowercase();
+        self.replacements.iter().find(|(v, _)| *v == value).map(|(_, p)| p.clone())
+    }
+
+    fn add(&mut self, value: &str, placeholder: &str) {
+        if self.placeholder_for(value).is_none() {
+            self.replacements.push((value.to_ascii_lowercase(), placeholder.to_string()));
+        }
+    }
+
+    /// `text` with every hostname and username replaced.
+    pub fn redact(&self, text: &str) -> String {
+        // ASCII lowercasing keeps byte offsets, so both strings index alike
+        let lower = text.to_ascii_lowercase();
+        let mut redacted = String::with_capacity(text.len());
+        let mut idx = 0;
+        'scan: while idx < text.len() {
+            let at_boundary = !text[..idx].chars().next_back().is_some_and(char::is_alphanumeric);
+            if at_boundary {
+                for (value, placeholder) in &self.replacements {
+                    let end = idx + value.len();
+                    if lower[idx..].starts_with(value.as_str())
+                        && !text[end..].chars().next().is_some_and(char::is_alphanumeric)
+                    {
+                        redacted.push_str(placeholder);
+                        idx = end;
+                        continue 'scan;
+                    }
+                }
+            }
+            let ch = text[idx..].chars().next().unwrap_or_default();
+            redacted.push(ch);
+            idx += ch.len_utf8();
+        }
+        redacted
+    }
+}
+
+/// Whether `table` is a plain osquery table name (also used as a file name).
+fn is_table_name(table: &str) -> bool {
+    !table.is_empty() && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// `value` as the JSON type of an osquery column type.
+fn typed(value: Value, column_type: &str) -> Value {
+    let Value::String(text) = &value else {
+        return value;
+    };
+    let number = match column_type.to_ascii_uppercase().as_str() {
+        "INTEGER" | "BIGINT" | "UNSIGNED_BIGINT" => text
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| text.parse::<u64>().map(Value::from))
+            .ok(),
+        "DOUBLE" => text.parse::<f64>().ok().and_then(|f| serde_json::Number::from_f64(f).map(Value::Number)),
+        _ => None,
+    };
+    number.unwrap_or(value)
+}
+
+/// Hostnames and human usernames of the host osqueryi runs on.
+fn identities<F>(run: &F) -> Result<(Vec<String>, Vec<String>)>
+where
+    F: Fn(&str) -> Result<Vec<Value>, String>,
+{
+    let text = |row: &Value, column: &str| row.get(column).and_then(Value::as_str).unwrap_or_default().to_string();
+
+    let system = run("SELECT hostname, computer_name, local_hostname FROM system_info")
+        .map_err(|e| anyhow::anyhow!("Failed to read hostnames for redaction: {}", e))?;
+    let hostnames = system
+        .iter()
+        .flat_map(|row| ["hostname", "computer_name", "local_hostname"].map(|column| text(row, column)))
+        .collect();
+
+    // Accounts with a home directory under /Users, /home or C:\Users, not
+    // service accounts like root or _www that policies refer to by name
+    let users = run("SELECT username, directory FROM users")
+        .map_err(|e| anyhow::anyhow!("Failed to read usernames for redaction: {}", e))?;
+    let usernames = users
+        .iter()
+        .filter(|row| {
+            let directory = text(row, "directory").replace('\\', "/").to_ascii_lowercase();
+            let home = directory.split_once(":/").map_or(directory.as_str(), |(_, rest)| rest).trim_start_matches('/');
+            (home.starts_with("users/") || home.starts_with("home/"))
+                && !matches!(home, "users/shared" | "users/public")
+        })
+        .map(|row| text(row, "username"))
+        .collect();
+    Ok((hostnames, usernames))
+}
+
+/// Record `tables` into `dir` with `run`, which executes SQL on the host
+/// (normally [`super::osqueryi::run_query`]).
+///
+/// With `redact`, hostnames and usernames of the host are replaced first.
+pub fn record<F>(tables: &[String], dir: &Path, limit: Option<usize>, redact: bool, run: F) -> Result<(Vec<RecordedTable>, Redactor)>
+where
+    F: Fn(&str) -> Result<Vec<Value>, String>,
+{
+    if let Some(table) = tables.iter().find(|table| !is_table_name(table)) {
+        anyhow::bail!("Invalid table name '{}'", table);
+    }
+    let redactor = if redact {
+        let (hostnames, usernames) = identities(&run)?;
+        Redactor::new(&hostnames, &usernames)
+    } else {
+        Redactor::default()
+    };
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut recorded = Vec::new();
+    for table in tables {
+        let columns = run(&format!("PRAGMA table_info({})", table))
+            .map_err(|e| anyhow::anyhow!("Failed to read the columns of {}: {}", table, e))?;
+        let column_type = |name: &str| {
+            columns
+                .iter()
+                .find(|column| column.get("name").and_then(Value::as_str) == Some(name))
+                .and_then(|column| column.get("type").and_then(Value::as_str))
+                .unwrap_or("TEXT")
+        };
+
+        let mut sql = format!("SELECT * FROM {}", table);
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+        let rows = run(&sql).map_err(|e| anyhow::anyhow!("Failed to query {}: {}", table, e))?;
+
+        let rows: Vec<Value> = rows
+            .into_iter()
+            .map(|row| match row {
+                Value::Object(object) => Value::Object(
+                    object
+                        .into_iter()
+                        .map(|(name, value)| {
+                            let value = match value {
+                                Value::String(text) => Value::String(redactor.redact(&text)),
+                                other => other,
+                            };
+                            let value = typed(value, column_type(&name));
+                            (name, value)
+                        })
+                        .collect::<Map<_, _>>(),
+                ),
+                other => other,
+            })
+            .collect();
+
+        let path = dir.join(format!("{}.json", table));
+        let json = serde_json::to_string_pretty(&rows)?;
+        fs::write(&path, json + "\n").with_context(|| format!("Failed to write {}", path.display()))?;
+        recorded.push(RecordedTable { table: table.clone(), path, rows: rows.len() });
+    }
+    Ok((recorded, redactor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_redactor() {
+        let redactor = Redactor::new(
+            &["alices-mbp.corp.example.com".to_string(), "Alices-MBP".to_string(), "Alice's MacBook".to_string()],
+            &["alice".to_string(), "al".to_string()],
+        );
+        assert_eq!((redactor.hostnames, redactor.usernames), (2, 2));
+        assert_eq!(redactor.redact("ssh alice@alices-mbp.corp.example.com"), "ssh user-1@host-1.example");
+        assert_eq!(redactor.redact("ALICES-MBP / Alice's MacBook"), "host-1 / host-2");
+        // Only whole words
+        assert_eq!(redactor.redact("/Users/alice/Library /usr/local al"), "/Users/user-1/Library /usr/local user-2");
+        assert_eq!(redactor.redact("malice"), "malice");
+    }
+
+    #[test]
+    fn test_typed() {
+        assert_eq!(typed(json!("42"), "BIGINT"), json!(42));
+        assert_eq!(typed(json!("18446744073709551615"), "UNSIGNED_BIGINT"), json!(18446744073709551615u64));
+        assert_eq!(typed(json!("1.5"), "DOUBLE"), json!(1.5));
+        assert_eq!(typed(json!("14.1"), "TEXT"), json!("14.1"));
+        assert_eq!(typed(json!(""), "INTEGER"), json!(""));
+    }
+
+    #[test]
+    fn test_record() {
+        let temp_dir = TempDir::new().unwrap();
+        let run = |sql: &str| -> Result<Vec<Value>, String> {
+            Ok(match sql {
+                s if s.contains("system_info") => {
+                    vec![json!({"hostname": "alices-mbp.local", "computer_name": "Alice's MacBook", "local_hostname": "alices-mbp"})]
+                }
+                s if s.contains("FROM users") => vec![
+                    json!({"username": "alice", "directory": "/Users/alice"}),
+                    json!({"username": "root", "directory": "/var/root"}),
+                    json!({"username": "Shared", "directory": "/Users/Shared"}),
+                ],
+                "PRAGMA table_info(processes)" => {
+                    vec![json!({"name": "pid", "type": "BIGINT"}), json!({"name": "path", "type": "TEXT"})]
+                }
+                "SELECT * FROM processes LIMIT 1" => {
+                    vec![json!({"pid": "42", "path": "/Users/alice/bin/tool", "cwd": "/var/root"})]
+                }
+                _ => return Err(format!("no such table: {}", sql)),
+            })
+        };
+
+        let dir = temp_dir.path().join("fixtures/recorded");
+        let (recorded, redactor) = record(&["processes".to_string()], &dir, Some(1), true, run).unwrap();
+        assert_eq!(recorded[0].rows, 1);
+        assert_eq!((redactor.hostnames, redactor.usernames), (2, 1));
+        let rows: Value = serde_json::from_str(&fs::read_to_string(dir.join("processes.json")).unwrap()).unwrap();
+        assert_eq!(rows, json!([{"pid": 42, "path": "/Users/user-1/bin/tool", "cwd": "/var/root"}]));
+
+        assert!(record(&["os_version; DROP".to_string()], &dir, None, true, run).is_err());
+        let error = record(&["nope".to_string()], &dir, None, false, run).unwrap_err();
+        assert!(error.to_string().contains("nope"));
+    }
+}
@@ -68,7 +68,7 @@ pub enum LabelOrPath {
     Label(Label),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Policy {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -93,6 +93,10 @@ pub struct Policy {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub calendar_events_enabled: Option<bool>,
+
+    // Catch-all for unknown fields (e.g. translated `resolution_de`)
+    #[serde(flatten)]
+    pub other: serde_yaml::Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
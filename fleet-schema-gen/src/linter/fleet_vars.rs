@@ -0,0 +1,322 @@
+//! Registry of Fleet built-in variables (`$FLEET_VAR_*`).
+//!
+//! Fleet substitutes these in configuration profiles and scripts when they
+//! are delivered to a host. Each variable is only supported in some places
+//! (e.g. NDES SCEP variables only in Apple profiles), so references are
+//! checked against the context of the file they appear in.
+
+use super::error::LintError;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+
+/// Where a Fleet variable may be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarContext {
+    /// `.mobileconfig` / `.plist` profiles
+    AppleProfile,
+    /// Windows SyncML `.xml` profiles
+    WindowsProfile,
+    /// Scripts (`.sh`, `.zsh`, `.ps1`, ...)
+    Script,
+}
+
+impl VarContext {
+    /// Context of a referenced file, from its extension.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "mobileconfig" | "plist" => Some(VarContext::AppleProfile),
+            "xml" => Some(VarContext::WindowsProfile),
+            "sh" | "zsh" | "bash" | "ps1" | "py" => Some(VarContext::Script),
+            _ => None,
+        }
+    }
+
+    /// Human-readable name for messages.
+    pub fn label(self) -> &'static str {
+        match self {
+            VarContext::AppleProfile => "Apple profiles",
+            VarContext::WindowsProfile => "Windows profiles",
+            VarContext::Script => "scripts",
+        }
+    }
+}
+
+/// A Fleet built-in variable.
+#[derive(Debug, Clone)]
+pub struct FleetVariable {
+    /// Name without the leading `$`, e.g. `FLEET_VAR_HOST_UUID`.
+    pub name: &'static str,
+    /// The name is a prefix completed by a certificate authority name
+    /// (e.g. `FLEET_VAR_DIGICERT_DATA_<CA_NAME>`).
+    pub prefix: bool,
+    pub description: &'static str,
+    pub contexts: &'static [VarContext],
+}
+
+impl FleetVariable {
+    /// Whether `name` refers to this variable.
+    pub fn matches(&self, name: &str) -> bool {
+        if self.prefix {
+            name.len() > self.name.len() && name.starts_with(self.name)
+        } else {
+            name == self.name
+        }
+    }
+
+    /// Display form (`$FLEET_VAR_DIGICERT_DATA_<CA_NAME>` for prefixes).
+    pub fn display(&self) -> String {
+        if self.prefix {
+            format!("${}<CA_NAME>", self.name)
+        } else {
+            format!("${}", self.name)
+        }
+    }
+}
+
+use VarContext::{AppleProfile, WindowsProfile};
+
+/// Fleet variables supported by the current Fleet release.
+pub static FLEET_VARIABLES: &[FleetVariable] = &[
+    FleetVariable {
+        name: "FLEET_VAR_NDES_SCEP_CHALLENGE",
+        prefix: false,
+        description: "One-time challenge from the NDES SCEP server",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_NDES_SCEP_PROXY_URL",
+        prefix: false,
+        description: "Fleet's SCEP proxy URL for the NDES server",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_CUSTOM_SCEP_CHALLENGE_",
+        prefix: true,
+        description: "Challenge for a custom SCEP certificate authority",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_CUSTOM_SCEP_PROXY_URL_",
+        prefix: true,
+        description: "Fleet's SCEP proxy URL for a custom SCEP certificate authority",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_DIGICERT_DATA_",
+        prefix: true,
+        description: "Base64-encoded certificate issued by a DigiCert certificate authority",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_DIGICERT_PASSWORD_",
+        prefix: true,
+        description: "Password for the PKCS #12 certificate issued by DigiCert",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_SCEP_RENEWAL_ID",
+        prefix: false,
+        description: "Identifier Fleet uses to renew SCEP certificates before they expire",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_HOST_END_USER_EMAIL_IDP",
+        prefix: false,
+        description: "End user's email from the identity provider (IdP)",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_HOST_END_USER_IDP_USERNAME",
+        prefix: false,
+        description: "End user's IdP username",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_HOST_END_USER_IDP_USERNAME_LOCAL_PART",
+        prefix: false,
+        description: "Local part of the end user's IdP username (before the @)",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_HOST_END_USER_IDP_GROUPS",
+        prefix: false,
+        description: "Comma-separated IdP groups of the end user",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_HOST_END_USER_IDP_DEPARTMENT",
+        prefix: false,
+        description: "End user's department from the IdP",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_HOST_HARDWARE_SERIAL",
+        prefix: false,
+        description: "Host's hardware serial number",
+        contexts: &[AppleProfile],
+    },
+    FleetVariable {
+        name: "FLEET_VAR_HOST_UUID",
+        prefix: false,
+        description: "Host's hardware UUID",
+        contexts: &[AppleProfile, WindowsProfile],
+    },
+];
+
+/// `$FLEET_VAR_NAME` or `${FLEET_VAR_NAME}`
+static VAR_REFERENCE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\$\{?(FLEET_VAR_[A-Za-z0-9_]*)\}?").unwrap()
+});
+
+/// A `$FLEET_VAR_*` reference in some text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarReference {
+    /// Name without `$` / braces.
+    pub name: String,
+    /// 1-indexed line and column of the `$`.
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Find every `$FLEET_VAR_*` reference in text.
+pub fn find_references(text: &str) -> Vec<VarReference> {
+    text.lines()
+        .enumerate()
+        .flat_map(|(idx, line)| {
+            VAR_REFERENCE
+                .captures_iter(line)
+                .map(|caps| VarReference {
+                    name: caps[1].to_string(),
+                    line: idx + 1,
+                    column: caps.get(0).map(|m| m.start() + 1).unwrap_or(1),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Look up a variable by name (prefix variables match any CA name).
+pub fn lookup(name: &str) -> Option<&'static FleetVariable> {
+    FLEET_VARIABLES.iter().find(|var| var.matches(name))
+}
+
+/// Variables usable in a context (all variables when `None`).
+pub fn variables_for(context: Option<VarContext>) -> impl Iterator<Item = &'static FleetVariable> {
+    FLEET_VARIABLES
+        .iter()
+        .filter(move |var| context.is_none_or(|c| var.contexts.contains(&c)))
+}
+
+/// Problem with a variable reference in a given context.
+pub fn check_reference(name: &str, context: Option<VarContext>) -> Option<String> {
+    match lookup(name) {
+        None => Some(format!("Unknown Fleet variable ${}", name)),
+        Some(var) => match context {
+            Some(context) if !var.contexts.contains(&context) => Some(format!(
+                "${} is not supported in {} (supported in: {})",
+                name,
+                context.label(),
+                var.contexts.iter().map(|c| c.label()).collect::<Vec<_>>().join(", ")
+            )),
+            _ => None,
+        },
+    }
+}
+
+/// Check every reference in a profile or script.
+///
+/// Locations in the returned errors refer to `file`.
+pub fn check_file(text: &str, context: Option<VarContext>, file: &Path) -> Vec<LintError> {
+    find_references(text)
+        .into_iter()
+        .filter_map(|reference| {
+            let message = check_reference(&reference.name, context)?;
+            let mut error = LintError::error(message, file)
+                .with_location(reference.line, reference.column)
+                .with_context(format!("${}", reference.name));
+            if let Some(suggestion) = lookup(&reference.name).is_none().then(|| suggest(&reference.name)).flatten() {
+                error = error
+                    .with_help(format!("Did you mean ${}?", suggestion))
+                    .with_suggestion(format!("${}", suggestion));
+            }
+            Some(error)
+        })
+        .collect()
+}
+
+/// Closest known variable name, for "did you mean" suggestions.
+pub fn suggest(name: &str) -> Option<String> {
+    FLEET_VARIABLES
+        .iter()
+        .filter(|var| !var.prefix)
+        .map(|var| (edit_distance(name, var.name), var.name))
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name.to_string())
+}
+
+/// Levenshtein distance between two strings.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_references() {
+        let refs = find_references("<string>$FLEET_VAR_HOST_UUID</string>\n  ${FLEET_VAR_NDES_SCEP_CHALLENGE}");
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].name, "FLEET_VAR_HOST_UUID");
+        assert_eq!((refs[0].line, refs[0].column), (1, 9));
+        assert_eq!(refs[1].name, "FLEET_VAR_NDES_SCEP_CHALLENGE");
+        assert_eq!((refs[1].line, refs[1].column), (2, 3));
+    }
+
+    #[test]
+    fn test_lookup_prefix_variables() {
+        assert!(lookup("FLEET_VAR_DIGICERT_DATA_CORP_CA").is_some());
+        assert!(lookup("FLEET_VAR_DIGICERT_DATA_").is_none());
+        assert!(lookup("FLEET_VAR_HOST_SERIAL").is_none());
+    }
+
+    #[test]
+    fn test_check_reference() {
+        assert_eq!(check_reference("FLEET_VAR_HOST_UUID", Some(VarContext::WindowsProfile)), None);
+        assert!(check_reference("FLEET_VAR_NDES_SCEP_CHALLENGE", Some(VarContext::WindowsProfile))
+            .unwrap()
+            .contains("not supported in Windows profiles"));
+        assert!(check_reference("FLEET_VAR_BOGUS", None).unwrap().starts_with("Unknown"));
+    }
+
+    #[test]
+    fn test_check_file() {
+        let errors = check_file(
+            "echo $FLEET_VAR_HOST_UIID\n",
+            Some(VarContext::Script),
+            Path::new("scripts/setup.sh"),
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, Some(1));
+        assert_eq!(errors[0].column, Some(6));
+        assert_eq!(errors[0].suggestion.as_deref(), Some("$FLEET_VAR_HOST_UUID"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(suggest("FLEET_VAR_HOST_UIID").as_deref(), Some("FLEET_VAR_HOST_UUID"));
+        assert_eq!(suggest("FLEET_VAR_SOMETHING_ELSE"), None);
+    }
+}
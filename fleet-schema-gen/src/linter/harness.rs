@@ -0,0 +1,463 @@
+//! Policy test harness.
+//!
+//! Runs policy and query SQL against local fixture data loaded into an
+//! in-memory SQLite database, and checks the results against expectations.
+//!
+//! Test cases live next to the policy file in a `<name>.tests.yml` sidecar:
+//!
+//! ```yaml
+//! # lib/macos/policies/firewall.tests.yml (tests for firewall.yml)
+//! - policy: Firewall enabled
+//!   fixtures: fixtures/firewall-on
+//!   expect: pass
+//! - policy: Firewall enabled
+//!   fixtures: fixtures/firewall-off
+//!   expect: fail
+//! ```
+//!
+//! A fixture directory holds one file per osquery table: `alf.csv` (header
+//! row with column names) or `alf.json` (array of row objects). A policy
+//! passes when its query returns at least one row. `fixtures record` writes
+//! JSON fixtures from the local osquery (see [`super::fixtures`]).
+
+use super::engine::parse_fleet_config;
+use super::fleet_config::{PolicyOrPath, QueryOrPath};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::Connection;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Suffix identifying test sidecar files.
+pub const TESTS_FILE_SUFFIX: &str = ".tests.yml";
+
+/// Expected outcome of running a policy or query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Expectation {
+    /// Query returns at least one row.
+    Pass,
+    /// Query returns no rows.
+    Fail,
+}
+
+/// A single test case from a `.tests.yml` file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestCase {
+    /// Name of the policy under test.
+    #[serde(default)]
+    pub policy: Option<String>,
+
+    /// Name of the query under test (alternative to `policy`).
+    #[serde(default)]
+    pub query: Option<String>,
+
+    /// Fixture directory, relative to the tests file.
+    pub fixtures: String,
+
+    /// Expected outcome.
+    pub expect: Expectation,
+
+    /// Policy/query file to load (default: tests file without `.tests`).
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+impl TestCase {
+    fn target_name(&self) -> &str {
+        self.policy
+            .as_deref()
+            .or(self.query.as_deref())
+            .unwrap_or("unnamed")
+    }
+}
+
+/// Result of running one test case.
+#[derive(Debug)]
+pub struct TestOutcome {
+    pub tests_file: PathBuf,
+    pub name: String,
+    pub fixtures: String,
+    pub expected: Expectation,
+    /// Rows returned, or the error that prevented running the SQL.
+    pub result: Result<usize, String>,
+}
+
+impl TestOutcome {
+    pub fn passed(&self) -> bool {
+        match (&self.result, self.expected) {
+            (Ok(rows), Expectation::Pass) => *rows > 0,
+            (Ok(rows), Expectation::Fail) => *rows == 0,
+            (Err(_), _) => false,
+        }
+    }
+}
+
+/// Find all `.tests.yml` files under a path.
+pub fn find_tests_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    fn visit(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+            if path.is_dir() {
+                if name.starts_with('.') || name == "node_modules" || name == "target" {
+                    continue;
+                }
+                visit(&path, files)?;
+            } else if name.ends_with(TESTS_FILE_SUFFIX) {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    visit(path, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// Run every test case in a `.tests.yml` file.
+pub fn run_tests_file(tests_file: &Path) -> Result<Vec<TestOutcome>> {
+    let content = fs::read_to_string(tests_file)
+        .with_context(|| format!("Failed to read {}", tests_file.display()))?;
+    let cases: Vec<TestCase> = serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", tests_file.display()))?;
+
+    let base_dir = tests_file.parent().unwrap_or(Path::new("."));
+
+    let mut outcomes = Vec::new();
+    for case in cases {
+        let result = run_case(tests_file, base_dir, &case).map_err(|e| format!("{:#}", e));
+        outcomes.push(TestOutcome {
+            tests_file: tests_file.to_path_buf(),
+            name: case.target_name().to_string(),
+            fixtures: case.fixtures.clone(),
+            expected: case.expect,
+            result,
+        });
+    }
+
+    Ok(outcomes)
+}
+
+fn run_case(tests_file: &Path, base_dir: &Path, case: &TestCase) -> Result<usize> {
+    let target_file = match &case.file {
+        Some(file) => base_dir.join(file),
+        None => default_target_file(tests_file)?,
+    };
+
+    let sql = find_sql(&target_file, case)?;
+
+    let conn = Connection::open_in_memory()?;
+    load_fixtures(&conn, &base_dir.join(&case.fixtures))?;
+
+    count_rows(&conn, &sql)
+}
+
+/// `firewall.tests.yml` tests `firewall.yml` (or `firewall.yaml`).
+fn default_target_file(tests_file: &Path) -> Result<PathBuf> {
+    let name = tests_file.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let stem = name.strip_suffix(TESTS_FILE_SUFFIX).unwrap_or(name);
+    let dir = tests_file.parent().unwrap_or(Path::new("."));
+
+    for ext in ["yml", "yaml"] {
+        let candidate = dir.join(format!("{}.{}", stem, ext));
+        if candidate.is_file() {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!("No {}.yml next to {} (set `file:` explicitly)", stem, tests_file.display())
+}
+
+/// Find the SQL for the policy or query named by a test case.
+fn find_sql(target_file: &Path, case: &TestCase) -> Result<String> {
+    let content = fs::read_to_string(target_file)
+        .with_context(|| format!("Failed to read {}", target_file.display()))?;
+
+    let config = parse_fleet_config(&content, target_file)?;
+
+    let sql = if let Some(name) = &case.policy {
+        config.policies.iter().flatten().find_map(|p| match p {
+            PolicyOrPath::Policy(policy) if policy.name.as_deref() == Some(name) => policy.query.clone(),
+            _ => None,
+        })
+    } else if let Some(name) = &case.query {
+        config.queries.iter().flatten().find_map(|q| match q {
+            QueryOrPath::Query(query) if query.name.as_deref() == Some(name) => query.query.clone(),
+            _ => None,
+        })
+    } else {
+        anyhow::bail!("Test case must set `policy` or `query`");
+    };
+
+    sql.ok_or_else(|| {
+        anyhow::anyhow!("'{}' not found in {}", case.target_name(), target_file.display())
+    })
+}
+
+/// Load every `.csv`/`.json` file in a directory as a table.
+pub fn load_fixtures(conn: &Connection, dir: &Path) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read fixtures directory {}", dir.display()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+        let Some(table) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        let (columns, rows) = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => read_csv_fixture(&path)?,
+            Some("json") => read_json_fixture(&path)?,
+            _ => continue,
+        };
+
+        create_table(conn, table, &columns, &rows)
+            .with_context(|| format!("Failed to load fixture {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+type FixtureRows = (Vec<String>, Vec<Vec<SqlValue>>);
+
+fn read_csv_fixture(path: &Path) -> Result<FixtureRows> {
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let columns: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        rows.push(record.iter().map(parse_csv_value).collect());
+    }
+
+    Ok((columns, rows))
+}
+
+/// CSV cells are untyped; store numbers as numbers so comparisons work.
+fn parse_csv_value(cell: &str) -> SqlValue {
+    if let Ok(i) = cell.parse::<i64>() {
+        SqlValue::Integer(i)
+    } else if let Ok(f) = cell.parse::<f64>() {
+        SqlValue::Real(f)
+    } else {
+        SqlValue::Text(cell.to_string())
+    }
+}
+
+fn read_json_fixture(path: &Path) -> Result<FixtureRows> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let objects: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(&content)
+        .with_context(|| format!("{} must be an array of objects", path.display()))?;
+
+    // Column set is the union of keys, in first-seen order
+    let mut columns: Vec<String> = Vec::new();
+    for object in &objects {
+        for key in object.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+
+    let rows = objects
+        .iter()
+        .map(|object| {
+            columns
+                .iter()
+                .map(|c| json_to_sql(object.get(c).unwrap_or(&serde_json::Value::Null)))
+                .collect()
+        })
+        .collect();
+
+    Ok((columns, rows))
+}
+
+fn json_to_sql(value: &serde_json::Value) -> SqlValue {
+    match value {
+        serde_json::Value::Null => SqlValue::Null,
+        serde_json::Value::Bool(b) => SqlValue::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn create_table(conn: &Connection, table: &str, columns: &[String], rows: &[Vec<SqlValue>]) -> Result<()> {
+    if columns.is_empty() {
+        anyhow::bail!("Fixture has no columns");
+    }
+
+    let column_list = columns.iter().map(|c| quote_ident(c)).collect::<Vec<_>>().join(", ");
+    conn.execute(&format!("CREATE TABLE {} ({})", quote_ident(table), column_list), [])?;
+
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let mut insert = conn.prepare(&format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote_ident(table),
+        column_list,
+        placeholders
+    ))?;
+
+    for row in rows {
+        insert.execute(rusqlite::params_from_iter(row.iter()))?;
+    }
+
+    Ok(())
+}
+
+/// Run SQL and count the rows it returns.
+pub fn count_rows(conn: &Connection, sql: &str) -> Result<usize> {
+    let sql = sql.trim().trim_end_matches(';');
+    let mut stmt = conn.prepare(sql)?;
+    let mut rows = stmt.query([])?;
+
+    let mut count = 0;
+    while rows.next()?.is_some() {
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Print outcomes and a summary; returns the number of failures.
+pub fn print_outcomes(outcomes: &[TestOutcome]) -> usize {
+    let mut current_file: Option<&Path> = None;
+    let mut failures = 0;
+
+    for outcome in outcomes {
+        if current_file != Some(outcome.tests_file.as_path()) {
+            println!("\n{}", outcome.tests_file.display().to_string().bold());
+            current_file = Some(outcome.tests_file.as_path());
+        }
+
+        let expected = match outcome.expected {
+            Expectation::Pass => "pass",
+            Expectation::Fail => "fail",
+        };
+
+        if outcome.passed() {
+            println!("  {} {} [{}] expect {}",
+                "✓".green(),
+                outcome.name,
+                outcome.fixtures.dimmed(),
+                expected
+            );
+        } else {
+            failures += 1;
+            let detail = match &outcome.result {
+                Ok(rows) => format!("returned {} row(s)", rows),
+                Err(e) => format!("error: {}", e),
+            };
+            println!("  {} {} [{}] expect {}, {}",
+                "✗".red(),
+                outcome.name,
+                outcome.fixtures.dimmed(),
+                expected,
+                detail.red()
+            );
+        }
+    }
+
+    println!("\n{} {} passed, {} failed",
+        "Summary:".bold(),
+        (outcomes.len() - failures).to_string().green(),
+        failures.to_string().red()
+    );
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_fixture_repo(root: &Path) {
+        fs::create_dir_all(root.join("fixtures/firewall-on")).unwrap();
+        fs::create_dir_all(root.join("fixtures/firewall-off")).unwrap();
+
+        fs::write(root.join("fixtures/firewall-on/alf.csv"), "global_state,stealth_enabled\n1,0\n").unwrap();
+        fs::write(root.join("fixtures/firewall-off/alf.json"), r#"[{"global_state": 0, "stealth_enabled": false}]"#).unwrap();
+
+        fs::write(
+            root.join("firewall.yml"),
+            r#"- name: Firewall enabled
+  query: SELECT 1 FROM alf WHERE global_state >= 1;
+  platform: darwin
+"#,
+        )
+        .unwrap();
+
+        fs::write(
+            root.join("firewall.tests.yml"),
+            r#"- policy: Firewall enabled
+  fixtures: fixtures/firewall-on
+  expect: pass
+- policy: Firewall enabled
+  fixtures: fixtures/firewall-off
+  expect: fail
+- policy: Firewall enabled
+  fixtures: fixtures/firewall-off
+  expect: pass
+"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_run_tests_file() {
+        let temp_dir = TempDir::new().unwrap();
+        write_fixture_repo(temp_dir.path());
+
+        let files = find_tests_files(temp_dir.path()).unwrap();
+        assert_eq!(files.len(), 1);
+
+        let outcomes = run_tests_file(&files[0]).unwrap();
+        assert_eq!(outcomes.len(), 3);
+        assert!(outcomes[0].passed());
+        assert!(outcomes[1].passed());
+        assert!(!outcomes[2].passed());
+        assert_eq!(outcomes[2].result, Ok(0));
+    }
+
+    #[test]
+    fn test_missing_table_is_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_fixture_repo(root);
+        fs::write(
+            root.join("firewall.yml"),
+            "- name: Firewall enabled\n  query: SELECT 1 FROM no_such_table;\n",
+        )
+        .unwrap();
+
+        let outcomes = run_tests_file(&root.join("firewall.tests.yml")).unwrap();
+        assert!(outcomes[0].result.as_ref().unwrap_err().contains("no_such_table"));
+    }
+
+    #[test]
+    fn test_parse_csv_value() {
+        assert_eq!(parse_csv_value("42"), SqlValue::Integer(42));
+        assert_eq!(parse_csv_value("1.5"), SqlValue::Real(1.5));
+        assert_eq!(parse_csv_value("on"), SqlValue::Text("on".to_string()));
+    }
+}
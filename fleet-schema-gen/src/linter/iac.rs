@@ -0,0 +1,351 @@
+//! Terraform and Pulumi exports of a GitOps repository.
+//!
+//! `export --format terraform` writes resource blocks for the fleetdm
+//! Terraform provider, which only manages teams (`fleetdm_teams`): one
+//! block per team. Policies and queries have no provider resource, so they
+//! are reported as left out and stay managed by `fleetctl gitops`.
+//! `--format pulumi` writes the same resources as a Pulumi YAML program;
+//! there is no published Pulumi provider, so the program declares the
+//! Terraform provider as a parameterized package (`pulumi package add
+//! terraform-provider fleetdm/fleetdm`). Attributes use the Fleet API
+//! field names.
+
+use super::repo::GitopsRepo;
+use anyhow::Result;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashSet;
+
+/// Resources an export creates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    Team,
+}
+
+impl ResourceKind {
+    fn terraform_type(self) -> &'static str {
+        match self {
+            ResourceKind::Team => "fleetdm_teams",
+        }
+    }
+
+    fn pulumi_type(self) -> &'static str {
+        match self {
+            ResourceKind::Team => "fleetdm:index:Teams",
+        }
+    }
+}
+
+/// An attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Attr {
+    Text(String),
+}
+
+/// One resource: its kind, resource name, and attributes in output order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resource {
+    pub kind: ResourceKind,
+    pub id: String,
+    pub attributes: Vec<(&'static str, Attr)>,
+}
+
+/// An item the provider has no resource for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    /// "policy" or "query"
+    pub kind: &'static str,
+    pub name: String,
+    pub team: String,
+}
+
+/// The resources of an export and the items left out of it.
+#[derive(Debug, Clone, Default)]
+pub struct IacExport {
+    pub resources: Vec<Resource>,
+    pub skipped: Vec<Skipped>,
+}
+
+/// A Terraform/Pulumi resource name for `name`: lowercase letters, digits
+/// and underscores, not starting with a digit.
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    let slug = slug.trim_end_matches('_');
+    match slug.chars().next() {
+        None => "item".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{}", slug),
+        Some(_) => slug.to_string(),
+    }
+}
+
+/// `id`, or `id_2`, `id_3`, ... when it's taken.
+fn unique(taken: &mut HashSet<String>, id: String) -> String {
+    let mut candidate = id.clone();
+    let mut n = 1;
+    while !taken.insert(candidate.clone()) {
+        n += 1;
+        candidate = format!("{}_{}", id, n);
+    }
+    candidate
+}
+
+/// Team resources of the repository, and the policies and queries the
+/// provider can't represent.
+///
+/// `default.yml` and `no-team.yml` have no team resource; policies and
+/// queries without a name are not reported.
+pub fn iac_resources(repo: &GitopsRepo) -> IacExport {
+    let mut taken = HashSet::new();
+    let mut export = IacExport::default();
+
+    for team in &repo.teams {
+        let stem = team.file.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let is_global = stem == "default" && team.file.parent() == Some(repo.root.as_path());
+        if !is_global && stem != "no-team" {
+            export.resources.push(Resource {
+                kind: ResourceKind::Team,
+                id: unique(&mut taken, slug(&team.name)),
+                attributes: vec![("name", Attr::Text(team.name.clone()))],
+            });
+        }
+
+        let policies = team.policies.iter().filter_map(|sourced| sourced.item.name.as_ref()).map(|name| ("policy", name));
+        let queries = team.queries.iter().filter_map(|sourced| sourced.item.name.as_ref()).map(|name| ("query", name));
+        export.skipped.extend(policies.chain(queries).map(|(kind, name)| Skipped {
+            kind,
+            name: name.clone(),
+            team: team.name.clone(),
+        }));
+    }
+
+    export
+}
+
+const TERRAFORM_HEADER: &str = r#"# Generated by `fleet-schema-gen export --format terraform`.
+terraform {
+  required_providers {
+    fleetdm = {
+      source = "fleetdm/fleetdm"
+    }
+  }
+}
+"#;
+
+/// `text` as an HCL quoted string; `${` and `%{` would start template sequences.
+fn hcl_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted.replace("${", "$${").replace("%{", "%%{")
+}
+
+/// Multi-line text as an indented heredoc, unless a line would end it.
+fn hcl_heredoc(text: &str) -> Option<String> {
+    if !text.contains('\n') || text.lines().any(|line| line.trim() == "EOT") {
+        return None;
+    }
+    let mut heredoc = String::from("<<-EOT\n");
+    for line in text.lines() {
+        if !line.trim().is_empty() {
+            heredoc.push_str("    ");
+            heredoc.push_str(&line.trim_end().replace("${", "$${").replace("%{", "%%{"));
+        }
+        heredoc.push('\n');
+    }
+    heredoc.push_str("  EOT");
+    Some(heredoc)
+}
+
+fn hcl_value(value: &Attr) -> String {
+    match value {
+        Attr::Text(text) => hcl_heredoc(text).unwrap_or_else(|| hcl_string(text)),
+    }
+}
+
+/// Resources as a Terraform configuration, laid out like `terraform fmt`.
+pub fn render_terraform(resources: &[Resource]) -> String {
+    let mut out = String::from(TERRAFORM_HEADER);
+    for resource in resources {
+        out.push_str(&format!("\nresource \"{}\" \"{}\" {{\n", resource.kind.terraform_type(), resource.id));
+        let values: Vec<(&str, String)> =
+            resource.attributes.iter().map(|(key, value)| (*key, hcl_value(value))).collect();
+        // `=` is aligned within runs of single-line attributes
+        for run in values.split_inclusive(|(_, value)| value.contains('\n')) {
+            let (single, multi) = match run.last() {
+                Some((_, value)) if value.contains('\n') => (&run[..run.len() - 1], run.last()),
+                _ => (run, None),
+            };
+            let width = single.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+            for (key, value) in single {
+                out.push_str(&format!("  {:width$} = {}\n", key, value, width = width));
+            }
+            if let Some((key, value)) = multi {
+                out.push_str(&format!("  {} = {}\n", key, value));
+            }
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+/// `snake_case` as `camelCase`, Pulumi's property naming.
+fn camel_case(key: &str) -> String {
+    let mut parts = key.split('_');
+    let mut camel = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            camel.push(first.to_ascii_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
+
+/// Resources as a Pulumi YAML program (`Pulumi.yaml`).
+pub fn render_pulumi(resources: &[Resource]) -> Result<String> {
+    let mut entries = Mapping::new();
+    for resource in resources {
+        let mut properties = Mapping::new();
+        for (key, value) in &resource.attributes {
+            let value = match value {
+                // `${` starts an interpolation in Pulumi YAML
+                Attr::Text(text) => Value::from(text.replace("${", "$${")),
+            };
+            properties.insert(Value::from(camel_case(key)), value);
+        }
+        let mut entry = Mapping::new();
+        entry.insert("type".into(), resource.kind.pulumi_type().into());
+        entry.insert("properties".into(), Value::Mapping(properties));
+        entries.insert(resource.id.clone().into(), Value::Mapping(entry));
+    }
+
+    let mut program = Mapping::new();
+    program.insert("name".into(), "fleet-gitops".into());
+    program.insert("runtime".into(), "yaml".into());
+    program.insert("description".into(), "Generated by `fleet-schema-gen export --format pulumi`".into());
+    // The Terraform provider, bridged on the fly (`pulumi package add`)
+    let mut package = Mapping::new();
+    package.insert("source".into(), "terraform-provider".into());
+    package.insert("parameters".into(), Value::Sequence(vec!["fleetdm/fleetdm".into()]));
+    let mut packages = Mapping::new();
+    packages.insert("fleetdm".into(), Value::Mapping(package));
+    program.insert("packages".into(), Value::Mapping(packages));
+    program.insert("resources".into(), Value::Mapping(entries));
+    Ok(serde_yaml::to_string(&program)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::repo::load_repo;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn sample_repo() -> (TempDir, GitopsRepo) {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(
+            root.join("default.yml"),
+            "policies:\n  - name: Disk encrypted\n    query: SELECT 1 FROM disk_encryption WHERE encrypted = 1;\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("teams/servers.yml"),
+            r#"name: Servers
+policies:
+  - path: ../lib/ssh.yml
+queries:
+  - name: Uptime
+    query: SELECT * FROM uptime;
+    interval: 3600
+    observer_can_run: true
+"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("lib/ssh.yml"),
+            r#"- name: SSH root login disabled
+  query: |
+    SELECT 1 FROM ssh_configs
+    WHERE "${X}" = 'no';
+  platform: linux
+  critical: true
+"#,
+        )
+        .unwrap();
+        fs::write(root.join("teams/no-team.yml"), "name: No team\npolicies:\n  - name: Disk encrypted\n    query: SELECT 1;\n").unwrap();
+
+        let repo = load_repo(root).unwrap();
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("SSH root login (disabled)"), "ssh_root_login_disabled");
+        assert_eq!(slug("macOS 14+"), "macos_14");
+        assert_eq!(slug("1Password"), "_1password");
+        assert_eq!(slug("✓"), "item");
+    }
+
+    #[test]
+    fn test_iac_resources() {
+        let (_temp_dir, repo) = sample_repo();
+        let export = iac_resources(&repo);
+        let ids: Vec<&str> = export.resources.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["servers"]);
+        let skipped: Vec<(&str, &str)> = export.skipped.iter().map(|s| (s.kind, s.name.as_str())).collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("policy", "Disk encrypted"),
+                ("policy", "Disk encrypted"),
+                ("policy", "SSH root login disabled"),
+                ("query", "Uptime"),
+            ]
+        );
+        assert_eq!(export.skipped[3].team, "Servers");
+    }
+
+    #[test]
+    fn test_render_terraform() {
+        let (_temp_dir, repo) = sample_repo();
+        let hcl = render_terraform(&iac_resources(&repo).resources);
+        assert_eq!(hcl, format!("{}\nresource \"fleetdm_teams\" \"servers\" {{\n  name = \"Servers\"\n}}\n", TERRAFORM_HEADER));
+        assert_eq!(
+            hcl_value(&Attr::Text("SELECT 1\nWHERE \"${X}\" = 'no';".to_string())),
+            "<<-EOT\n    SELECT 1\n    WHERE \"$${X}\" = 'no';\n  EOT"
+        );
+        assert_eq!(hcl_string("a \"b\"\n%{c}"), r#""a \"b\"\n%%{c}""#);
+    }
+
+    #[test]
+    fn test_render_pulumi() {
+        let (_temp_dir, repo) = sample_repo();
+        let yaml = render_pulumi(&iac_resources(&repo).resources).unwrap();
+        let program: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(program["runtime"], "yaml");
+        assert_eq!(program["packages"]["fleetdm"]["source"], "terraform-provider");
+        let team = &program["resources"]["servers"];
+        assert_eq!(team["type"], "fleetdm:index:Teams");
+        assert_eq!(team["properties"]["name"], "Servers");
+        assert_eq!(program["resources"].as_mapping().unwrap().len(), 1);
+    }
+}
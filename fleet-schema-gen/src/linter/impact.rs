@@ -0,0 +1,544 @@
+//! Host impact of policy and label changes (`fleet-schema-gen impact`).
+//!
+//! Compares a file with its version at a git ref and asks a Fleet server,
+//! through the hosts API, how many hosts each changed policy targets before
+//! and after the change, per team. For labels the "targeted" hosts are the
+//! ones that evaluate the label query (its platform scope), and the current
+//! member count is shown as well.
+//!
+//! Counts are estimates: hosts matching several `labels_include_any` labels
+//! are counted once per label, and exclusions are subtracted the same way.
+//! Only items defined inline in the file are compared; pass a lib file to
+//! assess the items it defines.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_yaml::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use super::file_role::FileRole;
+
+/// Environment variable with the Fleet server URL.
+pub const SERVER_ENV: &str = "FLEET_URL";
+
+/// Environment variable with the Fleet API token.
+pub const TOKEN_ENV: &str = "FLEET_API_TOKEN";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Policy,
+    Label,
+}
+
+/// Which hosts an item applies to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Targeting {
+    /// Platforms; empty means all.
+    pub platforms: Vec<String>,
+    pub labels_include_any: Vec<String>,
+    pub labels_exclude_any: Vec<String>,
+}
+
+impl Targeting {
+    fn from_item(item: &Value) -> Self {
+        let list = |key: &str| -> Vec<String> {
+            item.get(key)
+                .and_then(|v| v.as_sequence())
+                .map(|seq| seq.iter().filter_map(|v| v.as_str()).map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+        let platforms = item
+            .get("platform")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .split(',')
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        Self { platforms, labels_include_any: list("labels_include_any"), labels_exclude_any: list("labels_exclude_any") }
+    }
+}
+
+/// A policy or label that differs from the base version.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub kind: ItemKind,
+    pub name: String,
+    /// Targeting at the base ref; `None` for new items.
+    pub before: Option<Targeting>,
+    /// Targeting in the working tree; `None` for removed items.
+    pub after: Option<Targeting>,
+    pub query_changed: bool,
+}
+
+impl Change {
+    /// What changed, e.g. "platform darwin -> darwin, windows; query".
+    pub fn describe(&self) -> String {
+        let (before, after) = match (&self.before, &self.after) {
+            (None, _) => return "added".to_string(),
+            (_, None) => return "removed".to_string(),
+            (Some(before), Some(after)) => (before, after),
+        };
+        let show = |values: &[String], empty: &str| if values.is_empty() { empty.to_string() } else { values.join(", ") };
+        let mut parts = Vec::new();
+        for (field, old, new, empty) in [
+            ("platform", &before.platforms, &after.platforms, "(all)"),
+            ("labels_include_any", &before.labels_include_any, &after.labels_include_any, "(none)"),
+            ("labels_exclude_any", &before.labels_exclude_any, &after.labels_exclude_any, "(none)"),
+        ] {
+            if old != new {
+                parts.push(format!("{} {} -> {}", field, show(old, empty), show(new, empty)));
+            }
+        }
+        if self.query_changed {
+            parts.push("query".to_string());
+        }
+        parts.join("; ")
+    }
+}
+
+/// Inline policies and labels of a team file, or of a lib file with `role`.
+fn items(source: &str, role: Option<FileRole>) -> Result<Vec<(ItemKind, Value)>> {
+    let doc: Value = serde_yaml::from_str(source).context("Failed to parse YAML")?;
+    let sections: Vec<(ItemKind, Option<&Value>)> = match role {
+        Some(FileRole::Policies) => vec![(ItemKind::Policy, Some(&doc))],
+        Some(FileRole::Labels) => vec![(ItemKind::Label, Some(&doc))],
+        Some(_) => Vec::new(),
+        None => vec![(ItemKind::Policy, doc.get("policies")), (ItemKind::Label, doc.get("labels"))],
+    };
+
+    Ok(sections
+        .into_iter()
+        .flat_map(|(kind, section)| {
+            section
+                .and_then(|s| s.as_sequence())
+                .into_iter()
+                .flatten()
+                .filter(|item| item.get("path").is_none() && item.get("name").is_some())
+                .map(move |item| (kind, item.clone()))
+        })
+        .collect())
+}
+
+fn normalized_query(item: &Value) -> String {
+    item.get("query")
+        .and_then(|q| q.as_str())
+        .unwrap_or("")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(';')
+        .to_lowercase()
+}
+
+/// Policies and labels that differ between `before` (missing for a new
+/// file) and `after`.
+pub fn changes(before: Option<&str>, after: &str, role: Option<FileRole>) -> Result<Vec<Change>> {
+    let name = |item: &Value| item.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string();
+    let old: HashMap<(ItemKind, String), Value> = match before {
+        Some(before) => items(before, role)?.into_iter().map(|(kind, item)| ((kind, name(&item)), item)).collect(),
+        None => HashMap::new(),
+    };
+    let new = items(after, role)?;
+
+    let mut changes = Vec::new();
+    for (kind, item) in &new {
+        let key = (*kind, name(item));
+        let after = Targeting::from_item(item);
+        let change = match old.get(&key) {
+            None => Change { kind: *kind, name: key.1, before: None, after: Some(after), query_changed: true },
+            Some(previous) => {
+                let before = Targeting::from_item(previous);
+                let query_changed = normalized_query(previous) != normalized_query(item);
+                if before == after && !query_changed {
+                    continue;
+                }
+                Change { kind: *kind, name: key.1, before: Some(before), after: Some(after), query_changed }
+            }
+        };
+        changes.push(change);
+    }
+    for ((kind, item_name), item) in &old {
+        if !new.iter().any(|(k, i)| k == kind && name(i) == *item_name) {
+            changes.push(Change {
+                kind: *kind,
+                name: item_name.clone(),
+                before: Some(Targeting::from_item(item)),
+                after: None,
+                query_changed: true,
+            });
+        }
+    }
+    changes.sort_by(|a, b| (a.kind as u8, &a.name).cmp(&(b.kind as u8, &b.name)));
+    Ok(changes)
+}
+
+/// Content of `file` at a git ref, or `None` if it did not exist there.
+pub fn read_at_ref(file: &Path, git_ref: &str) -> Result<Option<String>> {
+    let dir = file.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = file.file_name().and_then(|n| n.to_str()).context("Invalid file name")?;
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .arg("show")
+        .arg(format!("{}:./{}", git_ref, name))
+        .output()
+        .context("Failed to run git")?;
+    if output.status.success() {
+        return Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()));
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("does not exist") || stderr.contains("exists on disk, but not in") {
+        return Ok(None);
+    }
+    anyhow::bail!("git show {}:{} failed: {}", git_ref, file.display(), stderr.trim())
+}
+
+/// A team to break counts down by. `id` 0 is "No team"; `None` is every host.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TeamScope {
+    pub name: String,
+    pub id: Option<u64>,
+}
+
+/// One host count request: `GET /api/v1/fleet/hosts/count`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CountKey {
+    pub team: Option<u64>,
+    pub platform: Option<String>,
+    pub label: Option<u64>,
+}
+
+/// Counts added and subtracted to estimate a targeting, or `None` if it
+/// uses a label the server does not know (yet).
+fn terms(targeting: &Targeting, team: Option<u64>, label_ids: &HashMap<String, u64>) -> Option<(Vec<CountKey>, Vec<CountKey>)> {
+    let platforms: Vec<Option<String>> = if targeting.platforms.is_empty() {
+        vec![None]
+    } else {
+        targeting.platforms.iter().cloned().map(Some).collect()
+    };
+    let ids = |names: &[String]| names.iter().map(|name| label_ids.get(name).copied()).collect::<Option<Vec<u64>>>();
+    let include = ids(&targeting.labels_include_any)?;
+    let exclude = ids(&targeting.labels_exclude_any)?;
+
+    let key = |platform: &Option<String>, label: Option<u64>| CountKey { team, platform: platform.clone(), label };
+    let mut plus = Vec::new();
+    let mut minus = Vec::new();
+    for platform in &platforms {
+        if include.is_empty() {
+            plus.push(key(platform, None));
+        }
+        plus.extend(include.iter().map(|label| key(platform, Some(*label))));
+        minus.extend(exclude.iter().map(|label| key(platform, Some(*label))));
+    }
+    Some((plus, minus))
+}
+
+/// Count requests needed to assess `changes` for `teams`.
+pub fn count_keys(changes: &[Change], teams: &[TeamScope], label_ids: &HashMap<String, u64>) -> BTreeSet<CountKey> {
+    let mut keys = BTreeSet::new();
+    for change in changes {
+        for team in teams {
+            for targeting in change.before.iter().chain(&change.after) {
+                if let Some((plus, minus)) = terms(targeting, team.id, label_ids) {
+                    keys.extend(plus);
+                    keys.extend(minus);
+                }
+            }
+            if change.kind == ItemKind::Label {
+                if let Some(id) = label_ids.get(&change.name) {
+                    keys.insert(CountKey { team: team.id, platform: None, label: Some(*id) });
+                }
+            }
+        }
+    }
+    keys
+}
+
+/// Hosts of one team affected by a change.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TeamImpact {
+    pub team: String,
+    /// Hosts targeted at the base ref (`None` if unknown).
+    pub before: Option<u64>,
+    /// Hosts targeted after the change (`None` if unknown).
+    pub after: Option<u64>,
+    /// Current members, for labels.
+    pub members: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Impact {
+    pub change: Change,
+    /// Counts overlap between labels and may be overestimated.
+    pub approximate: bool,
+    pub teams: Vec<TeamImpact>,
+}
+
+/// Combine fetched host counts into per-team impacts.
+pub fn assess(
+    changes: &[Change],
+    teams: &[TeamScope],
+    label_ids: &HashMap<String, u64>,
+    counts: &HashMap<CountKey, u64>,
+) -> Vec<Impact> {
+    let estimate = |targeting: Option<&Targeting>, team: Option<u64>| -> Option<u64> {
+        let Some(targeting) = targeting else {
+            return Some(0);
+        };
+        let (plus, minus) = terms(targeting, team, label_ids)?;
+        let sum = |keys: &[CountKey]| keys.iter().map(|key| counts.get(key).copied()).sum::<Option<u64>>();
+        Some(sum(&plus)?.saturating_sub(sum(&minus)?))
+    };
+
+    changes
+        .iter()
+        .map(|change| {
+            let approximate = change
+                .before
+                .iter()
+                .chain(&change.after)
+                .any(|t| t.labels_include_any.len() > 1 || !t.labels_exclude_any.is_empty());
+            let teams = teams
+                .iter()
+                .map(|team| TeamImpact {
+                    team: team.name.clone(),
+                    before: estimate(change.before.as_ref(), team.id),
+                    after: estimate(change.after.as_ref(), team.id),
+                    members: (change.kind == ItemKind::Label)
+                        .then(|| label_ids.get(&change.name))
+                        .flatten()
+                        .and_then(|id| counts.get(&CountKey { team: team.id, platform: None, label: Some(*id) }).copied()),
+                })
+                .collect();
+            Impact { change: change.clone(), approximate, teams }
+        })
+        .collect()
+}
+
+/// Plain-text report.
+pub fn render(impacts: &[Impact]) -> String {
+    let count = |n: Option<u64>| n.map(|n| n.to_string()).unwrap_or_else(|| "?".to_string());
+    let mut out = String::new();
+    for impact in impacts {
+        let change = &impact.change;
+        let kind = match change.kind {
+            ItemKind::Policy => "Policy",
+            ItemKind::Label => "Label",
+        };
+        out.push_str(&format!("{} '{}' ({})\n", kind, change.name, change.describe()));
+        let width = impact.teams.iter().map(|t| t.team.len()).max().unwrap_or(0);
+        for team in &impact.teams {
+            let delta = match (team.before, team.after) {
+                (Some(before), Some(after)) if after != before => format!(" ({:+})", after as i64 - before as i64),
+                _ => String::new(),
+            };
+            let targeted = if change.kind == ItemKind::Label { "hosts evaluate it" } else { "hosts" };
+            out.push_str(&format!(
+                "  {:<width$}  {}{} -> {} {}{}",
+                team.team,
+                if impact.approximate { "~" } else { "" },
+                count(team.before),
+                count(team.after),
+                targeted,
+                delta,
+                width = width
+            ));
+            if let Some(members) = team.members {
+                out.push_str(&format!(", {} members now", members));
+            }
+            out.push('\n');
+        }
+    }
+    if impacts.iter().any(|i| i.teams.iter().any(|t| t.before.is_none() || t.after.is_none())) {
+        out.push_str("? = uses a label the server does not know yet\n");
+    }
+    out
+}
+
+/// Minimal Fleet REST API client.
+pub struct FleetApi {
+    server: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+impl FleetApi {
+    pub fn new(server: &str, token: &str) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent("fleet-schema-gen")
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?;
+        Ok(Self { server: server.trim_end_matches('/').to_string(), token: token.to_string(), client })
+    }
+
+    /// Server and token from arguments or `FLEET_URL`/`FLEET_API_TOKEN`.
+    pub fn from_args(server: Option<String>, token: Option<String>) -> Result<Self> {
+        let server = server
+            .or_else(|| std::env::var(SERVER_ENV).ok())
+            .with_context(|| format!("No Fleet server: pass --server or set {}", SERVER_ENV))?;
+        let token = token
+            .or_else(|| std::env::var(TOKEN_ENV).ok())
+            .with_context(|| format!("No API token: pass --token or set {}", TOKEN_ENV))?;
+        Self::new(&server, &token)
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<serde_json::Value> {
+        let url = format!("{}/api/v1/fleet/{}", self.server, path);
+        self.client
+            .get(&url)
+            .bearer_auth(&self.token)
+            .query(query)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", url))?
+            .error_for_status()
+            .with_context(|| format!("Request to {} failed", url))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid response from {}", url))
+    }
+
+    /// Teams by name.
+    pub async fn teams(&self) -> Result<Vec<TeamScope>> {
+        let body = self.get("teams", &[("per_page", "10000".to_string())]).await?;
+        Ok(body["teams"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|team| Some(TeamScope { name: team["name"].as_str()?.to_string(), id: team["id"].as_u64() }))
+            .collect())
+    }
+
+    /// Label IDs by name.
+    pub async fn label_ids(&self) -> Result<HashMap<String, u64>> {
+        let body = self.get("labels", &[]).await?;
+        Ok(body["labels"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|label| Some((label["name"].as_str()?.to_string(), label["id"].as_u64()?)))
+            .collect())
+    }
+
+    pub async fn host_count(&self, key: &CountKey) -> Result<u64> {
+        let mut query = Vec::new();
+        if let Some(team) = key.team {
+            query.push(("team_id", team.to_string()));
+        }
+        if let Some(platform) = &key.platform {
+            query.push(("platform", platform.clone()));
+        }
+        if let Some(label) = key.label {
+            query.push(("label_id", label.to_string()));
+        }
+        let body = self.get("hosts/count", &query).await?;
+        body["count"].as_u64().context("hosts/count response without a count")
+    }
+}
+
+/// Teams a file's items apply to: the team of a team file, every team for
+/// `default.yml` and labels, or the teams referencing a lib file.
+pub fn file_teams(file: &Path, server_teams: &[TeamScope]) -> Result<Vec<TeamScope>> {
+    let everyone = || {
+        let mut teams = server_teams.to_vec();
+        teams.push(TeamScope { name: "No team".to_string(), id: Some(0) });
+        teams
+    };
+    let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let role = FileRole::detect(file);
+
+    let names: Vec<String> = match (stem, role) {
+        ("default", None) | (_, Some(FileRole::Labels)) => return Ok(everyone()),
+        ("no-team", None) => return Ok(vec![TeamScope { name: "No team".to_string(), id: Some(0) }]),
+        (_, None) => vec![super::repo::load_team(file)?.name],
+        (_, Some(_)) => {
+            let root = super::repo::find_root(file).context("No GitOps repository (default.yml or teams/) above the file")?;
+            let repo = super::repo::load_repo(&root)?;
+            let file = file.canonicalize()?;
+            let referencing: Vec<&super::repo::Team> = repo
+                .teams
+                .iter()
+                .filter(|team| team.policies.iter().any(|p| p.file.canonicalize().is_ok_and(|f| f == file)))
+                .collect();
+            if referencing.iter().any(|team| team.file.file_stem().is_some_and(|s| s == "default")) {
+                return Ok(everyone());
+            }
+            referencing.iter().map(|team| team.name.clone()).collect()
+        }
+    };
+
+    names
+        .into_iter()
+        .map(|name| {
+            server_teams
+                .iter()
+                .find(|team| team.name == name)
+                .cloned()
+                .with_context(|| format!("Team '{}' does not exist on the server", name))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes() {
+        let before = "policies:\n  - name: Disk encryption\n    query: SELECT 1 FROM disk_encryption;\n    platform: darwin\n\
+                      \x20 - name: Firewall\n    query: SELECT 1 FROM alf;\n\
+                      \x20 - name: Gatekeeper\n    query: SELECT 1 FROM gatekeeper;\n";
+        let after = "policies:\n  - name: Disk encryption\n    query: SELECT 1 FROM  disk_encryption\n    platform: windows,darwin\n\
+                     \x20 - name: Gatekeeper\n    query: SELECT 1 FROM gatekeeper;\n\
+                     \x20 - name: Updates\n    query: SELECT 1;\n    labels_include_any: [Macs]\n\
+                     labels:\n  - name: Macs\n    query: SELECT 1 FROM os_version WHERE platform = 'darwin';\n";
+
+        let found = changes(Some(before), after, None).unwrap();
+        let summary: Vec<(&str, String)> = found.iter().map(|c| (c.name.as_str(), c.describe())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Disk encryption", "platform darwin -> darwin, windows".to_string()),
+                ("Firewall", "removed".to_string()),
+                ("Updates", "added".to_string()),
+                ("Macs", "added".to_string()),
+            ]
+        );
+
+        let lib = changes(None, "- name: Macs\n  query: SELECT 1;\n", Some(FileRole::Labels)).unwrap();
+        assert_eq!(lib[0].kind, ItemKind::Label);
+    }
+
+    #[test]
+    fn test_assess() {
+        let change = Change {
+            kind: ItemKind::Policy,
+            name: "Updates".to_string(),
+            before: Some(Targeting { platforms: vec!["darwin".to_string()], ..Default::default() }),
+            after: Some(Targeting {
+                platforms: vec!["darwin".to_string()],
+                labels_exclude_any: vec!["Servers".to_string()],
+                ..Default::default()
+            }),
+            query_changed: false,
+        };
+        let teams = vec![TeamScope { name: "Workstations".to_string(), id: Some(1) }];
+        let label_ids = HashMap::from([("Servers".to_string(), 7)]);
+        let keys = count_keys(std::slice::from_ref(&change), &teams, &label_ids);
+        assert_eq!(keys.len(), 2);
+
+        let key = |label| CountKey { team: Some(1), platform: Some("darwin".to_string()), label };
+        let counts = HashMap::from([(key(None), 120), (key(Some(7)), 20)]);
+        let impacts = assess(&[change], &teams, &label_ids, &counts);
+        assert!(impacts[0].approximate);
+        assert_eq!(impacts[0].teams[0].before, Some(120));
+        assert_eq!(impacts[0].teams[0].after, Some(100));
+        assert_eq!(
+            render(&impacts),
+            "Policy 'Updates' (labels_exclude_any (none) -> Servers)\n  Workstations  ~120 -> 100 hosts (-20)\n"
+        );
+    }
+}
@@ -50,6 +50,34 @@ pub enum StrictnessLevel {
     Relaxed,
 }
 
+impl std::str::FromStr for StrictnessLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "strict" => Ok(StrictnessLevel::Strict),
+            "moderate" => Ok(StrictnessLevel::Moderate),
+            "relaxed" => Ok(StrictnessLevel::Relaxed),
+            _ => anyhow::bail!("Unknown strictness: {} (expected strict, moderate or relaxed)", s),
+        }
+    }
+}
+
+/// Options for [`init`].
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    /// Config path (default: `.fleetlint.toml` in the root).
+    pub output: Option<PathBuf>,
+    /// Prompt for answers not given as flags.
+    pub interactive: bool,
+    /// Overwrite an existing config.
+    pub force: bool,
+    /// Strictness level; skips the prompt when set.
+    pub strictness: Option<StrictnessLevel>,
+    /// Write the config to stdout instead of a file.
+    pub print: bool,
+}
+
 /// Detect Fleet GitOps structure in the given directory.
 pub fn detect_workspace(root: &Path) -> DetectedConfig {
     let mut config = DetectedConfig::default();
@@ -89,35 +117,21 @@ pub fn detect_workspace(root: &Path) -> DetectedConfig {
     // Recursively scan for YAML files and extract info
     scan_yaml_files(root, &mut config);
 
+    // Directory order varies between filesystems; keep generated configs stable
+    config.root_yaml_files.sort();
+    config.detected_platforms.sort();
+
     config
 }
 
-/// Recursively scan for YAML files and extract configuration info.
+/// Scan for YAML files (skipping gitignored ones) and extract configuration info.
 fn scan_yaml_files(dir: &Path, config: &mut DetectedConfig) {
-    let Ok(entries) = fs::read_dir(dir) else {
-        return;
-    };
-
-    for entry in entries.filter_map(|e| e.ok()) {
-        let path = entry.path();
-
-        if path.is_dir() {
-            // Skip common ignore directories
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if name.starts_with('.') || name == "node_modules" || name == "target" || name == "dist" {
-                    continue;
-                }
-            }
-            scan_yaml_files(&path, config);
-        } else if path.is_file() {
-            if let Some(ext) = path.extension() {
-                if ext == "yml" || ext == "yaml" {
-                    config.yaml_file_count += 1;
-                    // Parse file for platform and path references
-                    if let Ok(content) = fs::read_to_string(&path) {
-                        extract_info_from_yaml(&content, config);
-                    }
-                }
+    for path in super::walk::files(dir, &super::walk::WalkOptions::default()) {
+        if super::walk::is_yaml(&path) {
+            config.yaml_file_count += 1;
+            // Parse file for platform and path references
+            if let Ok(content) = fs::read_to_string(&path) {
+                extract_info_from_yaml(&content, config);
             }
         }
     }
@@ -155,26 +169,16 @@ fn extract_info_from_yaml(content: &str, config: &mut DetectedConfig) {
 }
 
 /// Run interactive prompts and return user's answers.
-pub fn prompt_user(detected: &DetectedConfig) -> io::Result<UserAnswers> {
+///
+/// The strictness prompt is skipped when `strictness` is already known.
+pub fn prompt_user(detected: &DetectedConfig, strictness: Option<StrictnessLevel>) -> io::Result<UserAnswers> {
     let mut answers = UserAnswers::default();
 
-    // Strictness level prompt
-    println!("\n{}", "? What strictness level would you like?".bold());
-    println!("  {} - Enforce best practices (require platform, warn on SELECT *)", "1. Strict".cyan());
-    println!("  {} - Balanced defaults (recommended)", "2. Moderate".green());
-    println!("  {} - Minimal warnings", "3. Relaxed".yellow());
-    print!("\n  Enter choice [2]: ");
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let input = input.trim();
-
-    answers.strictness = match input {
-        "1" | "strict" => StrictnessLevel::Strict,
-        "3" | "relaxed" => StrictnessLevel::Relaxed,
-        _ => StrictnessLevel::Moderate,
-    };
+    if let Some(strictness) = strictness {
+        answers.strictness = strictness;
+    } else {
+        prompt_strictness(&mut answers)?;
+    }
 
     // Confirm file inclusion
     if detected.yaml_file_count > 0 {
@@ -194,6 +198,34 @@ pub fn prompt_user(detected: &DetectedConfig) -> io::Result<UserAnswers> {
     Ok(answers)
 }
 
+fn prompt_strictness(answers: &mut UserAnswers) -> io::Result<()> {
+    println!("\n{}", "? What strictness level would you like?".bold());
+    println!("  {} - Enforce best practices (require platform, warn on SELECT *)", "1. Strict".cyan());
+    println!("  {} - Balanced defaults (recommended)", "2. Moderate".green());
+    println!("  {} - Minimal warnings", "3. Relaxed".yellow());
+    print!("\n  Enter choice [2]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+
+    answers.strictness = match input {
+        "1" | "strict" => StrictnessLevel::Strict,
+        "3" | "relaxed" => StrictnessLevel::Relaxed,
+        _ => StrictnessLevel::Moderate,
+    };
+    Ok(())
+}
+
+/// Opt-in rules turned on by the strict preset.
+const STRICT_OPT_IN_RULES: &[&str] = &[
+    "policy-resolution",
+    "critical-policy-platform",
+    "policy-select-star",
+    "policy-description",
+];
+
 /// Generate a FleetLintConfig based on detection and user answers.
 pub fn generate_config(detected: &DetectedConfig, answers: &UserAnswers) -> FleetLintConfig {
     let mut config = FleetLintConfig::default();
@@ -205,6 +237,9 @@ pub fn generate_config(detected: &DetectedConfig, answers: &UserAnswers) -> Flee
             config.thresholds.warn_trailing_semicolon = true;
             config.thresholds.min_interval = 60;
             config.schema.require_platform = true;
+            config.rules.enabled.extend(
+                STRICT_OPT_IN_RULES.iter().map(|r| r.to_string()),
+            );
         }
         StrictnessLevel::Moderate => {
             // Use defaults
@@ -250,6 +285,12 @@ pub fn generate_config_toml(detected: &DetectedConfig, answers: &UserAnswers) ->
     output.push('\n');
 
     // Rules section
+    output.push_str("# Fleet version this repository targets (default: latest)\n");
+    output.push_str("# fleet_version = \"4.58.0\"\n\n");
+    output.push_str("# Built-in preset to start from: fleet-recommended, cis-strict or minimal\n");
+    output.push_str("# preset = \"fleet-recommended\"\n\n");
+    output.push_str("# Shared configs to build on (paths relative to this file, or preset names)\n");
+    output.push_str("# extends = \"../fleet-org/.fleetlint.toml\"\n\n");
     output.push_str("# Rule Configuration\n");
     output.push_str("[rules]\n");
     output.push_str("# Rules to disable entirely (by name)\n");
@@ -261,6 +302,23 @@ pub fn generate_config_toml(detected: &DetectedConfig, answers: &UserAnswers) ->
     output.push_str("#   - interval-validation: Warns about extreme interval values\n");
     output.push_str("#   - duplicate-names: Detects duplicate policy/query/label names\n");
     output.push_str("#   - query-syntax: Validates SQL query syntax\n");
+    output.push_str("#   - sql-dialect: Detects MySQL/Postgres syntax in queries that SQLite doesn't support\n");
+    output.push_str("#   - constant-result: Detects policy queries that always pass or always fail\n");
+    output.push_str("#   - event-tables: Checks queries on evented tables have a time window, join conditions and differential logging\n");
+    output.push_str("#   - benchmark-ids: Validates CIS benchmark IDs in policy annotations\n");
+    output.push_str("#   - osquery-version: Validates min_osquery_version against tables and columns with a tracked introduction version\n");
+    output.push_str("#   - chrome-tables: Validates ChromeOS queries only use tables supported by fleetd for Chrome\n");
+    output.push_str("#   - windows-profiles: Validates SyncML structure, OMA-URIs, and data formats of Windows XML profiles\n");
+    output.push_str("#   - fleet-variables: Validates $FLEET_VAR_* references in profiles and scripts\n");
+    output.push_str("#   - webhook-settings: Validates webhook URLs and required fields of enabled webhooks\n");
+    output.push_str("#   - enroll-secrets: Detects literal, short, or repeated enroll secrets\n");
+    output.push_str("#   - host-settings: Validates host_expiry_settings and features flags\n");
+    output.push_str("#   - deprecated-fields: Detects fields deprecated by the targeted Fleet version\n");
+    output.push_str("#   - contradictory-override: Detects conflicting global/team settings (repository lint)\n");
+    output.push_str("#   - redundant-override: Detects team settings that repeat the global value (repository lint)\n");
+    output.push_str("#   - cross-team-policies: Detects policies duplicated or conflicting across teams (repository lint)\n");
+    output.push_str("#   - calendar-events: Checks calendar-enabled policies have a Google Calendar integration (repository lint)\n");
+    output.push_str("#   - shared-enroll-secrets: Detects enroll secrets shared between teams (repository lint)\n");
 
     match answers.strictness {
         StrictnessLevel::Relaxed => {
@@ -273,6 +331,27 @@ pub fn generate_config_toml(detected: &DetectedConfig, answers: &UserAnswers) ->
 
     output.push_str("\n# Rules to downgrade from error to warning\n");
     output.push_str("warn = []\n");
+    output.push_str("\n# Opt-in rules to enable (off by default)\n");
+    output.push_str("# Available opt-in rules:\n");
+    output.push_str("#   - policy-resolution: Policies should include a resolution\n");
+    output.push_str("#   - critical-policy-platform: Critical policies should target a specific platform\n");
+    output.push_str("#   - policy-select-star: Policy queries should use SELECT 1 rather than SELECT *\n");
+    output.push_str("#   - policy-description: Policies should have a non-empty description\n");
+    output.push_str("#   - common-misspellings: Flags common misspellings in policy descriptions and resolutions\n");
+    output.push_str("#   - localization: Checks translated policy fields exist for every locale\n");
+    output.push_str("#   - prefer-path-references: Team files should reference lib files instead of defining items inline\n");
+    output.push_str("#   - naming-conventions: Team files, policies, queries and lib files follow naming conventions\n");
+    output.push_str("#   - strict-schema: Files match the strict schema of their kind (generated or bundled)\n");
+
+    match answers.strictness {
+        StrictnessLevel::Strict => {
+            let quoted: Vec<String> = STRICT_OPT_IN_RULES.iter().map(|r| format!("\"{}\"", r)).collect();
+            output.push_str(&format!("enabled = [{}]\n", quoted.join(", ")));
+        }
+        _ => {
+            output.push_str("enabled = []\n");
+        }
+    }
     output.push('\n');
 
     // Thresholds section
@@ -313,6 +392,10 @@ pub fn generate_config_toml(detected: &DetectedConfig, answers: &UserAnswers) ->
     output.push_str("    \"**/.git/**\",\n");
     output.push_str("    \"**/dist/**\",\n");
     output.push_str("]\n");
+    output.push_str("\n# Skip files ignored by .gitignore/.ignore (`lint --no-ignore` overrides)\n");
+    output.push_str("gitignore = true\n");
+    output.push_str("\n# Skip files larger than this many bytes (0 = no limit)\n");
+    output.push_str("max_file_size = 1048576\n");
 
     if detected.has_teams_dir {
         output.push_str("\n# Root directory for path resolution\n");
@@ -343,11 +426,24 @@ pub fn generate_config_toml(detected: &DetectedConfig, answers: &UserAnswers) ->
 }
 
 /// Initialize Fleet linter configuration in the given directory.
-pub fn init(root: &Path, output: Option<PathBuf>, interactive: bool, force: bool) -> anyhow::Result<()> {
-    let config_path = output.unwrap_or_else(|| root.join(CONFIG_FILE_NAME));
+///
+/// With `print`, nothing is written or prompted: the config goes to stdout
+/// so scripts can generate it deterministically.
+pub fn init(root: &Path, options: InitOptions) -> anyhow::Result<()> {
+    if options.print {
+        let detected = detect_workspace(root);
+        let answers = UserAnswers {
+            strictness: options.strictness.unwrap_or_default(),
+            include_all_files: true,
+        };
+        print!("{}", generate_config_toml(&detected, &answers));
+        return Ok(());
+    }
+
+    let config_path = options.output.unwrap_or_else(|| root.join(CONFIG_FILE_NAME));
 
     // Check if config already exists
-    if config_path.exists() && !force {
+    if config_path.exists() && !options.force {
         anyhow::bail!(
             "Configuration file already exists: {}\nUse --force to overwrite.",
             config_path.display()
@@ -380,10 +476,13 @@ pub fn init(root: &Path, output: Option<PathBuf>, interactive: bool, force: bool
     }
 
     // Get user answers (interactive or defaults)
-    let answers = if interactive {
-        prompt_user(&detected)?
+    let answers = if options.interactive {
+        prompt_user(&detected, options.strictness)?
     } else {
-        UserAnswers::default()
+        UserAnswers {
+            strictness: options.strictness.unwrap_or_default(),
+            include_all_files: true,
+        }
     };
 
     // Generate config
@@ -510,4 +609,28 @@ mod tests {
         assert!(toml.contains("teams/ directory"));
         assert!(toml.contains("darwin, linux"));
     }
+
+    #[test]
+    fn test_non_interactive_init() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::write(root.join("teams/a.yml"), "policies:\n  - name: a\n    platform: linux\n").unwrap();
+        fs::write(root.join("default.yml"), "policies:\n  - name: w\n    platform: windows\n  - name: d\n    platform: darwin\n").unwrap();
+
+        let options = InitOptions {
+            strictness: Some("strict".parse().unwrap()),
+            ..Default::default()
+        };
+        init(root, options.clone()).unwrap();
+        let written = fs::read_to_string(root.join(CONFIG_FILE_NAME)).unwrap();
+        assert!(written.contains("policy-resolution"));
+        assert!(written.contains("Platforms: darwin, linux, windows"));
+
+        // Existing configs are kept unless forced
+        assert!(init(root, options.clone()).is_err());
+        init(root, InitOptions { force: true, ..options }).unwrap();
+
+        assert!("lenient".parse::<StrictnessLevel>().is_err());
+    }
 }
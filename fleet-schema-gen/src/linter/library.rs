@@ -0,0 +1,495 @@
+//! Vendoring from Fleet's standard query library.
+//!
+//! `library sync` fetches `standard-query-library.yml` from fleetdm/fleet,
+//! selects policies and queries by tag, name or platform (or interactively),
+//! and writes them in GitOps form to `lib/fleet-library/policies.yml` and
+//! `queries.yml`. Each vendored item carries a provenance comment with the
+//! upstream ref and a hash of its definition:
+//!
+//! ```yaml
+//! # upstream: fleetdm/fleet@main sha256:4f2a9c1e0b7d3a58
+//! - name: Full disk encryption enabled (macOS)
+//! ```
+//!
+//! `library check` compares vendored items with upstream and reports items
+//! changed upstream, edited locally, or removed from the library.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The library in the fleetdm/fleet repository.
+pub const LIBRARY_PATH: &str = "docs/01-Using-Fleet/standard-query-library/standard-query-library.yml";
+
+/// Subdirectory of `lib/` vendored items are written to.
+pub const VENDOR_DIR: &str = "fleet-library";
+
+const UPSTREAM_REPO: &str = "fleetdm/fleet";
+
+/// `# upstream: fleetdm/fleet@main sha256:4f2a9c1e0b7d3a58`
+static PROVENANCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#\s*upstream:\s*(\S+)@(\S+)\s+sha256:([0-9a-f]+)\s*$").unwrap());
+
+/// Whether a library item is a policy or a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Policy,
+    Query,
+}
+
+impl ItemKind {
+    /// Vendored file holding items of this kind.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ItemKind::Policy => "policies.yml",
+            ItemKind::Query => "queries.yml",
+        }
+    }
+}
+
+impl std::str::FromStr for ItemKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "policy" | "policies" => Ok(ItemKind::Policy),
+            "query" | "queries" => Ok(ItemKind::Query),
+            _ => bail!("Unknown kind: {} (expected policy or query)", s),
+        }
+    }
+}
+
+/// A policy or query from the library, in GitOps form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryItem {
+    pub kind: ItemKind,
+    pub name: String,
+    pub tags: Vec<String>,
+    pub platforms: Vec<String>,
+    /// The item as written to a lib file (`name`, `description`, `query`, ...).
+    pub definition: serde_yaml::Value,
+}
+
+impl LibraryItem {
+    pub fn hash(&self) -> String {
+        definition_hash(&self.definition)
+    }
+}
+
+/// An item of a vendored lib file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VendoredItem {
+    /// `(repository, ref, hash)` from the provenance comment; `None` for
+    /// items added by hand.
+    pub upstream: Option<(String, String, String)>,
+    pub definition: serde_yaml::Value,
+}
+
+impl VendoredItem {
+    pub fn name(&self) -> Option<&str> {
+        self.definition.get("name").and_then(|n| n.as_str())
+    }
+}
+
+/// How a vendored item differs from upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drift {
+    /// Upstream changed since the item was vendored.
+    UpstreamChanged,
+    /// The vendored copy was edited.
+    ModifiedLocally,
+    /// Both changed.
+    Diverged,
+    /// The item is no longer in the library.
+    RemovedUpstream,
+}
+
+impl std::fmt::Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Drift::UpstreamChanged => "changed upstream",
+            Drift::ModifiedLocally => "modified locally",
+            Drift::Diverged => "changed upstream and modified locally",
+            Drift::RemovedUpstream => "removed upstream",
+        })
+    }
+}
+
+/// A drifted item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftReport {
+    pub file: PathBuf,
+    pub name: String,
+    pub drift: Drift,
+}
+
+/// Items added, updated and kept by a sync.
+#[derive(Debug, Default)]
+pub struct SyncSummary {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub files: Vec<PathBuf>,
+}
+
+/// Filters for `library sync`.
+#[derive(Debug, Default)]
+pub struct Selection {
+    pub tags: Vec<String>,
+    pub names: Vec<String>,
+    pub platform: Option<String>,
+    pub kind: Option<ItemKind>,
+}
+
+impl Selection {
+    /// Whether any filter besides `kind` narrows the selection.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.names.is_empty() && self.platform.is_none()
+    }
+
+    pub fn matches(&self, item: &LibraryItem) -> bool {
+        let any_tag = self.tags.is_empty()
+            || self.tags.iter().any(|tag| item.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+        let any_name = self.names.is_empty() || self.names.iter().any(|name| item.name.eq_ignore_ascii_case(name));
+        let platform = self.platform.as_ref().is_none_or(|p| item.platforms.iter().any(|ip| ip == p));
+        let kind = self.kind.is_none_or(|kind| kind == item.kind);
+        any_tag && any_name && platform && kind
+    }
+}
+
+pub fn library_url(git_ref: &str) -> String {
+    format!("https://raw.githubusercontent.com/{}/{}/{}", UPSTREAM_REPO, git_ref, LIBRARY_PATH)
+}
+
+/// Fetch and parse the library at a branch or tag.
+pub async fn fetch(git_ref: &str) -> Result<Vec<LibraryItem>> {
+    let content = crate::sources::github_api::GitHubClient::new()?
+        .get_text(&library_url(git_ref))
+        .await?;
+    parse(&content)
+}
+
+#[derive(Deserialize)]
+struct LibraryDocument {
+    kind: String,
+    spec: serde_yaml::Mapping,
+}
+
+/// Parse the multi-document library YAML (`kind: policy` / `kind: query`).
+pub fn parse(content: &str) -> Result<Vec<LibraryItem>> {
+    let mut items = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(content) {
+        let value = serde_yaml::Value::deserialize(document).context("Failed to parse query library")?;
+        if value.is_null() {
+            continue;
+        }
+        let document: LibraryDocument = serde_yaml::from_value(value).context("Unexpected query library document")?;
+        let kind = match document.kind.as_str() {
+            "policy" => ItemKind::Policy,
+            "query" => ItemKind::Query,
+            _ => continue,
+        };
+
+        let spec = |key: &str| document.spec.get(key).and_then(|v| v.as_str()).map(str::trim);
+        let Some(name) = spec("name") else {
+            continue;
+        };
+        let list = |key: &str| -> Vec<String> {
+            spec(key)
+                .map(|s| s.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect())
+                .unwrap_or_default()
+        };
+        let platforms = list("platform");
+
+        let mut definition = serde_yaml::Mapping::new();
+        definition.insert("name".into(), name.into());
+        let fields: &[&str] = match kind {
+            ItemKind::Policy => &["description", "resolution", "query"],
+            ItemKind::Query => &["description", "query"],
+        };
+        if !platforms.is_empty() {
+            definition.insert("platform".into(), platforms.join(",").into());
+        }
+        for field in fields {
+            if let Some(value) = spec(field).filter(|v| !v.is_empty()) {
+                definition.insert((*field).into(), value.into());
+            }
+        }
+
+        items.push(LibraryItem {
+            kind,
+            name: name.to_string(),
+            tags: list("tags"),
+            platforms,
+            definition: serde_yaml::Value::Mapping(definition),
+        });
+    }
+    Ok(items)
+}
+
+/// Let the user pick items by number (`1,3,5-7` or `all`).
+pub fn prompt(items: &[&LibraryItem]) -> io::Result<Vec<usize>> {
+    for (i, item) in items.iter().enumerate() {
+        let kind = match item.kind {
+            ItemKind::Policy => "policy",
+            ItemKind::Query => "query",
+        };
+        println!(
+            "  {:>3}. [{}] {} {}",
+            i + 1,
+            kind.cyan(),
+            item.name,
+            format!("({}; {})", item.platforms.join(","), item.tags.join(", ")).dimmed()
+        );
+    }
+    print!("\n  Items to vendor (e.g. 1,3,5-7 or all): ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(parse_choice(input.trim(), items.len()))
+}
+
+/// Zero-based indices for `1,3,5-7`; out-of-range numbers are ignored.
+fn parse_choice(input: &str, count: usize) -> Vec<usize> {
+    if input.eq_ignore_ascii_case("all") {
+        return (0..count).collect();
+    }
+    let mut chosen = Vec::new();
+    for part in input.split(',').map(str::trim) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim().parse::<usize>(), end.trim().parse::<usize>()),
+            None => (part.parse(), part.parse()),
+        };
+        let (Ok(start), Ok(end)) = (start, end) else {
+            continue;
+        };
+        for n in start.max(1)..=end.min(count) {
+            if !chosen.contains(&(n - 1)) {
+                chosen.push(n - 1);
+            }
+        }
+    }
+    chosen
+}
+
+/// Read a vendored lib file with its provenance comments.
+pub fn read_vendored(path: &Path) -> Result<Vec<VendoredItem>> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let definitions: Option<Vec<serde_yaml::Value>> =
+        serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    // Items start at column 0 with "- "; a provenance comment right above belongs to them
+    let mut upstreams = Vec::new();
+    let mut pending = None;
+    for line in content.lines() {
+        if let Some(captures) = PROVENANCE.captures(line) {
+            pending = Some((captures[1].to_string(), captures[2].to_string(), captures[3].to_string()));
+        } else if line.starts_with("- ") || line == "-" {
+            upstreams.push(pending.take());
+        } else if !line.trim().is_empty() && !line.starts_with(' ') && !line.starts_with('#') {
+            pending = None;
+        }
+    }
+
+    Ok(definitions
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(i, definition)| VendoredItem { upstream: upstreams.get(i).cloned().flatten(), definition })
+        .collect())
+}
+
+/// A vendored lib file.
+pub fn render(items: &[VendoredItem]) -> Result<String> {
+    let mut out = String::from(
+        "# Vendored from Fleet's standard query library by `fleet-schema-gen library sync`.\n\
+         # `fleet-schema-gen library check` reports items that drift from upstream.\n",
+    );
+    for item in items {
+        out.push('\n');
+        if let Some((repo, git_ref, hash)) = &item.upstream {
+            out.push_str(&format!("# upstream: {}@{} sha256:{}\n", repo, git_ref, hash));
+        }
+        out.push_str(&serde_yaml::to_string(&vec![&item.definition])?);
+    }
+    Ok(out)
+}
+
+/// Vendor `items` into `lib_dir/fleet-library/`, replacing earlier copies
+/// of the same items and keeping the rest.
+pub fn sync(lib_dir: &Path, items: &[&LibraryItem], git_ref: &str) -> Result<SyncSummary> {
+    let mut summary = SyncSummary::default();
+    for kind in [ItemKind::Policy, ItemKind::Query] {
+        let selected: Vec<&&LibraryItem> = items.iter().filter(|item| item.kind == kind).collect();
+        if selected.is_empty() {
+            continue;
+        }
+
+        let path = lib_dir.join(VENDOR_DIR).join(kind.file_name());
+        let mut vendored = if path.exists() { read_vendored(&path)? } else { Vec::new() };
+        for item in selected {
+            let new = VendoredItem {
+                upstream: Some((UPSTREAM_REPO.to_string(), git_ref.to_string(), item.hash())),
+                definition: item.definition.clone(),
+            };
+            match vendored.iter_mut().find(|v| v.name() == Some(item.name.as_str())) {
+                Some(existing) if *existing == new => summary.unchanged.push(item.name.clone()),
+                Some(existing) => {
+                    *existing = new;
+                    summary.updated.push(item.name.clone());
+                }
+                None => {
+                    vendored.push(new);
+                    summary.added.push(item.name.clone());
+                }
+            }
+        }
+
+        fs::create_dir_all(path.parent().unwrap_or(lib_dir))?;
+        fs::write(&path, render(&vendored)?)?;
+        summary.files.push(path);
+    }
+    Ok(summary)
+}
+
+/// Compare vendored items in `lib_dir/fleet-library/` with upstream.
+pub fn check(lib_dir: &Path, upstream: &[LibraryItem]) -> Result<Vec<DriftReport>> {
+    let mut reports = Vec::new();
+    for kind in [ItemKind::Policy, ItemKind::Query] {
+        let path = lib_dir.join(VENDOR_DIR).join(kind.file_name());
+        if !path.exists() {
+            continue;
+        }
+        for item in read_vendored(&path)? {
+            let (Some((_, _, recorded)), Some(name)) = (&item.upstream, item.name()) else {
+                continue;
+            };
+            let local_changed = definition_hash(&item.definition) != *recorded;
+            let current = upstream.iter().find(|u| u.kind == kind && u.name == name);
+            let drift = match current.map(|u| u.hash() != *recorded) {
+                None => Some(Drift::RemovedUpstream),
+                Some(true) if local_changed => Some(Drift::Diverged),
+                Some(true) => Some(Drift::UpstreamChanged),
+                Some(false) if local_changed => Some(Drift::ModifiedLocally),
+                Some(false) => None,
+            };
+            if let Some(drift) = drift {
+                reports.push(DriftReport { file: path.clone(), name: name.to_string(), drift });
+            }
+        }
+    }
+    Ok(reports)
+}
+
+/// Short SHA-256 of an item definition.
+fn definition_hash(definition: &serde_yaml::Value) -> String {
+    let json = serde_json::to_string(definition).unwrap_or_default();
+    Sha256::digest(json.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY: &str = "\
+---
+apiVersion: v1
+kind: query
+spec:
+  name: Get OpenSSL versions
+  platform: linux
+  description: Retrieves the OpenSSL version.
+  query: SELECT name AS name, version AS version FROM deb_packages WHERE name LIKE 'openssl%';
+  purpose: Informational
+  tags: inventory
+  contributors: zwass
+---
+apiVersion: v1
+kind: policy
+spec:
+  name: Full disk encryption enabled (macOS)
+  query: SELECT 1 FROM disk_encryption WHERE user_uuid IS NOT '' AND filevault_status = 'on' LIMIT 1;
+  description: Checks to make sure that full disk encryption (FileVault) is enabled on macOS devices.
+  resolution: To enable full disk encryption, on the failing device, select System Preferences > Security & Privacy > FileVault > Turn On FileVault.
+  tags: compliance, hardening, built-in
+  platform: darwin
+---
+apiVersion: v1
+kind: policy
+spec:
+  name: Antivirus healthy (Windows)
+  query: SELECT 1 FROM windows_security_center WHERE antivirus = 'Good';
+  tags: compliance, malware
+  platform: windows
+";
+
+    #[test]
+    fn test_parse_library() {
+        let items = parse(LIBRARY).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].kind, ItemKind::Query);
+        assert_eq!(items[1].tags, vec!["compliance", "hardening", "built-in"]);
+
+        let keys: Vec<&str> = items[1].definition.as_mapping().unwrap().keys().filter_map(|k| k.as_str()).collect();
+        assert_eq!(keys, vec!["name", "platform", "description", "resolution", "query"]);
+
+        let selection = Selection { tags: vec!["Compliance".to_string()], platform: Some("windows".to_string()), ..Default::default() };
+        let selected: Vec<&str> = items.iter().filter(|i| selection.matches(i)).map(|i| i.name.as_str()).collect();
+        assert_eq!(selected, vec!["Antivirus healthy (Windows)"]);
+    }
+
+    #[test]
+    fn test_parse_choice() {
+        assert_eq!(parse_choice("1, 3-4, 9", 5), vec![0, 2, 3]);
+        assert_eq!(parse_choice("all", 2), vec![0, 1]);
+        assert!(parse_choice("", 3).is_empty());
+    }
+
+    #[test]
+    fn test_sync_and_check_drift() {
+        let lib = tempfile::tempdir().unwrap();
+        let items = parse(LIBRARY).unwrap();
+        let policies: Vec<&LibraryItem> = items.iter().filter(|i| i.kind == ItemKind::Policy).collect();
+
+        let summary = sync(lib.path(), &policies, "main").unwrap();
+        assert_eq!(summary.added.len(), 2);
+        assert_eq!(summary.files, vec![lib.path().join("fleet-library/policies.yml")]);
+        assert!(check(lib.path(), &items).unwrap().is_empty());
+
+        // The vendored file is a valid policies lib file
+        let path = lib.path().join("fleet-library/policies.yml");
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains(&format!("# upstream: fleetdm/fleet@main sha256:{}\n- name: Full disk", items[1].hash())));
+        let report = crate::linter::Linter::new().lint_content(&content, &path).unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+        // Local edit, upstream change and removal
+        fs::write(&path, content.replace("antivirus = 'Good';", "antivirus = 'Good' AND 1;")).unwrap();
+        let mut upstream = items.clone();
+        upstream[1].definition["description"] = "Checks FileVault.".into();
+        let reports = check(lib.path(), &upstream).unwrap();
+        let drift: Vec<(&str, Drift)> = reports.iter().map(|r| (r.name.as_str(), r.drift)).collect();
+        assert_eq!(
+            drift,
+            vec![("Full disk encryption enabled (macOS)", Drift::UpstreamChanged), ("Antivirus healthy (Windows)", Drift::ModifiedLocally)]
+        );
+        let reports = check(lib.path(), &upstream[..2]).unwrap();
+        assert_eq!(reports[1].drift, Drift::RemovedUpstream);
+
+        // Re-syncing replaces the vendored copy
+        let summary = sync(lib.path(), &policies, "main").unwrap();
+        assert_eq!((summary.updated.len(), summary.unchanged.len()), (1, 1));
+        assert!(check(lib.path(), &items).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+//! Opt-in, local-only usage metrics.
+//!
+//! With `[metrics] enabled = true` in `.fleetlint.toml`, the linter counts
+//! the findings of each rule and the LSP records completion latency. Counts
+//! go to a JSON file on this machine (by default
+//! `~/.cache/fleet-schema-gen/metrics.json`) and are never sent anywhere;
+//! `fleet-schema-gen metrics report` prints them so platform teams can see
+//! which rules fire most and tune their configuration.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use super::config::MetricsConfig;
+use super::error::{LintError, Severity};
+
+/// Metrics file name in the user cache directory.
+pub const METRICS_FILE: &str = "metrics.json";
+
+/// Completion latencies kept (the most recent ones).
+const MAX_LATENCY_SAMPLES: usize = 1000;
+
+/// Long-running processes (the LSP) write at most this often.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Findings of one rule, by severity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleCounts {
+    pub errors: u64,
+    pub warnings: u64,
+    pub infos: u64,
+}
+
+impl RuleCounts {
+    pub fn total(&self) -> u64 {
+        self.errors + self.warnings + self.infos
+    }
+}
+
+/// Contents of the metrics file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Metrics {
+    /// When recording started (RFC 3339).
+    pub since: Option<String>,
+    /// Files linted.
+    pub files_linted: u64,
+    /// Findings per rule name.
+    pub rules: BTreeMap<String, RuleCounts>,
+    /// Recent completion latencies in microseconds, oldest first.
+    pub completion_latency_us: Vec<u64>,
+}
+
+impl Metrics {
+    /// Read a metrics file; a missing file is empty.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Add the counts recorded in `other`.
+    pub fn merge(&mut self, other: &Metrics) {
+        if self.since.is_none() {
+            self.since = other.since.clone();
+        }
+        self.files_linted += other.files_linted;
+        for (rule, counts) in &other.rules {
+            let total = self.rules.entry(rule.clone()).or_default();
+            total.errors += counts.errors;
+            total.warnings += counts.warnings;
+            total.infos += counts.infos;
+        }
+        self.completion_latency_us.extend(&other.completion_latency_us);
+        let excess = self.completion_latency_us.len().saturating_sub(MAX_LATENCY_SAMPLES);
+        self.completion_latency_us.drain(..excess);
+    }
+
+    /// Rules by number of findings, most first.
+    pub fn top_rules(&self) -> Vec<(&str, &RuleCounts)> {
+        let mut rules: Vec<(&str, &RuleCounts)> = self.rules.iter().map(|(name, counts)| (name.as_str(), counts)).collect();
+        rules.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then(a.0.cmp(b.0)));
+        rules
+    }
+
+    /// Nearest-rank percentile (0-100) of the completion latencies.
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut samples = self.completion_latency_us.clone();
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        let rank = ((percentile / 100.0) * samples.len() as f64).ceil() as usize;
+        Some(Duration::from_micros(samples[rank.clamp(1, samples.len()) - 1]))
+    }
+
+    /// Plain-text report.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        if let Some(since) = &self.since {
+            out.push_str(&format!("Recording since {}\n", since));
+        }
+        out.push_str(&format!("Files linted: {}\n", self.files_linted));
+
+        out.push_str("\nRules by findings:\n");
+        if self.rules.is_empty() {
+            out.push_str("  (none)\n");
+        }
+        for (name, counts) in self.top_rules() {
+            out.push_str(&format!(
+                "  {:<28} {:>7}  ({} errors, {} warnings, {} info)\n",
+                name,
+                counts.total(),
+                counts.errors,
+                counts.warnings,
+                counts.infos
+            ));
+        }
+
+        out.push_str(&format!("\nCompletion latency ({} samples):\n", self.completion_latency_us.len()));
+        match (self.latency_percentile(50.0), self.latency_percentile(90.0), self.latency_percentile(99.0)) {
+            (Some(p50), Some(p90), Some(p99)) => out.push_str(&format!(
+                "  p50 {:.1} ms, p90 {:.1} ms, p99 {:.1} ms\n",
+                p50.as_secs_f64() * 1000.0,
+                p90.as_secs_f64() * 1000.0,
+                p99.as_secs_f64() * 1000.0
+            )),
+            _ => out.push_str("  (none)\n"),
+        }
+        out
+    }
+}
+
+/// Default metrics file in the user cache directory.
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("fleet-schema-gen").join(METRICS_FILE))
+}
+
+/// Metrics file for a configuration, whether or not recording is enabled.
+pub fn path_for(config: &MetricsConfig) -> Option<PathBuf> {
+    config.file.as_ref().map(PathBuf::from).or_else(default_path)
+}
+
+/// Records metrics in memory and adds them to the metrics file on flush.
+#[derive(Debug)]
+pub struct Recorder {
+    path: PathBuf,
+    state: Mutex<(Metrics, Instant)>,
+}
+
+impl Recorder {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, state: Mutex::new((Metrics::default(), Instant::now())) }
+    }
+
+    /// Recorder for a configuration, if metrics are enabled.
+    pub fn from_config(config: &MetricsConfig) -> Option<Self> {
+        config.enabled.then(|| path_for(config)).flatten().map(Self::new)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Count a linted file.
+    pub fn record_file(&self) {
+        self.update(|metrics| metrics.files_linted += 1);
+    }
+
+    /// Count the findings of a rule.
+    pub fn record_findings(&self, rule: &str, findings: &[LintError]) {
+        if findings.is_empty() {
+            return;
+        }
+        self.update(|metrics| {
+            let counts = metrics.rules.entry(rule.to_string()).or_default();
+            for finding in findings {
+                match finding.severity {
+                    Severity::Error => counts.errors += 1,
+                    Severity::Warning => counts.warnings += 1,
+                    Severity::Info => counts.infos += 1,
+                }
+            }
+        });
+    }
+
+    pub fn record_completion(&self, latency: Duration) {
+        self.update(|metrics| metrics.completion_latency_us.push(latency.as_micros() as u64));
+    }
+
+    fn update(&self, record: impl FnOnce(&mut Metrics)) {
+        let due = {
+            let Ok(mut state) = self.state.lock() else {
+                return;
+            };
+            record(&mut state.0);
+            state.1.elapsed() >= FLUSH_INTERVAL
+        };
+        if due {
+            self.flush();
+        }
+    }
+
+    /// Add what was recorded to the metrics file. Failures are logged: metrics
+    /// never fail a lint run.
+    pub fn flush(&self) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.1 = Instant::now();
+        if state.0 == Metrics::default() {
+            return;
+        }
+        let pending = std::mem::take(&mut state.0);
+
+        let result = Metrics::load(&self.path).and_then(|mut metrics| {
+            if metrics.since.is_none() {
+                metrics.since = Some(chrono::Utc::now().to_rfc3339());
+            }
+            metrics.merge(&pending);
+            metrics.save(&self.path)
+        });
+        if let Err(e) = result {
+            tracing::debug!("Failed to record metrics: {:#}", e);
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder_accumulates_in_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(METRICS_FILE);
+
+        for _ in 0..2 {
+            let recorder = Recorder::new(path.clone());
+            recorder.record_file();
+            recorder.record_findings("policy-resolution", &[
+                LintError::warning("no resolution", "a.yml"),
+                LintError::error("no resolution", "a.yml"),
+            ]);
+            recorder.record_findings("query-syntax", &[]);
+            recorder.record_completion(Duration::from_millis(3));
+        }
+
+        let metrics = Metrics::load(&path).unwrap();
+        assert!(metrics.since.is_some());
+        assert_eq!(metrics.files_linted, 2);
+        assert_eq!(metrics.rules["policy-resolution"], RuleCounts { errors: 2, warnings: 2, infos: 0 });
+        assert!(!metrics.rules.contains_key("query-syntax"));
+        assert_eq!(metrics.completion_latency_us, vec![3000, 3000]);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut metrics = Metrics { files_linted: 12, ..Default::default() };
+        metrics.rules.insert("duplicate-names".to_string(), RuleCounts { errors: 1, ..Default::default() });
+        metrics.rules.insert("policy-resolution".to_string(), RuleCounts { warnings: 7, ..Default::default() });
+        metrics.completion_latency_us = (1..=100).map(|ms| ms * 1000).collect();
+
+        assert_eq!(metrics.latency_percentile(50.0), Some(Duration::from_millis(50)));
+        assert_eq!(metrics.latency_percentile(99.0), Some(Duration::from_millis(99)));
+        assert_eq!(metrics.top_rules()[0].0, "policy-resolution");
+
+        let report = metrics.render();
+        assert!(report.contains("Files linted: 12\n"));
+        assert!(report.contains("p50 50.0 ms, p90 90.0 ms, p99 99.0 ms"));
+        assert!(report.find("policy-resolution").unwrap() < report.find("duplicate-names").unwrap());
+        assert!(Metrics::default().render().contains("(none)"));
+    }
+
+    #[test]
+    fn test_latency_samples_are_bounded() {
+        let mut metrics = Metrics::default();
+        metrics.merge(&Metrics { completion_latency_us: vec![1; MAX_LATENCY_SAMPLES], ..Default::default() });
+        metrics.merge(&Metrics { completion_latency_us: vec![2; 10], ..Default::default() });
+        assert_eq!(metrics.completion_latency_us.len(), MAX_LATENCY_SAMPLES);
+        assert_eq!(metrics.completion_latency_us.last(), Some(&2));
+    }
+}
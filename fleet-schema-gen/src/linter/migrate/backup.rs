@@ -1,54 +1,115 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use crate::linter::config::BackupRetention;
+
+/// Directory holding migration backups, relative to the migrated directory
+pub const BACKUPS_DIR: &str = ".fleet-migrate/backups";
+
+/// Manifest file inside each backup directory
+pub const MANIFEST_FILE: &str = "manifest.json";
 
 /// Backup of files before migration
 #[derive(Debug, Clone)]
 pub struct Backup {
+    pub id: String,
     pub timestamp: DateTime<Utc>,
     pub backup_dir: PathBuf,
     pub files: HashMap<PathBuf, String>, // Original path -> content
 }
 
+/// What a backup contains (`manifest.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub id: String,
+    pub created: DateTime<Utc>,
+    pub files: Vec<BackedUpFile>,
+}
+
+/// One file in a backup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackedUpFile {
+    /// Original path, relative to the backed-up directory when inside it
+    pub path: PathBuf,
+    /// Name of the copy in the backup's `files/` directory
+    pub stored: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl BackupManifest {
+    pub fn size_bytes(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+fn sha256(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Path of `file` relative to `base_dir`, or as given when outside it
+fn relative_to(file: &Path, base_dir: &Path) -> PathBuf {
+    match (file.canonicalize(), base_dir.canonicalize()) {
+        (Ok(file_abs), Ok(base_abs)) => file_abs
+            .strip_prefix(&base_abs)
+            .map(Path::to_path_buf)
+            .unwrap_or(file_abs),
+        _ => file.to_path_buf(),
+    }
+}
+
 impl Backup {
-    /// Create a new backup of the given files
+    /// Create a new backup of the given files under `<base_dir>/.fleet-migrate/backups/<id>/`
     pub fn create(files: &[PathBuf], base_dir: &Path) -> Result<Self> {
         let timestamp = Utc::now();
-        let backup_dir = base_dir.join(format!(
-            ".fleet-migration-backup-{}",
-            timestamp.format("%Y%m%d-%H%M%S")
-        ));
+        let backups_dir = base_dir.join(BACKUPS_DIR);
+        let stamp = timestamp.format("%Y%m%d-%H%M%S").to_string();
+        let id = (0..)
+            .map(|n| if n == 0 { stamp.clone() } else { format!("{}-{}", stamp, n) })
+            .find(|id| !backups_dir.join(id).exists())
+            .unwrap_or(stamp);
+        let backup_dir = backups_dir.join(&id);
+        let files_dir = backup_dir.join("files");
 
-        fs::create_dir_all(&backup_dir)
+        fs::create_dir_all(&files_dir)
             .with_context(|| format!("Failed to create backup directory: {}", backup_dir.display()))?;
 
         let mut file_contents = HashMap::new();
+        let mut manifest = BackupManifest { id: id.clone(), created: timestamp, files: Vec::new() };
 
         for file in files {
             if file.exists() {
                 let content = fs::read_to_string(file)
                     .with_context(|| format!("Failed to read {}", file.display()))?;
 
-                file_contents.insert(file.clone(), content.clone());
+                // Numbered copies: files from different directories share names
+                let file_name = file.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+                let stored = format!("{:04}-{}", manifest.files.len() + 1, file_name);
+                let backup_file = files_dir.join(&stored);
+                fs::write(&backup_file, &content)
+                    .with_context(|| format!("Failed to write backup: {}", backup_file.display()))?;
 
-                // Copy to backup directory
-                if let Some(filename) = file.file_name() {
-                    let backup_file = backup_dir.join(filename);
-                    fs::write(&backup_file, &content)
-                        .with_context(|| format!("Failed to write backup: {}", backup_file.display()))?;
-                }
+                manifest.files.push(BackedUpFile {
+                    path: relative_to(file, base_dir),
+                    stored,
+                    sha256: sha256(content.as_bytes()),
+                    size: content.len() as u64,
+                });
+                file_contents.insert(file.clone(), content);
             }
         }
 
-        println!(
-            "{} Created backup at: {}",
-            "✓".to_string(),
-            backup_dir.display()
-        );
+        fs::write(backup_dir.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)
+            .with_context(|| format!("Failed to write backup manifest in {}", backup_dir.display()))?;
+
+        tracing::info!("Created backup at: {}", backup_dir.display());
 
         Ok(Self {
+            id,
             timestamp,
             backup_dir,
             files: file_contents,
@@ -62,7 +123,7 @@ impl Backup {
                 .with_context(|| format!("Failed to restore {}", path.display()))?;
         }
 
-        println!("{} Restored {} file(s) from backup", "✓", self.files.len());
+        tracing::info!("Restored {} file(s) from backup", self.files.len());
 
         Ok(())
     }
@@ -80,6 +141,79 @@ impl Backup {
     pub fn size_bytes(&self) -> usize {
         self.files.values().map(|c| c.len()).sum()
     }
+
+    /// Backups under `base_dir`, newest first
+    pub fn list(base_dir: &Path) -> Result<Vec<BackupManifest>> {
+        let backups_dir = base_dir.join(BACKUPS_DIR);
+        if !backups_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut manifests = Vec::new();
+        for entry in fs::read_dir(&backups_dir)?.flatten() {
+            let manifest_path = entry.path().join(MANIFEST_FILE);
+            let Ok(content) = fs::read_to_string(&manifest_path) else {
+                continue;
+            };
+            match serde_json::from_str::<BackupManifest>(&content) {
+                Ok(manifest) => manifests.push(manifest),
+                Err(e) => tracing::warn!("Skipping {}: {}", manifest_path.display(), e),
+            }
+        }
+        manifests.sort_by(|a, b| b.created.cmp(&a.created).then(b.id.cmp(&a.id)));
+        Ok(manifests)
+    }
+
+    /// Restore the backup `id` under `base_dir`, after checking every copy
+    /// against its recorded hash. Returns the restored paths.
+    pub fn restore_id(base_dir: &Path, id: &str) -> Result<Vec<PathBuf>> {
+        let backup_dir = base_dir.join(BACKUPS_DIR).join(id);
+        let manifest: BackupManifest = serde_json::from_str(
+            &fs::read_to_string(backup_dir.join(MANIFEST_FILE))
+                .with_context(|| format!("No backup '{}' in {}", id, base_dir.join(BACKUPS_DIR).display()))?,
+        )
+        .with_context(|| format!("Invalid manifest for backup '{}'", id))?;
+
+        // Verify everything before touching any file
+        let mut contents = Vec::new();
+        for file in &manifest.files {
+            let stored = backup_dir.join("files").join(&file.stored);
+            let content = fs::read(&stored).with_context(|| format!("Failed to read {}", stored.display()))?;
+            if sha256(&content) != file.sha256 {
+                anyhow::bail!("Backup '{}' is corrupt: {} does not match its hash", id, file.path.display());
+            }
+            contents.push((base_dir.join(&file.path), content));
+        }
+
+        let mut restored = Vec::new();
+        for (path, content) in contents {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, content).with_context(|| format!("Failed to restore {}", path.display()))?;
+            restored.push(path);
+        }
+
+        tracing::info!("Restored {} file(s) from backup {}", restored.len(), id);
+        Ok(restored)
+    }
+
+    /// Delete backups beyond the retention limits; returns the deleted IDs
+    pub fn prune(base_dir: &Path, retention: &BackupRetention, now: DateTime<Utc>) -> Result<Vec<String>> {
+        let mut deleted = Vec::new();
+        for (idx, manifest) in Self::list(base_dir)?.into_iter().enumerate() {
+            let too_many = retention.keep.is_some_and(|keep| idx >= keep);
+            let too_old = retention
+                .max_age_days
+                .is_some_and(|days| now - manifest.created > chrono::Duration::days(days as i64));
+            if too_many || too_old {
+                let dir = base_dir.join(BACKUPS_DIR).join(&manifest.id);
+                fs::remove_dir_all(&dir).with_context(|| format!("Failed to delete backup: {}", dir.display()))?;
+                deleted.push(manifest.id);
+            }
+        }
+        Ok(deleted)
+    }
 }
 
 #[cfg(test)]
@@ -120,4 +254,47 @@ mod tests {
         let content = fs::read_to_string(&test_file).unwrap();
         assert_eq!(content, "original");
     }
+
+    #[test]
+    fn test_manifest_and_restore_by_id() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("teams")).unwrap();
+        let files = [temp.path().join("default.yml"), temp.path().join("teams/default.yml")];
+        fs::write(&files[0], "global").unwrap();
+        fs::write(&files[1], "team").unwrap();
+
+        let backup = Backup::create(&files, temp.path()).unwrap();
+        let manifests = Backup::list(temp.path()).unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].id, backup.id);
+        assert_eq!(manifests[0].files[1].path, PathBuf::from("teams/default.yml"));
+        assert_eq!(manifests[0].size_bytes(), 10);
+
+        fs::write(&files[1], "migrated").unwrap();
+        let restored = Backup::restore_id(temp.path(), &backup.id).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(fs::read_to_string(&files[1]).unwrap(), "team");
+
+        // A tampered copy is refused
+        let stored = backup.backup_dir.join("files").join(&manifests[0].files[0].stored);
+        fs::write(stored, "tampered").unwrap();
+        assert!(Backup::restore_id(temp.path(), &backup.id).is_err());
+        assert_eq!(fs::read_to_string(&files[0]).unwrap(), "global");
+    }
+
+    #[test]
+    fn test_prune() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("default.yml");
+        fs::write(&file, "x").unwrap();
+        let ids: Vec<String> = (0..3).map(|_| Backup::create(std::slice::from_ref(&file), temp.path()).unwrap().id).collect();
+
+        let keep_two = BackupRetention { keep: Some(2), max_age_days: None };
+        assert_eq!(Backup::prune(temp.path(), &keep_two, Utc::now()).unwrap(), vec![ids[0].clone()]);
+
+        let max_age = BackupRetention { keep: None, max_age_days: Some(7) };
+        let later = Utc::now() + chrono::Duration::days(8);
+        assert_eq!(Backup::prune(temp.path(), &max_age, later).unwrap().len(), 2);
+        assert!(Backup::list(temp.path()).unwrap().is_empty());
+    }
 }
@@ -2,6 +2,46 @@ use colored::*;
 use similar::{ChangeTag, TextDiff};
 use std::fmt;
 
+/// Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Width of side-by-side diffs when the terminal width is unknown
+pub const DEFAULT_WIDTH: usize = 120;
+
+/// How file diffs are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffStyle {
+    /// Unified diff with hunk headers
+    #[default]
+    Unified,
+    /// Old and new side by side in columns of half the given width
+    SideBySide(usize),
+}
+
+impl DiffStyle {
+    /// Side-by-side at the terminal width (`COLUMNS`), else unified
+    pub fn from_flag(side_by_side: bool) -> Self {
+        if !side_by_side {
+            return Self::Unified;
+        }
+        let width = std::env::var("COLUMNS")
+            .ok()
+            .and_then(|c| c.parse().ok())
+            .unwrap_or(DEFAULT_WIDTH);
+        Self::SideBySide(width)
+    }
+}
+
+/// `text` cut or padded to exactly `width` characters
+fn pad(text: &str, width: usize) -> String {
+    if text.chars().count() > width {
+        let cut: String = text.chars().take(width.saturating_sub(3)).collect();
+        format!("{}...", cut)
+    } else {
+        format!("{:<width$}", text, width = width)
+    }
+}
+
 /// Represents a diff between two versions of a file
 #[derive(Debug, Clone)]
 pub struct FileDiff {
@@ -35,61 +75,33 @@ impl FileDiff {
         }
     }
 
-    /// Generate a unified diff output
+    /// Generate a unified diff output: hunks of changes with their context lines
     pub fn unified_diff(&self) -> String {
         let diff = TextDiff::from_lines(&self.old_content, &self.new_content);
         let mut output = String::new();
 
         // Header
-        output.push_str(&format!("--- {}\n", self.path.dimmed()));
-        output.push_str(&format!("+++ {}\n", self.path.dimmed()));
-
-        // Generate unified diff using iter_all_changes
-        let mut in_hunk = false;
-        let mut hunk_lines: Vec<String> = Vec::new();
-
-        for (idx, change) in diff.iter_all_changes().enumerate() {
-            let line = format!("{}", change);
-            let formatted = match change.tag() {
-                ChangeTag::Delete => {
-                    if !in_hunk {
-                        in_hunk = true;
-                        hunk_lines.clear();
-                    }
-                    format!("-{}", line).red().to_string()
-                }
-                ChangeTag::Insert => {
-                    if !in_hunk {
-                        in_hunk = true;
-                        hunk_lines.clear();
-                    }
-                    format!("+{}", line).green().to_string()
-                }
-                ChangeTag::Equal => {
-                    if in_hunk && hunk_lines.len() > 0 {
-                        // End of hunk, print accumulated lines
-                        for hunk_line in &hunk_lines {
-                            output.push_str(hunk_line);
-                        }
-                        hunk_lines.clear();
-                        in_hunk = false;
-                    }
-                    format!(" {}", line)
-                }
-            };
-
-            if in_hunk {
-                hunk_lines.push(formatted);
-            } else {
+        output.push_str(&format!("{}\n", format!("--- a/{}", self.path).bold()));
+        output.push_str(&format!("{}\n", format!("+++ b/{}", self.path).bold()));
+
+        for hunk in diff.unified_diff().context_radius(CONTEXT_LINES).iter_hunks() {
+            output.push_str(&format!("{}\n", hunk.header().to_string().cyan()));
+            for change in hunk.iter_changes() {
+                let line = change.to_string_lossy();
+                let line = line.trim_end_matches('\n');
+                let formatted = match change.tag() {
+                    ChangeTag::Delete => format!("-{}", line).red().to_string(),
+                    ChangeTag::Insert => format!("+{}", line).green().to_string(),
+                    ChangeTag::Equal => format!(" {}", line),
+                };
                 output.push_str(&formatted);
+                output.push('\n');
+                if change.missing_newline() {
+                    output.push_str(&format!("{}\n", "\\ No newline at end of file".dimmed()));
+                }
             }
         }
 
-        // Print any remaining hunk lines
-        for hunk_line in &hunk_lines {
-            output.push_str(hunk_line);
-        }
-
         output
     }
 
@@ -97,17 +109,16 @@ impl FileDiff {
     pub fn side_by_side(&self, width: usize) -> String {
         let diff = TextDiff::from_lines(&self.old_content, &self.new_content);
         let mut output = String::new();
-        let col_width = width / 2 - 2;
+        let col_width = (width / 2).saturating_sub(2).max(8);
 
         output.push_str(&format!(
-            "{:<width$} | {}\n",
-            "OLD".bold(),
-            "NEW".bold(),
-            width = col_width
+            "{} | {}\n",
+            pad("OLD", col_width).bold(),
+            "NEW".bold()
         ));
         output.push_str(&format!("{}\n", "=".repeat(width)));
 
-        for (idx, group) in diff.grouped_ops(0).iter().enumerate() {
+        for (idx, group) in diff.grouped_ops(CONTEXT_LINES).iter().enumerate() {
             if idx > 0 {
                 output.push_str(&format!("{}\n", "-".repeat(width).dimmed()));
             }
@@ -115,18 +126,14 @@ impl FileDiff {
             for op in group {
                 for change in diff.iter_changes(op) {
                     let line = change.to_string().trim_end().to_string();
-                    let truncated = if line.len() > col_width {
-                        format!("{}...", &line[..col_width - 3])
-                    } else {
-                        format!("{:<width$}", line, width = col_width)
-                    };
+                    let truncated = pad(&line, col_width);
 
                     match change.tag() {
                         ChangeTag::Delete => {
                             output.push_str(&format!("{} | \n", truncated.red()));
                         }
                         ChangeTag::Insert => {
-                            output.push_str(&format!("{:<width$} | {}\n", "", truncated.green(), width = col_width));
+                            output.push_str(&format!("{} | {}\n", pad("", col_width), truncated.green()));
                         }
                         ChangeTag::Equal => {
                             output.push_str(&format!("{} | {}\n", truncated.dimmed(), truncated.dimmed()));
@@ -139,6 +146,11 @@ impl FileDiff {
         output
     }
 
+    /// Whether the file changes at all
+    pub fn has_changes(&self) -> bool {
+        self.additions + self.deletions > 0
+    }
+
     /// Generate a compact summary
     pub fn summary(&self) -> String {
         let total_changes = self.additions + self.deletions;
@@ -202,25 +214,33 @@ impl DiffSet {
         );
     }
 
-    /// Print full unified diffs
-    pub fn print_unified(&self) {
-        for (idx, diff) in self.diffs.iter().enumerate() {
-            if idx > 0 {
-                println!("\n{}\n", "=".repeat(80).dimmed());
+    /// Diffs of the files that change, in the given style
+    pub fn render(&self, style: DiffStyle) -> String {
+        let mut output = String::new();
+        for diff in self.diffs.iter().filter(|d| d.has_changes()) {
+            match style {
+                DiffStyle::Unified => {
+                    output.push_str(&diff.unified_diff());
+                    output.push('\n');
+                }
+                DiffStyle::SideBySide(width) => {
+                    output.push_str(&format!("{}\n\n", diff.path.bold().underline()));
+                    output.push_str(&diff.side_by_side(width));
+                    output.push('\n');
+                }
             }
-            println!("{}", diff.unified_diff());
         }
+        output
+    }
+
+    /// Print full unified diffs
+    pub fn print_unified(&self) {
+        print!("{}", self.render(DiffStyle::Unified));
     }
 
     /// Print side-by-side diffs
     pub fn print_side_by_side(&self, width: usize) {
-        for (idx, diff) in self.diffs.iter().enumerate() {
-            if idx > 0 {
-                println!("\n{}\n", "=".repeat(width).dimmed());
-            }
-            println!("\n{}\n", diff.path.bold().underline());
-            println!("{}", diff.side_by_side(width));
-        }
+        print!("{}", self.render(DiffStyle::SideBySide(width)));
     }
 }
 
@@ -269,6 +289,29 @@ mod tests {
         assert!(set.total_additions() > 0);
     }
 
+    #[test]
+    fn test_unified_diff_hunks() {
+        colored::control::set_override(false);
+        let old: String = (1..=20).map(|n| format!("line{}\n", n)).collect();
+        let new = old.replace("line10\n", "line10 modified\n");
+        let diff = FileDiff::new("default.yml".to_string(), old, new);
+
+        let unified = diff.unified_diff();
+        assert!(unified.starts_with("--- a/default.yml\n+++ b/default.yml\n@@ -7,7 +7,7 @@\n line7\n"));
+        assert!(unified.contains("\n-line10\n+line10 modified\n line11\n"));
+        assert!(!unified.contains("line3\n"));
+
+        let side = diff.side_by_side(40);
+        assert!(side.contains(&format!("{} | \n", pad("line10", 18))));
+        assert!(side.contains(&format!("{} | {}\n", " ".repeat(18), pad("line10 modified", 18))));
+
+        let mut set = DiffSet::new();
+        set.add(diff);
+        set.add(FileDiff::new("same.yml".to_string(), "a\n".to_string(), "a\n".to_string()));
+        assert!(!set.render(DiffStyle::Unified).contains("same.yml"));
+        assert!(set.render(DiffStyle::SideBySide(40)).starts_with("default.yml\n\nOLD"));
+    }
+
     #[test]
     fn test_no_changes() {
         let content = "unchanged\n";
@@ -1,18 +1,50 @@
 use super::backup::Backup;
-use super::diff::{DiffSet, FileDiff};
+use super::diff::{DiffSet, DiffStyle, FileDiff};
+use super::error::MigrationError;
 use super::resolver::PathResolver;
-use super::transformations::{apply_changes, execute_field_delete, execute_field_move, execute_field_rename};
-use super::types::{FileChange, Migration, MigrationPlan, MigrationStep, Transformation, Version};
+use super::transformations::{apply_changes_to, document_changes, execute_field_delete, execute_field_move, execute_field_rename};
+use super::validate::{pattern_matches, relative_yaml_files, validate_migrations, MigrationIssue};
+use super::types::{DocumentMigration, FailureMode, FileChange, FileFailure, Migration, MigrationReport, MigrationPlan, MigrationStep, Transformation, Version};
 use anyhow::{Context, Result};
 use colored::*;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Original and migrated content of a file after applying its steps
+fn migrate_file(file: &Path, steps: &[&MigrationStep]) -> Result<(String, String)> {
+    let original = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let mut migrated = original.clone();
+    for step in steps {
+        tracing::info!("{}", step.description);
+        migrated = apply_changes_to(file, &migrated, &step.changes)?;
+    }
+    Ok((original, migrated))
+}
+
+fn print_failures(failures: &[FileFailure]) {
+    if failures.is_empty() {
+        return;
+    }
+    println!("\n{} {} file(s) failed:", "✗".red().bold(), failures.len());
+    for failure in failures {
+        println!("  {}: {}", failure.file.display().to_string().bold(), failure.error);
+    }
+}
 
 /// Migration execution engine
 pub struct MigrationEngine {
     migrations: Vec<Migration>,
     resolver: PathResolver,
+    diff_style: DiffStyle,
+    /// Problems found while loading the migrations
+    load_issues: Vec<MigrationIssue>,
+    failure_mode: FailureMode,
+    /// Files migrated concurrently
+    jobs: usize,
 }
 
 impl MigrationEngine {
@@ -20,21 +52,54 @@ impl MigrationEngine {
         Self {
             migrations: Vec::new(),
             resolver: PathResolver::new(),
+            diff_style: DiffStyle::default(),
+            load_issues: Vec::new(),
+            failure_mode: FailureMode::default(),
+            jobs: 1,
         }
     }
 
     /// Load migrations from a list
     pub fn load_migrations(&mut self, migrations: Vec<Migration>) {
         self.migrations = migrations;
+        self.load_issues.clear();
+    }
+
+    /// How dry runs show the changes
+    pub fn set_diff_style(&mut self, style: DiffStyle) {
+        self.diff_style = style;
+    }
+
+    /// Whether a failing file aborts the run or is reported at the end
+    pub fn set_failure_mode(&mut self, mode: FailureMode) {
+        self.failure_mode = mode;
+    }
+
+    /// Number of files migrated concurrently (at least 1)
+    pub fn set_jobs(&mut self, jobs: usize) {
+        self.jobs = jobs.max(1);
     }
 
     /// Load migrations from a TOML file
-    pub fn load_migrations_from_file(&mut self, path: &Path) -> Result<()> {
-        let migrations = super::loader::load_migrations_from_file(path)?;
+    pub fn load_migrations_from_file(&mut self, path: &Path) -> Result<(), MigrationError> {
+        let definitions = |source| MigrationError::Definitions { path: path.to_path_buf(), source };
+        let content = fs::read_to_string(path).map_err(|e| definitions(e.into()))?;
+        let (migrations, issues) =
+            super::loader::load_migrations_with_issues(&content).map_err(|e| definitions(e.into()))?;
         self.migrations = migrations;
+        self.load_issues = issues;
         Ok(())
     }
 
+    /// Problems with the loaded migrations; with `root`, also patterns that
+    /// match no YAML file under it
+    pub fn validate(&self, root: Option<&Path>) -> Vec<MigrationIssue> {
+        let files = root.map(relative_yaml_files);
+        let mut issues = self.load_issues.clone();
+        issues.extend(validate_migrations(&self.migrations, files.as_deref()));
+        issues
+    }
+
     /// Get the latest supported version
     pub fn latest_version(&self) -> Version {
         self.migrations
@@ -46,36 +111,29 @@ impl MigrationEngine {
     }
 
     /// Create a migration plan
-    pub fn plan(&mut self, path: &Path, from: &Version, to: &Version) -> Result<MigrationPlan> {
-        println!("{} Creating migration plan from {} to {}...",
-            "→".blue().bold(),
-            from.to_string().cyan(),
-            to.to_string().cyan()
-        );
+    pub fn plan(&mut self, path: &Path, from: &Version, to: &Version) -> Result<MigrationPlan, MigrationError> {
+        tracing::info!("Creating migration plan from {} to {}...", from, to);
 
         // Find applicable migrations
         let applicable = self.find_migrations_between(from, to);
 
         if applicable.is_empty() {
-            return Err(anyhow::anyhow!(
-                "No migration path found from {} to {}",
-                from,
-                to
-            ));
+            return Err(MigrationError::NoMigrationPath { from: from.clone(), to: to.clone() });
         }
 
-        println!("{} Found {} migration(s):", "✓".green(), applicable.len());
+        tracing::info!("Found {} migration(s)", applicable.len());
         for migration in &applicable {
-            println!("  - {} ({})", migration.id.bold(), migration.description);
+            tracing::info!(id = %migration.id, "  - {} ({})", migration.id, migration.description);
         }
 
         // Build list of affected files
         let affected_files = self.find_affected_files(path, &applicable)?;
 
-        println!("{} Will affect {} file(s)", "→".blue(), affected_files.len());
+        tracing::info!("Will affect {} file(s)", affected_files.len());
 
         // Generate migration steps
-        let steps = self.generate_steps(path, &applicable, &affected_files)?;
+        let mut failures = Vec::new();
+        let steps = self.generate_steps(path, &applicable, &affected_files, &mut failures)?;
 
         let estimated_changes: usize = steps.iter().map(|s| s.changes.len()).sum();
 
@@ -84,80 +142,195 @@ impl MigrationEngine {
             affected_files,
             steps,
             estimated_changes,
+            failures,
         })
     }
 
-    /// Execute a migration plan
-    pub fn execute(&mut self, plan: &MigrationPlan, dry_run: bool) -> Result<()> {
+    /// Execute a migration plan. Each file is migrated on its own, so a
+    /// malformed file fails alone; with fail-fast nothing is written when
+    /// any file fails, with keep-going the others are written and the
+    /// failures reported.
+    pub fn execute(&mut self, plan: &MigrationPlan, dry_run: bool) -> Result<MigrationReport, MigrationError> {
         if dry_run {
             println!("{} DRY RUN MODE - No files will be modified\n", "ℹ".blue().bold());
         }
 
-        // Create backup if not dry run
-        let backup = if !dry_run {
-            println!("{} Creating backup...", "→".blue().bold());
-            Some(Backup::create(&plan.affected_files, Path::new("."))?)
-        } else {
-            None
-        };
+        let mut report = MigrationReport { failed: plan.failures.clone(), ..Default::default() };
+        if self.failure_mode == FailureMode::FailFast && !report.failed.is_empty() {
+            print_failures(&report.failed);
+            return Err(MigrationError::PlanningFailed(report.failed));
+        }
 
-        // Track diffs
-        let mut diff_set = DiffSet::new();
+        // Steps per file, in order of first change
+        let mut files: Vec<(&Path, Vec<&MigrationStep>)> = Vec::new();
+        for step in &plan.steps {
+            match files.iter_mut().find(|(file, _)| *file == step.file.as_path()) {
+                Some((_, steps)) => steps.push(step),
+                None => files.push((step.file.as_path(), vec![step])),
+            }
+        }
 
-        // Execute each step
-        for (idx, step) in plan.steps.iter().enumerate() {
-            println!("\n{} Step {}/{}: {}",
-                "→".blue().bold(),
-                idx + 1,
-                plan.steps.len(),
-                step.description.bold()
-            );
-
-            // Read original content
-            let original_content = if step.file.exists() {
-                fs::read_to_string(&step.file)?
-            } else {
-                String::new()
-            };
+        let results = self.migrate_files(&files);
 
-            // Apply changes
-            let new_content = apply_changes(&step.file, &step.changes)?;
+        // (file, original, migrated) of the files that migrated
+        let mut contents: Vec<(PathBuf, String, String)> = Vec::new();
+        for ((file, _), result) in files.iter().zip(results) {
+            match result {
+                Some(Ok((original, migrated))) => contents.push((file.to_path_buf(), original, migrated)),
+                Some(Err(e)) => report.failed.push(FileFailure { file: file.to_path_buf(), error: format!("{:#}", e) }),
+                // Not attempted after a failure (fail-fast)
+                None => {}
+            }
+        }
 
-            // Create diff
-            let diff = FileDiff::new(
-                step.file.display().to_string(),
-                original_content.clone(),
-                new_content.clone(),
-            );
+        if self.failure_mode == FailureMode::FailFast && !report.failed.is_empty() {
+            print_failures(&report.failed);
+            return Err(MigrationError::FilesFailed(report.failed));
+        }
 
-            println!("  {}", diff.summary());
+        // Track diffs
+        let mut diff_set = DiffSet::new();
+        for (file, original, migrated) in &contents {
+            let diff = FileDiff::new(file.display().to_string(), original.clone(), migrated.clone());
+            tracing::debug!("  {}", diff.summary());
             diff_set.add(diff);
+        }
+        let changed: Vec<&(PathBuf, String, String)> = contents.iter().filter(|(_, original, migrated)| original != migrated).collect();
 
-            // Write if not dry run
-            if !dry_run {
-                fs::write(&step.file, &new_content)
-                    .with_context(|| format!("Failed to write {}", step.file.display()))?;
+        let mut backup = None;
+        if dry_run {
+            print!("{}", diff_set.render(self.diff_style));
+            report.migrated = changed.iter().map(|(file, _, _)| file.clone()).collect();
+        } else if !changed.is_empty() {
+            tracing::info!("Creating backup...");
+            let to_back_up: Vec<PathBuf> = changed.iter().map(|(file, _, _)| file.clone()).collect();
+            backup = Some(Backup::create(&to_back_up, Path::new(".")).map_err(|e| MigrationError::Backup(e.into()))?);
+
+            for (file, _, migrated) in changed {
+                match fs::write(file, migrated) {
+                    Ok(()) => report.migrated.push(file.clone()),
+                    Err(e) => report.failed.push(FileFailure { file: file.clone(), error: format!("Failed to write: {}", e) }),
+                }
             }
         }
 
         // Show diff summary
         println!("\n{}", "=".repeat(60));
         diff_set.print_summary();
+        print_failures(&report.failed);
 
         if dry_run {
             println!("\n{} This was a dry run. No files were modified.", "ℹ".blue().bold());
         } else {
-            println!("\n{} Migration completed successfully!", "✓".green().bold());
+            if report.failed.is_empty() {
+                println!("\n{} Migration completed successfully!", "✓".green().bold());
+            } else {
+                println!(
+                    "\n{} Migrated {} file(s); {} file(s) failed",
+                    "⚠".yellow().bold(),
+                    report.migrated.len(),
+                    report.failed.len()
+                );
+            }
 
             if let Some(backup) = backup {
-                println!("{} Backup saved at: {}",
-                    "ℹ".blue(),
-                    backup.backup_dir.display().to_string().dimmed()
-                );
+                tracing::info!("Backup saved at: {}", backup.backup_dir.display());
             }
         }
 
-        Ok(())
+        Ok(report)
+    }
+
+    /// Apply each file's steps in memory, on up to `jobs` threads. Results
+    /// are in the order of `files`; `None` for files skipped after a
+    /// failure in fail-fast mode.
+    #[allow(clippy::type_complexity)]
+    fn migrate_files(&self, files: &[(&Path, Vec<&MigrationStep>)]) -> Vec<Option<Result<(String, String)>>> {
+        let next = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+        let results: Mutex<Vec<Option<Result<(String, String)>>>> = Mutex::new((0..files.len()).map(|_| None).collect());
+        let fail_fast = self.failure_mode == FailureMode::FailFast;
+
+        let work = || loop {
+            let idx = next.fetch_add(1, Ordering::SeqCst);
+            if idx >= files.len() || stop.load(Ordering::SeqCst) {
+                break;
+            }
+            let (file, steps) = &files[idx];
+            let result = migrate_file(file, steps);
+            if result.is_err() && fail_fast {
+                stop.store(true, Ordering::SeqCst);
+            }
+            if let Ok(mut results) = results.lock() {
+                results[idx] = Some(result);
+            }
+        };
+
+        let jobs = self.jobs.clamp(1, files.len().max(1));
+        if jobs == 1 {
+            work();
+        } else {
+            std::thread::scope(|scope| {
+                for _ in 0..jobs {
+                    scope.spawn(work);
+                }
+            });
+        }
+
+        results.into_inner().unwrap_or_default()
+    }
+
+    /// Migrate one YAML document in memory, without touching any file.
+    /// With a `file_name` (relative to the repository root), only
+    /// transformations whose pattern matches it apply.
+    pub fn migrate_document(
+        &self,
+        content: &str,
+        file_name: Option<&str>,
+        from: &Version,
+        to: &Version,
+    ) -> Result<DocumentMigration, MigrationError> {
+        let applicable = self.find_migrations_between(from, to);
+        if applicable.is_empty() {
+            return Err(MigrationError::NoMigrationPath { from: from.clone(), to: to.clone() });
+        }
+
+        let label = Path::new(file_name.unwrap_or("<stdin>"));
+        let mut migrated = DocumentMigration { content: content.to_string(), changes: 0, skipped: Vec::new() };
+
+        for migration in &applicable {
+            for transformation in &migration.transformations {
+                let pattern = match transformation {
+                    Transformation::FieldRename { pattern, .. } | Transformation::FieldDelete { pattern, .. } => Some(pattern),
+                    _ => None,
+                };
+                if let (Some(pattern), Some(file_name)) = (pattern, file_name) {
+                    if !pattern_matches(pattern, file_name) {
+                        continue;
+                    }
+                }
+
+                let yaml: serde_yaml::Value = serde_yaml::from_str(&migrated.content)
+                    .map_err(|source| MigrationError::Parse { file: label.to_path_buf(), source })?;
+                match document_changes(transformation, &yaml) {
+                    Some(changes) if changes.is_empty() => {}
+                    Some(changes) => {
+                        migrated.content = apply_changes_to(label, &migrated.content, &changes)?;
+                        migrated.changes += changes.len();
+                    }
+                    None => migrated.skipped.push(format!(
+                        "{}: {} needs the whole repository; skipped",
+                        migration.id,
+                        match transformation {
+                            Transformation::FieldMove { .. } => "field_move",
+                            _ => "restructure",
+                        }
+                    )),
+                }
+            }
+        }
+
+        Ok(migrated)
     }
 
     // Helper methods
@@ -165,7 +338,7 @@ impl MigrationEngine {
     fn find_migrations_between(&self, from: &Version, to: &Version) -> Vec<Migration> {
         self.migrations
             .iter()
-            .filter(|m| &m.from_version >= from && &m.to_version <= to)
+            .filter(|m| m.applies_from(from) && &m.to_version <= to)
             .cloned()
             .collect()
     }
@@ -277,9 +450,17 @@ impl MigrationEngine {
         _root: &Path,
         migrations: &[Migration],
         affected_files: &[PathBuf],
+        failures: &mut Vec<FileFailure>,
     ) -> Result<Vec<MigrationStep>> {
         let mut steps = Vec::new();
 
+        // A file that can't be read or parsed is reported once, not per transformation
+        let mut record = |file: &Path, e: &anyhow::Error| {
+            if !failures.iter().any(|f| f.file == file) {
+                failures.push(FileFailure { file: file.to_path_buf(), error: format!("{:#}", e) });
+            }
+        };
+
         for migration in migrations {
             for transformation in &migration.transformations {
                 match transformation {
@@ -296,11 +477,19 @@ impl MigrationEngine {
                             for file in affected_files {
                                 if self.matches_pattern(file, target_pattern) {
                                     // Find referenced software files
-                                    if let Ok(referenced) = self.resolver.find_referenced_files(file) {
+                                    let referenced = self.resolver.find_referenced_files(file);
+                                    if let Err(e) = &referenced {
+                                        record(file, e);
+                                    }
+                                    if let Ok(referenced) = referenced {
                                         for software_file in referenced {
                                             if self.matches_pattern(&software_file, source_pattern) {
                                                 // Load source file and extract fields
-                                                if let Ok(source_yaml) = self.resolver.load_file(&software_file) {
+                                                let source_yaml = self.resolver.load_file(&software_file);
+                                                if let Err(e) = &source_yaml {
+                                                    record(&software_file, e);
+                                                }
+                                                if let Ok(source_yaml) = source_yaml {
                                                     let mut source_changes = Vec::new();
                                                     let mut target_changes = Vec::new();
 
@@ -352,36 +541,37 @@ impl MigrationEngine {
                     }
                     Transformation::FieldRename { .. } => {
                         for file in affected_files {
-                            if let Ok(changes) = execute_field_rename(transformation, file, &mut self.resolver) {
-                                if !changes.is_empty() {
+                            match execute_field_rename(transformation, file, &mut self.resolver) {
+                                Err(e) => record(file, &e),
+                                Ok(changes) if !changes.is_empty() => {
                                     steps.push(MigrationStep {
                                         description: format!("Rename fields in {}", file.display()),
                                         file: file.clone(),
                                         changes,
                                     });
                                 }
+                                Ok(_) => {}
                             }
                         }
                     }
                     Transformation::FieldDelete { .. } => {
                         for file in affected_files {
-                            if let Ok(changes) = execute_field_delete(transformation, file, &mut self.resolver) {
-                                if !changes.is_empty() {
+                            match execute_field_delete(transformation, file, &mut self.resolver) {
+                                Err(e) => record(file, &e),
+                                Ok(changes) if !changes.is_empty() => {
                                     steps.push(MigrationStep {
                                         description: format!("Delete deprecated fields in {}", file.display()),
                                         file: file.clone(),
                                         changes,
                                     });
                                 }
+                                Ok(_) => {}
                             }
                         }
                     }
                     Transformation::Restructure { name, description } => {
                         // Custom restructuring logic would go here
-                        println!("{} Restructure transformation '{}' not yet implemented",
-                            "⚠".yellow(),
-                            name
-                        );
+                        tracing::warn!("Restructure transformation '{}' not yet implemented", name);
                     }
                 }
             }
@@ -397,3 +587,92 @@ impl Default for MigrationEngine {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_document() {
+        let mut engine = MigrationEngine::new();
+        engine.load_migrations(super::super::loader::load_migrations_from_str(r#"
+[[migration]]
+id = "rename"
+from_version = "4.29.0"
+to_version = "4.30.0"
+description = "Rename"
+
+[[migration.transformations]]
+type = "field_rename"
+pattern = "teams/*.yml"
+old_path = "enable_disk_encryption"
+new_path = "macos_settings.enable_disk_encryption"
+
+[[migration.transformations]]
+type = "restructure"
+name = "split"
+description = "Split"
+"#).unwrap());
+
+        let (from, to) = (Version::new(4, 29, 0), Version::new(4, 30, 0));
+        let source = "name: Workstations\nenable_disk_encryption: true\n";
+
+        let migrated = engine.migrate_document(source, None, &from, &to).unwrap();
+        assert_eq!(migrated.content, "name: Workstations\nmacos_settings:\n  enable_disk_encryption: true\n");
+        assert_eq!(migrated.changes, 1);
+        assert_eq!(migrated.skipped, vec!["rename: restructure needs the whole repository; skipped"]);
+
+        // The file name selects the transformations by pattern
+        let untouched = engine.migrate_document(source, Some("default.yml"), &from, &to).unwrap();
+        assert_eq!(untouched.content, source);
+        assert_eq!(untouched.changes, 0);
+
+        assert!(matches!(
+            engine.migrate_document("a: [", None, &from, &to),
+            Err(MigrationError::Parse { .. })
+        ));
+        assert!(matches!(
+            engine.migrate_document(source, None, &to, &Version::new(4, 31, 0)),
+            Err(MigrationError::NoMigrationPath { .. })
+        ));
+    }
+
+    #[test]
+    fn test_failing_file_is_isolated() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("teams")).unwrap();
+        for n in 0..4 {
+            std::fs::write(dir.path().join(format!("teams/t{}.yml", n)), "name: T\nold: 1\n").unwrap();
+        }
+        std::fs::write(dir.path().join("teams/broken.yml"), "name: [unclosed\n").unwrap();
+
+        let mut engine = MigrationEngine::new();
+        engine.load_migrations(super::super::loader::load_migrations_from_str(r#"
+[[migration]]
+id = "delete"
+from_version = "4.50.0"
+to_version = "4.51.0"
+description = "Delete"
+
+[[migration.transformations]]
+type = "field_delete"
+pattern = "teams/*.yml"
+fields = ["old"]
+"#).unwrap());
+        engine.set_jobs(3);
+
+        let plan = engine.plan(dir.path(), &Version::new(4, 50, 0), &Version::new(4, 51, 0)).unwrap();
+        assert_eq!(plan.failures.len(), 1);
+        assert!(plan.failures[0].file.ends_with("teams/broken.yml"));
+
+        // Fail-fast (default) refuses to run
+        let err = engine.execute(&plan, true).unwrap_err();
+        assert!(matches!(err, MigrationError::PlanningFailed(_)));
+        assert_eq!(err.failures().len(), 1);
+
+        engine.set_failure_mode(FailureMode::KeepGoing);
+        let report = engine.execute(&plan, true).unwrap();
+        assert_eq!(report.migrated.len(), 4);
+        assert_eq!(report.failed.len(), 1);
+    }
+}
@@ -0,0 +1,115 @@
+//! Errors of the migration API.
+//!
+//! [`MigrationError`] lets callers tell a missing migration path or an
+//! aborted run apart from IO problems without matching on messages.
+
+use super::types::{FileFailure, Version};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Why a migration could not be planned or run
+#[derive(Debug)]
+pub enum MigrationError {
+    /// A version string is not a Fleet version
+    InvalidVersion(String),
+    /// The latest Fleet release could not be looked up
+    LatestRelease(BoxError),
+    /// A configuration file could not be read to detect its version
+    Detection { path: PathBuf, source: BoxError },
+    /// The Fleet version of a configuration could not be detected
+    UnknownVersion(PathBuf),
+    /// No loaded migration leads from one version to the other
+    NoMigrationPath { from: Version, to: Version },
+    /// A migrations file could not be read or parsed
+    Definitions { path: PathBuf, source: BoxError },
+    /// A document is not valid YAML
+    Parse { file: PathBuf, source: serde_yaml::Error },
+    /// Files could not be planned (fail-fast); nothing was written
+    PlanningFailed(Vec<FileFailure>),
+    /// Files failed to migrate (fail-fast); nothing was written
+    FilesFailed(Vec<FileFailure>),
+    /// The backup taken before writing failed; nothing was written
+    Backup(BoxError),
+    /// Any other failure reading or transforming files
+    Other(BoxError),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::InvalidVersion(version) => write!(f, "Invalid version format: {}", version),
+            MigrationError::LatestRelease(_) => write!(f, "Failed to resolve 'latest' from the Fleet GitHub releases"),
+            MigrationError::Detection { path, .. } => write!(f, "Failed to detect the Fleet version of {}", path.display()),
+            MigrationError::UnknownVersion(path) => write!(f, "Could not detect Fleet version of {}", path.display()),
+            MigrationError::NoMigrationPath { from, to } => write!(f, "No migration path found from {} to {}", from, to),
+            MigrationError::Definitions { path, .. } => write!(f, "Invalid migrations file: {}", path.display()),
+            MigrationError::Parse { file, .. } => write!(f, "Failed to parse YAML in {}", file.display()),
+            MigrationError::PlanningFailed(failures) => write!(
+                f,
+                "Migration aborted: {} file(s) could not be planned; no files were modified",
+                failures.len()
+            ),
+            MigrationError::FilesFailed(failures) => {
+                write!(f, "Migration aborted: {} file(s) failed; no files were modified", failures.len())
+            }
+            MigrationError::Backup(_) => write!(f, "Failed to back up files; no files were modified"),
+            MigrationError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for MigrationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MigrationError::LatestRelease(e)
+            | MigrationError::Detection { source: e, .. }
+            | MigrationError::Definitions { source: e, .. }
+            | MigrationError::Backup(e) => Some(e.as_ref()),
+            MigrationError::Parse { source, .. } => Some(source),
+            // Displayed in place of this error
+            MigrationError::Other(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl MigrationError {
+    /// Files that failed, for aborted runs
+    pub fn failures(&self) -> &[FileFailure] {
+        match self {
+            MigrationError::PlanningFailed(failures) | MigrationError::FilesFailed(failures) => failures,
+            _ => &[],
+        }
+    }
+}
+
+impl From<anyhow::Error> for MigrationError {
+    fn from(e: anyhow::Error) -> Self {
+        MigrationError::Other(e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_and_source() {
+        let err = MigrationError::NoMigrationPath { from: Version::new(4, 60, 0), to: Version::new(4, 50, 0) };
+        assert_eq!(err.to_string(), "No migration path found from 4.60.0 to 4.50.0");
+        assert!(err.source().is_none());
+
+        let yaml = serde_yaml::from_str::<serde_yaml::Value>("a: [").unwrap_err();
+        let err = MigrationError::Parse { file: PathBuf::from("default.yml"), source: yaml };
+        assert_eq!(err.to_string(), "Failed to parse YAML in default.yml");
+        assert!(err.source().is_some());
+
+        // Wrapped anyhow errors keep their message and chain
+        let err = MigrationError::from(anyhow::anyhow!("inner").context("outer"));
+        assert_eq!(err.to_string(), "outer");
+        assert_eq!(err.source().unwrap().to_string(), "inner");
+    }
+}
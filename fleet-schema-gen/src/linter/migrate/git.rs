@@ -1,13 +1,20 @@
 use anyhow::{Context, Result};
-use colored::*;
 use git2::{
     BranchType, Commit, IndexAddOption, ObjectType, Oid, Repository, Signature, StatusOptions,
 };
 use std::path::Path;
+use crate::linter::config::MigrateConfig;
 
 /// Git integration for migrations
 pub struct GitMigrator {
     repo: Repository,
+    config: MigrateConfig,
+}
+
+/// Replace `{name}` placeholders in a template.
+pub fn render_template(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
 }
 
 impl GitMigrator {
@@ -16,7 +23,27 @@ impl GitMigrator {
         let repo = Repository::discover(path)
             .with_context(|| format!("Failed to find Git repository at {}", path.display()))?;
 
-        Ok(Self { repo })
+        Ok(Self { repo, config: MigrateConfig::default() })
+    }
+
+    /// Use branch/commit templates and publishing options from `.fleetlint.toml`
+    pub fn with_config(mut self, config: MigrateConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Whether the branch should be pushed after committing
+    pub fn should_push(&self) -> bool {
+        self.config.push || self.config.pull_request
+    }
+
+    /// Whether a pull request should be opened after pushing
+    pub fn should_open_pr(&self) -> bool {
+        self.config.pull_request
+    }
+
+    fn workdir(&self) -> &Path {
+        self.repo.workdir().unwrap_or_else(|| self.repo.path())
     }
 
     /// Check if we're in a Git repository
@@ -34,9 +61,16 @@ impl GitMigrator {
         Ok(branch_name.to_string())
     }
 
-    /// Create a new branch for migration
+    /// Create a new branch for migration, named by the `branch` template
     pub fn create_migration_branch(&self, from_version: &str, to_version: &str) -> Result<String> {
-        let branch_name = format!("fleet-migrate-{}-to-{}", from_version, to_version);
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let branch_name = render_template(
+            &self.config.branch,
+            &[("from", from_version), ("to", to_version), ("date", &date)],
+        );
+        if !git2::Branch::name_is_valid(&branch_name)? {
+            return Err(anyhow::anyhow!("Invalid branch name '{}' (check migrate.branch)", branch_name));
+        }
 
         // Get current HEAD commit
         let head_commit = self.get_head_commit()?;
@@ -55,10 +89,7 @@ impl GitMigrator {
         // Switch to new branch
         self.checkout_branch(&branch_name)?;
 
-        println!("{} Created and switched to branch: {}",
-            "✓".green().bold(),
-            branch_name.cyan().bold()
-        );
+        tracing::info!("Created and switched to branch: {}", branch_name);
 
         Ok(branch_name)
     }
@@ -82,12 +113,19 @@ impl GitMigrator {
         Ok(())
     }
 
-    /// Stage specific files
+    /// Stage specific files (relative to the working directory or absolute)
     pub fn stage_files(&self, files: &[&Path]) -> Result<()> {
         let mut index = self.repo.index()?;
+        let workdir = self.workdir().canonicalize()?;
 
         for file in files {
-            index.add_path(file)?;
+            let absolute = file
+                .canonicalize()
+                .with_context(|| format!("Failed to stage {}", file.display()))?;
+            let relative = absolute
+                .strip_prefix(&workdir)
+                .with_context(|| format!("{} is outside the repository", file.display()))?;
+            index.add_path(relative)?;
         }
 
         index.write()?;
@@ -113,30 +151,74 @@ impl GitMigrator {
             &[&parent_commit],
         )?;
 
-        println!("{} Committed: {}",
-            "✓".green().bold(),
-            message.bold()
-        );
+        tracing::info!("Committed: {}", message);
 
         Ok(commit_id)
     }
 
-    /// Create a commit for migration
+    /// Commit message for a migration, from the `commit_message` template
+    pub fn migration_message(&self, from_version: &str, to_version: &str, files_changed: usize) -> Result<String> {
+        let branch = self.current_branch()?;
+        let files = files_changed.to_string();
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        Ok(render_template(
+            &self.config.commit_message,
+            &[("from", from_version), ("to", to_version), ("files", &files), ("date", &date), ("branch", &branch)],
+        ))
+    }
+
+    /// Create a commit for migration, signed if `sign` is set
     pub fn commit_migration(
         &self,
         from_version: &str,
         to_version: &str,
         files_changed: usize,
     ) -> Result<Oid> {
-        let message = format!(
-            "Migrate Fleet GitOps from {} to {}\n\n\
-            Automated migration of {} file(s).\n\
-            \n\
-            Migration performed by fleet-schema-gen",
-            from_version, to_version, files_changed
-        );
+        let message = self.migration_message(from_version, to_version, files_changed)?;
+
+        if self.config.sign {
+            self.commit_signed(&message)
+        } else {
+            self.commit(&message)
+        }
+    }
+
+    /// Commit the index with `git commit -S`, so git's own signing setup
+    /// (GPG, SSH or X.509 keys, agents) is used
+    fn commit_signed(&self, message: &str) -> Result<Oid> {
+        let sign_arg = match &self.config.signing_key {
+            Some(key) => format!("-S{}", key),
+            None => "-S".to_string(),
+        };
+        let output = std::process::Command::new("git")
+            .current_dir(self.workdir())
+            .args(["commit", "--quiet", &sign_arg, "-m", message])
+            .output()
+            .context("Failed to run git commit")?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Signed commit failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        tracing::info!("Committed (signed): {}", message.lines().next().unwrap_or(""));
+        Ok(self.get_head_commit()?.id())
+    }
 
-        self.commit(&message)
+    /// Pull request title and body for a commit message, from the `pr_title`
+    /// and `pr_body` templates (default: the subject and the rest)
+    pub fn pr_text(&self, message: &str, vars: &[(&str, &str)]) -> (String, String) {
+        let (subject, body) = message.split_once('\n').unwrap_or((message, ""));
+        let title = match &self.config.pr_title {
+            Some(template) => render_template(template, vars),
+            None => subject.to_string(),
+        };
+        let body = match &self.config.pr_body {
+            Some(template) => render_template(template, vars),
+            None => body.trim().to_string(),
+        };
+        (title, body)
     }
 
     /// Show diff of uncommitted changes
@@ -187,8 +269,8 @@ impl GitMigrator {
         Ok(!self.has_uncommitted_changes()?)
     }
 
-    /// Create a pull request (via gh CLI)
-    pub fn create_pr(&self, title: &str, body: &str) -> Result<()> {
+    /// Create a pull request (via gh CLI), returning its URL
+    pub fn create_pr(&self, title: &str, body: &str) -> Result<Option<String>> {
         // Check if gh CLI is available
         let gh_available = std::process::Command::new("gh")
             .arg("--version")
@@ -196,49 +278,38 @@ impl GitMigrator {
             .is_ok();
 
         if !gh_available {
-            println!("{} GitHub CLI (gh) not found. Skipping PR creation.",
-                "⚠️".yellow()
-            );
-            println!("   Install with: brew install gh");
-            return Ok(());
+            tracing::warn!("GitHub CLI (gh) not found. Skipping PR creation (install with: brew install gh)");
+            return Ok(None);
         }
 
         // Create PR using gh CLI
         let output = std::process::Command::new("gh")
-            .args(&["pr", "create", "--title", title, "--body", body])
+            .current_dir(self.workdir())
+            .args(["pr", "create", "--title", title, "--body", body])
             .output()?;
 
         if output.status.success() {
-            let pr_url = String::from_utf8_lossy(&output.stdout);
-            println!("{} Pull request created: {}",
-                "✓".green().bold(),
-                pr_url.trim().cyan()
-            );
+            let pr_url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            tracing::info!("Pull request created: {}", pr_url);
+            Ok(Some(pr_url))
         } else {
             let error = String::from_utf8_lossy(&output.stderr);
-            println!("{} Failed to create PR: {}",
-                "✗".red().bold(),
-                error.trim()
-            );
+            tracing::error!("Failed to create PR: {}", error.trim());
+            Ok(None)
         }
-
-        Ok(())
     }
 
-    /// Push current branch to remote
+    /// Push a branch to the configured remote, using git's credentials
     pub fn push(&self, branch: &str) -> Result<()> {
-        println!("{} Pushing branch {} to remote...",
-            "→".blue().bold(),
-            branch.cyan()
-        );
+        tracing::info!("Pushing branch {} to {}...", branch, self.config.remote);
 
-        // This is a simplified version - real implementation would need credentials
         let output = std::process::Command::new("git")
-            .args(&["push", "-u", "origin", branch])
+            .current_dir(self.workdir())
+            .args(["push", "-u", &self.config.remote, branch])
             .output()?;
 
         if output.status.success() {
-            println!("{} Branch pushed successfully", "✓".green().bold());
+            tracing::info!("Branch pushed successfully");
         } else {
             let error = String::from_utf8_lossy(&output.stderr);
             return Err(anyhow::anyhow!("Failed to push: {}", error));
@@ -328,4 +399,43 @@ mod tests {
         assert_eq!(branch, "fleet-migrate-4.73-to-4.74");
         assert_eq!(git.current_branch().unwrap(), branch);
     }
+
+    #[test]
+    fn test_migration_templates() {
+        let temp = TempDir::new().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .unwrap();
+
+        let config = MigrateConfig {
+            branch: "chore/fleet-{to}".to_string(),
+            commit_message: "chore: migrate to Fleet {to}\n\n{files} file(s) from {from} on {branch}".to_string(),
+            pr_title: Some("Fleet {to} migration".to_string()),
+            ..Default::default()
+        };
+        let git = GitMigrator::open(temp.path()).unwrap().with_config(config);
+        assert!(!git.should_push());
+
+        let branch = git.create_migration_branch("4.73", "4.74").unwrap();
+        assert_eq!(branch, "chore/fleet-4.74");
+
+        fs::write(temp.path().join("default.yml"), "policies: []\n").unwrap();
+        git.stage_all().unwrap();
+        let oid = git.commit_migration("4.73", "4.74", 1).unwrap();
+        let message = repo.find_commit(oid).unwrap().message().unwrap().to_string();
+        assert_eq!(message, "chore: migrate to Fleet 4.74\n\n1 file(s) from 4.73 on chore/fleet-4.74");
+
+        let (title, body) = git.pr_text(&message, &[("to", "4.74")]);
+        assert_eq!(title, "Fleet 4.74 migration");
+        assert_eq!(body, "1 file(s) from 4.73 on chore/fleet-4.74");
+
+        let invalid = GitMigrator::open(temp.path())
+            .unwrap()
+            .with_config(MigrateConfig { branch: "bad..{to}".to_string(), ..Default::default() });
+        assert!(invalid.create_migration_branch("4.73", "4.74").is_err());
+    }
 }
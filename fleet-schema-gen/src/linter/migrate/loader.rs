@@ -1,4 +1,5 @@
-use super::types::{MatchStrategy, Migration, Transformation, Version};
+use super::types::{MatchStrategy, Migration, Transformation, Version, VersionRange};
+use super::validate::MigrationIssue;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -7,18 +8,30 @@ use std::path::Path;
 /// TOML representation of migrations
 #[derive(Debug, Deserialize, Serialize)]
 struct MigrationsToml {
+    #[serde(default)]
     migration: Vec<MigrationToml>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 struct MigrationToml {
     id: String,
-    from_version: String,
-    to_version: String,
+    #[serde(default)]
+    from_version: Option<String>,
+    #[serde(default)]
+    to_version: Option<String>,
+    /// Range of source versions (`>=4.58, <4.60`); its bounds stand in for
+    /// `from_version` and `to_version` when those are not set
+    #[serde(default)]
+    versions: Option<String>,
     description: String,
-    transformations: Vec<TransformationToml>,
+    /// Parsed one by one so an unknown `type` skips that transformation only
+    #[serde(default)]
+    transformations: Vec<toml::Value>,
 }
 
+/// Transformation kinds `migrations.toml` accepts
+const TRANSFORMATION_TYPES: &[&str] = &["field_move", "field_rename", "field_delete", "restructure"];
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum TransformationToml {
@@ -54,35 +67,81 @@ pub fn load_migrations_from_file(path: &Path) -> Result<Vec<Migration>> {
     load_migrations_from_str(&content)
 }
 
+const BUILTIN_MIGRATIONS: &str = include_str!("../../../migrations.toml");
+
+/// Migrations shipped with this release (`migrations.toml`)
+pub fn builtin_migrations() -> Vec<Migration> {
+    load_migrations_from_str(BUILTIN_MIGRATIONS).unwrap_or_else(|e| {
+        tracing::error!("Built-in migrations.toml is invalid: {:#}", e);
+        Vec::new()
+    })
+}
+
 /// Load migrations from a TOML string
 pub fn load_migrations_from_str(content: &str) -> Result<Vec<Migration>> {
+    let (migrations, issues) = load_migrations_with_issues(content)?;
+    for issue in issues {
+        tracing::warn!("Migration {}", issue);
+    }
+    Ok(migrations)
+}
+
+/// Load migrations from a TOML string, returning the transformations of
+/// unknown kinds that were skipped as issues
+pub fn load_migrations_with_issues(content: &str) -> Result<(Vec<Migration>, Vec<MigrationIssue>)> {
     let migrations_toml: MigrationsToml = toml::from_str(content)
         .context("Failed to parse migrations TOML")?;
 
-    migrations_toml
+    let mut issues = Vec::new();
+    let migrations = migrations_toml
         .migration
         .into_iter()
-        .map(|m| migration_from_toml(m))
-        .collect()
+        .map(|m| migration_from_toml(m, &mut issues))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((migrations, issues))
 }
 
-fn migration_from_toml(m: MigrationToml) -> Result<Migration> {
-    let from_version = Version::parse(&m.from_version)
-        .with_context(|| format!("Invalid from_version: {}", m.from_version))?;
+fn migration_from_toml(m: MigrationToml, issues: &mut Vec<MigrationIssue>) -> Result<Migration> {
+    let versions = m
+        .versions
+        .as_deref()
+        .map(|v| VersionRange::parse(v).with_context(|| format!("Invalid versions range in migration {}: {}", m.id, v)))
+        .transpose()?;
 
-    let to_version = Version::parse(&m.to_version)
-        .with_context(|| format!("Invalid to_version: {}", m.to_version))?;
+    let from_version = match (&m.from_version, versions.as_ref().and_then(VersionRange::lower)) {
+        (Some(v), _) => Version::parse(v).with_context(|| format!("Invalid from_version: {}", v))?,
+        (None, Some(lower)) => lower.clone(),
+        (None, None) => anyhow::bail!("Migration {} needs from_version or a versions range with a lower bound", m.id),
+    };
 
-    let transformations = m
-        .transformations
-        .into_iter()
-        .map(|t| transformation_from_toml(t))
-        .collect::<Result<Vec<_>>>()?;
+    let to_version = match (&m.to_version, versions.as_ref().and_then(VersionRange::upper)) {
+        (Some(v), _) => Version::parse(v).with_context(|| format!("Invalid to_version: {}", v))?,
+        (None, Some(upper)) => upper.clone(),
+        (None, None) => anyhow::bail!("Migration {} needs to_version or a versions range with an upper bound", m.id),
+    };
+
+    let mut transformations = Vec::new();
+    for value in m.transformations {
+        let kind = value.get("type").and_then(|t| t.as_str()).unwrap_or("").to_string();
+        if !TRANSFORMATION_TYPES.contains(&kind.as_str()) {
+            issues.push(MigrationIssue::new(&m.id, format!(
+                "unknown transformation type '{}' (valid: {}); skipped",
+                kind,
+                TRANSFORMATION_TYPES.join(", ")
+            )));
+            continue;
+        }
+        let transformation: TransformationToml = value
+            .try_into()
+            .with_context(|| format!("Invalid {} transformation in migration {}", kind, m.id))?;
+        transformations.push(transformation_from_toml(transformation)?);
+    }
 
     Ok(Migration {
         id: m.id,
         from_version,
         to_version,
+        versions,
         description: m.description,
         transformations,
     })
@@ -147,6 +206,12 @@ fn transformation_from_toml(t: TransformationToml) -> Result<Transformation> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_builtin_migrations_load() {
+        let migrations = load_migrations_from_str(BUILTIN_MIGRATIONS).unwrap();
+        assert!(!migrations.is_empty());
+    }
+
     #[test]
     fn test_load_migrations() {
         let toml = r#"
@@ -206,6 +271,60 @@ new_path = "macos_settings.enable_disk_encryption"
         }
     }
 
+    #[test]
+    fn test_version_range_migration() {
+        let toml = r#"
+[[migration]]
+id = "range"
+versions = ">=4.58, <4.60"
+description = "Applies to any 4.58 or 4.59 config"
+
+[[migration.transformations]]
+type = "field_delete"
+pattern = "**/*.yml"
+fields = ["old"]
+"#;
+
+        let migrations = load_migrations_from_str(toml).unwrap();
+        let migration = &migrations[0];
+        assert_eq!(migration.from_version, Version::new(4, 58, 0));
+        assert_eq!(migration.to_version, Version::new(4, 60, 0));
+        assert!(migration.applies_from(&Version::new(4, 59, 2)));
+        assert!(migration.applies_from(&Version::new(4, 50, 0)));
+        assert!(!migration.applies_from(&Version::new(4, 60, 0)));
+
+        assert!(load_migrations_from_str(&toml.replace(">=4.58, <4.60", ">=4.58")).is_err());
+        assert!(load_migrations_from_str(&toml.replace(">=4.58, <4.60", "soon")).is_err());
+    }
+
+    #[test]
+    fn test_unknown_transformation_type() {
+        let toml = r#"
+[[migration]]
+id = "typo"
+from_version = "4.50.0"
+to_version = "4.51.0"
+description = "Has a typo"
+
+[[migration.transformations]]
+type = "field_renam"
+pattern = "**/*.yml"
+
+[[migration.transformations]]
+type = "field_delete"
+pattern = "**/*.yml"
+fields = ["deprecated_field"]
+"#;
+
+        let (migrations, issues) = load_migrations_with_issues(toml).unwrap();
+        assert_eq!(migrations[0].transformations.len(), 1);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].to_string().starts_with("typo: unknown transformation type 'field_renam'"));
+
+        // A known kind with missing fields is still an error
+        assert!(load_migrations_from_str(&toml.replace("field_renam", "field_rename")).is_err());
+    }
+
     #[test]
     fn test_field_delete_transformation() {
         let toml = r#"
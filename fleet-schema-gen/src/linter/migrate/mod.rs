@@ -7,16 +7,32 @@ pub mod backup;
 pub mod git;
 pub mod diff;
 pub mod loader;
+pub mod validate;
+pub mod error;
 
-pub use types::{Migration, Transformation, MigrationPlan, Version};
+pub use types::{DocumentMigration, FailureMode, Migration, MigrationReport, Transformation, MigrationPlan, Version};
 pub use detector::VersionDetector;
 pub use resolver::PathResolver;
 pub use engine::MigrationEngine;
 pub use backup::Backup;
+pub use diff::DiffStyle;
+pub use validate::MigrationIssue;
+pub use error::MigrationError;
 
-use anyhow::Result;
 use std::path::Path;
 
+/// Parse a version given on the command line; `latest` is the newest Fleet
+/// release on GitHub
+pub async fn resolve_version(spec: &str) -> Result<Version, MigrationError> {
+    if spec.eq_ignore_ascii_case("latest") {
+        let tag = crate::sources::github::latest_release_tag()
+            .await
+            .map_err(|e| MigrationError::LatestRelease(e.into()))?;
+        return Version::parse(&tag).ok_or(MigrationError::InvalidVersion(tag));
+    }
+    Version::parse(spec).ok_or_else(|| MigrationError::InvalidVersion(spec.to_string()))
+}
+
 /// Main entry point for migrations
 pub struct Migrator {
     detector: VersionDetector,
@@ -32,15 +48,22 @@ impl Migrator {
     }
 
     /// Detect the Fleet version of a configuration
-    pub fn detect_version(&self, path: &Path) -> Result<Option<Version>> {
-        self.detector.detect(path)
+    pub fn detect_version(&self, path: &Path) -> Result<Option<Version>, MigrationError> {
+        self.detector
+            .detect(path)
+            .map_err(|e| MigrationError::Detection { path: path.to_path_buf(), source: e.into() })
     }
 
     /// Load migrations from a TOML file
-    pub fn load_migrations_from_file(&mut self, path: &Path) -> Result<()> {
+    pub fn load_migrations_from_file(&mut self, path: &Path) -> Result<(), MigrationError> {
         self.engine.load_migrations_from_file(path)
     }
 
+    /// Problems with the loaded migrations, checked against the files under `root`
+    pub fn validate_migrations(&self, root: Option<&Path>) -> Vec<MigrationIssue> {
+        self.engine.validate(root)
+    }
+
     /// Get the latest supported version
     pub fn latest_version(&self) -> Version {
         self.engine.latest_version()
@@ -52,30 +75,56 @@ impl Migrator {
         path: &Path,
         from: &Version,
         to: &Version,
-    ) -> Result<MigrationPlan> {
+    ) -> Result<MigrationPlan, MigrationError> {
         self.engine.plan(path, from, to)
     }
 
+    /// How dry runs show the changes (unified diffs by default)
+    pub fn set_diff_style(&mut self, style: DiffStyle) {
+        self.engine.set_diff_style(style);
+    }
+
+    /// Whether a failing file aborts the run or is reported at the end
+    pub fn set_failure_mode(&mut self, mode: FailureMode) {
+        self.engine.set_failure_mode(mode);
+    }
+
+    /// Number of files migrated concurrently
+    pub fn set_jobs(&mut self, jobs: usize) {
+        self.engine.set_jobs(jobs);
+    }
+
     /// Execute a migration
     pub fn execute_migration(
         &mut self,
         plan: &MigrationPlan,
         dry_run: bool,
-    ) -> Result<()> {
+    ) -> Result<MigrationReport, MigrationError> {
         self.engine.execute(plan, dry_run)
     }
 
+    /// Migrate a single YAML document in memory (no file IO, no backup)
+    pub fn migrate_document(
+        &self,
+        content: &str,
+        file_name: Option<&str>,
+        from: &Version,
+        to: &Version,
+    ) -> Result<DocumentMigration, MigrationError> {
+        self.engine.migrate_document(content, file_name, from, to)
+    }
+
     /// Auto-migrate to latest version
-    pub fn auto_migrate(&mut self, path: &Path, dry_run: bool) -> Result<()> {
+    pub fn auto_migrate(&mut self, path: &Path, dry_run: bool) -> Result<(), MigrationError> {
         // Detect current version
         let current = self.detect_version(path)?
-            .ok_or_else(|| anyhow::anyhow!("Could not detect Fleet version"))?;
+            .ok_or_else(|| MigrationError::UnknownVersion(path.to_path_buf()))?;
 
         // Get latest supported version
         let latest = self.engine.latest_version();
 
         if current >= latest {
-            println!("Already at latest version: {}", current);
+            tracing::info!("Already at latest version: {}", current);
             return Ok(());
         }
 
@@ -83,7 +132,7 @@ impl Migrator {
         let plan = self.plan_migration(path, &current, &latest)?;
 
         // Execute
-        self.execute_migration(&plan, dry_run)
+        self.execute_migration(&plan, dry_run).map(|_| ())
     }
 }
 
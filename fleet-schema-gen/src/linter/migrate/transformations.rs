@@ -60,27 +60,9 @@ pub fn execute_field_rename(
     file: &Path,
     resolver: &mut PathResolver,
 ) -> Result<Vec<FileChange>> {
-    let mut changes = Vec::new();
-
-    if let Transformation::FieldRename {
-        old_path,
-        new_path,
-        ..
-    } = transformation
-    {
-        // Load YAML
-        let yaml = resolver.load_file(file)?.clone();
-
-        // Get value at old path
-        if let Some(value) = get_nested_value(&yaml, old_path) {
-            changes.push(FileChange::RenameField {
-                old_path: old_path.clone(),
-                new_path: new_path.clone(),
-            });
-        }
-    }
-
-    Ok(changes)
+    // Load YAML
+    let yaml = resolver.load_file(file)?;
+    Ok(document_changes(transformation, yaml).unwrap_or_default())
 }
 
 /// Execute a field delete transformation
@@ -89,22 +71,35 @@ pub fn execute_field_delete(
     file: &Path,
     resolver: &mut PathResolver,
 ) -> Result<Vec<FileChange>> {
-    let mut changes = Vec::new();
-
-    if let Transformation::FieldDelete { fields, .. } = transformation {
-        // Load YAML
-        let yaml = resolver.load_file(file)?.clone();
+    // Load YAML
+    let yaml = resolver.load_file(file)?;
+    Ok(document_changes(transformation, yaml).unwrap_or_default())
+}
 
-        for field in fields {
-            if get_nested_value(&yaml, field).is_some() {
-                changes.push(FileChange::RemoveField {
-                    path: field.clone(),
-                });
-            }
+/// Changes a transformation makes to a single document, or `None` when it
+/// needs other files (field moves) or custom logic (restructures)
+pub fn document_changes(transformation: &Transformation, yaml: &serde_yaml::Value) -> Option<Vec<FileChange>> {
+    match transformation {
+        Transformation::FieldRename { old_path, new_path, .. } => {
+            // Only when there is a value at the old path
+            let changes = get_nested_value(yaml, old_path)
+                .map(|_| FileChange::RenameField {
+                    old_path: old_path.clone(),
+                    new_path: new_path.clone(),
+                })
+                .into_iter()
+                .collect();
+            Some(changes)
         }
+        Transformation::FieldDelete { fields, .. } => Some(
+            fields
+                .iter()
+                .filter(|field| get_nested_value(yaml, field).is_some())
+                .map(|field| FileChange::RemoveField { path: field.clone() })
+                .collect(),
+        ),
+        Transformation::FieldMove { .. } | Transformation::Restructure { .. } => None,
     }
-
-    Ok(changes)
 }
 
 /// Apply file changes to a YAML file
@@ -112,7 +107,12 @@ pub fn apply_changes(file: &Path, changes: &[FileChange]) -> Result<String> {
     let content = fs::read_to_string(file)
         .with_context(|| format!("Failed to read {}", file.display()))?;
 
-    let mut yaml: serde_yaml::Value = serde_yaml::from_str(&content)
+    apply_changes_to(file, &content, changes)
+}
+
+/// Apply changes to `content`, the current text of `file`
+pub fn apply_changes_to(file: &Path, content: &str, changes: &[FileChange]) -> Result<String> {
+    let mut yaml: serde_yaml::Value = serde_yaml::from_str(content)
         .with_context(|| format!("Failed to parse YAML in {}", file.display()))?;
 
     for change in changes {
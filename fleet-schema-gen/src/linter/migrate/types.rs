@@ -1,37 +1,219 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::path::PathBuf;
 use std::fmt;
 
-/// Fleet version
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+/// Fleet version: semver with an optional prerelease tag (`4.62.0-rc1`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Version {
     pub major: u32,
     pub minor: u32,
     pub patch: u32,
+    /// Prerelease tag after `-`, e.g. `rc1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre: Option<String>,
 }
 
 impl Version {
     pub fn new(major: u32, minor: u32, patch: u32) -> Self {
-        Self { major, minor, patch }
+        Self { major, minor, patch, pre: None }
     }
 
+    /// Parse `4.62`, `4.62.0`, `v4.62.0-rc1` or a release tag such as
+    /// `fleet-v4.62.0`. Build metadata (`+...`) is ignored.
     pub fn parse(s: &str) -> Option<Self> {
-        let parts: Vec<&str> = s.split('.').collect();
-        if parts.len() < 2 {
+        let s = s.trim();
+        let s = s.strip_prefix("fleet-").unwrap_or(s);
+        let s = s.strip_prefix('v').unwrap_or(s);
+        let s = s.split('+').next()?;
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) if !pre.is_empty() => (core, Some(pre.to_string())),
+            Some(_) => return None,
+            None => (s, None),
+        };
+
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() < 2 || parts.len() > 3 {
             return None;
         }
 
         let major = parts[0].parse().ok()?;
         let minor = parts[1].parse().ok()?;
-        let patch = parts.get(2).and_then(|p| p.parse().ok()).unwrap_or(0);
+        let patch = match parts.get(2) {
+            Some(p) => p.parse().ok()?,
+            None => 0,
+        };
+
+        Some(Self { major, minor, patch, pre })
+    }
 
-        Some(Self::new(major, minor, patch))
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+}
+
+/// Order of prerelease tags: dot-separated identifiers compared in turn,
+/// numbers numerically and below words, and words with a trailing number
+/// (`rc2`, `rc10`) by word then number.
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    fn split_number(id: &str) -> (&str, Option<u64>) {
+        let digits = id.len() - id.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        let (word, number) = id.split_at(id.len() - digits);
+        (word, number.parse().ok())
+    }
+
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+    loop {
+        let ordering = match (a_ids.next(), b_ids.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => split_number(x).cmp(&split_number(y)),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                // A prerelease comes before its release
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(a), Some(b)) => compare_pre(a, b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Migration {
+    /// Whether migrating a config at `version` passes through this
+    /// migration: it starts at or after `version`, or `version` falls in
+    /// its range of source versions
+    pub fn applies_from(&self, version: &Version) -> bool {
+        match &self.versions {
+            Some(range) => range.contains(version) || range.lower().is_some_and(|lower| version < lower),
+            None => &self.from_version >= version,
+        }
     }
 }
 
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+/// Comparison in a version range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionOp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Versions matching all of a list of comparisons, written `>=4.58, <4.60`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct VersionRange {
+    pub comparators: Vec<(VersionOp, Version)>,
+}
+
+impl VersionRange {
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut comparators = Vec::new();
+        for part in s.split(',').map(str::trim) {
+            let (op, version) = [(">=", VersionOp::Ge), ("<=", VersionOp::Le), (">", VersionOp::Gt), ("<", VersionOp::Lt), ("=", VersionOp::Eq)]
+                .iter()
+                .find_map(|(prefix, op)| part.strip_prefix(prefix).map(|rest| (*op, rest)))
+                .unwrap_or((VersionOp::Eq, part));
+            comparators.push((op, Version::parse(version)?));
+        }
+        Some(Self { comparators })
+    }
+
+    pub fn contains(&self, version: &Version) -> bool {
+        self.comparators.iter().all(|(op, bound)| match op {
+            VersionOp::Eq => version == bound,
+            VersionOp::Lt => version < bound,
+            VersionOp::Le => version <= bound,
+            VersionOp::Gt => version > bound,
+            VersionOp::Ge => version >= bound,
+        })
+    }
+
+    /// Lowest version named by a `>=`, `>` or `=` comparison
+    pub fn lower(&self) -> Option<&Version> {
+        self.comparators
+            .iter()
+            .filter(|(op, _)| matches!(op, VersionOp::Ge | VersionOp::Gt | VersionOp::Eq))
+            .map(|(_, v)| v)
+            .max()
+    }
+
+    /// Highest version named by a `<`, `<=` or `=` comparison
+    pub fn upper(&self) -> Option<&Version> {
+        self.comparators
+            .iter()
+            .filter(|(op, _)| matches!(op, VersionOp::Lt | VersionOp::Le | VersionOp::Eq))
+            .map(|(_, v)| v)
+            .min()
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self
+            .comparators
+            .iter()
+            .map(|(op, v)| {
+                let op = match op {
+                    VersionOp::Eq => "=",
+                    VersionOp::Lt => "<",
+                    VersionOp::Le => "<=",
+                    VersionOp::Gt => ">",
+                    VersionOp::Ge => ">=",
+                };
+                format!("{}{}", op, v)
+            })
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl TryFrom<String> for VersionRange {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s).ok_or_else(|| format!("Invalid version range: {}", s))
+    }
+}
+
+impl From<VersionRange> for String {
+    fn from(range: VersionRange) -> Self {
+        range.to_string()
     }
 }
 
@@ -41,6 +223,10 @@ pub struct Migration {
     pub id: String,
     pub from_version: Version,
     pub to_version: Version,
+    /// Source versions the migration applies to, when given as a range
+    /// (`versions = ">=4.58, <4.60"`) instead of a single `from_version`
+    #[serde(default)]
+    pub versions: Option<VersionRange>,
     pub description: String,
     pub transformations: Vec<Transformation>,
 }
@@ -95,6 +281,33 @@ pub struct MigrationPlan {
     pub affected_files: Vec<PathBuf>,
     pub steps: Vec<MigrationStep>,
     pub estimated_changes: usize,
+    /// Files that could not be read or parsed while planning
+    pub failures: Vec<FileFailure>,
+}
+
+/// A file that could not be migrated
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileFailure {
+    pub file: PathBuf,
+    pub error: String,
+}
+
+/// What a failing file does to the rest of the run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailureMode {
+    /// Stop at the first failure and write nothing
+    #[default]
+    FailFast,
+    /// Migrate the other files and report the failures at the end
+    KeepGoing,
+}
+
+/// Outcome of executing a migration plan
+#[derive(Debug, Clone, Default)]
+pub struct MigrationReport {
+    /// Files changed (or that would change, in a dry run)
+    pub migrated: Vec<PathBuf>,
+    pub failed: Vec<FileFailure>,
 }
 
 /// A single step in a migration plan
@@ -126,6 +339,16 @@ pub enum FileChange {
     },
 }
 
+/// A single document migrated in memory (`migrate --stdin`)
+#[derive(Debug, Clone)]
+pub struct DocumentMigration {
+    pub content: String,
+    /// Number of changes applied
+    pub changes: usize,
+    /// Transformations that could not run on a lone document
+    pub skipped: Vec<String>,
+}
+
 /// Result of version detection
 #[derive(Debug, Clone)]
 pub struct DetectionResult {
@@ -165,5 +388,40 @@ mod tests {
     fn test_version_display() {
         let v = Version::new(4, 74, 0);
         assert_eq!(format!("{}", v), "4.74.0");
+        assert_eq!(Version::parse("4.62.0-rc1").unwrap().to_string(), "4.62.0-rc1");
+    }
+
+    #[test]
+    fn test_prerelease_versions() {
+        let rc1 = Version::parse("fleet-v4.62.0-rc1").unwrap();
+        assert_eq!(rc1.pre.as_deref(), Some("rc1"));
+        assert!(rc1.is_prerelease());
+        assert_eq!(Version::parse("v4.62.0+build.5"), Some(Version::new(4, 62, 0)));
+        assert_eq!(Version::parse("4.62.0-"), None);
+        assert_eq!(Version::parse("4.62.x"), None);
+
+        let ordered: Vec<Version> = ["4.61.9", "4.62.0-alpha", "4.62.0-rc1", "4.62.0-rc2", "4.62.0-rc10", "4.62.0", "4.62.1-rc1"]
+            .iter()
+            .map(|v| Version::parse(v).unwrap())
+            .collect();
+        for pair in ordered.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+        assert!(Version::parse("4.62.0-1").unwrap() < Version::parse("4.62.0-rc").unwrap());
+    }
+
+    #[test]
+    fn test_version_ranges() {
+        let range = VersionRange::parse(">=4.58, <4.60").unwrap();
+        assert!(range.contains(&Version::new(4, 58, 0)));
+        assert!(range.contains(&Version::new(4, 59, 3)));
+        assert!(!range.contains(&Version::new(4, 60, 0)));
+        assert!(range.contains(&Version::parse("4.60.0-rc1").unwrap()));
+        assert_eq!(range.lower(), Some(&Version::new(4, 58, 0)));
+        assert_eq!(range.upper(), Some(&Version::new(4, 60, 0)));
+        assert_eq!(range.to_string(), ">=4.58.0, <4.60.0");
+
+        assert!(VersionRange::parse("4.58").unwrap().contains(&Version::new(4, 58, 0)));
+        assert_eq!(VersionRange::parse(">=4.58, <soon"), None);
     }
 }
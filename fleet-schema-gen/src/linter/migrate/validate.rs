@@ -0,0 +1,201 @@
+//! Up-front checks for migration definitions.
+//!
+//! A mistake in a custom `migrations.toml` otherwise shows up as a step
+//! that silently does nothing halfway through a run. These checks run when
+//! migrations are loaded and report what would be skipped or ambiguous.
+
+use super::types::{MatchStrategy, Migration, Transformation};
+use crate::linter::config::matches_glob;
+use std::fmt;
+use std::path::Path;
+
+/// A problem with a migration definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationIssue {
+    /// ID of the migration
+    pub migration: String,
+    pub message: String,
+}
+
+impl MigrationIssue {
+    pub fn new(migration: &str, message: impl Into<String>) -> Self {
+        Self { migration: migration.to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for MigrationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.migration, self.message)
+    }
+}
+
+/// Whether a transformation pattern applies to `path` (relative to the migrated directory)
+pub(super) fn pattern_matches(pattern: &str, path: &str) -> bool {
+    matches_glob(pattern, path) || matches_glob(&format!("**/{}", pattern), path)
+}
+
+/// Check migrations for inverted or overlapping version ranges and for
+/// transformations that would be skipped. With `files` (paths relative to
+/// the migrated directory), also report patterns that match none of them.
+pub fn validate_migrations(migrations: &[Migration], files: Option<&[String]>) -> Vec<MigrationIssue> {
+    let mut issues = Vec::new();
+
+    for (idx, migration) in migrations.iter().enumerate() {
+        let id = migration.id.as_str();
+        let ordered = migration.from_version < migration.to_version;
+        if !ordered {
+            issues.push(MigrationIssue::new(id, format!(
+                "from_version {} is not before to_version {}",
+                migration.from_version, migration.to_version
+            )));
+        }
+
+        for other in migrations.iter().skip(idx + 1) {
+            let overlaps = ordered
+                && other.from_version < other.to_version
+                && migration.from_version < other.to_version
+                && other.from_version < migration.to_version;
+            if overlaps {
+                issues.push(MigrationIssue::new(id, format!(
+                    "version range {} → {} overlaps {} ({} → {})",
+                    migration.from_version, migration.to_version, other.id, other.from_version, other.to_version
+                )));
+            }
+        }
+
+        if migration.transformations.is_empty() {
+            issues.push(MigrationIssue::new(id, "has no transformations"));
+        }
+
+        for transformation in &migration.transformations {
+            let patterns: Vec<&str> = match transformation {
+                Transformation::FieldMove { source_pattern, target_pattern, match_strategy, .. } => {
+                    if *match_strategy != MatchStrategy::PathReference {
+                        issues.push(MigrationIssue::new(id, format!(
+                            "field_move with match strategy {:?} is not supported yet and will be skipped",
+                            match_strategy
+                        )));
+                    }
+                    vec![source_pattern, target_pattern]
+                }
+                Transformation::FieldRename { pattern, .. } | Transformation::FieldDelete { pattern, .. } => vec![pattern],
+                Transformation::Restructure { name, .. } => {
+                    issues.push(MigrationIssue::new(id, format!(
+                        "restructure transformation '{}' is not implemented and will be skipped",
+                        name
+                    )));
+                    vec![]
+                }
+            };
+
+            if let Some(files) = files {
+                for pattern in patterns {
+                    if !files.iter().any(|file| pattern_matches(pattern, file)) {
+                        issues.push(MigrationIssue::new(id, format!("pattern '{}' matches no files", pattern)));
+                    }
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// YAML files under `root` relative to it, skipping hidden directories
+pub fn relative_yaml_files(root: &Path) -> Vec<String> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<String>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if !entry.file_name().to_string_lossy().starts_with('.') {
+                    walk(root, &path, files);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "yml" || ext == "yaml") {
+                if let Ok(relative) = path.strip_prefix(root) {
+                    files.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    walk(root, root, &mut files);
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::migrate::loader::load_migrations_from_str;
+
+    #[test]
+    fn test_validate_migrations() {
+        let migrations = load_migrations_from_str(r#"
+[[migration]]
+id = "a"
+from_version = "4.58.0"
+to_version = "4.60.0"
+description = "A"
+
+[[migration.transformations]]
+type = "field_rename"
+pattern = "teams/**/*.yml"
+old_path = "old"
+new_path = "new"
+
+[[migration]]
+id = "b"
+from_version = "4.59.0"
+to_version = "4.61.0"
+description = "B"
+
+[[migration.transformations]]
+type = "field_delete"
+pattern = "lib/**/*.yml"
+fields = ["gone"]
+
+[[migration.transformations]]
+type = "restructure"
+name = "split"
+description = "Split files"
+
+[[migration]]
+id = "c"
+from_version = "4.62.0"
+to_version = "4.61.0"
+description = "C"
+transformations = []
+"#)
+        .unwrap();
+
+        let files = vec!["default.yml".to_string(), "teams/workstations.yml".to_string()];
+        let issues: Vec<String> = validate_migrations(&migrations, Some(&files)).iter().map(|i| i.to_string()).collect();
+        assert_eq!(issues, vec![
+            "a: version range 4.58.0 → 4.60.0 overlaps b (4.59.0 → 4.61.0)",
+            "b: pattern 'lib/**/*.yml' matches no files",
+            "b: restructure transformation 'split' is not implemented and will be skipped",
+            "c: from_version 4.62.0 is not before to_version 4.61.0",
+            "c: has no transformations",
+        ]);
+
+        // Without files, patterns are not checked
+        assert_eq!(validate_migrations(&migrations, None).len(), 4);
+    }
+
+    #[test]
+    fn test_relative_yaml_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("teams")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".fleet-migrate")).unwrap();
+        std::fs::write(dir.path().join("default.yml"), "").unwrap();
+        std::fs::write(dir.path().join("teams/a.yaml"), "").unwrap();
+        std::fs::write(dir.path().join(".fleet-migrate/b.yml"), "").unwrap();
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+
+        assert_eq!(relative_yaml_files(dir.path()), vec!["default.yml", "teams/a.yaml"]);
+    }
+}
@@ -0,0 +1,268 @@
+//! Common-misspelling checks for human-facing strings (policy descriptions
+//! and resolutions shown to end users in Fleet Desktop).
+//!
+//! This is not a spellchecker: only words listed in [`MISSPELLINGS`] (or in
+//! the project's `corrections`) are flagged, and any other typo passes. The
+//! fixed list keeps technical vocabulary (table names, product names, paths)
+//! from producing false positives. Projects can add misspellings or accept
+//! listed words via `[rules.common-misspellings]` in `.fleetlint.toml`.
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Common misspellings and their corrections (lowercase).
+pub static MISSPELLINGS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    HashMap::from([
+        ("accesible", "accessible"),
+        ("accomodate", "accommodate"),
+        ("accross", "across"),
+        ("acheive", "achieve"),
+        ("adress", "address"),
+        ("adminstrator", "administrator"),
+        ("administator", "administrator"),
+        ("agian", "again"),
+        ("alot", "a lot"),
+        ("aplication", "application"),
+        ("applicaton", "application"),
+        ("applcation", "application"),
+        ("authenication", "authentication"),
+        ("authentification", "authentication"),
+        ("automaticly", "automatically"),
+        ("availabe", "available"),
+        ("availible", "available"),
+        ("avaliable", "available"),
+        ("becuase", "because"),
+        ("befor", "before"),
+        ("begining", "beginning"),
+        ("beleive", "believe"),
+        ("browswer", "browser"),
+        ("calender", "calendar"),
+        ("certficate", "certificate"),
+        ("certifcate", "certificate"),
+        ("cerificate", "certificate"),
+        ("challange", "challenge"),
+        ("chnage", "change"),
+        ("comming", "coming"),
+        ("commited", "committed"),
+        ("compatable", "compatible"),
+        ("compatibile", "compatible"),
+        ("complience", "compliance"),
+        ("compliancy", "compliance"),
+        ("compuer", "computer"),
+        ("comptuer", "computer"),
+        ("configuraton", "configuration"),
+        ("configuation", "configuration"),
+        ("conneciton", "connection"),
+        ("contorl", "control"),
+        ("currenly", "currently"),
+        ("definately", "definitely"),
+        ("defintely", "definitely"),
+        ("dependancy", "dependency"),
+        ("desable", "disable"),
+        ("descripton", "description"),
+        ("diable", "disable"),
+        ("dissable", "disable"),
+        ("doesnt", "doesn't"),
+        ("dont", "don't"),
+        ("downlaod", "download"),
+        ("enabel", "enable"),
+        ("enabeled", "enabled"),
+        ("enviroment", "environment"),
+        ("enviornment", "environment"),
+        ("encyption", "encryption"),
+        ("encrpytion", "encryption"),
+        ("encrytion", "encryption"),
+        ("existant", "existent"),
+        ("exsist", "exist"),
+        ("firewal", "firewall"),
+        ("firwall", "firewall"),
+        ("folowing", "following"),
+        ("follwing", "following"),
+        ("goverment", "government"),
+        ("gaurantee", "guarantee"),
+        ("harware", "hardware"),
+        ("immediatly", "immediately"),
+        ("independant", "independent"),
+        ("infomation", "information"),
+        ("informaton", "information"),
+        ("instaled", "installed"),
+        ("installtion", "installation"),
+        ("instalation", "installation"),
+        ("intial", "initial"),
+        ("lenght", "length"),
+        ("liscense", "license"),
+        ("maintainance", "maintenance"),
+        ("maintenence", "maintenance"),
+        ("managment", "management"),
+        ("mangement", "management"),
+        ("mesage", "message"),
+        ("neccessary", "necessary"),
+        ("necesary", "necessary"),
+        ("necessery", "necessary"),
+        ("occured", "occurred"),
+        ("occurence", "occurrence"),
+        ("occuring", "occurring"),
+        ("organisaton", "organisation"),
+        ("organizaton", "organization"),
+        ("pasword", "password"),
+        ("passowrd", "password"),
+        ("paswords", "passwords"),
+        ("permision", "permission"),
+        ("permisions", "permissions"),
+        ("plase", "please"),
+        ("pleae", "please"),
+        ("polciy", "policy"),
+        ("plocy", "policy"),
+        ("posible", "possible"),
+        ("preferrences", "preferences"),
+        ("prefrences", "preferences"),
+        ("privilige", "privilege"),
+        ("priviledge", "privilege"),
+        ("proccess", "process"),
+        ("proces", "process"),
+        ("protecton", "protection"),
+        ("reccomend", "recommend"),
+        ("recomend", "recommend"),
+        ("recomended", "recommended"),
+        ("recieve", "receive"),
+        ("recieved", "received"),
+        ("recquired", "required"),
+        ("refered", "referred"),
+        ("relevent", "relevant"),
+        ("remvoe", "remove"),
+        ("requried", "required"),
+        ("resolutoin", "resolution"),
+        ("resouce", "resource"),
+        ("retreive", "retrieve"),
+        ("scren", "screen"),
+        ("securty", "security"),
+        ("secuirty", "security"),
+        ("seperate", "separate"),
+        ("seperately", "separately"),
+        ("settigns", "settings"),
+        ("setings", "settings"),
+        ("shoud", "should"),
+        ("softare", "software"),
+        ("sofware", "software"),
+        ("succesful", "successful"),
+        ("successfull", "successful"),
+        ("sucessful", "successful"),
+        ("suport", "support"),
+        ("suported", "supported"),
+        ("systme", "system"),
+        ("teh", "the"),
+        ("thier", "their"),
+        ("threshhold", "threshold"),
+        ("truely", "truly"),
+        ("udpate", "update"),
+        ("untill", "until"),
+        ("updgrade", "upgrade"),
+        ("upgarde", "upgrade"),
+        ("usally", "usually"),
+        ("verfiy", "verify"),
+        ("verison", "version"),
+        ("wich", "which"),
+        ("whith", "with"),
+        ("writting", "writing"),
+    ])
+});
+
+/// Project-specific options from `[rules.common-misspellings]`.
+///
+/// ```toml
+/// [rules.common-misspellings]
+/// words = ["teh"]                          # accepted as-is
+/// corrections = { fleetdm = "Fleet" }      # additional misspellings
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MisspellingOptions {
+    /// Words accepted even if listed in [`MISSPELLINGS`].
+    pub words: Vec<String>,
+
+    /// Additional misspellings and their corrections.
+    pub corrections: HashMap<String, String>,
+}
+
+/// A misspelled word found in a string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Misspelling {
+    pub word: String,
+    pub suggestion: String,
+    /// Byte offset of the word within the checked text.
+    pub offset: usize,
+}
+
+/// Find misspelled words in a string.
+pub fn check_text(text: &str, options: &MisspellingOptions) -> Vec<Misspelling> {
+    let word_re = regex::Regex::new(r"[A-Za-z][A-Za-z']*").unwrap();
+
+    word_re
+        .find_iter(text)
+        .filter_map(|m| {
+            let word = m.as_str().trim_end_matches('\'');
+            let lower = word.to_lowercase();
+
+            if options.words.iter().any(|w| w.eq_ignore_ascii_case(word)) {
+                return None;
+            }
+
+            let correction = options
+                .corrections
+                .iter()
+                .find(|(wrong, _)| wrong.eq_ignore_ascii_case(word))
+                .map(|(_, right)| right.as_str())
+                .or_else(|| MISSPELLINGS.get(lower.as_str()).copied())?;
+
+            Some(Misspelling {
+                word: word.to_string(),
+                suggestion: match_case(word, correction),
+                offset: m.start(),
+            })
+        })
+        .collect()
+}
+
+/// Apply the capitalization of `original` to `correction`.
+fn match_case(original: &str, correction: &str) -> String {
+    if original.len() > 1 && original.chars().all(|c| !c.is_lowercase()) {
+        return correction.to_uppercase();
+    }
+
+    let mut chars = correction.chars();
+    match (original.chars().next(), chars.next()) {
+        (Some(first), Some(c)) if first.is_uppercase() => c.to_uppercase().chain(chars).collect(),
+        _ => correction.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_text_finds_misspellings() {
+        let found = check_text(
+            "Enable FileVault to protect data. Seperate instructions are availible.",
+            &MisspellingOptions::default(),
+        );
+        let words: Vec<(&str, &str)> = found
+            .iter()
+            .map(|m| (m.word.as_str(), m.suggestion.as_str()))
+            .collect();
+        assert_eq!(words, vec![("Seperate", "Separate"), ("availible", "available")]);
+        assert_eq!(found[0].offset, 34);
+    }
+
+    #[test]
+    fn test_project_dictionary() {
+        let options = MisspellingOptions {
+            words: vec!["teh".to_string()],
+            corrections: HashMap::from([("fleetdm".to_string(), "Fleet".to_string())]),
+        };
+        let found = check_text("teh fleetdm agent", &options);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].suggestion, "Fleet");
+    }
+}
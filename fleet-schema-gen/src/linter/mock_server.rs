@@ -0,0 +1,483 @@
+//! Mock Fleet API server (`fleet-schema-gen mock-server`).
+//!
+//! Answers the endpoints `fleetctl gitops` uses to apply a repository
+//! (config, team/policy/query/label specs, with or without `dry_run`), the
+//! teams API, and the labels and host count endpoints used by `impact`,
+//! with canned responses, so CI for a GitOps repository can exercise its
+//! apply and drift workflows without a real Fleet instance.
+//!
+//! State lives in memory: applied team and label specs are visible to later
+//! requests, dry runs change nothing. `GET /mock/requests` returns every
+//! request received so far, for tests to assert on what was applied.
+//!
+//! ```yaml
+//! # --seed: what the server knows about at startup
+//! version: 4.74.0
+//! teams: [Workstations, Servers]
+//! labels: [macOS 14+]
+//! hosts: 120
+//! ```
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// API prefixes Fleet serves (clients use either).
+const API_PREFIXES: &[&str] = &["/api/v1/fleet/", "/api/latest/fleet/"];
+
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+/// Token returned by `POST /login`.
+pub const MOCK_TOKEN: &str = "mock-fleet-token";
+
+/// Initial server state, from `--seed`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MockSeed {
+    pub version: String,
+    pub teams: Vec<String>,
+    pub labels: Vec<String>,
+    /// Host count reported for every `hosts/count` query
+    pub hosts: u64,
+}
+
+impl Default for MockSeed {
+    fn default() -> Self {
+        Self {
+            version: super::bundled::SNAPSHOT_VERSION.to_string(),
+            teams: Vec::new(),
+            labels: Vec::new(),
+            hosts: 0,
+        }
+    }
+}
+
+impl MockSeed {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
+/// A request the server received.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub dry_run: bool,
+    pub body: Value,
+}
+
+#[derive(Debug)]
+struct Team {
+    id: u64,
+    name: String,
+    /// Last applied spec
+    spec: Value,
+}
+
+impl Team {
+    fn to_json(&self) -> Value {
+        let mut team = match &self.spec {
+            Value::Object(spec) => spec.clone(),
+            _ => serde_json::Map::new(),
+        };
+        team.insert("id".to_string(), json!(self.id));
+        team.insert("name".to_string(), json!(self.name));
+        team.entry("host_count").or_insert(json!(0));
+        Value::Object(team)
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    version: String,
+    config: Value,
+    teams: Vec<Team>,
+    labels: Vec<(u64, String)>,
+    hosts: u64,
+    next_id: u64,
+    requests: Vec<RecordedRequest>,
+}
+
+/// A JSON response.
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// Error in Fleet's format.
+    fn error(status: u16, message: &str, reason: &str) -> Self {
+        Self { status, body: json!({ "message": message, "errors": [{ "name": "base", "reason": reason }] }) }
+    }
+
+    fn not_found(reason: &str) -> Self {
+        Self::error(404, "Resource Not Found", reason)
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            413 => "Payload Too Large",
+            431 => "Request Header Fields Too Large",
+            _ => "Error",
+        }
+    }
+}
+
+/// Recursively merge `patch` into `target`, like a config PATCH.
+fn merge(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+/// The mock server's state and request handling.
+pub struct MockFleet {
+    state: Mutex<State>,
+    /// Required bearer token; any token is accepted when unset
+    token: Option<String>,
+}
+
+impl MockFleet {
+    pub fn new(seed: MockSeed, token: Option<String>) -> Self {
+        let teams: Vec<Team> = seed
+            .teams
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| Team { id: idx as u64 + 1, spec: json!({ "name": name }), name })
+            .collect();
+        let labels: Vec<(u64, String)> =
+            seed.labels.into_iter().enumerate().map(|(idx, name)| (idx as u64 + 1, name)).collect();
+        let next_id = teams.len().max(labels.len()) as u64 + 1;
+        let state = State {
+            version: seed.version,
+            config: json!({
+                "org_info": { "org_name": "Mock Fleet", "org_logo_url": "" },
+                "server_settings": { "server_url": "", "enable_analytics": false },
+                "features": { "enable_host_users": true, "enable_software_inventory": true },
+            }),
+            teams,
+            labels,
+            hosts: seed.hosts,
+            next_id,
+            requests: Vec::new(),
+        };
+        Self { state: Mutex::new(state), token }
+    }
+
+    /// Answer one request. `target` is the request path with its query string.
+    pub fn handle(&self, method: &str, target: &str, authorization: Option<&str>, body: &[u8]) -> Response {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let dry_run = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .any(|(key, value)| key == "dry_run" && matches!(value, "true" | "1"));
+        let body: Value = if body.iter().all(u8::is_ascii_whitespace) {
+            Value::Null
+        } else {
+            match serde_json::from_slice(body) {
+                Ok(body) => body,
+                Err(e) => return Response::error(400, "Bad request", &format!("Invalid JSON body: {}", e)),
+            }
+        };
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if path == "/mock/requests" {
+            return Response::ok(json!({ "requests": state.requests }));
+        }
+        state.requests.push(RecordedRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            dry_run,
+            body: body.clone(),
+        });
+
+        let Some(endpoint) = API_PREFIXES.iter().find_map(|prefix| path.strip_prefix(prefix)) else {
+            return Response::not_found(&format!("{} is not a Fleet API path", path));
+        };
+        let endpoint = endpoint.trim_end_matches('/');
+
+        if endpoint != "login" {
+            let token = authorization.and_then(|value| value.strip_prefix("Bearer ")).map(str::trim);
+            let authorized = match &self.token {
+                Some(expected) => token == Some(expected.as_str()),
+                None => token.is_some_and(|token| !token.is_empty()),
+            };
+            if !authorized {
+                return Response::error(401, "Authentication required", "Authentication required");
+            }
+        }
+
+        let user = json!({ "id": 1, "name": "Mock Admin", "email": "admin@example.com", "global_role": "admin" });
+        match (method, endpoint) {
+            ("GET", "version") => Response::ok(json!({
+                "version": state.version,
+                "branch": "mock",
+                "revision": "mock",
+                "go_version": "",
+                "build_date": "",
+                "build_user": "",
+            })),
+            ("POST", "login") => Response::ok(json!({ "user": user, "token": MOCK_TOKEN, "available_teams": [] })),
+            ("GET", "me") => Response::ok(json!({ "user": user, "available_teams": [] })),
+
+            ("GET", "config") => Response::ok(state.config.clone()),
+            ("PATCH", "config") => {
+                let mut config = state.config.clone();
+                merge(&mut config, body);
+                if !dry_run {
+                    state.config = config.clone();
+                }
+                Response::ok(config)
+            }
+
+            ("POST", "spec/teams") => {
+                let specs = body["specs"].as_array().cloned().unwrap_or_default();
+                let mut ids = serde_json::Map::new();
+                for spec in specs {
+                    let Some(name) = spec["name"].as_str().map(str::to_string) else {
+                        return Response::error(400, "Validation Failed", "Team spec without a name");
+                    };
+                    let existing = state.teams.iter().position(|team| team.name == name);
+                    let id = match existing {
+                        Some(idx) if !dry_run => {
+                            state.teams[idx].spec = spec;
+                            Some(state.teams[idx].id)
+                        }
+                        Some(idx) => Some(state.teams[idx].id),
+                        // Dry runs don't create teams, so new ones have no ID yet
+                        None if dry_run => None,
+                        None => {
+                            let id = state.next_id;
+                            state.next_id += 1;
+                            state.teams.push(Team { id, name: name.clone(), spec });
+                            Some(id)
+                        }
+                    };
+                    if let Some(id) = id {
+                        ids.insert(name, json!(id));
+                    }
+                }
+                Response::ok(json!({ "team_ids_by_name": ids }))
+            }
+            ("GET", "teams") => Response::ok(json!({ "teams": state.teams.iter().map(Team::to_json).collect::<Vec<_>>() })),
+            (method, endpoint) if endpoint.starts_with("teams/") => {
+                let id = endpoint["teams/".len()..].parse::<u64>().ok();
+                let Some(idx) = state.teams.iter().position(|team| Some(team.id) == id) else {
+                    return Response::not_found(&format!("Team {} was not found in the datastore", &endpoint["teams/".len()..]));
+                };
+                match method {
+                    "GET" => Response::ok(json!({ "team": state.teams[idx].to_json() })),
+                    "DELETE" => {
+                        if !dry_run {
+                            state.teams.remove(idx);
+                        }
+                        Response::ok(json!({}))
+                    }
+                    _ => Response::not_found(&format!("{} {} is not supported by the mock server", method, path)),
+                }
+            }
+
+            ("GET", "labels") => Response::ok(json!({
+                "labels": state.labels.iter().map(|(id, name)| json!({ "id": id, "name": name })).collect::<Vec<_>>(),
+            })),
+            ("POST", "spec/labels") => {
+                if !dry_run {
+                    for spec in body["specs"].as_array().into_iter().flatten() {
+                        if let Some(name) = spec["name"].as_str() {
+                            if !state.labels.iter().any(|(_, existing)| existing == name) {
+                                let id = state.next_id;
+                                state.next_id += 1;
+                                state.labels.push((id, name.to_string()));
+                            }
+                        }
+                    }
+                }
+                Response::ok(json!({}))
+            }
+            ("POST", "spec/policies") | ("POST", "spec/queries") => Response::ok(json!({})),
+            ("GET", "hosts/count") => Response::ok(json!({ "count": state.hosts })),
+
+            _ => Response::not_found(&format!("{} {} is not supported by the mock server", method, path)),
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+async fn respond(stream: &mut TcpStream, response: Response) -> std::io::Result<()> {
+    let body = response.body.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason(),
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Read one HTTP/1.1 request from `stream` and answer it.
+async fn handle_connection(mut stream: TcpStream, fleet: Arc<MockFleet>) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let header_end = loop {
+        if let Some(pos) = find(&buf, b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return respond(&mut stream, Response::error(431, "Bad request", "Request headers too large")).await;
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (method, target) = (request_line.next().unwrap_or_default(), request_line.next().unwrap_or_default());
+    let header = |name: &str| {
+        lines.clone().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    };
+
+    let length: usize = header("content-length").and_then(|value| value.parse().ok()).unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return respond(&mut stream, Response::error(413, "Bad request", "Request body too large")).await;
+    }
+    // curl waits for this before sending large bodies
+    if header("expect").is_some_and(|value| value.eq_ignore_ascii_case("100-continue")) {
+        stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").await?;
+    }
+    while buf.len() < header_end + length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let body = &buf[header_end..buf.len().min(header_end + length)];
+
+    let response = fleet.handle(method, target, header("authorization"), body);
+    tracing::info!("{} {} -> {}", method, target, response.status);
+    respond(&mut stream, response).await
+}
+
+/// Serve requests on `listener` until the process is stopped.
+pub async fn serve(listener: TcpListener, fleet: Arc<MockFleet>) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await.context("Failed to accept a connection")?;
+        let fleet = fleet.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, fleet).await {
+                tracing::debug!("Mock server connection failed: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::impact::{CountKey, FleetApi};
+
+    fn seeded() -> MockFleet {
+        let seed = MockSeed {
+            teams: vec!["Workstations".to_string()],
+            labels: vec!["macOS 14+".to_string()],
+            hosts: 120,
+            ..MockSeed::default()
+        };
+        MockFleet::new(seed, Some("secret".to_string()))
+    }
+
+    fn post(fleet: &MockFleet, method: &str, target: &str, body: Value) -> Response {
+        fleet.handle(method, target, Some("Bearer secret"), body.to_string().as_bytes())
+    }
+
+    #[test]
+    fn test_mock_fleet() {
+        let fleet = seeded();
+        assert_eq!(fleet.handle("GET", "/api/v1/fleet/teams", None, b"").status, 401);
+        assert_eq!(fleet.handle("GET", "/api/v1/fleet/teams", Some("Bearer wrong"), b"").status, 401);
+        let login = fleet.handle("POST", "/api/v1/fleet/login", None, br#"{"email":"a","password":"b"}"#);
+        assert_eq!(login.body["token"], MOCK_TOKEN);
+
+        // Dry runs report existing teams and change nothing
+        let specs = json!({ "specs": [{ "name": "Workstations", "features": {} }, { "name": "Servers" }] });
+        let dry_run = post(&fleet, "POST", "/api/latest/fleet/spec/teams?dry_run=true", specs.clone());
+        assert_eq!(dry_run.body, json!({ "team_ids_by_name": { "Workstations": 1 } }));
+        assert_eq!(post(&fleet, "GET", "/api/latest/fleet/teams", Value::Null).body["teams"].as_array().unwrap().len(), 1);
+
+        let applied = post(&fleet, "POST", "/api/latest/fleet/spec/teams", specs);
+        assert_eq!(applied.body, json!({ "team_ids_by_name": { "Workstations": 1, "Servers": 2 } }));
+        let team = post(&fleet, "GET", "/api/v1/fleet/teams/1", Value::Null);
+        assert_eq!(team.body["team"]["features"], json!({}));
+        assert_eq!(post(&fleet, "DELETE", "/api/v1/fleet/teams/2", Value::Null).status, 200);
+        assert_eq!(post(&fleet, "GET", "/api/v1/fleet/teams/2", Value::Null).status, 404);
+
+        let patch = json!({ "org_info": { "org_name": "Acme" } });
+        let config = post(&fleet, "PATCH", "/api/latest/fleet/config?dry_run=true", patch);
+        assert_eq!(config.body["org_info"]["org_name"], "Acme");
+        assert_eq!(config.body["org_info"]["org_logo_url"], "");
+        assert_eq!(post(&fleet, "GET", "/api/latest/fleet/config", Value::Null).body["org_info"]["org_name"], "Mock Fleet");
+
+        assert_eq!(post(&fleet, "POST", "/api/latest/fleet/spec/policies", json!({ "specs": [] })).status, 200);
+        assert_eq!(post(&fleet, "GET", "/api/latest/fleet/software/titles", Value::Null).status, 404);
+        assert_eq!(fleet.handle("POST", "/api/latest/fleet/spec/queries", Some("Bearer secret"), b"{").status, 400);
+
+        let requests = post(&fleet, "GET", "/mock/requests", Value::Null).body;
+        let dry_runs: Vec<&str> = requests["requests"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|request| request["dry_run"] == true)
+            .map(|request| request["path"].as_str().unwrap())
+            .collect();
+        assert_eq!(dry_runs, vec!["/api/latest/fleet/spec/teams", "/api/latest/fleet/config"]);
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, Arc::new(seeded())));
+
+        let api = FleetApi::new(&server, "secret").unwrap();
+        let teams = api.teams().await.unwrap();
+        assert_eq!((teams[0].name.as_str(), teams[0].id), ("Workstations", Some(1)));
+        assert_eq!(api.label_ids().await.unwrap().get("macOS 14+"), Some(&1));
+        let key = CountKey { team: Some(1), platform: Some("darwin".to_string()), label: None };
+        assert_eq!(api.host_count(&key).await.unwrap(), 120);
+
+        assert!(FleetApi::new(&server, "wrong").unwrap().teams().await.is_err());
+    }
+}
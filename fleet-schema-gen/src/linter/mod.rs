@@ -1,15 +1,56 @@
 pub mod config;
+pub mod constant_result;
 pub mod error;
+pub mod event_tables;
 pub mod init;
+pub mod ci;
 pub mod rules;
 pub mod engine;
 pub mod fleet_config;
 pub mod osquery;
 pub mod migrate;
+pub mod benchmarks;
+pub mod coverage;
+pub mod harness;
+pub mod fixtures;
+pub mod osqueryi;
+pub mod misspellings;
+pub mod repo;
+pub mod docs;
+pub mod export;
+pub mod iac;
+pub mod software_report;
+pub mod urls;
+pub mod package_hash;
+pub mod profiles;
+pub mod fleet_vars;
+pub mod validate;
+pub mod effective;
+pub mod bundled;
+pub mod presets;
+pub mod walk;
+pub mod file_role;
+pub mod custom_rules;
+pub mod packs;
+pub mod library;
+pub mod metrics;
+pub mod fix;
+pub mod sort;
+pub mod duplicates;
+pub mod file_size;
+pub mod platform_coverage;
+pub mod sql;
+pub mod sql_dialect;
+pub mod sql_format;
+pub mod sql_functions;
 
 pub use config::{FleetLintConfig, ConfigError};
 pub use error::{LintError, LintResult, Severity};
 pub use init::init as init_config;
 pub use rules::{Rule, RuleSet};
-pub use engine::Linter;
+pub use engine::{LintEngineError, Linter};
 pub use fleet_config::FleetConfig;
+pub mod schedule;
+pub mod impact;
+pub mod mock_server;
+pub mod owners;
@@ -7,6 +7,21 @@ pub struct OsqueryTable {
     pub description: &'static str,
 }
 
+impl OsqueryTable {
+    /// Whether the fleetd Chrome extension implements this table.
+    pub fn supports_chrome(&self) -> bool {
+        self.platforms.contains(&"chrome")
+    }
+}
+
+/// Whether a table can be queried on ChromeOS.
+///
+/// Tables missing from the registry are treated as unsupported: the Chrome
+/// extension only implements a small, fixed set of tables.
+pub fn is_chrome_table(table: &str) -> bool {
+    OSQUERY_TABLES.get(table).is_some_and(|t| t.supports_chrome())
+}
+
 /// osquery table compatibility matrix
 /// Source: https://osquery.io/schema/
 pub static OSQUERY_TABLES: Lazy<HashMap<&'static str, OsqueryTable>> = Lazy::new(|| {
@@ -89,7 +104,7 @@ pub static OSQUERY_TABLES: Lazy<HashMap<&'static str, OsqueryTable>> = Lazy::new
     // Cross-platform tables
     tables.insert("users", OsqueryTable {
         name: "users",
-        platforms: vec!["darwin", "linux", "windows"],
+        platforms: vec!["darwin", "linux", "windows", "chrome"],
         description: "Local user accounts",
     });
 
@@ -101,13 +116,13 @@ pub static OSQUERY_TABLES: Lazy<HashMap<&'static str, OsqueryTable>> = Lazy::new
 
     tables.insert("system_info", OsqueryTable {
         name: "system_info",
-        platforms: vec!["darwin", "linux", "windows"],
+        platforms: vec!["darwin", "linux", "windows", "chrome"],
         description: "System information",
     });
 
     tables.insert("os_version", OsqueryTable {
         name: "os_version",
-        platforms: vec!["darwin", "linux", "windows"],
+        platforms: vec!["darwin", "linux", "windows", "chrome"],
         description: "Operating system version",
     });
 
@@ -388,7 +403,7 @@ pub static OSQUERY_TABLES: Lazy<HashMap<&'static str, OsqueryTable>> = Lazy::new
 
     tables.insert("disk_info", OsqueryTable {
         name: "disk_info",
-        platforms: vec!["darwin", "linux", "windows"],
+        platforms: vec!["darwin", "linux", "windows", "chrome"],
         description: "Physical disk information",
     });
 
@@ -528,7 +543,7 @@ pub static OSQUERY_TABLES: Lazy<HashMap<&'static str, OsqueryTable>> = Lazy::new
 
     tables.insert("screenlock", OsqueryTable {
         name: "screenlock",
-        platforms: vec!["darwin"],
+        platforms: vec!["darwin", "chrome"],
         description: "Screen lock settings",
     });
 
@@ -684,14 +699,39 @@ pub static OSQUERY_TABLES: Lazy<HashMap<&'static str, OsqueryTable>> = Lazy::new
 
     // =========================================================================
     // ChromeOS tables
+    // Only tables implemented by the fleetd Chrome extension list "chrome"
     // =========================================================================
 
     tables.insert("chrome_extension_content_scripts", OsqueryTable {
         name: "chrome_extension_content_scripts",
-        platforms: vec!["darwin", "linux", "windows", "chrome"],
+        platforms: vec!["darwin", "linux", "windows"],
         description: "Chrome extension content scripts",
     });
 
+    tables.insert("client_certificates", OsqueryTable {
+        name: "client_certificates",
+        platforms: vec!["chrome"],
+        description: "ChromeOS client certificates",
+    });
+
+    tables.insert("network_interfaces", OsqueryTable {
+        name: "network_interfaces",
+        platforms: vec!["chrome"],
+        description: "ChromeOS network interfaces",
+    });
+
+    tables.insert("privacy_preferences", OsqueryTable {
+        name: "privacy_preferences",
+        platforms: vec!["chrome"],
+        description: "ChromeOS privacy preferences",
+    });
+
+    tables.insert("system_state", OsqueryTable {
+        name: "system_state",
+        platforms: vec!["chrome"],
+        description: "ChromeOS system idle and screen lock state",
+    });
+
     // =========================================================================
     // Package manager tables
     // =========================================================================
@@ -802,7 +842,7 @@ pub static OSQUERY_TABLES: Lazy<HashMap<&'static str, OsqueryTable>> = Lazy::new
 
     tables.insert("osquery_info", OsqueryTable {
         name: "osquery_info",
-        platforms: vec!["darwin", "linux", "windows"],
+        platforms: vec!["darwin", "linux", "windows", "chrome"],
         description: "osquery version info",
     });
 
@@ -844,3 +884,155 @@ pub static OSQUERY_TABLES: Lazy<HashMap<&'static str, OsqueryTable>> = Lazy::new
 
     tables
 });
+
+/// osquery version in which a table was introduced.
+///
+/// Curated by hand from the osquery release notes, so it covers only a few
+/// tables added since 4.0 rather than the full schema history. Tables not
+/// listed here are treated as available in every version, which means
+/// `osquery-version` cannot flag them.
+pub static TABLE_VERSIONS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    HashMap::from([
+        ("windows_security_products", "4.2.0"),
+        ("windows_security_center", "4.4.0"),
+        ("chrome_extension_content_scripts", "4.4.0"),
+        ("connectivity", "4.6.0"),
+        ("es_process_events", "4.7.0"),
+        ("location_services", "4.7.0"),
+        ("windows_update_history", "4.9.0"),
+        ("screenlock", "5.3.0"),
+        ("windows_firewall_rules", "5.6.0"),
+    ])
+});
+
+/// osquery version in which a column was added to an existing table.
+///
+/// Hand-curated like [`TABLE_VERSIONS`]; unlisted columns are never flagged.
+pub static COLUMN_VERSIONS: Lazy<HashMap<(&'static str, &'static str), &'static str>> = Lazy::new(|| {
+    HashMap::from([
+        (("bitlocker_info", "percentage_encrypted"), "4.5.0"),
+        (("users", "is_hidden"), "4.6.0"),
+        (("chrome_extensions", "manifest_hash"), "5.0.1"),
+        (("os_version", "arch"), "5.0.1"),
+        (("os_version", "extra"), "5.8.1"),
+        (("system_info", "emulated_cpu_type"), "5.10.2"),
+    ])
+});
+
+/// A table or column whose availability depends on the osquery version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionRequirement {
+    /// `table` or `table.column`
+    pub item: String,
+    pub version: &'static str,
+}
+
+/// Version requirements for every versioned table/column used in a query,
+/// newest first.
+pub fn query_version_requirements(query: &str) -> Vec<VersionRequirement> {
+    let query_lower = query.to_lowercase();
+    let table_re = regex::Regex::new(r"\b(?:from|join)\s+(\w+)").unwrap();
+    let word_re = regex::Regex::new(r"\w+").unwrap();
+
+    let tables: Vec<&str> = table_re
+        .captures_iter(&query_lower)
+        .filter_map(|cap| cap.get(1).map(|m| m.as_str()))
+        .collect();
+    let words: Vec<&str> = word_re.find_iter(&query_lower).map(|m| m.as_str()).collect();
+
+    let mut requirements: Vec<VersionRequirement> = Vec::new();
+    for table in &tables {
+        if let Some(version) = TABLE_VERSIONS.get(table) {
+            requirements.push(VersionRequirement { item: table.to_string(), version });
+        }
+    }
+    for ((table, column), version) in COLUMN_VERSIONS.iter() {
+        if tables.contains(table) && words.contains(column) {
+            requirements.push(VersionRequirement { item: format!("{}.{}", table, column), version });
+        }
+    }
+
+    requirements.sort_by(|a, b| compare_versions(b.version, a.version).then(a.item.cmp(&b.item)));
+    requirements.dedup();
+    requirements
+}
+
+/// Tables a query reads (`FROM`/`JOIN`), lowercased, in order of first use.
+pub fn query_tables(query: &str) -> Vec<String> {
+    static TABLE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"\b(?:from|join)\s+(\w+)").unwrap());
+
+    let mut tables: Vec<String> = Vec::new();
+    for cap in TABLE.captures_iter(&query.to_lowercase()) {
+        if !tables.iter().any(|t| t == &cap[1]) {
+            tables.push(cap[1].to_string());
+        }
+    }
+    tables
+}
+
+/// Compare dotted osquery versions numerically (`5.10.2` > `5.9.1`).
+///
+/// Missing components count as zero and non-numeric suffixes are ignored.
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |v: &str| -> Vec<u32> {
+        v.trim()
+            .split('.')
+            .map(|part| {
+                part.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0)
+            })
+            .collect()
+    };
+
+    let (a, b) = (parse(a), parse(b));
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|ord| ord.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("5.10.2", "5.9.1"), Ordering::Greater);
+        assert_eq!(compare_versions("5.0", "5.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("4.9.0", "5.0.1"), Ordering::Less);
+        assert_eq!(compare_versions("5.6.0-rc1", "5.6.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_query_tables() {
+        assert_eq!(
+            query_tables("SELECT * FROM users u JOIN user_groups ug USING (uid) JOIN Users ON 1 WHERE uid IN (SELECT uid FROM processes);"),
+            vec!["users", "user_groups", "processes"]
+        );
+        assert!(query_tables("SELECT 1;").is_empty());
+    }
+
+    #[test]
+    fn test_chrome_tables() {
+        assert!(is_chrome_table("os_version"));
+        assert!(is_chrome_table("client_certificates"));
+        assert!(!is_chrome_table("processes"));
+        assert!(!is_chrome_table("not_a_table"));
+    }
+
+    #[test]
+    fn test_query_version_requirements() {
+        let reqs = query_version_requirements(
+            "SELECT name, arch FROM os_version JOIN windows_firewall_rules USING (name);",
+        );
+        let items: Vec<&str> = reqs.iter().map(|r| r.item.as_str()).collect();
+        assert_eq!(items, vec!["windows_firewall_rules", "os_version.arch"]);
+
+        assert!(query_version_requirements("SELECT 1 FROM alf;").is_empty());
+    }
+}
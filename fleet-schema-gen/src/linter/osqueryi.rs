@@ -0,0 +1,226 @@
+//! Run policy/query SQL against a locally installed `osqueryi`.
+//!
+//! Results depend entirely on the machine running the linter (its OS,
+//! osquery version, and state), so everything reported here is marked as
+//! host-specific rather than treated as a property of the config.
+
+use super::error::LintError;
+use super::fleet_config::{FleetConfig, LabelOrPath, PolicyOrPath, QueryOrPath};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Number of result rows included in reports.
+pub const SAMPLE_ROWS: usize = 3;
+
+/// How long a single query may run before osqueryi is killed.
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Locate `osqueryi`, honoring the `OSQUERYI` environment variable.
+pub fn find_osqueryi() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("OSQUERYI") {
+        let path = PathBuf::from(path);
+        return path.is_file().then_some(path);
+    }
+
+    let exe = if cfg!(windows) { "osqueryi.exe" } else { "osqueryi" };
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(exe))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Pipe SQL into `osqueryi --json` and return the result rows.
+///
+/// Returns the error text reported by osquery (e.g. unknown table or
+/// column on this host) when the query fails, or a timeout error when it
+/// runs longer than [`QUERY_TIMEOUT`].
+pub fn run_query(osqueryi: &Path, sql: &str) -> Result<Vec<serde_json::Value>, String> {
+    run_query_with_timeout(osqueryi, sql, QUERY_TIMEOUT)
+}
+
+/// [`run_query`] with a custom deadline, after which osqueryi is killed.
+pub fn run_query_with_timeout(osqueryi: &Path, sql: &str, timeout: Duration) -> Result<Vec<serde_json::Value>, String> {
+    let mut child = Command::new(osqueryi)
+        .arg("--json")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", osqueryi.display(), e))?;
+
+    {
+        let mut stdin = child.stdin.take().ok_or("Failed to open osqueryi stdin")?;
+        let mut statement = sql.trim().to_string();
+        if !statement.ends_with(';') {
+            statement.push(';');
+        }
+        writeln!(stdin, "{}", statement).map_err(|e| e.to_string())?;
+    }
+
+    // Drain the pipes on threads so a chatty osqueryi can't block on a
+    // full pipe while we wait for it
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Query timed out after {}s", timeout.as_secs_f32()));
+            }
+            None => std::thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    let stdout = String::from_utf8_lossy(&stdout);
+    let stderr = String::from_utf8_lossy(&stderr);
+
+    // osqueryi reports SQL errors on stderr, sometimes with a zero exit code
+    let error_text = stderr
+        .lines()
+        .map(|l| l.trim())
+        .find(|l| l.starts_with("Error") || l.contains("no such table") || l.contains("no such column"));
+
+    if let Some(error) = error_text {
+        return Err(error.to_string());
+    }
+    if !status.success() {
+        return Err(format!("osqueryi exited with {}: {}", status, stderr.trim()));
+    }
+
+    parse_json_rows(&stdout)
+}
+
+/// Parse the JSON array printed by `osqueryi --json`.
+fn parse_json_rows(stdout: &str) -> Result<Vec<serde_json::Value>, String> {
+    let trimmed = stdout.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Ignore anything printed before the JSON array (e.g. warnings)
+    let start = trimmed.find('[').ok_or_else(|| format!("Unexpected osqueryi output: {}", trimmed))?;
+    serde_json::from_str(&trimmed[start..]).map_err(|e| format!("Failed to parse osqueryi output: {}", e))
+}
+
+/// Format the first few rows for display.
+pub fn format_sample(rows: &[serde_json::Value]) -> String {
+    rows.iter()
+        .take(SAMPLE_ROWS)
+        .map(|row| row.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Execute every inline policy, query, and label in a config on this host.
+///
+/// Failures are reported as warnings (they may only apply to this host);
+/// successful runs as info with sample rows.
+pub fn exec_config(config: &FleetConfig, file: &Path, osqueryi: &Path) -> Vec<LintError> {
+    let mut items: Vec<(String, &str)> = Vec::new();
+
+    if let Some(policies) = &config.policies {
+        for policy_or_path in policies {
+            if let PolicyOrPath::Policy(policy) = policy_or_path {
+                if let Some(query) = &policy.query {
+                    items.push((format!("Policy '{}'", policy.name.as_deref().unwrap_or("unnamed")), query));
+                }
+            }
+        }
+    }
+
+    if let Some(queries) = &config.queries {
+        for query_or_path in queries {
+            if let QueryOrPath::Query(query) = query_or_path {
+                if let Some(sql) = &query.query {
+                    items.push((format!("Query '{}'", query.name.as_deref().unwrap_or("unnamed")), sql));
+                }
+            }
+        }
+    }
+
+    if let Some(labels) = &config.labels {
+        for label_or_path in labels {
+            if let LabelOrPath::Label(label) = label_or_path {
+                if let Some(query) = &label.query {
+                    items.push((format!("Label '{}'", label.name.as_deref().unwrap_or("unnamed")), query));
+                }
+            }
+        }
+    }
+
+    items
+        .into_iter()
+        .map(|(item_name, sql)| match run_query(osqueryi, sql) {
+            Ok(rows) => {
+                let mut error = LintError::info(
+                    format!("[host-specific] {} returned {} row(s) on this host", item_name, rows.len()),
+                    file,
+                );
+                if !rows.is_empty() {
+                    error = error.with_help(format!("Sample rows from this host:\n{}", format_sample(&rows)));
+                }
+                error
+            }
+            Err(e) => LintError::warning(
+                format!("[host-specific] {} failed in osqueryi: {}", item_name, e),
+                file,
+            )
+            .with_help("Results come from the local osqueryi and may differ on other hosts or platforms"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_rows() {
+        let rows = parse_json_rows("[\n  {\"global_state\":\"1\"}\n]\n").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["global_state"], "1");
+
+        assert!(parse_json_rows("").unwrap().is_empty());
+        assert!(parse_json_rows("W1015 warning\n[]").unwrap().is_empty());
+        assert!(parse_json_rows("garbage").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_query_times_out() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let osqueryi = temp_dir.path().join("osqueryi");
+        std::fs::write(&osqueryi, "#!/bin/sh\nexec sleep 10\n").unwrap();
+        std::fs::set_permissions(&osqueryi, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = Instant::now();
+        let error = run_query_with_timeout(&osqueryi, "SELECT 1", Duration::from_millis(200)).unwrap_err();
+        assert!(error.contains("timed out"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_format_sample_limits_rows() {
+        let rows: Vec<serde_json::Value> = (0..5).map(|i| serde_json::json!({ "n": i })).collect();
+        assert_eq!(format_sample(&rows).lines().count(), SAMPLE_ROWS);
+    }
+}
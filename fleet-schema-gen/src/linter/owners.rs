@@ -0,0 +1,400 @@
+//! Who owns the policies and queries of a GitOps repository
+//! (`fleet-schema-gen owners`, owner metadata in `lint --format json`).
+//!
+//! Ownership combines three sources:
+//!
+//! - CODEOWNERS rules matching the defining file (`.github/CODEOWNERS`,
+//!   `CODEOWNERS` or `docs/CODEOWNERS`, in the repository or a parent);
+//! - the team files whose `path:` references lead to the file;
+//! - `git blame`: the people who last modified the item's lines.
+//!
+//! CI can route a failing finding to these owners instead of to whoever
+//! happened to open the pull request.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use super::error::LintReport;
+use super::repo::GitopsRepo;
+
+/// Where GitHub looks for CODEOWNERS, relative to the repository root.
+pub const CODEOWNERS_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Modifiers listed per item.
+const MAX_MODIFIERS: usize = 3;
+
+/// Parsed CODEOWNERS rules; the last matching rule wins.
+#[derive(Debug, Clone)]
+pub struct CodeOwners {
+    /// Directory patterns are relative to.
+    pub base: PathBuf,
+    rules: Vec<(Regex, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Find CODEOWNERS in `start` or its parents.
+    pub fn discover(start: &Path) -> Option<Self> {
+        let start = start.canonicalize().ok()?;
+        start.ancestors().find_map(|dir| {
+            CODEOWNERS_LOCATIONS.iter().find_map(|location| {
+                let content = std::fs::read_to_string(dir.join(location)).ok()?;
+                Some(Self::parse(&content, dir))
+            })
+        })
+    }
+
+    pub fn parse(content: &str, base: &Path) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = pattern_regex(parts.next()?)?;
+                let owners = parts.take_while(|p| !p.starts_with('#')).map(str::to_string).collect();
+                Some((pattern, owners))
+            })
+            .collect();
+        Self { base: base.to_path_buf(), rules }
+    }
+
+    /// Owners of a file (absolute, or relative to the working directory).
+    pub fn owners(&self, file: &Path) -> Vec<String> {
+        let Some(relative) = file
+            .canonicalize()
+            .ok()
+            .and_then(|file| file.strip_prefix(&self.base).ok().map(Path::to_path_buf))
+        else {
+            return Vec::new();
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.is_match(&relative))
+            .map(|(_, owners)| owners.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// CODEOWNERS (gitignore-style) pattern as a regex over `/`-separated
+/// paths relative to the base directory.
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    // A slash anywhere but the end anchors the pattern to the base
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+
+    let mut body = String::new();
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    body.push_str("(?:.*/)?");
+                } else {
+                    body.push_str(".*");
+                }
+            }
+            '*' => body.push_str("[^/]*"),
+            '?' => body.push_str("[^/]"),
+            c => body.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    let prefix = if anchored { "^" } else { "^(?:.*/)?" };
+    let suffix = if directory {
+        "/.*"
+    } else if trimmed.ends_with("/*") {
+        ""
+    } else {
+        "(?:/.*)?"
+    };
+    Regex::new(&format!("{}{}{}$", prefix, body, suffix)).ok()
+}
+
+/// Author of one line according to `git blame`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlameLine {
+    pub author: String,
+    /// Commit time (Unix seconds).
+    pub time: i64,
+}
+
+/// `git blame` of a file, one entry per line; `None` outside git or for
+/// untracked files.
+pub fn blame(file: &Path) -> Option<Vec<BlameLine>> {
+    let dir = file.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(["blame", "--line-porcelain", "--"])
+        .arg(file.file_name()?)
+        .output()
+        .ok()?;
+    output.status.success().then(|| parse_blame(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_blame(porcelain: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut author = String::new();
+    let mut time = 0;
+    for line in porcelain.lines() {
+        if line.starts_with('\t') {
+            lines.push(BlameLine { author: std::mem::take(&mut author), time });
+        } else if let Some(name) = line.strip_prefix("author ") {
+            author = name.to_string();
+        } else if let Some(t) = line.strip_prefix("author-time ") {
+            time = t.parse().unwrap_or(0);
+        }
+    }
+    lines
+}
+
+/// Distinct authors of lines `range` (0-indexed), most recent first.
+fn modifiers(blame: &[BlameLine], range: std::ops::Range<usize>) -> (Vec<String>, Option<i64>) {
+    let mut lines: Vec<&BlameLine> = blame.get(range).map(|lines| lines.iter().collect()).unwrap_or_default();
+    lines.sort_by_key(|line| std::cmp::Reverse(line.time));
+    let mut authors: Vec<String> = Vec::new();
+    for line in &lines {
+        if !authors.contains(&line.author) && !line.author.starts_with("Not Committed") {
+            authors.push(line.author.clone());
+        }
+    }
+    authors.truncate(MAX_MODIFIERS);
+    (authors, lines.first().map(|l| l.time))
+}
+
+/// Lines (0-indexed) of the list item named `name` in a YAML file.
+pub fn item_lines(source: &str, name: &str) -> Option<std::ops::Range<usize>> {
+    let lines: Vec<&str> = source.lines().collect();
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let is_name = |line: &str| {
+        let value = line.trim_start().trim_start_matches("- ").strip_prefix("name:")?;
+        (value.trim().trim_matches(|c| c == '"' || c == '\'') == name).then_some(())
+    };
+
+    let name_line = lines.iter().position(|line| is_name(line).is_some())?;
+    let start = (0..=name_line).rev().find(|i| lines[*i].trim_start().starts_with("- "))?;
+    let item_indent = indent(lines[start]);
+    let end = (start + 1..lines.len())
+        .find(|i| {
+            let line = lines[*i];
+            !line.trim().is_empty()
+                && (indent(line) < item_indent || (indent(line) == item_indent && line.trim_start().starts_with("- ")))
+        })
+        .unwrap_or(lines.len());
+    Some(start..end)
+}
+
+/// Owners of a policy or query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemOwners {
+    /// `policy` or `query`.
+    pub kind: &'static str,
+    pub name: String,
+    /// Defining file.
+    pub file: PathBuf,
+    /// 1-indexed line of the item in `file`.
+    pub line: Option<usize>,
+    /// Team files the item is part of.
+    pub team_files: Vec<PathBuf>,
+    pub codeowners: Vec<String>,
+    /// Last people to modify the item's lines, most recent first.
+    pub last_modified_by: Vec<String>,
+    /// Date of the last modification (YYYY-MM-DD).
+    pub last_modified: Option<String>,
+}
+
+/// Owners of a lint finding.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FindingOwners {
+    pub codeowners: Vec<String>,
+    /// Team files referencing the file (the file itself for team files).
+    pub team_files: Vec<PathBuf>,
+    /// Last person to modify the finding's line.
+    pub last_modified_by: Option<String>,
+}
+
+/// Ownership sources for a repository, with blame results cached per file.
+pub struct Ownership {
+    codeowners: Option<CodeOwners>,
+    /// Defining file -> team files using it.
+    referenced_by: HashMap<PathBuf, BTreeSet<PathBuf>>,
+    blames: HashMap<PathBuf, Option<Vec<BlameLine>>>,
+}
+
+impl Ownership {
+    /// Ownership for the repository containing `path`; outside a GitOps
+    /// repository there is still CODEOWNERS and blame.
+    pub fn load(path: &Path) -> Self {
+        let repo = super::repo::load_repo(path)
+            .ok()
+            .or_else(|| super::repo::load_repo(&super::repo::find_root(path)?).ok());
+        Self::new(path, repo.as_ref())
+    }
+
+    pub fn new(root: &Path, repo: Option<&GitopsRepo>) -> Self {
+        let mut referenced_by: HashMap<PathBuf, BTreeSet<PathBuf>> = HashMap::new();
+        for team in repo.map(|r| r.teams.as_slice()).unwrap_or_default() {
+            let files = team
+                .policies
+                .iter()
+                .map(|p| &p.file)
+                .chain(team.queries.iter().map(|q| &q.file))
+                .chain(std::iter::once(&team.file));
+            for file in files {
+                referenced_by.entry(canonical(file)).or_default().insert(team.file.clone());
+            }
+        }
+        Self { codeowners: CodeOwners::discover(root), referenced_by, blames: HashMap::new() }
+    }
+
+    fn blame(&mut self, file: &Path) -> Option<&[BlameLine]> {
+        self.blames.entry(canonical(file)).or_insert_with(|| blame(file)).as_deref()
+    }
+
+    fn team_files(&self, file: &Path) -> Vec<PathBuf> {
+        self.referenced_by.get(&canonical(file)).map(|files| files.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    fn codeowners(&self, file: &Path) -> Vec<String> {
+        self.codeowners.as_ref().map(|c| c.owners(file)).unwrap_or_default()
+    }
+
+    /// Owners of a finding at `line` (1-indexed) of `file`.
+    pub fn finding(&mut self, file: &Path, line: Option<usize>) -> FindingOwners {
+        let last_modified_by = line.and_then(|line| {
+            let blame = self.blame(file)?;
+            modifiers(blame, line.saturating_sub(1)..line).0.into_iter().next()
+        });
+        FindingOwners { codeowners: self.codeowners(file), team_files: self.team_files(file), last_modified_by }
+    }
+
+    /// Owners of every policy and query in `repo`, once per defining file.
+    pub fn items(&mut self, repo: &GitopsRepo) -> Vec<ItemOwners> {
+        let mut seen = BTreeSet::new();
+        let mut items = Vec::new();
+        for team in &repo.teams {
+            let policies = team.policies.iter().map(|p| ("policy", p.item.name.as_deref(), &p.file));
+            let queries = team.queries.iter().map(|q| ("query", q.item.name.as_deref(), &q.file));
+            for (kind, name, file) in policies.chain(queries) {
+                let Some(name) = name else { continue };
+                if !seen.insert((kind, name.to_string(), canonical(file))) {
+                    continue;
+                }
+                let range = std::fs::read_to_string(file).ok().and_then(|source| item_lines(&source, name));
+                let (last_modified_by, time) = match (&range, self.blame(file)) {
+                    (Some(range), Some(blame)) => modifiers(blame, range.clone()),
+                    _ => (Vec::new(), None),
+                };
+                items.push(ItemOwners {
+                    kind,
+                    name: name.to_string(),
+                    file: file.clone(),
+                    line: range.map(|r| r.start + 1),
+                    team_files: self.team_files(file),
+                    codeowners: self.codeowners(file),
+                    last_modified_by,
+                    last_modified: time
+                        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                        .map(|t| t.format("%Y-%m-%d").to_string()),
+                });
+            }
+        }
+        items
+    }
+}
+
+fn canonical(file: &Path) -> PathBuf {
+    file.canonicalize().unwrap_or_else(|_| file.to_path_buf())
+}
+
+/// Plain-text owners report.
+pub fn render(items: &[ItemOwners]) -> String {
+    let list = |values: Vec<String>| if values.is_empty() { "-".to_string() } else { values.join(", ") };
+    let mut out = String::new();
+    for item in items {
+        let location = match item.line {
+            Some(line) => format!("{}:{}", item.file.display(), line),
+            None => item.file.display().to_string(),
+        };
+        let kind = if item.kind == "policy" { "Policy" } else { "Query" };
+        out.push_str(&format!("{} '{}'  {}\n", kind, item.name, location));
+        out.push_str(&format!(
+            "  teams: {}\n",
+            list(item.team_files.iter().map(|f| f.display().to_string()).collect())
+        ));
+        out.push_str(&format!("  codeowners: {}\n", list(item.codeowners.clone())));
+        let modified = match &item.last_modified {
+            Some(date) => format!("{} ({})", list(item.last_modified_by.clone()), date),
+            None => list(item.last_modified_by.clone()),
+        };
+        out.push_str(&format!("  last modified by: {}\n", modified));
+    }
+    out
+}
+
+/// `lint --format json` output: the findings of each file, each with the
+/// owners of its location.
+pub fn lint_json(results: &[(String, LintReport)], ownership: &mut Ownership) -> Result<serde_json::Value> {
+    let mut files = Vec::new();
+    for (file, report) in results.iter().filter(|(_, report)| report.total_issues() > 0) {
+        let mut findings = Vec::new();
+        for finding in report.errors.iter().chain(&report.warnings).chain(&report.infos) {
+            let mut value = serde_json::to_value(finding).context("Failed to serialize finding")?;
+            value["owners"] = serde_json::to_value(ownership.finding(&finding.file, finding.line))?;
+            findings.push(value);
+        }
+        files.push(serde_json::json!({ "file": file, "findings": findings }));
+    }
+    Ok(serde_json::Value::Array(files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codeowners() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".github")).unwrap();
+        std::fs::create_dir_all(root.join("lib/policies")).unwrap();
+        std::fs::create_dir_all(root.join("teams")).unwrap();
+        std::fs::write(
+            root.join(".github/CODEOWNERS"),
+            "# Fallback\n*  @acme/platform\n/teams/ @acme/it\npolicies/ @acme/secops # security\nlib/*.yml @acme/lib\n",
+        )
+        .unwrap();
+        for file in ["default.yml", "teams/servers.yml", "lib/policies/disk.yml", "lib/labels.yml"] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+
+        let codeowners = CodeOwners::discover(&root.join("teams")).unwrap();
+        let owners = |file: &str| codeowners.owners(&root.join(file));
+        assert_eq!(owners("default.yml"), vec!["@acme/platform"]);
+        assert_eq!(owners("teams/servers.yml"), vec!["@acme/it"]);
+        assert_eq!(owners("lib/policies/disk.yml"), vec!["@acme/secops"]);
+        assert_eq!(owners("lib/labels.yml"), vec!["@acme/lib"]);
+    }
+
+    #[test]
+    fn test_item_lines_and_blame() {
+        let source = "policies:\n  - name: Disk encryption\n    query: SELECT 1;\n\n  - name: 'Firewall'\n    query: >\n      SELECT 1;\nqueries: []\n";
+        assert_eq!(item_lines(source, "Disk encryption"), Some(1..4));
+        assert_eq!(item_lines(source, "Firewall"), Some(4..7));
+        assert_eq!(item_lines(source, "Gatekeeper"), None);
+
+        let porcelain = "abc 1 1 2\nauthor Ana\nauthor-time 100\n\tpolicies:\n\
+                         def 2 2\nauthor Bo\nauthor-time 200\n\t  - name: x\n\
+                         abc 3 3\nauthor Ana\nauthor-time 100\n\t    query: SELECT 1;\n";
+        let blame = parse_blame(porcelain);
+        assert_eq!(blame.len(), 3);
+        assert_eq!(modifiers(&blame, 0..3), (vec!["Bo".to_string(), "Ana".to_string()], Some(200)));
+    }
+}
@@ -0,0 +1,192 @@
+//! SHA-256 helper for software package definitions (`fleet-schema-gen hash`).
+//!
+//! Downloads the installer referenced by a package's `url:`, computes its
+//! SHA-256, and writes it to `hash_sha256:` while leaving the rest of the
+//! YAML (comments, ordering) untouched.
+
+use super::fleet_config::SoftwarePackage;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::Duration;
+
+/// Time allowed to establish a connection to the installer host.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Longest gap between body chunks before a download counts as stalled.
+pub const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Upper bound for a whole download; installers can be several GB.
+pub const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Outcome of comparing a downloaded installer with the declared hash.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HashStatus {
+    /// No `hash_sha256` declared yet.
+    Missing,
+    /// Declared hash matches the download.
+    Match,
+    /// Declared hash differs from the download.
+    Mismatch { declared: String },
+}
+
+/// Hash result for a package definition.
+#[derive(Debug, Clone)]
+pub struct PackageHash {
+    pub url: String,
+    pub sha256: String,
+    pub size: u64,
+    pub status: HashStatus,
+}
+
+/// Read a package definition and return its `url` and declared hash.
+pub fn read_package(source: &str, file: &Path) -> Result<(String, Option<String>)> {
+    let package: SoftwarePackage = serde_yaml::from_str(source)
+        .with_context(|| format!("Failed to parse {}", file.display()))?;
+    let url = package
+        .url
+        .with_context(|| format!("{} has no url: field (not a software package definition?)", file.display()))?;
+    Ok((url, package.hash_sha256))
+}
+
+/// Download `url` and compute its SHA-256, streaming the body.
+pub async fn download_sha256(url: &str) -> Result<(String, u64)> {
+    let client = reqwest::Client::builder()
+        .user_agent("fleet-schema-gen")
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()?;
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to download {}", url))?;
+
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?
+    {
+        size += chunk.len() as u64;
+        hasher.update(&chunk);
+    }
+
+    Ok((hex(&hasher.finalize()), size))
+}
+
+/// Download the installer for a package definition and compare hashes.
+pub async fn hash_package(source: &str, file: &Path) -> Result<PackageHash> {
+    let (url, declared) = read_package(source, file)?;
+    let (sha256, size) = download_sha256(&url).await?;
+
+    let status = match declared {
+        None => HashStatus::Missing,
+        Some(declared) if declared.trim().eq_ignore_ascii_case(&sha256) => HashStatus::Match,
+        Some(declared) => HashStatus::Mismatch { declared },
+    };
+
+    Ok(PackageHash { url, sha256, size, status })
+}
+
+/// Set `hash_sha256:` in a package definition.
+///
+/// Replaces the value of an existing top-level `hash_sha256:` line, or
+/// inserts one after the top-level `url:` line (at the end otherwise).
+pub fn set_hash(source: &str, sha256: &str) -> String {
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+    let top_level = |line: &str, key: &str| {
+        line.strip_prefix(key)
+            .is_some_and(|rest| rest.starts_with(':'))
+    };
+
+    if let Some(line) = lines.iter_mut().find(|l| top_level(l, "hash_sha256")) {
+        // Keep trailing comments
+        let comment = line
+            .find(" #")
+            .map(|idx| line[idx..].to_string())
+            .unwrap_or_default();
+        *line = format!("hash_sha256: {}{}", sha256, comment);
+    } else {
+        let new_line = format!("hash_sha256: {}", sha256);
+        match lines.iter().position(|l| top_level(l, "url")) {
+            Some(idx) => lines.insert(idx + 1, new_line),
+            None => lines.push(new_line),
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if source.ends_with('\n') || source.is_empty() {
+        updated.push('\n');
+    }
+    updated
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_set_hash_inserts_after_url() {
+        let source = "# Zoom\nurl: https://example.com/zoom.pkg\nself_service: true\n";
+        assert_eq!(
+            set_hash(source, "abc123"),
+            "# Zoom\nurl: https://example.com/zoom.pkg\nhash_sha256: abc123\nself_service: true\n"
+        );
+    }
+
+    #[test]
+    fn test_set_hash_replaces_existing() {
+        let source = "url: https://example.com/zoom.pkg\nhash_sha256: old # pinned\ninstall_script:\n  hash_sha256: keep\n";
+        assert_eq!(
+            set_hash(source, "new"),
+            "url: https://example.com/zoom.pkg\nhash_sha256: new # pinned\ninstall_script:\n  hash_sha256: keep\n"
+        );
+    }
+
+    #[test]
+    fn test_read_package_requires_url() {
+        assert!(read_package("self_service: true\n", Path::new("lib/app.yml")).is_err());
+
+        let (url, hash) = read_package("url: https://example.com/a.msi\nhash_sha256: abc\n", Path::new("lib/app.yml")).unwrap();
+        assert_eq!(url, "https://example.com/a.msi");
+        assert_eq!(hash.as_deref(), Some("abc"));
+    }
+
+    #[tokio::test]
+    async fn test_hash_package_detects_mismatch() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello");
+            }
+        });
+
+        // sha256("hello")
+        let expected = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let url = format!("http://{}/app.pkg", addr);
+
+        let fresh = hash_package(&format!("url: {}\n", url), Path::new("app.yml")).await.unwrap();
+        assert_eq!(fresh.sha256, expected);
+        assert_eq!(fresh.size, 5);
+        assert_eq!(fresh.status, HashStatus::Missing);
+
+        let stale = hash_package(&format!("url: {}\nhash_sha256: deadbeef\n", url), Path::new("app.yml")).await.unwrap();
+        assert_eq!(stale.status, HashStatus::Mismatch { declared: "deadbeef".to_string() });
+    }
+}
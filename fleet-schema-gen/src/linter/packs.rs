@@ -0,0 +1,441 @@
+//! Policy packs: shareable bundles of lint settings, rules and templates.
+//!
+//! A pack is a directory (usually a git repository) with:
+//!
+//! ```text
+//! fleet-pack.toml           # name, version, description
+//! fleetlint.toml            # lint settings and [[custom_rules]] (optional)
+//! templates/<section>/*.yml # new-file snippets, e.g. templates/policies/
+//! lib/**                    # recommended lib files
+//! ```
+//!
+//! `fleet-schema-gen pack add <source>` vendors the pack into
+//! `.fleet/packs/<name>/` next to the repository's `.fleetlint.toml`, so CI
+//! and every editor see the same version. The pack's `fleetlint.toml` is
+//! merged beneath that config (the repository's own settings win), its
+//! templates are offered when completing an empty lib file, and its lib
+//! files are copied into `lib/` unless a file of that name already exists.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use super::config::{FleetLintConfig, CONFIG_FILE_NAME};
+use super::walk;
+
+/// Where packs are installed, relative to the config directory.
+pub const PACKS_DIR: &str = ".fleet/packs";
+
+/// Pack metadata file.
+pub const MANIFEST_FILE: &str = "fleet-pack.toml";
+
+/// Lint settings of a pack.
+pub const PACK_CONFIG_FILE: &str = "fleetlint.toml";
+
+const TEMPLATES_DIR: &str = "templates";
+const LIB_DIR: &str = "lib";
+
+/// Contents of `fleet-pack.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Where `pack add` installed the pack from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Git branch or tag it was installed at.
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+}
+
+/// A pack directory.
+#[derive(Debug, Clone)]
+pub struct Pack {
+    pub dir: PathBuf,
+    pub manifest: PackManifest,
+}
+
+/// A new-file template from a pack.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackTemplate {
+    pub pack: String,
+    /// File stem, e.g. `disk-encryption`.
+    pub label: String,
+    /// Snippet text (LSP snippet syntax allowed).
+    pub snippet: String,
+}
+
+/// Result of `pack add`.
+#[derive(Debug)]
+pub struct Installed {
+    pub pack: Pack,
+    /// Lib files copied into the repository, relative to its root.
+    pub lib_files: Vec<PathBuf>,
+    /// Lib files left alone because the repository already has them.
+    pub skipped_lib_files: Vec<PathBuf>,
+}
+
+impl Pack {
+    /// Read a pack directory and check its contents.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let content = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("{} is not a pack: missing {}", dir.display(), MANIFEST_FILE))?;
+        let manifest: PackManifest =
+            toml::from_str(&content).with_context(|| format!("Failed to parse {}", manifest_path.display()))?;
+
+        let valid_name = !manifest.name.is_empty()
+            && manifest.name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+            && manifest.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.".contains(c));
+        if !valid_name {
+            bail!("Invalid pack name '{}' in {}: use lowercase letters, digits, '-', '_' and '.'", manifest.name, manifest_path.display());
+        }
+
+        Ok(Self { dir: dir.to_path_buf(), manifest })
+    }
+
+    /// The pack's lint settings, if it has any.
+    pub fn config_file(&self) -> Option<PathBuf> {
+        Some(self.dir.join(PACK_CONFIG_FILE)).filter(|path| path.is_file())
+    }
+
+    /// New-file templates for a section (`policies`, `software_lib`).
+    pub fn templates(&self, section: &str) -> Vec<PackTemplate> {
+        let mut templates: Vec<PackTemplate> = yaml_files(&self.dir.join(TEMPLATES_DIR).join(section))
+            .into_iter()
+            .filter_map(|path| {
+                Some(PackTemplate {
+                    pack: self.manifest.name.clone(),
+                    label: path.file_stem()?.to_str()?.to_string(),
+                    snippet: fs::read_to_string(&path).ok()?,
+                })
+            })
+            .collect();
+        templates.sort_by(|a, b| a.label.cmp(&b.label));
+        templates
+    }
+
+    /// Recommended lib files, relative to the pack's `lib/`.
+    pub fn lib_files(&self) -> Result<Vec<PathBuf>> {
+        let lib = self.dir.join(LIB_DIR);
+        let files = walk::tree_files(&lib).with_context(|| format!("Failed to read {}", lib.display()))?;
+        Ok(files
+            .into_iter()
+            .filter_map(|path| path.strip_prefix(&lib).ok().map(Path::to_path_buf))
+            .collect())
+    }
+
+    /// Check that the pack's lint settings load.
+    fn check_config(&self) -> Result<()> {
+        let Some(path) = self.config_file() else {
+            return Ok(());
+        };
+        let config = FleetLintConfig::from_file(&path).map_err(|e| anyhow::anyhow!("{}", e))?;
+        for rule in &config.custom_rules {
+            rule.validate().map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Packs installed next to a config file in `config_dir`, by name.
+///
+/// Directories that aren't valid packs are logged and skipped.
+pub fn installed(config_dir: &Path) -> Vec<Pack> {
+    let Ok(entries) = fs::read_dir(config_dir.join(PACKS_DIR)) else {
+        return Vec::new();
+    };
+    let mut packs: Vec<Pack> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| match Pack::load(&entry.path()) {
+            Ok(pack) => Some(pack),
+            Err(e) => {
+                tracing::warn!("Skipping pack: {:#}", e);
+                None
+            }
+        })
+        .collect();
+    packs.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    packs
+}
+
+/// Directory packs are installed for: the nearest directory with a
+/// `.fleetlint.toml` up to the repository root, otherwise `start`.
+pub fn default_root(start: &Path) -> PathBuf {
+    let start = std::path::absolute(start).unwrap_or_else(|_| start.to_path_buf());
+    for dir in start.ancestors() {
+        if dir.join(CONFIG_FILE_NAME).is_file() {
+            return dir.to_path_buf();
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    start
+}
+
+/// Install a pack from a local directory or git URL into `root`.
+///
+/// An installed pack of the same name is replaced. Without a
+/// `.fleetlint.toml` in `root`, an empty one is created so the pack applies.
+/// Packs containing symlinks are rejected, as is installing a pack over or
+/// into its own source directory.
+pub fn add(root: &Path, source: &str, git_ref: Option<&str>) -> Result<Installed> {
+    let local = Path::new(source);
+    let checkout = if local.is_dir() {
+        if git_ref.is_some() {
+            bail!("--ref only applies to git sources; {} is a local directory", source);
+        }
+        None
+    } else if is_git_url(source) {
+        Some(clone(source, git_ref)?)
+    } else {
+        bail!("{} is neither a directory nor a git URL", source);
+    };
+    let pack_dir = checkout.as_ref().map(|c| c.0.as_path()).unwrap_or(local);
+
+    let mut pack = Pack::load(pack_dir)?;
+    pack.check_config()?;
+
+    let files = walk::tree_files(pack_dir).with_context(|| format!("Failed to read {}", pack_dir.display()))?;
+    let dest = root.join(PACKS_DIR).join(&pack.manifest.name);
+    let canonical_root = root.canonicalize().with_context(|| format!("Failed to resolve {}", root.display()))?;
+    let canonical_dest = canonical_root.join(PACKS_DIR).join(&pack.manifest.name);
+    let canonical_source = pack_dir.canonicalize().with_context(|| format!("Failed to resolve {}", pack_dir.display()))?;
+    if canonical_source.starts_with(&canonical_dest) || canonical_dest.starts_with(&canonical_source) {
+        bail!("Cannot install {} into {}, which overlaps its source", source, dest.display());
+    }
+    if dest.exists() {
+        fs::remove_dir_all(&dest).with_context(|| format!("Failed to replace {}", dest.display()))?;
+    }
+    copy_files(pack_dir, &files, &dest)?;
+
+    pack.manifest.source = Some(match checkout {
+        Some(_) => source.to_string(),
+        None => local.canonicalize().unwrap_or_else(|_| local.to_path_buf()).display().to_string(),
+    });
+    pack.manifest.git_ref = git_ref.map(str::to_string);
+    fs::write(dest.join(MANIFEST_FILE), toml::to_string(&pack.manifest)?)?;
+
+    let config_path = root.join(CONFIG_FILE_NAME);
+    if !config_path.exists() {
+        fs::write(&config_path, format!("# Packs in {} apply beneath these settings.\n", PACKS_DIR))?;
+    }
+
+    let mut lib_files = Vec::new();
+    let mut skipped_lib_files = Vec::new();
+    for file in pack.lib_files()? {
+        let target = root.join(LIB_DIR).join(&file);
+        let relative = Path::new(LIB_DIR).join(&file);
+        if target.exists() {
+            skipped_lib_files.push(relative);
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(pack_dir.join(LIB_DIR).join(&file), &target)?;
+        lib_files.push(relative);
+    }
+
+    Ok(Installed {
+        pack: Pack { dir: dest, manifest: pack.manifest },
+        lib_files,
+        skipped_lib_files,
+    })
+}
+
+/// Uninstall a pack. Lib files it added stay in place.
+pub fn remove(root: &Path, name: &str) -> Result<Pack> {
+    let pack = installed(root)
+        .into_iter()
+        .find(|pack| pack.manifest.name == name)
+        .with_context(|| format!("No pack named '{}' in {}", name, root.join(PACKS_DIR).display()))?;
+    fs::remove_dir_all(&pack.dir).with_context(|| format!("Failed to remove {}", pack.dir.display()))?;
+    Ok(pack)
+}
+
+/// New-file templates for a section from the packs that apply to `file`:
+/// those installed in any directory from the file up to the repository root.
+pub fn templates_for(file: &Path, section: &str) -> Vec<PackTemplate> {
+    let mut templates = Vec::new();
+    for dir in file.ancestors().skip(1) {
+        for pack in installed(dir) {
+            templates.extend(pack.templates(section));
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    templates
+}
+
+fn is_git_url(source: &str) -> bool {
+    source.contains("://") || source.starts_with("git@") || source.ends_with(".git")
+}
+
+/// A shallow clone, removed when dropped.
+struct Checkout(PathBuf);
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn clone(url: &str, git_ref: Option<&str>) -> Result<Checkout> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let checkout = Checkout(std::env::temp_dir().join(format!("fleet-pack-{}-{}", std::process::id(), nanos)));
+
+    let mut cmd = Command::new("git");
+    cmd.arg("clone").arg("--depth").arg("1");
+    if let Some(git_ref) = git_ref {
+        cmd.arg("--branch").arg(git_ref);
+    }
+    let output = cmd.arg(url).arg(&checkout.0).output().context("Failed to execute git clone")?;
+    if !output.status.success() {
+        bail!("Failed to clone {}: {}", url, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(checkout)
+}
+
+/// Copy `files` (from [`walk::tree_files`] of `from`) to the same relative
+/// paths under `to`.
+fn copy_files(from: &Path, files: &[PathBuf], to: &Path) -> Result<()> {
+    fs::create_dir_all(to).with_context(|| format!("Failed to create {}", to.display()))?;
+    for path in files {
+        let Ok(relative) = path.strip_prefix(from) else {
+            continue;
+        };
+        let target = to.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::copy(path, &target).with_context(|| format!("Failed to copy {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn yaml_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yml" || ext == "yaml"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn sample_pack(dir: &Path) {
+        write(&dir.join(MANIFEST_FILE), "name = \"acme-baseline\"\nversion = \"1.2.0\"\n");
+        write(
+            &dir.join(PACK_CONFIG_FILE),
+            "[rules]\nenabled = [\"policy-resolution\"]\n\n\
+             [[custom_rules]]\nid = \"acme/owner\"\nsection = \"policies\"\nfield = \"description\"\nrequired = true\n",
+        );
+        write(&dir.join("templates/policies/disk-encryption.yml"), "- name: ${1:Disk encryption}\n");
+        write(&dir.join("lib/macos/policies.yml"), "- name: FileVault\n");
+        write(&dir.join("lib/windows/policies.yml"), "- name: BitLocker\n");
+    }
+
+    #[test]
+    fn test_add_local_pack() {
+        let source = tempfile::tempdir().unwrap();
+        sample_pack(source.path());
+        let repo = tempfile::tempdir().unwrap();
+        write(&repo.path().join("lib/windows/policies.yml"), "- name: Ours\n");
+
+        let result = add(repo.path(), source.path().to_str().unwrap(), None).unwrap();
+        assert_eq!(result.pack.dir, repo.path().join(".fleet/packs/acme-baseline"));
+        assert_eq!(result.lib_files, vec![PathBuf::from("lib/macos/policies.yml")]);
+        assert_eq!(result.skipped_lib_files, vec![PathBuf::from("lib/windows/policies.yml")]);
+        assert_eq!(fs::read_to_string(repo.path().join("lib/windows/policies.yml")).unwrap(), "- name: Ours\n");
+
+        let packs = installed(repo.path());
+        assert_eq!(packs.len(), 1);
+        assert_eq!(packs[0].manifest.version, "1.2.0");
+        assert!(packs[0].manifest.source.is_some());
+
+        // The pack's settings apply beneath the repository config
+        write(&repo.path().join(CONFIG_FILE_NAME), "[rules]\nwarn = [\"acme/owner\"]\n");
+        let config = FleetLintConfig::from_file(&repo.path().join(CONFIG_FILE_NAME)).unwrap();
+        assert!(config.is_rule_enabled("policy-resolution"));
+        assert!(config.is_rule_warning("acme/owner"));
+        assert_eq!(config.custom_rules.len(), 1);
+
+        let file = repo.path().join("lib/new/policies.yml");
+        let templates = templates_for(&file, "policies");
+        assert_eq!(templates.len(), 1);
+        assert_eq!((templates[0].pack.as_str(), templates[0].label.as_str()), ("acme-baseline", "disk-encryption"));
+        assert!(templates_for(&file, "queries").is_empty());
+
+        remove(repo.path(), "acme-baseline").unwrap();
+        assert!(installed(repo.path()).is_empty());
+        assert!(repo.path().join("lib/macos/policies.yml").exists());
+    }
+
+    #[test]
+    fn test_add_rejects_invalid_packs() {
+        let source = tempfile::tempdir().unwrap();
+        let repo = tempfile::tempdir().unwrap();
+        let source_str = source.path().to_str().unwrap();
+
+        assert!(add(repo.path(), source_str, None).unwrap_err().to_string().contains("missing fleet-pack.toml"));
+
+        write(&source.path().join(MANIFEST_FILE), "name = \"Acme Pack\"\nversion = \"1\"\n");
+        assert!(add(repo.path(), source_str, None).unwrap_err().to_string().contains("Invalid pack name"));
+
+        write(&source.path().join(MANIFEST_FILE), "name = \"acme\"\nversion = \"1\"\n");
+        write(&source.path().join(PACK_CONFIG_FILE), "[[custom_rules]]\nid = \"acme/x\"\nsection = \"hosts\"\nfield = \"name\"\n");
+        assert!(add(repo.path(), source_str, None).unwrap_err().to_string().contains("section must be one of"));
+
+        assert!(add(repo.path(), source_str, Some("v1")).is_err());
+        assert!(add(repo.path(), "not-a-pack", None).unwrap_err().to_string().contains("neither a directory nor a git URL"));
+        assert!(installed(repo.path()).is_empty());
+    }
+
+    #[test]
+    fn test_add_rejects_installed_pack_as_source() {
+        let source = tempfile::tempdir().unwrap();
+        sample_pack(source.path());
+        let repo = tempfile::tempdir().unwrap();
+        let installed_pack = add(repo.path(), source.path().to_str().unwrap(), None).unwrap().pack;
+
+        let error = add(repo.path(), installed_pack.dir.to_str().unwrap(), None).unwrap_err();
+        assert!(error.to_string().contains("overlaps its source"));
+        assert!(installed_pack.dir.join(MANIFEST_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_add_rejects_symlinks() {
+        let source = tempfile::tempdir().unwrap();
+        sample_pack(source.path());
+        let secret = tempfile::tempdir().unwrap();
+        write(&secret.path().join("id_rsa"), "secret\n");
+        std::os::unix::fs::symlink(secret.path().join("id_rsa"), source.path().join("lib/macos/key.yml")).unwrap();
+        let repo = tempfile::tempdir().unwrap();
+
+        let error = add(repo.path(), source.path().to_str().unwrap(), None).unwrap_err();
+        assert!(format!("{:#}", error).contains("is a symlink"));
+        assert!(installed(repo.path()).is_empty());
+        assert!(!repo.path().join("lib/macos/key.yml").exists());
+    }
+}
@@ -0,0 +1,307 @@
+//! Platform coverage of control objectives per team.
+//!
+//! A control objective (disk encryption, firewall, screen lock, ...) is
+//! covered on a platform when one of the policies a team's hosts run checks
+//! it there. Policies match an objective by name or description keywords,
+//! or by querying one of its tables; their platforms come from `platform:`,
+//! else from the tables they query, else all of them (Fleet runs a policy
+//! without a platform everywhere). Global policies from `default.yml` apply
+//! to every team.
+
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::effective::global_team;
+use super::fleet_config::Policy;
+use super::osquery::{query_tables, OSQUERY_TABLES};
+use super::repo::{GitopsRepo, Team};
+
+/// Platforms reported when none are chosen.
+pub const DEFAULT_PLATFORMS: &[&str] = &["darwin", "windows", "linux"];
+
+/// A security control teams are expected to enforce on every platform.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Objective {
+    pub id: &'static str,
+    pub title: &'static str,
+    /// Lowercase words in a policy's name or description
+    keywords: &'static [&'static str],
+    /// osquery tables checking the control
+    tables: &'static [&'static str],
+}
+
+/// Built-in objectives, in report order.
+pub const OBJECTIVES: &[Objective] = &[
+    Objective {
+        id: "disk-encryption",
+        title: "disk encryption",
+        keywords: &["disk encryption", "encrypted", "filevault", "bitlocker", "luks"],
+        tables: &["disk_encryption", "filevault_status", "bitlocker_info"],
+    },
+    Objective {
+        id: "firewall",
+        title: "firewall",
+        keywords: &["firewall"],
+        tables: &["alf", "windows_firewall_rules", "iptables"],
+    },
+    Objective {
+        id: "screen-lock",
+        title: "screen lock",
+        keywords: &["screen lock", "screenlock", "screensaver", "screen saver", "lock screen"],
+        tables: &["screenlock"],
+    },
+    Objective {
+        id: "os-updates",
+        title: "OS updates",
+        keywords: &["os update", "software update", "os version", "patch"],
+        tables: &["windows_update_history", "patches"],
+    },
+    Objective {
+        id: "antivirus",
+        title: "antivirus",
+        keywords: &["antivirus", "anti-virus", "malware", "defender", "xprotect"],
+        tables: &["windows_security_products", "xprotect_meta", "xprotect_entries"],
+    },
+];
+
+impl Objective {
+    /// The built-in objective with this id.
+    pub fn parse(id: &str) -> Option<&'static Objective> {
+        OBJECTIVES.iter().find(|objective| objective.id == id)
+    }
+
+    /// Whether `policy` checks this objective.
+    fn matches(&self, policy: &Policy, tables: &[String]) -> bool {
+        let text = format!(
+            "{} {}",
+            policy.name.as_deref().unwrap_or(""),
+            policy.description.as_deref().unwrap_or("")
+        )
+        .to_lowercase();
+        self.keywords.iter().any(|keyword| text.contains(keyword))
+            || tables.iter().any(|table| self.tables.contains(&table.as_str()))
+    }
+}
+
+/// Platforms a policy runs on.
+fn policy_platforms(policy: &Policy, tables: &[String]) -> Vec<String> {
+    let declared: Vec<String> = policy
+        .platform
+        .iter()
+        .flat_map(|platform| platform.split(','))
+        .map(|platform| platform.trim().to_lowercase())
+        .filter(|platform| !platform.is_empty())
+        .collect();
+    if !declared.is_empty() {
+        return declared;
+    }
+
+    // The platforms every known table of the query exists on
+    let mut inferred: Option<Vec<&str>> = None;
+    for table in tables.iter().filter_map(|table| OSQUERY_TABLES.get(table.as_str())) {
+        inferred = Some(match inferred {
+            None => table.platforms.clone(),
+            Some(platforms) => platforms.into_iter().filter(|p| table.platforms.contains(p)).collect(),
+        });
+    }
+    match inferred {
+        Some(platforms) if !platforms.is_empty() => platforms.into_iter().map(str::to_string).collect(),
+        _ => DEFAULT_PLATFORMS.iter().chain(&["chrome"]).map(|p| p.to_string()).collect(),
+    }
+}
+
+/// Coverage of one objective for a team.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ObjectiveCoverage {
+    pub objective: &'static str,
+    pub title: &'static str,
+    /// Policies checking the objective, per covered platform
+    pub covered: BTreeMap<String, Vec<String>>,
+    /// Chosen platforms without such a policy
+    pub missing: Vec<String>,
+}
+
+impl ObjectiveCoverage {
+    /// "has a firewall policy for darwin and windows but not linux"
+    pub fn summary(&self) -> String {
+        let covered: Vec<&str> = self.covered.keys().map(String::as_str).collect();
+        match (covered.is_empty(), self.missing.is_empty()) {
+            (true, _) => format!("has no {} policy", self.title),
+            (false, true) => format!("has a {} policy for {}", self.title, join_list(&covered, "and")),
+            (false, false) => {
+                let missing: Vec<&str> = self.missing.iter().map(String::as_str).collect();
+                format!("has a {} policy for {} but not {}", self.title, join_list(&covered, "and"), join_list(&missing, "or"))
+            }
+        }
+    }
+}
+
+/// "a", "a and b", "a, b and c"
+fn join_list(items: &[&str], conjunction: &str) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.to_string(),
+        [rest @ .., last] => format!("{} {} {}", rest.join(", "), conjunction, last),
+    }
+}
+
+/// Platform coverage of a team (or the global `default.yml`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TeamPlatformCoverage {
+    pub team: String,
+    pub file: PathBuf,
+    pub objectives: Vec<ObjectiveCoverage>,
+}
+
+impl TeamPlatformCoverage {
+    /// Whether any objective is missing a platform.
+    pub fn has_gaps(&self) -> bool {
+        self.objectives.iter().any(|objective| !objective.missing.is_empty())
+    }
+}
+
+fn team_coverage(
+    team: &Team,
+    global: Option<&Team>,
+    objectives: &[&'static Objective],
+    platforms: &[String],
+) -> TeamPlatformCoverage {
+    let inherited = global.filter(|global| global.file != team.file);
+    let policies = team
+        .policies
+        .iter()
+        .map(|sourced| (&sourced.item, false))
+        .chain(inherited.into_iter().flat_map(|global| global.policies.iter().map(|sourced| (&sourced.item, true))));
+
+    let mut covered: Vec<BTreeMap<String, Vec<String>>> = vec![BTreeMap::new(); objectives.len()];
+    for (policy, is_global) in policies {
+        let tables = query_tables(policy.query.as_deref().unwrap_or(""));
+        let name = policy.name.clone().unwrap_or_else(|| "unnamed".to_string());
+        let name = if is_global { format!("{} (global)", name) } else { name };
+        for (idx, objective) in objectives.iter().enumerate() {
+            if !objective.matches(policy, &tables) {
+                continue;
+            }
+            for platform in policy_platforms(policy, &tables) {
+                if platforms.contains(&platform) {
+                    covered[idx].entry(platform).or_default().push(name.clone());
+                }
+            }
+        }
+    }
+
+    TeamPlatformCoverage {
+        team: team.name.clone(),
+        file: team.file.clone(),
+        objectives: objectives
+            .iter()
+            .zip(covered)
+            .map(|(objective, covered)| ObjectiveCoverage {
+                objective: objective.id,
+                title: objective.title,
+                missing: platforms.iter().filter(|p| !covered.contains_key(*p)).cloned().collect(),
+                covered,
+            })
+            .collect(),
+    }
+}
+
+/// Coverage of `objectives` (all built-in ones when empty) on `platforms`
+/// ([`DEFAULT_PLATFORMS`] when empty) for every team, global first.
+pub fn platform_coverage(
+    repo: &GitopsRepo,
+    objectives: &[&'static Objective],
+    platforms: &[String],
+) -> Vec<TeamPlatformCoverage> {
+    let objectives: Vec<&'static Objective> =
+        if objectives.is_empty() { OBJECTIVES.iter().collect() } else { objectives.to_vec() };
+    let platforms: Vec<String> = if platforms.is_empty() {
+        DEFAULT_PLATFORMS.iter().map(|p| p.to_string()).collect()
+    } else {
+        platforms.to_vec()
+    };
+
+    let global = global_team(repo);
+    let mut teams: Vec<TeamPlatformCoverage> =
+        repo.teams.iter().map(|team| team_coverage(team, global, &objectives, &platforms)).collect();
+    teams.sort_by_key(|coverage| global.is_none_or(|g| g.file != coverage.file));
+    teams
+}
+
+/// Print a platform coverage report to stdout.
+pub fn print(teams: &[TeamPlatformCoverage]) {
+    for team in teams {
+        println!("\n{} {} ({})", "Team:".bold(), team.team.cyan(), team.file.display().to_string().dimmed());
+        for objective in &team.objectives {
+            let mark = if objective.missing.is_empty() { "✓".green() } else { "✗".red() };
+            println!("  {} {} {}", mark, team.team, objective.summary());
+            for (platform, policies) in &objective.covered {
+                println!("      {} {}", format!("{}:", platform).dimmed(), policies.join(", ").dimmed());
+            }
+        }
+    }
+
+    let gaps = teams.iter().filter(|team| team.has_gaps()).count();
+    if gaps > 0 {
+        println!("\n{} {} of {} team(s) miss a platform", "⚠".yellow(), gaps, teams.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_platform_coverage() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("default.yml"),
+            "policies:\n  - name: Screen lock enabled\n    query: SELECT 1 FROM screenlock WHERE enabled = 1;\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("teams")).unwrap();
+        std::fs::write(
+            dir.path().join("teams/workstations.yml"),
+            r#"name: Workstations
+policies:
+  - name: macOS - Firewall enabled
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+  - name: Windows - Firewall enabled
+    query: SELECT 1 FROM windows_firewall_rules;
+    platform: windows
+  - name: Disk encrypted
+    query: SELECT 1 FROM disk_encryption WHERE encrypted = 1;
+"#,
+        )
+        .unwrap();
+
+        let repo = crate::linter::repo::load_repo(dir.path()).unwrap();
+        let objectives: Vec<_> = ["firewall", "disk-encryption", "screen-lock"].iter().filter_map(|id| Objective::parse(id)).collect();
+        let report = platform_coverage(&repo, &objectives, &[]);
+        assert_eq!(report.len(), 2);
+
+        let global = &report[0];
+        assert_eq!(global.objectives[0].summary(), "has no firewall policy");
+        assert_eq!(global.objectives[2].summary(), "has a screen lock policy for darwin but not windows or linux");
+
+        let team = &report[1];
+        assert_eq!(team.team, "Workstations");
+        let summaries: Vec<String> = team.objectives.iter().map(|o| o.summary()).collect();
+        assert_eq!(
+            summaries,
+            vec![
+                "has a firewall policy for darwin and windows but not linux",
+                "has a disk encryption policy for darwin, linux and windows",
+                "has a screen lock policy for darwin but not windows or linux",
+            ]
+        );
+        assert_eq!(team.objectives[2].covered["darwin"], vec!["Screen lock enabled (global)"]);
+        assert!(team.has_gaps());
+
+        // Chosen platforms only
+        let report = platform_coverage(&repo, &objectives[..1], &["darwin".to_string(), "windows".to_string()]);
+        assert!(!report[1].has_gaps());
+    }
+}
@@ -0,0 +1,78 @@
+//! Built-in configuration presets.
+//!
+//! A preset is a `.fleetlint.toml` fragment shipped with the binary. It is
+//! selected with `preset = "<name>"` or `extends = "<name>"` in a config,
+//! or with `lint --preset <name>`, and sits beneath the settings of the
+//! file that selects it.
+
+/// A named preset.
+#[derive(Debug, Clone, Copy)]
+pub struct Preset {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// TOML source, in `.fleetlint.toml` format.
+    pub config: &'static str,
+}
+
+/// All built-in presets.
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "fleet-recommended",
+        description: "Default rules plus policy quality checks and a 5 minute minimum interval",
+        config: include_str!("../../presets/fleet-recommended.toml"),
+    },
+    Preset {
+        name: "cis-strict",
+        description: "All policy rules as errors, 15 minute minimum interval, explicit platforms",
+        config: include_str!("../../presets/cis-strict.toml"),
+    },
+    Preset {
+        name: "minimal",
+        description: "Only checks for configs Fleet would reject; hygiene findings are warnings",
+        config: include_str!("../../presets/minimal.toml"),
+    },
+];
+
+/// Look up a preset by name.
+pub fn get(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|p| p.name == name)
+}
+
+/// Names of all presets, for error messages.
+pub fn names() -> Vec<&'static str> {
+    PRESETS.iter().map(|p| p.name).collect()
+}
+
+/// Presets as clap values, with their descriptions as help.
+pub fn possible_values() -> Vec<clap::builder::PossibleValue> {
+    PRESETS
+        .iter()
+        .map(|p| clap::builder::PossibleValue::new(p.name).help(p.description))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::config::FleetLintConfig;
+    use crate::linter::RuleSet;
+
+    #[test]
+    fn test_presets_parse_and_name_known_rules() {
+        let rules = RuleSet::default_rules();
+        let known: Vec<&str> = rules
+            .rules()
+            .iter()
+            .map(|r| r.name())
+            .chain(rules.workspace_rules().iter().map(|r| r.name()))
+            .collect();
+
+        for preset in PRESETS {
+            let config = FleetLintConfig::from_str(preset.config)
+                .unwrap_or_else(|e| panic!("preset {} is invalid: {}", preset.name, e));
+            for rule in config.rules.disabled.iter().chain(&config.rules.warn).chain(&config.rules.enabled) {
+                assert!(known.contains(&rule.as_str()), "preset {} names unknown rule {}", preset.name, rule);
+            }
+        }
+    }
+}
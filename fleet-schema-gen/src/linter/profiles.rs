@@ -0,0 +1,420 @@
+//! Configuration profile inspection for `custom_settings` entries.
+//!
+//! Parses Apple `.mobileconfig` property lists (plain or CMS-signed) into a
+//! short summary of what the profile configures, and validates Windows
+//! SyncML (`.xml`) profiles: structure, OMA-URI paths, and `<Data>` against
+//! the declared `<Format>`.
+
+use super::error::LintError;
+use once_cell::sync::Lazy;
+use plist::Value;
+use regex::Regex;
+use std::path::Path;
+
+/// Summary of an Apple configuration profile.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MobileconfigSummary {
+    pub display_name: Option<String>,
+    pub identifier: Option<String>,
+    pub description: Option<String>,
+    /// `System` or `User`
+    pub scope: Option<String>,
+    pub organization: Option<String>,
+    /// Whether the file is a CMS-signed profile.
+    pub signed: bool,
+    pub payloads: Vec<ProfilePayload>,
+}
+
+/// A single entry of `PayloadContent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilePayload {
+    /// e.g. `com.apple.security.firewall`
+    pub payload_type: String,
+    pub display_name: Option<String>,
+}
+
+/// Parse a `.mobileconfig` file.
+///
+/// Signed profiles are DER-encoded CMS envelopes with the XML plist embedded
+/// verbatim, so the plist is located inside the envelope rather than decoding
+/// the signature.
+pub fn parse_mobileconfig(bytes: &[u8]) -> Option<MobileconfigSummary> {
+    let (plist_bytes, signed) = match Value::from_reader(std::io::Cursor::new(bytes)) {
+        Ok(value) => return Some(summarize(&value, false)),
+        Err(_) => (embedded_plist(bytes)?, true),
+    };
+
+    let value = Value::from_reader_xml(plist_bytes).ok()?;
+    Some(summarize(&value, signed))
+}
+
+/// Locate an XML plist inside a signed profile.
+fn embedded_plist(bytes: &[u8]) -> Option<&[u8]> {
+    let start = find(bytes, b"<?xml")?;
+    let end = find(&bytes[start..], b"</plist>")? + start + b"</plist>".len();
+    Some(&bytes[start..end])
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn summarize(value: &Value, signed: bool) -> MobileconfigSummary {
+    let dict = value.as_dictionary();
+    let string = |key: &str| {
+        dict.and_then(|d| d.get(key))
+            .and_then(Value::as_string)
+            .map(String::from)
+    };
+
+    let payloads = dict
+        .and_then(|d| d.get("PayloadContent"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_dictionary)
+        .filter_map(|payload| {
+            Some(ProfilePayload {
+                payload_type: payload.get("PayloadType")?.as_string()?.to_string(),
+                display_name: payload
+                    .get("PayloadDisplayName")
+                    .and_then(Value::as_string)
+                    .map(String::from),
+            })
+        })
+        .collect();
+
+    MobileconfigSummary {
+        display_name: string("PayloadDisplayName"),
+        identifier: string("PayloadIdentifier"),
+        description: string("PayloadDescription"),
+        scope: string("PayloadScope"),
+        organization: string("PayloadOrganization"),
+        signed,
+        payloads,
+    }
+}
+
+impl MobileconfigSummary {
+    /// Render the summary as Markdown for hover.
+    pub fn to_markdown(&self, file_name: &str) -> String {
+        let mut doc = format!(
+            "**{}** (configuration profile)",
+            self.display_name.as_deref().unwrap_or(file_name)
+        );
+
+        if let Some(description) = &self.description {
+            doc.push_str(&format!("\n\n{}", description));
+        }
+
+        let mut fields = Vec::new();
+        if let Some(identifier) = &self.identifier {
+            fields.push(format!("**Identifier:** `{}`", identifier));
+        }
+        if let Some(organization) = &self.organization {
+            fields.push(format!("**Organization:** {}", organization));
+        }
+        fields.push(format!("**Scope:** {}", self.scope.as_deref().unwrap_or("System")));
+        if self.signed {
+            fields.push("**Signed:** yes".to_string());
+        }
+        doc.push_str(&format!("\n\n{}", fields.join("  \n")));
+
+        if self.payloads.is_empty() {
+            doc.push_str("\n\n_No payloads_");
+        } else {
+            doc.push_str("\n\n**Payloads:**\n");
+            for payload in &self.payloads {
+                match &payload.display_name {
+                    Some(name) => doc.push_str(&format!("\n- `{}` — {}", payload.payload_type, name)),
+                    None => doc.push_str(&format!("\n- `{}`", payload.payload_type)),
+                }
+            }
+        }
+
+        doc
+    }
+}
+
+/// SyncML commands Fleet accepts at the top level of a Windows profile.
+const SYNCML_COMMANDS: &[&str] = &["Add", "Replace", "Atomic", "Delete", "Exec"];
+
+/// Values allowed in `<Meta><Format>`.
+const SYNCML_FORMATS: &[&str] = &[
+    "b64", "bin", "bool", "chr", "int", "node", "null", "xml", "date", "time", "float",
+];
+
+/// `./Device/Vendor/MSFT/...`, `./User/Vendor/MSFT/...`, or `./Vendor/MSFT/...`
+static LOC_URI: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\./(?:Device/|User/)?Vendor/[^/\s]+(?:/[^/\s]+)+$").unwrap()
+});
+
+static BASE64: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9+/\s]*={0,2}\s*$").unwrap());
+
+/// Validate a Windows SyncML profile.
+///
+/// Profiles are a sequence of top-level commands (not a single XML
+/// document), so the content is wrapped in a synthetic root before parsing.
+/// Locations in the returned errors refer to `file`.
+pub fn validate_windows_profile(xml: &str, file: &Path) -> Vec<LintError> {
+    const ROOT_OPEN: &str = "<SyncBody>";
+
+    // Blank out an XML declaration; it can't appear inside the wrapper
+    let body = match xml.trim_start().strip_prefix("<?xml") {
+        Some(rest) => match rest.find("?>") {
+            Some(end) => {
+                let decl_len = xml.len() - rest.len() + end + 2;
+                let blank: String = xml[..decl_len]
+                    .chars()
+                    .map(|c| if c == '\n' { c } else { ' ' })
+                    .collect();
+                format!("{}{}", blank, &xml[decl_len..])
+            }
+            None => xml.to_string(),
+        },
+        None => xml.to_string(),
+    };
+    let wrapped = format!("{}{}</SyncBody>", ROOT_OPEN, body);
+
+    // Undo the wrapper's column shift on the first line
+    let position = |row: u32, col: u32| {
+        let col = if row == 1 { col.saturating_sub(ROOT_OPEN.len() as u32) } else { col };
+        (row as usize, col.max(1) as usize)
+    };
+
+    let doc = match roxmltree::Document::parse(&wrapped) {
+        Ok(doc) => doc,
+        Err(e) => {
+            let pos = e.pos();
+            let (line, col) = position(pos.row, pos.col);
+            return vec![LintError::error(format!("Malformed SyncML: {}", e), file)
+                .with_location(line, col)
+                .with_help("Windows profiles must be well-formed XML made of <Replace>/<Add> commands")];
+        }
+    };
+
+    let mut errors = Vec::new();
+    let located = |error: LintError, node: roxmltree::Node| {
+        let pos = doc.text_pos_at(node.range().start);
+        let (line, col) = position(pos.row, pos.col);
+        error.with_location(line, col)
+    };
+
+    let commands: Vec<_> = doc.root_element().children().filter(|n| n.is_element()).collect();
+    if commands.is_empty() {
+        errors.push(LintError::error("Windows profile contains no SyncML commands", file)
+            .with_help("Wrap settings in <Replace><Item>...</Item></Replace>"));
+    }
+
+    for command in commands {
+        let name = command.tag_name().name();
+        if !SYNCML_COMMANDS.contains(&name) {
+            errors.push(located(
+                LintError::error(format!("Unsupported SyncML command <{}>", name), file)
+                    .with_help(format!("Expected one of: {}", SYNCML_COMMANDS.join(", "))),
+                command,
+            ));
+            continue;
+        }
+
+        let items: Vec<_> = command
+            .descendants()
+            .filter(|n| n.has_tag_name("Item"))
+            .collect();
+        if items.is_empty() {
+            errors.push(located(
+                LintError::warning(format!("<{}> has no <Item>", name), file),
+                command,
+            ));
+        }
+
+        for item in items {
+            errors.extend(validate_item(item, file).into_iter().map(|(error, node)| located(error, node)));
+        }
+    }
+
+    errors
+}
+
+/// Validate one `<Item>`: its LocURI and its Data against the declared Format.
+fn validate_item<'a, 'input>(
+    item: roxmltree::Node<'a, 'input>,
+    file: &Path,
+) -> Vec<(LintError, roxmltree::Node<'a, 'input>)> {
+    let mut errors = Vec::new();
+    let child = |name: &str| item.descendants().find(|n| n.has_tag_name(name));
+
+    match child("LocURI") {
+        None => errors.push((
+            LintError::error("<Item> is missing <Target><LocURI>", file),
+            item,
+        )),
+        Some(loc_uri) => {
+            let uri = loc_uri.text().unwrap_or("").trim();
+            if !LOC_URI.is_match(uri) {
+                errors.push((
+                    LintError::error(format!("Invalid OMA-URI: '{}'", uri), file)
+                        .with_help("OMA-URIs look like ./Device/Vendor/MSFT/Policy/Config/<Area>/<Setting>"),
+                    loc_uri,
+                ));
+            }
+        }
+    }
+
+    let format = child("Format").map(|n| n.text().unwrap_or("").trim().to_string());
+    if let (Some(format), Some(node)) = (&format, child("Format")) {
+        if !SYNCML_FORMATS.contains(&format.as_str()) {
+            errors.push((
+                LintError::error(format!("Unknown Format '{}'", format), file)
+                    .with_help(format!("Expected one of: {}", SYNCML_FORMATS.join(", "))),
+                node,
+            ));
+            return errors;
+        }
+    }
+
+    let Some(data) = child("Data") else {
+        return errors;
+    };
+    let value = data.text().unwrap_or("").trim();
+
+    let problem = match format.as_deref().unwrap_or("chr") {
+        "int" if value.parse::<i64>().is_err() => Some("an integer"),
+        "bool" if !matches!(value, "true" | "false") => Some("true or false"),
+        "float" if value.parse::<f64>().is_err() => Some("a number"),
+        "b64" if !BASE64.is_match(value) => Some("base64"),
+        "node" | "null" if !value.is_empty() => Some("empty"),
+        "xml" if !value.is_empty()
+            && roxmltree::Document::parse(&format!("<Data>{}</Data>", value)).is_err() =>
+        {
+            Some("well-formed XML")
+        }
+        _ => None,
+    };
+
+    if let Some(expected) = problem {
+        errors.push((
+            LintError::error(
+                format!(
+                    "<Data> '{}' does not match Format '{}' (expected {})",
+                    value,
+                    format.as_deref().unwrap_or("chr"),
+                    expected
+                ),
+                file,
+            ),
+            data,
+        ));
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>PayloadContent</key>
+  <array>
+    <dict>
+      <key>PayloadType</key>
+      <string>com.apple.security.firewall</string>
+      <key>PayloadDisplayName</key>
+      <string>Firewall</string>
+      <key>EnableFirewall</key>
+      <true/>
+    </dict>
+    <dict>
+      <key>PayloadType</key>
+      <string>com.apple.screensaver</string>
+    </dict>
+  </array>
+  <key>PayloadDisplayName</key>
+  <string>Security baseline</string>
+  <key>PayloadIdentifier</key>
+  <string>com.example.security</string>
+  <key>PayloadType</key>
+  <string>Configuration</string>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn test_parse_mobileconfig() {
+        let summary = parse_mobileconfig(PROFILE.as_bytes()).unwrap();
+        assert_eq!(summary.display_name.as_deref(), Some("Security baseline"));
+        assert_eq!(summary.identifier.as_deref(), Some("com.example.security"));
+        assert!(!summary.signed);
+        assert_eq!(summary.payloads.len(), 2);
+        assert_eq!(summary.payloads[0].payload_type, "com.apple.security.firewall");
+        assert_eq!(summary.payloads[0].display_name.as_deref(), Some("Firewall"));
+        assert_eq!(summary.payloads[1].display_name, None);
+    }
+
+    #[test]
+    fn test_parse_signed_mobileconfig() {
+        // DER envelope bytes around the embedded plist
+        let mut bytes = vec![0x30, 0x82, 0x1f, 0x00, 0x06, 0x09];
+        bytes.extend_from_slice(PROFILE.as_bytes());
+        bytes.extend_from_slice(&[0xa0, 0x82, 0x00, 0x10]);
+
+        let summary = parse_mobileconfig(&bytes).unwrap();
+        assert!(summary.signed);
+        assert_eq!(summary.payloads.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_windows_profile() {
+        let xml = r#"<Replace>
+  <Item>
+    <Meta><Format xmlns="syncml:metinf">int</Format></Meta>
+    <Target><LocURI>./Device/Vendor/MSFT/Policy/Config/Update/ActiveHoursStart</LocURI></Target>
+    <Data>8</Data>
+  </Item>
+</Replace>
+<Replace>
+  <Item>
+    <Meta><Format xmlns="syncml:metinf">bool</Format></Meta>
+    <Target><LocURI>Device/Vendor/MSFT/Policy//Defender</LocURI></Target>
+    <Data>yes</Data>
+  </Item>
+</Replace>
+"#;
+        let errors = validate_windows_profile(xml, Path::new("update.xml"));
+        let messages: Vec<(&str, Option<usize>)> =
+            errors.iter().map(|e| (e.message.as_str(), e.line)).collect();
+
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert!(messages[0].0.starts_with("Invalid OMA-URI"));
+        assert_eq!(messages[0].1, Some(11));
+        assert!(messages[1].0.contains("does not match Format 'bool'"));
+        assert_eq!(messages[1].1, Some(12));
+    }
+
+    #[test]
+    fn test_validate_windows_profile_malformed() {
+        let errors = validate_windows_profile("<Replace><Item></Replace>", Path::new("bad.xml"));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.starts_with("Malformed SyncML"));
+        assert_eq!(errors[0].line, Some(1));
+
+        let errors = validate_windows_profile("<Set><Item/></Set>", Path::new("bad.xml"));
+        assert!(errors[0].message.contains("Unsupported SyncML command <Set>"));
+        assert_eq!(errors[0].column, Some(1));
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let markdown = parse_mobileconfig(PROFILE.as_bytes())
+            .unwrap()
+            .to_markdown("security.mobileconfig");
+        assert!(markdown.starts_with("**Security baseline** (configuration profile)"));
+        assert!(markdown.contains("- `com.apple.security.firewall` — Firewall"));
+        assert!(markdown.contains("- `com.apple.screensaver`"));
+        assert!(markdown.contains("**Scope:** System"));
+    }
+}
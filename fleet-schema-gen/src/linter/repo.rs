@@ -0,0 +1,418 @@
+//! Loading a whole Fleet GitOps repository.
+//!
+//! Reads the global `default.yml` plus `teams/*.yml` and resolves `path:`
+//! references to policy, query, and software package files, so reports can
+//! work with every item a team actually deploys.
+
+use super::engine::{parse_fleet_config, read_file, LintEngineError};
+use super::fleet_config::{FleetConfig, Policy, PolicyOrPath, Query, QueryOrPath, SoftwarePackage};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A GitOps repository with all team files loaded.
+#[derive(Debug)]
+pub struct GitopsRepo {
+    pub root: PathBuf,
+    pub teams: Vec<Team>,
+}
+
+/// A team file (or the global `default.yml`) with references resolved.
+#[derive(Debug)]
+pub struct Team {
+    pub name: String,
+    pub file: PathBuf,
+    pub config: FleetConfig,
+    pub policies: Vec<Sourced<Policy>>,
+    pub queries: Vec<Sourced<Query>>,
+    pub software: Vec<Software>,
+    /// `path:` references that could not be read or parsed.
+    pub unresolved: Vec<String>,
+}
+
+/// An item together with the file that defines it.
+#[derive(Debug, Clone)]
+pub struct Sourced<T> {
+    pub item: T,
+    pub file: PathBuf,
+    /// Position in the file's list (`policies:`/`queries:`, or a lib file's).
+    pub index: usize,
+}
+
+/// Where a software entry comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoftwareSource {
+    Package,
+    AppStoreApp,
+    FleetMaintainedApp,
+}
+
+impl SoftwareSource {
+    /// The team file key listing this kind of software.
+    pub fn key(&self) -> &'static str {
+        match self {
+            SoftwareSource::Package => "packages",
+            SoftwareSource::AppStoreApp => "app_store_apps",
+            SoftwareSource::FleetMaintainedApp => "fleet_maintained_apps",
+        }
+    }
+}
+
+/// A software entry from a team's `software:` section.
+#[derive(Debug, Clone)]
+pub struct Software {
+    pub source: SoftwareSource,
+    /// Display name: package file name, App Store ID, or maintained app slug.
+    pub name: String,
+    /// Package definition file for `path:` entries.
+    pub file: Option<PathBuf>,
+    /// Resolved package definition (inline or from `path:`).
+    pub package: Option<SoftwarePackage>,
+    /// The raw entry from the team file (labels, self_service, ...).
+    pub entry: serde_yaml::Value,
+}
+
+impl Software {
+    /// Whether the software is offered in self-service, from the team entry
+    /// or the package definition.
+    pub fn self_service(&self) -> Option<bool> {
+        self.entry
+            .get("self_service")
+            .and_then(|v| v.as_bool())
+            .or_else(|| self.package.as_ref().and_then(|p| p.self_service))
+    }
+}
+
+/// Nearest directory at or above `path` holding `default.yml` or `teams/`.
+pub fn find_root(path: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().ok()?;
+    path.ancestors()
+        .find(|dir| dir.join("default.yml").is_file() || dir.join("teams").is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Find `default.yml` and `teams/*.yml` in a repository root.
+pub fn team_files(root: &Path) -> Result<Vec<PathBuf>, LintEngineError> {
+    let mut files = Vec::new();
+
+    let default_file = root.join("default.yml");
+    if default_file.is_file() {
+        files.push(default_file);
+    }
+
+    let teams_dir = root.join("teams");
+    if teams_dir.is_dir() {
+        let mut entries: Vec<PathBuf> = fs::read_dir(&teams_dir)
+            .map_err(|source| LintEngineError::Read { path: teams_dir.clone(), source })?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_file()
+                    && matches!(p.extension().and_then(|e| e.to_str()), Some("yml" | "yaml"))
+            })
+            .collect();
+        entries.sort();
+        files.extend(entries);
+    }
+
+    if files.is_empty() {
+        return Err(LintEngineError::NoTeamFiles(root.to_path_buf()));
+    }
+
+    Ok(files)
+}
+
+/// Display name for a team file: its `name:`, or "Global" for `default.yml`.
+pub fn team_name(config: &FleetConfig, file: &Path) -> String {
+    config.name.clone().unwrap_or_else(|| {
+        match file.file_stem().and_then(|s| s.to_str()) {
+            Some("default") => "Global".to_string(),
+            Some(stem) => stem.to_string(),
+            None => "unknown".to_string(),
+        }
+    })
+}
+
+/// Load every team in a repository.
+pub fn load_repo(root: &Path) -> Result<GitopsRepo, LintEngineError> {
+    let teams = team_files(root)?
+        .iter()
+        .map(|file| load_team(file))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(GitopsRepo {
+        root: root.to_path_buf(),
+        teams,
+    })
+}
+
+/// Load a single team file and resolve its references.
+pub fn load_team(file: &Path) -> Result<Team, LintEngineError> {
+    let source = read_file(file)?;
+    let config = parse_fleet_config(&source, file)?;
+    let base_dir = file.parent().unwrap_or(Path::new("."));
+
+    let mut team = Team {
+        name: team_name(&config, file),
+        file: file.to_path_buf(),
+        config: FleetConfig::default(),
+        policies: Vec::new(),
+        queries: Vec::new(),
+        software: Vec::new(),
+        unresolved: Vec::new(),
+    };
+
+    for (index, policy_or_path) in config.policies.iter().flatten().enumerate() {
+        match policy_or_path {
+            PolicyOrPath::Policy(policy) => team.policies.push(Sourced {
+                item: policy.clone(),
+                file: file.to_path_buf(),
+                index,
+            }),
+            PolicyOrPath::Path { path } => {
+                let resolved = resolve_path(base_dir, path);
+                match load_policies_file(&resolved) {
+                    Some(policies) => team.policies.extend(
+                        policies.into_iter().map(|(index, item)| Sourced { item, file: resolved.clone(), index }),
+                    ),
+                    None => team.unresolved.push(path.clone()),
+                }
+            }
+        }
+    }
+
+    for (index, query_or_path) in config.queries.iter().flatten().enumerate() {
+        match query_or_path {
+            QueryOrPath::Query(query) => team.queries.push(Sourced {
+                item: query.clone(),
+                file: file.to_path_buf(),
+                index,
+            }),
+            QueryOrPath::Path { path } => {
+                let resolved = resolve_path(base_dir, path);
+                match load_queries_file(&resolved) {
+                    Some(queries) => team.queries.extend(
+                        queries.into_iter().map(|(index, item)| Sourced { item, file: resolved.clone(), index }),
+                    ),
+                    None => team.unresolved.push(path.clone()),
+                }
+            }
+        }
+    }
+
+    if let Some(software) = &config.software {
+        for source in [
+            SoftwareSource::Package,
+            SoftwareSource::AppStoreApp,
+            SoftwareSource::FleetMaintainedApp,
+        ] {
+            let entries = software.get(source.key()).and_then(|v| v.as_sequence());
+            for entry in entries.into_iter().flatten() {
+                match resolve_software(source, entry, base_dir) {
+                    Some(item) => team.software.push(item),
+                    None => {
+                        if let Some(path) = entry.get("path").and_then(|p| p.as_str()) {
+                            team.unresolved.push(path.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    team.config = config;
+    Ok(team)
+}
+
+/// Join a `path:` reference onto the referencing file's directory,
+/// collapsing `..` so reports show `lib/x.yml` rather than `teams/../lib/x.yml`.
+pub fn resolve_path(base_dir: &Path, path: &str) -> PathBuf {
+    use std::path::Component;
+
+    let mut resolved = PathBuf::new();
+    for component in base_dir.join(path).components() {
+        match component {
+            Component::ParentDir if matches!(resolved.components().next_back(), Some(Component::Normal(_))) => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// Policies from a referenced file (a bare list or a `policies:` section).
+fn load_policies_file(path: &Path) -> Option<Vec<(usize, Policy)>> {
+    let content = fs::read_to_string(path).ok()?;
+    let config = parse_fleet_config(&content, path).ok()?;
+
+    Some(
+        config
+            .policies?
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, p)| match p {
+                PolicyOrPath::Policy(policy) => Some((index, policy)),
+                PolicyOrPath::Path { .. } => None,
+            })
+            .collect(),
+    )
+}
+
+/// Queries from a referenced file (a bare list or a `queries:` section).
+fn load_queries_file(path: &Path) -> Option<Vec<(usize, Query)>> {
+    let content = fs::read_to_string(path).ok()?;
+
+    // Bare lists would otherwise be read as policies by parse_fleet_config
+    if let Ok(queries) = serde_yaml::from_str::<Vec<Query>>(&content) {
+        return Some(queries.into_iter().enumerate().collect());
+    }
+
+    let config: FleetConfig = serde_yaml::from_str(&content).ok()?;
+    Some(
+        config
+            .queries?
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, q)| match q {
+                QueryOrPath::Query(query) => Some((index, query)),
+                QueryOrPath::Path { .. } => None,
+            })
+            .collect(),
+    )
+}
+
+/// Resolve a software entry, loading `path:` package definitions.
+///
+/// Returns `None` when a referenced package file can't be loaded.
+fn resolve_software(source: SoftwareSource, entry: &serde_yaml::Value, base_dir: &Path) -> Option<Software> {
+    let str_field = |key: &str| entry.get(key).and_then(|v| v.as_str()).map(String::from);
+
+    let (name, file, package) = match source {
+        SoftwareSource::Package => match str_field("path") {
+            Some(path) => {
+                let resolved = resolve_path(base_dir, &path);
+                let content = fs::read_to_string(&resolved).ok()?;
+                let package: SoftwarePackage = serde_yaml::from_str(&content).ok()?;
+                let name = resolved
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or(path);
+                (name, Some(resolved), Some(package))
+            }
+            None => {
+                let package: Option<SoftwarePackage> = serde_yaml::from_value(entry.clone()).ok();
+                let name = package
+                    .as_ref()
+                    .and_then(|p| p.url.as_deref())
+                    .and_then(|url| url.rsplit('/').next())
+                    .filter(|n| !n.is_empty())
+                    .unwrap_or("inline package")
+                    .to_string();
+                (name, None, package)
+            }
+        },
+        SoftwareSource::AppStoreApp => (
+            // App Store IDs are often written unquoted (numbers)
+            entry
+                .get("app_store_id")
+                .map(yaml_scalar)
+                .unwrap_or_else(|| "unknown".to_string()),
+            None,
+            None,
+        ),
+        SoftwareSource::FleetMaintainedApp => (
+            str_field("slug").unwrap_or_else(|| "unknown".to_string()),
+            None,
+            None,
+        ),
+    };
+
+    Some(Software {
+        source,
+        name,
+        file,
+        package,
+        entry: entry.clone(),
+    })
+}
+
+/// Render a scalar YAML value (numbers included) as a string.
+fn yaml_scalar(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.clone(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::Bool(b) => b.to_string(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_repo_resolves_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+
+        fs::write(
+            root.join("lib/policies.yml"),
+            "- name: FileVault enabled\n  query: SELECT 1 FROM filevault_status;\n  platform: darwin\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("lib/queries.yml"),
+            "- name: Uptime\n  query: SELECT * FROM uptime;\n  interval: 3600\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("lib/firefox.yml"),
+            "url: https://example.com/firefox.pkg\nhash_sha256: abc\n",
+        )
+        .unwrap();
+
+        fs::write(
+            root.join("teams/workstations.yml"),
+            r#"name: Workstations
+policies:
+  - path: ../lib/policies.yml
+  - name: Firewall enabled
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+  - path: ../lib/missing.yml
+queries:
+  - path: ../lib/queries.yml
+software:
+  packages:
+    - path: ../lib/firefox.yml
+      self_service: true
+  app_store_apps:
+    - app_store_id: 1091189122
+  fleet_maintained_apps:
+    - slug: slack/darwin
+"#,
+        )
+        .unwrap();
+
+        let repo = load_repo(root).unwrap();
+        assert_eq!(repo.teams.len(), 1);
+
+        let team = &repo.teams[0];
+        assert_eq!(team.name, "Workstations");
+        assert_eq!(team.policies.len(), 2);
+        assert_eq!(team.policies[0].file, root.join("lib/policies.yml"));
+        assert_eq!(team.queries.len(), 1);
+        assert_eq!(team.queries[0].item.interval, Some(3600));
+        assert_eq!(team.unresolved, vec!["../lib/missing.yml"]);
+
+        let names: Vec<&str> = team.software.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["firefox", "1091189122", "slack/darwin"]);
+        assert_eq!(team.software[0].self_service(), Some(true));
+        assert_eq!(team.software[0].package.as_ref().unwrap().hash_sha256.as_deref(), Some("abc"));
+    }
+}
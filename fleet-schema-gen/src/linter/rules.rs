@@ -1,5 +1,9 @@
-use super::error::LintError;
+use super::config::FleetLintConfig;
+use super::error::{LintError, RelatedLocation, Severity};
 use super::fleet_config::FleetConfig;
+use super::fleet_config::Policy;
+use super::repo::{GitopsRepo, Sourced, Team};
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
 
 /// Trait for linting rules
@@ -12,16 +16,52 @@ pub trait Rule: Send + Sync {
 
     /// Check the Fleet config and return any lint errors
     fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError>;
+
+    /// Whether the rule runs without being listed in `rules.enabled`.
+    ///
+    /// Opt-in rules (best-practice heuristics) override this to return `false`.
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    /// Apply project configuration (e.g. `[rules.<name>]` options).
+    ///
+    /// Called whenever the linter's `.fleetlint.toml` is loaded.
+    fn configure(&mut self, _config: &FleetLintConfig) {}
+}
+
+/// Trait for rules that check a whole GitOps repository (cross-file checks)
+pub trait WorkspaceRule: Send + Sync {
+    /// Name of the rule (e.g., "redundant-override")
+    fn name(&self) -> &'static str;
+
+    /// Description of what this rule checks
+    fn description(&self) -> &'static str;
+
+    /// Check the repository and return any lint errors (located in team files)
+    fn check(&self, repo: &GitopsRepo) -> Vec<LintError>;
+
+    /// Whether the rule runs without being listed in `rules.enabled`.
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    /// Apply project configuration; see [`Rule::configure`].
+    fn configure(&mut self, _config: &FleetLintConfig) {}
 }
 
 /// Collection of linting rules
 pub struct RuleSet {
     rules: Vec<Box<dyn Rule>>,
+    workspace_rules: Vec<Box<dyn WorkspaceRule>>,
 }
 
 impl RuleSet {
     pub fn new() -> Self {
-        Self { rules: Vec::new() }
+        Self {
+            rules: Vec::new(),
+            workspace_rules: Vec::new(),
+        }
     }
 
     /// Add a rule to the set
@@ -29,11 +69,31 @@ impl RuleSet {
         self.rules.push(rule);
     }
 
+    /// Add a workspace (whole-repository) rule to the set
+    pub fn add_workspace_rule(&mut self, rule: Box<dyn WorkspaceRule>) {
+        self.workspace_rules.push(rule);
+    }
+
     /// Get all rules
     pub fn rules(&self) -> &[Box<dyn Rule>] {
         &self.rules
     }
 
+    /// Get all workspace rules
+    pub fn workspace_rules(&self) -> &[Box<dyn WorkspaceRule>] {
+        &self.workspace_rules
+    }
+
+    /// Apply project configuration to every rule
+    pub fn configure(&mut self, config: &FleetLintConfig) {
+        for rule in &mut self.rules {
+            rule.configure(config);
+        }
+        for rule in &mut self.workspace_rules {
+            rule.configure(config);
+        }
+    }
+
     /// Create default ruleset with all built-in rules
     pub fn default_rules() -> Self {
         let mut set = Self::new();
@@ -45,6 +105,40 @@ impl RuleSet {
         set.add_rule(Box::new(IntervalValidationRule));
         set.add_rule(Box::new(DuplicateNamesRule));
         set.add_rule(Box::new(QuerySyntaxRule));
+        set.add_rule(Box::new(SqlDialectRule));
+        set.add_rule(Box::new(ConstantResultRule::default()));
+        set.add_rule(Box::new(EventTablesRule));
+        set.add_rule(Box::new(BenchmarkIdsRule));
+        set.add_rule(Box::new(OsqueryVersionRule));
+        set.add_rule(Box::new(ChromeTablesRule));
+        set.add_rule(Box::new(WindowsProfilesRule));
+        set.add_rule(Box::new(FleetVariablesRule));
+        set.add_rule(Box::new(WebhookSettingsRule::default()));
+        set.add_rule(Box::new(EnrollSecretsRule::default()));
+        set.add_rule(Box::new(HostSettingsRule::default()));
+        set.add_rule(Box::new(DeprecatedFieldsRule::default()));
+        set.add_rule(Box::new(CustomRulesRule::default()));
+        set.add_rule(Box::new(FileSizeRule::default()));
+
+        // Opt-in policy quality rules
+        set.add_rule(Box::new(PolicyResolutionRule));
+        set.add_rule(Box::new(CriticalPolicyPlatformRule));
+        set.add_rule(Box::new(PolicySelectStarRule));
+        set.add_rule(Box::new(PolicyDescriptionRule));
+        set.add_rule(Box::new(CommonMisspellingsRule::default()));
+        set.add_rule(Box::new(LocalizationRule::default()));
+        set.add_rule(Box::new(PathReferencesRule::default()));
+        set.add_rule(Box::new(NamingConventionsRule::default()));
+        set.add_rule(Box::new(StrictSchemaRule::default()));
+
+        // Cross-file rules
+        set.add_workspace_rule(Box::new(ContradictoryOverrideRule));
+        set.add_workspace_rule(Box::new(RedundantOverrideRule));
+        set.add_workspace_rule(Box::new(CrossTeamPoliciesRule));
+        set.add_workspace_rule(Box::new(CalendarEventsRule));
+        set.add_workspace_rule(Box::new(SharedEnrollSecretsRule));
+        set.add_workspace_rule(Box::new(ScheduleBudgetRule::default()));
+        set.add_workspace_rule(Box::new(RedundantQueriesRule));
 
         set
     }
@@ -244,8 +338,18 @@ impl Rule for TypeValidationRule {
         "Validates field types match expected values"
     }
 
-    fn check(&self, config: &FleetConfig, file: &Path, _source: &str) -> Vec<LintError> {
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
         let mut errors = Vec::new();
+        // Occurrences located so far, so repeated values map to successive lines
+        let mut located: HashMap<(&str, String), usize> = HashMap::new();
+        let mut locate = |error: LintError, key: &'static str, value: &str| {
+            let nth = located.entry((key, value.to_string())).or_insert(0);
+            *nth += 1;
+            match find_key_value(source, key, value, *nth - 1) {
+                Some((line, col)) => error.with_location(line, col).with_context(value),
+                None => error,
+            }
+        };
 
         // Check policies
         if let Some(policies) = &config.policies {
@@ -254,18 +358,23 @@ impl Rule for TypeValidationRule {
                     // Platform must be valid enum
                     if let Some(platform) = &policy.platform {
                         if !["darwin", "windows", "linux", "chrome"].contains(&platform.as_str()) {
-                            errors.push(
-                                LintError::error(
-                                    format!(
-                                        "Policy '{}' has invalid platform '{}'",
-                                        policy.name.as_deref().unwrap_or("unnamed"),
-                                        platform
-                                    ),
-                                    file,
-                                )
-                                .with_help("Valid platforms: darwin, windows, linux, chrome")
-                                .with_suggestion(find_similar_platform(platform))
-                            );
+                            let error = LintError::error(
+                                format!(
+                                    "Policy '{}' has invalid platform '{}'",
+                                    policy.name.as_deref().unwrap_or("unnamed"),
+                                    platform
+                                ),
+                                file,
+                            )
+                            .with_help("Valid platforms: darwin, windows, linux, chrome");
+                            errors.push(locate(
+                                match platform_alias(platform) {
+                                    Some(fixed) => error.with_fix(fixed),
+                                    None => error.with_suggestion(find_similar_platform(platform)),
+                                },
+                                "platform",
+                                platform,
+                            ));
                         }
                     }
                 }
@@ -295,29 +404,96 @@ impl Rule for TypeValidationRule {
 
                     // Logging must be valid enum
                     if let Some(logging) = &query.logging {
-                        if !["snapshot", "differential", "differential_ignore_removals"].contains(&logging.as_str()) {
-                            errors.push(
-                                LintError::error(
-                                    format!(
-                                        "Query '{}' has invalid logging type '{}'",
-                                        query.name.as_deref().unwrap_or("unnamed"),
-                                        logging
-                                    ),
-                                    file,
-                                )
-                                .with_help("Valid logging types: snapshot, differential, differential_ignore_removals")
-                                .with_suggestion(find_similar_logging(logging))
-                            );
+                        if !LOGGING_TYPES.contains(&logging.as_str()) {
+                            let error = LintError::error(
+                                format!(
+                                    "Query '{}' has invalid logging type '{}'",
+                                    query.name.as_deref().unwrap_or("unnamed"),
+                                    logging
+                                ),
+                                file,
+                            )
+                            .with_help("Valid logging types: snapshot, differential, differential_ignore_removals");
+                            // Only a difference in case is certain to be a typo
+                            let fixed = LOGGING_TYPES.iter().find(|valid| valid.eq_ignore_ascii_case(logging));
+                            errors.push(locate(
+                                match fixed {
+                                    Some(fixed) => error.with_fix(*fixed),
+                                    None => error.with_suggestion(find_similar_logging(logging)),
+                                },
+                                "logging",
+                                logging,
+                            ));
                         }
                     }
                 }
             }
         }
 
+        // Works on the source: yes/no values make the typed config fail to parse
+        errors.extend(boolean_value_errors(file, source));
+
         errors
     }
 }
 
+/// Boolean settings outside the `enable_*` family.
+const BOOLEAN_FIELDS: &[&str] = &[
+    "critical",
+    "calendar_events_enabled",
+    "observer_can_run",
+    "automations_enabled",
+    "discard_data",
+    "self_service",
+];
+
+/// YAML 1.1 booleans (`yes`, `off`, `True`) in boolean settings. Fleet
+/// parses YAML 1.2, where they are strings and the file is rejected.
+fn boolean_value_errors(file: &Path, source: &str) -> Vec<LintError> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let field = line.trim_start().trim_start_matches('-').trim_start();
+            let (key, rest) = field.split_once(':')?;
+            if !(BOOLEAN_FIELDS.contains(&key) || key.starts_with("enable_")) {
+                return None;
+            }
+            let value = rest.split(" #").next().unwrap_or("").trim();
+            let fixed = match value.to_lowercase().as_str() {
+                "true" | "false" if value == "true" || value == "false" => return None,
+                "yes" | "y" | "on" | "true" => "true",
+                "no" | "n" | "off" | "false" => "false",
+                _ => return None,
+            };
+
+            let col = line.len() - rest.trim_start().len();
+            Some(
+                LintError::error(format!("'{}' must be true or false, not '{}'", key, value), file)
+                    .with_location(idx + 1, col + 1)
+                    .with_context(value)
+                    .with_help("Fleet reads YAML 1.2, where yes/no/on/off are strings, not booleans")
+                    .with_fix(fixed),
+            )
+        })
+        .collect()
+}
+
+/// The nth line with `key: value` (quotes ignored), as 1-indexed line and
+/// column of the value.
+fn find_key_value(source: &str, key: &str, value: &str, nth: usize) -> Option<(usize, usize)> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let field = line.trim_start().trim_start_matches('-').trim_start();
+            let raw = field.strip_prefix(key)?.strip_prefix(':')?.trim();
+            let unquoted = raw.trim_matches('"').trim_matches('\'');
+            (unquoted == value).then(|| (idx + 1, line.rfind(unquoted).unwrap_or(0) + 1))
+        })
+        .nth(nth)
+}
+
 /// Check for security issues
 pub struct SecurityRule;
 
@@ -366,6 +542,12 @@ fn check_query_platform_compat(
     use super::osquery::OSQUERY_TABLES;
 
     let mut errors = Vec::new();
+
+    // ChromeOS table support is checked by the chrome-tables rule
+    if platform == "chrome" {
+        return errors;
+    }
+
     let query_lower = query.to_lowercase();
 
     // Extract table names from query (simple regex for FROM clauses)
@@ -397,7 +579,57 @@ fn check_query_platform_compat(
     errors
 }
 
+fn check_chrome_tables(
+    query: &str,
+    platform: &str,
+    item_name: &str,
+    file: &Path,
+) -> Vec<LintError> {
+    use super::osquery::{is_chrome_table, OSQUERY_TABLES};
+
+    let mut errors = Vec::new();
+    if !platform.split(',').any(|p| p.trim() == "chrome") {
+        return errors;
+    }
+
+    let query_lower = query.to_lowercase();
+    let re = regex::Regex::new(r"\b(?:from|join)\s+(\w+)").unwrap();
+    let mut seen = Vec::new();
+    for cap in re.captures_iter(&query_lower) {
+        let table = cap[1].to_string();
+        if is_chrome_table(&table) || seen.contains(&table) {
+            continue;
+        }
+
+        let mut supported: Vec<&str> = OSQUERY_TABLES
+            .values()
+            .filter(|t| t.supports_chrome())
+            .map(|t| t.name)
+            .collect();
+        supported.sort();
+
+        errors.push(
+            LintError::error(
+                format!(
+                    "{} uses table '{}' which is not supported on ChromeOS",
+                    item_name, table
+                ),
+                file,
+            )
+            .with_help(format!(
+                "fleetd for Chrome only supports: {}",
+                supported.join(", ")
+            ))
+        );
+        seen.push(table);
+    }
+
+    errors
+}
+
 /// Find the most similar valid logging type for a suggestion.
+const LOGGING_TYPES: &[&str] = &["snapshot", "differential", "differential_ignore_removals"];
+
 fn find_similar_logging(input: &str) -> String {
     let input_lower = input.to_lowercase();
 
@@ -415,6 +647,18 @@ fn find_similar_logging(input: &str) -> String {
     "snapshot".to_string()
 }
 
+/// The platform `input` certainly means: a valid platform in other case,
+/// or another name for one (`macOS`, `win64`).
+fn platform_alias(input: &str) -> Option<&'static str> {
+    match input.to_lowercase().as_str() {
+        "darwin" | "macos" | "mac" | "osx" => Some("darwin"),
+        "windows" | "win" | "win32" | "win64" => Some("windows"),
+        "linux" => Some("linux"),
+        "chrome" | "chromeos" => Some("chrome"),
+        _ => None,
+    }
+}
+
 /// Find the most similar valid platform for a suggestion.
 /// Returns the platform name itself (not a message) for use in code actions.
 fn find_similar_platform(input: &str) -> String {
@@ -508,67 +752,140 @@ impl Rule for DuplicateNamesRule {
         "Detects duplicate names within policies, queries, or labels"
     }
 
-    fn check(&self, config: &FleetConfig, file: &Path, _source: &str) -> Vec<LintError> {
-        use std::collections::HashSet;
-        let mut errors = Vec::new();
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::fleet_config::{LabelOrPath, PolicyOrPath, QueryOrPath};
 
-        // Check policies
-        if let Some(policies) = &config.policies {
-            let mut seen = HashSet::new();
-            for policy_or_path in policies {
-                if let super::fleet_config::PolicyOrPath::Policy(policy) = policy_or_path {
-                    if let Some(name) = &policy.name {
-                        if !seen.insert(name.clone()) {
-                            errors.push(
-                                LintError::error(
-                                    format!("Duplicate policy name: '{}'", name),
-                                    file,
-                                )
-                                .with_help("Policy names must be unique within the organization")
-                            );
-                        }
-                    }
-                }
-            }
+        let policies = config.policies.iter().flatten().filter_map(|p| match p {
+            PolicyOrPath::Policy(policy) => policy.name.as_deref(),
+            _ => None,
+        });
+        let queries = config.queries.iter().flatten().filter_map(|q| match q {
+            QueryOrPath::Query(query) => query.name.as_deref(),
+            _ => None,
+        });
+        let labels = config.labels.iter().flatten().filter_map(|l| match l {
+            LabelOrPath::Label(label) => label.name.as_deref(),
+            _ => None,
+        });
+
+        let mut errors = duplicate_name_errors("policy", policies, file, source);
+        errors.extend(duplicate_name_errors("query", queries, file, source));
+        errors.extend(duplicate_name_errors("label", labels, file, source));
+        errors
+    }
+}
+
+/// Errors for repeated names of one kind, located at each repeat and
+/// related to the first definition.
+fn duplicate_name_errors<'a>(
+    kind: &str,
+    names: impl Iterator<Item = &'a str>,
+    file: &Path,
+    source: &str,
+) -> Vec<LintError> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut errors = Vec::new();
+
+    for name in names {
+        let count = seen.entry(name).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            continue;
         }
 
-        // Check queries
-        if let Some(queries) = &config.queries {
-            let mut seen = HashSet::new();
-            for query_or_path in queries {
-                if let super::fleet_config::QueryOrPath::Query(query) = query_or_path {
-                    if let Some(name) = &query.name {
-                        if !seen.insert(name.clone()) {
-                            errors.push(
-                                LintError::error(
-                                    format!("Duplicate query name: '{}'", name),
-                                    file,
-                                )
-                                .with_help("Query names must be unique within the organization")
-                            );
-                        }
-                    }
-                }
-            }
+        let definitions = find_name_definitions(source, name);
+        let mut error = LintError::error(format!("Duplicate {} name: '{}'", kind, name), file).with_help(format!(
+            "{}{} names must be unique within the organization",
+            kind[..1].to_uppercase(),
+            &kind[1..]
+        ));
+        if let Some(&(line, col)) = definitions.get(*count - 1) {
+            error = error.with_location(line, col).with_context(name);
         }
+        if let Some(&first) = definitions.first() {
+            error = error.with_related(file, Some(first), format!("'{}' first defined here", name));
+        }
+        errors.push(error);
+    }
+    errors
+}
 
-        // Check labels
-        if let Some(labels) = &config.labels {
-            let mut seen = HashSet::new();
-            for label_or_path in labels {
-                if let super::fleet_config::LabelOrPath::Label(label) = label_or_path {
-                    if let Some(name) = &label.name {
-                        if !seen.insert(name.clone()) {
-                            errors.push(
-                                LintError::error(
-                                    format!("Duplicate label name: '{}'", name),
-                                    file,
-                                )
-                                .with_help("Label names must be unique within the organization")
-                            );
-                        }
-                    }
+/// Lines defining `name: <name>` (1-indexed line, column of the value).
+fn find_name_definitions(source: &str, name: &str) -> Vec<(usize, usize)> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let field = line.trim_start().trim_start_matches('-').trim_start();
+            let value = field.strip_prefix("name:")?.trim();
+            let unquoted = value.trim_matches('"').trim_matches('\'');
+            (unquoted == name).then(|| (idx + 1, line.find(unquoted).unwrap_or(0) + 1))
+        })
+        .collect()
+}
+
+/// Declarative rules from `[[custom_rules]]` in the config and its packs
+#[derive(Default)]
+pub struct CustomRulesRule {
+    rules: Vec<super::custom_rules::CustomRule>,
+    /// Rule ids listed in `rules.warn`
+    warn: Vec<String>,
+}
+
+impl Rule for CustomRulesRule {
+    fn name(&self) -> &'static str {
+        "custom-rules"
+    }
+
+    fn description(&self) -> &'static str {
+        "Runs the declarative rules from .fleetlint.toml and installed packs"
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.rules = config
+            .custom_rules
+            .iter()
+            .filter(|rule| !config.is_rule_disabled(&rule.id))
+            .filter(|rule| match rule.validate() {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("Skipping {}", e);
+                    false
+                }
+            })
+            .cloned()
+            .collect();
+        self.warn = config.rules.warn.clone();
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        for rule in &self.rules {
+            let items = match rule.section.as_str() {
+                "policies" => serde_yaml::to_value(&config.policies),
+                "queries" => serde_yaml::to_value(&config.queries),
+                _ => serde_yaml::to_value(&config.labels),
+            };
+            let Some(items) = items.ok().and_then(|v| v.as_sequence().cloned()) else {
+                continue;
+            };
+
+            let mut severity = rule.severity();
+            if severity == Severity::Error && self.warn.contains(&rule.id) {
+                severity = Severity::Warning;
+            }
+            for violation in rule.check(&items) {
+                let mut error = LintError::error(violation.message, file);
+                error.severity = severity.clone();
+                if let Some(help) = &rule.help {
+                    error = error.with_help(help.clone());
                 }
+                let location = violation.item.and_then(|name| find_name_definitions(source, &name).into_iter().next());
+                if let Some((line, column)) = location {
+                    error = error.with_location(line, column);
+                }
+                errors.push(error);
             }
         }
 
@@ -700,3 +1017,3731 @@ fn check_query_syntax(query: &str, item_name: &str, file: &Path) -> Vec<LintErro
 
     errors
 }
+
+/// Flag MySQL and Postgres syntax SQLite doesn't support
+pub struct SqlDialectRule;
+
+impl Rule for SqlDialectRule {
+    fn name(&self) -> &'static str {
+        "sql-dialect"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects MySQL/Postgres syntax in queries that SQLite doesn't support"
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::fleet_config::{LabelOrPath, PolicyOrPath, QueryOrPath};
+
+        // ((kind, index, "Policy 'name'"), query) of each inline item
+        let item = |kind: &'static str, idx: usize, what: &str, name: &Option<String>| {
+            (kind, idx, format!("{} '{}'", what, name.as_deref().unwrap_or("unnamed")))
+        };
+        let policies = config.policies.iter().flatten().enumerate().filter_map(|(idx, p)| match p {
+            PolicyOrPath::Policy(policy) => Some((item("policies", idx, "Policy", &policy.name), policy.query.as_deref()?)),
+            _ => None,
+        });
+        let queries = config.queries.iter().flatten().enumerate().filter_map(|(idx, q)| match q {
+            QueryOrPath::Query(query) => Some((item("queries", idx, "Query", &query.name), query.query.as_deref()?)),
+            _ => None,
+        });
+        let labels = config.labels.iter().flatten().enumerate().filter_map(|(idx, l)| match l {
+            LabelOrPath::Label(label) => Some((item("labels", idx, "Label", &label.name), label.query.as_deref()?)),
+            _ => None,
+        });
+
+        let mut errors = Vec::new();
+        for ((kind, idx, item), query) in policies.chain(queries).chain(labels) {
+            let query_line = field_line(source, kind, idx, "query");
+            for issue in super::sql_dialect::dialect_issues(query) {
+                let message = format!("{} {}", item, issue.message);
+                let error = if issue.rejected {
+                    LintError::error(message, file)
+                } else {
+                    LintError::warning(message, file)
+                }
+                .with_help(issue.help);
+
+                // Suggestions replace the construct, so only located ones carry one
+                let text = &query[issue.start..issue.end];
+                let nth = query[..issue.start].matches(text).count();
+                let location = query_line.filter(|_| !text.contains('\n')).and_then(|line| find_in_field(source, line, text, nth));
+                errors.push(match (location, issue.suggestion) {
+                    (Some((line, col)), Some(suggestion)) => {
+                        let error = error.with_location(line, col).with_context(text);
+                        // Backticks and double quotes mean the same to SQLite
+                        if issue.rejected { error.with_suggestion(suggestion) } else { error.with_fix(suggestion) }
+                    }
+                    (Some((line, col)), None) => error.with_location(line, col).with_context(text),
+                    (None, _) => error,
+                });
+            }
+        }
+        errors
+    }
+}
+
+/// Line of `field` of item `idx` of `kind`, in a team file or a lib file's
+/// bare list.
+fn field_line(source: &str, kind: &str, idx: usize, field: &str) -> Option<usize> {
+    use super::validate::pointer_line;
+
+    pointer_line(source, &format!("/{}/{}/{}", kind, idx, field))
+        .or_else(|| pointer_line(source, &format!("/{}/{}", idx, field)))
+}
+
+/// 1-indexed line and column of the `name:` value of item `idx` of `kind`
+/// (inside quotes, if any), so a suggestion can replace exactly the name.
+fn name_location(source: &str, kind: &str, idx: usize, name: &str) -> Option<(usize, usize)> {
+    let line = field_line(source, kind, idx, "name")?;
+    let text = source.lines().nth(line - 1)?;
+    let value = text.find("name:")? + "name:".len();
+    text[value..].find(name).map(|col| (line, value + col + 1))
+}
+
+/// 1-indexed line and column of the `nth` occurrence of `text` in the
+/// field starting on `line` (its key line and the more indented ones below).
+fn find_in_field(source: &str, line: usize, text: &str, nth: usize) -> Option<(usize, usize)> {
+    field_lines(source, line)
+        .flat_map(|(line, l)| l.match_indices(text).map(move |(col, _)| (line, col + 1)))
+        .nth(nth)
+}
+
+/// Like [`find_in_field`], matching `word` only as a whole word.
+fn find_word_in_field(source: &str, line: usize, word: &str, nth: usize) -> Option<(usize, usize)> {
+    let re = regex::Regex::new(&format!(r"\b{}\b", regex::escape(word))).ok()?;
+    field_lines(source, line)
+        .flat_map(|(line, l)| re.find_iter(l).map(move |m| (line, m.start() + 1)).collect::<Vec<_>>())
+        .nth(nth)
+}
+
+/// The 1-indexed lines of the field starting on `line`: its key line and
+/// the more indented ones below.
+fn field_lines(source: &str, line: usize) -> impl Iterator<Item = (usize, &str)> {
+    use super::sort::indent;
+
+    let lines: Vec<&str> = source.lines().collect();
+    let key_indent = lines.get(line.wrapping_sub(1)).map(|l| indent(l));
+    lines
+        .into_iter()
+        .enumerate()
+        .skip(line.saturating_sub(1))
+        .take_while(move |&(idx, l)| match key_indent {
+            Some(key_indent) => idx + 1 == line || l.trim().is_empty() || indent(l) > key_indent,
+            None => false,
+        })
+        .map(|(idx, l)| (idx + 1, l))
+}
+
+/// Options for the constant-result rule from `[rules.constant-result]`.
+///
+/// ```toml
+/// [rules.constant-result]
+/// allow = ["Always on *"]        # policy names allowed a constant result (globs)
+/// min_confidence = "certain"     # "likely" (default) or "certain"
+/// ```
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ConstantResultOptions {
+    pub allow: Vec<String>,
+    pub min_confidence: super::constant_result::Confidence,
+}
+
+/// Policies whose query passes or fails on every host
+#[derive(Default)]
+pub struct ConstantResultRule {
+    options: ConstantResultOptions,
+}
+
+impl Rule for ConstantResultRule {
+    fn name(&self) -> &'static str {
+        "constant-result"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects policy queries that always pass or always fail"
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.options = config.rule_options(self.name());
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::config::matches_glob;
+        use super::constant_result::{constant_result, Confidence, Outcome};
+        use super::fleet_config::PolicyOrPath;
+
+        let mut errors = Vec::new();
+        for (idx, item) in config.policies.iter().flatten().enumerate() {
+            let PolicyOrPath::Policy(policy) = item else { continue };
+            let Some(query) = policy.query.as_deref() else { continue };
+            let name = policy.name.as_deref().unwrap_or("unnamed");
+            if self.options.allow.iter().any(|pattern| matches_glob(pattern, name)) {
+                continue;
+            }
+            let Some(result) = constant_result(query).filter(|r| r.confidence >= self.options.min_confidence) else {
+                continue;
+            };
+
+            let (outcome, help) = match result.outcome {
+                Outcome::AlwaysPasses => (
+                    "always passes",
+                    "Policies pass when the query returns rows: filter with WHERE so that only compliant hosts return one",
+                ),
+                Outcome::AlwaysFails => ("always fails", "Remove the condition that can never match"),
+            };
+            let message = format!("Policy '{}' {}: its query {}", name, outcome, result.reason);
+            let error = match result.confidence {
+                Confidence::Certain => LintError::warning(message, file),
+                Confidence::Likely => LintError::info(message, file),
+            }
+            .with_help(format!("{}, or allow it in [rules.{}]", help, self.name()));
+            errors.push(match field_line(source, "policies", idx, "query") {
+                Some(line) => {
+                    let col = source.lines().nth(line - 1).map_or(0, |l| l.len() - l.trim_start().len());
+                    error.with_location(line, col + 1)
+                }
+                None => error,
+            });
+        }
+        errors
+    }
+}
+
+/// Flag evented tables read without a time window or joined without a
+/// condition, and scheduled queries on them without differential logging
+pub struct EventTablesRule;
+
+impl Rule for EventTablesRule {
+    fn name(&self) -> &'static str {
+        "event-tables"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks queries on evented tables have a time window, join conditions and differential logging"
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::event_tables::{event_table_issues, is_event_table, DIFFERENTIAL_LOGGING};
+        use super::fleet_config::{LabelOrPath, PolicyOrPath, QueryOrPath};
+        use super::osquery::query_tables;
+
+        let located = |error: LintError, line: Option<usize>| match line {
+            Some(line) => {
+                let col = source.lines().nth(line - 1).map_or(0, |l| l.len() - l.trim_start().len());
+                error.with_location(line, col + 1)
+            }
+            None => error,
+        };
+
+        let mut errors = Vec::new();
+        let mut check_sql = |kind: &str, idx: usize, item: String, query: &str| {
+            for issue in event_table_issues(query) {
+                let error = LintError::warning(format!("{} {}", item, issue.message), file).with_help(issue.help);
+                errors.push(located(error, field_line(source, kind, idx, "query")));
+            }
+        };
+        let label = |what: &str, name: &Option<String>| format!("{} '{}'", what, name.as_deref().unwrap_or("unnamed"));
+        for (idx, item) in config.policies.iter().flatten().enumerate() {
+            if let PolicyOrPath::Policy(policy) = item {
+                if let Some(query) = &policy.query {
+                    check_sql("policies", idx, label("Policy", &policy.name), query);
+                }
+            }
+        }
+        for (idx, item) in config.labels.iter().flatten().enumerate() {
+            if let LabelOrPath::Label(l) = item {
+                if let Some(query) = &l.query {
+                    check_sql("labels", idx, label("Label", &l.name), query);
+                }
+            }
+        }
+        let mut scheduled = Vec::new();
+        for (idx, item) in config.queries.iter().flatten().enumerate() {
+            if let QueryOrPath::Query(query) = item {
+                if let Some(sql) = &query.query {
+                    check_sql("queries", idx, label("Query", &query.name), sql);
+                    scheduled.push((idx, query, sql));
+                }
+            }
+        }
+
+        // Each run of a scheduled query only sees events since the last one
+        for (idx, query, sql) in scheduled {
+            if query.interval.unwrap_or(0) <= 0 {
+                continue;
+            }
+            let Some(table) = query_tables(sql).into_iter().find(|table| is_event_table(table)) else { continue };
+            let logging = query.logging.as_deref();
+            if logging.is_some_and(|logging| DIFFERENTIAL_LOGGING.contains(&logging)) {
+                continue;
+            }
+            let error = LintError::warning(
+                format!(
+                    "Query '{}' reads {} with {} logging",
+                    query.name.as_deref().unwrap_or("unnamed"),
+                    table,
+                    logging.unwrap_or("the default snapshot")
+                ),
+                file,
+            )
+            .with_help("Snapshot logging reports every buffered event on each run; set `logging: differential_ignore_removals`");
+            let line = field_line(source, "queries", idx, "logging");
+            let value = line.zip(logging).and_then(|(line, logging)| Some((line, source.lines().nth(line - 1)?.rfind(logging)?, logging)));
+            errors.push(match value {
+                Some((line, col, logging)) => error
+                    .with_location(line, col + 1)
+                    .with_context(logging)
+                    .with_suggestion("differential_ignore_removals"),
+                None => located(error, line.or_else(|| field_line(source, "queries", idx, "query"))),
+            });
+        }
+        errors
+    }
+}
+
+/// Check `# cis:` policy annotations against the bundled benchmark catalog
+pub struct BenchmarkIdsRule;
+
+impl Rule for BenchmarkIdsRule {
+    fn name(&self) -> &'static str {
+        "benchmark-ids"
+    }
+
+    fn description(&self) -> &'static str {
+        "Validates CIS benchmark IDs in policy annotations"
+    }
+
+    fn check(&self, _config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::benchmarks::{extract_annotations, Benchmark};
+
+        let mut errors = Vec::new();
+
+        for annotation in extract_annotations(source) {
+            let name = annotation.policy_name.as_deref().unwrap_or("unnamed");
+            let platform_benchmark = annotation.platform.as_deref().and_then(Benchmark::for_platform);
+
+            for id in &annotation.ids {
+                // With a platform, `benchmarks_for` is that platform's catalog
+                // whether or not it has the control
+                if annotation.benchmarks_for(id).iter().any(|b| b.control(id).is_some()) {
+                    continue;
+                }
+
+                let catalog = match platform_benchmark {
+                    Some(benchmark) => benchmark.display_name().to_string(),
+                    None => Benchmark::ALL
+                        .iter()
+                        .map(|b| b.display_name())
+                        .collect::<Vec<_>>()
+                        .join(" or "),
+                };
+
+                errors.push(
+                    LintError::warning(
+                        format!("Policy '{}' references unknown CIS control '{}'", name, id),
+                        file,
+                    )
+                    .with_location(annotation.line, 1)
+                    .with_help(format!("No control with this ID in the {} catalog", catalog))
+                );
+            }
+        }
+
+        errors
+    }
+}
+
+/// Check that ChromeOS queries only use tables supported by the Chrome extension
+pub struct ChromeTablesRule;
+
+impl Rule for ChromeTablesRule {
+    fn name(&self) -> &'static str {
+        "chrome-tables"
+    }
+
+    fn description(&self) -> &'static str {
+        "Validates ChromeOS queries only use tables supported by fleetd for Chrome"
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, _source: &str) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        // Check policies
+        if let Some(policies) = &config.policies {
+            for policy_or_path in policies {
+                if let super::fleet_config::PolicyOrPath::Policy(policy) = policy_or_path {
+                    if let (Some(platform), Some(query)) = (&policy.platform, &policy.query) {
+                        errors.extend(check_chrome_tables(
+                            query,
+                            platform,
+                            &format!("Policy '{}'", policy.name.as_deref().unwrap_or("unnamed")),
+                            file,
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Check queries
+        if let Some(queries) = &config.queries {
+            for query_or_path in queries {
+                if let super::fleet_config::QueryOrPath::Query(query) = query_or_path {
+                    if let (Some(platform), Some(query_sql)) = (&query.platform, &query.query) {
+                        errors.extend(check_chrome_tables(
+                            query_sql,
+                            platform,
+                            &format!("Query '{}'", query.name.as_deref().unwrap_or("unnamed")),
+                            file,
+                        ));
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Validate Windows SyncML profiles referenced from `windows_settings.custom_settings`
+pub struct WindowsProfilesRule;
+
+impl Rule for WindowsProfilesRule {
+    fn name(&self) -> &'static str {
+        "windows-profiles"
+    }
+
+    fn description(&self) -> &'static str {
+        "Validates SyncML structure, OMA-URIs, and data formats of Windows XML profiles"
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        let custom_settings = config
+            .controls
+            .as_ref()
+            .and_then(|c| c.get("windows_settings"))
+            .or(config.windows_settings.as_ref())
+            .and_then(|w| w.get("custom_settings"))
+            .and_then(|c| c.as_sequence());
+
+        let paths = custom_settings
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.get("path").and_then(|p| p.as_str()))
+            .filter(|path| path.ends_with(".xml"));
+
+        let base_dir = file.parent().unwrap_or(Path::new("."));
+        let mut errors = Vec::new();
+
+        for path in paths {
+            // Missing files are reported by path validation
+            let profile = base_dir.join(path);
+            let Ok(xml) = std::fs::read_to_string(&profile) else {
+                continue;
+            };
+
+            for issue in super::profiles::validate_windows_profile(&xml, &profile) {
+                errors.push(referenced_file_error(issue, path, file, source));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Validate `$FLEET_VAR_*` references in the YAML and in referenced profiles and scripts
+pub struct FleetVariablesRule;
+
+impl Rule for FleetVariablesRule {
+    fn name(&self) -> &'static str {
+        "fleet-variables"
+    }
+
+    fn description(&self) -> &'static str {
+        "Validates $FLEET_VAR_* references in profiles and scripts"
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::fleet_vars::{check_file, VarContext};
+
+        // References written directly in the YAML
+        let mut errors: Vec<LintError> = check_file(source, None, file);
+
+        // Profiles and scripts referenced from controls
+        let controls = config.controls.as_ref();
+        let sections = [
+            controls
+                .and_then(|c| c.get("macos_settings"))
+                .or(config.macos_settings.as_ref())
+                .and_then(|m| m.get("custom_settings")),
+            controls
+                .and_then(|c| c.get("windows_settings"))
+                .or(config.windows_settings.as_ref())
+                .and_then(|w| w.get("custom_settings")),
+            controls.and_then(|c| c.get("scripts")),
+        ];
+
+        let paths = sections
+            .into_iter()
+            .flatten()
+            .filter_map(|section| section.as_sequence())
+            .flatten()
+            .filter_map(|entry| entry.get("path").and_then(|p| p.as_str()));
+
+        let base_dir = file.parent().unwrap_or(Path::new("."));
+        for path in paths {
+            let referenced = base_dir.join(path);
+            let Some(context) = VarContext::for_path(&referenced) else {
+                continue;
+            };
+            // Signed profiles aren't UTF-8; skip anything unreadable
+            let Ok(text) = std::fs::read_to_string(&referenced) else {
+                continue;
+            };
+
+            for issue in check_file(&text, Some(context), &referenced) {
+                errors.push(referenced_file_error(issue, path, file, source));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Report an issue found in a referenced file at the YAML line that references it.
+///
+/// The message is prefixed with `path:line` so the location inside the
+/// referenced file isn't lost.
+fn referenced_file_error(issue: LintError, path: &str, file: &Path, source: &str) -> LintError {
+    let message = match issue.line {
+        Some(line) => format!("{}:{}: {}", path, line, issue.message),
+        None => format!("{}: {}", path, issue.message),
+    };
+    // Link to the issue inside the referenced file
+    let related = vec![RelatedLocation {
+        file: issue.file.clone(),
+        line: issue.line,
+        column: issue.column,
+        message: issue.message.clone(),
+    }];
+    let error = LintError {
+        message,
+        file: file.to_path_buf(),
+        line: None,
+        column: None,
+        context: None,
+        // A fix for the referenced file can't be applied to the YAML
+        suggestion: None,
+        fixable: false,
+        related,
+        ..issue
+    };
+
+    let location = source.lines().enumerate().find_map(|(idx, line)| {
+        let trimmed = line.trim().trim_start_matches('-').trim();
+        if trimmed.starts_with("path:") {
+            line.find(path).map(|col| (idx + 1, col + 1))
+        } else {
+            None
+        }
+    });
+
+    match location {
+        Some((line, col)) => error.with_location(line, col).with_context(path),
+        None => error,
+    }
+}
+
+/// Check `min_osquery_version` against the tables and columns a query uses.
+///
+/// Only items listed in [`super::osquery::TABLE_VERSIONS`] and
+/// [`super::osquery::COLUMN_VERSIONS`] carry a version; any other table or
+/// column is treated as available everywhere and never reported.
+pub struct OsqueryVersionRule;
+
+impl Rule for OsqueryVersionRule {
+    fn name(&self) -> &'static str {
+        "osquery-version"
+    }
+
+    fn description(&self) -> &'static str {
+        "Validates min_osquery_version against tables and columns with a tracked introduction version"
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::osquery::{compare_versions, query_version_requirements};
+
+        let mut errors = Vec::new();
+
+        let Some(queries) = &config.queries else {
+            return errors;
+        };
+
+        let well_formed = regex::Regex::new(r"^\d+(\.\d+){0,2}$").unwrap();
+
+        for (idx, query_or_path) in queries.iter().enumerate() {
+            let super::fleet_config::QueryOrPath::Query(query) = query_or_path else {
+                continue;
+            };
+            let Some(min_version) = &query.min_osquery_version else {
+                continue;
+            };
+
+            let name = query.name.as_deref().unwrap_or("unnamed");
+            // The key of this query's own mapping, not a look-alike line in
+            // another query's block scalar
+            let location = field_line(source, "queries", idx, "min_osquery_version").and_then(|line| {
+                let text = source.lines().nth(line - 1)?;
+                let value_start = text.find("min_osquery_version:")? + "min_osquery_version:".len();
+                let col = text[value_start..].find(min_version.as_str())? + value_start + 1;
+                Some((line, col))
+            });
+            let locate = |error: LintError| match location {
+                Some((line, col)) => error.with_location(line, col).with_context(min_version.clone()),
+                None => error,
+            };
+
+            if !well_formed.is_match(min_version.trim()) {
+                errors.push(locate(
+                    LintError::warning(
+                        format!("Query '{}' has invalid min_osquery_version '{}'", name, min_version),
+                        file,
+                    )
+                    .with_help("Use a dotted osquery version such as 5.9.1"),
+                ));
+                continue;
+            }
+
+            let Some(sql) = &query.query else {
+                continue;
+            };
+
+            // Requirements are sorted newest first
+            let newer: Vec<_> = query_version_requirements(sql)
+                .into_iter()
+                .filter(|req| compare_versions(req.version, min_version).is_gt())
+                .collect();
+
+            if let Some(newest) = newer.first() {
+                let items = newer
+                    .iter()
+                    .map(|req| format!("{} (osquery {})", req.item, req.version))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let error = LintError::warning(
+                    format!(
+                        "Query '{}' declares min_osquery_version {} but uses {} which needs osquery >= {}",
+                        name, min_version, newest.item, newest.version
+                    ),
+                    file,
+                )
+                .with_help(format!(
+                    "Newer than the declared minimum: {}. Only tables and columns added since osquery 4.0 with a tracked version are checked",
+                    items
+                ));
+                // Only safe to apply on the value of this query's own key
+                errors.push(match location {
+                    Some(_) => locate(error.with_fix(newest.version)),
+                    None => error.with_suggestion(newest.version),
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+// ============================================================================
+// Policy Quality Rules (opt-in)
+// ============================================================================
+
+/// Policies should tell end users how to fix a failure
+pub struct PolicyResolutionRule;
+
+impl Rule for PolicyResolutionRule {
+    fn name(&self) -> &'static str {
+        "policy-resolution"
+    }
+
+    fn description(&self) -> &'static str {
+        "Policies should include a resolution"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, _source: &str) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        if let Some(policies) = &config.policies {
+            for policy_or_path in policies {
+                if let super::fleet_config::PolicyOrPath::Policy(policy) = policy_or_path {
+                    if is_blank(&policy.resolution) {
+                        errors.push(
+                            LintError::warning(
+                                format!(
+                                    "Policy '{}' has no resolution",
+                                    policy.name.as_deref().unwrap_or("unnamed")
+                                ),
+                                file,
+                            )
+                            .with_help("Resolution text is shown to end users when the policy fails")
+                            .with_suggestion("resolution: \"Steps to bring this host into compliance\"")
+                        );
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Critical policies should be scoped to a single platform
+pub struct CriticalPolicyPlatformRule;
+
+impl Rule for CriticalPolicyPlatformRule {
+    fn name(&self) -> &'static str {
+        "critical-policy-platform"
+    }
+
+    fn description(&self) -> &'static str {
+        "Critical policies should target a specific platform"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, _source: &str) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        if let Some(policies) = &config.policies {
+            for policy_or_path in policies {
+                if let super::fleet_config::PolicyOrPath::Policy(policy) = policy_or_path {
+                    if policy.critical != Some(true) {
+                        continue;
+                    }
+
+                    let name = policy.name.as_deref().unwrap_or("unnamed");
+
+                    if is_blank(&policy.platform) {
+                        errors.push(
+                            LintError::warning(
+                                format!("Critical policy '{}' does not specify a platform", name),
+                                file,
+                            )
+                            .with_help("Without a platform, the policy runs on every host and may fail where its tables don't exist")
+                            .with_suggestion("platform: darwin")
+                        );
+                    } else if policy.platform.as_deref().is_some_and(|p| p.contains(',')) {
+                        errors.push(
+                            LintError::warning(
+                                format!(
+                                    "Critical policy '{}' targets multiple platforms ({})",
+                                    name,
+                                    policy.platform.as_deref().unwrap_or_default()
+                                ),
+                                file,
+                            )
+                            .with_help("Split critical policies into one policy per platform")
+                        );
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Policy queries should select a constant, not every column
+pub struct PolicySelectStarRule;
+
+impl Rule for PolicySelectStarRule {
+    fn name(&self) -> &'static str {
+        "policy-select-star"
+    }
+
+    fn description(&self) -> &'static str {
+        "Policy queries should use SELECT 1 rather than SELECT *"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, _source: &str) -> Vec<LintError> {
+        let mut errors = Vec::new();
+        let select_star_pattern = regex::Regex::new(r"(?i)\bSELECT\s+\*").unwrap();
+
+        if let Some(policies) = &config.policies {
+            for policy_or_path in policies {
+                if let super::fleet_config::PolicyOrPath::Policy(policy) = policy_or_path {
+                    if let Some(query) = &policy.query {
+                        if select_star_pattern.is_match(query) {
+                            errors.push(
+                                LintError::warning(
+                                    format!(
+                                        "Policy '{}' uses SELECT * in its query",
+                                        policy.name.as_deref().unwrap_or("unnamed")
+                                    ),
+                                    file,
+                                )
+                                .with_help("Policies pass when the query returns rows and fail when it returns none; filter with WHERE so that no results means failing")
+                                .with_suggestion(select_star_pattern.replace(query, "SELECT 1").to_string())
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Policies should explain what they check
+pub struct PolicyDescriptionRule;
+
+impl Rule for PolicyDescriptionRule {
+    fn name(&self) -> &'static str {
+        "policy-description"
+    }
+
+    fn description(&self) -> &'static str {
+        "Policies should have a non-empty description"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, _source: &str) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        if let Some(policies) = &config.policies {
+            for policy_or_path in policies {
+                if let super::fleet_config::PolicyOrPath::Policy(policy) = policy_or_path {
+                    if is_blank(&policy.description) {
+                        errors.push(
+                            LintError::warning(
+                                format!(
+                                    "Policy '{}' has an empty description",
+                                    policy.name.as_deref().unwrap_or("unnamed")
+                                ),
+                                file,
+                            )
+                            .with_help("Describe what the policy checks so reviewers and end users understand it")
+                        );
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+fn is_blank(value: &Option<String>) -> bool {
+    value.as_deref().map(|v| v.trim().is_empty()).unwrap_or(true)
+}
+
+/// Flag common misspellings in human-facing strings shown to end users in
+/// Fleet Desktop. Only known misspellings are reported; this is not a full
+/// spellcheck.
+#[derive(Default)]
+pub struct CommonMisspellingsRule {
+    options: super::misspellings::MisspellingOptions,
+}
+
+impl Rule for CommonMisspellingsRule {
+    fn name(&self) -> &'static str {
+        "common-misspellings"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags common misspellings in policy descriptions and resolutions"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.options = config.rule_options(self.name());
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::misspellings::check_text;
+
+        let mut errors = Vec::new();
+        // (item, kind, index in its list, field, text)
+        let mut texts: Vec<(String, &str, usize, &str, &str)> = Vec::new();
+
+        if let Some(policies) = &config.policies {
+            for (idx, policy_or_path) in policies.iter().enumerate() {
+                if let super::fleet_config::PolicyOrPath::Policy(policy) = policy_or_path {
+                    let name = format!("Policy '{}'", policy.name.as_deref().unwrap_or("unnamed"));
+                    if let Some(description) = &policy.description {
+                        texts.push((name.clone(), "policies", idx, "description", description));
+                    }
+                    if let Some(resolution) = &policy.resolution {
+                        texts.push((name, "policies", idx, "resolution", resolution));
+                    }
+                }
+            }
+        }
+
+        if let Some(queries) = &config.queries {
+            for (idx, query_or_path) in queries.iter().enumerate() {
+                if let super::fleet_config::QueryOrPath::Query(query) = query_or_path {
+                    if let Some(description) = &query.description {
+                        let name = format!("Query '{}'", query.name.as_deref().unwrap_or("unnamed"));
+                        texts.push((name, "queries", idx, "description", description));
+                    }
+                }
+            }
+        }
+
+        for (item_name, kind, idx, field, text) in texts {
+            let field_line = field_line(source, kind, idx, field);
+            // Repeated misspellings in one field point at successive occurrences
+            let mut seen: HashMap<String, usize> = HashMap::new();
+            for misspelling in check_text(text, &self.options) {
+                let nth = seen.entry(misspelling.word.clone()).or_insert(0);
+                let location = field_line.and_then(|line| find_word_in_field(source, line, &misspelling.word, *nth));
+                *nth += 1;
+
+                let mut error = LintError::warning(
+                    format!(
+                        "{} {} has a possible misspelling: '{}'",
+                        item_name, field, misspelling.word
+                    ),
+                    file,
+                )
+                .with_help(format!(
+                    "Did you mean '{}'? Add accepted words to [rules.common-misspellings] words in .fleetlint.toml",
+                    misspelling.suggestion
+                ))
+                .with_suggestion(misspelling.suggestion);
+
+                if let Some((line, col)) = location {
+                    error = error.with_location(line, col).with_context(misspelling.word);
+                }
+                errors.push(error);
+            }
+        }
+
+        errors
+    }
+}
+
+/// Options for the localization rule from `[rules.localization]`.
+///
+/// ```toml
+/// [rules.localization]
+/// locales = ["de", "fr"]     # required translations (default: inferred)
+/// fields = ["resolution"]    # translated fields (default: resolution)
+/// ```
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct LocalizationOptions {
+    /// Locales every policy must provide. When empty, the locales used by
+    /// any policy in the file are required from all of them.
+    pub locales: Vec<String>,
+
+    /// Fields with translated variants (`<field>_<locale>`).
+    pub fields: Vec<String>,
+}
+
+/// Check translated policy fields (e.g. `resolution_de`) are consistent
+#[derive(Default)]
+pub struct LocalizationRule {
+    options: LocalizationOptions,
+}
+
+impl LocalizationRule {
+    fn fields(&self) -> Vec<&str> {
+        if self.options.fields.is_empty() {
+            vec!["resolution"]
+        } else {
+            self.options.fields.iter().map(|f| f.as_str()).collect()
+        }
+    }
+}
+
+impl Rule for LocalizationRule {
+    fn name(&self) -> &'static str {
+        "localization"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks translated policy fields exist for every locale"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.options = config.rule_options(self.name());
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use std::collections::BTreeSet;
+
+        let mut errors = Vec::new();
+        let fields = self.fields();
+
+        // Policies with their index in the file's list
+        let policies: Vec<(usize, &super::fleet_config::Policy)> = config
+            .policies
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(idx, p)| match p {
+                super::fleet_config::PolicyOrPath::Policy(policy) => Some((idx, policy)),
+                _ => None,
+            })
+            .collect();
+
+        // Translated keys per policy, as (field, locale)
+        let translations: Vec<BTreeSet<(String, String)>> = policies
+            .iter()
+            .map(|(_, policy)| {
+                policy
+                    .other
+                    .as_mapping()
+                    .into_iter()
+                    .flat_map(|m| m.keys())
+                    .filter_map(|k| k.as_str())
+                    .filter_map(|key| {
+                        fields.iter().find_map(|field| {
+                            key.strip_prefix(field)
+                                .and_then(|rest| rest.strip_prefix('_'))
+                                .filter(|locale| !locale.is_empty())
+                                .map(|locale| (field.to_string(), locale.to_string()))
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let declared: BTreeSet<String> = self.options.locales.iter().cloned().collect();
+        let required: BTreeSet<String> = if declared.is_empty() {
+            translations.iter().flatten().map(|(_, locale)| locale.clone()).collect()
+        } else {
+            declared.clone()
+        };
+
+        for ((idx, policy), keys) in policies.iter().zip(&translations) {
+            let name = policy.name.as_deref().unwrap_or("unnamed");
+            let location = policy.name.as_deref().and_then(|n| name_location(source, "policies", *idx, n));
+            let locate = |error: LintError| match location {
+                Some((line, col)) => error.with_location(line, col),
+                None => error,
+            };
+
+            for field in &fields {
+                let has_base = match *field {
+                    "resolution" => policy.resolution.is_some(),
+                    "description" => policy.description.is_some(),
+                    custom => policy.other.get(custom).is_some(),
+                };
+                if !has_base {
+                    continue;
+                }
+
+                let missing: Vec<String> = required
+                    .iter()
+                    .filter(|locale| !keys.contains(&(field.to_string(), locale.to_string())))
+                    .map(|locale| format!("{}_{}", field, locale))
+                    .collect();
+
+                if !missing.is_empty() {
+                    errors.push(locate(
+                        LintError::warning(
+                            format!("Policy '{}' is missing translations: {}", name, missing.join(", ")),
+                            file,
+                        )
+                        .with_help(format!(
+                            "Every policy with a {} needs a translation for each locale ({})",
+                            field,
+                            required.iter().cloned().collect::<Vec<_>>().join(", ")
+                        )),
+                    ));
+                }
+            }
+
+            if !declared.is_empty() {
+                for (field, locale) in keys.iter().filter(|(_, l)| !declared.contains(l)) {
+                    errors.push(locate(
+                        LintError::warning(
+                            format!("Policy '{}' has {}_{} for undeclared locale '{}'", name, field, locale, locale),
+                            file,
+                        )
+                        .with_help("Add the locale to [rules.localization] locales in .fleetlint.toml"),
+                    ));
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Options for the prefer-path-references rule from
+/// `[rules.prefer-path-references]`.
+///
+/// ```toml
+/// [rules.prefer-path-references]
+/// kinds = ["policies", "queries"]   # default: policies, queries, labels, software
+/// allow = ["Emergency *"]           # item names allowed inline (globs)
+/// allow_files = ["default.yml"]     # files allowed inline items (globs)
+/// ```
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct PathReferencesOptions {
+    pub kinds: Vec<String>,
+    pub allow: Vec<String>,
+    pub allow_files: Vec<String>,
+}
+
+/// Inline items in team files, for repositories that keep everything in
+/// `lib/` and reference it with `- path:`
+#[derive(Default)]
+pub struct PathReferencesRule {
+    options: PathReferencesOptions,
+}
+
+impl PathReferencesRule {
+    fn checks(&self, kind: &str) -> bool {
+        self.options.kinds.is_empty() || self.options.kinds.iter().any(|k| k == kind)
+    }
+
+    fn allowed(&self, name: Option<&str>) -> bool {
+        use super::config::matches_glob;
+        name.is_some_and(|name| self.options.allow.iter().any(|pattern| matches_glob(pattern, name)))
+    }
+}
+
+impl Rule for PathReferencesRule {
+    fn name(&self) -> &'static str {
+        "prefer-path-references"
+    }
+
+    fn description(&self) -> &'static str {
+        "Team files should reference lib files instead of defining items inline"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.options = config.rule_options(self.name());
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::config::matches_glob;
+        use super::fleet_config::{LabelOrPath, PolicyOrPath, QueryOrPath};
+        use super::validate::pointer_line;
+
+        // Lib files are where definitions belong
+        if super::file_role::FileRole::detect(file).is_some() {
+            return Vec::new();
+        }
+        let path = file.to_string_lossy().replace('\\', "/");
+        if self.options.allow_files.iter().any(|pattern| {
+            matches_glob(pattern, &path) || file.file_name().is_some_and(|name| matches_glob(pattern, &name.to_string_lossy()))
+        }) {
+            return Vec::new();
+        }
+
+        // (kind, index, name) of inline items
+        let mut inline: Vec<(&str, usize, Option<&str>)> = Vec::new();
+        if self.checks("policies") {
+            for (idx, item) in config.policies.iter().flatten().enumerate() {
+                if let PolicyOrPath::Policy(policy) = item {
+                    inline.push(("policies", idx, policy.name.as_deref()));
+                }
+            }
+        }
+        if self.checks("queries") {
+            for (idx, item) in config.queries.iter().flatten().enumerate() {
+                if let QueryOrPath::Query(query) = item {
+                    inline.push(("queries", idx, query.name.as_deref()));
+                }
+            }
+        }
+        if self.checks("labels") {
+            for (idx, item) in config.labels.iter().flatten().enumerate() {
+                if let LabelOrPath::Label(label) = item {
+                    inline.push(("labels", idx, label.name.as_deref()));
+                }
+            }
+        }
+        let packages = config.software.as_ref().and_then(|s| s.get("packages")).and_then(|p| p.as_sequence());
+        if self.checks("software") {
+            for (idx, package) in packages.into_iter().flatten().enumerate() {
+                if package.get("path").is_none() {
+                    inline.push(("software/packages", idx, package.get("url").and_then(|u| u.as_str())));
+                }
+            }
+        }
+
+        inline
+            .into_iter()
+            .filter(|(_, _, name)| !self.allowed(*name))
+            .map(|(kind, idx, name)| {
+                let what = match kind {
+                    "policies" => "Policy",
+                    "queries" => "Query",
+                    "labels" => "Label",
+                    _ => "Software package",
+                };
+                let error = LintError::warning(
+                    format!("{} '{}' is defined inline", what, name.unwrap_or("unnamed")),
+                    file,
+                )
+                .with_help(format!(
+                    "Move it to a file under lib/ and reference it with `- path:` (the \"Extract to lib file\" code action does this), or allow it in [rules.{}]",
+                    self.name()
+                ));
+                match pointer_line(source, &format!("/{}/{}", kind, idx)) {
+                    Some(line) => {
+                        let col = source.lines().nth(line - 1).map_or(0, |l| l.len() - l.trim_start().len());
+                        error.with_location(line, col + 1)
+                    }
+                    None => error,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Validate files against the strict schema of their kind (opt-in)
+///
+/// Uses the schemas generated for the pinned `fleet_version`, or the
+/// snapshot embedded in the binary when there are none, so the check works
+/// without network access.
+pub struct StrictSchemaRule {
+    validators: super::validate::SchemaValidators,
+    /// Set when the snapshot is for another Fleet version than the pinned one
+    version_mismatch: Option<String>,
+}
+
+impl Default for StrictSchemaRule {
+    fn default() -> Self {
+        Self {
+            validators: super::validate::SchemaValidators::new(super::validate::SchemaSource::Bundled),
+            version_mismatch: None,
+        }
+    }
+}
+
+impl Rule for StrictSchemaRule {
+    fn name(&self) -> &'static str {
+        "strict-schema"
+    }
+
+    fn description(&self) -> &'static str {
+        "Files match the strict schema of their kind (generated or bundled)"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        use super::validate::{SchemaSource, SchemaValidators};
+
+        let source = match config.fleet_version.as_deref() {
+            Some(version) => SchemaSource::for_version(Path::new(&config.schema.versions_dir), version),
+            None => SchemaSource::Bundled,
+        };
+        self.version_mismatch = match (&source, config.fleet_version.as_deref()) {
+            (SchemaSource::Bundled, Some(version)) => super::bundled::version_mismatch(version),
+            _ => None,
+        };
+        self.validators = SchemaValidators::new(source);
+    }
+
+    fn check(&self, _config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        let validator = match self.validators.for_file(file) {
+            Ok(Some(validator)) => validator,
+            Ok(None) => return Vec::new(),
+            Err(e) => {
+                return vec![LintError::error(format!("Failed to load {}: {:#}", self.validators.source(), e), file)];
+            }
+        };
+        let mut help = format!("Checked against {}", self.validators.source());
+        if let Some(mismatch) = &self.version_mismatch {
+            help = format!("{}. {}", help, mismatch);
+        }
+        validator
+            .validate(source, file)
+            .violations
+            .into_iter()
+            .map(|violation| {
+                let message = match violation.pointer.as_deref() {
+                    Some(pointer) if !pointer.is_empty() => format!("{}: {}", pointer, violation.message),
+                    _ => violation.message,
+                };
+                let error = LintError::error(message, file).with_help(help.clone());
+                match violation.line {
+                    Some(line) => error.with_location(line, 1),
+                    None => error,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Warn when a file is over `thresholds.max_*_per_file`
+#[derive(Default)]
+pub struct FileSizeRule {
+    thresholds: super::config::ThresholdsConfig,
+}
+
+impl Rule for FileSizeRule {
+    fn name(&self) -> &'static str {
+        "file-size"
+    }
+
+    fn description(&self) -> &'static str {
+        "Warns when a file defines too many policies or queries, or has too many lines"
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.thresholds = config.thresholds.clone();
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        let stats = super::file_size::FileStats::new(config, source);
+        stats
+            .exceeded(&self.thresholds)
+            .into_iter()
+            .map(|exceeded| {
+                let message = match exceeded.what {
+                    "lines" => format!("File has {} lines (max {})", exceeded.count, exceeded.max),
+                    what => format!("File defines {} {} inline (max {})", exceeded.count, what, exceeded.max),
+                };
+                let error = LintError::warning(message, file).with_help(format!(
+                    "Split it into lib files and reference them with `- path:`; adjust thresholds.{} if the size is intended (lint -v shows a breakdown)",
+                    exceeded.setting
+                ));
+                match super::validate::pointer_line(source, &format!("/{}", exceeded.what)) {
+                    Some(line) => error.with_location(line, 1),
+                    None => error,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Team file names the naming-conventions rule accepts by default
+const TEAM_FILE_PATTERN: &str = r"^[a-z0-9]+(-[a-z0-9]+)*\.ya?ml$";
+
+/// Options for the naming-conventions rule from `[rules.naming-conventions]`.
+/// Every check is on by default.
+///
+/// ```toml
+/// [rules.naming-conventions]
+/// team_files = true                         # team file names match the pattern
+/// team_file_pattern = "^[a-z0-9-]+\\.yml$"  # default: lowercase words joined by dashes
+/// policy_names = true                       # "Platform - Description"
+/// query_names = true                        # snake_case
+/// lib_suffixes = true                       # .policies.yml / .queries.yml
+/// ```
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct NamingOptions {
+    pub team_files: bool,
+    pub team_file_pattern: Option<String>,
+    pub policy_names: bool,
+    pub query_names: bool,
+    pub lib_suffixes: bool,
+}
+
+impl Default for NamingOptions {
+    fn default() -> Self {
+        Self { team_files: true, team_file_pattern: None, policy_names: true, query_names: true, lib_suffixes: true }
+    }
+}
+
+/// Display name of a platform for policy names ("macOS - ...")
+fn platform_display(platform: &str) -> Option<&'static str> {
+    match platform_alias(platform.trim())? {
+        "darwin" => Some("macOS"),
+        "windows" => Some("Windows"),
+        "linux" => Some("Linux"),
+        _ => Some("ChromeOS"),
+    }
+}
+
+/// `name` in snake_case: words split at separators and lower-to-upper
+/// case changes
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            let boundary = c.is_uppercase() && prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit());
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+        prev = Some(c);
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+/// Names of team files, policies, queries and lib files
+#[derive(Default)]
+pub struct NamingConventionsRule {
+    options: NamingOptions,
+}
+
+impl NamingConventionsRule {
+    /// "Platform - Description" for a policy name that doesn't follow it
+    fn policy_name_suggestion(name: &str, platform: Option<&str>) -> Option<String> {
+        // "macOS: Firewall", "[macOS] Firewall"
+        if let Some((prefix, rest)) = name.split_once(": ") {
+            return Some(format!("{} - {}", prefix.trim(), rest.trim()));
+        }
+        if let Some((prefix, rest)) = name.strip_prefix('[').and_then(|n| n.split_once(']')) {
+            return Some(format!("{} - {}", prefix.trim(), rest.trim()));
+        }
+        let platforms: Vec<&str> = platform?.split(',').filter_map(platform_display).collect();
+        (!platforms.is_empty()).then(|| format!("{} - {}", platforms.join(", "), name.trim()))
+    }
+
+    fn check_file_name(&self, file: &Path) -> Option<LintError> {
+        use super::file_role::FileRole;
+
+        let file_name = file.file_name()?.to_str()?;
+        match FileRole::detect(file) {
+            Some(role @ (FileRole::Policies | FileRole::Queries)) if self.options.lib_suffixes => {
+                let suffix = if role == FileRole::Policies { ".policies.yml" } else { ".queries.yml" };
+                let stem = file_name.strip_suffix(".yml").or_else(|| file_name.strip_suffix(".yaml"))?;
+                if file_name.ends_with(suffix) || stem.ends_with(&suffix[..suffix.len() - 4]) {
+                    return None;
+                }
+                Some(
+                    LintError::warning(format!("Lib file '{}' should end in {}", file_name, suffix), file)
+                        .with_help(format!("Rename it to '{}{}'", stem, suffix)),
+                )
+            }
+            None if self.options.team_files && file.parent()?.file_name()? == "teams" => {
+                let pattern = self.options.team_file_pattern.as_deref().unwrap_or(TEAM_FILE_PATTERN);
+                let re = match regex::Regex::new(pattern) {
+                    Ok(re) => re,
+                    Err(e) => {
+                        return Some(
+                            LintError::error(format!("Invalid team_file_pattern '{}': {}", pattern, e), file)
+                                .with_help(format!("Fix the pattern in [rules.{}] in .fleetlint.toml", self.name())),
+                        )
+                    }
+                };
+                if re.is_match(file_name) {
+                    return None;
+                }
+                let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+                let suggested = format!("{}.yml", super::docs::slugify(stem));
+                let help = if re.is_match(&suggested) {
+                    format!("Rename it to '{}'", suggested)
+                } else {
+                    format!("Rename it to match {}", pattern)
+                };
+                Some(
+                    LintError::warning(format!("Team file name '{}' doesn't match {}", file_name, pattern), file)
+                        .with_help(help),
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Rule for NamingConventionsRule {
+    fn name(&self) -> &'static str {
+        "naming-conventions"
+    }
+
+    fn description(&self) -> &'static str {
+        "Team files, policies, queries and lib files follow naming conventions"
+    }
+
+    fn default_enabled(&self) -> bool {
+        false
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.options = config.rule_options(self.name());
+    }
+
+    fn check(&self, config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::fleet_config::{PolicyOrPath, QueryOrPath};
+
+        let mut errors: Vec<LintError> = self.check_file_name(file).into_iter().collect();
+        // Suggestions replace the name, so only located ones carry one
+        let locate = |error: LintError, kind: &str, idx: usize, name: &str, suggestion: Option<String>| {
+            match name_location(source, kind, idx, name) {
+                Some((line, col)) => {
+                    let error = error.with_location(line, col).with_context(name);
+                    match suggestion {
+                        Some(suggestion) => error.with_suggestion(suggestion),
+                        None => error,
+                    }
+                }
+                None => error,
+            }
+        };
+
+        if self.options.policy_names {
+            for (idx, item) in config.policies.iter().flatten().enumerate() {
+                let PolicyOrPath::Policy(policy) = item else { continue };
+                let Some(name) = policy.name.as_deref() else { continue };
+                let follows = name.split_once(" - ").is_some_and(|(platform, description)| {
+                    !platform.trim().is_empty() && !description.trim().is_empty()
+                });
+                if follows {
+                    continue;
+                }
+                let suggestion = Self::policy_name_suggestion(name, policy.platform.as_deref());
+                let error = LintError::warning(
+                    format!("Policy name '{}' should follow 'Platform - Description'", name),
+                    file,
+                )
+                .with_help(match &suggestion {
+                    Some(suggestion) => format!("Rename it to '{}'", suggestion),
+                    None => "Prefix the name with the platforms it checks, e.g. 'macOS - Firewall enabled'".to_string(),
+                });
+                errors.push(locate(error, "policies", idx, name, suggestion));
+            }
+        }
+
+        if self.options.query_names {
+            for (idx, item) in config.queries.iter().flatten().enumerate() {
+                let QueryOrPath::Query(query) = item else { continue };
+                let Some(name) = query.name.as_deref() else { continue };
+                let suggestion = snake_case(name);
+                if suggestion == name || suggestion.is_empty() {
+                    continue;
+                }
+                let error = LintError::warning(format!("Query name '{}' should be snake_case", name), file)
+                    .with_help(format!("Rename it to '{}'", suggestion));
+                errors.push(locate(error, "queries", idx, name, Some(suggestion)));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Options for the webhook settings rule from `[rules.webhook-settings]`.
+///
+/// ```toml
+/// [rules.webhook-settings]
+/// allow_private = false              # allow localhost/private destinations
+/// allowed_hosts = ["hooks.internal"] # hosts accepted regardless
+/// ```
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct WebhookOptions {
+    /// Accept destinations on localhost and private address ranges.
+    pub allow_private: bool,
+
+    /// Hosts accepted even when they are private or use plain HTTP.
+    pub allowed_hosts: Vec<String>,
+}
+
+/// Fields an enabled webhook needs besides its `enable_*` flag
+const WEBHOOK_REQUIRED_FIELDS: &[(&str, &[&str])] = &[
+    ("host_status_webhook", &["destination_url", "host_percentage", "days_count"]),
+    ("failing_policies_webhook", &["destination_url"]),
+    ("vulnerabilities_webhook", &["destination_url"]),
+    ("activities_webhook", &["destination_url"]),
+];
+
+/// Validate webhook destinations and required fields
+#[derive(Default)]
+pub struct WebhookSettingsRule {
+    options: WebhookOptions,
+}
+
+impl WebhookSettingsRule {
+    /// Problem with a destination URL, if any.
+    fn check_url(&self, url: &str) -> Option<String> {
+        // Environment references are resolved by fleetctl
+        if url.is_empty() || url.starts_with('$') {
+            return None;
+        }
+
+        let parsed = match reqwest::Url::parse(url) {
+            Ok(parsed) => parsed,
+            Err(e) => return Some(format!("Invalid webhook URL '{}': {}", url, e)),
+        };
+        let host = parsed.host_str().unwrap_or_default().trim_matches(['[', ']']);
+        if self.options.allowed_hosts.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+            return None;
+        }
+
+        if parsed.scheme() != "https" {
+            Some(format!("Webhook URL '{}' does not use HTTPS", url))
+        } else if !self.options.allow_private && is_private_host(host) {
+            Some(format!("Webhook URL '{}' points to a local or private address", url))
+        } else {
+            None
+        }
+    }
+}
+
+impl Rule for WebhookSettingsRule {
+    fn name(&self) -> &'static str {
+        "webhook-settings"
+    }
+
+    fn description(&self) -> &'static str {
+        "Validates webhook URLs (HTTPS, public destinations) and required fields of enabled webhooks"
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.options = config.rule_options(self.name());
+    }
+
+    fn check(&self, _config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::effective::get_path;
+        use super::validate::pointer_line;
+
+        let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(source) else {
+            return Vec::new();
+        };
+
+        let mut errors = Vec::new();
+        let locate = |error: LintError, path: &str| {
+            match pointer_line(source, &format!("/{}", path.replace('.', "/"))) {
+                Some(line) => error.with_location(line, 1),
+                None => error,
+            }
+        };
+
+        // Calendar webhooks are team settings
+        let calendar = "team_settings.integrations.google_calendar.webhook_url";
+        if let Some(url) = get_path(&value, calendar).and_then(|v| v.as_str()) {
+            if let Some(message) = self.check_url(url) {
+                errors.push(locate(LintError::warning(message, file), calendar));
+            }
+        }
+
+        for root in ["webhook_settings", "org_settings.webhook_settings", "team_settings.webhook_settings"] {
+            let Some(settings) = get_path(&value, root).and_then(|v| v.as_mapping()) else {
+                continue;
+            };
+
+            for (key, webhook) in settings {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                let path = format!("{}.{}", root, key);
+
+                // Legacy flat form: webhook_settings.url
+                if key == "url" {
+                    if let Some(message) = webhook.as_str().and_then(|url| self.check_url(url)) {
+                        errors.push(locate(LintError::warning(message, file), &path));
+                    }
+                    continue;
+                }
+
+                if let Some(url) = webhook.get("destination_url").and_then(|v| v.as_str()) {
+                    if let Some(message) = self.check_url(url) {
+                        errors.push(locate(
+                            LintError::warning(message, file)
+                                .with_help("Use a public HTTPS endpoint, or allow the host in [rules.webhook-settings]"),
+                            &format!("{}.destination_url", path),
+                        ));
+                    }
+                }
+
+                let enabled = webhook.get(format!("enable_{}", key)).and_then(|v| v.as_bool()) == Some(true);
+                if !enabled {
+                    continue;
+                }
+                let required = WEBHOOK_REQUIRED_FIELDS
+                    .iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, fields)| *fields)
+                    .unwrap_or_default();
+
+                let missing: Vec<&str> = required
+                    .iter()
+                    .copied()
+                    .filter(|field| match webhook.get(*field) {
+                        None | Some(serde_yaml::Value::Null) => true,
+                        Some(serde_yaml::Value::String(s)) => s.trim().is_empty(),
+                        Some(_) => false,
+                    })
+                    .collect();
+                if !missing.is_empty() {
+                    errors.push(locate(
+                        LintError::error(
+                            format!("{} is enabled but missing: {}", key, missing.join(", ")),
+                            file,
+                        )
+                        .with_help(format!("Set {} or disable the webhook", missing.join(", "))),
+                        &path,
+                    ));
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Whether a host is localhost or in a private/link-local range.
+fn is_private_host(host: &str) -> bool {
+    use std::net::IpAddr;
+
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        Ok(IpAddr::V6(ip)) => {
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || (first & 0xfe00) == 0xfc00 // unique local
+                || (first & 0xffc0) == 0xfe80 // link-local
+        }
+        Err(_) => {
+            let host = host.to_ascii_lowercase();
+            host == "localhost" || host.ends_with(".localhost") || host.ends_with(".local")
+        }
+    }
+}
+
+/// Options for the enroll secrets rule from `[rules.enroll-secrets]`.
+///
+/// ```toml
+/// [rules.enroll-secrets]
+/// min_length = 32    # shortest accepted literal secret
+/// ```
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct EnrollSecretOptions {
+    /// Minimum secret length. Fleet generates 24 random bytes, base64-encoded
+    /// to 32 characters.
+    pub min_length: usize,
+}
+
+impl Default for EnrollSecretOptions {
+    fn default() -> Self {
+        Self { min_length: 32 }
+    }
+}
+
+/// Enroll secrets in a file as (JSON Pointer, secret).
+fn enroll_secrets(value: &serde_yaml::Value) -> Vec<(String, String)> {
+    ["org_settings", "team_settings"]
+        .iter()
+        .filter_map(|root| Some((root, value.get(root)?.get("secrets")?.as_sequence()?)))
+        .flat_map(|(root, secrets)| {
+            secrets.iter().enumerate().filter_map(move |(idx, entry)| {
+                let secret = entry.get("secret")?.as_str()?;
+                Some((format!("/{}/secrets/{}/secret", root, idx), secret.to_string()))
+            })
+        })
+        .collect()
+}
+
+/// Check enroll secrets are env references of sufficient length
+#[derive(Default)]
+pub struct EnrollSecretsRule {
+    options: EnrollSecretOptions,
+}
+
+impl Rule for EnrollSecretsRule {
+    fn name(&self) -> &'static str {
+        "enroll-secrets"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects literal, short, or repeated enroll secrets"
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.options = config.rule_options(self.name());
+    }
+
+    fn check(&self, _config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(source) else {
+            return Vec::new();
+        };
+
+        let mut errors = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for (pointer, secret) in enroll_secrets(&value) {
+            let line = super::validate::pointer_line(source, &pointer);
+            let locate = |error: LintError| match line {
+                Some(line) => error.with_location(line, 1),
+                None => error,
+            };
+
+            if !seen.insert(secret.clone()) {
+                errors.push(locate(
+                    LintError::warning("Duplicate enroll secret", file)
+                        .with_help("Remove the duplicate entry"),
+                ));
+                continue;
+            }
+
+            // Env references are substituted by fleetctl from CI secrets
+            if secret.starts_with('$') {
+                continue;
+            }
+
+            errors.push(locate(
+                LintError::warning("Enroll secret is committed as a literal value", file)
+                    .with_help("Reference an environment variable and store the secret in your CI secrets")
+                    .with_suggestion("- secret: $FLEET_ENROLL_SECRET"),
+            ));
+
+            if secret.chars().count() < self.options.min_length {
+                errors.push(locate(
+                    LintError::warning(
+                        format!(
+                            "Enroll secret is {} characters; at least {} are recommended",
+                            secret.chars().count(),
+                            self.options.min_length
+                        ),
+                        file,
+                    )
+                    .with_help("Generate a random secret, e.g. `openssl rand -base64 24`"),
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Validate host expiry settings and `features` flags
+///
+/// Feature flags are checked against the generated schemas in
+/// `[schema] versions_dir`; without any, they are not checked.
+#[derive(Default)]
+pub struct HostSettingsRule {
+    /// Targeted Fleet version (latest when unset)
+    fleet_version: Option<String>,
+    features: Vec<super::effective::FeatureSpec>,
+}
+
+impl Rule for HostSettingsRule {
+    fn name(&self) -> &'static str {
+        "host-settings"
+    }
+
+    fn description(&self) -> &'static str {
+        "Validates host_expiry_settings and features flags for the targeted Fleet version"
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.fleet_version = config.fleet_version.clone();
+        self.features = super::effective::versioned_features(Path::new(&config.schema.versions_dir));
+    }
+
+    fn check(&self, _config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::osquery::compare_versions;
+        use serde_yaml::Value;
+
+        let Ok(value) = serde_yaml::from_str::<Value>(source) else {
+            return Vec::new();
+        };
+
+        let mut errors = Vec::new();
+        let locate = |error: LintError, pointer: &str| match super::validate::pointer_line(source, pointer) {
+            Some(line) => error.with_location(line, 1),
+            None => error,
+        };
+
+        for root in ["org_settings", "team_settings"] {
+            let Some(settings) = value.get(root) else {
+                continue;
+            };
+
+            if let Some(expiry) = settings.get("host_expiry_settings") {
+                let enabled = expiry.get("host_expiry_enabled").and_then(|v| v.as_bool()) == Some(true);
+                let pointer = format!("/{}/host_expiry_settings/host_expiry_window", root);
+                match expiry.get("host_expiry_window") {
+                    Some(window) if !window.is_null() && window.as_i64().is_none_or(|days| days <= 0) => {
+                        errors.push(locate(
+                            LintError::error(
+                                format!("host_expiry_window must be a positive number of days, got {}", yaml_scalar(window)),
+                                file,
+                            ),
+                            &pointer,
+                        ));
+                    }
+                    None | Some(Value::Null) if enabled => {
+                        errors.push(locate(
+                            LintError::error("host_expiry_enabled is true but host_expiry_window is not set", file)
+                                .with_help("Set host_expiry_window to the number of days before offline hosts are removed")
+                                .with_suggestion("host_expiry_window: 30"),
+                            &format!("/{}/host_expiry_settings", root),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+
+            let Some(features) = settings.get("features").and_then(|f| f.as_mapping()) else {
+                continue;
+            };
+            if self.features.is_empty() {
+                continue;
+            }
+            for key in features.keys().filter_map(|k| k.as_str()) {
+                let pointer = format!("/{}/features/{}", root, key);
+                match self.features.iter().find(|f| f.name == key) {
+                    None => {
+                        let mut error = LintError::warning(format!("Unknown feature flag '{}'", key), file)
+                            .with_help(format!(
+                                "Known features: {}",
+                                self.features.iter().map(|f| f.name.as_str()).collect::<Vec<_>>().join(", ")
+                            ));
+                        if let Some(similar) = self
+                            .features
+                            .iter()
+                            .map(|f| (super::fleet_vars::edit_distance(key, &f.name), f))
+                            .filter(|(distance, _)| *distance <= 3)
+                            .min_by_key(|(distance, _)| *distance)
+                            .map(|(_, feature)| feature)
+                        {
+                            let help = match &similar.description {
+                                Some(description) => format!("Did you mean {}? ({})", similar.name, description),
+                                None => format!("Did you mean {}?", similar.name),
+                            };
+                            error = error.with_help(help).with_suggestion(similar.name.clone());
+                        }
+                        errors.push(locate(error, &pointer));
+                    }
+                    Some(feature) => {
+                        let (Some(target), Some(since)) = (&self.fleet_version, &feature.since) else {
+                            continue;
+                        };
+                        if compare_versions(target, since).is_lt() {
+                            errors.push(locate(
+                                LintError::warning(
+                                    format!(
+                                        "Feature flag '{}' requires Fleet {} (targeting {})",
+                                        key, since, target
+                                    ),
+                                    file,
+                                )
+                                .with_help(format!(
+                                    "The flag first appears in the generated Fleet {} schema; remove it or raise the targeted Fleet version",
+                                    since
+                                )),
+                                &pointer,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// A YAML scalar as written (for messages).
+fn yaml_scalar(value: &serde_yaml::Value) -> String {
+    serde_yaml::to_string(value).unwrap_or_default().trim().to_string()
+}
+
+/// Flag fields deprecated by migrations up to the targeted Fleet version.
+///
+/// Deprecations come from the reviewed migrations (`migrations.toml`).
+/// Drafts from Fleet's changelog (`update --source changelog`) are merged
+/// into it by hand; when both are given, the reviewed ones come first.
+pub struct DeprecatedFieldsRule {
+    /// Targeted Fleet version (latest when unset)
+    fleet_version: Option<super::migrate::Version>,
+    migrations: Vec<super::migrate::Migration>,
+}
+
+impl Default for DeprecatedFieldsRule {
+    fn default() -> Self {
+        Self {
+            fleet_version: None,
+            migrations: super::migrate::loader::builtin_migrations(),
+        }
+    }
+}
+
+impl DeprecatedFieldsRule {
+    /// Deprecated dotted paths for a file, with the replacement and the
+    /// Fleet version that deprecated them.
+    fn deprecations(&self, file: &Path) -> Vec<(String, String, &super::migrate::Version)> {
+        use super::config::matches_glob;
+        use super::migrate::Transformation;
+
+        let path = file.to_string_lossy().replace('\\', "/");
+        let applies = |pattern: &str| matches_glob(pattern, &path) || matches_glob(&format!("**/{}", pattern), &path);
+
+        let mut seen = std::collections::HashSet::new();
+        self.migrations
+            .iter()
+            .filter(|m| self.fleet_version.as_ref().is_none_or(|target| m.to_version <= *target))
+            .flat_map(|m| {
+                m.transformations.iter().flat_map(move |t| match t {
+                    Transformation::FieldMove { source_pattern, target_location, fields, .. } if applies(source_pattern) => fields
+                        .iter()
+                        .map(|f| (f.clone(), format!("Move it to {} in the team file", target_location), &m.to_version))
+                        .collect(),
+                    Transformation::FieldRename { pattern, old_path, new_path } if applies(pattern) => {
+                        vec![(old_path.clone(), format!("Rename it to {}", new_path), &m.to_version)]
+                    }
+                    Transformation::FieldDelete { pattern, fields, reason } if applies(pattern) => fields
+                        .iter()
+                        .map(|f| (f.clone(), reason.clone().unwrap_or_else(|| "Remove it".to_string()), &m.to_version))
+                        .collect(),
+                    _ => Vec::new(),
+                })
+            })
+            // A key deprecated by several sources is reported once
+            .filter(|(path, _, _)| seen.insert(path.clone()))
+            .collect()
+    }
+}
+
+impl Rule for DeprecatedFieldsRule {
+    fn name(&self) -> &'static str {
+        "deprecated-fields"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects fields deprecated by the targeted Fleet version"
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.fleet_version = config.fleet_version.as_deref().and_then(super::migrate::Version::parse);
+    }
+
+    fn check(&self, _config: &FleetConfig, file: &Path, source: &str) -> Vec<LintError> {
+        use super::effective::get_path;
+
+        let deprecations = self.deprecations(file);
+        if deprecations.is_empty() {
+            return Vec::new();
+        }
+        let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(source) else {
+            return Vec::new();
+        };
+
+        deprecations
+            .into_iter()
+            .filter(|(path, _, _)| get_path(&value, path).is_some())
+            .map(|(path, help, version)| {
+                let error = LintError::warning(format!("{} is deprecated as of Fleet {}", path, version), file)
+                    .with_help(format!("{} (see `fleet-schema-gen migrate`)", help));
+                match super::validate::pointer_line(source, &format!("/{}", path.replace('.', "/"))) {
+                    Some(line) => error.with_location(line, 1),
+                    None => error,
+                }
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Workspace Rules
+// ============================================================================
+
+/// Flag team overrides that contradict the global configuration
+pub struct ContradictoryOverrideRule;
+
+impl WorkspaceRule for ContradictoryOverrideRule {
+    fn name(&self) -> &'static str {
+        "contradictory-override"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects controls set in both default.yml and no-team.yml, and teams disabling global security controls"
+    }
+
+    fn check(&self, repo: &GitopsRepo) -> Vec<LintError> {
+        use super::effective::{get_path, global_team, no_team, team_value, SETTINGS};
+
+        let mut errors = Vec::new();
+        let Some(global) = global_team(repo) else {
+            return errors;
+        };
+        let global_value = team_value(global);
+
+        // Fleet applies default.yml controls to "No team"; setting the same
+        // control in no-team.yml is ambiguous
+        if let Some(no_team) = no_team(repo) {
+            let no_team_value = team_value(no_team);
+            let both = global_value
+                .get("controls")
+                .and_then(|c| c.as_mapping())
+                .into_iter()
+                .flat_map(|m| m.keys())
+                .filter_map(|k| k.as_str())
+                .filter(|key| no_team_value.get("controls").and_then(|c| c.get(*key)).is_some());
+
+            for key in both {
+                errors.push(
+                    locate_setting(
+                        LintError::error(
+                            format!("controls.{} is set in both default.yml and no-team.yml", key),
+                            &no_team.file,
+                        ),
+                        &no_team.file,
+                        &format!("controls.{}", key),
+                    )
+                    .with_help("Set \"No team\" controls in only one file (prefer teams/no-team.yml)"),
+                );
+            }
+        }
+
+        for team in repo.teams.iter().filter(|t| t.file != global.file) {
+            let value = team_value(team);
+            for spec in SETTINGS.iter().filter(|s| s.security) {
+                let global_on = get_path(&global_value, spec.global_path).and_then(|v| v.as_bool()) == Some(true);
+                let team_off = get_path(&value, spec.team_path).and_then(|v| v.as_bool()) == Some(false);
+                if global_on && team_off {
+                    errors.push(
+                        locate_setting(
+                            LintError::warning(
+                                format!("Team '{}' disables {}, which is enabled globally", team.name, spec.key),
+                                &team.file,
+                            ),
+                            &team.file,
+                            spec.team_path,
+                        )
+                        .with_help("Remove the override or document why this team is exempt"),
+                    );
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Flag team settings that repeat the inherited global value
+pub struct RedundantOverrideRule;
+
+impl WorkspaceRule for RedundantOverrideRule {
+    fn name(&self) -> &'static str {
+        "redundant-override"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects team settings that repeat the value inherited from default.yml"
+    }
+
+    fn check(&self, repo: &GitopsRepo) -> Vec<LintError> {
+        use super::effective::{effective_configs, Origin, SETTINGS};
+
+        let mut errors = Vec::new();
+        for config in effective_configs(repo) {
+            let Some(file) = &config.file else {
+                continue;
+            };
+            for spec in SETTINGS.iter().filter(|s| s.inherited) {
+                let Some(setting) = config.settings.get(spec.key) else {
+                    continue;
+                };
+                if setting.origin == Origin::Team && setting.overrides.as_ref() == Some(&setting.value) {
+                    errors.push(
+                        locate_setting(
+                            LintError::info(
+                                format!("Team '{}' sets {} to the global value", config.team, spec.key),
+                                file,
+                            ),
+                            file,
+                            spec.team_path,
+                        )
+                        .with_help("Remove the setting to inherit it from default.yml"),
+                    );
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Detect duplicate or conflicting policies across teams
+pub struct CrossTeamPoliciesRule;
+
+impl WorkspaceRule for CrossTeamPoliciesRule {
+    fn name(&self) -> &'static str {
+        "cross-team-policies"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects policies duplicated or conflicting across teams"
+    }
+
+    fn check(&self, repo: &GitopsRepo) -> Vec<LintError> {
+        // (team, policy name, normalized SQL, critical, defining file, index in it)
+        let policies: Vec<(&str, &str, String, bool, &Path, usize)> = repo
+            .teams
+            .iter()
+            .flat_map(|team| {
+                team.policies.iter().filter_map(move |sourced| {
+                    let policy = &sourced.item;
+                    Some((
+                        team.name.as_str(),
+                        policy.name.as_deref()?,
+                        normalize_sql(policy.query.as_deref()?),
+                        policy.critical.unwrap_or(false),
+                        sourced.file.as_path(),
+                        sourced.index,
+                    ))
+                })
+            })
+            .collect();
+
+        let mut errors = Vec::new();
+        for (i, first) in policies.iter().enumerate() {
+            for second in &policies[i + 1..] {
+                let (team_a, name_a, sql_a, critical_a, file_a, index_a) = first;
+                let (team_b, name_b, sql_b, critical_b, file_b, index_b) = second;
+
+                // Shared lib files are the consolidated form; same team is duplicate-names' job
+                if file_a == file_b || team_a == team_b {
+                    continue;
+                }
+
+                let pair = format!("'{}' ({}) and '{}' ({})", name_a, team_a, name_b, team_b);
+                let error = if sql_a == sql_b && name_a != name_b {
+                    LintError::warning(format!("Policies {} have identical queries but different names", pair), *file_b)
+                        .with_help("Use one name, and move the policy to a lib file referenced by both teams")
+                } else if sql_a != sql_b && name_a == name_b {
+                    LintError::warning(format!("Policies {} share a name but have different queries", pair), *file_b)
+                        .with_help("Rename one policy, or align the queries if they check the same thing")
+                } else if sql_a == sql_b && critical_a != critical_b {
+                    LintError::warning(format!("Policies {} are identical but differ in criticality", pair), *file_b)
+                        .with_help("Decide whether the policy is critical and share it from a lib file")
+                } else if sql_a == sql_b {
+                    LintError::info(format!("Policy {} is duplicated across teams", pair), *file_b)
+                        .with_help("Move the policy to a lib file and reference it with `path:` from both teams")
+                } else {
+                    continue;
+                };
+
+                let locate = |file: &Path, index: usize, name: &str| {
+                    std::fs::read_to_string(file)
+                        .ok()
+                        .and_then(|source| name_location(&source, "policies", index, name))
+                };
+                let error = error.with_related(
+                    *file_a,
+                    locate(file_a, *index_a, name_a),
+                    format!("'{}' is defined here for {}", name_a, team_a),
+                );
+                errors.push(match locate(file_b, *index_b, name_b) {
+                    Some((line, col)) => error.with_location(line, col),
+                    None => error,
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+/// Check calendar-enabled policies have a calendar integration to use
+pub struct CalendarEventsRule;
+
+impl WorkspaceRule for CalendarEventsRule {
+    fn name(&self) -> &'static str {
+        "calendar-events"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks policies with calendar_events_enabled belong to teams with Google Calendar configured"
+    }
+
+    fn check(&self, repo: &GitopsRepo) -> Vec<LintError> {
+        use super::effective::{get_path, global_team, team_value};
+
+        let mut errors = Vec::new();
+        let global = global_team(repo);
+        let global_configured = global
+            .map(team_value)
+            .as_ref()
+            .and_then(|value| get_path(value, "org_settings.integrations.google_calendar"))
+            .and_then(|v| v.as_sequence())
+            .is_some_and(|calendars| !calendars.is_empty());
+
+        for team in &repo.teams {
+            let calendar_policies: Vec<&Sourced<Policy>> = team
+                .policies
+                .iter()
+                .filter(|p| p.item.calendar_events_enabled == Some(true))
+                .collect();
+            if calendar_policies.is_empty() {
+                continue;
+            }
+
+            let is_global = global.is_some_and(|g| g.file == team.file);
+            let value = team_value(team);
+            let team_enabled = get_path(&value, "team_settings.integrations.google_calendar.enable_calendar_events")
+                .and_then(|v| v.as_bool())
+                == Some(true);
+            let has_webhook = get_path(&value, "team_settings.integrations.google_calendar.webhook_url")
+                .and_then(|v| v.as_str())
+                .is_some_and(|url| !url.trim().is_empty());
+
+            let (problem, help) = if is_global {
+                (
+                    "calendar events only apply to team policies".to_string(),
+                    "Move the policy to a team file",
+                )
+            } else if !global_configured {
+                (
+                    "no Google Calendar integration is configured in default.yml".to_string(),
+                    "Add org_settings.integrations.google_calendar (api_key_json and domain) to default.yml",
+                )
+            } else if !team_enabled || !has_webhook {
+                (
+                    format!("team '{}' does not enable calendar events", team.name),
+                    "Set team_settings.integrations.google_calendar.enable_calendar_events: true and webhook_url",
+                )
+            } else {
+                continue;
+            };
+
+            for policy in calendar_policies {
+                let name = policy.item.name.as_deref().unwrap_or("unnamed");
+                let mut error = LintError::warning(
+                    format!("Policy '{}' has calendar_events_enabled, but {}", name, problem),
+                    &policy.file,
+                )
+                .with_help(help);
+                // The policy's own calendar_events_enabled key
+                let location = std::fs::read_to_string(&policy.file).ok().and_then(|source| {
+                    let item = super::owners::item_lines(&source, policy.item.name.as_deref()?)?;
+                    source
+                        .lines()
+                        .enumerate()
+                        .take(item.end)
+                        .skip(item.start)
+                        .find_map(|(idx, line)| line.find("calendar_events_enabled:").map(|col| (idx + 1, col + 1)))
+                });
+                if let Some((line, col)) = location {
+                    error = error.with_location(line, col);
+                }
+                errors.push(error);
+            }
+        }
+
+        errors
+    }
+}
+
+/// Detect enroll secrets shared between teams
+pub struct SharedEnrollSecretsRule;
+
+impl WorkspaceRule for SharedEnrollSecretsRule {
+    fn name(&self) -> &'static str {
+        "shared-enroll-secrets"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects the same enroll secret (or environment variable) used by more than one team"
+    }
+
+    fn check(&self, repo: &GitopsRepo) -> Vec<LintError> {
+        use super::effective::team_value;
+        use std::collections::HashMap;
+
+        let mut errors = Vec::new();
+        // Secret -> first team using it
+        let mut owners: HashMap<String, &str> = HashMap::new();
+
+        for team in &repo.teams {
+            for (pointer, secret) in enroll_secrets(&team_value(team)) {
+                match owners.get(secret.as_str()) {
+                    Some(owner) if *owner != team.name => {
+                        let what = if secret.starts_with('$') {
+                            format!("the same variable {}", secret)
+                        } else {
+                            "the same secret".to_string()
+                        };
+                        let mut error = LintError::error(
+                            format!("Team '{}' uses {} as team '{}'", team.name, what, owner),
+                            &team.file,
+                        )
+                        .with_help("Hosts enroll into the team owning the secret; give every team its own secret");
+                        let line = std::fs::read_to_string(&team.file)
+                            .ok()
+                            .and_then(|source| super::validate::pointer_line(&source, &pointer));
+                        if let Some(line) = line {
+                            error = error.with_location(line, 1);
+                        }
+                        errors.push(error);
+                    }
+                    Some(_) => {}
+                    None => {
+                        owners.insert(secret, &team.name);
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Warn when a team's scheduled queries exceed `thresholds.max_schedule_load`
+pub struct ScheduleBudgetRule {
+    budget: f64,
+}
+
+impl Default for ScheduleBudgetRule {
+    fn default() -> Self {
+        Self { budget: super::config::ThresholdsConfig::default().max_schedule_load }
+    }
+}
+
+impl WorkspaceRule for ScheduleBudgetRule {
+    fn name(&self) -> &'static str {
+        "schedule-budget"
+    }
+
+    fn description(&self) -> &'static str {
+        "Warns when a team's scheduled queries (including global ones) likely exceed the performance budget"
+    }
+
+    fn configure(&mut self, config: &FleetLintConfig) {
+        self.budget = config.thresholds.max_schedule_load;
+    }
+
+    fn check(&self, repo: &GitopsRepo) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        for load in super::schedule::team_loads(repo) {
+            if load.load <= self.budget {
+                continue;
+            }
+            let heaviest: Vec<String> = load
+                .heaviest()
+                .iter()
+                .take(3)
+                .map(|q| format!("'{}' ({:.0}/h, every {}s)", q.name, q.load, q.interval))
+                .collect();
+            let error = LintError::warning(
+                format!(
+                    "Team '{}' schedules about {:.0} table reads per host per hour (budget {:.0})",
+                    load.team, load.load, self.budget
+                ),
+                &load.file,
+            )
+            .with_help(format!(
+                "Heaviest: {}. Raise their intervals or drop unused queries; adjust thresholds.max_schedule_load if the load is intended",
+                heaviest.join(", ")
+            ));
+            errors.push(locate_setting(error, &load.file, "queries"));
+        }
+
+        errors
+    }
+}
+
+/// Detect active queries that collect data another active query already collects
+pub struct RedundantQueriesRule;
+
+impl WorkspaceRule for RedundantQueriesRule {
+    fn name(&self) -> &'static str {
+        "redundant-queries"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects active queries identical to, or selecting a subset of, another active query on the same platforms"
+    }
+
+    fn check(&self, repo: &GitopsRepo) -> Vec<LintError> {
+        use super::effective::global_team;
+
+        // (name, columns, rest of the statement, platforms, defining file, index in it)
+        type Active<'a> = (&'a str, BTreeSet<String>, String, Vec<String>, &'a Path, usize);
+        fn active(team: &Team) -> Vec<Active<'_>> {
+            team.queries
+                .iter()
+                .filter(|sourced| {
+                    let query = &sourced.item;
+                    query.interval.is_some_and(|i| i > 0) || query.automations_enabled == Some(true)
+                })
+                .filter_map(|sourced| {
+                    let query = &sourced.item;
+                    let (columns, rest) = select_parts(query.query.as_deref()?)?;
+                    let platforms = query
+                        .platform
+                        .as_deref()
+                        .unwrap_or("")
+                        .split(',')
+                        .map(|p| p.trim().to_lowercase())
+                        .filter(|p| !p.is_empty())
+                        .collect();
+                    Some((query.name.as_deref()?, columns, rest, platforms, sourced.file.as_path(), sourced.index))
+                })
+                .collect()
+        }
+
+        let global = global_team(repo);
+        let global_queries = global.map(active).unwrap_or_default();
+
+        let mut errors = Vec::new();
+        for team in &repo.teams {
+            let is_global = global.is_some_and(|g| g.file == team.file);
+            let own = if is_global { global_queries.clone() } else { active(team) };
+            // Hosts of a team run its queries and the global ones
+            let inherited = if is_global { &[][..] } else { &global_queries[..] };
+            let visible: Vec<&Active> = own.iter().chain(inherited).collect();
+
+            for (i, (name, columns, rest, platforms, file, index)) in own.iter().enumerate() {
+                for (j, (other, other_columns, other_rest, other_platforms, other_file, other_index)) in
+                    visible.iter().enumerate()
+                {
+                    // An empty platform list means every platform
+                    let covers_platforms = other_platforms.is_empty()
+                        || (!platforms.is_empty() && platforms.iter().all(|p| other_platforms.contains(p)));
+                    if i == j || rest != other_rest || !covers_platforms {
+                        continue;
+                    }
+
+                    let identical = columns == other_columns && platforms == other_platforms;
+                    let subset = (other_columns.contains("*") && !columns.contains("*"))
+                        || (columns.is_subset(other_columns) && columns != other_columns)
+                        || (columns == other_columns && platforms != other_platforms);
+                    // Report identical pairs once, on the later query
+                    let error = if identical && j < i {
+                        LintError::warning(format!("Query '{}' is identical to query '{}'", name, other), *file)
+                            .with_help(format!("Remove '{}' or merge the two; both collect the same data", name))
+                    } else if subset && !identical {
+                        LintError::info(
+                            format!("Query '{}' collects a subset of what query '{}' collects", name, other),
+                            *file,
+                        )
+                        .with_help(format!(
+                            "'{}' reads the same tables with the same filters on the same platforms; consolidate into it",
+                            other
+                        ))
+                    } else {
+                        continue;
+                    };
+
+                    let locate = |file: &Path, index: usize, name: &str| {
+                        std::fs::read_to_string(file)
+                            .ok()
+                            .and_then(|source| name_location(&source, "queries", index, name))
+                    };
+                    let error = error.with_related(
+                        *other_file,
+                        locate(other_file, *other_index, other),
+                        format!("'{}' is defined here", other),
+                    );
+                    errors.push(match locate(file, *index, name) {
+                        Some((line, col)) => error.with_location(line, col),
+                        None => error,
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Split a SELECT into its sorted column list and the normalized rest of
+/// the statement (`from ...`), so column order does not matter.
+fn select_parts(sql: &str) -> Option<(BTreeSet<String>, String)> {
+    let sql = normalize_sql(sql);
+    let body = sql.strip_prefix("select ")?;
+    if body.starts_with("distinct ") {
+        return None;
+    }
+
+    let mut depth = 0i32;
+    let mut columns = BTreeSet::new();
+    let mut start = 0;
+    for (idx, c) in body.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                columns.insert(body[start..idx].trim().to_string());
+                start = idx + 1;
+            }
+            _ if depth == 0 && body[idx..].starts_with(" from ") => {
+                columns.insert(body[start..idx].trim().to_string());
+                return Some((columns, body[idx + 1..].to_string()));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Normalize SQL for comparison: case, whitespace, and trailing semicolons.
+fn normalize_sql(sql: &str) -> String {
+    sql.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(';')
+        .trim_end()
+        .to_lowercase()
+}
+
+/// Locate a dotted setting path in a team file.
+fn locate_setting(error: LintError, file: &Path, path: &str) -> LintError {
+    let pointer = format!("/{}", path.replace('.', "/"));
+    let line = std::fs::read_to_string(file)
+        .ok()
+        .and_then(|source| super::validate::pointer_line(&source, &pointer));
+    match line {
+        Some(line) => error.with_location(line, 1),
+        None => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::fleet_config::{Policy, PolicyOrPath};
+
+    fn config_with(policy: Policy) -> FleetConfig {
+        FleetConfig {
+            policies: Some(vec![PolicyOrPath::Policy(policy)]),
+            ..Default::default()
+        }
+    }
+
+    fn complete_policy() -> Policy {
+        Policy {
+            name: Some("Firewall enabled".to_string()),
+            description: Some("Checks the application firewall".to_string()),
+            query: Some("SELECT 1 FROM alf WHERE global_state >= 1;".to_string()),
+            platform: Some("darwin".to_string()),
+            critical: Some(true),
+            resolution: Some("Enable the firewall in System Settings".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn run(rule: &dyn Rule, config: &FleetConfig) -> Vec<LintError> {
+        rule.check(config, Path::new("test.yml"), "")
+    }
+
+    #[test]
+    fn test_policy_quality_rules_are_opt_in() {
+        assert!(!PolicyResolutionRule.default_enabled());
+        assert!(!CriticalPolicyPlatformRule.default_enabled());
+        assert!(!PolicySelectStarRule.default_enabled());
+        assert!(!PolicyDescriptionRule.default_enabled());
+        assert!(RequiredFieldsRule.default_enabled());
+    }
+
+    #[test]
+    fn test_complete_policy_passes_quality_rules() {
+        let config = config_with(complete_policy());
+        assert!(run(&PolicyResolutionRule, &config).is_empty());
+        assert!(run(&CriticalPolicyPlatformRule, &config).is_empty());
+        assert!(run(&PolicySelectStarRule, &config).is_empty());
+        assert!(run(&PolicyDescriptionRule, &config).is_empty());
+    }
+
+    #[test]
+    fn test_policy_missing_resolution_and_description() {
+        let config = config_with(Policy {
+            resolution: None,
+            description: Some("   ".to_string()),
+            ..complete_policy()
+        });
+        assert_eq!(run(&PolicyResolutionRule, &config).len(), 1);
+        assert_eq!(run(&PolicyDescriptionRule, &config).len(), 1);
+    }
+
+    #[test]
+    fn test_critical_policy_platform() {
+        let no_platform = config_with(Policy { platform: None, ..complete_policy() });
+        assert_eq!(run(&CriticalPolicyPlatformRule, &no_platform).len(), 1);
+
+        let multi = config_with(Policy { platform: Some("darwin,linux".to_string()), ..complete_policy() });
+        assert_eq!(run(&CriticalPolicyPlatformRule, &multi).len(), 1);
+
+        let not_critical = config_with(Policy { platform: None, critical: Some(false), ..complete_policy() });
+        assert!(run(&CriticalPolicyPlatformRule, &not_critical).is_empty());
+    }
+
+    #[test]
+    fn test_policy_select_star() {
+        let config = config_with(Policy {
+            query: Some("SELECT * FROM alf WHERE global_state >= 1;".to_string()),
+            ..complete_policy()
+        });
+        let errors = run(&PolicySelectStarRule, &config);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].suggestion.as_deref(),
+            Some("SELECT 1 FROM alf WHERE global_state >= 1;")
+        );
+    }
+
+    #[test]
+    fn test_osquery_version_rule() {
+        let source = r#"queries:
+  - name: Firewall rules
+    query: SELECT name FROM windows_firewall_rules;
+    min_osquery_version: 5.0.1
+  - name: Up to date
+    query: SELECT name FROM windows_firewall_rules;
+    min_osquery_version: 5.9.1
+  - name: Bogus
+    query: SELECT 1;
+    min_osquery_version: latest
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let errors = OsqueryVersionRule.check(&config, Path::new("test.yml"), source);
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].message.contains("needs osquery >= 5.6.0"));
+        assert_eq!(errors[0].line, Some(4));
+        assert_eq!(errors[0].suggestion.as_deref(), Some("5.6.0"));
+        assert!(errors[1].message.contains("invalid min_osquery_version"));
+        assert_eq!(errors[1].line, Some(10));
+
+        // Tables without a tracked version are never reported
+        let source = r#"queries:
+  - name: Santa rules
+    query: SELECT * FROM santa_rules;
+    min_osquery_version: "1.0"
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        assert!(!crate::linter::osquery::TABLE_VERSIONS.contains_key("santa_rules"));
+        assert!(OsqueryVersionRule.check(&config, Path::new("test.yml"), source).is_empty());
+    }
+
+    #[test]
+    fn test_osquery_version_rule_locates_own_key() {
+        let source = r#"queries:
+  - name: Notes
+    description: |
+      min_osquery_version: 4.0 is not enough here
+    query: SELECT 1;
+  - name: Updates
+    query: SELECT * FROM windows_update_history;
+    min_osquery_version: "4.0"
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let errors = OsqueryVersionRule.check(&config, Path::new("test.yml"), source);
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.starts_with("Query 'Updates'"));
+        assert_eq!((errors[0].line, errors[0].column), (Some(8), Some(27)));
+    }
+
+    #[test]
+    fn test_benchmark_ids_rule() {
+        let source = r#"policies:
+  - name: Firewall enabled
+    # cis: 2.2.1, 9.9.9
+    platform: darwin
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+  - name: Any platform
+    # cis: 2.2.1
+    query: SELECT 1;
+"#;
+        let errors = BenchmarkIdsRule.check(&FleetConfig::default(), Path::new("test.yml"), source);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("'9.9.9'"));
+        assert_eq!(errors[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_chrome_tables_rule() {
+        let config = config_with(Policy {
+            platform: Some("chrome".to_string()),
+            query: Some("SELECT 1 FROM os_version JOIN processes USING (name);".to_string()),
+            ..complete_policy()
+        });
+        let errors = run(&ChromeTablesRule, &config);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("'processes'"));
+
+        // Non-Chrome platforms are left to platform-compatibility
+        assert!(run(&ChromeTablesRule, &config_with(complete_policy())).is_empty());
+        assert!(run(&PlatformCompatibilityRule, &config).is_empty());
+    }
+
+    #[test]
+    fn test_common_misspellings_rule() {
+        let source = r#"policies:
+  - name: Firewall enabled
+    description: Checks the firewal is on.
+    resolution: Turn the firewal on in System Settings.
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let mut rule = CommonMisspellingsRule::default();
+        assert!(!rule.default_enabled());
+
+        let errors = rule.check(&config, Path::new("test.yml"), source);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line, Some(3));
+        assert_eq!(errors[1].line, Some(4));
+        assert_eq!(errors[1].suggestion.as_deref(), Some("firewall"));
+
+        // Located in the field itself, not at an earlier occurrence
+        let source = r#"# firewal: see below
+- name: firewal check
+  query: SELECT 1;
+  description: |
+    Whether the firewal is on.
+"#;
+        let config = FleetConfig {
+            policies: Some(serde_yaml::from_str::<Vec<Policy>>(source).unwrap().into_iter().map(PolicyOrPath::Policy).collect()),
+            ..Default::default()
+        };
+        let errors = rule.check(&config, Path::new("lib/policies.yml"), source);
+        assert_eq!(errors.iter().map(|e| (e.line, e.column)).collect::<Vec<_>>(), vec![(Some(5), Some(17))]);
+
+        let lint_config = FleetLintConfig::from_str("[rules.common-misspellings]\nwords = [\"firewal\"]\n").unwrap();
+        rule.configure(&lint_config);
+        assert!(rule.check(&config, Path::new("test.yml"), source).is_empty());
+    }
+
+    #[test]
+    fn test_localization_rule_infers_locales() {
+        let source = r#"policies:
+  - name: Firewall enabled
+    resolution: Turn on the firewall.
+    resolution_de: Firewall einschalten.
+  - name: FileVault enabled
+    resolution: Turn on FileVault.
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let errors = LocalizationRule::default().check(&config, Path::new("test.yml"), source);
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("'FileVault enabled' is missing translations: resolution_de"));
+        assert_eq!(errors[0].line, Some(5));
+
+        // Located on the policy lacking the translation, past earlier mentions
+        let source = r#"policies:
+  - path: ../lib/firewall.yml
+  - name: Disk (CIS)
+    resolution: Same as Disk (CIS) on macOS.
+    resolution_de: Wie Disk (CIS) auf macOS.
+  - name: Disk
+    resolution: Encrypt the Disk (CIS) volume.
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let errors = LocalizationRule::default().check(&config, Path::new("test.yml"), source);
+        assert_eq!(errors.iter().map(|e| (e.line, e.column)).collect::<Vec<_>>(), vec![(Some(6), Some(11))]);
+    }
+
+    #[test]
+    fn test_custom_rules_rule() {
+        let source = r#"policies:
+  - name: Firewall enabled
+    platform: darwin
+  - name: FileVault enabled
+    platform: darwin
+    resolution: Turn on FileVault.
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let lint_config = FleetLintConfig::from_str(
+            "[rules]\nwarn = [\"acme/resolution\"]\ndisabled = [\"acme/platform\"]\n\n\
+             [[custom_rules]]\nid = \"acme/resolution\"\nsection = \"policies\"\nfield = \"resolution\"\nrequired = true\nseverity = \"error\"\n\n\
+             [[custom_rules]]\nid = \"acme/platform\"\nsection = \"policies\"\nfield = \"platform\"\nvalues = [\"windows\"]\n",
+        )
+        .unwrap();
+        let mut rule = CustomRulesRule::default();
+        rule.configure(&lint_config);
+
+        let errors = rule.check(&config, Path::new("test.yml"), source);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Policy 'Firewall enabled' is missing `resolution` (acme/resolution)");
+        assert_eq!(errors[0].severity, Severity::Warning);
+        assert_eq!(errors[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_file_size_rule() {
+        let source = "name: Team\npolicies:\n  - name: A\n  - name: B\n  - path: ../lib/c.yml\nqueries:\n  - name: Q\n";
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let mut rule = FileSizeRule::default();
+        assert!(rule.check(&config, Path::new("teams/team.yml"), source).is_empty());
+
+        rule.configure(&FleetLintConfig::from_str("[thresholds]\nmax_policies_per_file = 1\nmax_lines_per_file = 5\n").unwrap());
+        let errors = rule.check(&config, Path::new("teams/team.yml"), source);
+        let found: Vec<(&str, Option<usize>)> = errors.iter().map(|e| (e.message.as_str(), e.line)).collect();
+        assert_eq!(
+            found,
+            vec![("File defines 2 policies inline (max 1)", Some(2)), ("File has 7 lines (max 5)", None)]
+        );
+        assert!(errors[0].help.as_deref().unwrap().contains("thresholds.max_policies_per_file"));
+    }
+
+    #[test]
+    fn test_strict_schema_rule() {
+        let rule = StrictSchemaRule::default();
+        assert!(!rule.default_enabled());
+
+        let source = "- name: FileVault\n  query: SELECT 1;\n  platfrom: darwin\n";
+        let config = FleetConfig::default();
+        let errors = rule.check(&config, Path::new("lib/macos/policies/filevault.yml"), source);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.starts_with("/0: ") && errors[0].message.contains("platfrom"));
+        assert_eq!(errors[0].line, Some(1));
+        assert!(errors[0].help.as_deref().unwrap().contains("bundled"));
+
+        // No strict schema for software files
+        assert!(rule.check(&config, Path::new("lib/software/firefox.yml"), "url: x\nbogus: 1\n").is_empty());
+
+        // A pinned version without generated schemas falls back to the snapshot, with a warning
+        let mut rule = StrictSchemaRule::default();
+        rule.configure(&FleetLintConfig::from_str("fleet_version = \"4.58.0\"\n[schema]\nversions_dir = \"/nonexistent\"\n").unwrap());
+        let errors = rule.check(&config, Path::new("default.yml"), "not_a_fleet_key: true\n");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].help.as_deref().unwrap().contains("not 4.58.0"));
+    }
+
+    #[test]
+    fn test_sql_dialect_rule() {
+        let source = r#"policies:
+  - name: Chrome installed
+    query: SELECT 1 FROM apps WHERE name ILIKE '%chrome%';
+queries:
+  - name: Apps
+    query: |
+      SELECT `name`, size::int
+      FROM apps
+      OFFSET 10 LIMIT 5;
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let errors = SqlDialectRule.check(&config, Path::new("default.yml"), source);
+        let found: Vec<_> = errors
+            .iter()
+            .map(|e| (e.severity.clone(), e.line.zip(e.column), e.context.as_deref(), e.suggestion.as_deref(), e.fixable))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Severity::Error, Some((3, 42)), Some("ILIKE"), Some("LIKE"), false),
+                (Severity::Warning, Some((7, 14)), Some("`name`"), Some("\"name\""), true),
+                (Severity::Error, Some((7, 22)), Some("size::int"), Some("CAST(size AS INTEGER)"), false),
+                (Severity::Error, Some((9, 7)), Some("OFFSET 10 LIMIT 5"), Some("LIMIT 5 OFFSET 10"), false),
+            ]
+        );
+        assert_eq!(errors[0].message, "Policy 'Chrome installed' uses ILIKE, which SQLite doesn't support");
+    }
+
+    #[test]
+    fn test_constant_result_rule() {
+        let source = r#"policies:
+  - name: Always on
+    query: SELECT 1;
+  - name: Never
+    query: SELECT 1 FROM alf WHERE 1 = 0;
+  - name: Firewall
+    query: SELECT 1 FROM alf;
+  - name: Real
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let mut rule = ConstantResultRule::default();
+        let errors = rule.check(&config, Path::new("default.yml"), source);
+        let found: Vec<_> = errors.iter().map(|e| (e.severity.clone(), e.message.as_str(), e.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (Severity::Warning, "Policy 'Always on' always passes: its query has no FROM or WHERE clause", Some(3)),
+                (Severity::Warning, "Policy 'Never' always fails: its query WHERE 1 = 0 is always false", Some(5)),
+                (
+                    Severity::Info,
+                    "Policy 'Firewall' always passes: its query reads alf without filtering it, so it passes wherever the table has a row",
+                    Some(7)
+                ),
+            ]
+        );
+
+        rule.configure(
+            &FleetLintConfig::from_str("[rules.constant-result]\nallow = [\"Always *\"]\nmin_confidence = \"certain\"\n").unwrap(),
+        );
+        let errors = rule.check(&config, Path::new("default.yml"), source);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.starts_with("Policy 'Never'"));
+    }
+
+    #[test]
+    fn test_event_tables_rule() {
+        let source = r#"queries:
+  - name: processes
+    query: SELECT * FROM process_events;
+    interval: 300
+    logging: snapshot
+  - name: sockets
+    query: SELECT * FROM socket_events WHERE time > strftime('%s', 'now') - 300;
+    interval: 300
+  - name: recent
+    query: SELECT * FROM process_events WHERE time > 1;
+    interval: 300
+    logging: differential
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let errors = EventTablesRule.check(&config, Path::new("default.yml"), source);
+        let found: Vec<_> =
+            errors.iter().map(|e| (e.message.as_str(), e.line.zip(e.column), e.suggestion.as_deref())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Query 'processes' reads process_events without a time window", Some((3, 5)), None),
+                ("Query 'processes' reads process_events with snapshot logging", Some((5, 14)), Some("differential_ignore_removals")),
+                ("Query 'sockets' reads socket_events with the default snapshot logging", Some((7, 5)), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_naming_conventions_rule() {
+        let source = r#"policies:
+  - name: macOS - Firewall enabled
+    query: SELECT 1 FROM alf;
+  - name: Disk encrypted
+    query: SELECT 1;
+    platform: darwin,linux
+  - name: "Windows: BitLocker"
+    query: SELECT 1;
+  - name: Anything
+    query: SELECT 1;
+queries:
+  - name: uptime_check
+    query: SELECT * FROM uptime;
+  - name: USB Devices
+    query: SELECT * FROM usb_devices;
+  - name: diskSpace2Check
+    query: SELECT 1;
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let mut rule = NamingConventionsRule::default();
+        let errors = rule.check(&config, Path::new("teams/Work Stations.yml"), source);
+        let found: Vec<(&str, Option<usize>, Option<&str>)> =
+            errors.iter().map(|e| (e.message.as_str(), e.line, e.suggestion.as_deref())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Team file name 'Work Stations.yml' doesn't match ^[a-z0-9]+(-[a-z0-9]+)*\\.ya?ml$", None, None),
+                ("Policy name 'Disk encrypted' should follow 'Platform - Description'", Some(4), Some("macOS, Linux - Disk encrypted")),
+                ("Policy name 'Windows: BitLocker' should follow 'Platform - Description'", Some(7), Some("Windows - BitLocker")),
+                ("Policy name 'Anything' should follow 'Platform - Description'", Some(9), None),
+                ("Query name 'USB Devices' should be snake_case", Some(14), Some("usb_devices")),
+                ("Query name 'diskSpace2Check' should be snake_case", Some(16), Some("disk_space2_check")),
+            ]
+        );
+        assert_eq!(errors[0].help.as_deref(), Some("Rename it to 'work-stations.yml'"));
+
+        // The policy's own name is located, not an earlier mention of it
+        let source = r#"- name: Firewall check
+  query: SELECT 1;
+  description: Firewall is the same as Disk encryption (CIS)
+- name: Firewall
+  query: SELECT 1;
+- name: "Disk encryption (CIS)"
+  query: SELECT 1;
+"#;
+        let config = FleetConfig {
+            policies: Some(serde_yaml::from_str::<Vec<Policy>>(source).unwrap().into_iter().map(PolicyOrPath::Policy).collect()),
+            ..Default::default()
+        };
+        let errors = NamingConventionsRule::default().check(&config, Path::new("lib/policies/disk.policies.yml"), source);
+        let found: Vec<(Option<usize>, Option<usize>, Option<&str>)> =
+            errors.iter().map(|e| (e.line, e.column, e.context.as_deref())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some(1), Some(9), Some("Firewall check")),
+                (Some(4), Some(9), Some("Firewall")),
+                (Some(6), Some(10), Some("Disk encryption (CIS)")),
+            ]
+        );
+
+        // Lib files need their kind in the name
+        let lib = rule.check(&FleetConfig::default(), Path::new("lib/policies/disk.yml"), "");
+        assert_eq!(lib[0].message, "Lib file 'disk.yml' should end in .policies.yml");
+        assert_eq!(lib[0].help.as_deref(), Some("Rename it to 'disk.policies.yml'"));
+        assert!(rule.check(&FleetConfig::default(), Path::new("lib/queries/disk.queries.yml"), "").is_empty());
+
+        // Each check can be turned off, and the team file pattern replaced
+        rule.configure(
+            &FleetLintConfig::from_str(
+                "[rules.naming-conventions]\npolicy_names = false\nquery_names = false\nlib_suffixes = false\nteam_file_pattern = \"^[A-Z]\"\n",
+            )
+            .unwrap(),
+        );
+        assert!(rule.check(&config, Path::new("teams/Work Stations.yml"), source).is_empty());
+        assert!(rule.check(&config, Path::new("lib/policies/disk.yml"), "").is_empty());
+        assert_eq!(rule.check(&config, Path::new("teams/work.yml"), source)[0].help.as_deref(), Some("Rename it to match ^[A-Z]"));
+    }
+
+    #[test]
+    fn test_path_references_rule() {
+        let source = r#"name: Workstations
+policies:
+  - path: ../lib/policies/disk.yml
+  - name: Emergency patch
+    query: SELECT 1;
+  - name: Firewall
+    query: SELECT 1 FROM alf;
+queries:
+  - name: Uptime
+    query: SELECT * FROM uptime;
+software:
+  packages:
+    - path: ../lib/software/slack.yml
+    - url: https://example.com/zoom.pkg
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let mut rule = PathReferencesRule::default();
+        rule.configure(
+            &FleetLintConfig::from_str("[rules.prefer-path-references]\nkinds = [\"policies\", \"software\"]\nallow = [\"Emergency *\"]\n")
+                .unwrap(),
+        );
+
+        let errors = rule.check(&config, Path::new("teams/workstations.yml"), source);
+        let found: Vec<(&str, Option<usize>, Option<usize>)> =
+            errors.iter().map(|e| (e.message.as_str(), e.line, e.column)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Policy 'Firewall' is defined inline", Some(6), Some(3)),
+                ("Software package 'https://example.com/zoom.pkg' is defined inline", Some(14), Some(5)),
+            ]
+        );
+
+        // Lib files and allowed files are left alone
+        assert!(rule.check(&config, Path::new("lib/all.policies.yml"), source).is_empty());
+        rule.configure(&FleetLintConfig::from_str("[rules.prefer-path-references]\nallow_files = [\"teams/*.yml\"]\n").unwrap());
+        assert!(rule.check(&config, Path::new("teams/workstations.yml"), source).is_empty());
+        assert_eq!(rule.check(&config, Path::new("default.yml"), source).len(), 4);
+    }
+
+    #[test]
+    fn test_localization_rule_declared_locales() {
+        let source = r#"policies:
+  - name: Firewall enabled
+    resolution: Turn on the firewall.
+    resolution_de: Firewall einschalten.
+    resolution_es: Activa el firewall.
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let mut rule = LocalizationRule::default();
+        rule.configure(&FleetLintConfig::from_str("[rules.localization]\nlocales = [\"de\", \"fr\"]\n").unwrap());
+
+        let messages: Vec<String> = rule
+            .check(&config, Path::new("test.yml"), source)
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("missing translations: resolution_fr"));
+        assert!(messages[1].contains("undeclared locale 'es'"));
+    }
+
+    #[test]
+    fn test_windows_profiles_rule() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        std::fs::write(
+            root.join("lib/update.xml"),
+            "<Replace>\n  <Item>\n    <Target><LocURI>./Device/Vendor/MSFT/Policy/Config/Update/ActiveHoursStart</LocURI></Target>\n    <Meta><Format xmlns=\"syncml:metinf\">int</Format></Meta>\n    <Data>eight</Data>\n  </Item>\n</Replace>\n",
+        )
+        .unwrap();
+
+        let source = "name: Workstations\ncontrols:\n  windows_settings:\n    custom_settings:\n      - path: ./lib/update.xml\n";
+        let file = root.join("team.yml");
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+
+        let errors = WindowsProfilesRule.check(&config, &file, source);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.starts_with("./lib/update.xml:5: <Data> 'eight' does not match Format 'int'"));
+        assert_eq!(errors[0].file, file);
+        assert_eq!(errors[0].line, Some(5));
+        assert_eq!(errors[0].column, Some(15));
+    }
+
+    #[test]
+    fn test_fleet_variables_rule() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        std::fs::write(
+            root.join("lib/wifi.xml"),
+            "<Replace><Item><Data>$FLEET_VAR_NDES_SCEP_CHALLENGE</Data></Item></Replace>\n",
+        )
+        .unwrap();
+
+        let source = "controls:\n  windows_settings:\n    custom_settings:\n      - path: ./lib/wifi.xml\n  scripts:\n    - path: ./lib/missing.sh\nagent_options:\n  note: $FLEET_VAR_HOST_UIID\n";
+        let file = root.join("team.yml");
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+
+        let errors = FleetVariablesRule.check(&config, &file, source);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].message, "Unknown Fleet variable $FLEET_VAR_HOST_UIID");
+        assert_eq!(errors[0].line, Some(8));
+        assert_eq!(errors[0].suggestion.as_deref(), Some("$FLEET_VAR_HOST_UUID"));
+        assert!(errors[1].message.starts_with("./lib/wifi.xml:1: $FLEET_VAR_NDES_SCEP_CHALLENGE is not supported in Windows profiles"));
+        assert_eq!(errors[1].line, Some(4));
+    }
+
+    #[test]
+    fn test_override_workspace_rules() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("teams")).unwrap();
+        std::fs::write(
+            root.join("default.yml"),
+            "org_settings:\n  features:\n    enable_host_users: true\ncontrols:\n  enable_disk_encryption: true\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/laptops.yml"),
+            "name: Laptops\nteam_settings:\n  features:\n    enable_host_users: true\ncontrols:\n  enable_disk_encryption: false\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/no-team.yml"),
+            "name: No team\ncontrols:\n  enable_disk_encryption: true\n",
+        )
+        .unwrap();
+        let repo = crate::linter::repo::load_repo(root).unwrap();
+
+        let contradictions = ContradictoryOverrideRule.check(&repo);
+        let messages: Vec<&str> = contradictions.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "controls.enable_disk_encryption is set in both default.yml and no-team.yml",
+                "Team 'Laptops' disables controls.enable_disk_encryption, which is enabled globally",
+            ]
+        );
+        assert_eq!(contradictions[1].line, Some(6));
+
+        let redundant = RedundantOverrideRule.check(&repo);
+        assert_eq!(redundant.len(), 1);
+        assert_eq!(redundant[0].message, "Team 'Laptops' sets features.enable_host_users to the global value");
+        assert_eq!(redundant[0].file, root.join("teams/laptops.yml"));
+        assert_eq!(redundant[0].line, Some(4));
+    }
+
+    #[test]
+    fn test_cross_team_policies_rule() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("teams")).unwrap();
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        std::fs::write(
+            root.join("lib/shared.yml"),
+            "- name: Shared\n  query: SELECT 1 FROM shared;\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/a.yml"),
+            r#"name: A
+policies:
+  - path: ../lib/shared.yml
+  - name: Firewall enabled
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+  - name: Gatekeeper
+    query: SELECT 1 FROM gatekeeper WHERE assessments_enabled = 1;
+    description: Checked before SIP
+  - name: SIP
+    query: SELECT 1 FROM sip_config WHERE config_flag = 'sip' AND enabled = 1;
+    critical: true
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/b.yml"),
+            r#"name: B
+policies:
+  - path: ../lib/shared.yml
+  - name: macOS firewall
+    query: select 1 from alf where global_state >= 1
+  - name: Gatekeeper
+    query: SELECT 1 FROM gatekeeper;
+  - name: SIP
+    query: SELECT 1 FROM sip_config WHERE config_flag = 'sip' AND enabled = 1;
+"#,
+        )
+        .unwrap();
+        let repo = crate::linter::repo::load_repo(root).unwrap();
+
+        let errors = CrossTeamPoliciesRule.check(&repo);
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Policies 'Firewall enabled' (A) and 'macOS firewall' (B) have identical queries but different names",
+                "Policies 'Gatekeeper' (A) and 'Gatekeeper' (B) share a name but have different queries",
+                "Policies 'SIP' (A) and 'SIP' (B) are identical but differ in criticality",
+            ]
+        );
+        assert_eq!(errors[0].file, root.join("teams/b.yml"));
+        assert_eq!(errors[0].line, Some(4));
+        assert_eq!(errors[0].related[0].file, root.join("teams/a.yml"));
+        assert_eq!(errors[0].related[0].line, Some(4));
+        // The conflicting item itself, not an earlier mention of its name
+        assert_eq!((errors[2].related[0].line, errors[2].related[0].column), (Some(9), Some(11)));
+        assert_eq!(errors[2].line, Some(8));
+    }
+
+    #[test]
+    fn test_duplicate_names_relate_to_first_definition() {
+        let source = r#"policies:
+  - name: Firewall
+    query: SELECT 1 FROM alf;
+  - name: "Firewall"
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+"#;
+        let config: FleetConfig = serde_yaml::from_str(source).unwrap();
+        let errors = DuplicateNamesRule.check(&config, Path::new("default.yml"), source);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Duplicate policy name: 'Firewall'");
+        assert_eq!((errors[0].line, errors[0].column), (Some(4), Some(12)));
+        assert_eq!(errors[0].related[0].line, Some(2));
+        assert_eq!(errors[0].related[0].message, "'Firewall' first defined here");
+    }
+
+    #[test]
+    fn test_type_validation_locates_suggestions() {
+        let source = "policies:\n  - name: A\n    platform: macos\n    query: SELECT 1;\n    critical: yes\n  - name: B\n    platform: macos\n    query: SELECT 1;\n";
+        let config = FleetConfig {
+            policies: Some(
+                ["A", "B"]
+                    .iter()
+                    .map(|name| {
+                        PolicyOrPath::Policy(Policy {
+                            name: Some(name.to_string()),
+                            platform: Some("macos".to_string()),
+                            ..Default::default()
+                        })
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        let errors = TypeValidationRule.check(&config, Path::new("test.yml"), source);
+        let located: Vec<_> = errors
+            .iter()
+            .map(|e| (e.line, e.column, e.context.as_deref(), e.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            located,
+            vec![
+                (Some(3), Some(15), Some("macos"), Some("darwin")),
+                (Some(7), Some(15), Some("macos"), Some("darwin")),
+                (Some(5), Some(15), Some("yes"), Some("true")),
+            ]
+        );
+
+        let source = "features:\n  enable_host_users: Off # legacy\n  enable_software_inventory: true\n  label: on\n";
+        let errors = TypeValidationRule.check(&FleetConfig::default(), Path::new("test.yml"), source);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "'enable_host_users' must be true or false, not 'Off'");
+        assert_eq!(errors[0].suggestion.as_deref(), Some("false"));
+    }
+
+    #[test]
+    fn test_webhook_settings_rule() {
+        let source = r#"org_settings:
+  webhook_settings:
+    host_status_webhook:
+      enable_host_status_webhook: true
+      destination_url: https://hooks.example.com/status
+    failing_policies_webhook:
+      enable_failing_policies_webhook: true
+      destination_url: http://10.0.0.5/hook
+    activities_webhook:
+      enable_activities_webhook: false
+      destination_url: $ACTIVITIES_WEBHOOK_URL
+"#;
+        let file = Path::new("default.yml");
+        let errors = WebhookSettingsRule::default().check(&FleetConfig::default(), file, source);
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "host_status_webhook is enabled but missing: host_percentage, days_count",
+                "Webhook URL 'http://10.0.0.5/hook' does not use HTTPS",
+            ]
+        );
+        assert_eq!(errors[0].line, Some(3));
+        assert_eq!(errors[1].line, Some(8));
+
+        let private = source.replace("http://10.0.0.5", "https://localhost");
+        let errors = WebhookSettingsRule::default().check(&FleetConfig::default(), file, &private);
+        assert!(errors[1].message.contains("local or private"));
+
+        let allowed = WebhookSettingsRule {
+            options: WebhookOptions {
+                allow_private: true,
+                ..Default::default()
+            },
+        };
+        assert_eq!(allowed.check(&FleetConfig::default(), file, &private).len(), 1);
+    }
+
+    #[test]
+    fn test_is_private_host() {
+        assert!(is_private_host("127.0.0.1"));
+        assert!(is_private_host("192.168.1.10"));
+        assert!(is_private_host("fd00::1"));
+        assert!(is_private_host("printer.local"));
+        assert!(!is_private_host("8.8.8.8"));
+        assert!(!is_private_host("hooks.example.com"));
+    }
+
+    #[test]
+    fn test_calendar_events_rule() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("teams")).unwrap();
+        std::fs::write(
+            root.join("default.yml"),
+            "org_settings:\n  integrations:\n    google_calendar:\n      - domain: example.com\n        api_key_json: $GOOGLE_CALENDAR_API_KEY\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/laptops.yml"),
+            r#"name: Laptops
+team_settings:
+  integrations:
+    google_calendar:
+      enable_calendar_events: true
+      webhook_url: https://hooks.example.com/calendar
+policies:
+  - name: Disk encryption
+    query: SELECT 1 FROM disk_encryption WHERE encrypted = 1;
+    calendar_events_enabled: true
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/servers.yml"),
+            r#"name: Servers
+policies:
+  - name: Firewall
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+    calendar_events_enabled: true
+  - name: Screen lock
+    query: SELECT 1 FROM screenlock WHERE enabled = 1;
+    calendar_events_enabled: true
+"#,
+        )
+        .unwrap();
+
+        let repo = crate::linter::repo::load_repo(root).unwrap();
+        let errors = CalendarEventsRule.check(&repo);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].message,
+            "Policy 'Firewall' has calendar_events_enabled, but team 'Servers' does not enable calendar events"
+        );
+        assert_eq!(errors[0].line, Some(5));
+        assert_eq!((errors[1].line, errors[1].column), (Some(8), Some(5)));
+    }
+
+    #[test]
+    fn test_enroll_secrets_rule() {
+        let source = r#"team_settings:
+  secrets:
+    - secret: $LAPTOPS_ENROLL_SECRET
+    - secret: hunter2
+    - secret: $LAPTOPS_ENROLL_SECRET
+"#;
+        let errors = EnrollSecretsRule::default().check(&FleetConfig::default(), Path::new("teams/laptops.yml"), source);
+        let found: Vec<(&str, Option<usize>)> = errors.iter().map(|e| (e.message.as_str(), e.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Enroll secret is committed as a literal value", Some(4)),
+                ("Enroll secret is 7 characters; at least 32 are recommended", Some(4)),
+                ("Duplicate enroll secret", Some(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shared_enroll_secrets_rule() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("teams")).unwrap();
+        std::fs::write(
+            root.join("teams/laptops.yml"),
+            "name: Laptops\nteam_settings:\n  secrets:\n    - secret: $ENROLL_SECRET\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/servers.yml"),
+            "name: Servers\nteam_settings:\n  secrets:\n    - secret: $SERVERS_SECRET\n    - secret: $ENROLL_SECRET\n",
+        )
+        .unwrap();
+
+        let repo = crate::linter::repo::load_repo(root).unwrap();
+        let errors = SharedEnrollSecretsRule.check(&repo);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "Team 'Servers' uses the same variable $ENROLL_SECRET as team 'Laptops'"
+        );
+        assert_eq!(errors[0].line, Some(5));
+    }
+
+    #[test]
+    fn test_schedule_budget_rule() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("teams")).unwrap();
+        std::fs::write(
+            root.join("default.yml"),
+            "queries:\n  - name: Uptime\n    query: SELECT * FROM uptime;\n    interval: 60\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/servers.yml"),
+            "name: Servers\nqueries:\n  - name: Ports\n    query: SELECT * FROM listening_ports JOIN processes USING (pid);\n    interval: 10\n",
+        )
+        .unwrap();
+
+        let repo = crate::linter::repo::load_repo(root).unwrap();
+        let mut rule = ScheduleBudgetRule::default();
+        let errors = rule.check(&repo);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "Team 'Servers' schedules about 780 table reads per host per hour (budget 600)"
+        );
+        assert_eq!(errors[0].line, Some(2));
+        assert!(errors[0].help.as_deref().unwrap().starts_with("Heaviest: 'Ports' (720/h, every 10s), 'Uptime' (60/h, every 60s)."));
+
+        let mut config = FleetLintConfig::default();
+        config.thresholds.max_schedule_load = 1000.0;
+        rule.configure(&config);
+        assert!(rule.check(&repo).is_empty());
+    }
+
+    #[test]
+    fn test_select_parts() {
+        let (columns, rest) = select_parts("SELECT name,  pid FROM processes WHERE on_disk = 0;").unwrap();
+        assert_eq!(columns.into_iter().collect::<Vec<_>>(), vec!["name", "pid"]);
+        assert_eq!(rest, "from processes where on_disk = 0");
+
+        let (columns, _) = select_parts("select count(*), coalesce(a, b) as c from t").unwrap();
+        assert_eq!(columns.len(), 2);
+        assert!(select_parts("SELECT DISTINCT name FROM users").is_none());
+        assert!(select_parts("WITH x AS (SELECT 1) SELECT * FROM x").is_none());
+    }
+
+    #[test]
+    fn test_redundant_queries_rule() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("teams")).unwrap();
+        std::fs::write(
+            root.join("default.yml"),
+            "# Processes: every process, hourly\nqueries:\n  - name: Processes\n    query: SELECT * FROM processes;\n    interval: 3600\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("teams/servers.yml"),
+            "name: Servers\nqueries:\n\
+             \x20 - name: Process names\n    query: select pid, name from processes\n    interval: 600\n\
+             \x20 - name: Names and pids\n    query: SELECT name, pid FROM  processes;\n    interval: 60\n    platform: linux\n\
+             \x20 - name: Same names\n    query: SELECT name, pid FROM processes\n    interval: 60\n    platform: linux\n\
+             \x20 - name: Ad hoc\n    query: SELECT name FROM processes\n",
+        )
+        .unwrap();
+
+        let repo = crate::linter::repo::load_repo(root).unwrap();
+        let errors = RedundantQueriesRule.check(&repo);
+
+        // Both sides point at the queries' own names, not earlier mentions
+        let subset = errors
+            .iter()
+            .find(|e| e.message == "Query 'Process names' collects a subset of what query 'Processes' collects")
+            .unwrap();
+        assert_eq!((subset.line, subset.column), (Some(3), Some(11)));
+        assert_eq!((subset.related[0].line, subset.related[0].column), (Some(3), Some(11)));
+
+        let mut messages: Vec<String> = errors.into_iter().map(|e| e.message).collect();
+        messages.sort();
+        assert_eq!(
+            messages,
+            vec![
+                "Query 'Names and pids' collects a subset of what query 'Process names' collects",
+                "Query 'Names and pids' collects a subset of what query 'Processes' collects",
+                "Query 'Process names' collects a subset of what query 'Processes' collects",
+                "Query 'Same names' collects a subset of what query 'Process names' collects",
+                "Query 'Same names' collects a subset of what query 'Processes' collects",
+                "Query 'Same names' is identical to query 'Names and pids'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_host_settings_rule() {
+        let source = r#"org_settings:
+  host_expiry_settings:
+    host_expiry_enabled: true
+  features:
+    enable_host_users: true
+    enable_sofware_inventory: true
+    detail_query_overrides:
+      users: null
+team_settings:
+  host_expiry_settings:
+    host_expiry_enabled: true
+    host_expiry_window: -5
+"#;
+        let file = Path::new("default.yml");
+        let found = |rule: &HostSettingsRule| -> Vec<(String, Option<usize>)> {
+            rule.check(&FleetConfig::default(), file, source)
+                .into_iter()
+                .map(|e| (e.message, e.line))
+                .collect()
+        };
+
+        // Generated schemas: detail_query_overrides is new in 4.24.0
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let versions_dir = temp_dir.path().join("schemas");
+        for (version, flags) in [
+            ("4.7.0", vec!["enable_host_users", "enable_software_inventory"]),
+            ("4.24.0", vec!["enable_host_users", "enable_software_inventory", "detail_query_overrides"]),
+        ] {
+            let dir = versions_dir.join(version).join("strict");
+            std::fs::create_dir_all(&dir).unwrap();
+            let properties: serde_json::Map<String, serde_json::Value> = flags
+                .into_iter()
+                .map(|flag| (flag.to_string(), serde_json::json!({ "type": "boolean", "description": flag })))
+                .collect();
+            let schema = serde_json::json!({
+                "properties": { "org_settings": { "properties": { "features": { "properties": properties } } } }
+            });
+            std::fs::write(dir.join(crate::linter::validate::SchemaKind::Default.file_name()), schema.to_string()).unwrap();
+        }
+        let features = crate::linter::effective::versioned_features(&versions_dir);
+        assert_eq!(
+            features.iter().map(|f| (f.name.as_str(), f.since.as_deref())).collect::<Vec<_>>(),
+            vec![
+                ("enable_host_users", None),
+                ("enable_software_inventory", None),
+                ("detail_query_overrides", Some("4.24.0")),
+            ]
+        );
+
+        // Without generated schemas, flags are not checked
+        assert_eq!(
+            found(&HostSettingsRule::default()),
+            vec![
+                ("host_expiry_enabled is true but host_expiry_window is not set".to_string(), Some(2)),
+                ("host_expiry_window must be a positive number of days, got -5".to_string(), Some(12)),
+            ]
+        );
+
+        let latest = HostSettingsRule {
+            fleet_version: None,
+            features: features.clone(),
+        };
+        assert_eq!(
+            found(&latest),
+            vec![
+                ("host_expiry_enabled is true but host_expiry_window is not set".to_string(), Some(2)),
+                ("Unknown feature flag 'enable_sofware_inventory'".to_string(), Some(6)),
+                ("host_expiry_window must be a positive number of days, got -5".to_string(), Some(12)),
+            ]
+        );
+        let typo = latest.check(&FleetConfig::default(), file, source);
+        assert_eq!(typo[1].suggestion.as_deref(), Some("enable_software_inventory"));
+
+        let pinned = HostSettingsRule {
+            fleet_version: Some("4.20".to_string()),
+            features,
+        };
+        let pinned = found(&pinned);
+        assert!(pinned.contains(&(
+            "Feature flag 'detail_query_overrides' requires Fleet 4.24.0 (targeting 4.20)".to_string(),
+            Some(7)
+        )));
+        assert!(!pinned.iter().any(|(message, _)| message.contains("'enable_host_users' requires")));
+    }
+
+    #[test]
+    fn test_deprecated_fields_rule() {
+        let source = "url: https://example.com/app.pkg\nself_service: true\n";
+        let file = Path::new("lib/software/app.yml");
+
+        let latest = DeprecatedFieldsRule::default();
+        let errors = latest.check(&FleetConfig::default(), file, source);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "self_service is deprecated as of Fleet 4.74.0");
+        assert_eq!(errors[0].line, Some(2));
+
+        // Not deprecated yet in the targeted release
+        let mut pinned = DeprecatedFieldsRule::default();
+        pinned.configure(&FleetLintConfig {
+            fleet_version: Some("4.73.0".to_string()),
+            ..Default::default()
+        });
+        assert!(pinned.check(&FleetConfig::default(), file, source).is_empty());
+
+        // Only package files are affected
+        assert!(latest.check(&FleetConfig::default(), Path::new("teams/a.yml"), source).is_empty());
+    }
+
+    #[test]
+    fn test_deprecated_fields_from_changelog() {
+        let changelog = "## Fleet 4.75.0 (Oct 20, 2025)\n\
+            - Removed support for `host_status_webhook` in GitOps files.\n\
+            - Deprecated `self_service` in GitOps package YAML.\n\
+            ## Fleet 4.74.0 (Sep 25, 2025)\n";
+        let changes = crate::sources::changelog::parse(changelog);
+        let rule = DeprecatedFieldsRule {
+            fleet_version: None,
+            migrations: super::super::migrate::loader::builtin_migrations()
+                .into_iter()
+                .chain(crate::sources::changelog::to_migrations(&changes))
+                .collect(),
+        };
+
+        let source = "name: Workstations\nhost_status_webhook:\n  enable_host_status_webhook: true\n";
+        let errors = rule.check(&FleetConfig::default(), Path::new("teams/a.yml"), source);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "host_status_webhook is deprecated as of Fleet 4.75.0");
+        assert_eq!(errors[0].line, Some(2));
+        assert!(errors[0].help.as_deref().unwrap().starts_with("Removed support for `host_status_webhook`"));
+
+        // Reviewed migrations win over the changelog for the same key
+        let package = "url: https://example.com/app.pkg\nself_service: true\n";
+        let errors = rule.check(&FleetConfig::default(), Path::new("lib/software/app.yml"), package);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "self_service is deprecated as of Fleet 4.74.0");
+    }
+}
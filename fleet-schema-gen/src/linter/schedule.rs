@@ -0,0 +1,176 @@
+//! Scheduled query load per team.
+//!
+//! Every scheduled query makes osquery read its tables once per interval on
+//! each host. The load of a query is estimated as the tables it touches
+//! times runs per hour; a team's hosts run the team's queries plus the
+//! global ones from `default.yml`. Tables differ wildly in cost, so this is
+//! a budget for catching schedule growth, not a CPU prediction.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use super::effective::global_team;
+use super::osquery::query_tables;
+use super::repo::{GitopsRepo, Team};
+
+/// A query that runs on a schedule (`interval` > 0).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScheduledQuery {
+    pub name: String,
+    /// File defining the query.
+    pub file: PathBuf,
+    /// Seconds between runs.
+    pub interval: i64,
+    pub tables: Vec<String>,
+    /// Estimated table reads per host per hour.
+    pub load: f64,
+}
+
+/// Schedule of the hosts in a team.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TeamLoad {
+    pub team: String,
+    pub file: PathBuf,
+    /// The team's scheduled queries, then the global ones it inherits.
+    pub queries: Vec<ScheduledQuery>,
+    /// Number of `queries` inherited from `default.yml`.
+    pub inherited: usize,
+    /// Estimated table reads per host per hour.
+    pub load: f64,
+}
+
+impl TeamLoad {
+    /// Queries by load, heaviest first.
+    pub fn heaviest(&self) -> Vec<&ScheduledQuery> {
+        let mut queries: Vec<&ScheduledQuery> = self.queries.iter().collect();
+        queries.sort_by(|a, b| b.load.total_cmp(&a.load).then(a.name.cmp(&b.name)));
+        queries
+    }
+}
+
+/// Table reads per hour of a query; queries without a recognizable table
+/// still cost one read per run.
+pub fn query_load(interval: i64, tables: usize) -> f64 {
+    if interval <= 0 {
+        return 0.0;
+    }
+    tables.max(1) as f64 * 3600.0 / interval as f64
+}
+
+/// The scheduled queries a team file defines.
+pub fn scheduled_queries(team: &Team) -> Vec<ScheduledQuery> {
+    team.queries
+        .iter()
+        .filter_map(|sourced| {
+            let query = &sourced.item;
+            let interval = query.interval.filter(|i| *i > 0)?;
+            let tables = query_tables(query.query.as_deref().unwrap_or(""));
+            Some(ScheduledQuery {
+                name: query.name.clone().unwrap_or_else(|| "unnamed".to_string()),
+                file: sourced.file.clone(),
+                interval,
+                load: query_load(interval, tables.len()),
+                tables,
+            })
+        })
+        .collect()
+}
+
+/// Load of every team, global first.
+pub fn team_loads(repo: &GitopsRepo) -> Vec<TeamLoad> {
+    let global = global_team(repo);
+    let global_queries = global.map(scheduled_queries).unwrap_or_default();
+
+    let mut loads: Vec<TeamLoad> = repo
+        .teams
+        .iter()
+        .map(|team| {
+            let is_global = global.is_some_and(|g| g.file == team.file);
+            let mut queries = if is_global { global_queries.clone() } else { scheduled_queries(team) };
+            let inherited = if is_global { 0 } else { global_queries.len() };
+            if !is_global {
+                queries.extend(global_queries.iter().cloned());
+            }
+            TeamLoad {
+                team: team.name.clone(),
+                file: team.file.clone(),
+                load: queries.iter().map(|q| q.load).sum(),
+                queries,
+                inherited,
+            }
+        })
+        .collect();
+    loads.sort_by_key(|load| global.is_none_or(|g| g.file != load.file));
+    loads
+}
+
+/// Per-team schedule summary for `stats`.
+pub fn render(loads: &[TeamLoad], budget: f64) -> String {
+    let mut out = String::new();
+    for load in loads {
+        let status = if load.load > budget { "over budget" } else { "ok" };
+        out.push_str(&format!(
+            "{}\n  scheduled queries: {} ({} inherited)\n  load: {:.0} table reads/host/hour (budget {:.0}, {})\n",
+            load.team,
+            load.queries.len(),
+            load.inherited,
+            load.load,
+            budget,
+            status
+        ));
+        for query in load.heaviest().into_iter().take(3) {
+            out.push_str(&format!(
+                "    {:>6.0}/h  {} (every {}s, {})\n",
+                query.load,
+                query.name,
+                query.interval,
+                if query.tables.is_empty() { "no tables".to_string() } else { query.tables.join(", ") }
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_team_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("default.yml"),
+            "queries:\n  - name: Uptime\n    query: SELECT * FROM uptime;\n    interval: 3600\n  \
+             - name: Ad hoc\n    query: SELECT * FROM users;\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("teams")).unwrap();
+        std::fs::write(
+            dir.path().join("teams/workstations.yml"),
+            "name: Workstations\nqueries:\n  - name: Logins\n    query: SELECT * FROM users JOIN logged_in_users USING (uid);\n    interval: 60\n",
+        )
+        .unwrap();
+
+        let repo = crate::linter::repo::load_repo(dir.path()).unwrap();
+        let loads = team_loads(&repo);
+
+        assert_eq!(loads[0].team, "Global");
+        assert_eq!(loads[0].queries.len(), 1);
+        assert_eq!(loads[0].load, 1.0);
+
+        let workstations = &loads[1];
+        assert_eq!(workstations.inherited, 1);
+        assert_eq!(workstations.load, 121.0);
+        assert_eq!(workstations.heaviest()[0].tables, vec!["users", "logged_in_users"]);
+
+        let report = render(&loads, 100.0);
+        assert!(report.contains("Workstations\n  scheduled queries: 2 (1 inherited)\n  load: 121 table reads/host/hour (budget 100, over budget)\n"));
+        assert!(report.contains("     120/h  Logins (every 60s, users, logged_in_users)\n"));
+    }
+
+    #[test]
+    fn test_query_load() {
+        assert_eq!(query_load(60, 2), 120.0);
+        assert_eq!(query_load(3600, 0), 1.0);
+        assert_eq!(query_load(0, 3), 0.0);
+    }
+}
@@ -0,0 +1,215 @@
+//! Software bill-of-deployments: what software each team deploys.
+//!
+//! Resolves every team's `packages`, `app_store_apps`, and
+//! `fleet_maintained_apps` and reports name, source, version (when the
+//! package definition declares one), self-service flag, and target labels.
+
+use super::repo::{GitopsRepo, Software, SoftwareSource};
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+
+/// Software inventory for every team.
+#[derive(Debug, Serialize)]
+pub struct SoftwareReport {
+    pub teams: Vec<TeamSoftware>,
+}
+
+/// Software deployed by one team.
+#[derive(Debug, Serialize)]
+pub struct TeamSoftware {
+    pub team: String,
+    pub file: String,
+    pub software: Vec<SoftwareItem>,
+}
+
+/// A single deployed software item.
+#[derive(Debug, Serialize)]
+pub struct SoftwareItem {
+    pub name: String,
+    pub source: SoftwareSource,
+    pub version: Option<String>,
+    pub self_service: bool,
+    pub labels_include_any: Vec<String>,
+    pub labels_exclude_any: Vec<String>,
+    /// Package definition file for `path:` entries.
+    pub file: Option<String>,
+    pub url: Option<String>,
+}
+
+/// Build the software report for a repository.
+pub fn software_report(repo: &GitopsRepo) -> SoftwareReport {
+    let relative = |file: &Path| {
+        file.strip_prefix(&repo.root)
+            .unwrap_or(file)
+            .display()
+            .to_string()
+    };
+
+    let teams = repo
+        .teams
+        .iter()
+        .map(|team| TeamSoftware {
+            team: team.name.clone(),
+            file: relative(&team.file),
+            software: team
+                .software
+                .iter()
+                .map(|software| SoftwareItem {
+                    name: software.name.clone(),
+                    source: software.source,
+                    version: software_version(software),
+                    self_service: software.self_service().unwrap_or(false),
+                    labels_include_any: software_labels(software, "labels_include_any"),
+                    labels_exclude_any: software_labels(software, "labels_exclude_any"),
+                    file: software.file.as_deref().map(relative),
+                    url: software.package.as_ref().and_then(|p| p.url.clone()),
+                })
+                .collect(),
+        })
+        .collect();
+
+    SoftwareReport { teams }
+}
+
+/// Version declared on the team entry or in the package definition.
+fn software_version(software: &Software) -> Option<String> {
+    let from_value = |value: &serde_yaml::Value| match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+
+    software
+        .entry
+        .get("version")
+        .and_then(from_value)
+        .or_else(|| {
+            software
+                .package
+                .as_ref()
+                .and_then(|p| p.other.as_ref())
+                .and_then(|other| other.get("version"))
+                .and_then(from_value)
+        })
+}
+
+/// Target labels from the team entry, falling back to the package definition.
+fn software_labels(software: &Software, key: &str) -> Vec<String> {
+    let labels = |value: &serde_yaml::Value| -> Vec<String> {
+        value
+            .as_sequence()
+            .into_iter()
+            .flatten()
+            .filter_map(|l| l.as_str().map(String::from))
+            .collect()
+    };
+
+    software
+        .entry
+        .get(key)
+        .map(labels)
+        .or_else(|| {
+            software
+                .package
+                .as_ref()
+                .and_then(|p| p.other.as_ref())
+                .and_then(|other| other.get(key))
+                .map(labels)
+        })
+        .unwrap_or_default()
+}
+
+impl SoftwareReport {
+    /// Write the report as CSV (one row per team/software pair).
+    ///
+    /// Label lists are joined with `;`.
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record([
+            "team",
+            "name",
+            "source",
+            "version",
+            "self_service",
+            "labels_include_any",
+            "labels_exclude_any",
+            "file",
+            "url",
+        ])?;
+
+        for team in &self.teams {
+            for item in &team.software {
+                csv_writer.write_record([
+                    team.team.as_str(),
+                    item.name.as_str(),
+                    item.source.key(),
+                    item.version.as_deref().unwrap_or(""),
+                    if item.self_service { "true" } else { "false" },
+                    item.labels_include_any.join(";").as_str(),
+                    item.labels_exclude_any.join(";").as_str(),
+                    item.file.as_deref().unwrap_or(""),
+                    item.url.as_deref().unwrap_or(""),
+                ])?;
+            }
+        }
+
+        csv_writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::repo::load_repo;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_software_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+
+        fs::write(
+            root.join("lib/zoom.yml"),
+            "url: https://example.com/zoom.pkg\nversion: 6.1.0\nlabels_exclude_any:\n  - Contractors\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("teams/workstations.yml"),
+            r#"name: Workstations
+software:
+  packages:
+    - path: ../lib/zoom.yml
+      self_service: true
+      labels_include_any:
+        - Engineering
+        - Design
+  fleet_maintained_apps:
+    - slug: slack/darwin
+"#,
+        )
+        .unwrap();
+
+        let report = software_report(&load_repo(root).unwrap());
+        let items = &report.teams[0].software;
+        assert_eq!(items.len(), 2);
+
+        let zoom = &items[0];
+        assert_eq!(zoom.version.as_deref(), Some("6.1.0"));
+        assert!(zoom.self_service);
+        assert_eq!(zoom.labels_include_any, vec!["Engineering", "Design"]);
+        assert_eq!(zoom.labels_exclude_any, vec!["Contractors"]);
+        assert_eq!(zoom.file.as_deref(), Some("lib/zoom.yml"));
+
+        let mut out = Vec::new();
+        report.write_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.contains("Workstations,zoom,packages,6.1.0,true,Engineering;Design,Contractors,"));
+        assert!(csv.contains("Workstations,slack/darwin,fleet_maintained_apps,,false,,,,"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Sorting policies, queries and labels by name.
+//!
+//! Items are reordered as text, so comments and formatting survive: the
+//! comment lines directly above an item move with it, and blank lines
+//! between items stay where they were. Items without a name sort by their
+//! `path:`, and ties keep their order, so sorting twice changes nothing.
+
+/// Top-level lists that are sorted in team and default files.
+pub const SORTABLE_SECTIONS: &[&str] = &["policies", "queries", "labels"];
+
+/// A list whose items are out of order, and its sorted text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedSection {
+    /// Top-level key of the list, or `None` for a lib file's bare list
+    pub section: Option<String>,
+    /// First line of the items (0-indexed)
+    pub start_line: usize,
+    /// Line after the last item (0-indexed, exclusive)
+    pub end_line: usize,
+    /// The items' lines in sorted order, newline-terminated
+    pub text: String,
+}
+
+/// An item of a list, by line (0-indexed).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    /// Top-level key of the list, or `None` for a lib file's bare list
+    pub section: Option<String>,
+    /// First line, including the comments right above the item
+    pub start_line: usize,
+    /// The `- ` line
+    pub dash_line: usize,
+    /// Line after the item's last line (exclusive)
+    pub end_line: usize,
+    /// Indentation of the dash
+    pub indent: usize,
+    /// Its `name:`, if any
+    pub name: Option<String>,
+    /// Its `path:`, if any
+    pub path: Option<String>,
+}
+
+impl ListItem {
+    /// Sort key: the name, else the path
+    fn sort_key(&self) -> String {
+        self.name.as_deref().or(self.path.as_deref()).unwrap_or("").to_lowercase()
+    }
+}
+
+/// A parsed list
+struct List {
+    items: Vec<ListItem>,
+}
+
+pub(crate) fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+pub(crate) fn is_trivia(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+/// Unquoted value of `key:` in an item line, with or without the dash
+fn field_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let field = line.trim_start().trim_start_matches('-').trim_start();
+    let value = field.strip_prefix(key)?.strip_prefix(':')?;
+    let value = value.split(" #").next().unwrap_or("").trim();
+    Some(value.trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// Value of an item-level `key:` (on the dash line, or a field of the item
+/// itself rather than a nested one)
+fn item_field(lines: &[&str], item: &ListItem, key: &str) -> Option<String> {
+    (item.dash_line..item.end_line).find_map(|idx| {
+        let line = lines[idx];
+        let at_item_level = idx == item.dash_line || (!is_trivia(line) && indent(line) == item.indent + 2);
+        at_item_level.then(|| field_value(line, key)).flatten().map(str::to_string)
+    })
+}
+
+/// Parse the list whose lines start at `start` (after its key, or at the
+/// top of a lib file).
+fn parse_list(lines: &[&str], start: usize, section: Option<&str>) -> Option<List> {
+    let first = (start..lines.len()).find(|&idx| !is_trivia(lines[idx]))?;
+    let item_indent = indent(lines[first]);
+    if !lines[first].trim_start().starts_with('-') {
+        return None;
+    }
+
+    let mut items: Vec<ListItem> = Vec::new();
+    // Trivia lines since the last content line
+    let mut pending = 0;
+    for (idx, line) in lines.iter().enumerate().skip(start) {
+        if is_trivia(line) {
+            pending += 1;
+            continue;
+        }
+        if indent(line) == item_indent && line.trim_start().starts_with('-') {
+            // Comments right above the item (no blank line between) are its own
+            let attached = lines[idx - pending..idx].iter().rev().take_while(|l| !l.trim().is_empty()).count();
+            items.push(ListItem {
+                section: section.map(str::to_string),
+                start_line: idx - attached,
+                dash_line: idx,
+                end_line: idx + 1,
+                indent: item_indent,
+                name: None,
+                path: None,
+            });
+        } else if indent(line) > item_indent && !items.is_empty() {
+            items.last_mut().unwrap().end_line = idx + 1;
+        } else {
+            break;
+        }
+        pending = 0;
+    }
+
+    for item in &mut items {
+        let (name, path) = (item_field(lines, item, "name"), item_field(lines, item, "path"));
+        item.name = name;
+        item.path = path;
+    }
+    Some(List { items })
+}
+
+/// Lists of policies, queries and labels in `source`, or a lib file's list
+fn lists(lines: &[&str]) -> Vec<List> {
+    let Some(first) = lines.iter().position(|line| !is_trivia(line)) else {
+        return Vec::new();
+    };
+    // A lib file: the document is the list
+    if lines[first].starts_with('-') {
+        return parse_list(lines, first, None).into_iter().collect();
+    }
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let key = line.strip_suffix(':').or_else(|| line.split_once(": #").map(|(key, _)| key))?;
+            SORTABLE_SECTIONS.contains(&key).then(|| parse_list(lines, idx + 1, Some(key))).flatten()
+        })
+        .collect()
+}
+
+/// Items of the policy, query and label lists in `source`.
+pub fn list_items(source: &str) -> Vec<ListItem> {
+    let lines: Vec<&str> = source.lines().collect();
+    lists(&lines).into_iter().flat_map(|list| list.items).collect()
+}
+
+/// The list sorted, if it is out of order. Blank lines and comments between
+/// items that aren't attached to one stay where they are.
+fn sort_list(lines: &[&str], list: &List) -> Option<SortedSection> {
+    let items = &list.items;
+    if items.windows(2).all(|pair| pair[0].sort_key() <= pair[1].sort_key()) {
+        return None;
+    }
+    let mut sorted: Vec<&ListItem> = items.iter().collect();
+    sorted.sort_by_key(|item| item.sort_key());
+
+    let mut text = String::new();
+    for (idx, item) in sorted.iter().enumerate() {
+        if idx > 0 {
+            for line in &lines[items[idx - 1].end_line..items[idx].start_line] {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        for line in &lines[item.start_line..item.end_line] {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    Some(SortedSection {
+        section: items[0].section.clone(),
+        start_line: items[0].start_line,
+        end_line: items[items.len() - 1].end_line,
+        text,
+    })
+}
+
+/// Lists in `source` that are out of order.
+pub fn unsorted_sections(source: &str) -> Vec<SortedSection> {
+    let lines: Vec<&str> = source.lines().collect();
+    lists(&lines).iter().filter_map(|list| sort_list(&lines, list)).collect()
+}
+
+/// `source` with every list sorted by name.
+pub fn sort_items(source: &str) -> String {
+    let sections = unsorted_sections(source);
+    if sections.is_empty() {
+        return source.to_string();
+    }
+    let lines: Vec<&str> = source.lines().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut next = 0;
+    for section in &sections {
+        for line in &lines[next..section.start_line] {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(&section.text);
+        next = section.end_line;
+    }
+    for line in &lines[next..] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    if !source.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_items_keeps_comments_with_their_item() {
+        let source = "\
+name: Team
+policies:
+  # Disk checks
+  - name: Zeta
+    query: SELECT 1;
+    # still Zeta
+    platform: darwin
+
+  # Firewall
+  - name: alpha
+    query: SELECT 1;
+  - path: ../lib/beta.yml
+# end of policies
+queries:
+  - name: B
+  - name: A
+";
+        let sorted = sort_items(source);
+        assert_eq!(
+            sorted,
+            "\
+name: Team
+policies:
+  - path: ../lib/beta.yml
+
+  # Firewall
+  - name: alpha
+    query: SELECT 1;
+  # Disk checks
+  - name: Zeta
+    query: SELECT 1;
+    # still Zeta
+    platform: darwin
+# end of policies
+queries:
+  - name: A
+  - name: B
+"
+        );
+        assert_eq!(sort_items(&sorted), sorted);
+        assert_eq!(unsorted_sections(source).len(), 2);
+        assert_eq!(unsorted_sections(source)[1].section.as_deref(), Some("queries"));
+    }
+
+    #[test]
+    fn test_sort_lib_file() {
+        let source = "# Lib\n- name: B\n  query: SELECT 1;\n- name: A\n  query: SELECT 2;\n";
+        assert_eq!(sort_items(source), "# Lib\n- name: A\n  query: SELECT 2;\n- name: B\n  query: SELECT 1;\n");
+        let sections = unsorted_sections(source);
+        assert_eq!((sections[0].section.clone(), sections[0].start_line, sections[0].end_line), (None, 1, 5));
+    }
+
+    #[test]
+    fn test_sorted_and_nested_names_are_left_alone() {
+        let source = "policies:\n  - name: A\n    labels:\n      - name: Z\n  - name: B\nlabels: []\n";
+        assert!(unsorted_sections(source).is_empty());
+        assert_eq!(sort_items(source), source);
+    }
+}
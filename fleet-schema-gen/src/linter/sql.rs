@@ -0,0 +1,247 @@
+//! SQLite tokenizer for osquery SQL.
+//!
+//! osquery runs queries through SQLite, so this follows SQLite's own
+//! tokenizer (`tokenize.c`): `--` and `/* */` comments, `'strings'` with
+//! doubled quotes, identifiers quoted with `"`, `` ` `` or `[]`, blobs
+//! (`x'00'`), parameters and multi-character operators. Tokens cover the
+//! input exactly, so concatenating their text gives the query back, and
+//! two queries with the same tokens (ignoring whitespace and the case of
+//! keywords) mean the same thing, which is how [`super::sql_format`] checks
+//! its output.
+
+/// Kind of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Whitespace,
+    /// `-- ...` (without the newline) or `/* ... */`
+    Comment,
+    /// A word SQLite reserves (`SELECT`, `FROM`, `LIKE`, ...)
+    Keyword,
+    Identifier,
+    /// `"name"`, `` `name` `` or `[name]`
+    QuotedIdentifier,
+    /// `'text'` or a blob `x'00'`
+    String,
+    Number,
+    /// `=`, `>=`, `||`, `->>`, ...
+    Operator,
+    /// `(`, `)`, `,`, `;` or `.`
+    Punctuation,
+    /// `?`, `?1`, `:name`, `@name` or `$name`
+    Parameter,
+    /// Anything SQLite would reject, including unterminated strings
+    Unknown,
+}
+
+/// A token and its byte offset in the query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub offset: usize,
+}
+
+impl Token<'_> {
+    /// Whether this is the keyword `word` (case-insensitive).
+    pub fn is_keyword(&self, word: &str) -> bool {
+        self.kind == TokenKind::Keyword && self.text.eq_ignore_ascii_case(word)
+    }
+
+    /// Whether this is punctuation or an operator spelled `text`.
+    pub fn is(&self, text: &str) -> bool {
+        matches!(self.kind, TokenKind::Punctuation | TokenKind::Operator) && self.text == text
+    }
+}
+
+/// SQLite's keywords (<https://sqlite.org/lang_keywords.html>).
+pub const KEYWORDS: &[&str] = &[
+    "ABORT", "ACTION", "ADD", "AFTER", "ALL", "ALTER", "ALWAYS", "ANALYZE", "AND", "AS", "ASC", "ATTACH",
+    "AUTOINCREMENT", "BEFORE", "BEGIN", "BETWEEN", "BY", "CASCADE", "CASE", "CAST", "CHECK", "COLLATE",
+    "COLUMN", "COMMIT", "CONFLICT", "CONSTRAINT", "CREATE", "CROSS", "CURRENT", "CURRENT_DATE",
+    "CURRENT_TIME", "CURRENT_TIMESTAMP", "DATABASE", "DEFAULT", "DEFERRABLE", "DEFERRED", "DELETE", "DESC",
+    "DETACH", "DISTINCT", "DO", "DROP", "EACH", "ELSE", "END", "ESCAPE", "EXCEPT", "EXCLUDE", "EXCLUSIVE",
+    "EXISTS", "EXPLAIN", "FAIL", "FILTER", "FIRST", "FOLLOWING", "FOR", "FOREIGN", "FROM", "FULL",
+    "GENERATED", "GLOB", "GROUP", "GROUPS", "HAVING", "IF", "IGNORE", "IMMEDIATE", "IN", "INDEX", "INDEXED",
+    "INITIALLY", "INNER", "INSERT", "INSTEAD", "INTERSECT", "INTO", "IS", "ISNULL", "JOIN", "KEY", "LAST",
+    "LEFT", "LIKE", "LIMIT", "MATCH", "MATERIALIZED", "NATURAL", "NO", "NOT", "NOTHING", "NOTNULL", "NULL",
+    "NULLS", "OF", "OFFSET", "ON", "OR", "ORDER", "OTHERS", "OUTER", "OVER", "PARTITION", "PLAN", "PRAGMA",
+    "PRECEDING", "PRIMARY", "QUERY", "RAISE", "RANGE", "RECURSIVE", "REFERENCES", "REGEXP", "REINDEX",
+    "RELEASE", "RENAME", "REPLACE", "RESTRICT", "RETURNING", "RIGHT", "ROLLBACK", "ROW", "ROWS", "SAVEPOINT",
+    "SELECT", "SET", "TABLE", "TEMP", "TEMPORARY", "THEN", "TIES", "TO", "TRANSACTION", "TRIGGER",
+    "UNBOUNDED", "UNION", "UNIQUE", "UPDATE", "USING", "VACUUM", "VALUES", "VIEW", "VIRTUAL", "WHEN",
+    "WHERE", "WINDOW", "WITH", "WITHOUT",
+];
+
+/// Whether `word` is an SQLite keyword (case-insensitive).
+pub fn is_keyword(word: &str) -> bool {
+    KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+fn is_word_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$' || !c.is_ascii()
+}
+
+/// Byte length of the token starting at the beginning of `rest`.
+fn scan(rest: &str) -> (TokenKind, usize) {
+    let mut chars = rest.chars();
+    let Some(first) = chars.next() else {
+        return (TokenKind::Unknown, 0);
+    };
+    let second = chars.next();
+    // Up to (and including) the first `close` after the opening character,
+    // with doubled `close` characters escaping it
+    let quoted = |close: char, doubled: bool| -> Option<usize> {
+        let mut iter = rest.char_indices().skip(1).peekable();
+        while let Some((idx, c)) = iter.next() {
+            if c == close {
+                if doubled && iter.peek().is_some_and(|&(_, next)| next == close) {
+                    iter.next();
+                    continue;
+                }
+                return Some(idx + c.len_utf8());
+            }
+        }
+        None
+    };
+    let word_end = |from: usize| {
+        rest[from..].find(|c: char| !is_word_char(c)).map_or(rest.len(), |idx| from + idx)
+    };
+
+    match first {
+        c if c.is_whitespace() => {
+            (TokenKind::Whitespace, rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len()))
+        }
+        '-' if second == Some('-') => (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len())),
+        '/' if second == Some('*') => {
+            (TokenKind::Comment, rest[2..].find("*/").map_or(rest.len(), |idx| idx + 4))
+        }
+        '\'' => match quoted('\'', true) {
+            Some(len) => (TokenKind::String, len),
+            None => (TokenKind::Unknown, rest.len()),
+        },
+        '"' | '`' => match quoted(first, true) {
+            Some(len) => (TokenKind::QuotedIdentifier, len),
+            None => (TokenKind::Unknown, rest.len()),
+        },
+        '[' => match quoted(']', false) {
+            Some(len) => (TokenKind::QuotedIdentifier, len),
+            None => (TokenKind::Unknown, rest.len()),
+        },
+        'x' | 'X' if second == Some('\'') => match quoted_blob(rest) {
+            Some(len) => (TokenKind::String, len),
+            None => (TokenKind::Unknown, rest.len()),
+        },
+        c if c.is_ascii_digit() || (c == '.' && second.is_some_and(|s| s.is_ascii_digit())) => {
+            (TokenKind::Number, number_len(rest))
+        }
+        c if is_word_start(c) => {
+            let len = word_end(0);
+            let kind = if is_keyword(&rest[..len]) { TokenKind::Keyword } else { TokenKind::Identifier };
+            (kind, len)
+        }
+        '?' => (TokenKind::Parameter, 1 + rest[1..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - 1)),
+        ':' | '@' | '$' if second.is_some_and(is_word_char) => (TokenKind::Parameter, word_end(1)),
+        '(' | ')' | ',' | ';' | '.' => (TokenKind::Punctuation, 1),
+        _ => {
+            const OPERATORS: &[&str] = &[
+                "->>", "||", "<=", ">=", "==", "!=", "<>", "<<", ">>", "->", "<", ">", "=", "+", "-", "*", "/",
+                "%", "&", "|", "~",
+            ];
+            match OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+                Some(op) => (TokenKind::Operator, op.len()),
+                None => (TokenKind::Unknown, first.len_utf8()),
+            }
+        }
+    }
+}
+
+/// Length of a blob literal `x'..'` at the start of `rest`.
+fn quoted_blob(rest: &str) -> Option<usize> {
+    rest[2..].find('\'').map(|idx| idx + 3)
+}
+
+/// Length of a numeric literal: decimal with fraction and exponent, or hex.
+fn number_len(rest: &str) -> usize {
+    let bytes = rest.as_bytes();
+    if bytes.len() > 2 && bytes[0] == b'0' && (bytes[1] | 0x20) == b'x' && bytes[2].is_ascii_hexdigit() {
+        return 2 + bytes[2..].iter().take_while(|b| b.is_ascii_hexdigit() || **b == b'_').count();
+    }
+    let digits = |from: usize| from + bytes[from..].iter().take_while(|b| b.is_ascii_digit() || **b == b'_').count();
+    let mut end = digits(0);
+    if bytes.get(end) == Some(&b'.') {
+        end = digits(end + 1);
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        if bytes.get(end + 1 + sign).is_some_and(|b| b.is_ascii_digit()) {
+            end = digits(end + 1 + sign);
+        }
+    }
+    end
+}
+
+/// Split `sql` into tokens covering all of it.
+pub fn tokenize(sql: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while offset < sql.len() {
+        let (kind, len) = scan(&sql[offset..]);
+        let len = len.max(1);
+        tokens.push(Token { kind, text: &sql[offset..offset + len], offset });
+        offset += len;
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(sql: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(sql).into_iter().filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Comment)).map(|t| (t.kind, t.text)).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        use TokenKind::*;
+        let sql = "SELECT `name`, [path], \"a\"\"b\" FROM t -- note\nWHERE x>=1.5e3 AND y = 'it''s' || x'0F' /* c */ AND z->>'$.a' = ?1;";
+        assert_eq!(tokenize(sql).iter().map(|t| t.text).collect::<std::string::String>(), sql);
+        assert_eq!(
+            kinds(sql),
+            vec![
+                (Keyword, "SELECT"),
+                (QuotedIdentifier, "`name`"),
+                (Punctuation, ","),
+                (QuotedIdentifier, "[path]"),
+                (Punctuation, ","),
+                (QuotedIdentifier, "\"a\"\"b\""),
+                (Keyword, "FROM"),
+                (Identifier, "t"),
+                (Keyword, "WHERE"),
+                (Identifier, "x"),
+                (Operator, ">="),
+                (Number, "1.5e3"),
+                (Keyword, "AND"),
+                (Identifier, "y"),
+                (Operator, "="),
+                (String, "'it''s'"),
+                (Operator, "||"),
+                (String, "x'0F'"),
+                (Keyword, "AND"),
+                (Identifier, "z"),
+                (Operator, "->>"),
+                (String, "'$.a'"),
+                (Operator, "="),
+                (Parameter, "?1"),
+                (Punctuation, ";"),
+            ]
+        );
+        assert_eq!(kinds("SELECT 'open"), vec![(Keyword, "SELECT"), (Unknown, "'open")]);
+        // A Postgres cast reads as a stray colon and a parameter
+        assert_eq!(kinds("a::int"), vec![(Identifier, "a"), (Unknown, ":"), (Parameter, ":int")]);
+    }
+}
@@ -0,0 +1,266 @@
+//! MySQL and Postgres syntax in osquery SQL.
+//!
+//! osquery runs queries through SQLite, which rejects `ILIKE`, `::` casts,
+//! `LIMIT ALL`, `OFFSET` without `LIMIT`, `FETCH FIRST` and `TOP`, and only
+//! accepts backtick-quoted names for MySQL compatibility. Each construct is
+//! found on the [`super::sql`] tokens, so text in strings and comments is
+//! left alone, and comes with the SQLite spelling where there is one.
+
+use super::sql::{tokenize, Token, TokenKind};
+
+/// A construct SQLite doesn't support (or only tolerates).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialectIssue {
+    /// Byte range of the construct in the query
+    pub start: usize,
+    pub end: usize,
+    /// Whether SQLite rejects the query (rather than just accepting it)
+    pub rejected: bool,
+    pub message: String,
+    pub help: String,
+    /// SQLite equivalent of the range, when there is one
+    pub suggestion: Option<String>,
+}
+
+/// Type names for `CAST(... AS ...)`, by SQLite affinity.
+fn sqlite_type(name: &str) -> String {
+    match name.to_ascii_lowercase().as_str() {
+        "int" | "int2" | "int4" | "int8" | "integer" | "bigint" | "smallint" | "tinyint" | "bool" | "boolean" => {
+            "INTEGER".to_string()
+        }
+        "text" | "varchar" | "char" | "string" | "uuid" | "json" | "jsonb" => "TEXT".to_string(),
+        "float" | "float4" | "float8" | "real" | "double" => "REAL".to_string(),
+        "numeric" | "decimal" => "NUMERIC".to_string(),
+        _ => name.to_ascii_uppercase(),
+    }
+}
+
+/// Index of the first token of the operand ending at `end`: a literal, a
+/// (qualified) name, a parenthesized expression or a function call.
+fn operand_start(tokens: &[&Token], end: usize) -> Option<usize> {
+    let mut idx = end;
+    if tokens[idx].is(")") {
+        let mut depth = 0;
+        loop {
+            if tokens[idx].is(")") {
+                depth += 1;
+            } else if tokens[idx].is("(") {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            idx = idx.checked_sub(1)?;
+        }
+        if idx > 0 && tokens[idx - 1].kind == TokenKind::Identifier {
+            idx -= 1;
+        }
+        return Some(idx);
+    }
+    match tokens[idx].kind {
+        TokenKind::Number | TokenKind::String | TokenKind::Parameter => Some(idx),
+        TokenKind::Identifier | TokenKind::QuotedIdentifier => {
+            while idx >= 2
+                && tokens[idx - 1].is(".")
+                && matches!(tokens[idx - 2].kind, TokenKind::Identifier | TokenKind::QuotedIdentifier)
+            {
+                idx -= 2;
+            }
+            Some(idx)
+        }
+        _ => None,
+    }
+}
+
+/// Whether a `LIMIT` precedes `idx` in the same statement and subquery.
+fn limit_before(tokens: &[&Token], idx: usize) -> bool {
+    let mut depth = 0;
+    for token in tokens[..idx].iter().rev() {
+        if token.is(")") {
+            depth += 1;
+        } else if token.is("(") {
+            if depth == 0 {
+                return false;
+            }
+            depth -= 1;
+        } else if token.is(";") && depth == 0 {
+            return false;
+        } else if depth == 0 && token.is_keyword("LIMIT") {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether a token is a single-token `LIMIT`/`OFFSET` value.
+fn is_count(token: &Token) -> bool {
+    matches!(token.kind, TokenKind::Number | TokenKind::Parameter)
+}
+
+fn is_word(token: &Token, word: &str) -> bool {
+    matches!(token.kind, TokenKind::Keyword | TokenKind::Identifier) && token.text.eq_ignore_ascii_case(word)
+}
+
+/// Non-SQLite constructs in `sql`, in order.
+pub fn dialect_issues(sql: &str) -> Vec<DialectIssue> {
+    let all = tokenize(sql);
+    let tokens: Vec<&Token> =
+        all.iter().filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment)).collect();
+    let end_of = |token: &Token| token.offset + token.text.len();
+    let text = |from: usize, to: usize| &sql[tokens[from].offset..end_of(tokens[to])];
+    let mut issues = Vec::new();
+
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let token = tokens[idx];
+        let next = tokens.get(idx + 1).copied();
+
+        if token.kind == TokenKind::QuotedIdentifier && token.text.starts_with('`') {
+            let name = token.text[1..token.text.len() - 1].replace("``", "`");
+            issues.push(DialectIssue {
+                start: token.offset,
+                end: end_of(token),
+                rejected: false,
+                message: format!("uses MySQL backtick quoting for {}", token.text),
+                help: "SQLite quotes identifiers with double quotes".to_string(),
+                suggestion: Some(format!("\"{}\"", name.replace('"', "\"\""))),
+            });
+        } else if token.kind == TokenKind::Identifier && token.text.eq_ignore_ascii_case("ILIKE") {
+            issues.push(DialectIssue {
+                start: token.offset,
+                end: end_of(token),
+                rejected: true,
+                message: "uses ILIKE, which SQLite doesn't support".to_string(),
+                help: "LIKE is already case-insensitive for ASCII text in SQLite".to_string(),
+                suggestion: Some("LIKE".to_string()),
+            });
+        } else if token.kind == TokenKind::Unknown
+            && token.text == ":"
+            && next.is_some_and(|next| next.kind == TokenKind::Parameter && next.text.starts_with(':'))
+        {
+            // `expr::type` tokenizes as `expr`, `:` and the parameter `:type`
+            let cast = next.unwrap();
+            let type_name = sqlite_type(&cast.text[1..]);
+            let operand = idx.checked_sub(1).and_then(|end| operand_start(&tokens, end));
+            let (start, suggestion) = match operand {
+                Some(from) => (
+                    tokens[from].offset,
+                    Some(format!("CAST({} AS {})", text(from, idx - 1), type_name)),
+                ),
+                None => (token.offset, None),
+            };
+            issues.push(DialectIssue {
+                start,
+                end: end_of(cast),
+                rejected: true,
+                message: format!("uses a Postgres cast '::{}'", &cast.text[1..]),
+                help: format!("Use CAST(value AS {}) in SQLite", type_name),
+                suggestion,
+            });
+            idx += 2;
+            continue;
+        } else if token.is_keyword("LIMIT") && next.is_some_and(|next| is_word(next, "ALL")) {
+            issues.push(DialectIssue {
+                start: token.offset,
+                end: end_of(next.unwrap()),
+                rejected: true,
+                message: "uses LIMIT ALL, which SQLite doesn't support".to_string(),
+                help: "A negative limit means no limit in SQLite".to_string(),
+                suggestion: Some("LIMIT -1".to_string()),
+            });
+        } else if token.is_keyword("OFFSET") && !limit_before(&tokens, idx) {
+            // Postgres allows `OFFSET n [ROWS]` alone and before `LIMIT m`
+            let mut last = idx;
+            let mut suggestion = None;
+            if let Some(count) = next.filter(|next| is_count(next)) {
+                last = idx + 1;
+                if tokens.get(last + 1).is_some_and(|t| is_word(t, "ROWS") || is_word(t, "ROW")) {
+                    last += 1;
+                }
+                suggestion = Some(format!("LIMIT -1 OFFSET {}", count.text));
+                if let (Some(limit), Some(max)) = (tokens.get(last + 1), tokens.get(last + 2)) {
+                    if limit.is_keyword("LIMIT") && is_count(max) {
+                        last += 2;
+                        suggestion = Some(format!("LIMIT {} OFFSET {}", max.text, count.text));
+                    }
+                }
+            }
+            issues.push(DialectIssue {
+                start: token.offset,
+                end: end_of(tokens[last]),
+                rejected: true,
+                message: "uses OFFSET without a preceding LIMIT".to_string(),
+                help: "SQLite only accepts OFFSET after LIMIT: LIMIT <count> OFFSET <skip>, or LIMIT -1 for no limit".to_string(),
+                suggestion,
+            });
+            idx = last + 1;
+            continue;
+        } else if is_word(token, "FETCH") && next.is_some_and(|next| is_word(next, "FIRST") || is_word(next, "NEXT")) {
+            // FETCH FIRST n ROWS ONLY
+            let count = tokens.get(idx + 2).filter(|count| is_count(count));
+            let rows = tokens.get(idx + 3).filter(|t| is_word(t, "ROWS") || is_word(t, "ROW"));
+            let only = tokens.get(idx + 4).filter(|t| is_word(t, "ONLY"));
+            let (end, suggestion) = match (count, rows, only) {
+                (Some(count), Some(_), Some(only)) => (end_of(only), Some(format!("LIMIT {}", count.text))),
+                _ => (end_of(next.unwrap()), None),
+            };
+            issues.push(DialectIssue {
+                start: token.offset,
+                end,
+                rejected: true,
+                message: format!("uses {} {}, which SQLite doesn't support", token.text, next.unwrap().text),
+                help: "Use LIMIT <count> in SQLite".to_string(),
+                suggestion,
+            });
+        } else if token.is_keyword("SELECT") && next.is_some_and(|next| is_word(next, "TOP")) {
+            let top = next.unwrap();
+            let count = tokens.get(idx + 2).filter(|count| is_count(count));
+            issues.push(DialectIssue {
+                start: top.offset,
+                end: count.map_or(end_of(top), |count| end_of(count)),
+                rejected: true,
+                message: format!("uses SQL Server's SELECT {}", text(idx + 1, idx + 1 + usize::from(count.is_some()))),
+                help: format!("Remove it and end the query with LIMIT {}", count.map_or("<count>", |count| count.text)),
+                suggestion: None,
+            });
+        }
+        idx += 1;
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestions(sql: &str) -> Vec<(&str, Option<String>)> {
+        dialect_issues(sql).into_iter().map(|issue| (&sql[issue.start..issue.end], issue.suggestion)).collect()
+    }
+
+    #[test]
+    fn test_dialect_issues() {
+        assert!(dialect_issues("SELECT \"name\" FROM apps WHERE name LIKE '%ilike%' -- `x`::int\nLIMIT 5 OFFSET 10;").is_empty());
+        assert_eq!(
+            suggestions("SELECT `bundle name` FROM apps WHERE name ILIKE '%chrome%' AND lower(version)::int > 1 AND a.b::varchar = '1';"),
+            vec![
+                ("`bundle name`", Some("\"bundle name\"".to_string())),
+                ("ILIKE", Some("LIKE".to_string())),
+                ("lower(version)::int", Some("CAST(lower(version) AS INTEGER)".to_string())),
+                ("a.b::varchar", Some("CAST(a.b AS TEXT)".to_string())),
+            ]
+        );
+        assert_eq!(
+            suggestions("SELECT * FROM t OFFSET 10 LIMIT 5; SELECT * FROM t LIMIT ALL; SELECT * FROM t OFFSET 3 ROWS"),
+            vec![
+                ("OFFSET 10 LIMIT 5", Some("LIMIT 5 OFFSET 10".to_string())),
+                ("LIMIT ALL", Some("LIMIT -1".to_string())),
+                ("OFFSET 3 ROWS", Some("LIMIT -1 OFFSET 3".to_string())),
+            ]
+        );
+        assert_eq!(
+            suggestions("SELECT TOP 5 name FROM (SELECT name FROM t LIMIT 9) FETCH FIRST 5 ROWS ONLY"),
+            vec![("TOP 5", None), ("FETCH FIRST 5 ROWS ONLY", Some("LIMIT 5".to_string()))]
+        );
+        assert!(!dialect_issues("SELECT `a` FROM t")[0].rejected);
+    }
+}
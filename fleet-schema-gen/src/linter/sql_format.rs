@@ -0,0 +1,435 @@
+//! Pretty-printing the SQL of `query:` fields.
+//!
+//! Queries are laid out one clause per line (`SELECT`, `FROM`, joins,
+//! `WHERE`, ...), with `AND`/`OR` conditions and subqueries indented and
+//! SQL keywords in uppercase. Formatting only changes whitespace and the
+//! case of keywords: the result is tokenized again and must match the
+//! original token for token, or the query is left alone. Queries that
+//! don't tokenize cleanly are never touched.
+//!
+//! In YAML, formatted queries that span several lines are written as
+//! literal block scalars (`query: |-`) indented under their key.
+
+use super::sql::{tokenize, Token, TokenKind};
+use super::sort::indent;
+
+/// Keywords written in uppercase. Other keywords keep their case: they are
+/// more often function or column names (`replace(...)`, `key`).
+const UPPERCASE: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "AND", "OR", "NOT", "IN", "IS", "NULL", "LIKE", "GLOB", "REGEXP", "MATCH",
+    "BETWEEN", "ESCAPE", "JOIN", "LEFT", "RIGHT", "FULL", "INNER", "OUTER", "CROSS", "NATURAL", "ON", "USING",
+    "AS", "GROUP", "ORDER", "BY", "HAVING", "LIMIT", "OFFSET", "UNION", "ALL", "INTERSECT", "EXCEPT",
+    "DISTINCT", "CASE", "WHEN", "THEN", "ELSE", "END", "EXISTS", "WITH", "RECURSIVE", "ASC", "DESC", "CAST",
+    "COLLATE",
+];
+
+/// Words that start a join (`LEFT OUTER JOIN`, `CROSS JOIN`, ...).
+const JOIN_PREFIXES: &[&str] = &["LEFT", "RIGHT", "FULL", "INNER", "OUTER", "CROSS", "NATURAL"];
+
+/// A parenthesis being formatted, or the top level.
+struct Frame {
+    /// Holds a query (the top level or a subquery)
+    query: bool,
+    /// Indentation of the query's clauses
+    indent: usize,
+    /// Indentation of the line with the opening parenthesis
+    open_indent: usize,
+    /// The clause being written (`WHERE`, `JOIN`, ...)
+    clause: &'static str,
+    /// An `AND` belongs to a `BETWEEN`
+    between: bool,
+}
+
+/// Lines being written.
+struct Output {
+    lines: Vec<String>,
+    line: String,
+    indent: usize,
+}
+
+impl Output {
+    fn newline(&mut self, indent: usize) {
+        if !self.line.trim().is_empty() {
+            self.lines.push(self.line.trim_end().to_string());
+        }
+        self.line = " ".repeat(indent);
+        self.indent = indent;
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.line.trim().is_empty()
+    }
+}
+
+fn upper<'a>(token: &Token<'a>) -> Option<&'static str> {
+    (token.kind == TokenKind::Keyword)
+        .then(|| UPPERCASE.iter().find(|keyword| keyword.eq_ignore_ascii_case(token.text)).copied())
+        .flatten()
+}
+
+/// The clause `tokens[idx]` starts, if any.
+fn clause_start(tokens: &[Token], idx: usize) -> Option<&'static str> {
+    let token = &tokens[idx];
+    let next = tokens.get(idx + 1);
+    let prev = idx.checked_sub(1).map(|prev| &tokens[prev]);
+    match upper(token)? {
+        keyword @ ("SELECT" | "FROM" | "WHERE" | "HAVING" | "LIMIT" | "WITH" | "UNION" | "INTERSECT" | "EXCEPT") => {
+            Some(keyword)
+        }
+        keyword @ ("GROUP" | "ORDER") if next.is_some_and(|next| next.is_keyword("BY")) => Some(keyword),
+        "JOIN" if !prev.is_some_and(|prev| upper(prev).is_some_and(|p| JOIN_PREFIXES.contains(&p))) => Some("JOIN"),
+        prefix if JOIN_PREFIXES.contains(&prefix) => {
+            let leads_to_join = tokens[idx..]
+                .iter()
+                .take_while(|t| upper(t).is_some_and(|k| JOIN_PREFIXES.contains(&k) || k == "JOIN"))
+                .any(|t| t.is_keyword("JOIN"));
+            let first = !prev.is_some_and(|prev| upper(prev).is_some_and(|p| JOIN_PREFIXES.contains(&p)));
+            (leads_to_join && first).then_some("JOIN")
+        }
+        _ => None,
+    }
+}
+
+/// Whether a space goes between `prev` and `token`.
+fn spaced(prev: &Token, token: &Token, unary: bool) -> bool {
+    if unary || prev.is("(") || prev.is(".") {
+        return false;
+    }
+    if token.is(")") || token.is(",") || token.is(";") || token.is(".") {
+        return false;
+    }
+    if token.is("(") {
+        // Calls hug their arguments: count(*), CAST(x AS INT)
+        return match prev.kind {
+            TokenKind::Identifier | TokenKind::QuotedIdentifier => false,
+            TokenKind::Keyword => upper(prev).is_some_and(|keyword| keyword != "CAST"),
+            _ => true,
+        };
+    }
+    true
+}
+
+/// Whether `token` is a sign rather than an operator after `prev`.
+fn is_unary(prev: Option<&Token>, token: &Token) -> bool {
+    matches!(token.text, "-" | "+" | "~")
+        && token.kind == TokenKind::Operator
+        && prev.is_none_or(|prev| {
+            prev.kind == TokenKind::Operator
+                || prev.is("(")
+                || prev.is(",")
+                || (prev.kind == TokenKind::Keyword && !prev.is_keyword("END") && !prev.is_keyword("NULL"))
+        })
+}
+
+/// Whether two queries have the same tokens and comments, ignoring
+/// whitespace and the case of keywords.
+fn equivalent(a: &str, b: &str) -> bool {
+    let meaningful = |sql| -> Vec<Token> { tokenize(sql).into_iter().filter(|t| t.kind != TokenKind::Whitespace).collect() };
+    let (a, b) = (meaningful(a), meaningful(b));
+    a.len() == b.len()
+        && a.iter().zip(&b).all(|(x, y)| {
+            x.kind == y.kind
+                && match x.kind {
+                    TokenKind::Keyword => x.text.eq_ignore_ascii_case(y.text),
+                    TokenKind::Comment => x.text.trim_end() == y.text.trim_end(),
+                    _ => x.text == y.text,
+                }
+        })
+}
+
+/// `sql` pretty-printed, or `None` if it doesn't tokenize cleanly.
+pub fn format_sql(sql: &str) -> Option<String> {
+    let tokens: Vec<Token> = tokenize(sql).into_iter().filter(|t| t.kind != TokenKind::Whitespace).collect();
+    if tokens.is_empty() || tokens.iter().any(|t| t.kind == TokenKind::Unknown) {
+        return None;
+    }
+
+    let mut frames = vec![Frame { query: true, indent: 0, open_indent: 0, clause: "", between: false }];
+    let mut out = Output { lines: Vec::new(), line: String::new(), indent: 0 };
+    // Set after a line comment or a statement's `;`
+    let mut break_next = false;
+    let mut unary = false;
+
+    for (idx, token) in tokens.iter().enumerate() {
+        let prev = idx.checked_sub(1).map(|prev| &tokens[prev]);
+        let frame = frames.last_mut()?;
+
+        if frame.query {
+            if let Some(clause) = clause_start(&tokens, idx) {
+                frame.clause = clause;
+                frame.between = false;
+                out.newline(frame.indent);
+                break_next = false;
+            } else if token.is_keyword("AND") && frame.between {
+                frame.between = false;
+            } else if (token.is_keyword("AND") || token.is_keyword("OR"))
+                && matches!(frame.clause, "WHERE" | "HAVING" | "JOIN")
+            {
+                out.newline(frame.indent + 2);
+                break_next = false;
+            }
+        }
+        if token.is(")") {
+            let closed = frames.pop()?;
+            if frames.is_empty() {
+                return None;
+            }
+            if closed.query {
+                out.newline(closed.open_indent);
+                break_next = false;
+            }
+        }
+        if break_next {
+            let frame = frames.last()?;
+            out.newline(if token.is(";") { out.indent } else { frame.indent + 2 });
+            break_next = false;
+        }
+
+        if !out.at_line_start() && prev.is_some_and(|prev| spaced(prev, token, unary)) {
+            out.line.push(' ');
+        }
+        match upper(token) {
+            Some(keyword) if !prev.is_some_and(|prev| prev.is(".")) => out.line.push_str(keyword),
+            _ => out.line.push_str(token.text),
+        }
+        unary = is_unary(prev, token);
+
+        if token.is("(") {
+            let query = tokens.get(idx + 1).is_some_and(|next| next.is_keyword("SELECT") || next.is_keyword("WITH"));
+            let indent = out.indent;
+            frames.push(Frame { query, indent: indent + 2, open_indent: indent, clause: "", between: false });
+        } else if token.is_keyword("BETWEEN") {
+            frames.last_mut()?.between = true;
+        } else if token.kind == TokenKind::Comment && token.text.starts_with("--") {
+            break_next = true;
+        } else if token.is(";") && idx + 1 < tokens.len() {
+            let frame = frames.last_mut()?;
+            frame.clause = "";
+            out.newline(frame.indent);
+        }
+    }
+    if frames.len() != 1 {
+        return None;
+    }
+    out.newline(0);
+
+    let formatted = out.lines.join("\n");
+    equivalent(sql, &formatted).then_some(formatted)
+}
+
+/// A `query:` field to rewrite: lines `start_line..end_line` (0-indexed)
+/// become `text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryEdit {
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Replacement lines, newline-terminated
+    pub text: String,
+}
+
+/// Column where a line's key starts (after `- ` for list items)
+fn key_column(line: &str) -> usize {
+    let trimmed = line.trim_start();
+    match trimmed.strip_prefix("- ") {
+        Some(rest) => line.len() - rest.trim_start().len(),
+        None => indent(line),
+    }
+}
+
+/// The `query` value of a field's lines, parsed as YAML.
+fn parse_field(lines: &[String], key_col: usize) -> Option<String> {
+    let snippet: String = lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let line = if idx == 0 { &line[key_col..] } else { line.get(key_col..).unwrap_or("") };
+            format!("{}\n", line)
+        })
+        .collect();
+    let value: serde_yaml::Value = serde_yaml::from_str(&snippet).ok()?;
+    value.get("query")?.as_str().map(str::to_string)
+}
+
+/// The rewritten field, checked to parse back to `formatted`.
+fn rewrite_field(prefix: &str, key_col: usize, formatted: &str, block_header: Option<&str>) -> Option<String> {
+    let check = |text: &str| {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        parse_field(&lines, key_col).is_some_and(|value| value.trim_end() == formatted)
+    };
+    let body_indent = " ".repeat(key_col + 2);
+
+    if formatted.contains('\n') || block_header.is_some() {
+        let mut text = format!("{}query: {}\n", prefix, block_header.unwrap_or("|-"));
+        for line in formatted.lines() {
+            text.push_str(&body_indent);
+            text.push_str(line);
+            text.push('\n');
+        }
+        return check(&text).then_some(text);
+    }
+    [format!("{}query: {}\n", prefix, formatted), format!("{}query: '{}'\n", prefix, formatted.replace('\'', "''"))]
+        .into_iter()
+        .find(|text| check(text))
+}
+
+/// Edits formatting every `query:` field in `source` whose SQL would
+/// change. Fields with comments on the key line or multi-line plain and
+/// quoted values are skipped.
+pub fn query_edits(source: &str) -> Vec<QueryEdit> {
+    let lines: Vec<&str> = source.lines().collect();
+    let eol = if source.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut edits = Vec::new();
+    for (start, line) in lines.iter().enumerate() {
+        let key_col = key_column(line);
+        let Some(rest) = line.get(key_col..).and_then(|field| field.strip_prefix("query:")) else {
+            continue;
+        };
+        let rest = rest.trim();
+        if rest.starts_with('#') || rest.contains(" #") {
+            continue;
+        }
+
+        let mut end = start + 1;
+        while end < lines.len() && (lines[end].trim().is_empty() || indent(lines[end]) > key_col) {
+            end += 1;
+        }
+        while end > start + 1 && lines[end - 1].trim().is_empty() {
+            end -= 1;
+        }
+        let block = rest.starts_with(['|', '>']);
+        if !block && end > start + 1 {
+            continue;
+        }
+        // Literal, keeping the chomping indicator
+        let header = block.then(|| if rest.contains('-') { "|-" } else { "|" });
+
+        let field: Vec<String> = lines[start..end].iter().map(|line| line.to_string()).collect();
+        let Some(value) = parse_field(&field, key_col) else { continue };
+        let Some(formatted) = format_sql(value.trim()).filter(|formatted| formatted != value.trim()) else {
+            continue;
+        };
+        let Some(text) = rewrite_field(&line[..key_col], key_col, &formatted, header) else { continue };
+        let text = text.replace('\n', eol);
+
+        let original: String = field.iter().map(|line| format!("{}\n", line)).collect();
+        if text != original {
+            edits.push(QueryEdit { start_line: start, end_line: end, text });
+        }
+    }
+    edits
+}
+
+/// `source` with every `query:` field formatted.
+pub fn format_queries(source: &str) -> String {
+    let edits = query_edits(source);
+    if edits.is_empty() {
+        return source.to_string();
+    }
+    // Lines with their endings, so CRLF files stay CRLF
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let mut out = String::with_capacity(source.len());
+    let mut next = 0;
+    for edit in &edits {
+        out.push_str(&lines[next..edit.start_line].concat());
+        out.push_str(&edit.text);
+        next = edit.end_line;
+    }
+    out.push_str(&lines[next..].concat());
+    if !source.ends_with('\n') {
+        let trimmed = out.trim_end_matches(['\r', '\n']).len();
+        out.truncate(trimmed);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_sql() {
+        assert_eq!(
+            format_sql("select name, count(*) as n from users u left outer join groups g on u.gid = g.gid and g.x = -1 where u.uid between 1 and 500 or u.shell like '%sh' group by name order by n desc limit 5;").unwrap(),
+            "\
+SELECT name, count(*) AS n
+FROM users u
+LEFT OUTER JOIN groups g ON u.gid = g.gid
+  AND g.x = -1
+WHERE u.uid BETWEEN 1 AND 500
+  OR u.shell LIKE '%sh'
+GROUP BY name
+ORDER BY n DESC
+LIMIT 5;"
+        );
+        assert_eq!(
+            format_sql("SELECT 1 FROM t WHERE x IN (select y from s where z = 1) -- only s\n AND t.key = replace(a, 'b', 'c')").unwrap(),
+            "\
+SELECT 1
+FROM t
+WHERE x IN (
+  SELECT y
+  FROM s
+  WHERE z = 1
+) -- only s
+  AND t.key = replace(a, 'b', 'c')"
+        );
+        assert_eq!(format_sql("SELECT CAST(x AS INT) FROM t").unwrap(), "SELECT CAST(x AS INT)\nFROM t");
+        // Not SQLite, or unbalanced: left alone
+        assert!(format_sql("SELECT 'open FROM t").is_none());
+        assert!(format_sql("SELECT (1 FROM t").is_none());
+    }
+
+    #[test]
+    fn test_format_queries() {
+        let source = "\
+policies:
+  - name: Firewall
+    query: select 1 from alf where global_state >= 1;
+    platform: darwin
+  - name: Disk
+    query: >-
+      SELECT 1 FROM disk_encryption
+      WHERE encrypted = 1;
+
+  - name: Done
+    query: SELECT 1;
+queries:
+  - name: Commented
+    query: select 1 # keep
+";
+        let formatted = format_queries(source);
+        assert_eq!(
+            formatted,
+            "\
+policies:
+  - name: Firewall
+    query: |-
+      SELECT 1
+      FROM alf
+      WHERE global_state >= 1;
+    platform: darwin
+  - name: Disk
+    query: |-
+      SELECT 1
+      FROM disk_encryption
+      WHERE encrypted = 1;
+
+  - name: Done
+    query: SELECT 1;
+queries:
+  - name: Commented
+    query: select 1 # keep
+"
+        );
+        assert_eq!(format_queries(&formatted), formatted);
+        let edits = query_edits(source);
+        assert_eq!(edits.iter().map(|e| (e.start_line, e.end_line)).collect::<Vec<_>>(), vec![(2, 3), (5, 8)]);
+
+        // A lib file, with a one-line query that no longer needs quotes
+        let lib = "- name: A\n  query: 'select ''a'' as x'\n";
+        assert_eq!(format_queries(lib), "- name: A\n  query: SELECT 'a' AS x\n");
+        // Quotes alone aren't worth an edit; line endings are kept
+        assert!(query_edits("- name: A\n  query: \"SELECT 1;\"\n").is_empty());
+        assert_eq!(format_queries("- name: A\r\n  query: select 1 from t\r\n"), "- name: A\r\n  query: |-\r\n    SELECT 1\r\n    FROM t\r\n");
+    }
+}
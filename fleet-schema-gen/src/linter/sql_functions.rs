@@ -0,0 +1,214 @@
+//! SQL functions available in osquery queries.
+//!
+//! osquery runs SQLite (with the JSON functions built in) and registers
+//! functions of its own: string splitting, regular expressions, encoding,
+//! hashing, math and `carve()`. Used for hover and signature help in query
+//! fields.
+
+use std::collections::HashMap;
+use once_cell::sync::Lazy;
+
+/// Where a function comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionKind {
+    Core,
+    Aggregate,
+    DateTime,
+    Json,
+    /// Registered by osquery, not part of SQLite
+    Osquery,
+}
+
+impl FunctionKind {
+    fn label(self) -> &'static str {
+        match self {
+            FunctionKind::Core => "SQLite function",
+            FunctionKind::Aggregate => "SQLite aggregate function",
+            FunctionKind::DateTime => "SQLite date and time function",
+            FunctionKind::Json => "SQLite JSON function",
+            FunctionKind::Osquery => "osquery function",
+        }
+    }
+}
+
+/// A function callable from a query.
+#[derive(Debug)]
+pub struct SqlFunction {
+    pub name: &'static str,
+    pub kind: FunctionKind,
+    /// Parameter labels; optional ones are `[bracketed]` and a trailing
+    /// `...` repeats the one before it
+    pub params: &'static [&'static str],
+    pub description: &'static str,
+    /// Platform caveats
+    pub note: Option<&'static str>,
+}
+
+const OSQUERY_PLATFORMS: &str = "darwin, linux, windows";
+const ALL_PLATFORMS: &str = "darwin, linux, windows, chrome";
+
+impl SqlFunction {
+    /// `split(string, tokens, index)`
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, self.params.join(", "))
+    }
+
+    /// Markdown documentation, in the style of the osquery table hover.
+    pub fn to_markdown(&self) -> String {
+        let platforms = match self.kind {
+            FunctionKind::Osquery => OSQUERY_PLATFORMS,
+            _ => ALL_PLATFORMS,
+        };
+        let mut doc = format!(
+            "**{}** ({})\n\n```sql\n{}\n```\n\n{}\n\n**Platforms:** {}",
+            self.name,
+            self.kind.label(),
+            self.signature(),
+            self.description,
+            platforms
+        );
+        if let Some(note) = self.note {
+            doc.push_str(&format!("\n\n**Note:** {}", note));
+        }
+        doc
+    }
+}
+
+macro_rules! functions {
+    ($($kind:ident $name:literal ($($param:literal),*) $description:literal $(note $note:literal)?;)*) => {
+        &[$(SqlFunction {
+            name: $name,
+            kind: FunctionKind::$kind,
+            params: &[$($param),*],
+            description: $description,
+            note: functions!(@note $($note)?),
+        }),*]
+    };
+    (@note $note:literal) => { Some($note) };
+    (@note) => { None };
+}
+
+/// Functions queries can call, by kind.
+pub const SQL_FUNCTIONS: &[SqlFunction] = functions! {
+    // SQLite core functions
+    Core "abs" ("X") "Absolute value of X.";
+    Core "char" ("X1", "...") "String of the characters with the given Unicode code points.";
+    Core "coalesce" ("X", "Y", "...") "First argument that isn't NULL.";
+    Core "format" ("format", "...") "Formats the arguments like C printf (alias of printf).";
+    Core "glob" ("pattern", "string") "Whether string matches the case-sensitive glob pattern (like `string GLOB pattern`).";
+    Core "hex" ("X") "Uppercase hexadecimal rendering of X.";
+    Core "ifnull" ("X", "Y") "X, or Y when X is NULL.";
+    Core "iif" ("condition", "then", "else") "then when condition is true, else otherwise.";
+    Core "instr" ("string", "substring") "1-based position of the first substring in string, or 0.";
+    Core "length" ("X") "Number of characters in a string (bytes for a blob).";
+    Core "like" ("pattern", "string", "[escape]") "Whether string matches the case-insensitive LIKE pattern.";
+    Core "lower" ("string") "string in lowercase (ASCII only).";
+    Core "ltrim" ("string", "[characters]") "string without leading spaces (or characters).";
+    Core "nullif" ("X", "Y") "NULL when X equals Y, X otherwise.";
+    Core "printf" ("format", "...") "Formats the arguments like C printf.";
+    Core "quote" ("X") "X as an SQL literal.";
+    Core "random" () "A pseudo-random 64-bit integer.";
+    Core "replace" ("string", "from", "to") "string with every from replaced by to.";
+    Core "round" ("X", "[digits]") "X rounded to digits decimal places (0 by default).";
+    Core "rtrim" ("string", "[characters]") "string without trailing spaces (or characters).";
+    Core "substr" ("string", "start", "[length]") "Substring from the 1-based start, of length characters or to the end.";
+    Core "substring" ("string", "start", "[length]") "Alias of substr.";
+    Core "trim" ("string", "[characters]") "string without leading and trailing spaces (or characters).";
+    Core "typeof" ("X") "Storage class of X: null, integer, real, text or blob.";
+    Core "unicode" ("string") "Code point of the first character of string.";
+    Core "upper" ("string") "string in uppercase (ASCII only).";
+
+    // SQLite aggregates
+    Aggregate "avg" ("X") "Average of the non-NULL values in the group.";
+    Aggregate "count" ("X") "Number of non-NULL values in the group; count(*) counts rows.";
+    Aggregate "group_concat" ("X", "[separator]") "Non-NULL values of the group joined with separator (a comma by default).";
+    Aggregate "max" ("X", "...") "Largest value in the group; with several arguments, the largest argument.";
+    Aggregate "min" ("X", "...") "Smallest value in the group; with several arguments, the smallest argument.";
+    Aggregate "sum" ("X") "Sum of the non-NULL values in the group (NULL when there are none).";
+    Aggregate "total" ("X") "Sum of the non-NULL values in the group as a float (0.0 when there are none).";
+
+    // SQLite date and time functions
+    DateTime "date" ("time", "[modifier]", "...") "Date as YYYY-MM-DD.";
+    DateTime "datetime" ("time", "[modifier]", "...") "Date and time as YYYY-MM-DD HH:MM:SS.";
+    DateTime "julianday" ("time", "[modifier]", "...") "Julian day number.";
+    DateTime "strftime" ("format", "time", "[modifier]", "...") "time formatted with format; strftime('%s', 'now') is the current Unix time.";
+    DateTime "time" ("time", "[modifier]", "...") "Time as HH:MM:SS.";
+    DateTime "unixepoch" ("time", "[modifier]", "...") "Unix timestamp." note "Needs an osquery built with SQLite 3.38 or later; strftime('%s', ...) works everywhere.";
+
+    // SQLite JSON functions
+    Json "json" ("json") "Minified copy of a JSON string.";
+    Json "json_array" ("value", "...") "JSON array of the arguments.";
+    Json "json_array_length" ("json", "[path]") "Number of elements of the array at path.";
+    Json "json_extract" ("json", "path", "...") "Value at path, e.g. json_extract(data, '$.version').";
+    Json "json_group_array" ("value") "Aggregate: JSON array of the values in the group.";
+    Json "json_group_object" ("name", "value") "Aggregate: JSON object of the name/value pairs in the group.";
+    Json "json_object" ("name", "value", "...") "JSON object of name/value pairs.";
+    Json "json_type" ("json", "[path]") "Type of the value at path.";
+    Json "json_valid" ("json") "Whether the string is valid JSON.";
+
+    // osquery string functions
+    Osquery "concat" ("X", "...") "The arguments concatenated as strings, skipping NULLs.";
+    Osquery "concat_ws" ("separator", "X", "...") "The arguments joined with separator, skipping NULLs.";
+    Osquery "split" ("string", "tokens", "index") "0-based index-th part of string split on any of the characters in tokens.";
+    Osquery "regex_split" ("string", "pattern", "index") "0-based index-th part of string split on the regular expression pattern.";
+    Osquery "regex_match" ("string", "pattern", "index") "index-th group of the first match of the regular expression pattern (0 is the whole match), or NULL." note "Patterns use the ECMAScript syntax of std::regex.";
+    Osquery "inet_aton" ("ip") "IPv4 address as an integer.";
+    Osquery "community_id_v1" ("src_ip", "dst_ip", "src_port", "dst_port", "protocol", "[seed]") "Community ID flow hash of a network connection.";
+    Osquery "to_base64" ("string") "string encoded as base64.";
+    Osquery "from_base64" ("string") "base64 string decoded.";
+    Osquery "conditional_to_base64" ("string") "string encoded as base64 if it contains non-printable characters.";
+
+    // osquery hashing functions
+    Osquery "md5" ("string") "MD5 hash of string, as hex.";
+    Osquery "sha1" ("string") "SHA-1 hash of string, as hex.";
+    Osquery "sha256" ("string") "SHA-256 hash of string, as hex.";
+
+    // osquery math functions
+    Osquery "sqrt" ("X") "Square root of X.";
+    Osquery "log" ("X") "Natural logarithm of X.";
+    Osquery "log10" ("X") "Base-10 logarithm of X.";
+    Osquery "ln" ("X") "Natural logarithm of X.";
+    Osquery "exp" ("X") "e raised to X.";
+    Osquery "power" ("X", "Y") "X raised to Y.";
+    Osquery "ceil" ("X") "Smallest integer not less than X.";
+    Osquery "floor" ("X") "Largest integer not greater than X.";
+    Osquery "pi" () "The constant pi.";
+    Osquery "degrees" ("radians") "Radians converted to degrees.";
+    Osquery "radians" ("degrees") "Degrees converted to radians.";
+    Osquery "sin" ("X") "Sine of X radians.";
+    Osquery "cos" ("X") "Cosine of X radians.";
+    Osquery "tan" ("X") "Tangent of X radians.";
+    Osquery "cot" ("X") "Cotangent of X radians.";
+    Osquery "asin" ("X") "Arcsine of X, in radians.";
+    Osquery "acos" ("X") "Arccosine of X, in radians.";
+    Osquery "atan" ("X") "Arctangent of X, in radians.";
+
+    // osquery file carving
+    Osquery "carve" ("path") "Requests a carve of the file at path, e.g. SELECT carve(path) FROM files WHERE path = '/etc/hosts'." note "Only works when carving is enabled (--disable_carver=false); the carve is uploaded to Fleet, not returned by the query.";
+};
+
+static BY_NAME: Lazy<HashMap<&'static str, &'static SqlFunction>> =
+    Lazy::new(|| SQL_FUNCTIONS.iter().map(|function| (function.name, function)).collect());
+
+/// The function called `name` (case-insensitive).
+pub fn sql_function(name: &str) -> Option<&'static SqlFunction> {
+    BY_NAME.get(name.to_ascii_lowercase().as_str()).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sql_functions() {
+        assert_eq!(BY_NAME.len(), SQL_FUNCTIONS.len(), "duplicate function names");
+        let split = sql_function("SPLIT").unwrap();
+        assert_eq!(split.signature(), "split(string, tokens, index)");
+        assert!(split.to_markdown().contains("(osquery function)"));
+        assert!(split.to_markdown().contains("**Platforms:** darwin, linux, windows"));
+
+        assert!(sql_function("carve").unwrap().to_markdown().contains("**Note:** Only works when carving is enabled"));
+        assert!(sql_function("lower").unwrap().to_markdown().contains("chrome"));
+        assert!(sql_function("not_a_function").is_none());
+    }
+}
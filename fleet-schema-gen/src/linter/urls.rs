@@ -0,0 +1,457 @@
+//! Liveness checks for software package `url:` fields (`lint --check-urls`).
+//!
+//! Sends a HEAD request (falling back to a ranged GET for servers that
+//! reject HEAD) for every installer URL, with a concurrency limit and an
+//! on-disk cache of recent successful checks. Also flags packages missing
+//! `hash_sha256` when the project requires it.
+
+use super::config::FleetLintConfig;
+use super::error::LintError;
+use super::repo::{load_team, SoftwareSource};
+use super::fleet_config::SoftwarePackage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Options from `[rules.check-urls]` in `.fleetlint.toml`.
+///
+/// ```toml
+/// [rules.check-urls]
+/// concurrency = 8
+/// timeout_secs = 15
+/// cache_ttl_hours = 24
+/// require_hash = true
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct UrlCheckOptions {
+    /// Maximum number of requests in flight.
+    pub concurrency: usize,
+
+    /// Per-request timeout in seconds.
+    pub timeout_secs: u64,
+
+    /// How long a successful check is cached (0 disables the cache).
+    pub cache_ttl_hours: u64,
+
+    /// Require `hash_sha256` on packages downloaded from a URL.
+    pub require_hash: bool,
+
+    /// Report unreachable hosts as info instead of errors (set by
+    /// `--allow-offline`).
+    #[serde(skip)]
+    pub allow_offline: bool,
+}
+
+impl Default for UrlCheckOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            timeout_secs: 15,
+            cache_ttl_hours: 24,
+            require_hash: false,
+            allow_offline: false,
+        }
+    }
+}
+
+impl UrlCheckOptions {
+    /// Load options from the linter configuration, if any.
+    pub fn from_config(config: Option<&FleetLintConfig>) -> Self {
+        config
+            .map(|c| c.rule_options("check-urls"))
+            .unwrap_or_default()
+    }
+}
+
+/// A package URL to check, with the file that declares it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageUrl {
+    pub url: String,
+    pub hash_sha256: Option<String>,
+    /// Package definition (or team file for inline packages).
+    pub file: PathBuf,
+    /// 1-indexed line of the `url:` field.
+    pub line: Option<usize>,
+}
+
+/// Result of checking a single URL.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlStatus {
+    /// Reachable with a successful status.
+    Ok(u16),
+    /// The server answered with an error status.
+    Http(u16),
+    /// The host could not be reached (DNS, TLS, timeout, ...).
+    Unreachable(String),
+}
+
+/// Collect package URLs declared in a file.
+///
+/// Package definition files yield their own `url:`. Team files yield inline
+/// packages and, with `follow_refs`, packages referenced via `path:`.
+pub fn collect_package_urls(file: &Path, follow_refs: bool) -> Vec<PackageUrl> {
+    let Ok(content) = fs::read_to_string(file) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(&content) else {
+        return Vec::new();
+    };
+
+    // Package definition file: top-level `url:`
+    if value.get("url").is_some() {
+        let package: SoftwarePackage = serde_yaml::from_value(value).unwrap_or_default();
+        return package_url(&package, file, &content).into_iter().collect();
+    }
+
+    if value.get("software").is_none() {
+        return Vec::new();
+    }
+
+    let Ok(team) = load_team(file) else {
+        return Vec::new();
+    };
+
+    team.software
+        .iter()
+        .filter(|s| s.source == SoftwareSource::Package)
+        .filter_map(|software| {
+            let package = software.package.as_ref()?;
+            match &software.file {
+                None => package_url(package, file, &content),
+                Some(package_file) if follow_refs => {
+                    let package_source = fs::read_to_string(package_file).unwrap_or_default();
+                    package_url(package, package_file, &package_source)
+                }
+                Some(_) => None,
+            }
+        })
+        .collect()
+}
+
+fn package_url(package: &SoftwarePackage, file: &Path, source: &str) -> Option<PackageUrl> {
+    let url = package.url.clone()?;
+    let line = source
+        .lines()
+        .position(|l| l.trim().trim_start_matches('-').trim().starts_with("url:") && l.contains(url.as_str()))
+        .map(|idx| idx + 1);
+
+    Some(PackageUrl {
+        url,
+        hash_sha256: package.hash_sha256.clone(),
+        file: file.to_path_buf(),
+        line,
+    })
+}
+
+/// Check every package URL, returning lint errors for dead links and
+/// missing hashes.
+pub async fn check_package_urls(packages: &[PackageUrl], options: &UrlCheckOptions) -> Vec<LintError> {
+    let mut errors = Vec::new();
+
+    if options.require_hash {
+        for package in packages.iter().filter(|p| p.hash_sha256.as_deref().is_none_or(|h| h.trim().is_empty())) {
+            errors.push(
+                locate(
+                    LintError::warning(format!("Package {} has no hash_sha256", package.url), &package.file),
+                    package,
+                )
+                .with_help("Add hash_sha256 so Fleet can verify the downloaded installer"),
+            );
+        }
+    }
+
+    let mut urls: Vec<&str> = packages.iter().map(|p| p.url.as_str()).collect();
+    urls.sort();
+    urls.dedup();
+
+    let statuses = check_urls(&urls, options).await;
+
+    for package in packages {
+        let Some(status) = statuses.get(&package.url) else {
+            continue;
+        };
+
+        let error = match status {
+            UrlStatus::Ok(_) => continue,
+            UrlStatus::Http(code) => LintError::error(
+                format!("Package URL returned HTTP {}: {}", code, package.url),
+                &package.file,
+            )
+            .with_help("The installer link appears to be dead; update the url"),
+            UrlStatus::Unreachable(reason) if options.allow_offline => LintError::info(
+                format!("Could not reach package URL (offline?): {}", package.url),
+                &package.file,
+            )
+            .with_help(reason.clone()),
+            UrlStatus::Unreachable(reason) => LintError::error(
+                format!("Package URL is unreachable: {}", package.url),
+                &package.file,
+            )
+            .with_help(format!("{} (use --allow-offline when running without network)", reason)),
+        };
+        errors.push(locate(error, package));
+    }
+
+    errors
+}
+
+fn locate(error: LintError, package: &PackageUrl) -> LintError {
+    match package.line {
+        Some(line) => error.with_location(line, 1),
+        None => error,
+    }
+}
+
+/// Check URLs concurrently, consulting and updating the on-disk cache.
+pub async fn check_urls(urls: &[&str], options: &UrlCheckOptions) -> HashMap<String, UrlStatus> {
+    let mut cache = if options.cache_ttl_hours > 0 {
+        UrlCache::load()
+    } else {
+        UrlCache::default()
+    };
+    let ttl = Duration::from_secs(options.cache_ttl_hours * 3600);
+
+    let mut statuses = HashMap::new();
+    let mut pending = Vec::new();
+    for url in urls {
+        match cache.fresh(url, ttl) {
+            Some(status) => {
+                statuses.insert(url.to_string(), UrlStatus::Ok(status));
+            }
+            None => pending.push(url.to_string()),
+        }
+    }
+
+    let client = match reqwest::Client::builder()
+        .user_agent("fleet-schema-gen")
+        .timeout(Duration::from_secs(options.timeout_secs))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            for url in pending {
+                statuses.insert(url, UrlStatus::Unreachable(e.to_string()));
+            }
+            return statuses;
+        }
+    };
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(options.concurrency.max(1)));
+    let mut tasks = tokio::task::JoinSet::new();
+    // Task -> URL, to report tasks that panicked
+    let mut task_urls = HashMap::new();
+    for url in pending {
+        let client = client.clone();
+        let semaphore = semaphore.clone();
+        let task_url = url.clone();
+        let handle = tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let status = check_url(&client, &url).await;
+            (url, status)
+        });
+        task_urls.insert(handle.id(), task_url);
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok((url, status)) => {
+                if let UrlStatus::Ok(code) = status {
+                    cache.record(&url, code);
+                }
+                statuses.insert(url, status);
+            }
+            Err(e) => {
+                if let Some(url) = task_urls.remove(&e.id()) {
+                    statuses.insert(url, UrlStatus::Unreachable(format!("URL check failed: {}", e)));
+                }
+            }
+        }
+    }
+
+    if options.cache_ttl_hours > 0 {
+        cache.save();
+    }
+
+    statuses
+}
+
+/// HEAD a URL, retrying with a one-byte ranged GET when HEAD is rejected.
+async fn check_url(client: &reqwest::Client, url: &str) -> UrlStatus {
+    let head = match client.head(url).send().await {
+        Ok(response) => response.status(),
+        Err(e) => return UrlStatus::Unreachable(e.to_string()),
+    };
+
+    let status = if matches!(head.as_u16(), 403 | 405 | 501) {
+        match client.get(url).header(reqwest::header::RANGE, "bytes=0-0").send().await {
+            Ok(response) => response.status(),
+            Err(e) => return UrlStatus::Unreachable(e.to_string()),
+        }
+    } else {
+        head
+    };
+
+    if status.is_success() || status.is_redirection() {
+        UrlStatus::Ok(status.as_u16())
+    } else {
+        UrlStatus::Http(status.as_u16())
+    }
+}
+
+/// Successful URL checks, persisted between runs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UrlCache {
+    /// URL -> (HTTP status, unix timestamp of the check)
+    entries: HashMap<String, (u16, i64)>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl UrlCache {
+    fn cache_file() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(base.join("fleet-schema-gen").join("url-cache.json"))
+    }
+
+    fn load() -> Self {
+        let path = Self::cache_file();
+        let mut cache: Self = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        cache.path = path;
+        cache
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string(self) {
+            let _ = fs::write(path, content);
+        }
+    }
+
+    fn fresh(&self, url: &str, ttl: Duration) -> Option<u16> {
+        let (status, checked_at) = self.entries.get(url)?;
+        let age = chrono::Utc::now().timestamp() - checked_at;
+        (age >= 0 && (age as u64) < ttl.as_secs()).then_some(*status)
+    }
+
+    fn record(&mut self, url: &str, status: u16) {
+        self.entries.insert(url.to_string(), (status, chrono::Utc::now().timestamp()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use tempfile::TempDir;
+
+    /// Serve `count` requests, answering 200 for `/ok` and 404 otherwise.
+    fn serve(count: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let status = if request.contains(" /ok ") { "200 OK" } else { "404 Not Found" };
+                let _ = write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_collect_package_urls() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+
+        fs::write(root.join("lib/zoom.yml"), "# Zoom\nurl: https://example.com/zoom.pkg\n").unwrap();
+        fs::write(
+            root.join("teams/t.yml"),
+            "name: T\nsoftware:\n  packages:\n    - path: ../lib/zoom.yml\n    - url: https://example.com/inline.msi\n      hash_sha256: abc\n",
+        )
+        .unwrap();
+
+        let package = collect_package_urls(&root.join("lib/zoom.yml"), false);
+        assert_eq!(package.len(), 1);
+        assert_eq!(package[0].line, Some(2));
+
+        let team = collect_package_urls(&root.join("teams/t.yml"), false);
+        assert_eq!(team.len(), 1);
+        assert_eq!(team[0].url, "https://example.com/inline.msi");
+        assert_eq!(team[0].line, Some(5));
+
+        assert_eq!(collect_package_urls(&root.join("teams/t.yml"), true).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_check_package_urls() {
+        let base = serve(2);
+        let package = |path: &str, hash: Option<&str>| PackageUrl {
+            url: format!("{}{}", base, path),
+            hash_sha256: hash.map(String::from),
+            file: PathBuf::from("lib/app.yml"),
+            line: Some(1),
+        };
+        let options = UrlCheckOptions {
+            cache_ttl_hours: 0,
+            require_hash: true,
+            ..Default::default()
+        };
+
+        let errors = check_package_urls(&[package("/ok", Some("abc")), package("/gone", None)], &options).await;
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("has no hash_sha256"));
+        assert!(messages[1].contains("returned HTTP 404"));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_with_allow_offline() {
+        let unreachable = PackageUrl {
+            url: "http://127.0.0.1:1/pkg".to_string(),
+            hash_sha256: Some("abc".to_string()),
+            file: PathBuf::from("lib/app.yml"),
+            line: None,
+        };
+        let options = UrlCheckOptions {
+            cache_ttl_hours: 0,
+            allow_offline: true,
+            ..Default::default()
+        };
+
+        let errors = check_package_urls(&[unreachable], &options).await;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].severity, crate::linter::Severity::Info);
+    }
+
+    #[test]
+    fn test_cache_freshness() {
+        let mut cache = UrlCache::default();
+        cache.record("https://example.com/a.pkg", 200);
+
+        assert_eq!(cache.fresh("https://example.com/a.pkg", Duration::from_secs(3600)), Some(200));
+        assert_eq!(cache.fresh("https://example.com/a.pkg", Duration::ZERO), None);
+        assert_eq!(cache.fresh("https://example.com/b.pkg", Duration::from_secs(3600)), None);
+    }
+}
@@ -0,0 +1,655 @@
+//! Standalone schema validation (`fleet-schema-gen validate`).
+//!
+//! Validates YAML files against a generated JSON Schema and reports each
+//! violation with its JSON Pointer (e.g. `/policies/0/platform`) and, where
+//! it can be located, the source line. Results can be rendered as text,
+//! JSON, or SARIF for CI code-scanning integrations.
+
+use super::engine::{find_yaml_files, Linter};
+use super::file_role::FileRole;
+use super::walk::WalkOptions;
+use super::error::Severity;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// A single schema violation.
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    /// JSON Pointer to the offending value (`None` for file-level problems).
+    pub pointer: Option<String>,
+    pub message: String,
+    /// `error`, `warning`, or `info`
+    pub severity: &'static str,
+    /// 1-indexed source line, when the pointer could be located.
+    pub line: Option<usize>,
+}
+
+/// Validation result for one file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileValidation {
+    pub file: String,
+    pub valid: bool,
+    pub violations: Vec<Violation>,
+}
+
+impl FileValidation {
+    fn new(file: &Path, violations: Vec<Violation>) -> Self {
+        Self {
+            file: file.display().to_string(),
+            valid: !violations.iter().any(|v| v.severity == "error"),
+            violations,
+        }
+    }
+}
+
+/// Expand paths, directories, and glob patterns into YAML files.
+pub fn expand_targets(targets: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for target in targets {
+        let path = Path::new(target);
+        if path.is_dir() {
+            files.extend(find_yaml_files(path, &WalkOptions::default())?);
+        } else if path.is_file() {
+            files.push(path.to_path_buf());
+        } else if target.contains(['*', '?', '[']) {
+            let matches = glob::glob(target)
+                .with_context(|| format!("Invalid glob pattern: {}", target))?;
+            files.extend(matches.filter_map(|entry| entry.ok()).filter(|p| p.is_file()));
+        } else {
+            anyhow::bail!("Path does not exist: {}", target);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Validates files against a JSON Schema.
+pub struct SchemaValidator {
+    validator: jsonschema::Validator,
+    /// Validate each item of a list against the schema (lib files).
+    items: bool,
+}
+
+impl SchemaValidator {
+    /// Load a JSON Schema file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read schema: {}", path.display()))?;
+        let schema: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse schema: {}", path.display()))?;
+        Self::new(&schema)
+    }
+
+    pub fn new(schema: &Value) -> Result<Self> {
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|e| anyhow::anyhow!("Invalid JSON Schema: {}", e))?;
+        Ok(Self { validator, items: false })
+    }
+
+    /// Validate the items of a top-level list instead of the whole file,
+    /// for lib files holding a list of policies, queries or labels.
+    pub fn for_items(mut self) -> Self {
+        self.items = true;
+        self
+    }
+
+    /// Validate YAML source.
+    pub fn validate(&self, source: &str, file: &Path) -> FileValidation {
+        let instance: Value = match serde_yaml::from_str(source) {
+            Ok(value) => value,
+            Err(e) => {
+                let violation = Violation {
+                    pointer: None,
+                    message: format!("Invalid YAML: {}", e),
+                    severity: "error",
+                    line: e.location().map(|l| l.line()),
+                };
+                return FileValidation::new(file, vec![violation]);
+            }
+        };
+
+        let items: Vec<(String, &Value)> = match (&instance, self.items) {
+            (Value::Array(items), true) => {
+                items.iter().enumerate().map(|(idx, item)| (format!("/{}", idx), item)).collect()
+            }
+            (_, true) => {
+                let violation = Violation {
+                    pointer: Some(String::new()),
+                    message: "Expected a list of items".to_string(),
+                    severity: "error",
+                    line: Some(1),
+                };
+                return FileValidation::new(file, vec![violation]);
+            }
+            (instance, false) => vec![(String::new(), instance)],
+        };
+
+        let violations = items
+            .into_iter()
+            .flat_map(|(prefix, instance)| {
+                self.validator.iter_errors(instance).map(move |error| {
+                    let pointer = format!("{}{}", prefix, error.instance_path().as_str());
+                    Violation {
+                        line: pointer_line(source, &pointer),
+                        pointer: Some(pointer),
+                        message: error.to_string(),
+                        severity: "error",
+                    }
+                })
+            })
+            .collect();
+
+        FileValidation::new(file, violations)
+    }
+}
+
+/// Kinds of file with a strict schema of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// `default.yml` (or any file that isn't a team or lib file).
+    Default,
+    /// A file in `teams/`.
+    Team,
+    /// A lib file with a list of policies.
+    Policies,
+    /// A lib file with a list of queries.
+    Queries,
+    /// A lib file with a list of labels.
+    Labels,
+}
+
+impl SchemaKind {
+    pub const ALL: [SchemaKind; 5] =
+        [SchemaKind::Default, SchemaKind::Team, SchemaKind::Policies, SchemaKind::Queries, SchemaKind::Labels];
+
+    /// Kind of the file at `path`, or `None` for lib files without a strict
+    /// schema (software, agent options, scripts, unconventional names).
+    pub fn detect(path: &Path) -> Option<SchemaKind> {
+        let relative = super::file_role::relative_to_root(path);
+        let in_dir = |name: &str| relative.parent().is_some_and(|dir| dir.components().any(|c| c.as_os_str() == name));
+        match FileRole::detect(path) {
+            Some(FileRole::Policies) => Some(SchemaKind::Policies),
+            Some(FileRole::Queries) => Some(SchemaKind::Queries),
+            Some(FileRole::Labels) => Some(SchemaKind::Labels),
+            Some(_) => None,
+            None if path.parent().and_then(Path::file_name).is_some_and(|dir| dir == "teams") => Some(SchemaKind::Team),
+            None if in_dir("lib") => None,
+            None => Some(SchemaKind::Default),
+        }
+    }
+
+    /// File name of the kind's schema, as written by `generate --editor strict`.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            SchemaKind::Default => "fleet-gitops-default.strict.schema.json",
+            SchemaKind::Team => "fleet-gitops-team.strict.schema.json",
+            SchemaKind::Policies => "fleet-gitops-policy.strict.schema.json",
+            SchemaKind::Queries => "fleet-gitops-query.strict.schema.json",
+            SchemaKind::Labels => "fleet-gitops-label.strict.schema.json",
+        }
+    }
+
+    /// Whether files of this kind are lists validated item by item; the
+    /// generated policy, query and label schemas describe a single item.
+    pub fn is_list(self) -> bool {
+        matches!(self, SchemaKind::Policies | SchemaKind::Queries | SchemaKind::Labels)
+    }
+}
+
+/// Where strict schemas come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaSource {
+    /// One schema file for every file (`validate --schema <path>`).
+    File(PathBuf),
+    /// Schemas generated for a Fleet version, one directory per version
+    /// in `versions_dir`.
+    Generated { versions_dir: PathBuf, fleet_version: String },
+    /// The snapshot embedded in the binary (`--schema bundled`).
+    Bundled,
+}
+
+impl std::str::FromStr for SchemaSource {
+    type Err = std::convert::Infallible;
+
+    /// `bundled` is always the embedded snapshot; a schema file of that
+    /// name can be passed as `./bundled`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "bundled" => SchemaSource::Bundled,
+            path => SchemaSource::File(PathBuf::from(path)),
+        })
+    }
+}
+
+impl SchemaSource {
+    /// The generated schemas for `fleet_version` in `versions_dir`, or the
+    /// embedded snapshot when none were generated.
+    pub fn for_version(versions_dir: &Path, fleet_version: &str) -> Self {
+        match versioned_schema(versions_dir, fleet_version, SchemaKind::Default) {
+            Some(_) => SchemaSource::Generated {
+                versions_dir: versions_dir.to_path_buf(),
+                fleet_version: fleet_version.to_string(),
+            },
+            None => SchemaSource::Bundled,
+        }
+    }
+
+    /// Validator for files of `kind`, `None` when the source has no schema
+    /// for it.
+    pub fn validator(&self, kind: Option<SchemaKind>) -> Result<Option<SchemaValidator>> {
+        let validator = match (self, kind) {
+            (SchemaSource::File(path), _) => SchemaValidator::from_file(path)?,
+            (_, None) => return Ok(None),
+            (SchemaSource::Generated { versions_dir, fleet_version }, Some(kind)) => {
+                match versioned_schema(versions_dir, fleet_version, kind) {
+                    Some(path) => SchemaValidator::from_file(&path)?,
+                    None => return Ok(None),
+                }
+            }
+            (SchemaSource::Bundled, Some(kind)) => super::bundled::strict_validator(kind)?,
+        };
+        Ok(Some(match kind {
+            Some(kind) if kind.is_list() && !matches!(self, SchemaSource::File(_)) => validator.for_items(),
+            _ => validator,
+        }))
+    }
+}
+
+impl std::fmt::Display for SchemaSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaSource::File(path) => write!(f, "{}", path.display()),
+            SchemaSource::Generated { versions_dir, fleet_version } => {
+                write!(f, "the Fleet {} schemas in {}/", fleet_version, versions_dir.display())
+            }
+            SchemaSource::Bundled => write!(f, "the bundled Fleet {} schemas", super::bundled::SNAPSHOT_VERSION),
+        }
+    }
+}
+
+/// Validators of a [`SchemaSource`], compiled on first use of each kind.
+pub struct SchemaValidators {
+    source: SchemaSource,
+    /// One slot per [`SchemaKind`], then one for files without a kind
+    compiled: [OnceLock<Option<SchemaValidator>>; SchemaKind::ALL.len() + 1],
+}
+
+impl SchemaValidators {
+    pub fn new(source: SchemaSource) -> Self {
+        Self { source, compiled: Default::default() }
+    }
+
+    pub fn source(&self) -> &SchemaSource {
+        &self.source
+    }
+
+    /// Validator for the file at `path`, `None` when the source has no
+    /// schema for its kind.
+    pub fn for_file(&self, path: &Path) -> Result<Option<&SchemaValidator>> {
+        let kind = SchemaKind::detect(path);
+        let slot = &self.compiled[kind.map_or(SchemaKind::ALL.len(), |kind| kind as usize)];
+        if slot.get().is_none() {
+            let _ = slot.set(self.source.validator(kind)?);
+        }
+        Ok(slot.get().and_then(Option::as_ref))
+    }
+}
+
+/// Find the generated strict schema of `kind` for a Fleet version.
+///
+/// `versions_dir` holds one directory per generated version
+/// (`schemas/4.58.0/`). The newest version not newer than `fleet_version`
+/// is used, so a repo pinned to 4.58.3 validates against 4.58.0.
+pub fn versioned_schema(versions_dir: &Path, fleet_version: &str, kind: SchemaKind) -> Option<PathBuf> {
+    versioned_file(versions_dir, fleet_version, kind.file_name(), "strict")
+}
+
+/// Find a generated file for a Fleet version, directly in the version
+/// directory or in its `editor` subdirectory (`generate --editor all`).
+pub fn versioned_file(versions_dir: &Path, fleet_version: &str, file: &str, editor: &str) -> Option<PathBuf> {
+    use super::osquery::compare_versions;
+
+    generated_versions(versions_dir)
+        .into_iter()
+        .rev()
+        .filter(|(version, _)| compare_versions(version, fleet_version).is_le())
+        .find_map(|(_, dir)| generated_file(&dir, file, editor))
+}
+
+/// The version directories in `versions_dir`, oldest first.
+pub fn generated_versions(versions_dir: &Path) -> Vec<(String, PathBuf)> {
+    use super::osquery::compare_versions;
+
+    let Ok(entries) = fs::read_dir(versions_dir) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.trim_start_matches('v').to_string();
+            name.starts_with(|c: char| c.is_ascii_digit()).then(|| (name, entry.path()))
+        })
+        .collect();
+    versions.sort_by(|a, b| compare_versions(&a.0, &b.0));
+    versions
+}
+
+/// A generated file in one version directory or its `editor` subdirectory.
+pub fn generated_file(version_dir: &Path, file: &str, editor: &str) -> Option<PathBuf> {
+    [version_dir.join(file), version_dir.join(editor).join(file)]
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+/// Validate with the built-in lint rules when no schema is given.
+///
+/// Lint errors carry line numbers but no JSON Pointer.
+pub fn validate_with_linter(linter: &Linter, file: &Path) -> FileValidation {
+    let violations = match linter.lint_file(file) {
+        Ok(report) => report
+            .errors
+            .iter()
+            .chain(&report.warnings)
+            .chain(&report.infos)
+            .map(|error| Violation {
+                pointer: None,
+                message: error.message.clone(),
+                severity: match error.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "info",
+                },
+                line: error.line,
+            })
+            .collect(),
+        Err(e) => vec![Violation {
+            pointer: None,
+            message: e.to_string(),
+            severity: "error",
+            line: None,
+        }],
+    };
+
+    FileValidation::new(file, violations)
+}
+
+/// Find the source line of a JSON Pointer in block-style YAML.
+///
+/// Walks the pointer one segment at a time: object keys are matched as
+/// `key:` lines and array indices as the nth `- ` item below the parent.
+/// Flow-style collections (`{...}`, `[...]`) aren't descended into.
+pub fn pointer_line(source: &str, pointer: &str) -> Option<usize> {
+    let lines: Vec<&str> = source.lines().collect();
+    let indent_of = |i: usize| lines[i].len() - lines[i].trim_start().len();
+    let is_content = |i: usize| {
+        let trimmed = lines[i].trim();
+        !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed != "---"
+    };
+    let is_item = |i: usize| {
+        let trimmed = lines[i].trim_start();
+        trimmed == "-" || trimmed.starts_with("- ")
+    };
+    // Column where a line's key starts (after "- " for list items)
+    let key_column = |i: usize| if is_item(i) { indent_of(i) + 2 } else { indent_of(i) };
+
+    // Current node: lines [start, end), and the key column when the node is a
+    // list item whose mapping starts on the "- " line
+    let (mut start, mut end, mut item_column) = (0, lines.len(), None);
+    let mut line = None;
+
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        let body: Vec<usize> = (start..end).filter(|&i| is_content(i)).collect();
+
+        let found = match segment.parse::<usize>() {
+            Ok(index) if item_column.is_none() => {
+                let indent = body.iter().map(|&i| indent_of(i)).min()?;
+                let item = body
+                    .iter()
+                    .copied()
+                    .filter(|&i| indent_of(i) == indent && is_item(i))
+                    .nth(index)?;
+                let next = (item + 1..end)
+                    .find(|&i| is_content(i) && indent_of(i) <= indent)
+                    .unwrap_or(end);
+
+                start = item;
+                end = next;
+                item_column = Some(indent + 2);
+                item
+            }
+            Ok(_) => return None,
+            Err(_) => {
+                let column = match item_column {
+                    Some(column) => column,
+                    None => body.iter().map(|&i| key_column(i)).min()?,
+                };
+                let key = body.iter().copied().find(|&i| {
+                    let text = &lines[i][key_column(i).min(lines[i].len())..];
+                    key_column(i) == column
+                        && (text.starts_with(&format!("{}:", segment))
+                            || text.starts_with(&format!("\"{}\":", segment))
+                            || text.starts_with(&format!("'{}':", segment)))
+                })?;
+
+                // Block value: deeper lines, or a compact "- " list at the key's column
+                let next = (key + 1..end)
+                    .find(|&i| {
+                        is_content(i)
+                            && (indent_of(i) < column || (indent_of(i) == column && !is_item(i)))
+                    })
+                    .unwrap_or(end);
+
+                start = key + 1;
+                end = next;
+                item_column = None;
+                key
+            }
+        };
+
+        line = Some(found + 1);
+    }
+
+    line
+}
+
+/// Render results as a SARIF 2.1.0 log.
+pub fn to_sarif(results: &[FileValidation]) -> Value {
+    let sarif_results: Vec<Value> = results
+        .iter()
+        .flat_map(|result| {
+            result.violations.iter().map(move |violation| {
+                let level = match violation.severity {
+                    "warning" => "warning",
+                    "info" => "note",
+                    _ => "error",
+                };
+
+                let mut location = json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": result.file },
+                    },
+                });
+                if let Some(line) = violation.line {
+                    location["physicalLocation"]["region"] = json!({ "startLine": line });
+                }
+                if let Some(pointer) = &violation.pointer {
+                    location["logicalLocations"] = json!([{ "fullyQualifiedName": pointer }]);
+                }
+
+                json!({
+                    "ruleId": if violation.pointer.is_some() { "schema" } else { "lint" },
+                    "level": level,
+                    "message": { "text": violation.message },
+                    "locations": [location],
+                })
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "fleet-schema-gen",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": [
+                        { "id": "schema", "shortDescription": { "text": "JSON Schema violation" } },
+                        { "id": "lint", "shortDescription": { "text": "Fleet lint rule violation" } },
+                    ],
+                },
+            },
+            "results": sarif_results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"name: Workstations
+policies:
+  - name: Disk encryption
+    platform: darwin
+  - name: Firewall
+    platform: macos
+    critical: "yes"
+"#;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "policies": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "platform": { "enum": ["darwin", "windows", "linux", "chrome"] },
+                            "critical": { "type": "boolean" }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_pointer_line() {
+        assert_eq!(pointer_line(SOURCE, "/name"), Some(1));
+        assert_eq!(pointer_line(SOURCE, "/policies"), Some(2));
+        assert_eq!(pointer_line(SOURCE, "/policies/0"), Some(3));
+        assert_eq!(pointer_line(SOURCE, "/policies/1/platform"), Some(6));
+        assert_eq!(pointer_line(SOURCE, "/policies/1/name"), Some(5));
+        assert_eq!(pointer_line(SOURCE, "/policies/2"), None);
+    }
+
+    #[test]
+    fn test_schema_validation_reports_pointers() {
+        let validator = SchemaValidator::new(&schema()).unwrap();
+        let result = validator.validate(SOURCE, Path::new("teams/workstations.yml"));
+
+        assert!(!result.valid);
+        let mut found: Vec<(String, Option<usize>)> = result
+            .violations
+            .iter()
+            .map(|v| (v.pointer.clone().unwrap(), v.line))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("/policies/1/critical".to_string(), Some(7)),
+                ("/policies/1/platform".to_string(), Some(6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_sarif() {
+        let validator = SchemaValidator::new(&schema()).unwrap();
+        let result = validator.validate(SOURCE, Path::new("teams/workstations.yml"));
+        let sarif = to_sarif(&[result]);
+
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "schema");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "teams/workstations.yml"
+        );
+        assert!(results[0]["locations"][0]["physicalLocation"]["region"]["startLine"].is_number());
+    }
+
+    #[test]
+    fn test_expand_targets() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::write(root.join("teams/a.yml"), "name: A\n").unwrap();
+        fs::write(root.join("teams/b.yaml"), "name: B\n").unwrap();
+        fs::write(root.join("default.yml"), "org_settings: {}\n").unwrap();
+
+        let dir = expand_targets(&[root.join("teams").display().to_string()]).unwrap();
+        assert_eq!(dir.len(), 2);
+
+        let glob = expand_targets(&[format!("{}/*.yml", root.display())]).unwrap();
+        assert_eq!(glob, vec![root.join("default.yml")]);
+
+        assert!(expand_targets(&[root.join("missing.yml").display().to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_versioned_schema() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let default_schema = SchemaKind::Default.file_name();
+        for version in ["4.55.0", "4.58.0", "4.65.0"] {
+            fs::create_dir_all(root.join(version).join("strict")).unwrap();
+            fs::write(root.join(version).join("strict").join(default_schema), "{}").unwrap();
+        }
+
+        let schema = |version| {
+            versioned_schema(root, version, SchemaKind::Default).map(|p| p.strip_prefix(root).unwrap().to_path_buf())
+        };
+        assert_eq!(schema("4.58.0"), Some(PathBuf::from("4.58.0/strict").join(default_schema)));
+        assert_eq!(schema("4.60.1"), Some(PathBuf::from("4.58.0/strict").join(default_schema)));
+        assert_eq!(schema("4.50.0"), None);
+    }
+
+    #[test]
+    fn test_generated_schemas_by_kind() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("4.58.0");
+        fs::create_dir_all(&dir).unwrap();
+        let closed = |required: &str| json!({"type": "object", "required": [required]}).to_string();
+        fs::write(dir.join(SchemaKind::Default.file_name()), closed("org_settings")).unwrap();
+        fs::write(dir.join(SchemaKind::Team.file_name()), closed("name")).unwrap();
+        fs::write(dir.join(SchemaKind::Policies.file_name()), closed("query")).unwrap();
+
+        let source = SchemaSource::for_version(temp_dir.path(), "4.58.0");
+        assert!(matches!(source, SchemaSource::Generated { .. }));
+        let validators = SchemaValidators::new(source);
+        let validate = |file: &str, source: &str| {
+            validators.for_file(Path::new(file)).unwrap().map(|v| v.validate(source, Path::new(file)).valid)
+        };
+        assert_eq!(validate("default.yml", "org_settings: {}\n"), Some(true));
+        assert_eq!(validate("teams/ws.yml", "name: WS\n"), Some(true));
+        assert_eq!(validate("teams/ws.yml", "org_settings: {}\n"), Some(false));
+        assert_eq!(validate("lib/a.policies.yml", "- query: SELECT 1;\n"), Some(true));
+        assert_eq!(validate("lib/a.policies.yml", "- name: A\n"), Some(false));
+        // Not generated for this version
+        assert_eq!(validate("lib/a.queries.yml", "- name: A\n"), None);
+
+        assert_eq!(SchemaSource::for_version(temp_dir.path(), "4.50.0"), SchemaSource::Bundled);
+    }
+}
@@ -0,0 +1,282 @@
+//! Directory traversal shared by lint, workspace detection and LSP path
+//! completion.
+//!
+//! Honors `.gitignore`, `.ignore` and `.git/info/exclude` unless disabled,
+//! so build output and vendored YAML don't end up in lint results. Hidden
+//! entries and common build directories are always skipped.
+//!
+//! Symlinks are followed with cycle detection. Files over the size limit and
+//! entries that can't be read are reported instead of silently dropped.
+//!
+//! [`tree_files`] is the unfiltered variant for copying and archiving whole
+//! directories, where a symlink is an error rather than something to follow.
+
+use super::config::FleetLintConfig;
+use ignore::WalkBuilder;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directories never scanned, ignore files or not.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist"];
+
+/// Default size limit for scanned files (1 MiB).
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Traversal options.
+#[derive(Debug, Clone)]
+pub struct WalkOptions {
+    /// Skip files ignored by `.gitignore`/`.ignore`.
+    pub respect_ignore: bool,
+    /// Directory levels below the root to descend (`Some(0)`: the root only).
+    pub max_depth: Option<usize>,
+    /// Skip files larger than this many bytes.
+    pub max_file_size: Option<u64>,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            respect_ignore: true,
+            max_depth: None,
+            max_file_size: Some(DEFAULT_MAX_FILE_SIZE),
+        }
+    }
+}
+
+impl WalkOptions {
+    /// Options from the `[files]` section of a config.
+    pub fn from_config(config: Option<&FleetLintConfig>) -> Self {
+        match config {
+            Some(config) => Self {
+                respect_ignore: config.files.gitignore,
+                max_depth: None,
+                max_file_size: (config.files.max_file_size > 0).then_some(config.files.max_file_size),
+            },
+            None => Self::default(),
+        }
+    }
+}
+
+/// Why an entry was left out of a scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// File is larger than the limit (size in bytes).
+    TooLarge(u64),
+    /// Symlink points back to one of its parent directories.
+    SymlinkLoop,
+    /// Permission error or broken symlink.
+    Unreadable(String),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::TooLarge(size) => write!(f, "file is {} KiB, over the size limit", size / 1024),
+            SkipReason::SymlinkLoop => write!(f, "symlink loop"),
+            SkipReason::Unreadable(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// Result of a directory scan.
+#[derive(Debug, Default)]
+pub struct Scan {
+    /// Files found, sorted by path.
+    pub files: Vec<PathBuf>,
+    /// Entries left out, with the reason.
+    pub skipped: Vec<(PathBuf, SkipReason)>,
+}
+
+/// Scan `root` and report skipped entries.
+pub fn scan(root: &Path, options: &WalkOptions) -> Scan {
+    let respect_ignore = options.respect_ignore;
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(true)
+        .git_ignore(respect_ignore)
+        .git_exclude(respect_ignore)
+        .git_global(respect_ignore)
+        .ignore(respect_ignore)
+        .parents(respect_ignore)
+        // Honor .gitignore in exported trees and fresh checkouts without .git
+        .require_git(false)
+        // Loops are detected and reported as errors
+        .follow_links(true)
+        .max_depth(options.max_depth.map(|depth| depth + 1))
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|entry| {
+            !(entry.file_type().is_some_and(|t| t.is_dir())
+                && entry.file_name().to_str().is_some_and(|name| SKIPPED_DIRS.contains(&name)))
+        });
+
+    let mut result = Scan::default();
+    for entry in builder.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                result.skipped.push(skipped_from_error(root, &e));
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+
+        if let Some(limit) = options.max_file_size {
+            match entry.metadata() {
+                Ok(metadata) if metadata.len() > limit => {
+                    result.skipped.push((entry.into_path(), SkipReason::TooLarge(metadata.len())));
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    result.skipped.push((entry.into_path(), SkipReason::Unreadable(e.to_string())));
+                    continue;
+                }
+            }
+        }
+        result.files.push(entry.into_path());
+    }
+    result
+}
+
+fn skipped_from_error(root: &Path, error: &ignore::Error) -> (PathBuf, SkipReason) {
+    match error {
+        ignore::Error::WithPath { path, err } => (path.clone(), skipped_from_error(root, err).1),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => skipped_from_error(root, err),
+        ignore::Error::Loop { child, .. } => (child.clone(), SkipReason::SymlinkLoop),
+        ignore::Error::Io(e) => (root.to_path_buf(), SkipReason::Unreadable(e.to_string())),
+        other => (root.to_path_buf(), SkipReason::Unreadable(other.to_string())),
+    }
+}
+
+/// Files under `root`, sorted by path. Skipped entries are logged.
+pub fn files(root: &Path, options: &WalkOptions) -> Vec<PathBuf> {
+    let scan = scan(root, options);
+    for (path, reason) in &scan.skipped {
+        match reason {
+            SkipReason::TooLarge(_) => tracing::info!("Skipping {}: {}", path.display(), reason),
+            _ => tracing::warn!("Skipping {}: {}", path.display(), reason),
+        }
+    }
+    scan.files
+}
+
+/// Every file under `dir`, recursively and sorted by path, skipping `.git`.
+///
+/// Fails on the first symlink: following one would copy files from outside
+/// `dir` (or recurse forever on a loop). A missing `dir` has no files.
+pub fn tree_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = fs::symlink_metadata(&path)?.file_type();
+            if file_type.is_symlink() {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} is a symlink", path.display())));
+            } else if file_type.is_dir() {
+                if entry.file_name() != ".git" {
+                    walk(&path, files)?;
+                }
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    if fs::symlink_metadata(dir).is_ok_and(|m| m.is_dir()) {
+        walk(dir, &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Whether `path` has a `.yml`/`.yaml` extension.
+pub fn is_yaml(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("yml" | "yaml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn relative(root: &Path, files: &[PathBuf]) -> Vec<String> {
+        files
+            .iter()
+            .filter(|p| is_yaml(p))
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_files_respects_gitignore() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for file in ["default.yml", "teams/a.yml", "build/out.yml", "node_modules/x.yml", ".hidden/y.yml"] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        fs::write(root.join(".gitignore"), "build/\n").unwrap();
+
+        let options = WalkOptions::default();
+        assert_eq!(relative(root, &files(root, &options)), vec!["default.yml", "teams/a.yml"]);
+
+        let no_ignore = WalkOptions { respect_ignore: false, ..Default::default() };
+        assert_eq!(
+            relative(root, &files(root, &no_ignore)),
+            vec!["build/out.yml", "default.yml", "teams/a.yml"]
+        );
+
+        let shallow = WalkOptions { max_depth: Some(0), ..Default::default() };
+        assert_eq!(relative(root, &files(root, &shallow)), vec!["default.yml"]);
+    }
+
+    #[test]
+    fn test_scan_reports_large_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("small.yml"), "a: 1\n").unwrap();
+        fs::write(root.join("large.yml"), "a: 1\n".repeat(100)).unwrap();
+
+        let options = WalkOptions { max_file_size: Some(100), ..Default::default() };
+        let scan = scan(root, &options);
+        assert_eq!(relative(root, &scan.files), vec!["small.yml"]);
+        assert_eq!(scan.skipped, vec![(root.join("large.yml"), SkipReason::TooLarge(500))]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_survives_symlink_loops() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::write(root.join("teams/a.yml"), "").unwrap();
+        std::os::unix::fs::symlink(root.join("teams"), root.join("teams/loop")).unwrap();
+
+        let scan = scan(root, &WalkOptions::default());
+        assert_eq!(relative(root, &scan.files), vec!["teams/a.yml"]);
+        assert!(scan.skipped.iter().any(|(_, reason)| *reason == SkipReason::SymlinkLoop));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_tree_files_rejects_symlinks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("lib/.git")).unwrap();
+        fs::write(root.join("lib/a.yml"), "").unwrap();
+        fs::write(root.join("lib/.git/HEAD"), "").unwrap();
+        assert_eq!(tree_files(&root.join("lib")).unwrap(), vec![root.join("lib/a.yml")]);
+        assert!(tree_files(&root.join("missing")).unwrap().is_empty());
+
+        std::os::unix::fs::symlink(root.join("lib"), root.join("lib/loop")).unwrap();
+        let error = tree_files(&root.join("lib")).unwrap_err();
+        assert!(error.to_string().contains("is a symlink"));
+    }
+}
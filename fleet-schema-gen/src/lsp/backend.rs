@@ -2,42 +2,99 @@
 
 use dashmap::DashMap;
 use std::path::PathBuf;
-use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::{
-    CodeActionParams, CodeActionProviderCapability, CodeActionResponse,
-    CompletionOptions, CompletionParams, CompletionResponse,
-    Diagnostic, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    CodeActionKind, CodeActionOptions, CodeActionParams, CodeActionProviderCapability, CodeActionResponse,
+    CodeLens, CodeLensOptions, CodeLensParams, ExecuteCommandOptions, ExecuteCommandParams,
+    CompletionItem, CompletionOptions, CompletionParams, CompletionResponse, CompletionTriggerKind,
+    Diagnostic, DidChangeTextDocumentParams, DidChangeWatchedFilesParams,
+    DidChangeWatchedFilesRegistrationOptions, DidCloseTextDocumentParams, FileChangeType,
+    FileSystemWatcher, GlobPattern, Registration, WatchKind,
     DidOpenTextDocumentParams, DocumentSymbol, DocumentSymbolParams,
     DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse,
     Hover, HoverParams, HoverProviderCapability,
     InitializeParams, InitializeResult, InitializedParams,
     MessageType, OneOf, ServerCapabilities, ServerInfo, TextDocumentSyncCapability,
-    TextDocumentSyncKind, Url, Position, Range, DiagnosticSeverity,
+    TextDocumentSyncKind, Url,
     SemanticTokens, SemanticTokensFullOptions, SemanticTokensOptions,
     SemanticTokensParams, SemanticTokensResult, SemanticTokensServerCapabilities,
+    SelectionRange, SelectionRangeParams, SelectionRangeProviderCapability, ShowDocumentParams,
+    CodeActionOrCommand, Command, DocumentChanges, WorkspaceEdit,
+    DocumentRangeFormattingParams, TextEdit,
+    SignatureHelp, SignatureHelpOptions, SignatureHelpParams,
 };
 use tower_lsp::{Client, LanguageServer};
 
+use crate::generators::lsp::SCHEMA_FILE;
+use crate::linter::engine::find_yaml_files;
+use crate::linter::validate::versioned_file;
+use crate::linter::walk::WalkOptions;
 use crate::linter::{FleetLintConfig, Linter};
-use super::code_actions::generate_code_actions;
-use super::completion::complete_at_with_context;
-use super::diagnostics::lint_error_to_diagnostic;
-use super::hover::hover_at;
+use crate::linter::osqueryi::{find_osqueryi, format_sample, run_query};
+use super::code_actions::{
+    duplicate_actions, extract_to_lib_action, generate_code_actions, sort_items_actions, OPEN_FILE_COMMAND,
+    SORT_ITEMS_KIND,
+};
+use super::code_lens::{extract_query_at_line, query_code_lenses, RUN_QUERY_COMMAND};
+use super::formatting::range_formatting_edits;
+use super::signature_help::{signature_help, TRIGGER_CHARACTERS as SIGNATURE_TRIGGER_CHARACTERS};
+use super::completion::{
+    complete_at_with_index, complete_on_trigger, resolve_completion_item_with, CompletionDetail, TRIGGER_CHARACTERS,
+};
+use super::diagnostics::{engine_error_to_diagnostic, file_error_diagnostics, lint_error_to_diagnostic};
+use super::fix_all::{
+    document_edit, fix_all_action, fix_edits, kind_requested, FixAllParams, FixAllResult, FIX_ALL_KIND,
+};
+use super::hover::{hover_in_file, profile_hover};
+use super::schema;
+use super::selection::selection_ranges;
 use super::semantic_tokens::{compute_semantic_tokens, create_legend};
+use super::store::DocumentStore;
+use super::summary::{FileSummary, FileSummaryParams, FILE_SUMMARY_OPTION};
 use super::symbols::document_symbols;
-use super::workspace::{get_path_definition, validate_path_references};
+use super::workspace::{
+    get_path_definition, referenced_file_errors, references_file, relate_moved_files,
+    validate_path_references, FileIndex,
+};
+use super::workspace_edit::{
+    adapt_for_client, create_file, is_empty, preview, split_file_creations, stale_documents, to_changes_map,
+    EditSupport, PreviewParams, PreviewResult, APPLY_EDIT_COMMAND,
+};
 
 /// Fleet LSP backend that handles document events and publishes diagnostics.
 pub struct FleetLspBackend {
     /// LSP client for sending notifications.
     client: Client,
-    /// Document content cache, keyed by URI.
-    documents: DashMap<String, String>,
+    /// Open documents, and metadata of the files they reference.
+    store: DocumentStore,
+    /// Version of each open document, keyed by URI.
+    versions: DashMap<String, i32>,
     /// The Fleet GitOps linter.
     linter: RwLock<Linter>,
     /// Workspace root path.
     workspace_root: RwLock<Option<PathBuf>>,
+    /// Path completion candidates for the workspace root.
+    file_index: RwLock<Option<Arc<FileIndex>>>,
+    /// Where field docs come from; rebuilt when `schema-defs/` changes.
+    docs_source: RwLock<DocsSource>,
+    /// Which workspace edits the client can apply.
+    edit_support: RwLock<EditSupport>,
+    /// How much documentation completion items carry.
+    completion_detail: RwLock<CompletionDetail>,
+    /// Whether the client asked for `fleet/fileSummary` notifications.
+    file_summary: AtomicBool,
+}
+
+/// Inputs of the field docs in use.
+#[derive(Debug, Clone, Default)]
+struct DocsSource {
+    /// Generated `fleet-schema.json` for the workspace's Fleet version;
+    /// the embedded snapshot when unset.
+    schema_path: Option<PathBuf>,
+    /// Local schema enhancements applied on top.
+    defs_dir: Option<PathBuf>,
 }
 
 impl FleetLspBackend {
@@ -45,15 +102,70 @@ impl FleetLspBackend {
     pub fn new(client: Client, linter: Linter) -> Self {
         Self {
             client,
-            documents: DashMap::new(),
+            store: DocumentStore::new(),
+            versions: DashMap::new(),
             linter: RwLock::new(linter),
             workspace_root: RwLock::new(None),
+            file_index: RwLock::new(None),
+            docs_source: RwLock::new(DocsSource::default()),
+            edit_support: RwLock::new(EditSupport::default()),
+            completion_detail: RwLock::new(CompletionDetail::default()),
+            file_summary: AtomicBool::new(false),
+        }
+    }
+
+    /// Remember the workspace root and load its config.
+    fn set_workspace_root(&self, path: PathBuf) {
+        if let Ok(mut file_index) = self.file_index.write() {
+            *file_index = Some(Arc::new(FileIndex::new(&path)));
+        }
+        self.load_config(&path);
+        // Errors of stored files depend on the config
+        self.store.clear();
+        if let Ok(mut workspace_root) = self.workspace_root.write() {
+            *workspace_root = Some(path);
         }
     }
 
     /// Load configuration from workspace root.
     fn load_config(&self, workspace_root: &PathBuf) {
-        if let Some((config_path, config)) = FleetLintConfig::find_and_load(workspace_root) {
+        let loaded = FleetLintConfig::find_and_load(workspace_root);
+        let (config_dir, schema_config) = match &loaded {
+            Some((path, config)) => (path.parent().unwrap_or(workspace_root), config.schema.clone()),
+            None => (workspace_root.as_path(), Default::default()),
+        };
+        let fleet_version = loaded.as_ref().and_then(|(_, config)| config.fleet_version.as_deref());
+        let source = DocsSource {
+            schema_path: fleet_version.and_then(|version| {
+                versioned_file(&config_dir.join(&schema_config.versions_dir), version, SCHEMA_FILE, "lsp")
+            }),
+            defs_dir: Some(config_dir.join(&schema_config.defs_dir)).filter(|dir| dir.is_dir()),
+        };
+        if let (Some(version), None) = (fleet_version, &source.schema_path) {
+            // Editors without a Fleet status item only see this as a notification
+            let client = self.client.clone();
+            let message = format!(
+                "No generated schema for Fleet {} in {}/; using the bundled {} schema. Run `fleet-schema-gen generate --fleet-version {} --editor lsp --output {}/{}`",
+                version,
+                schema_config.versions_dir,
+                crate::linter::bundled::SNAPSHOT_VERSION,
+                version,
+                schema_config.versions_dir,
+                version
+            );
+            tokio::spawn(async move {
+                client.show_message(MessageType::WARNING, message).await;
+            });
+        }
+        if source.schema_path.is_some() || source.defs_dir.is_some() {
+            if let Ok(mut docs_source) = self.docs_source.write() {
+                *docs_source = source;
+            }
+            self.reload_field_docs();
+        }
+
+        if let Some((config_path, config)) = loaded {
+
             // Update linter with new config
             if let Ok(mut linter) = self.linter.write() {
                 linter.set_config(config);
@@ -71,20 +183,202 @@ impl FleetLspBackend {
         }
     }
 
+    /// Rebuild the hover/completion docs from the schema generated for the
+    /// workspace's Fleet version (or the embedded snapshot) plus the local
+    /// `schema-defs/` enhancements. On error the previous docs stay in use.
+    fn reload_field_docs(&self) {
+        let source = self.docs_source.read().map(|s| s.clone()).unwrap_or_default();
+        let message = match build_field_docs(&source) {
+            Ok(docs) => {
+                schema::set_field_docs(docs);
+                let mut from: Vec<String> = source.schema_path.iter().chain(&source.defs_dir)
+                    .map(|p| p.display().to_string())
+                    .collect();
+                if source.schema_path.is_none() {
+                    from.insert(0, "bundled schema".to_string());
+                }
+                (MessageType::INFO, format!("Loaded field docs from {}", from.join(" + ")))
+            }
+            Err(e) => (MessageType::WARNING, format!("Keeping previous field docs: {:#}", e)),
+        };
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            client.log_message(message.0, message.1).await;
+        });
+    }
+
     /// Handle document change - lint and publish diagnostics.
     async fn on_change(&self, uri: String, content: String) {
         // Cache the document content
-        self.documents.insert(uri.clone(), content.clone());
+        self.store.open(uri.clone(), content.clone());
+
+        self.publish(&uri, &content).await;
+
+        // Refresh the "referenced file has errors" diagnostics of open
+        // documents that include this one
+        let Some(path) = Url::parse(&uri).ok().and_then(|u| u.to_file_path().ok()) else {
+            return;
+        };
+        let dependents: Vec<(String, String)> = self
+            .store
+            .open_documents()
+            .filter(|doc| doc.key() != &uri)
+            .filter(|doc| {
+                Url::parse(doc.key())
+                    .ok()
+                    .and_then(|u| u.to_file_path().ok())
+                    .is_some_and(|doc_path| references_file(doc.value(), &doc_path, &path))
+            })
+            .map(|doc| (doc.key().clone(), doc.value().clone()))
+            .collect();
+        for (dependent, dependent_content) in dependents {
+            self.publish(&dependent, &dependent_content).await;
+        }
+    }
 
-        // Lint the document
-        let diagnostics = self.lint_document(&uri, &content);
+    /// Lint a document and publish its diagnostics, followed by a
+    /// `fleet/fileSummary` notification if the client asked for them.
+    async fn publish(&self, uri: &str, content: &str) {
+        let diagnostics = self.lint_document(uri, content);
 
         // Parse URI for publishing
-        if let Ok(url) = Url::parse(&uri) {
+        let Ok(url) = Url::parse(uri) else {
+            return;
+        };
+        let summary = self.file_summary.load(Ordering::Relaxed).then(|| {
+            let workspace_schema = self.docs_source.read().is_ok_and(|source| source.schema_path.is_some());
+            let configured = self
+                .linter
+                .read()
+                .ok()
+                .and_then(|linter| linter.config().and_then(|config| config.fleet_version.clone()));
+            let schema_version = match configured {
+                Some(version) if workspace_schema => version,
+                _ => crate::linter::bundled::SNAPSHOT_VERSION.to_string(),
+            };
+            FileSummaryParams::new(url.clone(), content, &diagnostics, &schema_version, !workspace_schema)
+        });
+
+        self.client.publish_diagnostics(url, diagnostics, None).await;
+        if let Some(summary) = summary {
+            self.client.send_notification::<FileSummary>(summary).await;
+        }
+    }
+
+    /// Apply a workspace edit on the server's side: refuse it if a document
+    /// changed since the edit was made, create its files on disk, then ask
+    /// the client to apply the text edits. Returns whether it was applied.
+    async fn apply_workspace_edit(&self, edit: WorkspaceEdit) -> bool {
+        let stale = stale_documents(&edit, |uri| self.versions.get(uri.as_str()).map(|v| *v));
+        if !stale.is_empty() {
+            let files: Vec<String> = stale.iter().map(|uri| uri.to_string()).collect();
             self.client
-                .publish_diagnostics(url, diagnostics, None)
+                .show_message(
+                    MessageType::WARNING,
+                    format!("Not applied: {} changed since the action was offered. Try again.", files.join(", ")),
+                )
                 .await;
+            return false;
+        }
+
+        let (creations, edit) = split_file_creations(edit);
+        for create in &creations {
+            if let Err(e) = create_file(create) {
+                self.client
+                    .show_message(MessageType::ERROR, format!("Could not create {}: {}", create.uri, e))
+                    .await;
+                return false;
+            }
+        }
+
+        let support = *self.edit_support.read().unwrap();
+        let edit = if support.document_changes { edit } else { to_changes_map(edit) };
+        if is_empty(&edit) {
+            return true;
+        }
+
+        match self.client.apply_edit(edit).await {
+            Ok(response) if response.applied => true,
+            Ok(response) => {
+                let reason = response.failure_reason.unwrap_or_else(|| "rejected by the editor".to_string());
+                self.client
+                    .show_message(MessageType::ERROR, format!("Could not apply the edit: {}", reason))
+                    .await;
+                false
+            }
+            Err(e) => {
+                self.client
+                    .show_message(MessageType::ERROR, format!("Could not apply the edit: {}", e))
+                    .await;
+                false
+            }
+        }
+    }
+
+    /// Handle `fleet/previewWorkspaceEdit`: the edit as a unified diff
+    /// against open documents, or the files on disk.
+    pub async fn preview_workspace_edit(&self, params: PreviewParams) -> Result<PreviewResult> {
+        Ok(preview(&params.edit, |uri| self.store.content(uri)))
+    }
+
+    /// Handle `fleet/fixAll`: apply every safe fix in a file, or in open
+    /// documents and the YAML files of the workspace.
+    pub async fn fix_all(&self, params: FixAllParams) -> Result<FixAllResult> {
+        let targets: Vec<Url> = match params.uri {
+            Some(uri) => vec![uri],
+            None => {
+                let root = self.workspace_root.read().unwrap().clone();
+                let linter = self.linter.read().unwrap();
+                let options = WalkOptions::from_config(linter.config());
+                let mut targets: Vec<Url> = root
+                    .and_then(|root| find_yaml_files(&root, &options).ok())
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|path| Url::from_file_path(path).ok())
+                    .collect();
+                for doc in self.store.open_documents() {
+                    if let Ok(uri) = Url::parse(doc.key()) {
+                        if !targets.contains(&uri) {
+                            targets.push(uri);
+                        }
+                    }
+                }
+                targets
+            }
+        };
+
+        let (changes, fixed) = {
+            let linter = self.linter.read().unwrap();
+            let mut changes = Vec::new();
+            let mut fixed = 0;
+            for uri in targets {
+                let (Some(content), Ok(path)) = (self.store.content(&uri), uri.to_file_path()) else {
+                    continue;
+                };
+                let (edits, count) = fix_edits(&linter, &content, &path);
+                if edits.is_empty() {
+                    continue;
+                }
+                let version = self.versions.get(uri.as_str()).map(|v| *v);
+                changes.push(document_edit(uri, version, edits));
+                fixed += count;
+            }
+            (changes, fixed)
+        };
+
+        let files = changes.len();
+        if files == 0 {
+            return Ok(FixAllResult { applied: true, ..Default::default() });
+        }
+        let edit = WorkspaceEdit {
+            changes: None,
+            document_changes: Some(DocumentChanges::Edits(changes)),
+            change_annotations: None,
+        };
+        if !self.apply_workspace_edit(edit).await {
+            return Ok(FixAllResult::default());
         }
+        Ok(FixAllResult { fixed, files, applied: true })
     }
 
     /// Lint a document and return LSP diagnostics.
@@ -117,52 +411,66 @@ impl FleetLspBackend {
 
                 diags
             }
-            Err(e) => {
-                // Parse error - create a single diagnostic at the start
-                vec![Diagnostic {
-                    range: Range {
-                        start: Position { line: 0, character: 0 },
-                        end: Position { line: 0, character: 0 },
-                    },
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    source: Some("fleet-lsp".to_string()),
-                    message: format!("Failed to parse YAML: {}", e),
-                    ..Default::default()
-                }]
-            }
+            // Parse error - a single diagnostic where parsing stopped
+            Err(e) => vec![engine_error_to_diagnostic(&e, content)],
         };
 
         // Add path reference validation diagnostics
         let workspace_root = file_path_buf.parent();
-        diagnostics.extend(validate_path_references(
-            content,
-            &file_path_buf,
-            workspace_root,
-        ));
+        let mut broken = validate_path_references(content, &file_path_buf, workspace_root);
+        if let Some(index) = self.file_index.read().ok().and_then(|i| i.clone()) {
+            relate_moved_files(&mut broken, &index);
+        }
+        diagnostics.extend(broken);
+
+        // Errors in referenced lib files, at the `path:` line
+        diagnostics.extend(referenced_file_errors(content, &file_path_buf, |path| {
+            self.store
+                .metadata(path, |referenced| file_error_diagnostics(&linter, referenced, path))
+                .map(|metadata| metadata.errors.clone())
+                .unwrap_or_default()
+        }));
 
         diagnostics
     }
 }
 
+/// Field docs for a docs source.
+fn build_field_docs(source: &DocsSource) -> anyhow::Result<schema::FieldDocs> {
+    let base = match &source.schema_path {
+        Some(path) => schema::load_schema(path)?,
+        None => crate::linter::bundled::fleet_schema(),
+    };
+    match &source.defs_dir {
+        Some(dir) => schema::docs_with_enhancements(&base, dir),
+        None => Ok(schema::docs_from_schema(&base)),
+    }
+}
+
 #[tower_lsp::async_trait]
 impl LanguageServer for FleetLspBackend {
     async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        *self.edit_support.write().unwrap() = EditSupport::from_capabilities(&params.capabilities);
+        *self.completion_detail.write().unwrap() =
+            CompletionDetail::from_initialize(params.initialization_options.as_ref(), &params.capabilities);
+        let file_summary = params
+            .initialization_options
+            .as_ref()
+            .and_then(|options| options.get(FILE_SUMMARY_OPTION))
+            .and_then(|enabled| enabled.as_bool())
+            .unwrap_or(false);
+        self.file_summary.store(file_summary, Ordering::Relaxed);
+
         // Store workspace root and load config
         if let Some(root_uri) = params.root_uri {
             if let Ok(path) = root_uri.to_file_path() {
-                if let Ok(mut workspace_root) = self.workspace_root.write() {
-                    *workspace_root = Some(path.clone());
-                }
-                self.load_config(&path);
+                self.set_workspace_root(path);
             }
         } else if let Some(folders) = params.workspace_folders {
             // Use first workspace folder
             if let Some(folder) = folders.first() {
                 if let Ok(path) = folder.uri.to_file_path() {
-                    if let Ok(mut workspace_root) = self.workspace_root.write() {
-                        *workspace_root = Some(path.clone());
-                    }
-                    self.load_config(&path);
+                    self.set_workspace_root(path);
                 }
             }
         }
@@ -174,23 +482,35 @@ impl LanguageServer for FleetLspBackend {
                 )),
                 // Enable hover for documentation tooltips
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
-                // Enable code actions for quick-fixes
-                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                // Quick-fixes, and fixing everything (on save)
+                code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+                    code_action_kinds: Some(vec![
+                        CodeActionKind::QUICKFIX,
+                        CodeActionKind::REFACTOR_EXTRACT,
+                        CodeActionKind::new(FIX_ALL_KIND),
+                        CodeActionKind::new(SORT_ITEMS_KIND),
+                    ]),
+                    ..Default::default()
+                })),
                 // Enable autocompletion
                 completion_provider: Some(CompletionOptions {
-                    trigger_characters: Some(vec![
-                        ":".to_string(),
-                        " ".to_string(),
-                        "-".to_string(),
-                        "/".to_string(),
-                        ".".to_string(),
-                    ]),
+                    trigger_characters: Some(TRIGGER_CHARACTERS.iter().map(|c| c.to_string()).collect()),
+                    // Documentation is attached lazily
+                    resolve_provider: Some(true),
                     ..Default::default()
                 }),
+                // Parameter hints inside SQL function calls
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(SIGNATURE_TRIGGER_CHARACTERS.iter().map(|c| c.to_string()).collect()),
+                    retrigger_characters: None,
+                    work_done_progress_options: Default::default(),
+                }),
                 // Enable document symbols for outline view
                 document_symbol_provider: Some(OneOf::Left(true)),
                 // Enable go-to-definition for path references
                 definition_provider: Some(OneOf::Left(true)),
+                // Enable expand/shrink selection over YAML and SQL structure
+                selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
                 // Enable semantic tokens for syntax highlighting
                 semantic_tokens_provider: Some(
                     SemanticTokensServerCapabilities::SemanticTokensOptions(
@@ -202,6 +522,20 @@ impl LanguageServer for FleetLspBackend {
                         },
                     ),
                 ),
+                // Format Selection lays out the SQL of queries in the range
+                document_range_formatting_provider: Some(OneOf::Left(true)),
+                // Enable "Run query" code lenses (executed with local osqueryi)
+                code_lens_provider: Some(CodeLensOptions {
+                    resolve_provider: Some(false),
+                }),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: vec![
+                        RUN_QUERY_COMMAND.to_string(),
+                        OPEN_FILE_COMMAND.to_string(),
+                        APPLY_EDIT_COMMAND.to_string(),
+                    ],
+                    ..Default::default()
+                }),
                 ..Default::default()
             },
             server_info: Some(ServerInfo {
@@ -212,23 +546,87 @@ impl LanguageServer for FleetLspBackend {
     }
 
     async fn initialized(&self, _params: InitializedParams) {
+        let schema = match self.docs_source.read().ok().and_then(|source| source.schema_path.clone()) {
+            Some(path) => path.display().to_string(),
+            None => format!("bundled Fleet {} schema", crate::linter::bundled::SNAPSHOT_VERSION),
+        };
         self.client
-            .log_message(MessageType::INFO, "Fleet LSP server initialized")
+            .log_message(
+                MessageType::INFO,
+                format!("Fleet LSP server {} initialized ({})", env!("CARGO_PKG_VERSION"), schema),
+            )
             .await;
+
+        // Keep the path completion index in sync with files created or
+        // deleted outside the editor (git checkout, scripts)
+        let mut watchers = vec![FileSystemWatcher {
+            glob_pattern: GlobPattern::String("**/*".to_string()),
+            kind: Some(WatchKind::Create | WatchKind::Delete),
+        }];
+        // Rebuild hover docs when local schema enhancements are edited
+        if let Some(defs_dir) = self.docs_source.read().ok().and_then(|s| s.defs_dir.clone()) {
+            watchers.push(FileSystemWatcher {
+                glob_pattern: GlobPattern::String(format!("{}/*.{{yml,yaml}}", defs_dir.display())),
+                kind: Some(WatchKind::Create | WatchKind::Change | WatchKind::Delete),
+            });
+        }
+        let options = DidChangeWatchedFilesRegistrationOptions { watchers };
+        let registration = Registration {
+            id: "fleet-file-index".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(options).ok(),
+        };
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            self.client
+                .log_message(MessageType::INFO, format!("File watching unavailable, path completions may be stale: {}", e))
+                .await;
+        }
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let structural = params
+            .changes
+            .iter()
+            .any(|change| change.typ == FileChangeType::CREATED || change.typ == FileChangeType::DELETED);
+        if structural {
+            if let Some(index) = self.file_index.read().ok().and_then(|i| i.clone()) {
+                index.invalidate();
+            }
+        }
+        for path in params.changes.iter().filter_map(|change| change.uri.to_file_path().ok()) {
+            self.store.invalidate(&path);
+        }
+
+        let defs_dir = self.docs_source.read().ok().and_then(|s| s.defs_dir.clone());
+        if let Some(defs_dir) = defs_dir {
+            let defs_changed = params
+                .changes
+                .iter()
+                .filter_map(|change| change.uri.to_file_path().ok())
+                .any(|path| path.starts_with(&defs_dir));
+            if defs_changed {
+                self.reload_field_docs();
+            }
+        }
     }
 
     async fn shutdown(&self) -> Result<()> {
+        if let Ok(linter) = self.linter.read() {
+            linter.flush_metrics();
+        }
         Ok(())
     }
 
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         let uri = params.text_document.uri.to_string();
         let content = params.text_document.text;
+        self.versions.insert(uri.clone(), params.text_document.version);
         self.on_change(uri, content).await;
     }
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let uri = params.text_document.uri.to_string();
+        self.versions.insert(uri.clone(), params.text_document.version);
         // We request FULL sync, so there's always exactly one change with full content
         if let Some(change) = params.content_changes.into_iter().next() {
             self.on_change(uri, change.text).await;
@@ -239,7 +637,8 @@ impl LanguageServer for FleetLspBackend {
         let uri = params.text_document.uri.to_string();
 
         // Remove from cache
-        self.documents.remove(&uri);
+        self.store.close(&uri);
+        self.versions.remove(&uri);
 
         // Clear diagnostics
         if let Ok(url) = Url::parse(&uri) {
@@ -248,7 +647,54 @@ impl LanguageServer for FleetLspBackend {
     }
 
     async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
-        let actions = generate_code_actions(&params);
+        let version = self.versions.get(params.text_document.uri.as_str()).map(|v| *v);
+        let support = *self.edit_support.read().unwrap();
+        let only = params.context.only.as_deref();
+        let mut actions: Vec<CodeActionOrCommand> = generate_code_actions(&params, version);
+        actions.retain(|action| match action {
+            CodeActionOrCommand::CodeAction(action) => {
+                action.kind.as_ref().is_none_or(|kind| kind_requested(only, kind))
+            }
+            CodeActionOrCommand::Command(_) => only.is_none(),
+        });
+
+        let uri = &params.text_document.uri;
+        if let Some(content) = self.store.content(uri) {
+            if kind_requested(only, &CodeActionKind::QUICKFIX) {
+                for diagnostic in &params.context.diagnostics {
+                    let duplicates = duplicate_actions(diagnostic, uri, version, &content);
+                    actions.extend(duplicates.into_iter().map(CodeActionOrCommand::CodeAction));
+                }
+            }
+            if kind_requested(only, &CodeActionKind::REFACTOR_EXTRACT) {
+                let line = params.range.start.line as usize;
+                if let Some(action) = extract_to_lib_action(uri, version, &content, line, &params.context.diagnostics) {
+                    actions.push(CodeActionOrCommand::CodeAction(action));
+                }
+            }
+            if kind_requested(only, &CodeActionKind::new(FIX_ALL_KIND)) {
+                if let Ok(path) = uri.to_file_path() {
+                    let linter = self.linter.read().unwrap();
+                    if let Some(action) = fix_all_action(&linter, uri, version, &content, &path) {
+                        actions.push(CodeActionOrCommand::CodeAction(action));
+                    }
+                }
+            }
+            if kind_requested(only, &CodeActionKind::new(SORT_ITEMS_KIND)) {
+                let sort = sort_items_actions(uri, version, &content);
+                actions.extend(sort.into_iter().map(CodeActionOrCommand::CodeAction));
+            }
+        }
+
+        let actions: Vec<CodeActionOrCommand> = actions
+            .into_iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => {
+                    CodeActionOrCommand::CodeAction(adapt_for_client(action, support))
+                }
+                command => command,
+            })
+            .collect();
         if actions.is_empty() {
             Ok(None)
         } else {
@@ -261,8 +707,13 @@ impl LanguageServer for FleetLspBackend {
         let position = params.text_document_position_params.position;
 
         // Get document content from cache
-        if let Some(content) = self.documents.get(&uri) {
-            Ok(hover_at(&content, position))
+        if let Some(content) = self.store.text(&uri) {
+            let file_path = Url::parse(&uri).ok().and_then(|u| u.to_file_path().ok());
+            // Referenced profiles get a payload summary instead of field docs
+            let profile = file_path
+                .as_deref()
+                .and_then(|file_path| profile_hover(&content, position, file_path));
+            Ok(profile.or_else(|| hover_in_file(&content, position, file_path.as_deref())))
         } else {
             Ok(None)
         }
@@ -271,33 +722,48 @@ impl LanguageServer for FleetLspBackend {
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
         let uri = params.text_document_position.text_document.uri.to_string();
         let position = params.text_document_position.position;
+        let started = std::time::Instant::now();
 
         // Get document content from cache
-        if let Some(content) = self.documents.get(&uri) {
+        if let Some(content) = self.store.text(&uri) {
             // Get file path for file path completions
             let file_path = Url::parse(&uri)
                 .ok()
                 .and_then(|u| u.to_file_path().ok());
 
-            // Get workspace root
-            let workspace_root = self.workspace_root.read().ok().and_then(|r| r.clone());
+            // Workspace index, or a one-off index next to the file
+            let index = self.file_index.read().ok().and_then(|i| i.clone()).or_else(|| {
+                file_path.as_deref().and_then(|f| f.parent()).map(|dir| Arc::new(FileIndex::new(dir)))
+            });
 
-            let items = complete_at_with_context(
-                &content,
-                position,
-                file_path.as_deref(),
-                workspace_root.as_deref(),
-            );
+            let trigger = params
+                .context
+                .filter(|context| context.trigger_kind == CompletionTriggerKind::TRIGGER_CHARACTER)
+                .and_then(|context| context.trigger_character);
+            let items = match trigger {
+                Some(trigger) => {
+                    complete_on_trigger(&content, position, &trigger, file_path.as_deref(), index.as_deref())
+                }
+                None => complete_at_with_index(&content, position, file_path.as_deref(), index.as_deref()),
+            };
+            if let Some(metrics) = self.linter.read().ok().and_then(|l| l.metrics().cloned()) {
+                metrics.record_completion(started.elapsed());
+            }
             if items.is_empty() {
                 Ok(None)
             } else {
-                Ok(Some(CompletionResponse::Array(items)))
+                let detail = *self.completion_detail.read().unwrap();
+                Ok(Some(CompletionResponse::Array(items.into_iter().map(|item| detail.apply(item)).collect())))
             }
         } else {
             Ok(None)
         }
     }
 
+    async fn completion_resolve(&self, item: CompletionItem) -> Result<CompletionItem> {
+        Ok(resolve_completion_item_with(item, *self.completion_detail.read().unwrap()))
+    }
+
     async fn document_symbol(
         &self,
         params: DocumentSymbolParams,
@@ -305,7 +771,7 @@ impl LanguageServer for FleetLspBackend {
         let uri = params.text_document.uri.to_string();
 
         // Get document content from cache
-        if let Some(content) = self.documents.get(&uri) {
+        if let Some(content) = self.store.text(&uri) {
             let symbols = document_symbols(&content);
             if symbols.is_empty() {
                 Ok(None)
@@ -325,7 +791,7 @@ impl LanguageServer for FleetLspBackend {
         let position = params.text_document_position_params.position;
 
         // Get document content from cache
-        if let Some(content) = self.documents.get(&uri) {
+        if let Some(content) = self.store.text(&uri) {
             // Get file path for resolution
             let file_path = Url::parse(&uri)
                 .ok()
@@ -347,11 +813,145 @@ impl LanguageServer for FleetLspBackend {
         let uri = params.text_document.uri.to_string();
 
         // Get document content from cache
-        if let Some(content) = self.documents.get(&uri) {
+        if let Some(content) = self.store.text(&uri) {
             let tokens = compute_semantic_tokens(&content);
             Ok(Some(SemanticTokensResult::Tokens(tokens)))
         } else {
             Ok(None)
         }
     }
+
+    async fn selection_range(&self, params: SelectionRangeParams) -> Result<Option<Vec<SelectionRange>>> {
+        let uri = params.text_document.uri.to_string();
+
+        Ok(self
+            .store
+            .text(&uri)
+            .map(|content| selection_ranges(&content, &params.positions)))
+    }
+
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+        Ok(self.store.text(uri.as_str()).and_then(|content| signature_help(&content, position)))
+    }
+
+    async fn range_formatting(&self, params: DocumentRangeFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        let uri = params.text_document.uri;
+        Ok(self.store.text(uri.as_str()).map(|content| range_formatting_edits(&content, params.range)))
+    }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        let uri = params.text_document.uri;
+
+        if let Some(content) = self.store.text(uri.as_str()) {
+            let lenses = query_code_lenses(&content, &uri);
+            Ok(if lenses.is_empty() { None } else { Some(lenses) })
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> Result<Option<serde_json::Value>> {
+        if params.command == APPLY_EDIT_COMMAND {
+            // Arguments: [workspace edit, optional follow-up command]
+            let edit = params.arguments.first().and_then(|v| serde_json::from_value::<WorkspaceEdit>(v.clone()).ok());
+            let Some(edit) = edit else {
+                return Ok(None);
+            };
+            let follow_up = params.arguments.get(1).and_then(|v| serde_json::from_value::<Command>(v.clone()).ok());
+            if self.apply_workspace_edit(edit).await {
+                if let Some(follow_up) = follow_up {
+                    return self
+                        .execute_command(ExecuteCommandParams {
+                            command: follow_up.command,
+                            arguments: follow_up.arguments.unwrap_or_default(),
+                            work_done_progress_params: Default::default(),
+                        })
+                        .await;
+                }
+            }
+            return Ok(None);
+        }
+        if params.command == OPEN_FILE_COMMAND {
+            // Argument: URI of a file created by a code action
+            let uri = params.arguments.first().and_then(|v| v.as_str()).and_then(|s| Url::parse(s).ok());
+            if let Some(uri) = uri {
+                let show = ShowDocumentParams { uri, external: None, take_focus: Some(true), selection: None };
+                if let Err(e) = self.client.show_document(show).await {
+                    self.client
+                        .log_message(MessageType::INFO, format!("Could not open the created file: {}", e))
+                        .await;
+                }
+            }
+            return Ok(None);
+        }
+        if params.command != RUN_QUERY_COMMAND {
+            return Ok(None);
+        }
+
+        // Arguments: [document URI, 0-indexed line of the `query:` field]
+        let uri = params.arguments.first().and_then(|v| v.as_str()).map(String::from);
+        let line = params.arguments.get(1).and_then(|v| v.as_u64());
+        let sql = match (uri, line) {
+            (Some(uri), Some(line)) => self
+                .store
+                .text(&uri)
+                .and_then(|content| extract_query_at_line(&content, line as usize)),
+            _ => None,
+        };
+
+        let Some(sql) = sql else {
+            self.client
+                .show_message(MessageType::WARNING, "No query found at this location")
+                .await;
+            return Ok(None);
+        };
+
+        let Some(osqueryi) = find_osqueryi() else {
+            self.client
+                .show_message(
+                    MessageType::WARNING,
+                    "osqueryi not found. Install osquery or set OSQUERYI to its path.",
+                )
+                .await;
+            return Ok(None);
+        };
+
+        let result = tokio::task::spawn_blocking(move || run_query(&osqueryi, &sql))
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+
+        match result {
+            Ok(rows) => {
+                self.client
+                    .show_message(
+                        MessageType::INFO,
+                        format!("[host-specific] Query returned {} row(s) on this host", rows.len()),
+                    )
+                    .await;
+                if !rows.is_empty() {
+                    self.client
+                        .log_message(
+                            MessageType::INFO,
+                            format!("[host-specific] Sample rows:\n{}", format_sample(&rows)),
+                        )
+                        .await;
+                }
+            }
+            Err(e) => {
+                self.client
+                    .show_message(
+                        MessageType::ERROR,
+                        format!("[host-specific] Query failed in osqueryi: {}", e),
+                    )
+                    .await;
+            }
+        }
+
+        Ok(None)
+    }
 }
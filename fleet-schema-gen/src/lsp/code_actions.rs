@@ -1,20 +1,38 @@
 //! Code action generation for quick-fixes.
 //!
 //! This module generates LSP code actions based on diagnostics that have
-//! suggestion data attached to them.
+//! suggestion data attached to them, "Create file" actions for `path:`
+//! references to files that don't exist, merging or deleting duplicate
+//! items, extracting inline items to lib files, and source actions sorting
+//! policies, queries and labels by name.
 
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use tower_lsp::lsp_types::{
-    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, Diagnostic, TextEdit, Url,
-    WorkspaceEdit,
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, Command, CreateFile,
+    CreateFileOptions, Diagnostic, DocumentChangeOperation, DocumentChanges, OneOf,
+    OptionalVersionedTextDocumentIdentifier, Position, Range, ResourceOp, TextDocumentEdit,
+    TextEdit, Url, WorkspaceEdit,
 };
 
+use super::completion::PathContextType;
+use crate::linter::docs::slugify;
+use crate::linter::duplicates::{find_duplicate, merge_plan};
+use crate::linter::file_role::FileRole;
+use crate::linter::sort::{list_items, unsorted_sections};
+
+/// Command that opens a file in the editor (argument: file URI).
+pub const OPEN_FILE_COMMAND: &str = "fleet.openFile";
+
+/// Code action kind of the "Sort ... alphabetically by name" actions.
+pub const SORT_ITEMS_KIND: &str = "source.sortItems.fleet";
+
 /// Generate code actions for diagnostics in the given range.
 ///
 /// This function looks at all diagnostics from fleet-lsp that have suggestion
-/// data attached, and generates quick-fix code actions for them.
-pub fn generate_code_actions(params: &CodeActionParams) -> Vec<CodeActionOrCommand> {
+/// data attached, and generates quick-fix code actions for them. Edits to the
+/// document are tagged with `version` so stale actions are rejected.
+pub fn generate_code_actions(params: &CodeActionParams, version: Option<i32>) -> Vec<CodeActionOrCommand> {
     let mut actions = Vec::new();
 
     for diagnostic in &params.context.diagnostics {
@@ -24,7 +42,11 @@ pub fn generate_code_actions(params: &CodeActionParams) -> Vec<CodeActionOrComma
         }
 
         // Check if diagnostic has suggestion data
-        if let Some(action) = create_fix_from_diagnostic(diagnostic, &params.text_document.uri) {
+        if let Some(action) = create_fix_from_diagnostic(diagnostic, &params.text_document.uri, version) {
+            actions.push(CodeActionOrCommand::CodeAction(action));
+        }
+
+        if let Some(action) = create_missing_file_action(diagnostic) {
             actions.push(CodeActionOrCommand::CodeAction(action));
         }
     }
@@ -33,7 +55,7 @@ pub fn generate_code_actions(params: &CodeActionParams) -> Vec<CodeActionOrComma
 }
 
 /// Create a quick-fix code action from a diagnostic with suggestion data.
-fn create_fix_from_diagnostic(diagnostic: &Diagnostic, uri: &Url) -> Option<CodeAction> {
+fn create_fix_from_diagnostic(diagnostic: &Diagnostic, uri: &Url, version: Option<i32>) -> Option<CodeAction> {
     // Get suggestion from diagnostic data
     let data = diagnostic.data.as_ref()?;
     let suggestion = data.get("suggestion")?.as_str()?;
@@ -44,13 +66,13 @@ fn create_fix_from_diagnostic(diagnostic: &Diagnostic, uri: &Url) -> Option<Code
         new_text: suggestion.to_string(),
     };
 
-    // Build workspace edit with changes to this document
-    let mut changes = HashMap::new();
-    changes.insert(uri.clone(), vec![edit]);
-
+    // Build workspace edit with changes to this version of the document
     let workspace_edit = WorkspaceEdit {
-        changes: Some(changes),
-        document_changes: None,
+        changes: None,
+        document_changes: Some(DocumentChanges::Edits(vec![TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri: uri.clone(), version },
+            edits: vec![OneOf::Left(edit)],
+        }])),
         change_annotations: None,
     };
 
@@ -67,6 +89,286 @@ fn create_fix_from_diagnostic(diagnostic: &Diagnostic, uri: &Url) -> Option<Code
     })
 }
 
+/// "Merge" and "Delete" for an item reported as a duplicate name.
+pub fn duplicate_actions(diagnostic: &Diagnostic, uri: &Url, version: Option<i32>, content: &str) -> Vec<CodeAction> {
+    if diagnostic.source.as_deref() != Some("fleet-lsp") || !diagnostic.message.starts_with("Duplicate ") {
+        return Vec::new();
+    }
+    let Some((first, duplicate)) = find_duplicate(content, diagnostic.range.start.line as usize) else {
+        return Vec::new();
+    };
+    let name = duplicate.name.clone().unwrap_or_default();
+    let line_range = |start: usize, end: usize| Range {
+        start: Position { line: start as u32, character: 0 },
+        end: Position { line: end as u32, character: 0 },
+    };
+    let delete = TextEdit { range: line_range(duplicate.start_line, duplicate.end_line), new_text: String::new() };
+    let action = |title: String, edits: Vec<TextEdit>, is_preferred: bool| CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: None,
+            document_changes: Some(DocumentChanges::Edits(vec![TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri: uri.clone(), version },
+                edits: edits.into_iter().map(OneOf::Left).collect(),
+            }])),
+            change_annotations: None,
+        }),
+        is_preferred: Some(is_preferred),
+        ..Default::default()
+    };
+
+    let plan = merge_plan(content, first, duplicate);
+    // Nothing would be lost when the duplicate is an exact copy
+    let exact_copy = plan.insert_text.is_empty() && plan.conflicts.is_empty();
+    let mut actions = Vec::new();
+    if !exact_copy {
+        let mut title = format!("Merge duplicate '{}' into the first definition", name);
+        if !plan.conflicts.is_empty() {
+            title.push_str(&format!(" (keeps the first {})", plan.conflicts.join(", ")));
+        }
+        let mut edits = Vec::new();
+        if !plan.insert_text.is_empty() {
+            let at = line_range(plan.insert_line, plan.insert_line);
+            edits.push(TextEdit { range: at, new_text: plan.insert_text });
+        }
+        edits.push(delete.clone());
+        actions.push(action(title, edits, false));
+    }
+    actions.push(action(format!("Delete duplicate '{}'", name), vec![delete], exact_copy));
+    actions
+}
+
+/// "Extract '<name>' to lib/<kind>/<name>.yml" for the inline policy, query
+/// or label of a team file at `line`: the item moves to a new lib file and
+/// a `- path:` reference takes its place. `diagnostics` about the item
+/// (prefer-path-references) are marked as resolved by it.
+pub fn extract_to_lib_action(
+    uri: &Url,
+    version: Option<i32>,
+    content: &str,
+    line: usize,
+    diagnostics: &[Diagnostic],
+) -> Option<CodeAction> {
+    let path = uri.to_file_path().ok()?;
+    if FileRole::detect(&path).is_some() {
+        return None;
+    }
+    let item = list_items(content)
+        .into_iter()
+        .find(|item| item.section.is_some() && (item.start_line..item.end_line).contains(&line))?;
+    let (section, name) = (item.section.as_deref()?, item.name.as_deref()?);
+    if item.path.is_some() {
+        return None;
+    }
+
+    // Team files live in teams/, next to lib/; default.yml next to lib/
+    let base = path.parent()?;
+    let in_teams = base.file_name().is_some_and(|dir| dir == "teams");
+    let root = if in_teams { base.parent()? } else { base };
+    let slug = slugify(name);
+    let file_name = (1..)
+        .map(|n| if n == 1 { format!("{}.yml", slug) } else { format!("{}-{}.yml", slug, n) })
+        .find(|file_name| !root.join("lib").join(section).join(file_name).exists())?;
+    let target = root.join("lib").join(section).join(&file_name);
+    let reference = format!("{}lib/{}/{}", if in_teams { "../" } else { "" }, section, file_name);
+    let target_uri = Url::from_file_path(&target).ok()?;
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut extracted = String::new();
+    for line in &lines[item.start_line..item.end_line] {
+        let dedent = line.len() - line.trim_start().len();
+        extracted.push_str(&line[dedent.min(item.indent)..]);
+        extracted.push('\n');
+    }
+    let line_range = |start: usize, end: usize| Range {
+        start: Position { line: start as u32, character: 0 },
+        end: Position { line: end as u32, character: 0 },
+    };
+
+    let operations = vec![
+        DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+            uri: target_uri.clone(),
+            options: Some(CreateFileOptions { overwrite: Some(false), ignore_if_exists: Some(false) }),
+            annotation_id: None,
+        })),
+        DocumentChangeOperation::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri: target_uri, version: None },
+            edits: vec![OneOf::Left(TextEdit { range: line_range(0, 0), new_text: extracted })],
+        }),
+        DocumentChangeOperation::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri: uri.clone(), version },
+            edits: vec![OneOf::Left(TextEdit {
+                range: line_range(item.start_line, item.end_line),
+                new_text: format!("{}- path: {}\n", " ".repeat(item.indent), reference),
+            })],
+        }),
+    ];
+    let resolved: Vec<Diagnostic> = diagnostics
+        .iter()
+        .filter(|d| d.message.contains("is defined inline") && (item.start_line..item.end_line).contains(&(d.range.start.line as usize)))
+        .cloned()
+        .collect();
+
+    Some(CodeAction {
+        title: format!("Extract '{}' to lib/{}/{}", name, section, file_name),
+        kind: Some(CodeActionKind::REFACTOR_EXTRACT),
+        diagnostics: (!resolved.is_empty()).then_some(resolved),
+        edit: Some(WorkspaceEdit {
+            changes: None,
+            document_changes: Some(DocumentChanges::Operations(operations)),
+            change_annotations: None,
+        }),
+        ..Default::default()
+    })
+}
+
+/// "Sort <list> alphabetically by name" for each list in `content` that
+/// is out of order.
+pub fn sort_items_actions(uri: &Url, version: Option<i32>, content: &str) -> Vec<CodeAction> {
+    unsorted_sections(content)
+        .into_iter()
+        .map(|section| {
+            let edit = TextEdit {
+                range: Range {
+                    start: Position { line: section.start_line as u32, character: 0 },
+                    end: Position { line: section.end_line as u32, character: 0 },
+                },
+                new_text: section.text,
+            };
+            CodeAction {
+                title: format!("Sort {} alphabetically by name", section.section.as_deref().unwrap_or("items")),
+                kind: Some(CodeActionKind::new(SORT_ITEMS_KIND)),
+                edit: Some(WorkspaceEdit {
+                    changes: None,
+                    document_changes: Some(DocumentChanges::Edits(vec![TextDocumentEdit {
+                        text_document: OptionalVersionedTextDocumentIdentifier { uri: uri.clone(), version },
+                        edits: vec![OneOf::Left(edit)],
+                    }])),
+                    change_annotations: None,
+                }),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Create the file a broken `path:` reference points to, from a template
+/// for the kind of file the reference expects, and open it.
+fn create_missing_file_action(diagnostic: &Diagnostic) -> Option<CodeAction> {
+    let data = diagnostic.data.as_ref()?;
+    let missing = PathBuf::from(data.get("missing_path")?.as_str()?);
+    let written = data.get("path")?.as_str()?;
+    let context_type: PathContextType = data
+        .get("context")
+        .and_then(|c| serde_json::from_value(c.clone()).ok())
+        .unwrap_or(PathContextType::Generic);
+    let uri = Url::from_file_path(&missing).ok()?;
+
+    let insert = TextEdit {
+        range: Range {
+            start: Position { line: 0, character: 0 },
+            end: Position { line: 0, character: 0 },
+        },
+        new_text: file_template(context_type, &missing),
+    };
+    let operations = vec![
+        DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+            uri: uri.clone(),
+            options: Some(CreateFileOptions {
+                overwrite: Some(false),
+                ignore_if_exists: Some(true),
+            }),
+            annotation_id: None,
+        })),
+        DocumentChangeOperation::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri: uri.clone(), version: None },
+            edits: vec![OneOf::Left(insert)],
+        }),
+    ];
+
+    Some(CodeAction {
+        title: format!("Create {} from template", written),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: None,
+            document_changes: Some(DocumentChanges::Operations(operations)),
+            change_annotations: None,
+        }),
+        command: Some(Command {
+            title: "Open file".to_string(),
+            command: OPEN_FILE_COMMAND.to_string(),
+            arguments: Some(vec![serde_json::json!(uri)]),
+        }),
+        is_preferred: Some(false),
+        disabled: None,
+        data: None,
+    })
+}
+
+/// Stub contents for a new file referenced as `context_type`.
+fn file_template(context_type: PathContextType, path: &Path) -> String {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("new");
+    let name = stem.replace(['-', '_'], " ");
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+
+    match (context_type, extension.as_str()) {
+        (PathContextType::Policy, _) => format!(
+            "- name: {name}\n  query: SELECT 1 FROM osquery_info;\n  platform: darwin\n  description: \"\"\n  resolution: \"\"\n",
+        ),
+        (PathContextType::Query, _) => format!(
+            "- name: {name}\n  query: SELECT * FROM osquery_info;\n  interval: 3600\n  platform: \"\"\n  description: \"\"\n",
+        ),
+        (PathContextType::Label, _) => format!(
+            "- name: {name}\n  query: SELECT 1 FROM osquery_info;\n  label_membership_type: dynamic\n  description: \"\"\n",
+        ),
+        (PathContextType::SoftwarePackage, _) => format!(
+            "url: https://example.com/{stem}.pkg\n# install_script:\n#   path: ../scripts/install-{stem}.sh\n# self_service: false\n",
+        ),
+        (_, "ps1") => format!("# {name}\n$ErrorActionPreference = \"Stop\"\n\n"),
+        (PathContextType::Script, _) | (_, "sh") => format!("#!/bin/sh\n# {name}\nset -eu\n\n"),
+        (_, "mobileconfig") => format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>PayloadContent</key>
+    <array/>
+    <key>PayloadDisplayName</key>
+    <string>{name}</string>
+    <key>PayloadIdentifier</key>
+    <string>com.example.{stem}</string>
+    <key>PayloadType</key>
+    <string>Configuration</string>
+    <key>PayloadUUID</key>
+    <string>{uuid}</string>
+    <key>PayloadVersion</key>
+    <integer>1</integer>
+</dict>
+</plist>
+"#,
+            uuid = payload_uuid(path),
+        ),
+        (_, "xml") => format!(
+            "<!-- {name} -->\n<Replace>\n  <Item>\n    <Target><LocURI>./Device/Vendor/MSFT/Policy/Config/</LocURI></Target>\n    <Meta><Format xmlns=\"syncml:metinf\">int</Format></Meta>\n    <Data></Data>\n  </Item>\n</Replace>\n",
+        ),
+        _ => String::new(),
+    }
+}
+
+/// A stable UUID-formatted identifier for a new profile, derived from its path.
+fn payload_uuid(path: &Path) -> String {
+    use sha2::{Digest, Sha256};
+    let hex: String = Sha256::digest(path.to_string_lossy().as_bytes())
+        .iter()
+        .take(16)
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
 /// Truncate a suggestion string for display in the action title.
 fn truncate_suggestion(s: &str, max_len: usize) -> String {
     // Take only the first line for display
@@ -123,13 +425,150 @@ mod tests {
         };
 
         let uri = Url::parse("file:///test.yml").unwrap();
-        let action = create_fix_from_diagnostic(&diagnostic, &uri);
+        let action = create_fix_from_diagnostic(&diagnostic, &uri, Some(4));
 
         assert!(action.is_some());
         let action = action.unwrap();
         assert_eq!(action.title, "Replace with 'darwin'");
         assert_eq!(action.kind, Some(CodeActionKind::QUICKFIX));
         assert_eq!(action.is_preferred, Some(true));
+        let Some(DocumentChanges::Edits(edits)) = action.edit.unwrap().document_changes else {
+            panic!("expected document edits");
+        };
+        assert_eq!(edits[0].text_document.version, Some(4));
+    }
+
+    #[test]
+    fn test_create_missing_file_action() {
+        let diagnostic = Diagnostic {
+            source: Some("fleet-lsp".to_string()),
+            message: "Referenced file not found: ../lib/policies/foo.yml".to_string(),
+            data: Some(serde_json::json!({
+                "missing_path": "/repo/teams/../lib/policies/foo.yml",
+                "path": "../lib/policies/foo.yml",
+                "context": "policy",
+            })),
+            ..Default::default()
+        };
+
+        let action = create_missing_file_action(&diagnostic).unwrap();
+        assert_eq!(action.title, "Create ../lib/policies/foo.yml from template");
+        assert_eq!(action.command.unwrap().command, OPEN_FILE_COMMAND);
+
+        let Some(DocumentChanges::Operations(operations)) = action.edit.unwrap().document_changes else {
+            panic!("expected resource operations");
+        };
+        assert!(matches!(operations[0], DocumentChangeOperation::Op(ResourceOp::Create(_))));
+        let DocumentChangeOperation::Edit(edit) = &operations[1] else {
+            panic!("expected a text edit");
+        };
+        let OneOf::Left(insert) = &edit.edits[0] else {
+            panic!("expected a plain edit");
+        };
+        assert!(insert.new_text.starts_with("- name: foo\n  query:"));
+
+        // Suggestion-only diagnostics don't get a create action
+        let diagnostic = Diagnostic { data: Some(serde_json::json!({"suggestion": "darwin"})), ..diagnostic };
+        assert!(create_missing_file_action(&diagnostic).is_none());
+    }
+
+    #[test]
+    fn test_duplicate_actions() {
+        let uri = Url::parse("file:///repo/default.yml").unwrap();
+        let content = "policies:\n  - name: A\n    query: SELECT 1;\n  - name: A\n    query: SELECT 2;\n    critical: true\n";
+        let diagnostic = Diagnostic {
+            range: Range::new(Position::new(3, 10), Position::new(3, 11)),
+            source: Some("fleet-lsp".to_string()),
+            message: "Duplicate policy name: 'A'".to_string(),
+            ..Default::default()
+        };
+        let actions = duplicate_actions(&diagnostic, &uri, Some(1), content);
+        let titles: Vec<&str> = actions.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, vec!["Merge duplicate 'A' into the first definition (keeps the first query)", "Delete duplicate 'A'"]);
+
+        let Some(DocumentChanges::Edits(edits)) = actions[0].edit.clone().unwrap().document_changes else {
+            panic!("expected document edits");
+        };
+        let edits: Vec<_> = edits[0].edits.iter().map(|edit| match edit {
+            OneOf::Left(edit) => (edit.range.start.line, edit.range.end.line, edit.new_text.as_str()),
+            OneOf::Right(_) => panic!("unexpected annotated edit"),
+        }).collect();
+        assert_eq!(edits, vec![(3, 3, "    critical: true\n"), (3, 6, "")]);
+
+        // An exact copy can only be deleted
+        let content = "policies:\n  - name: A\n    query: SELECT 1;\n  - name: A\n    query: SELECT 1;\n";
+        let actions = duplicate_actions(&diagnostic, &uri, None, content);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].is_preferred, Some(true));
+
+        let other = Diagnostic { message: "Invalid platform".to_string(), ..diagnostic };
+        assert!(duplicate_actions(&other, &uri, None, content).is_empty());
+    }
+
+    #[test]
+    fn test_extract_to_lib_action() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lib/policies")).unwrap();
+        std::fs::write(dir.path().join("lib/policies/firewall.yml"), "").unwrap();
+        let uri = Url::from_file_path(dir.path().join("teams/workstations.yml")).unwrap();
+        let content = "name: Workstations\npolicies:\n  - path: ../lib/policies/disk.yml\n  # macOS only\n  - name: Firewall\n    query: SELECT 1 FROM alf;\n";
+
+        let action = extract_to_lib_action(&uri, Some(3), content, 5, &[]).unwrap();
+        // firewall.yml is taken
+        assert_eq!(action.title, "Extract 'Firewall' to lib/policies/firewall-2.yml");
+        assert_eq!(action.kind, Some(CodeActionKind::REFACTOR_EXTRACT));
+        let Some(DocumentChanges::Operations(operations)) = action.edit.unwrap().document_changes else {
+            panic!("expected resource operations");
+        };
+        let edits: Vec<(String, String)> = operations[1..]
+            .iter()
+            .map(|op| match op {
+                DocumentChangeOperation::Edit(edit) => match &edit.edits[0] {
+                    OneOf::Left(text) => (edit.text_document.uri.path().rsplit('/').next().unwrap().to_string(), text.new_text.clone()),
+                    OneOf::Right(_) => panic!("unexpected annotated edit"),
+                },
+                DocumentChangeOperation::Op(_) => panic!("unexpected operation"),
+            })
+            .collect();
+        assert_eq!(edits[0], ("firewall-2.yml".to_string(), "# macOS only\n- name: Firewall\n  query: SELECT 1 FROM alf;\n".to_string()));
+        assert_eq!(edits[1], ("workstations.yml".to_string(), "  - path: ../lib/policies/firewall-2.yml\n".to_string()));
+
+        // Path references and lib files have nothing to extract
+        assert!(extract_to_lib_action(&uri, None, content, 2, &[]).is_none());
+        let lib = Url::from_file_path(dir.path().join("lib/policies/all.yml")).unwrap();
+        assert!(extract_to_lib_action(&lib, None, "- name: A\n", 0, &[]).is_none());
+    }
+
+    #[test]
+    fn test_sort_items_actions() {
+        let uri = Url::parse("file:///repo/default.yml").unwrap();
+        let content = "policies:\n  - name: B\n  - name: A\nqueries:\n  - name: A\n";
+        let actions = sort_items_actions(&uri, Some(2), content);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].title, "Sort policies alphabetically by name");
+
+        let Some(DocumentChanges::Edits(edits)) = actions[0].edit.clone().unwrap().document_changes else {
+            panic!("expected document edits");
+        };
+        let OneOf::Left(edit) = &edits[0].edits[0] else {
+            panic!("expected a plain edit");
+        };
+        assert_eq!((edit.range.start.line, edit.range.end.line), (1, 3));
+        assert_eq!(edit.new_text, "  - name: A\n  - name: B\n");
+
+        assert!(sort_items_actions(&uri, None, "- name: A\n- name: B\n").is_empty());
+    }
+
+    #[test]
+    fn test_file_template() {
+        let script = file_template(PathContextType::Script, Path::new("lib/install-app.ps1"));
+        assert!(script.starts_with("# install app\n"));
+        let script = file_template(PathContextType::Script, Path::new("lib/install.sh"));
+        assert!(script.starts_with("#!/bin/sh\n"));
+        let profile = file_template(PathContextType::MacOSProfile, Path::new("lib/wifi.mobileconfig"));
+        assert!(profile.contains("<string>com.example.wifi</string>"));
+        assert!(file_template(PathContextType::WindowsProfile, Path::new("lib/a.xml")).contains("<Replace>"));
+        assert_eq!(file_template(PathContextType::Generic, Path::new("lib/a.txt")), "");
     }
 
     #[test]
@@ -156,7 +595,7 @@ mod tests {
         };
 
         let uri = Url::parse("file:///test.yml").unwrap();
-        let action = create_fix_from_diagnostic(&diagnostic, &uri);
+        let action = create_fix_from_diagnostic(&diagnostic, &uri, None);
 
         assert!(action.is_none());
     }
@@ -0,0 +1,119 @@
+//! Code lenses for Fleet GitOps YAML files.
+//!
+//! Adds a "Run query" lens above each `query:` field that executes the SQL
+//! with the local `osqueryi` via the `fleet.runQuery` command.
+
+use serde_json::json;
+use tower_lsp::lsp_types::{CodeLens, Command, Position, Range, Url};
+
+/// Command executed by the "Run query" lens.
+pub const RUN_QUERY_COMMAND: &str = "fleet.runQuery";
+
+/// Create a "Run query" lens for every `query:` field in the document.
+pub fn query_code_lenses(source: &str, uri: &Url) -> Vec<CodeLens> {
+    source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            line.trim().trim_start_matches('-').trim().starts_with("query:")
+        })
+        .map(|(idx, line)| {
+            let indent = (line.len() - line.trim_start().len()) as u32;
+            let position = Position { line: idx as u32, character: indent };
+
+            CodeLens {
+                range: Range { start: position, end: position },
+                command: Some(Command {
+                    title: "▶ Run query (local osqueryi)".to_string(),
+                    command: RUN_QUERY_COMMAND.to_string(),
+                    arguments: Some(vec![json!(uri.to_string()), json!(idx)]),
+                }),
+                data: None,
+            }
+        })
+        .collect()
+}
+
+/// Extract the SQL of the `query:` field on a given line (0-indexed).
+///
+/// Handles inline values (quoted or not) and block scalars (`|`, `>`).
+pub fn extract_query_at_line(source: &str, line: usize) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let query_line = lines.get(line)?;
+
+    let field = query_line.trim().trim_start_matches('-').trim();
+    let value = field.strip_prefix("query:")?.trim();
+
+    let is_block = value.starts_with('|') || value.starts_with('>');
+    if !value.is_empty() && !is_block {
+        let unquoted = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        return Some(unquoted.to_string());
+    }
+
+    // Block scalar: every following line indented deeper than the key
+    // (the key column accounts for a leading `- `)
+    let key_indent = query_line.len()
+        - query_line.trim_start().trim_start_matches('-').trim_start().len();
+
+    let body: Vec<&str> = lines[line + 1..]
+        .iter()
+        .take_while(|l| l.trim().is_empty() || l.len() - l.trim_start().len() > key_indent)
+        .map(|l| l.trim())
+        .collect();
+
+    let sql = body.join("\n").trim().to_string();
+    if sql.is_empty() {
+        None
+    } else {
+        Some(sql)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_code_lenses() {
+        let source = r#"policies:
+  - name: Firewall
+    query: SELECT 1 FROM alf WHERE global_state >= 1;
+  - name: Block
+    query: |
+      SELECT 1 FROM filevault_status
+      WHERE status = 'FileVault is On.';
+"#;
+        let uri = Url::parse("file:///repo/default.yml").unwrap();
+        let lenses = query_code_lenses(source, &uri);
+
+        assert_eq!(lenses.len(), 2);
+        assert_eq!(lenses[0].range.start.line, 2);
+        assert_eq!(lenses[1].range.start.line, 4);
+        assert_eq!(lenses[0].command.as_ref().unwrap().command, RUN_QUERY_COMMAND);
+    }
+
+    #[test]
+    fn test_extract_inline_query() {
+        let source = "- name: Test\n  query: \"SELECT 1 FROM alf;\"\n";
+        assert_eq!(extract_query_at_line(source, 1), Some("SELECT 1 FROM alf;".to_string()));
+        assert_eq!(extract_query_at_line(source, 0), None);
+    }
+
+    #[test]
+    fn test_extract_block_query() {
+        let source = r#"policies:
+  - query: >-
+      SELECT 1 FROM filevault_status
+      WHERE status = 'FileVault is On.';
+    name: FileVault
+"#;
+        assert_eq!(
+            extract_query_at_line(source, 1),
+            Some("SELECT 1 FROM filevault_status\nWHERE status = 'FileVault is On.';".to_string())
+        );
+    }
+}
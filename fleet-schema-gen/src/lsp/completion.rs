@@ -2,14 +2,21 @@
 //!
 //! Provides context-aware autocompletion for field names, values, and osquery tables.
 
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use tower_lsp::lsp_types::{
-    CompletionItem, CompletionItemKind, Documentation, InsertTextFormat,
-    MarkupContent, MarkupKind, Position,
+    ClientCapabilities, Command, CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, InsertTextFormat,
+    MarkupContent, MarkupKind, Position, Range, TextEdit,
 };
 
+use super::hover::osquery_table_doc;
 use super::schema::{get_field_doc, LOGGING_DOCS, PLATFORM_DOCS};
+use super::position::{byte_offset_to_utf16, char_boundary};
+use crate::linter::file_role::FileRole;
+use crate::linter::fleet_vars::{variables_for, VarContext};
 use crate::linter::osquery::OSQUERY_TABLES;
+use super::workspace::{normalize_path, FileIndex};
 
 /// Context types for completion.
 #[derive(Debug, Clone, PartialEq)]
@@ -52,13 +59,16 @@ enum CompletionContext {
     PathValue { context_type: PathContextType },
     /// Inside an SQL query (for osquery tables)
     SqlContext { platform: Option<String> },
+    /// Typing a `$FLEET_VAR_` reference; `start` is the column of the name
+    FleetVariable { start: usize, context: Option<VarContext> },
     /// Unknown context
     Unknown,
 }
 
 /// Type of path being completed, determines file filtering.
-#[derive(Debug, Clone, PartialEq)]
-enum PathContextType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathContextType {
     /// Software package definitions (*.yml)
     SoftwarePackage,
     /// Scripts (*.sh, *.ps1)
@@ -77,6 +87,20 @@ enum PathContextType {
     Generic,
 }
 
+impl PathContextType {
+    /// Every context type.
+    pub const ALL: [PathContextType; 8] = [
+        PathContextType::SoftwarePackage,
+        PathContextType::Script,
+        PathContextType::MacOSProfile,
+        PathContextType::WindowsProfile,
+        PathContextType::Policy,
+        PathContextType::Query,
+        PathContextType::Label,
+        PathContextType::Generic,
+    ];
+}
+
 /// Provide completion items at a position in a Fleet YAML document.
 /// For file path completions, use `complete_at_with_context` instead.
 pub fn complete_at(source: &str, position: Position) -> Vec<CompletionItem> {
@@ -84,21 +108,125 @@ pub fn complete_at(source: &str, position: Position) -> Vec<CompletionItem> {
 }
 
 /// Provide completion items with workspace context for file path completions.
+///
+/// Path candidates are scanned on every call; the LSP backend keeps a
+/// [`FileIndex`] and uses [`complete_at_with_index`] instead.
 pub fn complete_at_with_context(
     source: &str,
     position: Position,
     current_file: Option<&Path>,
     workspace_root: Option<&Path>,
 ) -> Vec<CompletionItem> {
-    let line_idx = position.line as usize;
-    let col_idx = position.character as usize;
+    let root = workspace_root.or_else(|| current_file.and_then(|f| f.parent()));
+    let index = root.map(FileIndex::new);
+    complete_at_with_index(source, position, current_file, index.as_ref())
+}
 
+/// Provide completion items, serving path candidates from a file index.
+pub fn complete_at_with_index(
+    source: &str,
+    position: Position,
+    current_file: Option<&Path>,
+    index: Option<&FileIndex>,
+) -> Vec<CompletionItem> {
+    let line_idx = position.line as usize;
     // Get the line content (empty string if no line at that position)
     let line = source.lines().nth(line_idx).unwrap_or("");
+    let col_idx = char_boundary(line, position.character as usize);
 
     // Determine the context
-    let context = determine_completion_context(source, line_idx, line, col_idx);
+    let mut context = determine_completion_context(source, line_idx, line, col_idx);
+
+    // Lib files have no top-level key naming their items; outside any key,
+    // the file's name and directory say what to complete (empty new files too)
+    let role = current_file.and_then(FileRole::detect);
+    if let Some(role) = role {
+        if matches!(context, CompletionContext::TopLevel | CompletionContext::Unknown)
+            && find_parent_context(source, line_idx).is_none()
+        {
+            context = role_completion_context(role);
+        }
+    }
+
+    let mut items = complete_in_context(context, line_idx, line, col_idx, current_file, index);
+    if let Some(key) = KeyToken::at(line, col_idx) {
+        for item in &mut items {
+            key.replace_with(item, line_idx as u32, line);
+        }
+    }
+    // Right after a colon, values need a space before them
+    if line.get(..col_idx).unwrap_or(line).ends_with(':') {
+        let cursor = Position { line: line_idx as u32, character: byte_offset_to_utf16(line, col_idx) };
+        for item in items.iter_mut().filter(|item| item.kind == Some(CompletionItemKind::ENUM_MEMBER)) {
+            if item.text_edit.is_none() {
+                let new_text = format!(" {}", item.label);
+                item.filter_text = Some(new_text.clone());
+                item.text_edit = Some(CompletionTextEdit::Edit(TextEdit { range: Range::new(cursor, cursor), new_text }));
+            }
+        }
+    }
+
+    // A new lib file can be scaffolded in one go
+    if let Some(template) = role.and_then(|role| complete_lib_file_template(role, source, line_idx, line)) {
+        items.insert(0, template);
+
+        // Installed policy packs add their own templates after the built-in one
+        if let (Some(role), Some(file)) = (role, current_file) {
+            for (i, template) in crate::linter::packs::templates_for(file, role.section()).into_iter().enumerate() {
+                let detail = format!("New {} file ({} pack)", role.section().replace('_', " "), template.pack);
+                items.insert(i + 1, template_item(&template.label, detail, &template.snippet, line_idx, line));
+            }
+        }
+    }
+    items
+}
+
+/// Characters that open completion as they are typed.
+pub const TRIGGER_CHARACTERS: &[&str] = &["-", ":", " ", "/", "."];
+
+/// Completion opened by typing `trigger`.
+///
+/// Each trigger character only opens completion where it starts something:
+/// `-` or `- ` a new array item (its fields), `:` or `: ` a key's values,
+/// `/` and `.` a `path:` value. A space in a query or a dash in a name
+/// gets nothing, so the list doesn't pop up while typing.
+pub fn complete_on_trigger(
+    source: &str,
+    position: Position,
+    trigger: &str,
+    current_file: Option<&Path>,
+    index: Option<&FileIndex>,
+) -> Vec<CompletionItem> {
+    let line = source.lines().nth(position.line as usize).unwrap_or("");
+    let col_idx = char_boundary(line, position.character as usize);
+    let before = line.get(..col_idx).unwrap_or(line);
+    let item_start = before.trim() == "-";
+    let after_key = before.trim_end().ends_with(':') && !before.trim_end().trim_end_matches(':').contains(": ");
+    let in_path = get_key_at_cursor(line, col_idx).is_some_and(|key| key == "path");
+
+    let values_only = match trigger {
+        "-" | " " if item_start => false,
+        ":" | " " if after_key => true,
+        "/" | "." if in_path => false,
+        _ => return Vec::new(),
+    };
+    let items = complete_at_with_index(source, position, current_file, index);
+    if values_only {
+        items.into_iter().filter(|item| item.kind == Some(CompletionItemKind::ENUM_MEMBER)).collect()
+    } else {
+        items
+    }
+}
 
+/// Completion items for a determined context.
+fn complete_in_context(
+    context: CompletionContext,
+    line_idx: usize,
+    line: &str,
+    col_idx: usize,
+    current_file: Option<&Path>,
+    index: Option<&FileIndex>,
+) -> Vec<CompletionItem> {
     match context {
         CompletionContext::TopLevel => complete_top_level_fields(),
         CompletionContext::PolicyField => complete_policy_fields(line, col_idx),
@@ -118,9 +246,12 @@ pub fn complete_at_with_context(
         CompletionContext::LoggingValue => complete_logging_values(),
         CompletionContext::BooleanValue => complete_boolean_values(),
         CompletionContext::PathValue { context_type } => {
-            complete_file_paths(line, col_idx, current_file, workspace_root, context_type)
+            complete_file_paths(line_idx as u32, line, col_idx, current_file, index, context_type)
         }
         CompletionContext::SqlContext { platform } => complete_osquery_tables(platform.as_deref()),
+        CompletionContext::FleetVariable { start, context } => {
+            complete_fleet_variables(line_idx as u32, start, col_idx, context)
+        }
         CompletionContext::Unknown => vec![],
     }
 }
@@ -139,26 +270,25 @@ fn determine_completion_context(
         return CompletionContext::TopLevel;
     }
 
+    // `$FLEET_VAR_...` being typed anywhere in a value
+    if let Some(start) = fleet_variable_start(line, col_idx) {
+        let parent = find_parent_context(source, line_idx);
+        let context = match parent.as_deref() {
+            Some(p) if p.contains("macos_settings") => Some(VarContext::AppleProfile),
+            Some(p) if p.contains("windows_settings") => Some(VarContext::WindowsProfile),
+            Some(p) if p.contains("scripts") || p.contains("install_script") => Some(VarContext::Script),
+            _ => None,
+        };
+        return CompletionContext::FleetVariable { start, context };
+    }
+
     // Check if we're after a specific key (value position)
     if let Some(key) = get_key_at_cursor(line, col_idx) {
         match key.as_str() {
             "platform" => return CompletionContext::PlatformValue,
             "logging" => return CompletionContext::LoggingValue,
             "path" => {
-                // Determine path context type based on parent context
-                let parent = find_parent_context(source, line_idx);
-                let context_type = match parent.as_deref() {
-                    Some(p) if p.contains("software.packages") => PathContextType::SoftwarePackage,
-                    Some(p) if p.contains("fleet_maintained_apps") => PathContextType::SoftwarePackage,
-                    Some(p) if p.contains("scripts") => PathContextType::Script,
-                    Some(p) if p.contains("macos_settings") => PathContextType::MacOSProfile,
-                    Some(p) if p.contains("windows_settings") => PathContextType::WindowsProfile,
-                    // Policies, queries, and labels can also use path references
-                    Some(p) if p == "policies" || p.ends_with(".policies") => PathContextType::Policy,
-                    Some(p) if p == "queries" || p.ends_with(".queries") => PathContextType::Query,
-                    Some(p) if p == "labels" || p.ends_with(".labels") => PathContextType::Label,
-                    _ => PathContextType::Generic,
-                };
+                let context_type = path_context_at(source, line_idx);
                 return CompletionContext::PathValue { context_type };
             }
             _ => {}
@@ -195,6 +325,40 @@ fn determine_completion_context(
     CompletionContext::Unknown
 }
 
+/// Kind of file a `path:` on `line_idx` refers to, from its parent keys.
+pub(crate) fn path_context_at(source: &str, line_idx: usize) -> PathContextType {
+    let parent = find_parent_context(source, line_idx);
+    match parent.as_deref() {
+        Some(p) if p.contains("software.packages") => PathContextType::SoftwarePackage,
+        Some(p) if p.contains("fleet_maintained_apps") => PathContextType::SoftwarePackage,
+        Some(p) if p.contains("scripts") => PathContextType::Script,
+        // install_script, uninstall_script, post_install_script in packages
+        Some(p) if p.ends_with("_script") => PathContextType::Script,
+        Some(p) if p.contains("macos_settings") => PathContextType::MacOSProfile,
+        Some(p) if p.contains("windows_settings") => PathContextType::WindowsProfile,
+        // Policies, queries, and labels can also use path references
+        Some(p) if p == "policies" || p.ends_with(".policies") => PathContextType::Policy,
+        Some(p) if p == "queries" || p.ends_with(".queries") => PathContextType::Query,
+        Some(p) if p == "labels" || p.ends_with(".labels") => PathContextType::Label,
+        _ => PathContextType::Generic,
+    }
+}
+
+/// Column where a `$FLEET_VAR_` name being typed starts (after `$` or `${`).
+fn fleet_variable_start(line: &str, col_idx: usize) -> Option<usize> {
+    let before = line.get(..col_idx.min(line.len()))?;
+    let name_start = before
+        .char_indices()
+        .rfind(|&(_, c)| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map(|(idx, c)| idx + c.len_utf8())
+        .unwrap_or(0);
+    let name = &before[name_start..];
+    let sigil = &before[..name_start];
+
+    let is_reference = sigil.ends_with('$') || sigil.ends_with("${");
+    (is_reference && "FLEET_VAR_".starts_with(&name[..name.len().min(10)])).then_some(name_start)
+}
+
 /// Get the key if cursor is in a value position (after colon).
 fn get_key_at_cursor(line: &str, col_idx: usize) -> Option<String> {
     let trimmed = line.trim().trim_start_matches('-').trim();
@@ -348,6 +512,109 @@ fn find_array_parent(source: &str, line_idx: usize) -> CompletionContext {
     context_path_to_completion_context(context.as_deref())
 }
 
+/// Snippet scaffolding the first item of an empty lib file with `role`.
+///
+/// Offered while every other line is blank or a comment and the current
+/// line holds at most a word being typed; it replaces the current line.
+fn complete_lib_file_template(role: FileRole, source: &str, line_idx: usize, line: &str) -> Option<CompletionItem> {
+    let is_blank = |l: &str| l.trim().is_empty() || l.trim_start().starts_with('#');
+    let others_blank = source.lines().enumerate().all(|(i, l)| i == line_idx || is_blank(l));
+    let typed = line.trim().trim_start_matches('-').trim();
+    if !others_blank || line.trim_start().starts_with('#') || typed.contains(':') || typed.contains(' ') {
+        return None;
+    }
+
+    let (label, snippet) = match role {
+        FileRole::Policies => (
+            "policy",
+            "- name: ${1:Policy name}\n  platform: ${2|darwin,windows,linux,chrome|}\n  description: ${3}\n  resolution: ${4}\n  query: ${5:SELECT 1 FROM osquery_info;}\n$0",
+        ),
+        FileRole::Queries => (
+            "query",
+            "- name: ${1:Query name}\n  platform: ${2|darwin,windows,linux,chrome|}\n  description: ${3}\n  interval: ${4:3600}\n  query: ${5:SELECT * FROM osquery_info;}\n$0",
+        ),
+        FileRole::Labels => (
+            "label",
+            "- name: ${1:Label name}\n  description: ${2}\n  label_membership_type: ${3|dynamic,manual|}\n  query: ${4:SELECT 1 FROM os_version WHERE platform = 'darwin';}\n$0",
+        ),
+        FileRole::Software => (
+            "software package",
+            "url: ${1:https://example.com/package.pkg}\n# self_service and categories go on the team's software.packages entry\n$0",
+        ),
+        FileRole::AgentOptions => (
+            "agent options",
+            "config:\n  options:\n    distributed_interval: ${1:10}\n    logger_tls_period: ${2:10}\n  decorators:\n    load:\n      - ${3:SELECT uuid AS host_uuid FROM system_info;}\n$0",
+        ),
+        FileRole::Script => return None,
+    };
+
+    let detail = format!("New {} file", role.section().replace('_', " "));
+    Some(template_item(label, detail, snippet, line_idx, line))
+}
+
+/// Completion item replacing the current line with a file template.
+fn template_item(label: &str, detail: String, snippet: &str, line_idx: usize, line: &str) -> CompletionItem {
+    let typed = line.trim().trim_start_matches('-').trim();
+    let line_idx = line_idx as u32;
+    CompletionItem {
+        label: label.to_string(),
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some(detail),
+        documentation: Some(Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("```yaml\n{}\n```", strip_snippet_syntax(snippet)),
+        })),
+        // Clients filter on the replaced text, which may start with "- "
+        filter_text: Some(format!("{}{}", line.trim_start().strip_suffix(typed).unwrap_or(""), label)),
+        sort_text: Some("0".to_string()),
+        preselect: Some(true),
+        text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+            range: Range {
+                start: Position { line: line_idx, character: 0 },
+                end: Position { line: line_idx, character: line.len() as u32 },
+            },
+            new_text: snippet.to_string(),
+        })),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        ..Default::default()
+    }
+}
+
+/// Snippet text with tabstops replaced by their placeholders, for previews.
+fn strip_snippet_syntax(snippet: &str) -> String {
+    let mut out = String::new();
+    let mut rest = snippet;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let inner = &rest[start + 2..start + end];
+        let value = match inner.split_once(':') {
+            Some((_, placeholder)) => placeholder,
+            // Choices: show the first one
+            None => inner.split_once('|').map_or("", |(_, choices)| choices.split(',').next().unwrap_or("")),
+        };
+        out.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out.replace("$0", "").trim_end().to_string()
+}
+
+/// Completion context for items at the top of a lib file with `role`.
+fn role_completion_context(role: FileRole) -> CompletionContext {
+    match role {
+        FileRole::Policies => CompletionContext::PolicyField,
+        FileRole::Queries => CompletionContext::QueryField,
+        FileRole::Labels => CompletionContext::LabelField,
+        FileRole::Software => CompletionContext::SoftwarePackageField,
+        FileRole::AgentOptions => CompletionContext::AgentOptionsSection,
+        // Scripts aren't YAML
+        FileRole::Script => CompletionContext::Unknown,
+    }
+}
+
 /// Convert a context path string to a CompletionContext.
 fn context_path_to_completion_context(path: Option<&str>) -> CompletionContext {
     match path {
@@ -513,12 +780,22 @@ fn complete_logging_values() -> Vec<CompletionItem> {
 }
 
 /// Complete osquery table names, optionally filtered by platform.
+///
+/// With a comma-separated platform list, only tables available on every
+/// listed platform are offered (e.g. `darwin,chrome` narrows to the tables
+/// the Chrome extension supports).
 fn complete_osquery_tables(platform: Option<&str>) -> Vec<CompletionItem> {
     OSQUERY_TABLES
         .iter()
         .filter(|(_, info)| {
             platform
-                .map(|p| p == "all" || info.platforms.contains(&p))
+                .map(|p| {
+                    p == "all"
+                        || p.split(',')
+                            .map(str::trim)
+                            .filter(|p| !p.is_empty())
+                            .all(|p| info.platforms.contains(&p))
+                })
                 .unwrap_or(true)
         })
         .map(|(name, info)| {
@@ -527,16 +804,263 @@ fn complete_osquery_tables(platform: Option<&str>) -> Vec<CompletionItem> {
                 label: (*name).to_string(),
                 kind: Some(CompletionItemKind::CLASS),
                 detail: Some(format!("osquery table ({})", platforms)),
+                // Documentation is attached in completionItem/resolve
+                data: Some(serde_json::json!({ "resolve": "osquery_table", "name": name })),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Complete Fleet variable names supported in the current context.
+fn complete_fleet_variables(
+    line: u32,
+    start: usize,
+    col_idx: usize,
+    context: Option<VarContext>,
+) -> Vec<CompletionItem> {
+    let range = Range {
+        start: Position { line, character: start as u32 },
+        end: Position { line, character: col_idx as u32 },
+    };
+
+    variables_for(context)
+        .map(|var| {
+            // Prefix variables need a certificate authority name
+            let (new_text, format) = if var.prefix {
+                (format!("{}${{1:CA_NAME}}", var.name), InsertTextFormat::SNIPPET)
+            } else {
+                (var.name.to_string(), InsertTextFormat::PLAIN_TEXT)
+            };
+            let contexts: Vec<&str> = var.contexts.iter().map(|c| c.label()).collect();
+
+            CompletionItem {
+                label: var.display(),
+                kind: Some(CompletionItemKind::VARIABLE),
+                detail: Some(var.description.to_string()),
                 documentation: Some(Documentation::MarkupContent(MarkupContent {
                     kind: MarkupKind::Markdown,
-                    value: format!("**{}**\n\n{}\n\n**Platforms:** {}", name, info.description, platforms),
+                    value: format!(
+                        "**{}**\n\n{}\n\n**Supported in:** {}",
+                        var.display(),
+                        var.description,
+                        contexts.join(", ")
+                    ),
                 })),
+                filter_text: Some(var.name.to_string()),
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit { range, new_text })),
+                insert_text_format: Some(format),
                 ..Default::default()
             }
         })
         .collect()
 }
 
+/// Initialization option setting how much documentation completions carry.
+pub const COMPLETION_DETAIL_OPTION: &str = "completionDetail";
+
+/// How much documentation completion items carry. Some clients render
+/// long markdown poorly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionDetail {
+    /// The one-line `detail` only
+    Minimal,
+    /// Markdown docs without examples or platform matrices
+    Standard,
+    /// Everything, osquery platform matrices included
+    #[default]
+    Rich,
+}
+
+impl CompletionDetail {
+    /// The `completionDetail` initialization option, or else what the
+    /// client's capabilities suggest: no docs for clients that can't show
+    /// markdown, and the full docs for clients that resolve them lazily.
+    pub fn from_initialize(options: Option<&serde_json::Value>, capabilities: &ClientCapabilities) -> Self {
+        let option = options
+            .and_then(|options| options.get(COMPLETION_DETAIL_OPTION))
+            .and_then(|value| serde_json::from_value(value.clone()).ok());
+        if let Some(detail) = option {
+            return detail;
+        }
+
+        let item = capabilities
+            .text_document
+            .as_ref()
+            .and_then(|t| t.completion.as_ref())
+            .and_then(|c| c.completion_item.as_ref());
+        let markdown = item
+            .and_then(|i| i.documentation_format.as_ref())
+            .is_none_or(|formats| formats.contains(&MarkupKind::Markdown));
+        let lazy = item
+            .and_then(|i| i.resolve_support.as_ref())
+            .is_some_and(|support| support.properties.iter().any(|p| p == "documentation"));
+        match (markdown, lazy) {
+            (false, _) => CompletionDetail::Minimal,
+            (true, false) => CompletionDetail::Standard,
+            (true, true) => CompletionDetail::Rich,
+        }
+    }
+
+    /// Trim an item's documentation to this level.
+    pub fn apply(self, mut item: CompletionItem) -> CompletionItem {
+        match self {
+            CompletionDetail::Rich => {}
+            CompletionDetail::Minimal => item.documentation = None,
+            CompletionDetail::Standard => {
+                item.documentation = match item.documentation {
+                    Some(Documentation::MarkupContent(content)) if content.kind == MarkupKind::Markdown => {
+                        let value = without_examples(&content.value);
+                        (!value.is_empty()).then_some(Documentation::MarkupContent(MarkupContent { value, ..content }))
+                    }
+                    other => other,
+                };
+            }
+        }
+        item
+    }
+}
+
+/// Markdown without code blocks, example headings and tables
+fn without_examples(markdown: &str) -> String {
+    let mut blocks: Vec<Vec<&str>> = vec![Vec::new()];
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        } else if line.trim().is_empty() && !in_fence {
+            blocks.push(Vec::new());
+        }
+        blocks.last_mut().unwrap().push(line);
+    }
+
+    blocks
+        .iter()
+        .map(|block| block.iter().copied().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>())
+        .filter(|block| {
+            !block.is_empty()
+                && !block.iter().any(|line| {
+                    let line = line.trim_start();
+                    line.starts_with("```") || line.starts_with('|') || line.starts_with("**Example")
+                })
+        })
+        .map(|block| block.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Which platforms an osquery table runs on, as a markdown table
+fn osquery_platform_matrix(name: &str) -> Option<String> {
+    let info = OSQUERY_TABLES.get(name)?;
+    let platforms = ["darwin", "windows", "linux", "chrome"];
+    let marks: Vec<&str> = platforms.iter().map(|p| if info.platforms.contains(p) { "✓" } else { "–" }).collect();
+    Some(format!(
+        "| {} |\n|{}|\n| {} |",
+        platforms.join(" | "),
+        ":-:|".repeat(platforms.len()),
+        marks.join(" | ")
+    ))
+}
+
+/// Attach documentation to a completion item the client highlighted.
+///
+/// Items are returned without their markdown documentation, which is
+/// looked up here from the `resolve` data they carry.
+pub fn resolve_completion_item(item: CompletionItem) -> CompletionItem {
+    resolve_completion_item_with(item, CompletionDetail::Rich)
+}
+
+/// Attach documentation at a detail level (see [`resolve_completion_item`]).
+pub fn resolve_completion_item_with(mut item: CompletionItem, detail: CompletionDetail) -> CompletionItem {
+    if item.documentation.is_some() {
+        return detail.apply(item);
+    }
+    let Some(data) = &item.data else {
+        return item;
+    };
+    let name = data.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+    let markdown = match data.get("resolve").and_then(|r| r.as_str()) {
+        Some("osquery_table") => osquery_table_doc(name).map(|doc| match osquery_platform_matrix(name) {
+            Some(matrix) => format!("{}\n\n{}", doc, matrix),
+            None => doc,
+        }),
+        Some("field") => get_field_doc(name).map(|doc| doc.to_markdown()),
+        _ => None,
+    };
+
+    item.documentation = markdown.map(|value| {
+        Documentation::MarkupContent(MarkupContent { kind: MarkupKind::Markdown, value })
+    });
+    detail.apply(item)
+}
+
+/// The key being typed on a line, with its colon if there is one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeyToken {
+    /// Byte range of the key
+    start: usize,
+    end: usize,
+    /// End of the colon and spaces after it, when no value follows
+    colon_end: Option<usize>,
+    /// Whether a value follows the colon
+    has_value: bool,
+    /// Right after a bare `-`, which needs a space before the key
+    after_dash: bool,
+}
+
+impl KeyToken {
+    /// The key at `col_idx`, if the cursor is on the key (or right after
+    /// its colon, where typing `:` re-triggers completion)
+    fn at(line: &str, col_idx: usize) -> Option<Self> {
+        let content = line.trim_start();
+        if content == "-" && col_idx == line.len() {
+            let end = line.len();
+            return Some(Self { start: end, end, colon_end: None, has_value: false, after_dash: true });
+        }
+        let start = line.len() - content.len() + if content.starts_with("- ") { 2 } else { 0 };
+        let start = start + (line[start..].len() - line[start..].trim_start().len());
+        let end = start + line[start..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(line.len() - start);
+
+        let rest = &line[end..];
+        let (colon_end, has_value) = match rest.strip_prefix(':') {
+            Some(after) if after.trim().is_empty() => (Some(line.len()), false),
+            Some(_) => (None, true),
+            None if rest.trim().is_empty() => (None, false),
+            // Not a key (`- [`, `"quoted`)
+            None => return None,
+        };
+
+        let cursor_end = colon_end.unwrap_or(end);
+        (start <= col_idx && col_idx <= cursor_end).then_some(Self { start, end, colon_end, has_value, after_dash: false })
+    }
+
+    /// Turn a field item's `insert_text` into an edit of the typed key:
+    /// the colon isn't doubled and an existing value is kept.
+    fn replace_with(&self, item: &mut CompletionItem, line_idx: u32, line: &str) {
+        if item.kind != Some(CompletionItemKind::FIELD) || item.text_edit.is_some() {
+            return;
+        }
+        let Some(key) = item.insert_text.as_deref().and_then(|text| text.strip_suffix(": ")) else {
+            return;
+        };
+        let (end, new_text) = match (self.has_value, self.colon_end) {
+            // `key: value`: keep the colon and value
+            (true, _) => (self.end, key.to_string()),
+            (false, Some(colon_end)) => (colon_end, format!("{}: ", key)),
+            (false, None) => (self.end, format!("{}: ", key)),
+        };
+        let new_text = if self.after_dash { format!(" {}", new_text) } else { new_text };
+        let range = Range {
+            start: Position { line: line_idx, character: byte_offset_to_utf16(line, self.start) },
+            end: Position { line: line_idx, character: byte_offset_to_utf16(line, end) },
+        };
+        item.filter_text = Some(new_text.clone());
+        item.insert_text = None;
+        item.text_edit = Some(CompletionTextEdit::Edit(TextEdit { range, new_text }));
+    }
+}
+
 /// Create a completion item for a field name.
 fn create_field_completion(name: &str, description: &str, required: bool) -> CompletionItem {
     let detail = if required {
@@ -545,19 +1069,12 @@ fn create_field_completion(name: &str, description: &str, required: bool) -> Com
         description.to_string()
     };
 
-    // Get richer documentation from schema if available
-    let documentation = get_field_doc(name).map(|doc| {
-        Documentation::MarkupContent(MarkupContent {
-            kind: MarkupKind::Markdown,
-            value: doc.to_markdown(),
-        })
-    });
-
     CompletionItem {
         label: name.to_string(),
         kind: Some(CompletionItemKind::FIELD),
         detail: Some(detail),
-        documentation,
+        // Richer documentation from the schema is attached in completionItem/resolve
+        data: Some(serde_json::json!({ "resolve": "field", "name": name })),
         insert_text: Some(format!("{}: ", name)),
         insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
         ..Default::default()
@@ -754,57 +1271,234 @@ fn complete_agent_options_section() -> Vec<CompletionItem> {
 }
 
 /// Complete file paths for path: values.
+///
+/// Candidates are fuzzy-matched against the typed fragment and ranked by
+/// match quality, conventional directories for the context, and proximity
+/// to the current file. The whole value is replaced with the relative path,
+/// quoted when YAML needs it.
 fn complete_file_paths(
+    line_idx: u32,
     line: &str,
     col_idx: usize,
     current_file: Option<&Path>,
-    workspace_root: Option<&Path>,
+    index: Option<&FileIndex>,
     context_type: PathContextType,
 ) -> Vec<CompletionItem> {
-    let mut completions = Vec::new();
+    let Some(index) = index else {
+        return Vec::new();
+    };
 
     // Extract partial path already typed (text after "path: ")
     let partial = extract_partial_path(line, col_idx);
-
-    // Determine base directory for scanning
-    let base_dir = match (workspace_root, current_file) {
-        (Some(root), _) => root.to_path_buf(),
-        (None, Some(file)) => file.parent().unwrap_or(Path::new(".")).to_path_buf(),
-        (None, None) => return completions,
+    let range = Range {
+        start: Position { line: line_idx, character: path_value_start(line, col_idx) as u32 },
+        end: Position { line: line_idx, character: col_idx as u32 },
     };
 
-    // Scan lib/ directory for matching files
-    let lib_dir = base_dir.join("lib");
-    if lib_dir.exists() && lib_dir.is_dir() {
-        scan_directory_for_paths(
-            &lib_dir,
-            current_file,
-            &context_type,
-            &partial,
-            &base_dir,
-            &mut completions,
-            0,
-        );
+    // `../lib/` and `../lib/mac` complete the next segment
+    if let Some((dir, segment)) = partial.rsplit_once('/') {
+        let items = complete_path_segments(dir, segment, range, current_file, index, context_type);
+        if !items.is_empty() {
+            return items;
+        }
     }
 
-    // Also scan teams/ directory for team-level completions
-    let teams_dir = base_dir.join("teams");
-    if teams_dir.exists() && teams_dir.is_dir() {
-        scan_directory_for_paths(
-            &teams_dir,
-            current_file,
-            &context_type,
-            &partial,
-            &base_dir,
-            &mut completions,
-            0,
-        );
+    let mut ranked: Vec<(i64, CompletionItem)> = index
+        .files(context_type)
+        .iter()
+        .filter_map(|path| {
+            // Calculate relative path from current file or workspace root
+            let relative_path = calculate_relative_path(path, current_file, index.root());
+            let score = fuzzy_score(&partial, &relative_path)?
+                + convention_bonus(context_type, &relative_path)
+                - proximity_penalty(&relative_path);
+
+            let mut item = create_path_completion(&relative_path, path, &context_type);
+            item.filter_text = Some(relative_path.clone());
+            item.text_edit = Some(CompletionTextEdit::Edit(TextEdit {
+                range,
+                new_text: yaml_path_literal(&relative_path),
+            }));
+            Some((score, item))
+        })
+        .collect();
+
+    // Best first; sort_text keeps the order in clients that re-sort by label
+    ranked.sort_by(|(a, a_item), (b, b_item)| b.cmp(a).then_with(|| a_item.label.cmp(&b_item.label)));
+    ranked
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (_, mut item))| {
+            item.sort_text = Some(format!("{:05}", rank));
+            item
+        })
+        .collect()
+}
+
+/// Client command that opens completion again after a directory is picked
+const TRIGGER_SUGGEST_COMMAND: &str = "editor.action.triggerSuggest";
+
+/// Entries of the typed directory `dir` that lead to candidate files,
+/// directories first, narrowed by the `segment` typed after it, the way
+/// editors complete paths one segment at a time.
+///
+/// Empty when `dir` holds no candidates, e.g. for a fuzzy `lib/mac/ff`.
+fn complete_path_segments(
+    dir: &str,
+    segment: &str,
+    range: Range,
+    current_file: Option<&Path>,
+    index: &FileIndex,
+    context_type: PathContextType,
+) -> Vec<CompletionItem> {
+    let base = current_file.and_then(Path::parent).unwrap_or(index.root());
+    let dir_path = normalize_path(&base.join(if dir.is_empty() { "/" } else { dir }));
+    let prefix = format!("{}/", dir);
+
+    // Directory name -> number of candidates under it
+    let mut dirs: BTreeMap<String, usize> = BTreeMap::new();
+    let mut files: Vec<(String, &Path)> = Vec::new();
+    let candidates = index.files(context_type);
+    for path in candidates.iter() {
+        let Ok(rest) = path.strip_prefix(&dir_path) else {
+            continue;
+        };
+        let mut components = rest.components();
+        let Some(name) = components.next().map(|c| c.as_os_str().to_string_lossy().to_string()) else {
+            continue;
+        };
+        if components.next().is_some() {
+            *dirs.entry(name).or_default() += 1;
+        } else {
+            files.push((name, path));
+        }
     }
+    files.sort();
+
+    let narrowed = |name: &str| segment.is_empty() || fuzzy_score(segment, name).is_some();
+    let directories = dirs.into_iter().filter(|(name, _)| narrowed(name)).map(|(name, count)| {
+        let value = format!("{}{}/", prefix, name);
+        CompletionItem {
+            label: format!("{}/", name),
+            kind: Some(CompletionItemKind::FOLDER),
+            detail: Some(format!("{} matching file{}", count, if count == 1 { "" } else { "s" })),
+            filter_text: Some(value.clone()),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit { range, new_text: yaml_path_literal(&value) })),
+            command: Some(Command {
+                title: "Complete the next segment".to_string(),
+                command: TRIGGER_SUGGEST_COMMAND.to_string(),
+                arguments: None,
+            }),
+            ..Default::default()
+        }
+    });
+    let files = files.into_iter().filter(|(name, _)| narrowed(name)).map(|(name, path)| {
+        let value = format!("{}{}", prefix, name);
+        let mut item = create_path_completion(&value, path, &context_type);
+        item.label = name;
+        item.filter_text = Some(value.clone());
+        item.text_edit = Some(CompletionTextEdit::Edit(TextEdit { range, new_text: yaml_path_literal(&value) }));
+        item
+    });
+
+    directories
+        .chain(files)
+        .enumerate()
+        .map(|(rank, mut item)| {
+            item.sort_text = Some(format!("{:05}", rank));
+            item
+        })
+        .collect()
+}
+
+/// Fuzzy match score of `pattern` against `candidate`, or `None` when the
+/// characters of `pattern` don't appear in order (case-insensitive), so
+/// `lib/mac/ff` matches `lib/macos/software/firefox.yml`.
+///
+/// Consecutive characters, matches at the start of a path segment or word,
+/// and matches in the file name score higher.
+fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let chars: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+    let file_name_start = chars.iter().rposition(|c| *c == '/').map_or(0, |i| i + 1);
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for p in pattern {
+        let found = (next..chars.len()).find(|&i| chars[i] == p)?;
+        score += 1;
+        if previous.is_some_and(|prev| prev + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(chars[found - 1], '/' | '-' | '_' | '.' | ' ') {
+            score += 8;
+        }
+        if found >= file_name_start {
+            score += 2;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// Bonus for files in the directory (or with the suffix) Fleet's GitOps
+/// layout uses for the context, like `lib/<platform>/policies/`.
+fn convention_bonus(context_type: PathContextType, relative_path: &str) -> i64 {
+    let conventions: &[&str] = match context_type {
+        PathContextType::Policy => &["policies"],
+        PathContextType::Query => &["queries"],
+        PathContextType::Label => &["labels"],
+        PathContextType::Script => &["scripts"],
+        PathContextType::SoftwarePackage => &["software"],
+        PathContextType::MacOSProfile => &["macos", "profiles", "configuration-profiles"],
+        PathContextType::WindowsProfile => &["windows", "profiles", "configuration-profiles"],
+        PathContextType::Generic => &[],
+    };
 
-    // Sort completions alphabetically
-    completions.sort_by(|a, b| a.label.cmp(&b.label));
+    let lower = relative_path.to_lowercase();
+    let in_directory = lower.split('/').rev().skip(1).any(|dir| conventions.contains(&dir));
+    let with_suffix = conventions
+        .iter()
+        .any(|c| lower.ends_with(&format!(".{}.yml", c)) || lower.ends_with(&format!(".{}.yaml", c)));
+    if in_directory || with_suffix { 15 } else { 0 }
+}
 
-    completions
+/// Penalty for paths far from the current file (`..` hops and depth).
+fn proximity_penalty(relative_path: &str) -> i64 {
+    relative_path
+        .split('/')
+        .map(|segment| if segment == ".." { 3 } else { 1 })
+        .sum()
+}
+
+/// A path as a YAML scalar: plain when safe, double-quoted otherwise.
+fn yaml_path_literal(path: &str) -> String {
+    let plain = !path.is_empty()
+        && !path.starts_with(['-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`', ' '])
+        && !path.ends_with(' ')
+        && !path.contains(": ")
+        && !path.contains(" #")
+        && !path.contains(['\t', '\n', '"', '\\']);
+    if plain {
+        path.to_string()
+    } else {
+        format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Column where the `path:` value starts (after the colon and spaces),
+/// including an opening quote so completions replace it.
+fn path_value_start(line: &str, col_idx: usize) -> usize {
+    let Some(colon) = line.find(':') else {
+        return col_idx;
+    };
+    let value_offset = line[colon + 1..].len() - line[colon + 1..].trim_start().len();
+    (colon + 1 + value_offset).min(col_idx)
 }
 
 /// Extract the partial path the user has typed after "path: ".
@@ -826,55 +1520,8 @@ fn extract_partial_path(line: &str, col_idx: usize) -> String {
     String::new()
 }
 
-/// Recursively scan a directory for files matching the context type.
-fn scan_directory_for_paths(
-    dir: &Path,
-    current_file: Option<&Path>,
-    context_type: &PathContextType,
-    partial: &str,
-    workspace_root: &Path,
-    completions: &mut Vec<CompletionItem>,
-    depth: usize,
-) {
-    // Limit recursion depth to avoid performance issues
-    const MAX_DEPTH: usize = 5;
-    if depth > MAX_DEPTH {
-        return;
-    }
-
-    let entries = match std::fs::read_dir(dir) {
-        Ok(entries) => entries,
-        Err(_) => return,
-    };
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-
-        if path.is_dir() {
-            // Recursively scan subdirectories
-            scan_directory_for_paths(
-                &path,
-                current_file,
-                context_type,
-                partial,
-                workspace_root,
-                completions,
-                depth + 1,
-            );
-        } else if path.is_file() && matches_context_type(&path, context_type) {
-            // Calculate relative path from current file or workspace root
-            let relative_path = calculate_relative_path(&path, current_file, workspace_root);
-
-            // Filter by partial input
-            if partial.is_empty() || relative_path.to_lowercase().contains(&partial.to_lowercase()) {
-                completions.push(create_path_completion(&relative_path, &path, context_type));
-            }
-        }
-    }
-}
-
 /// Check if a file matches the expected context type based on extension.
-fn matches_context_type(path: &Path, context_type: &PathContextType) -> bool {
+pub(crate) fn matches_context_type(path: &Path, context_type: &PathContextType) -> bool {
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
 
     match context_type {
@@ -896,20 +1543,14 @@ fn calculate_relative_path(
     workspace_root: &Path,
 ) -> String {
     // If we have a current file, calculate path relative to it
-    if let Some(current) = current_file {
-        if let Some(current_dir) = current.parent() {
-            if let Some(relative) = pathdiff::diff_paths(target, current_dir) {
-                return relative.to_string_lossy().to_string();
-            }
-        }
-    }
-
-    // Fall back to path relative to workspace root
-    target
-        .strip_prefix(workspace_root)
-        .unwrap_or(target)
-        .to_string_lossy()
-        .to_string()
+    let relative = current_file
+        .and_then(|current| current.parent())
+        .and_then(|current_dir| pathdiff::diff_paths(target, current_dir))
+        // Fall back to path relative to workspace root
+        .unwrap_or_else(|| target.strip_prefix(workspace_root).unwrap_or(target).to_path_buf());
+
+    // Fleet resolves paths with forward slashes on every platform
+    relative.to_string_lossy().replace('\\', "/")
 }
 
 /// Create a completion item for a file path.
@@ -1000,6 +1641,181 @@ mod tests {
         assert!(labels.contains(&"processes"));
     }
 
+    #[test]
+    fn test_complete_osquery_tables_chrome() {
+        let source = "policies:\n  - name: test\n    platform: chrome\n    query: |\n      SELECT * FROM ";
+        let completions = complete_at(source, Position { line: 4, character: 20 });
+
+        let labels: Vec<_> = completions.iter().map(|c| c.label.as_str()).collect();
+        assert!(labels.contains(&"os_version"));
+        assert!(labels.contains(&"network_interfaces"));
+        assert!(!labels.contains(&"processes"));
+    }
+
+    #[test]
+    fn test_resolve_completion_item() {
+        let source = "policies:\n  - name: test\n    query: |\n      SELECT * FROM ";
+        let completions = complete_at(source, Position { line: 3, character: 20 });
+        let processes = completions.into_iter().find(|c| c.label == "processes").unwrap();
+        assert!(processes.documentation.is_none());
+
+        let Some(Documentation::MarkupContent(doc)) = resolve_completion_item(processes).documentation else {
+            panic!("expected markdown documentation");
+        };
+        assert!(doc.value.starts_with("**processes** (osquery table)"));
+
+        let field = create_field_completion("resolution", "How to fix policy failures", false);
+        assert!(field.documentation.is_none());
+        assert!(resolve_completion_item(field).documentation.is_some());
+    }
+
+    #[test]
+    fn test_completion_detail_levels() {
+        let source = "policies:\n  - name: test\n    query: |\n      SELECT * FROM ";
+        let completions = complete_at(source, Position { line: 3, character: 20 });
+        let processes = completions.into_iter().find(|c| c.label == "processes").unwrap();
+        let markdown = |item: CompletionItem| match item.documentation {
+            Some(Documentation::MarkupContent(doc)) => Some(doc.value),
+            _ => None,
+        };
+
+        let rich = markdown(resolve_completion_item_with(processes.clone(), CompletionDetail::Rich)).unwrap();
+        assert!(rich.contains("| darwin | windows | linux | chrome |"));
+        let standard = markdown(resolve_completion_item_with(processes.clone(), CompletionDetail::Standard)).unwrap();
+        assert!(standard.starts_with("**processes** (osquery table)"));
+        assert!(!standard.contains('|'));
+        let minimal = resolve_completion_item_with(processes, CompletionDetail::Minimal);
+        assert!(minimal.documentation.is_none());
+        assert!(minimal.detail.is_some());
+
+        // Field examples are left out below rich
+        let field = create_field_completion("platform", "Target platforms", false);
+        let data = serde_json::json!({ "resolve": "field", "name": "policies.platform" });
+        let field = CompletionItem { data: Some(data), ..field };
+        let rich = markdown(resolve_completion_item_with(field.clone(), CompletionDetail::Rich)).unwrap();
+        let standard = markdown(resolve_completion_item_with(field, CompletionDetail::Standard)).unwrap();
+        assert!(rich.contains("```yaml"));
+        assert!(!standard.contains("```") && !standard.contains("**Example:**"));
+        assert!(standard.contains("**Type:**"));
+    }
+
+    #[test]
+    fn test_without_examples() {
+        let markdown = "**a**\n\nText\n\n**Example:**\n```yaml\nkey: 1\n\nmore: 2\n```\n\n[Docs](x)";
+        assert_eq!(without_examples(markdown), "**a**\n\nText\n\n[Docs](x)");
+        assert_eq!(without_examples("```yaml\npolicies: []\n```"), "");
+    }
+
+    #[test]
+    fn test_completion_detail_from_initialize() {
+        let capabilities = |item: serde_json::Value| -> ClientCapabilities {
+            serde_json::from_value(serde_json::json!({ "textDocument": { "completion": { "completionItem": item } } }))
+                .unwrap()
+        };
+        let plain = capabilities(serde_json::json!({ "documentationFormat": ["plaintext"] }));
+        let lazy = capabilities(serde_json::json!({
+            "documentationFormat": ["markdown"],
+            "resolveSupport": { "properties": ["documentation", "detail"] }
+        }));
+
+        assert_eq!(CompletionDetail::from_initialize(None, &plain), CompletionDetail::Minimal);
+        assert_eq!(CompletionDetail::from_initialize(None, &ClientCapabilities::default()), CompletionDetail::Standard);
+        assert_eq!(CompletionDetail::from_initialize(None, &lazy), CompletionDetail::Rich);
+        let options = serde_json::json!({ "completionDetail": "standard" });
+        assert_eq!(CompletionDetail::from_initialize(Some(&options), &lazy), CompletionDetail::Standard);
+        let invalid = serde_json::json!({ "completionDetail": "verbose" });
+        assert_eq!(CompletionDetail::from_initialize(Some(&invalid), &plain), CompletionDetail::Minimal);
+    }
+
+    #[test]
+    fn test_field_completion_edits_typed_key() {
+        let edit = |source: &str, character: u32| {
+            let line = source.lines().count() as u32 - 1;
+            let items = complete_at(source, Position { line, character });
+            let item = items.into_iter().find(|item| item.label == "description").unwrap();
+            assert!(item.insert_text.is_none());
+            match item.text_edit {
+                Some(CompletionTextEdit::Edit(edit)) => (edit.range.start.character, edit.range.end.character, edit.new_text),
+                other => panic!("expected a text edit, got {:?}", other),
+            }
+        };
+
+        // Partly typed key
+        assert_eq!(edit("policies:\n  - name: A\n    desc", 8), (4, 8, "description: ".to_string()));
+        // Colon already typed: replaced, not doubled
+        assert_eq!(edit("policies:\n  - name: A\n    desc:", 9), (4, 9, "description: ".to_string()));
+        assert_eq!(edit("policies:\n  - name: A\n    desc: ", 10), (4, 10, "description: ".to_string()));
+        // Existing value: only the key is replaced
+        assert_eq!(edit("policies:\n  - name: A\n    desc: Checks things", 6), (4, 8, "description".to_string()));
+        // New list item
+        assert_eq!(edit("policies:\n  - desc", 8), (4, 8, "description: ".to_string()));
+    }
+
+    #[test]
+    fn test_complete_on_trigger() {
+        let trigger = |source: &str, trigger: &str| {
+            let line = source.lines().count() as u32 - 1;
+            let character = source.lines().last().unwrap().len() as u32;
+            complete_on_trigger(source, Position { line, character }, trigger, None, None)
+        };
+        let edit_text = |item: &CompletionItem| match &item.text_edit {
+            Some(CompletionTextEdit::Edit(edit)) => edit.new_text.clone(),
+            _ => item.label.clone(),
+        };
+
+        // New array item: its fields
+        let items = trigger("policies:\n  - name: A\n  -", "-");
+        let query = items.iter().find(|item| item.label == "query").unwrap();
+        assert_eq!(edit_text(query), " query: ");
+        let items = trigger("policies:\n  - name: A\n  - ", " ");
+        assert_eq!(edit_text(items.iter().find(|item| item.label == "query").unwrap()), "query: ");
+
+        // Enum values after the colon, with the space when it's missing
+        let items = trigger("policies:\n  - name: A\n    platform:", ":");
+        assert!(!items.is_empty() && items.iter().all(|item| item.kind == Some(CompletionItemKind::ENUM_MEMBER)));
+        assert_eq!(edit_text(items.iter().find(|item| item.label == "darwin").unwrap()), " darwin");
+        let items = trigger("policies:\n  - name: A\n    platform: ", " ");
+        assert_eq!(edit_text(items.iter().find(|item| item.label == "darwin").unwrap()), "darwin");
+
+        // Nothing while typing elsewhere
+        assert!(trigger("policies:\n  - name: A\n    description:", ":").is_empty());
+        assert!(trigger("policies:\n  - name: Disk encryption ", " ").is_empty());
+        assert!(trigger("policies:\n  - name: A\n    description: Up-", "-").is_empty());
+        assert!(trigger("policies:\n  - name: v1.", ".").is_empty());
+        assert!(trigger("policies:\n  - name: A\n    query: SELECT * FROM ", " ").is_empty());
+    }
+
+    #[test]
+    fn test_key_token() {
+        let key = |start, end, colon_end| KeyToken { start, end, colon_end, has_value: false, after_dash: false };
+        assert_eq!(KeyToken::at("    name", 8), Some(key(4, 8, None)));
+        assert_eq!(KeyToken::at("  - name: ", 10), Some(key(4, 8, Some(10))));
+        assert_eq!(KeyToken::at("  - name: x", 11), None);
+        assert_eq!(KeyToken::at("  - name: x", 5).map(|k| k.has_value), Some(true));
+        assert_eq!(KeyToken::at("    ", 4), Some(key(4, 4, None)));
+        assert_eq!(KeyToken::at("  - [a]", 4), None);
+        assert_eq!(KeyToken::at("  -", 3).map(|k| k.after_dash), Some(true));
+    }
+
+    #[test]
+    fn test_complete_fleet_variables() {
+        let source = "controls:\n  windows_settings:\n    custom_settings:\n      - note: $FLEET_VAR_HO\n";
+        let completions = complete_at(source, Position { line: 3, character: 26 });
+        let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["$FLEET_VAR_HOST_UUID"]);
+
+        if let Some(CompletionTextEdit::Edit(edit)) = &completions[0].text_edit {
+            assert_eq!(edit.range.start.character, 15);
+            assert_eq!(edit.new_text, "FLEET_VAR_HOST_UUID");
+        } else {
+            panic!("Expected a text edit");
+        }
+
+        assert_eq!(fleet_variable_start("x: ${FLEET", 10), Some(5));
+        assert_eq!(fleet_variable_start("x: $HOME", 8), None);
+        assert_eq!(fleet_variable_start("x: FLEET_VAR", 12), None);
+    }
+
     #[test]
     fn test_get_key_at_cursor() {
         assert_eq!(
@@ -1083,6 +1899,72 @@ mod tests {
         assert_eq!(context3, CompletionContext::PathValue { context_type: PathContextType::MacOSProfile });
     }
 
+    #[test]
+    fn test_lib_file_template() {
+        let template = complete_lib_file_template(FileRole::Queries, "# uptime\n- ", 1, "- ").unwrap();
+        assert_eq!(template.filter_text.as_deref(), Some("- query"));
+        let Some(CompletionTextEdit::Edit(edit)) = &template.text_edit else {
+            panic!("expected a text edit");
+        };
+        assert_eq!(edit.range.end.character, 2);
+        assert!(edit.new_text.starts_with("- name: ${1:Query name}\n"));
+
+        // Not once the file has content
+        assert!(complete_lib_file_template(FileRole::Queries, "- name: a\n", 1, "").is_none());
+        assert!(complete_lib_file_template(FileRole::Queries, "- name: a", 0, "- name: a").is_none());
+        assert!(complete_lib_file_template(FileRole::Script, "", 0, "").is_none());
+
+        // Package files no longer carry team-level fields
+        let template = complete_lib_file_template(FileRole::Software, "", 0, "").unwrap();
+        let Some(CompletionTextEdit::Edit(edit)) = &template.text_edit else {
+            panic!("expected a text edit");
+        };
+        use crate::linter::rules::{DeprecatedFieldsRule, Rule};
+        let scaffold = strip_snippet_syntax(&edit.new_text);
+        let deprecated =
+            DeprecatedFieldsRule::default().check(&Default::default(), Path::new("lib/software/app.yml"), &scaffold);
+        assert!(deprecated.is_empty(), "{:?}", deprecated);
+    }
+
+    #[test]
+    fn test_strip_snippet_syntax() {
+        assert_eq!(
+            strip_snippet_syntax("- name: ${1:Name}\n  platform: ${2|darwin,linux|}\n  description: ${3}\n$0"),
+            "- name: Name\n  platform: darwin\n  description:"
+        );
+    }
+
+    #[test]
+    fn test_completion_in_lib_files_uses_file_role() {
+        let labels = |items: Vec<CompletionItem>| items.into_iter().map(|i| i.label).collect::<Vec<_>>();
+        let policies = Path::new("/repo/lib/windows/policies/foo.policies.yml");
+        let queries = Path::new("/repo/lib/macos/queries/uptime.yml");
+        let start = Position { line: 0, character: 0 };
+
+        // Empty new files complete their items' fields, not team file keys
+        let items = labels(complete_at_with_index("", start, Some(policies), None));
+        assert!(items.contains(&"resolution".to_string()));
+        assert!(!items.contains(&"controls".to_string()));
+        let items = labels(complete_at_with_index("", start, Some(queries), None));
+        assert!(items.contains(&"interval".to_string()));
+
+        // Inside an item, too
+        let source = "- name: Uptime\n  ";
+        let position = Position { line: 1, character: 2 };
+        let items = labels(complete_at_with_index(source, position, Some(queries), None));
+        assert!(items.contains(&"interval".to_string()));
+
+        // Only the policy snippet is offered in a policies file, first
+        let items = complete_at_with_index("", start, Some(policies), None);
+        assert_eq!(items[0].label, "policy");
+        assert_eq!(items[0].insert_text_format, Some(InsertTextFormat::SNIPPET));
+        assert_eq!(items.iter().filter(|i| i.kind == Some(CompletionItemKind::SNIPPET)).count(), 1);
+
+        // Team files are unaffected
+        let items = labels(complete_at_with_index("", start, Some(Path::new("/repo/teams/a.yml")), None));
+        assert!(items.contains(&"controls".to_string()));
+    }
+
     #[test]
     fn test_complete_file_paths_with_workspace() {
         use std::fs;
@@ -1108,11 +1990,13 @@ mod tests {
         fs::write(&team_file, "software:\n  packages:\n    - path: ").unwrap();
 
         // Test file path completion
+        let index = FileIndex::new(workspace_root);
         let completions = complete_file_paths(
+            2,
             "    - path: ",
             12,
             Some(&team_file),
-            Some(workspace_root),
+            Some(&index),
             PathContextType::SoftwarePackage,
         );
 
@@ -1124,4 +2008,115 @@ mod tests {
             assert!(item.label.ends_with(".yml") || item.label.ends_with(".yaml"));
         }
     }
+
+    #[test]
+    fn test_path_segment_completion() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for file in [
+            "lib/macos/policies/disk.yml",
+            "lib/macos/policies/firewall.yml",
+            "lib/windows/policies/bitlocker.yml",
+            "lib/all.yml",
+            "teams/a.yml",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let index = FileIndex::new(root);
+        let team = root.join("teams/a.yml");
+        let complete = |line: &str| {
+            complete_file_paths(1, line, line.len(), Some(&team), Some(&index), PathContextType::Policy)
+        };
+        let labels = |items: &[CompletionItem]| items.iter().map(|c| c.label.clone()).collect::<Vec<_>>();
+
+        // Directories first, then files
+        let items = complete("  - path: ../lib/");
+        assert_eq!(labels(&items), vec!["macos/", "windows/", "all.yml"]);
+        assert_eq!(items[0].kind, Some(CompletionItemKind::FOLDER));
+        assert_eq!(items[0].detail.as_deref(), Some("2 matching files"));
+        let Some(CompletionTextEdit::Edit(edit)) = &items[0].text_edit else {
+            panic!("expected a text edit");
+        };
+        assert_eq!((edit.range.start.character, edit.new_text.as_str()), (10, "../lib/macos/"));
+        assert!(items[0].command.is_some());
+
+        // The typed segment narrows, and the next directory's entries follow
+        assert_eq!(labels(&complete("  - path: ../lib/mac")), vec!["macos/"]);
+        assert_eq!(labels(&complete("  - path: ../lib/macos/")), vec!["policies/"]);
+        let items = complete("  - path: ../lib/macos/policies/f");
+        assert_eq!(labels(&items), vec!["firewall.yml"]);
+        let Some(CompletionTextEdit::Edit(edit)) = &items[0].text_edit else {
+            panic!("expected a text edit");
+        };
+        assert_eq!(edit.new_text, "../lib/macos/policies/firewall.yml");
+
+        // Any spelling of the directory works; unknown ones fall back to fuzzy paths
+        assert_eq!(labels(&complete("  - path: ./../lib/./")), vec!["macos/", "windows/", "all.yml"]);
+        assert_eq!(labels(&complete("  - path: lib/mac/fire")), vec!["../lib/macos/policies/firewall.yml"]);
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "lib/macos/software/firefox.yml"), Some(0));
+        assert!(fuzzy_score("lib/mac/ff", "lib/macos/software/firefox.yml").is_some());
+        assert!(fuzzy_score("FIREFOX", "lib/macos/software/firefox.yml").is_some());
+        assert_eq!(fuzzy_score("chrome", "lib/macos/software/firefox.yml"), None);
+
+        // Contiguous file name matches beat scattered ones
+        let firefox = fuzzy_score("fire", "lib/firefox.yml").unwrap();
+        let scattered = fuzzy_score("fire", "lib/f/install/remove/everything.yml").unwrap();
+        assert!(firefox > scattered);
+    }
+
+    #[test]
+    fn test_yaml_path_literal() {
+        assert_eq!(yaml_path_literal("../lib/policies/a.yml"), "../lib/policies/a.yml");
+        assert_eq!(yaml_path_literal("lib/My Scripts/a.sh"), "lib/My Scripts/a.sh");
+        assert_eq!(yaml_path_literal("lib/#1.yml"), "lib/#1.yml");
+        assert_eq!(yaml_path_literal("lib/a #1.yml"), "\"lib/a #1.yml\"");
+        assert_eq!(yaml_path_literal("@scripts/a.sh"), "\"@scripts/a.sh\"");
+    }
+
+    #[test]
+    fn test_path_completion_ranking() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for file in ["lib/macos/policies/disk.yml", "lib/macos/queries/disk.yml", "lib/disk.yml", "teams/a.yml"] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let index = FileIndex::new(root);
+        let line = "  - path: \"disk";
+        let completions = complete_file_paths(
+            1,
+            line,
+            line.len(),
+            Some(&root.join("teams/a.yml")),
+            Some(&index),
+            PathContextType::Policy,
+        );
+
+        let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels[0], "../lib/macos/policies/disk.yml");
+        assert!(labels.contains(&"../lib/disk.yml"));
+        assert_eq!(completions[0].sort_text.as_deref(), Some("00000"));
+
+        // The edit replaces the typed value, opening quote included
+        let Some(CompletionTextEdit::Edit(edit)) = &completions[0].text_edit else {
+            panic!("expected a text edit");
+        };
+        assert_eq!(edit.range.start.character, 10);
+        assert_eq!(edit.range.end.character, line.len() as u32);
+        assert_eq!(edit.new_text, "../lib/macos/policies/disk.yml");
+    }
 }
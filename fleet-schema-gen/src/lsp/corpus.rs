@@ -0,0 +1,122 @@
+//! Diagnostics snapshots of the GitOps repositories in `testdata/corpus`.
+//!
+//! Each directory there is a Fleet GitOps repository modeled on real ones.
+//! Every YAML file in it is analyzed the way the server does for an open
+//! document, the repository rules run over the whole repository, and the
+//! result is compared with `<repository>.snap`. Run with `UPDATE_GOLDEN=1`
+//! to accept changes.
+
+use super::diagnostics::{engine_error_to_diagnostic, lint_error_to_diagnostic};
+use super::workspace::validate_path_references;
+use crate::linter::engine::find_yaml_files;
+use crate::linter::walk::WalkOptions;
+use crate::linter::{LintError, Linter};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Repositories in the corpus
+fn repositories(root: &Path) -> Vec<PathBuf> {
+    let mut repos: Vec<PathBuf> = fs::read_dir(root)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default();
+    repos.sort();
+    repos
+}
+
+/// YAML files of every repository in the corpus
+pub(super) fn corpus_files(root: &Path) -> Vec<PathBuf> {
+    repositories(root)
+        .iter()
+        .flat_map(|repo| find_yaml_files(repo, &WalkOptions::default()).unwrap_or_default())
+        .collect()
+}
+
+fn severity(severity: Option<DiagnosticSeverity>) -> &'static str {
+    match severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::INFORMATION) => "info",
+        _ => "hint",
+    }
+}
+
+/// First line of a message; help text varies more than the finding itself
+fn headline(message: &str) -> &str {
+    message.lines().next().unwrap_or("")
+}
+
+/// The diagnostics the server publishes for `file`, as in `lint_document`
+fn document_diagnostics(linter: &Linter, file: &Path, content: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = match linter.lint_content(content, file) {
+        Ok(report) => report
+            .errors
+            .iter()
+            .chain(&report.warnings)
+            .chain(&report.infos)
+            .map(|error| lint_error_to_diagnostic(error, content))
+            .collect(),
+        Err(e) => vec![engine_error_to_diagnostic(&e, content)],
+    };
+    diagnostics.extend(validate_path_references(content, file, file.parent()));
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character, d.message.clone()));
+    diagnostics
+}
+
+/// Snapshot of one repository: file diagnostics, then repository rules
+fn snapshot(repo: &Path) -> String {
+    let linter = Linter::new();
+    let relative = |path: &Path| path.strip_prefix(repo).unwrap_or(path).to_string_lossy().replace('\\', "/");
+    let mut out = String::new();
+
+    for file in find_yaml_files(repo, &WalkOptions::default()).unwrap() {
+        let content = fs::read_to_string(&file).unwrap();
+        for d in document_diagnostics(&linter, &file, &content) {
+            let _ = writeln!(
+                out,
+                "{}:{}:{}: {}: {}",
+                relative(&file),
+                d.range.start.line + 1,
+                d.range.start.character + 1,
+                severity(d.severity),
+                headline(&d.message)
+            );
+        }
+    }
+
+    let mut repo_errors: Vec<LintError> = linter.lint_repo(repo).unwrap_or_default();
+    repo_errors.sort_by(|a, b| (&a.file, a.line, &a.message).cmp(&(&b.file, b.line, &b.message)));
+    for error in repo_errors {
+        let _ = writeln!(
+            out,
+            "[repo] {}:{}: {}: {}",
+            relative(&error.file),
+            error.line.unwrap_or(0),
+            error.severity,
+            headline(&error.message)
+        );
+    }
+
+    out
+}
+
+#[test]
+fn test_corpus_snapshots() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/corpus");
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let repos = repositories(&root);
+    assert!(!repos.is_empty(), "no repositories in {}", root.display());
+
+    for repo in repos {
+        let actual = snapshot(&repo);
+        let golden = repo.with_extension("snap");
+        if update {
+            fs::write(&golden, &actual).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&golden)
+            .unwrap_or_else(|_| panic!("missing {}; run with UPDATE_GOLDEN=1", golden.display()));
+        assert_eq!(actual, expected, "{} changed; run with UPDATE_GOLDEN=1 to accept", golden.display());
+    }
+}
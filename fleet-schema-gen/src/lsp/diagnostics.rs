@@ -1,9 +1,13 @@
 //! Conversion utilities from LintError to LSP Diagnostic.
 
-use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Position, Range, Url,
+};
 
 use crate::linter::error::{LintError, Severity};
+use crate::linter::{LintEngineError, Linter};
 use super::position::to_lsp_position;
+use std::path::Path;
 
 /// Convert a LintError to an LSP Diagnostic.
 pub fn lint_error_to_diagnostic(error: &LintError, source: &str) -> Diagnostic {
@@ -31,12 +35,65 @@ pub fn lint_error_to_diagnostic(error: &LintError, source: &str) -> Diagnostic {
         code_description: None,
         source: Some("fleet-lsp".to_string()),
         message,
-        related_information: None,
+        related_information: related_information(error),
         tags: None,
         data,
     }
 }
 
+/// Diagnostic for a document the linter could not parse, at the YAML
+/// error's location when known.
+pub fn engine_error_to_diagnostic(error: &LintEngineError, source: &str) -> Diagnostic {
+    let (message, start) = match error {
+        LintEngineError::Parse { source: yaml_error, .. } => (
+            format!("Failed to parse YAML: {}", yaml_error),
+            yaml_error.location().map(|l| to_lsp_position(l.line(), l.column(), source)),
+        ),
+        other => (other.to_string(), None),
+    };
+    let start = start.unwrap_or(Position { line: 0, character: 0 });
+
+    Diagnostic {
+        range: Range { start, end: start },
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("fleet-lsp".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Errors (not warnings) in a file, as shown at `path:` lines that
+/// reference it.
+pub fn file_error_diagnostics(linter: &Linter, content: &str, path: &Path) -> Vec<Diagnostic> {
+    match linter.lint_content(content, path) {
+        Ok(report) => report.errors.iter().map(|e| lint_error_to_diagnostic(e, content)).collect(),
+        Err(e) => vec![engine_error_to_diagnostic(&e, content)],
+    }
+}
+
+/// Related locations as LSP related information.
+///
+/// Other files aren't loaded, so columns are taken as-is rather than
+/// converted to UTF-16.
+fn related_information(error: &LintError) -> Option<Vec<DiagnosticRelatedInformation>> {
+    let related: Vec<DiagnosticRelatedInformation> = error
+        .related
+        .iter()
+        .filter_map(|related| {
+            let uri = Url::from_file_path(&related.file).ok()?;
+            let position = Position {
+                line: related.line.unwrap_or(1).saturating_sub(1) as u32,
+                character: related.column.unwrap_or(1).saturating_sub(1) as u32,
+            };
+            Some(DiagnosticRelatedInformation {
+                location: Location { uri, range: Range { start: position, end: position } },
+                message: related.message.clone(),
+            })
+        })
+        .collect();
+    (!related.is_empty()).then_some(related)
+}
+
 /// Convert error location to LSP Range.
 fn error_to_range(error: &LintError, source: &str) -> Range {
     match (error.line, error.column) {
@@ -91,7 +148,7 @@ fn format_message(error: &LintError) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     #[test]
     fn test_lint_error_to_diagnostic() {
@@ -104,6 +161,8 @@ mod tests {
             context: Some("name".to_string()),
             help: Some("Policies must have a query field".to_string()),
             suggestion: Some("query: \"SELECT 1;\"".to_string()),
+            fixable: false,
+            related: Vec::new(),
         };
 
         let source = "policies:\n  - name: test\n    platform: darwin\n";
@@ -115,4 +174,30 @@ mod tests {
         assert!(diagnostic.message.contains("Help:"));
         assert!(diagnostic.data.is_some());
     }
+
+    #[test]
+    fn test_related_information() {
+        let error = LintError::error("Duplicate policy name: 'A'", "/repo/default.yml")
+            .with_related("/repo/default.yml", Some((2, 11)), "'A' first defined here")
+            .with_related("relative.yml", None, "not a URI, skipped");
+
+        let diagnostic = lint_error_to_diagnostic(&error, "");
+        let related = diagnostic.related_information.unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].location.uri.path(), "/repo/default.yml");
+        assert_eq!(related[0].location.range.start, Position { line: 1, character: 10 });
+        assert_eq!(related[0].message, "'A' first defined here");
+    }
+
+    #[test]
+    fn test_engine_error_to_diagnostic() {
+        let source = "policies:\n  - name: \"unclosed\n";
+        let error = crate::linter::Linter::new().lint_content(source, Path::new("default.yml")).unwrap_err();
+
+        let diagnostic = engine_error_to_diagnostic(&error, source);
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert!(diagnostic.message.starts_with("Failed to parse YAML: "));
+        assert!(!diagnostic.message.contains("default.yml"));
+        assert_ne!(diagnostic.range.start.line, 0);
+    }
 }
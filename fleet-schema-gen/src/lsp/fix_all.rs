@@ -0,0 +1,143 @@
+//! "Fix all auto-fixable problems" for a file or the whole workspace.
+//!
+//! Offered as a `source.fixAll.fleet` code action, which editors run on
+//! save (`"editor.codeActionsOnSave": { "source.fixAll.fleet": "explicit" }`),
+//! and as the `fleet/fixAll` request. Both apply the fixes `lint --fix`
+//! does, through [`Linter::fix_content`].
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier, Position, Range,
+    TextDocumentEdit, TextEdit, Url, WorkspaceEdit,
+};
+
+use super::position::byte_offset_to_utf16;
+use crate::linter::Linter;
+
+/// Code action kind of the fix-all action.
+pub const FIX_ALL_KIND: &str = "source.fixAll.fleet";
+
+/// Custom request applying every safe fix in a file or the workspace.
+pub const FIX_ALL_REQUEST: &str = "fleet/fixAll";
+
+/// Parameters of `fleet/fixAll`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixAllParams {
+    /// File to fix; the whole workspace when unset
+    #[serde(default)]
+    pub uri: Option<Url>,
+}
+
+/// Result of `fleet/fixAll`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixAllResult {
+    /// Problems fixed
+    pub fixed: usize,
+    /// Files changed
+    pub files: usize,
+    /// Whether the editor applied the edit
+    pub applied: bool,
+}
+
+/// Whether a code action request filtered by `only` wants `kind`.
+pub fn kind_requested(only: Option<&[CodeActionKind]>, kind: &CodeActionKind) -> bool {
+    only.is_none_or(|only| {
+        only.iter().any(|wanted| {
+            let wanted = wanted.as_str();
+            kind.as_str() == wanted || kind.as_str().strip_prefix(wanted).is_some_and(|rest| rest.starts_with('.'))
+        })
+    })
+}
+
+/// Edits fixing every safe problem in `content`, and how many problems
+/// they fix. Fixes never move lines, so each changed line is replaced.
+pub fn fix_edits(linter: &Linter, content: &str, path: &Path) -> (Vec<TextEdit>, usize) {
+    let Ok(result) = linter.fix_content(content, path) else {
+        return (Vec::new(), 0);
+    };
+    if !result.changed() {
+        return (Vec::new(), 0);
+    }
+    let edits = content
+        .lines()
+        .zip(result.content.lines())
+        .enumerate()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(idx, (before, after))| TextEdit {
+            range: Range {
+                start: Position::new(idx as u32, 0),
+                end: Position::new(idx as u32, byte_offset_to_utf16(before, before.len())),
+            },
+            new_text: after.to_string(),
+        })
+        .collect();
+    (edits, result.fixed)
+}
+
+/// A versioned edit of one document.
+pub fn document_edit(uri: Url, version: Option<i32>, edits: Vec<TextEdit>) -> TextDocumentEdit {
+    TextDocumentEdit {
+        text_document: OptionalVersionedTextDocumentIdentifier { uri, version },
+        edits: edits.into_iter().map(OneOf::Left).collect(),
+    }
+}
+
+/// The fix-all action for a document, if anything in it can be fixed.
+pub fn fix_all_action(linter: &Linter, uri: &Url, version: Option<i32>, content: &str, path: &Path) -> Option<CodeAction> {
+    let (edits, fixed) = fix_edits(linter, content, path);
+    if edits.is_empty() {
+        return None;
+    }
+    Some(CodeAction {
+        title: format!("Fix all auto-fixable problems ({})", fixed),
+        kind: Some(CodeActionKind::new(FIX_ALL_KIND)),
+        edit: Some(WorkspaceEdit {
+            changes: None,
+            document_changes: Some(DocumentChanges::Edits(vec![document_edit(uri.clone(), version, edits)])),
+            change_annotations: None,
+        }),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_requested() {
+        let kind = CodeActionKind::new(FIX_ALL_KIND);
+        assert!(kind_requested(None, &kind));
+        assert!(kind_requested(Some(&[CodeActionKind::SOURCE_FIX_ALL]), &kind));
+        assert!(kind_requested(Some(&[CodeActionKind::SOURCE]), &kind));
+        assert!(kind_requested(Some(std::slice::from_ref(&kind)), &kind));
+        assert!(!kind_requested(Some(&[CodeActionKind::QUICKFIX]), &kind));
+        assert!(!kind_requested(Some(&[CodeActionKind::new("source.fix")]), &kind));
+    }
+
+    #[test]
+    fn test_fix_all_action() {
+        let linter = Linter::new();
+        let uri = Url::parse("file:///repo/teams/t.yml").unwrap();
+        let content = "policies:\n  - name: Dïsk\n    query: SELECT 1;\n    platform: MacOS\n    critical: on\n";
+        let action = fix_all_action(&linter, &uri, Some(4), content, Path::new("/repo/teams/t.yml")).unwrap();
+        assert_eq!(action.kind, Some(CodeActionKind::new(FIX_ALL_KIND)));
+        assert!(action.title.contains("(2)"));
+
+        let Some(DocumentChanges::Edits(changes)) = action.edit.unwrap().document_changes else {
+            panic!("expected document edits");
+        };
+        assert_eq!(changes[0].text_document.version, Some(4));
+        let edits: Vec<_> = changes[0].edits.iter().map(|edit| match edit {
+            OneOf::Left(edit) => (edit.range.start.line, edit.range.end.character, edit.new_text.as_str()),
+            OneOf::Right(_) => panic!("unexpected annotated edit"),
+        }).collect();
+        assert_eq!(edits, vec![(3, 19, "    platform: darwin"), (4, 16, "    critical: true")]);
+
+        assert!(fix_all_action(&linter, &uri, None, "policies: []\n", Path::new("/repo/teams/t.yml")).is_none());
+    }
+}
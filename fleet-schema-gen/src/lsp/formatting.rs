@@ -0,0 +1,44 @@
+//! Range formatting: "Format Selection" on `query:` fields.
+//!
+//! Formats the SQL of every `query:` field the range touches (see
+//! [`crate::linter::sql_format`]); the rest of the document is left as is.
+//! Whole-document formatting isn't offered, so saving never reformats.
+
+use tower_lsp::lsp_types::{Position, Range, TextEdit};
+
+use crate::linter::sql_format::query_edits;
+
+/// Edits formatting the queries whose lines overlap `range`.
+pub fn range_formatting_edits(content: &str, range: Range) -> Vec<TextEdit> {
+    let (first, last) = (range.start.line as usize, range.end.line as usize);
+    query_edits(content)
+        .into_iter()
+        .filter(|edit| edit.start_line <= last && first < edit.end_line)
+        .map(|edit| TextEdit {
+            range: Range {
+                start: Position::new(edit.start_line as u32, 0),
+                end: Position::new(edit.end_line as u32, 0),
+            },
+            new_text: edit.text,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_formatting_edits() {
+        let content = "policies:\n  - name: A\n    query: select 1 from a where x = 1 and y = 2\n  - name: B\n    query: |\n      select 1\n      from b\n";
+        let range = |start, end| Range { start: Position::new(start, 0), end: Position::new(end, 0) };
+
+        let edits = range_formatting_edits(content, range(5, 5));
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range, range(4, 7));
+        assert_eq!(edits[0].new_text, "    query: |\n      SELECT 1\n      FROM b\n");
+
+        assert_eq!(range_formatting_edits(content, range(0, 6)).len(), 2);
+        assert!(range_formatting_edits(content, range(0, 1)).is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+//! Randomized robustness tests for the YAML front-end.
+//!
+//! Documents from the corpus (`testdata/corpus`) and a few inline samples
+//! are mutated (re-indented, truncated, spliced with unicode and YAML
+//! punctuation) and run through everything the server does with an open
+//! document. None of it may panic, whatever the input or cursor position.
+//!
+//! Runs are deterministic. `FLEET_FUZZ_SEED` picks another seed and
+//! `FLEET_FUZZ_ITERATIONS` the number of documents (default 200); a
+//! failure prints the seed and the document that caused it.
+
+use super::code_lens::query_code_lenses;
+use super::completion::{complete_at, resolve_completion_item};
+use super::diagnostics::lint_error_to_diagnostic;
+use super::hover::hover_in_file;
+use super::selection::selection_ranges;
+use super::semantic_tokens::compute_semantic_tokens;
+use super::summary::effective_platforms;
+use super::symbols::document_symbols;
+use super::workspace::{extract_path_references, validate_path_references};
+use crate::linter::Linter;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::{Position, Url};
+
+const SAMPLES: &[&str] = &[
+    "policies:\n  - name: Disk encryption\n    query: SELECT 1 FROM disk_encryption WHERE encrypted = 1;\n    platform: darwin\n",
+    "queries:\n  - name: Uptime\n    query: SELECT * FROM uptime;\n    interval: 3600\n    logging: snapshot\n",
+    "name: Workstations\npolicies:\n  - path: ../lib/policies.yml\nsoftware:\n  packages:\n    - path: ../lib/software/slack.yml\n",
+    "- name: Läufer 🏃\n  query: \"SELECT * FROM users WHERE username = 'ß';\"\n  platform: linux,windows\n",
+    "controls:\n  macos_settings:\n    custom_settings:\n      - path: ../lib/profiles/wifi.mobileconfig\n",
+    "agent_options:\n  config:\n    options:\n      distributed_interval: 10\n",
+];
+
+/// Fragments spliced into documents
+const FRAGMENTS: &[&str] = &[
+    ":", ": ", "- ", "  ", "\t", "\n", "\r\n", "[", "]", "{", "}", "\"", "'", "|", ">-", "&a", "*a", "!!str",
+    "#", "%", "---", "...", "é", "ß", "日本語", "🏃", "👨‍👩‍👧", "e\u{301}", "\u{200b}", "\u{feff}", "\u{202e}",
+    "path:", "query:", "platform:", "name:", "SELECT", "$FLEET_VAR_", "${", "\\",
+];
+
+/// xorshift64*; enough for picking mutations
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        if n == 0 { 0 } else { (self.next() % n as u64) as usize }
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+fn env_number(name: &str) -> Option<u64> {
+    std::env::var(name).ok()?.parse().ok()
+}
+
+/// A char boundary in `s`
+fn boundary(rng: &mut Rng, s: &str) -> usize {
+    let boundaries: Vec<usize> = s.char_indices().map(|(i, _)| i).chain([s.len()]).collect();
+    *rng.pick(&boundaries)
+}
+
+fn mutate(rng: &mut Rng, doc: &str) -> String {
+    let mut doc = doc.to_string();
+    for _ in 0..=rng.below(4) {
+        match rng.below(6) {
+            // Insert a fragment
+            0 => {
+                let at = boundary(rng, &doc);
+                let fragment = *rng.pick(FRAGMENTS);
+                doc.insert_str(at, fragment);
+            }
+            // Delete a range
+            1 => {
+                let (a, b) = (boundary(rng, &doc), boundary(rng, &doc));
+                doc.replace_range(a.min(b)..a.max(b), "");
+            }
+            // Truncate
+            2 => {
+                let at = boundary(rng, &doc);
+                doc.truncate(at);
+            }
+            // Re-indent a line with spaces or tabs
+            3 => {
+                let mut lines: Vec<String> = doc.lines().map(str::to_string).collect();
+                if !lines.is_empty() {
+                    let idx = rng.below(lines.len());
+                    let indent = if rng.below(4) == 0 { "\t" } else { " " }.repeat(rng.below(9));
+                    lines[idx] = format!("{}{}", indent, lines[idx].trim_start());
+                }
+                doc = lines.join("\n");
+            }
+            // Duplicate or swap lines
+            4 => {
+                let mut lines: Vec<&str> = doc.lines().collect();
+                if !lines.is_empty() {
+                    let (a, b) = (rng.below(lines.len()), rng.below(lines.len()));
+                    if rng.below(2) == 0 {
+                        lines.swap(a, b);
+                    } else {
+                        lines.insert(b, lines[a]);
+                    }
+                }
+                doc = lines.join("\n");
+            }
+            // Splice in part of a sample
+            _ => {
+                let sample = rng.pick(SAMPLES);
+                let (a, b) = (boundary(rng, sample), boundary(rng, sample));
+                let at = boundary(rng, &doc);
+                doc.insert_str(at, &sample[a.min(b)..a.max(b)]);
+            }
+        }
+    }
+    doc
+}
+
+/// Cursor positions to probe: every line start and end, and random spots
+/// including past the end of lines and of the document
+fn positions(rng: &mut Rng, doc: &str) -> Vec<Position> {
+    let lines: Vec<&str> = doc.lines().collect();
+    let mut positions = vec![Position::new(lines.len() as u32 + 2, 0)];
+    for (idx, line) in lines.iter().enumerate() {
+        let utf16 = line.encode_utf16().count() as u32;
+        positions.push(Position::new(idx as u32, 0));
+        positions.push(Position::new(idx as u32, utf16));
+        positions.push(Position::new(idx as u32, rng.below(line.len() + 4) as u32));
+    }
+    positions
+}
+
+/// Everything the server computes for an open document
+fn analyze(linter: &Linter, doc: &str, file: &Path, positions: &[Position]) {
+    if let Ok(report) = linter.lint_content(doc, file) {
+        for error in report.errors.iter().chain(&report.warnings).chain(&report.infos) {
+            lint_error_to_diagnostic(error, doc);
+        }
+    }
+    validate_path_references(doc, file, file.parent());
+    extract_path_references(doc, file);
+    compute_semantic_tokens(doc);
+    document_symbols(doc);
+    effective_platforms(doc);
+    let uri = Url::from_file_path(file).unwrap();
+    query_code_lenses(doc, &uri);
+    selection_ranges(doc, positions);
+    for &position in positions {
+        hover_in_file(doc, position, Some(file));
+        for item in complete_at(doc, position).into_iter().take(3) {
+            resolve_completion_item(item);
+        }
+    }
+}
+
+/// Corpus documents plus the inline samples, with the file name each is linted as
+fn seeds() -> Vec<(PathBuf, String)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/corpus");
+    let mut seeds: Vec<(PathBuf, String)> = super::corpus::corpus_files(&root)
+        .into_iter()
+        .filter_map(|file| Some((file.clone(), std::fs::read_to_string(&file).ok()?)))
+        .collect();
+    for (idx, sample) in SAMPLES.iter().enumerate() {
+        seeds.push((root.join(format!("sample-{}/teams/sample.yml", idx)), sample.to_string()));
+    }
+    seeds
+}
+
+#[test]
+fn test_front_end_never_panics() {
+    let seed = env_number("FLEET_FUZZ_SEED").unwrap_or(0x5eed_f1ee7);
+    let iterations = env_number("FLEET_FUZZ_ITERATIONS").unwrap_or(200);
+    let mut rng = Rng(seed.max(1));
+    let linter = Linter::new();
+    let seeds = seeds();
+
+    for iteration in 0..iterations {
+        let (file, original) = rng.pick(&seeds);
+        let doc = mutate(&mut rng, original);
+        let positions = positions(&mut rng, &doc);
+
+        let result = catch_unwind(AssertUnwindSafe(|| analyze(&linter, &doc, file, &positions)));
+        if result.is_err() {
+            panic!(
+                "panicked on iteration {} (FLEET_FUZZ_SEED={}) for {} with document:\n{:?}",
+                iteration,
+                seed,
+                file.display(),
+                doc
+            );
+        }
+    }
+}
+
+#[test]
+fn test_unicode_and_indentation_edge_cases() {
+    let linter = Linter::new();
+    let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/corpus/edge.yml");
+    let docs = [
+        "",
+        "\n\n\n",
+        "\u{feff}policies: []\n",
+        "policies:\n\t- name: tab\n",
+        "policies:\n  - name: 日本語🏃\n    query: SELECT 'é';\n",
+        "policies:\n  - name: \"unclosed\n",
+        "- - - - -\n",
+        "key: |\n  block\n    more\n less\n",
+        "a: &anchor\n  b: *anchor\n  c: *missing\n",
+        "policies:\r\n  - name: crlf\r\n    platform: darwin\r\n",
+        "\u{202e}policies:\u{200b}\n  - path: ./\u{301}.yml\n",
+    ];
+    let mut rng = Rng(1);
+    for doc in docs {
+        let positions = positions(&mut rng, doc);
+        analyze(&linter, doc, &file, &positions);
+    }
+}
@@ -4,11 +4,22 @@
 
 use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range};
 
-use super::schema::{get_field_doc, get_logging_doc, get_platform_doc, FIELD_DOCS};
-use crate::linter::osquery::OSQUERY_TABLES;
+use super::schema::{field_docs, get_field_doc, get_logging_doc, get_platform_doc};
+use crate::linter::osquery::{COLUMN_VERSIONS, OSQUERY_TABLES, TABLE_VERSIONS};
+use crate::linter::sql_functions::sql_function;
+use crate::linter::file_role::FileRole;
+use crate::linter::profiles::parse_mobileconfig;
+use std::path::Path;
 
 /// Provide hover information at a position in a Fleet YAML document.
 pub fn hover_at(source: &str, position: Position) -> Option<Hover> {
+    hover_in_file(source, position, None)
+}
+
+/// Provide hover information, using the file's name and location to tell
+/// what a lib file without top-level keys contains (see [`FileRole`]).
+pub fn hover_in_file(source: &str, position: Position, file_path: Option<&Path>) -> Option<Hover> {
+    let role = file_path.and_then(FileRole::detect);
     let line_idx = position.line as usize;
     let col_idx = position.character as usize;
 
@@ -19,7 +30,8 @@ pub fn hover_at(source: &str, position: Position) -> Option<Hover> {
     let (word, word_start, word_end) = find_word_at(line, col_idx)?;
 
     // Determine context from line content and build appropriate hover
-    let hover_content = determine_hover_content(source, line_idx, line, &word)?;
+    let is_call = line.chars().skip(word_end).find(|c| !c.is_whitespace()) == Some('(');
+    let hover_content = determine_hover_content(source, line_idx, line, &word, is_call, role)?;
 
     Some(Hover {
         contents: HoverContents::Markup(MarkupContent {
@@ -39,6 +51,51 @@ pub fn hover_at(source: &str, position: Position) -> Option<Hover> {
     })
 }
 
+/// Summarize the `.mobileconfig` referenced by a `custom_settings` path.
+///
+/// `file_path` is the document being hovered; profile paths are resolved
+/// relative to its directory.
+pub fn profile_hover(source: &str, position: Position, file_path: &Path) -> Option<Hover> {
+    let line_idx = position.line as usize;
+    let line = source.lines().nth(line_idx)?;
+    let trimmed = line.trim().trim_start_matches('-').trim();
+
+    let value = trimmed.strip_prefix("path:")?.trim().trim_matches('"').trim_matches('\'');
+    if !value.ends_with(".mobileconfig")
+        || !determine_full_yaml_context(source, line_idx, FileRole::detect(file_path)).ends_with("custom_settings")
+    {
+        return None;
+    }
+
+    let value_start = line.find(value)?;
+    let value_end = value_start + value.len();
+    let col = position.character as usize;
+    if col < value_start || col > value_end {
+        return None;
+    }
+
+    let profile = file_path.parent().unwrap_or(Path::new(".")).join(value);
+    let summary = parse_mobileconfig(&std::fs::read(&profile).ok()?)?;
+    let file_name = profile.file_name()?.to_string_lossy();
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: summary.to_markdown(&file_name),
+        }),
+        range: Some(Range {
+            start: Position {
+                line: position.line,
+                character: value_start as u32,
+            },
+            end: Position {
+                line: position.line,
+                character: value_end as u32,
+            },
+        }),
+    })
+}
+
 /// Find the word at a given column position in a line.
 /// Returns (word, start_col, end_col).
 fn find_word_at(line: &str, col: usize) -> Option<(String, usize, usize)> {
@@ -96,9 +153,16 @@ fn find_word_at(line: &str, col: usize) -> Option<(String, usize, usize)> {
 }
 
 /// Determine the hover content based on context.
-fn determine_hover_content(source: &str, line_idx: usize, line: &str, word: &str) -> Option<String> {
+fn determine_hover_content(
+    source: &str,
+    line_idx: usize,
+    line: &str,
+    word: &str,
+    is_call: bool,
+    role: Option<FileRole>,
+) -> Option<String> {
     // Determine context by looking at surrounding lines
-    let context = determine_full_yaml_context(source, line_idx);
+    let context = determine_full_yaml_context(source, line_idx, role);
 
     // Check if this is a YAML key (followed by colon)
     let is_key = line.contains(&format!("{}:", word));
@@ -114,7 +178,7 @@ fn determine_hover_content(source: &str, line_idx: usize, line: &str, word: &str
         }
 
         // Try with simpler context (e.g., "software" instead of "software.packages")
-        let simple_context = determine_yaml_context(source, line_idx);
+        let simple_context = determine_yaml_context(source, line_idx, role);
         let simple_path = format!("{}.{}", simple_context, word);
         if let Some(doc) = get_field_doc(&simple_path) {
             return Some(doc.to_markdown());
@@ -145,19 +209,18 @@ fn determine_hover_content(source: &str, line_idx: usize, line: &str, word: &str
         }
     }
 
-    // Check if it might be an osquery table name (in SQL context)
+    // Check if it might be a function or an osquery table name (in SQL context)
     if is_sql_context(source, line_idx, line) {
-        if let Some(table_info) = OSQUERY_TABLES.get(word) {
-            let platforms = table_info.platforms.join(", ");
-            return Some(format!(
-                "**{}** (osquery table)\n\n{}\n\n**Platforms:** {}",
-                word, table_info.description, platforms
-            ));
+        if let Some(function) = sql_function(word).filter(|_| is_call) {
+            return Some(function.to_markdown());
+        }
+        if let Some(doc) = osquery_table_doc(word) {
+            return Some(doc);
         }
     }
 
     // Fallback: try to find any matching field doc
-    for (path, doc) in FIELD_DOCS.iter() {
+    for (path, doc) in field_docs().iter() {
         if path.ends_with(word) {
             return Some(doc.to_markdown());
         }
@@ -166,8 +229,35 @@ fn determine_hover_content(source: &str, line_idx: usize, line: &str, word: &str
     None
 }
 
+/// Markdown documentation for an osquery table, with version requirements.
+pub(crate) fn osquery_table_doc(name: &str) -> Option<String> {
+    let table_info = OSQUERY_TABLES.get(name)?;
+    let platforms = table_info.platforms.join(", ");
+    let mut doc = format!(
+        "**{}** (osquery table)\n\n{}\n\n**Platforms:** {}",
+        name, table_info.description, platforms
+    );
+    if let Some(version) = TABLE_VERSIONS.get(name) {
+        doc.push_str(&format!("\n\n**Requires:** osquery >= {}", version));
+    }
+
+    let mut columns: Vec<String> = COLUMN_VERSIONS
+        .iter()
+        .filter(|((table, _), _)| *table == name)
+        .map(|((_, column), version)| format!("`{}` (osquery >= {})", column, version))
+        .collect();
+    if !columns.is_empty() {
+        columns.sort();
+        doc.push_str(&format!("\n\n**Newer columns:** {}", columns.join(", ")));
+    }
+    Some(doc)
+}
+
 /// Determine the YAML context (policies, queries, labels, etc.) at a line.
-fn determine_yaml_context(source: &str, line_idx: usize) -> &'static str {
+///
+/// Lib files without top-level keys fall back to their role, then to their
+/// structure.
+fn determine_yaml_context(source: &str, line_idx: usize, role: Option<FileRole>) -> &'static str {
     let lines: Vec<&str> = source.lines().collect();
 
     // Look backwards for context-defining lines
@@ -199,7 +289,7 @@ fn determine_yaml_context(source: &str, line_idx: usize) -> &'static str {
     // If no context found, try to infer from file structure
     // lib/ files often contain standalone policy/query definitions (list of items)
     // Check if file starts with "- name:" which indicates a list of policies/queries
-    if let Some(context) = infer_context_from_structure(source) {
+    if let Some(context) = role.map(FileRole::section).or_else(|| infer_context_from_structure(source)) {
         return context;
     }
 
@@ -284,7 +374,7 @@ fn infer_context_from_structure(source: &str) -> Option<&'static str> {
 }
 
 /// Determine the full YAML context path (e.g., "software.packages") at a line.
-fn determine_full_yaml_context(source: &str, line_idx: usize) -> String {
+fn determine_full_yaml_context(source: &str, line_idx: usize, role: Option<FileRole>) -> String {
     let lines: Vec<&str> = source.lines().collect();
     let mut path_parts: Vec<&str> = Vec::new();
     let mut last_indent: i32 = -1;
@@ -325,7 +415,7 @@ fn determine_full_yaml_context(source: &str, line_idx: usize) -> String {
 
     // If we found no parent context (lib file with direct list), infer from structure
     if path_parts.is_empty() {
-        if let Some(inferred) = infer_context_from_structure(source) {
+        if let Some(inferred) = role.map(FileRole::section).or_else(|| infer_context_from_structure(source)) {
             return inferred.to_string();
         }
     }
@@ -429,8 +519,8 @@ mod tests {
     #[test]
     fn test_determine_yaml_context() {
         let source = "policies:\n  - name: test\n    platform: darwin";
-        assert_eq!(determine_yaml_context(source, 1), "policies");
-        assert_eq!(determine_yaml_context(source, 2), "policies");
+        assert_eq!(determine_yaml_context(source, 1, None), "policies");
+        assert_eq!(determine_yaml_context(source, 2, None), "policies");
     }
 
     #[test]
@@ -475,6 +565,75 @@ mod tests {
         assert!(content.contains("processes") || content.contains("osquery"));
     }
 
+    #[test]
+    fn test_hover_sql_function() {
+        let content = |source: &str, character: u32| match hover_at(source, Position { line: 2, character })?.contents {
+            HoverContents::Markup(m) => Some(m.value),
+            _ => None,
+        };
+        let source = "queries:\n  - name: test\n    query: SELECT SPLIT(path, '/', 1), time FROM time";
+        let split = content(source, 20).unwrap();
+        assert!(split.starts_with("**split** (osquery function)"));
+        assert!(split.contains("split(string, tokens, index)"));
+
+        // `time` is a table (and a column) unless it's called
+        assert!(content(source, 38).unwrap().contains("(osquery table)"));
+        let source = "queries:\n  - name: test\n    query: |\n      SELECT time ('now') FROM uptime";
+        let hover = hover_at(source, Position { line: 3, character: 14 }).unwrap();
+        assert!(matches!(hover.contents, HoverContents::Markup(m) if m.value.contains("SQLite date and time function")));
+    }
+
+    #[test]
+    fn test_hover_osquery_table_version() {
+        let source = "queries:\n  - name: test\n    query: SELECT * FROM windows_firewall_rules";
+        let hover = hover_at(source, Position { line: 2, character: 30 }).unwrap();
+        let content = match hover.contents {
+            HoverContents::Markup(m) => m.value,
+            _ => panic!("Expected markup content"),
+        };
+        assert!(content.contains("**Requires:** osquery >= 5.6.0"));
+    }
+
+    #[test]
+    fn test_profile_hover() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        std::fs::write(
+            root.join("lib/firewall.mobileconfig"),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+  <key>PayloadContent</key>
+  <array>
+    <dict>
+      <key>PayloadType</key>
+      <string>com.apple.security.firewall</string>
+    </dict>
+  </array>
+  <key>PayloadDisplayName</key>
+  <string>Firewall</string>
+</dict>
+</plist>
+"#,
+        )
+        .unwrap();
+
+        let source = "controls:\n  macos_settings:\n    custom_settings:\n      - path: ./lib/firewall.mobileconfig\n";
+        let team_file = root.join("team.yml");
+
+        let hover = profile_hover(source, Position { line: 3, character: 20 }, &team_file).unwrap();
+        if let HoverContents::Markup(content) = hover.contents {
+            assert!(content.value.contains("**Firewall** (configuration profile)"));
+            assert!(content.value.contains("`com.apple.security.firewall`"));
+        } else {
+            panic!("Expected markup content");
+        }
+
+        // Cursor on the key, not the path
+        assert!(profile_hover(source, Position { line: 3, character: 9 }, &team_file).is_none());
+    }
+
     #[test]
     fn test_extract_key_from_line() {
         assert_eq!(extract_key_from_line("  platform: darwin"), Some("platform".to_string()));
@@ -553,6 +712,10 @@ mod tests {
   query: SELECT 1"#;
 
         // Should detect as "policies" context
-        assert_eq!(determine_yaml_context(source, 1), "policies");
+        assert_eq!(determine_yaml_context(source, 1, None), "policies");
+
+        // The file's role wins over its structure
+        assert_eq!(determine_yaml_context(source, 1, Some(FileRole::Queries)), "queries");
+        assert_eq!(determine_full_yaml_context("", 0, Some(FileRole::Queries)), "queries");
     }
 }
@@ -0,0 +1,304 @@
+//! Protocol-level tests with a scripted client.
+//!
+//! Each fixture in `testdata/lsp` is a JSON script: the files of a
+//! workspace, then steps sent to the server over in-memory pipes with the
+//! responses and notifications expected back. The format is described in
+//! `testdata/lsp/README.md`.
+
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, DuplexStream, ReadHalf, WriteHalf};
+use tower_lsp::lsp_types::Url;
+
+/// How long to wait for any one message from the server
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Client end of a server running over an in-memory pipe
+struct ScriptedClient {
+    reader: BufReader<ReadHalf<DuplexStream>>,
+    writer: WriteHalf<DuplexStream>,
+    next_id: i64,
+    /// Notifications and server requests no step has waited for yet
+    received: VecDeque<Value>,
+}
+
+impl ScriptedClient {
+    fn start() -> Self {
+        let (client, server) = tokio::io::duplex(1 << 20);
+        let (server_read, server_write) = tokio::io::split(server);
+        tokio::spawn(super::serve(server_read, server_write));
+
+        let (reader, writer) = tokio::io::split(client);
+        Self { reader: BufReader::new(reader), writer, next_id: 1, received: VecDeque::new() }
+    }
+
+    async fn send(&mut self, message: Value) {
+        let body = message.to_string();
+        let frame = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        self.writer.write_all(frame.as_bytes()).await.unwrap();
+        self.writer.flush().await.unwrap();
+    }
+
+    async fn read(&mut self) -> Value {
+        let mut length = None;
+        loop {
+            let mut line = String::new();
+            assert!(self.reader.read_line(&mut line).await.unwrap() > 0, "server closed the connection");
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(value) = line.strip_prefix("Content-Length:") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+        let mut body = vec![0; length.expect("message without Content-Length")];
+        self.reader.read_exact(&mut body).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    /// Next message from the server. Requests from the server are answered
+    /// the way an editor would (edits applied, configuration unset).
+    async fn receive(&mut self) -> Value {
+        let message = tokio::time::timeout(TIMEOUT, self.read()).await.expect("timed out waiting for the server");
+        if let (Some(id), Some(method)) = (message.get("id"), message["method"].as_str()) {
+            let result = match method {
+                "workspace/configuration" => {
+                    Value::Array(vec![Value::Null; message["params"]["items"].as_array().map_or(0, Vec::len)])
+                }
+                "workspace/applyEdit" => json!({ "applied": true }),
+                _ => Value::Null,
+            };
+            self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result })).await;
+        }
+        message
+    }
+
+    /// Send a request and return its response (with `result` or `error`)
+    async fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).await;
+        loop {
+            let message = self.receive().await;
+            if message.get("method").is_none() && message["id"] == id {
+                return message;
+            }
+            self.received.push_back(message);
+        }
+    }
+
+    async fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params })).await;
+    }
+
+    /// Params of the first notification (or server request) for `method`
+    /// whose params match `filter`, waiting for it if needed
+    async fn wait_for(&mut self, method: &str, filter: &Value) -> Value {
+        let wanted = |message: &Value| message["method"] == method && check(filter, &message["params"], "").is_ok();
+        if let Some(idx) = self.received.iter().position(wanted) {
+            return self.received.remove(idx).unwrap()["params"].take();
+        }
+        loop {
+            let mut message = self.receive().await;
+            if wanted(&message) {
+                return message["params"].take();
+            }
+            self.received.push_back(message);
+        }
+    }
+}
+
+/// Check that `actual` has everything in `expected`: objects may have more
+/// keys, arrays must contain a match for each expected element (an empty
+/// array only matches an empty one), and strings starting with `~` match
+/// any string containing the rest.
+fn check(expected: &Value, actual: &Value, path: &str) -> Result<(), String> {
+    let mismatch = || Err(format!("at {}: expected {}, got {}", if path.is_empty() { "/" } else { path }, expected, actual));
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                let path = format!("{}/{}", path, key);
+                check(value, actual.get(key).unwrap_or(&Value::Null), &path)?;
+            }
+            Ok(())
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            if expected.is_empty() && !actual.is_empty() {
+                return mismatch();
+            }
+            for (idx, item) in expected.iter().enumerate() {
+                let path = format!("{}/{}", path, idx);
+                if !actual.iter().any(|candidate| check(item, candidate, &path).is_ok()) {
+                    return Err(format!("at {}: no element matches {}, got {}", path, item, Value::Array(actual.clone())));
+                }
+            }
+            Ok(())
+        }
+        (Value::String(expected), Value::String(actual)) if expected.starts_with('~') => {
+            if actual.contains(&expected[1..]) { Ok(()) } else { mismatch() }
+        }
+        _ if expected == actual => Ok(()),
+        _ => mismatch(),
+    }
+}
+
+/// Replace `${root}` (workspace URI) and `${rootPath}` in every string
+fn substitute(value: &mut Value, root_uri: &str, root_path: &str) {
+    match value {
+        Value::String(s) => *s = s.replace("${rootPath}", root_path).replace("${root}", root_uri),
+        Value::Array(items) => items.iter_mut().for_each(|v| substitute(v, root_uri, root_path)),
+        Value::Object(map) => map.values_mut().for_each(|v| substitute(v, root_uri, root_path)),
+        _ => {}
+    }
+}
+
+/// Replace `"$saved:<name>/<pointer>"` strings with part of a saved message
+fn resolve_saved(value: &Value, saved: &HashMap<String, Value>) -> Value {
+    match value {
+        Value::String(s) if s.starts_with("$saved:") => {
+            let reference = &s["$saved:".len()..];
+            let (name, pointer) = reference.split_once('/').unwrap_or((reference, ""));
+            let message = saved.get(name).unwrap_or_else(|| panic!("nothing saved as '{}'", name));
+            let pointer = if pointer.is_empty() { String::new() } else { format!("/{}", pointer) };
+            message.pointer(&pointer).cloned().unwrap_or_else(|| panic!("{} has nothing at {}", name, pointer))
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| resolve_saved(v, saved)).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), resolve_saved(v, saved))).collect()),
+        other => other.clone(),
+    }
+}
+
+fn expect(expected: Option<&Value>, actual: &Value, context: &str) {
+    if let Some(expected) = expected {
+        if let Err(e) = check(expected, actual, "") {
+            panic!("{}: {}\nfull value: {}", context, e, serde_json::to_string_pretty(actual).unwrap());
+        }
+    }
+}
+
+/// Client capabilities used unless a fixture gives its own
+fn default_capabilities() -> Value {
+    json!({
+        "workspace": {
+            "applyEdit": true,
+            "workspaceEdit": { "documentChanges": true, "resourceOperations": ["create"] }
+        },
+        "textDocument": {
+            "completion": { "completionItem": { "snippetSupport": true } },
+            "hover": { "contentFormat": ["markdown", "plaintext"] }
+        }
+    })
+}
+
+async fn run_fixture(path: &Path) {
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    let mut fixture: Value = serde_json::from_str(&fs::read_to_string(path).unwrap())
+        .unwrap_or_else(|e| panic!("{}: invalid fixture: {}", name, e));
+
+    let dir = tempfile::tempdir().unwrap();
+    let root_uri = Url::from_file_path(dir.path()).unwrap().to_string();
+    substitute(&mut fixture, &root_uri, &dir.path().display().to_string());
+
+    let files: HashMap<String, String> = serde_json::from_value(fixture["files"].clone()).unwrap_or_default();
+    for (file, content) in &files {
+        let file = dir.path().join(file);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+    }
+    let uri = |file: &str| format!("{}/{}", root_uri, file);
+
+    let mut client = ScriptedClient::start();
+    let capabilities = fixture.get("capabilities").cloned().unwrap_or_else(default_capabilities);
+    let response = client
+        .request("initialize", json!({
+            "processId": null,
+            "rootUri": root_uri,
+            "capabilities": capabilities,
+            "initializationOptions": fixture.get("initializationOptions"),
+        }))
+        .await;
+    expect(fixture.get("initializeResult"), &response["result"], &format!("{}: initialize", name));
+    client.notify("initialized", json!({})).await;
+
+    let mut saved: HashMap<String, Value> = HashMap::new();
+    let steps = fixture["steps"].as_array().cloned().unwrap_or_default();
+    for (idx, step) in steps.iter().enumerate() {
+        let context = format!("{}: step {} {}", name, idx + 1, step);
+        let params = resolve_saved(step.get("params").unwrap_or(&Value::Null), &saved);
+
+        let result = if let Some(file) = step["open"].as_str() {
+            let text = step["text"].as_str().map(str::to_string).or_else(|| files.get(file).cloned()).unwrap_or_default();
+            client
+                .notify("textDocument/didOpen", json!({
+                    "textDocument": { "uri": uri(file), "languageId": "yaml", "version": 1, "text": text }
+                }))
+                .await;
+            continue;
+        } else if let Some(file) = step["change"].as_str() {
+            client
+                .notify("textDocument/didChange", json!({
+                    "textDocument": { "uri": uri(file), "version": step["version"].as_i64().unwrap_or(2) },
+                    "contentChanges": [{ "text": step["text"] }]
+                }))
+                .await;
+            continue;
+        } else if let Some(method) = step["notify"].as_str() {
+            client.notify(method, params).await;
+            continue;
+        } else if let Some(method) = step["request"].as_str() {
+            let response = client.request(method, params).await;
+            match step.get("expectError") {
+                Some(expected) => expect(Some(expected), &response["error"], &context),
+                None => assert!(response.get("error").is_none(), "{}: error response {}", context, response["error"]),
+            }
+            response["result"].clone()
+        } else if let Some(method) = step["await"].as_str() {
+            let filter = resolve_saved(step.get("match").unwrap_or(&json!({})), &saved);
+            client.wait_for(method, &filter).await
+        } else {
+            panic!("{}: unknown step", context);
+        };
+
+        expect(step.get("expect"), &result, &context);
+        if let Some(save) = step["save"].as_str() {
+            saved.insert(save.to_string(), result);
+        }
+    }
+
+    client.request("shutdown", Value::Null).await;
+    client.notify("exit", Value::Null).await;
+}
+
+#[test]
+fn test_check() {
+    let actual = json!({ "items": [{ "label": "darwin", "kind": 12 }, { "label": "linux" }], "extra": true });
+    assert!(check(&json!({ "items": [{ "label": "linux" }] }), &actual, "").is_ok());
+    assert!(check(&json!({ "items": [{ "label": "~dar" }] }), &actual, "").is_ok());
+    assert!(check(&json!({ "items": [] }), &actual, "").is_err());
+    assert!(check(&json!({ "missing": null }), &actual, "").is_ok());
+    assert_eq!(
+        check(&json!({ "items": [{ "label": "windows" }] }), &actual, "").unwrap_err().split(':').next(),
+        Some("at /items/0")
+    );
+}
+
+#[tokio::test]
+async fn test_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/lsp");
+    let mut fixtures: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "no fixtures in {}", dir.display());
+
+    for fixture in fixtures {
+        run_fixture(&fixture).await;
+    }
+}
@@ -5,30 +5,58 @@
 
 pub mod backend;
 pub mod code_actions;
+pub mod code_lens;
 pub mod completion;
 pub mod diagnostics;
+pub mod fix_all;
+pub mod formatting;
 pub mod hover;
 pub mod position;
 pub mod schema;
+pub mod selection;
+pub mod signature_help;
 pub mod semantic_tokens;
+pub mod store;
+pub mod summary;
 pub mod symbols;
 pub mod workspace;
+pub mod workspace_edit;
+
+#[cfg(test)]
+mod corpus;
+#[cfg(test)]
+mod fuzz;
+#[cfg(test)]
+mod integration;
 
 use anyhow::Result;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tower_lsp::{LspService, Server};
 
 use backend::FleetLspBackend;
+use fix_all::FIX_ALL_REQUEST;
+use workspace_edit::PREVIEW_EDIT_REQUEST;
 use crate::linter::Linter;
 
 /// Start the LSP server using stdio transport.
 ///
 /// This function blocks until the client disconnects.
 pub async fn start_server() -> Result<()> {
-    let stdin = tokio::io::stdin();
-    let stdout = tokio::io::stdout();
+    serve(tokio::io::stdin(), tokio::io::stdout()).await;
+    Ok(())
+}
 
-    let (service, socket) = LspService::new(|client| FleetLspBackend::new(client, Linter::new()));
+/// Serve the protocol over any pair of streams until the client
+/// disconnects (stdio for editors, in-memory pipes in tests).
+pub async fn serve<I, O>(input: I, output: O)
+where
+    I: AsyncRead + Unpin,
+    O: AsyncWrite,
+{
+    let (service, socket) = LspService::build(|client| FleetLspBackend::new(client, Linter::new()))
+        .custom_method(PREVIEW_EDIT_REQUEST, FleetLspBackend::preview_workspace_edit)
+        .custom_method(FIX_ALL_REQUEST, FleetLspBackend::fix_all)
+        .finish();
 
-    Server::new(stdin, stdout, socket).serve(service).await;
-    Ok(())
+    Server::new(input, output, socket).serve(service).await;
 }
@@ -42,8 +42,41 @@ pub fn to_lsp_position(line: usize, col: usize, source: &str) -> Position {
     }
 }
 
+/// Convert an LSP Position (UTF-16 columns) to a byte offset in `source`,
+/// clamped to the end of its line and of the source.
+pub fn to_byte_offset(source: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match source[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return source.len(),
+        }
+    }
+    let line_end = source[line_start..].find('\n').map(|i| line_start + i).unwrap_or(source.len());
+
+    let mut utf16 = 0u32;
+    for (offset, c) in source[line_start..line_end].char_indices() {
+        if utf16 >= position.character {
+            return line_start + offset;
+        }
+        utf16 += c.len_utf16() as u32;
+    }
+    line_end
+}
+
+/// Move a byte column inside a character of `line` back to the start of
+/// that character; columns past the end are returned as is. Cursor columns
+/// are UTF-16 units, so on lines with multibyte text they can land
+/// mid-character.
+pub fn char_boundary(line: &str, mut col: usize) -> usize {
+    while col < line.len() && !line.is_char_boundary(col) {
+        col -= 1;
+    }
+    col
+}
+
 /// Convert a byte offset within a line to UTF-16 code units.
-fn byte_offset_to_utf16(line: &str, byte_offset: usize) -> u32 {
+pub(crate) fn byte_offset_to_utf16(line: &str, byte_offset: usize) -> u32 {
     let mut utf16_offset = 0u32;
     let mut current_byte = 0usize;
 
@@ -138,6 +171,18 @@ mod tests {
         assert_eq!(find_yaml_key(source, "query", 0), Some((3, 5)));
     }
 
+    #[test]
+    fn test_to_byte_offset() {
+        let source = "a: 1\nname: 👋 hi\n";
+        assert_eq!(to_byte_offset(source, Position { line: 0, character: 3 }), 3);
+        assert_eq!(to_byte_offset(source, Position { line: 1, character: 0 }), 5);
+        // After the emoji (2 UTF-16 units, 4 bytes)
+        assert_eq!(to_byte_offset(source, Position { line: 1, character: 8 }), 15);
+        // Past the end of a line or of the source
+        assert_eq!(to_byte_offset(source, Position { line: 0, character: 99 }), 4);
+        assert_eq!(to_byte_offset(source, Position { line: 9, character: 0 }), source.len());
+    }
+
     #[test]
     fn test_utf16_conversion() {
         // ASCII-only
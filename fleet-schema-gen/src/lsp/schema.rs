@@ -1,26 +1,36 @@
 //! Schema documentation for Fleet GitOps fields.
 //!
-//! This module provides documentation for all Fleet configuration fields,
-//! used by hover and completion providers.
-
+//! Field docs are derived from a generated [`FleetSchema`]: the snapshot
+//! embedded in the binary, or the `fleet-schema.json` written by
+//! `generate --editor lsp` for the workspace's Fleet version. Hover and
+//! completion read them through [`get_field_doc`], so new Fleet fields are
+//! documented once the schema is regenerated.
+
+use crate::schema::types::{FleetSchema, SchemaDefinition, SchemaType};
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
 
 /// Documentation for a Fleet configuration field.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FieldDoc {
     /// The field name (e.g., "platform", "query")
-    pub name: &'static str,
+    pub name: String,
     /// Description of the field
-    pub description: &'static str,
+    pub description: String,
     /// Valid values for enum fields
-    pub valid_values: Option<&'static [&'static str]>,
+    pub valid_values: Option<Vec<String>>,
     /// Example usage
-    pub example: Option<&'static str>,
+    pub example: Option<String>,
     /// Whether this field is required
     pub required: bool,
     /// The field's data type
-    pub field_type: &'static str,
+    pub field_type: String,
+    /// Section of Fleet's YAML reference covering the field
+    pub docs_url: Option<String>,
 }
 
 impl FieldDoc {
@@ -34,17 +44,21 @@ impl FieldDoc {
 
         md.push_str(&format!("\n\n**Type:** `{}`", self.field_type));
 
-        if let Some(values) = self.valid_values {
+        if let Some(values) = &self.valid_values {
             md.push_str("\n\n**Valid values:**\n");
             for v in values {
                 md.push_str(&format!("- `{}`\n", v));
             }
         }
 
-        if let Some(example) = self.example {
+        if let Some(example) = &self.example {
             md.push_str(&format!("\n**Example:**\n```yaml\n{}\n```", example));
         }
 
+        if let Some(url) = &self.docs_url {
+            md.push_str(&format!("\n\n[Open Fleet docs]({})", url));
+        }
+
         md
     }
 }
@@ -78,1032 +92,169 @@ pub static LOGGING_DOCS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|
     m
 });
 
-/// Field documentation organized by context (policies, queries, labels, etc.)
-pub static FIELD_DOCS: Lazy<HashMap<&'static str, FieldDoc>> = Lazy::new(|| {
-    let mut m = HashMap::new();
-
-    // =========================================================================
-    // Policy fields
-    // =========================================================================
-    m.insert(
-        "policies.name",
-        FieldDoc {
-            name: "name",
-            description: "The display name of the policy. Must be unique within the organization.",
-            valid_values: None,
-            example: Some("name: Ensure FileVault is enabled"),
-            required: true,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "policies.description",
-        FieldDoc {
-            name: "description",
-            description: "A detailed description of what this policy checks and why it matters.",
-            valid_values: None,
-            example: Some("description: Verifies that disk encryption is enabled to protect data at rest"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "policies.query",
-        FieldDoc {
-            name: "query",
-            description: "The osquery SQL query that determines policy compliance. Returns results when the policy is violated (failing).",
-            valid_values: None,
-            example: Some("query: SELECT 1 FROM disk_encryption WHERE encrypted = 0"),
-            required: true,
-            field_type: "string (osquery SQL)",
-        },
-    );
+/// Field docs keyed by path (`policies.platform`, `software.packages.path`).
+pub type FieldDocs = IndexMap<String, FieldDoc>;
 
-    m.insert(
-        "policies.platform",
-        FieldDoc {
-            name: "platform",
-            description: "The operating system(s) this policy applies to. The query must use tables available on this platform.",
-            valid_values: Some(&["darwin", "windows", "linux", "chrome"]),
-            example: Some("platform: darwin"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "policies.critical",
-        FieldDoc {
-            name: "critical",
-            description: "Whether this policy is critical. Critical policy failures are highlighted and may trigger alerts.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("critical: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "policies.resolution",
-        FieldDoc {
-            name: "resolution",
-            description: "Instructions for end users on how to resolve a policy failure. Shown in Fleet Desktop.",
-            valid_values: None,
-            example: Some("resolution: Enable FileVault in System Preferences > Security & Privacy"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "policies.team",
-        FieldDoc {
-            name: "team",
-            description: "The team this policy belongs to. If not specified, applies to all teams.",
-            valid_values: None,
-            example: Some("team: Engineering"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "policies.calendar_events_enabled",
-        FieldDoc {
-            name: "calendar_events_enabled",
-            description: "Whether to create calendar events for policy failures to remind users to fix issues.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("calendar_events_enabled: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    // =========================================================================
-    // Query fields
-    // =========================================================================
-    m.insert(
-        "queries.name",
-        FieldDoc {
-            name: "name",
-            description: "The display name of the query. Must be unique within the organization.",
-            valid_values: None,
-            example: Some("name: Get running processes"),
-            required: true,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "queries.description",
-        FieldDoc {
-            name: "description",
-            description: "A description of what this query collects and its purpose.",
-            valid_values: None,
-            example: Some("description: Collects all running processes for security analysis"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "queries.query",
-        FieldDoc {
-            name: "query",
-            description: "The osquery SQL query to execute on hosts.",
-            valid_values: None,
-            example: Some("query: SELECT name, path, pid FROM processes"),
-            required: true,
-            field_type: "string (osquery SQL)",
-        },
-    );
-
-    m.insert(
-        "queries.interval",
-        FieldDoc {
-            name: "interval",
-            description: "How often to run this query, in seconds. Lower values increase resource usage.",
-            valid_values: None,
-            example: Some("interval: 3600  # Run every hour"),
-            required: false,
-            field_type: "integer (seconds)",
-        },
-    );
-
-    m.insert(
-        "queries.platform",
-        FieldDoc {
-            name: "platform",
-            description: "The operating system(s) this query runs on. The query must use tables available on this platform.",
-            valid_values: Some(&["darwin", "windows", "linux", "chrome", "all"]),
-            example: Some("platform: darwin"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "queries.logging",
-        FieldDoc {
-            name: "logging",
-            description: "How query results are logged. Affects log volume and what data is captured.",
-            valid_values: Some(&["snapshot", "differential", "differential_ignore_removals"]),
-            example: Some("logging: differential"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "queries.min_osquery_version",
-        FieldDoc {
-            name: "min_osquery_version",
-            description: "Minimum osquery version required to run this query. Hosts with older versions will skip it.",
-            valid_values: None,
-            example: Some("min_osquery_version: 5.0.0"),
-            required: false,
-            field_type: "string (semver)",
-        },
-    );
-
-    m.insert(
-        "queries.observer_can_run",
-        FieldDoc {
-            name: "observer_can_run",
-            description: "Whether users with Observer role can run this query on-demand.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("observer_can_run: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "queries.automations_enabled",
-        FieldDoc {
-            name: "automations_enabled",
-            description: "Whether this query can trigger automations (webhooks, integrations).",
-            valid_values: Some(&["true", "false"]),
-            example: Some("automations_enabled: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "queries.discard_data",
-        FieldDoc {
-            name: "discard_data",
-            description: "Whether to discard query results after processing. Useful for queries that only trigger automations.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("discard_data: false"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    // =========================================================================
-    // Label fields
-    // =========================================================================
-    m.insert(
-        "labels.name",
-        FieldDoc {
-            name: "name",
-            description: "The display name of the label. Must be unique within the organization.",
-            valid_values: None,
-            example: Some("name: macOS Tahoe"),
-            required: true,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "labels.description",
-        FieldDoc {
-            name: "description",
-            description: "A description of what hosts this label identifies.",
-            valid_values: None,
-            example: Some("description: Hosts running macOS 26.x"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "labels.query",
-        FieldDoc {
-            name: "query",
-            description: "For dynamic labels, the osquery query that determines label membership. Returns results for matching hosts.",
-            valid_values: None,
-            example: Some("query: SELECT 1 FROM os_version WHERE major = 26"),
-            required: false,
-            field_type: "string (osquery SQL)",
-        },
-    );
-
-    m.insert(
-        "labels.platform",
-        FieldDoc {
-            name: "platform",
-            description: "The operating system(s) this label applies to.",
-            valid_values: Some(&["darwin", "windows", "linux", "chrome", "all"]),
-            example: Some("platform: darwin"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "labels.label_membership_type",
-        FieldDoc {
-            name: "label_membership_type",
-            description: "How hosts are assigned to this label: 'dynamic' (via query) or 'manual' (explicit assignment).",
-            valid_values: Some(&["dynamic", "manual"]),
-            example: Some("label_membership_type: dynamic"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "labels.hosts",
-        FieldDoc {
-            name: "hosts",
-            description: "For manual labels, the list of host identifiers to include.",
-            valid_values: None,
-            example: Some("hosts:\n  - host1.example.com\n  - host2.example.com"),
-            required: false,
-            field_type: "array of strings",
-        },
-    );
-
-    // =========================================================================
-    // Top-level fields
-    // =========================================================================
-    m.insert(
-        "name",
-        FieldDoc {
-            name: "name",
-            description: "The name of this configuration file or team.",
-            valid_values: None,
-            example: Some("name: Engineering Team"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "policies",
-        FieldDoc {
-            name: "policies",
-            description: "List of compliance policies to enforce on hosts. Policies return results when violated.",
-            valid_values: None,
-            example: Some("policies:\n  - name: Disk Encryption\n    query: SELECT 1 FROM disk_encryption WHERE encrypted = 0"),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    m.insert(
-        "queries",
-        FieldDoc {
-            name: "queries",
-            description: "List of osquery queries to run on hosts for data collection.",
-            valid_values: None,
-            example: Some("queries:\n  - name: Running Processes\n    query: SELECT * FROM processes"),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    m.insert(
-        "labels",
-        FieldDoc {
-            name: "labels",
-            description: "List of labels to categorize hosts for targeting policies and queries.",
-            valid_values: None,
-            example: Some("labels:\n  - name: Production Servers\n    query: SELECT 1 FROM system_info WHERE hostname LIKE 'prod-%'"),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    m.insert(
-        "agent_options",
-        FieldDoc {
-            name: "agent_options",
-            description: "osquery agent configuration options applied to hosts.",
-            valid_values: None,
-            example: Some("agent_options:\n  config:\n    options:\n      logger_plugin: tls"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "controls",
-        FieldDoc {
-            name: "controls",
-            description: "MDM controls and settings for managed devices.",
-            valid_values: None,
-            example: Some("controls:\n  macos_settings:\n    custom_settings:\n      - path: profiles/filevault.mobileconfig"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "software",
-        FieldDoc {
-            name: "software",
-            description: "Software packages to install or manage on hosts.",
-            valid_values: None,
-            example: Some("software:\n  packages:\n    - path: ../lib/software/firefox.yml"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    // =========================================================================
-    // Software package fields
-    // =========================================================================
-    m.insert(
-        "software.packages",
-        FieldDoc {
-            name: "packages",
-            description: "List of software packages to install on hosts. Each item references a package definition file via `path`.",
-            valid_values: None,
-            example: Some("packages:\n  - path: ../lib/software/firefox.yml\n    self_service: true"),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    m.insert(
-        "software.packages.path",
-        FieldDoc {
-            name: "path",
-            description: "Path to a YAML file defining the software package (URL, install scripts, etc). Paths are relative to the current file.",
-            valid_values: None,
-            example: Some("path: ../lib/macos/software/firefox.yml"),
-            required: true,
-            field_type: "string (file path)",
-        },
-    );
-
-    m.insert(
-        "software.packages.self_service",
-        FieldDoc {
-            name: "self_service",
-            description: "Whether end users can install this package themselves through Fleet Desktop.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("self_service: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "software.packages.install_during_setup",
-        FieldDoc {
-            name: "install_during_setup",
-            description: "Whether to install this package during device setup (MDM enrollment).",
-            valid_values: Some(&["true", "false"]),
-            example: Some("install_during_setup: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "software.packages.categories",
-        FieldDoc {
-            name: "categories",
-            description: "Categories for organizing the software package in Fleet Desktop.",
-            valid_values: None,
-            example: Some("categories:\n  - Productivity\n  - Communication"),
-            required: false,
-            field_type: "array of strings",
-        },
-    );
-
-    m.insert(
-        "software.packages.labels_include_any",
-        FieldDoc {
-            name: "labels_include_any",
-            description: "Only install on hosts that have ANY of these labels.",
-            valid_values: None,
-            example: Some("labels_include_any:\n  - Engineering\n  - Product"),
-            required: false,
-            field_type: "array of strings",
-        },
-    );
-
-    m.insert(
-        "software.packages.labels_exclude_any",
-        FieldDoc {
-            name: "labels_exclude_any",
-            description: "Do not install on hosts that have ANY of these labels.",
-            valid_values: None,
-            example: Some("labels_exclude_any:\n  - Contractors"),
-            required: false,
-            field_type: "array of strings",
-        },
-    );
-
-    m.insert(
-        "software.app_store_apps",
-        FieldDoc {
-            name: "app_store_apps",
-            description: "List of App Store apps (VPP) to install via MDM.",
-            valid_values: None,
-            example: Some("app_store_apps:\n  - app_store_id: \"497799835\""),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    m.insert(
-        "software.fleet_maintained_apps",
-        FieldDoc {
-            name: "fleet_maintained_apps",
-            description: "List of Fleet-maintained applications to install. These are automatically updated by Fleet.",
-            valid_values: None,
-            example: Some("fleet_maintained_apps:\n  - slug: 1password"),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    m.insert(
-        "software.fleet_maintained_apps.slug",
-        FieldDoc {
-            name: "slug",
-            description: "The identifier slug for a Fleet-maintained app. Fleet maintains installers for popular apps.",
-            valid_values: None,
-            example: Some("slug: 1password"),
-            required: true,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "software.fleet_maintained_apps.self_service",
-        FieldDoc {
-            name: "self_service",
-            description: "Whether end users can install this app themselves through Fleet Desktop.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("self_service: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "software.fleet_maintained_apps.setup_experience",
-        FieldDoc {
-            name: "setup_experience",
-            description: "Whether to install this app during the macOS Setup Assistant experience.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("setup_experience: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "software.app_store_apps.app_store_id",
-        FieldDoc {
-            name: "app_store_id",
-            description: "The Apple App Store ID for the app to install via VPP.",
-            valid_values: None,
-            example: Some("app_store_id: \"497799835\""),
-            required: true,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "software.app_store_apps.self_service",
-        FieldDoc {
-            name: "self_service",
-            description: "Whether end users can install this app themselves through Fleet Desktop.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("self_service: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "software.packages.setup_experience",
-        FieldDoc {
-            name: "setup_experience",
-            description: "Whether to install this package during the macOS Setup Assistant experience.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("setup_experience: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    // =========================================================================
-    // Software lib file fields (standalone package definitions)
-    // These are used in lib/*/software/*.yml files
-    // =========================================================================
-    m.insert(
-        "software_lib.url",
-        FieldDoc {
-            name: "url",
-            description: "URL to download the software installer package (.pkg, .dmg, .msi, etc.).",
-            valid_values: None,
-            example: Some("url: https://downloads.1password.com/mac/1Password.pkg"),
-            required: true,
-            field_type: "string (URL)",
-        },
-    );
-
-    m.insert(
-        "software_lib.icon",
-        FieldDoc {
-            name: "icon",
-            description: "Icon to display for this software in Fleet Desktop.",
-            valid_values: None,
-            example: Some("icon:\n  path: ../../all/icons/app-logo.png"),
-            required: false,
-            field_type: "object with path",
-        },
-    );
-
-    m.insert(
-        "software_lib.install_script",
-        FieldDoc {
-            name: "install_script",
-            description: "Custom script to run for installation instead of the default installer.",
-            valid_values: None,
-            example: Some("install_script:\n  path: ./scripts/install.sh"),
-            required: false,
-            field_type: "object with path",
-        },
-    );
-
-    m.insert(
-        "software_lib.post_install_script",
-        FieldDoc {
-            name: "post_install_script",
-            description: "Script to run after the software is installed.",
-            valid_values: None,
-            example: Some("post_install_script:\n  path: ./scripts/post-install.sh"),
-            required: false,
-            field_type: "object with path",
-        },
-    );
-
-    m.insert(
-        "software_lib.uninstall_script",
-        FieldDoc {
-            name: "uninstall_script",
-            description: "Script to run when uninstalling the software.",
-            valid_values: None,
-            example: Some("uninstall_script:\n  path: ./scripts/uninstall.sh"),
-            required: false,
-            field_type: "object with path",
-        },
-    );
-
-    m.insert(
-        "software_lib.pre_install_query",
-        FieldDoc {
-            name: "pre_install_query",
-            description: "osquery SQL query to check before installing. Installation proceeds only if the query returns results.",
-            valid_values: None,
-            example: Some("pre_install_query:\n  path: ./queries/check-requirements.sql"),
-            required: false,
-            field_type: "object with path",
-        },
-    );
-
-    m.insert(
-        "software_lib.hash_sha256",
-        FieldDoc {
-            name: "hash_sha256",
-            description: "SHA256 hash of the installer package for verification.",
-            valid_values: None,
-            example: Some("hash_sha256: abc123..."),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    // =========================================================================
-    // Agent options lib file fields (standalone agent options definitions)
-    // These are used in lib/*/agent-options/*.yml files
-    // =========================================================================
-    m.insert(
-        "agent_options.config",
-        FieldDoc {
-            name: "config",
-            description: "osquery configuration options including decorators and runtime settings.",
-            valid_values: None,
-            example: Some("config:\n  decorators:\n    load:\n      - SELECT host_uuid AS uuid FROM system_info\n  options:\n    distributed_interval: 10"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "agent_options.config.decorators",
-        FieldDoc {
-            name: "decorators",
-            description: "osquery decorators that add extra columns to query results. Commonly used for host identification.",
-            valid_values: None,
-            example: Some("decorators:\n  load:\n    - SELECT host_uuid AS uuid FROM system_info\n    - SELECT hostname FROM system_info"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "agent_options.config.options",
-        FieldDoc {
-            name: "options",
-            description: "osquery daemon runtime options (intervals, endpoints, logging settings).",
-            valid_values: None,
-            example: Some("options:\n  distributed_interval: 10\n  distributed_tls_max_attempts: 3\n  logger_tls_period: 60"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "agent_options.update_channels",
-        FieldDoc {
-            name: "update_channels",
-            description: "Update channels for Fleet agent components. Use 'stable', 'edge', or specific versions.",
-            valid_values: Some(&["stable", "edge"]),
-            example: Some("update_channels:\n  osqueryd: stable\n  orbit: stable\n  desktop: edge"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    // =========================================================================
-    // Labels fields (for lib label files)
-    // =========================================================================
-    m.insert(
-        "labels.label_membership_type",
-        FieldDoc {
-            name: "label_membership_type",
-            description: "How hosts are assigned to this label. 'dynamic' uses the query, 'manual' requires explicit assignment.",
-            valid_values: Some(&["dynamic", "manual"]),
-            example: Some("label_membership_type: dynamic"),
-            required: false,
-            field_type: "string",
-        },
-    );
-
-    m.insert(
-        "labels.hosts",
-        FieldDoc {
-            name: "hosts",
-            description: "List of host identifiers for manual label membership. Only used when label_membership_type is 'manual'.",
-            valid_values: None,
-            example: Some("hosts:\n  - host1.example.com\n  - host2.example.com"),
-            required: false,
-            field_type: "array of strings",
-        },
-    );
-
-    // =========================================================================
-    // Controls fields
-    // =========================================================================
-    m.insert(
-        "controls.enable_disk_encryption",
-        FieldDoc {
-            name: "enable_disk_encryption",
-            description: "Whether to enable disk encryption (FileVault on macOS, BitLocker on Windows) via MDM.",
-            valid_values: Some(&["true", "false"]),
-            example: Some("enable_disk_encryption: true"),
-            required: false,
-            field_type: "boolean",
-        },
-    );
-
-    m.insert(
-        "controls.macos_settings",
-        FieldDoc {
-            name: "macos_settings",
-            description: "MDM settings specific to macOS devices.",
-            valid_values: None,
-            example: Some("macos_settings:\n  custom_settings:\n    - path: profiles/filevault.mobileconfig"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "controls.macos_settings.custom_settings",
-        FieldDoc {
-            name: "custom_settings",
-            description: "List of custom configuration profiles to install on macOS devices.",
-            valid_values: None,
-            example: Some("custom_settings:\n  - path: profiles/security.mobileconfig\n    labels_include_any:\n      - Engineering"),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    m.insert(
-        "controls.macos_settings.macos_setup",
-        FieldDoc {
-            name: "macos_setup",
-            description: "Configuration for the macOS Setup Assistant experience.",
-            valid_values: None,
-            example: Some("macos_setup:\n  bootstrap_package: bootstrap/pkg.pkg\n  enable_end_user_authentication: true"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "controls.macos_settings.macos_updates",
-        FieldDoc {
-            name: "macos_updates",
-            description: "macOS software update enforcement settings.",
-            valid_values: None,
-            example: Some("macos_updates:\n  minimum_version: \"15.0\"\n  deadline: \"2024-12-31\""),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "controls.windows_settings",
-        FieldDoc {
-            name: "windows_settings",
-            description: "MDM settings specific to Windows devices.",
-            valid_values: None,
-            example: Some("windows_settings:\n  custom_settings:\n    - path: profiles/security.xml"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "controls.windows_settings.custom_settings",
-        FieldDoc {
-            name: "custom_settings",
-            description: "List of custom configuration profiles to install on Windows devices.",
-            valid_values: None,
-            example: Some("custom_settings:\n  - path: profiles/bitlocker.xml"),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    m.insert(
-        "controls.windows_settings.windows_updates",
-        FieldDoc {
-            name: "windows_updates",
-            description: "Windows Update enforcement settings.",
-            valid_values: None,
-            example: Some("windows_updates:\n  deadline_days: 7\n  grace_period_days: 2"),
-            required: false,
-            field_type: "object",
-        },
-    );
-
-    m.insert(
-        "controls.scripts",
-        FieldDoc {
-            name: "scripts",
-            description: "List of scripts to run on hosts. Each item references a script file via `path`.",
-            valid_values: None,
-            example: Some("scripts:\n  - path: scripts/setup.sh"),
-            required: false,
-            field_type: "array",
-        },
-    );
-
-    // =========================================================================
-    // Team settings fields
-    // =========================================================================
-    m.insert(
-        "team_settings",
-        FieldDoc {
-            name: "team_settings",
-            description: "Settings specific to this team.",
-            valid_values: None,
-            example: Some("team_settings:\n  secrets:\n    - secret: $ENROLL_SECRET"),
-            required: false,
-            field_type: "object",
-        },
-    );
+/// Docs in use; starts from the embedded snapshot.
+static FIELD_DOCS: Lazy<RwLock<Arc<FieldDocs>>> = Lazy::new(|| {
+    let schema = crate::linter::bundled::fleet_schema();
+    RwLock::new(Arc::new(docs_from_schema(&schema)))
+});
 
-    m.insert(
-        "team_settings.secrets",
-        FieldDoc {
-            name: "secrets",
-            description: "Enrollment secrets for adding hosts to this team.",
-            valid_values: None,
-            example: Some("secrets:\n  - secret: $ENROLL_SECRET"),
-            required: false,
-            field_type: "array",
-        },
-    );
+/// Current field docs.
+pub fn field_docs() -> Arc<FieldDocs> {
+    FIELD_DOCS.read().map(|docs| docs.clone()).unwrap_or_default()
+}
 
-    m.insert(
-        "team_settings.features",
-        FieldDoc {
-            name: "features",
-            description: "Feature flags for this team.",
-            valid_values: None,
-            example: Some("features:\n  enable_host_users: true\n  enable_software_inventory: true"),
-            required: false,
-            field_type: "object",
-        },
-    );
+/// Replace the field docs, e.g. with those of a workspace's Fleet version.
+pub fn set_field_docs(docs: FieldDocs) {
+    if let Ok(mut current) = FIELD_DOCS.write() {
+        *current = Arc::new(docs);
+    }
+}
 
-    m.insert(
-        "team_settings.webhook_settings",
-        FieldDoc {
-            name: "webhook_settings",
-            description: "Webhook configuration for this team.",
-            valid_values: None,
-            example: Some("webhook_settings:\n  failing_policies_webhook:\n    enable_failing_policies_webhook: true"),
-            required: false,
-            field_type: "object",
-        },
-    );
+/// Load a `fleet-schema.json` written by `generate --editor lsp`.
+pub fn load_schema(path: &Path) -> Result<FleetSchema> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
 
-    m.insert(
-        "team_settings.integrations",
-        FieldDoc {
-            name: "integrations",
-            description: "Third-party integrations for this team (Google Calendar, etc.).",
-            valid_values: None,
-            example: Some("integrations:\n  google_calendar:\n    enable_calendar_events: true"),
-            required: false,
-            field_type: "object",
-        },
-    );
+/// Field docs for a schema with the `schema-defs/` enhancements in
+/// `defs_dir` applied, so local description tweaks show up in hover.
+pub fn docs_with_enhancements(schema: &FleetSchema, defs_dir: &Path) -> Result<FieldDocs> {
+    let enhancements = crate::sources::yaml_defs::read_enhancements(defs_dir)
+        .with_context(|| format!("Failed to load schema definitions from {}", defs_dir.display()))?;
+    let mut schema = schema.clone();
+    crate::schema::merger::apply_local_enhancements(&mut schema, &enhancements)?;
+    Ok(docs_from_schema(&schema))
+}
 
-    m.insert(
-        "team_settings.host_expiry_settings",
-        FieldDoc {
-            name: "host_expiry_settings",
-            description: "Settings for automatically removing inactive hosts.",
-            valid_values: None,
-            example: Some("host_expiry_settings:\n  host_expiry_enabled: true\n  host_expiry_window: 30"),
-            required: false,
-            field_type: "object",
-        },
-    );
+/// Field docs for every documented property of a schema.
+///
+/// Team and default files are walked first; the policy, query and label
+/// schemas fill in fields of lib files, and `$defs` entries are keyed by
+/// their name (`software_lib.url`). The first description of a path wins.
+/// Fields without an `x-docs-url` link to their nearest documented parent.
+pub fn docs_from_schema(schema: &FleetSchema) -> FieldDocs {
+    let mut docs = FieldDocs::new();
+    collect_properties("", &schema.team_schema, None, &mut docs);
+    collect_properties("", &schema.default_schema, None, &mut docs);
+    collect_properties("policies", &schema.policy_schema, None, &mut docs);
+    collect_properties("queries", &schema.query_schema, None, &mut docs);
+    collect_properties("labels", &schema.label_schema, None, &mut docs);
+
+    for root in [&schema.team_schema, &schema.default_schema] {
+        for (name, def) in root.defs.iter().flatten() {
+            collect_field(name, name, def, false, None, &mut docs);
+        }
+    }
+    docs
+}
 
-    // =========================================================================
-    // Agent options fields
-    // =========================================================================
-    m.insert(
-        "agent_options.config",
-        FieldDoc {
-            name: "config",
-            description: "osquery configuration options.",
-            valid_values: None,
-            example: Some("config:\n  options:\n    distributed_interval: 10"),
-            required: false,
-            field_type: "object",
-        },
-    );
+fn collect_properties(prefix: &str, schema: &SchemaDefinition, docs_url: Option<&str>, docs: &mut FieldDocs) {
+    let docs_url = schema.docs_url.as_deref().or(docs_url);
+    let required = schema.required.as_deref().unwrap_or_default();
+    for (name, property) in schema.properties.iter().flatten() {
+        let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        collect_field(&path, name, property, required.contains(name), docs_url, docs);
+    }
 
-    m.insert(
-        "agent_options.config.options",
-        FieldDoc {
-            name: "options",
-            description: "osquery daemon options (intervals, endpoints, etc.).",
-            valid_values: None,
-            example: Some("options:\n  distributed_interval: 10\n  logger_tls_period: 60"),
-            required: false,
-            field_type: "object",
-        },
-    );
+    // List items, including each `oneOf`/`anyOf` alternative (inline or `path:`)
+    if let Some(items) = &schema.items {
+        collect_properties(prefix, items, docs_url, docs);
+        for alternative in items.one_of.iter().chain(&items.any_of).flatten() {
+            collect_properties(prefix, alternative, docs_url, docs);
+        }
+    }
+}
 
-    m.insert(
-        "agent_options.config.decorators",
-        FieldDoc {
-            name: "decorators",
-            description: "osquery decorators that add extra columns to query results.",
-            valid_values: None,
-            example: Some("decorators:\n  load:\n    - SELECT hostname FROM system_info"),
-            required: false,
-            field_type: "object",
-        },
-    );
+fn collect_field(
+    path: &str,
+    name: &str,
+    schema: &SchemaDefinition,
+    required: bool,
+    parent_docs_url: Option<&str>,
+    docs: &mut FieldDocs,
+) {
+    let docs_url = schema.docs_url.as_deref().or(parent_docs_url);
+    if let Some(description) = &schema.description {
+        docs.entry(path.to_string()).or_insert_with(|| FieldDoc {
+            name: name.to_string(),
+            description: description.clone(),
+            valid_values: schema.enum_.as_ref().map(|values| values.iter().map(display_value).collect()),
+            example: schema.examples.as_ref().and_then(|e| e.first()).and_then(|e| example_yaml(name, e)),
+            required,
+            field_type: type_label(schema),
+            docs_url: docs_url.map(str::to_string),
+        });
+    }
+    collect_properties(path, schema, docs_url, docs);
+}
 
-    m.insert(
-        "agent_options.update_channels",
-        FieldDoc {
-            name: "update_channels",
-            description: "Update channels for Fleet agent components (osqueryd, orbit, desktop).",
-            valid_values: None,
-            example: Some("update_channels:\n  osqueryd: stable\n  orbit: stable"),
-            required: false,
-            field_type: "object",
-        },
-    );
+/// `string (file path)`, `array of strings`, ...
+fn type_label(schema: &SchemaDefinition) -> String {
+    let base = match &schema.type_ {
+        Some(SchemaType::Single(t)) => t.clone(),
+        Some(SchemaType::Multiple(types)) => types.join(" | "),
+        None => "any".to_string(),
+    };
+    let item_type = schema.items.as_ref().and_then(|items| match &items.type_ {
+        Some(SchemaType::Single(t)) if t != "object" => Some(t.as_str()),
+        _ => None,
+    });
+
+    match (base.as_str(), item_type, schema.format.as_deref()) {
+        ("array", Some(item), _) => format!("array of {}s", item),
+        (_, _, Some(format)) => format!("{} ({})", base, format_label(format)),
+        _ => base,
+    }
+}
 
-    m.insert(
-        "webhook_settings",
-        FieldDoc {
-            name: "webhook_settings",
-            description: "Configuration for webhook notifications.",
-            valid_values: None,
-            example: Some("webhook_settings:\n  url: https://example.com/webhook"),
-            required: false,
-            field_type: "object",
-        },
-    );
+fn format_label(format: &str) -> &str {
+    match format {
+        "path" => "file path",
+        "uri" => "URL",
+        "osquery-sql" => "osquery SQL",
+        other => other,
+    }
+}
 
-    m.insert(
-        "path",
-        FieldDoc {
-            name: "path",
-            description: "Reference to another YAML file containing configuration. Paths are relative to the repository root.",
-            valid_values: None,
-            example: Some("- path: lib/policies/security.yml"),
-            required: false,
-            field_type: "string (file path)",
-        },
-    );
+fn display_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
 
-    m
-});
+/// An example value rendered as `name: value` YAML.
+fn example_yaml(name: &str, value: &serde_json::Value) -> Option<String> {
+    let mut example = serde_yaml::Mapping::new();
+    example.insert(name.into(), serde_yaml::to_value(value).ok()?);
+    Some(serde_yaml::to_string(&example).ok()?.trim_end().to_string())
+}
 
 /// Get field documentation by path (e.g., "policies.platform" or just "platform").
-pub fn get_field_doc(path: &str) -> Option<&'static FieldDoc> {
+pub fn get_field_doc(path: &str) -> Option<FieldDoc> {
+    let docs = field_docs();
+
     // Try exact match first
-    if let Some(doc) = FIELD_DOCS.get(path) {
-        return Some(doc);
+    if let Some(doc) = docs.get(path) {
+        return Some(doc.clone());
     }
 
     // Try with common prefixes
     for prefix in &["policies", "queries", "labels"] {
         let full_path = format!("{}.{}", prefix, path);
-        if let Some(doc) = FIELD_DOCS.get(full_path.as_str()) {
-            return Some(doc);
+        if let Some(doc) = docs.get(full_path.as_str()) {
+            return Some(doc.clone());
         }
     }
 
     // Try just the field name (last segment)
     let field_name = path.split('.').last().unwrap_or(path);
-    for (key, doc) in FIELD_DOCS.iter() {
-        if key.ends_with(field_name) {
-            return Some(doc);
-        }
-    }
-
-    None
+    docs.iter()
+        .find(|(key, _)| key.ends_with(field_name))
+        .map(|(_, doc)| doc.clone())
 }
 
 /// Get documentation for a platform value.
@@ -1145,12 +296,85 @@ mod tests {
 
     #[test]
     fn test_field_doc_to_markdown() {
-        let doc = FIELD_DOCS.get("policies.platform").unwrap();
+        let doc = field_docs().get("policies.platform").cloned().unwrap();
         let md = doc.to_markdown();
         assert!(md.contains("**platform**"));
         assert!(md.contains("darwin"));
     }
 
+    #[test]
+    fn test_docs_with_enhancements() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("policies.yml"),
+            "fields:\n  platform:\n    description: Where the policy runs.\n",
+        )
+        .unwrap();
+
+        let schema = crate::linter::bundled::fleet_schema();
+        let docs = docs_with_enhancements(&schema, dir.path()).unwrap();
+        assert_eq!(docs["policies.platform"].description, "Where the policy runs.");
+        assert_eq!(docs["policies.name"], docs_from_schema(&schema)["policies.name"]);
+
+        std::fs::write(dir.path().join("policies.yml"), "fields: [").unwrap();
+        assert!(docs_with_enhancements(&schema, dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_docs_from_bundled_schema() {
+        let platform = get_field_doc("policies.platform").unwrap();
+        assert_eq!(platform.example.as_deref(), Some("platform: darwin"));
+        assert!(platform.valid_values.unwrap().contains(&"darwin".to_string()));
+        assert!(get_field_doc("policies.name").unwrap().required);
+
+        assert_eq!(get_field_doc("software.packages.path").unwrap().field_type, "string (file path)");
+        assert_eq!(get_field_doc("labels.hosts").unwrap().field_type, "array of strings");
+        assert_eq!(get_field_doc("software_lib.url").unwrap().field_type, "string (URL)");
+    }
+
+    #[test]
+    fn test_docs_links() {
+        let docs_url = |path: &str| get_field_doc(path).and_then(|doc| doc.docs_url);
+        let yaml_docs = crate::sources::yaml_defs::FLEET_YAML_DOCS;
+
+        // Own anchor, inherited from the nearest documented parent
+        assert_eq!(docs_url("controls.macos_settings"), Some(format!("{}#macos-settings", yaml_docs)));
+        assert_eq!(docs_url("controls.macos_settings.custom_settings"), Some(format!("{}#macos-settings", yaml_docs)));
+        assert_eq!(docs_url("policies.platform"), Some(format!("{}#policies", yaml_docs)));
+        assert_eq!(docs_url("software_lib.url"), Some(format!("{}#packages", yaml_docs)));
+
+        let md = get_field_doc("queries.interval").unwrap().to_markdown();
+        assert!(md.ends_with(&format!("[Open Fleet docs]({}#queries)", yaml_docs)));
+    }
+
+    #[test]
+    fn test_docs_from_generated_schema() {
+        let mut schema = crate::linter::bundled::fleet_schema();
+        let policy: SchemaDefinition = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string", "description": "Policy name"},
+                "conditional_access_enabled": {
+                    "type": "boolean",
+                    "description": "Block access to apps when failing",
+                    "examples": [true]
+                },
+                "undocumented": {"type": "string"}
+            }
+        }))
+        .unwrap();
+        schema.policy_schema = policy;
+
+        let docs = docs_from_schema(&schema);
+        let doc = &docs["policies.conditional_access_enabled"];
+        assert_eq!(doc.field_type, "boolean");
+        assert_eq!(doc.example.as_deref(), Some("conditional_access_enabled: true"));
+        assert!(!docs.contains_key("policies.undocumented"));
+        // Team schema descriptions come first
+        assert_ne!(docs["policies.name"].description, "Policy name");
+    }
+
     #[test]
     fn test_platform_docs() {
         assert!(get_platform_doc("darwin").is_some());
@@ -1189,9 +413,9 @@ mod tests {
         }
 
         // Verify software.packages does NOT have a `name` field (it uses path references)
-        // Use FIELD_DOCS.get() directly to check exact key, since get_field_doc() has fallbacks
+        // Check the docs map directly to check exact key, since get_field_doc() has fallbacks
         assert!(
-            FIELD_DOCS.get("software.packages.name").is_none(),
+            !field_docs().contains_key("software.packages.name"),
             "software.packages should not have a 'name' field - it uses 'path' to reference package files"
         );
 
@@ -1230,10 +454,10 @@ mod tests {
     /// Test that examples don't contain incorrect field structures
     #[test]
     fn test_examples_are_valid() {
-        for (path, doc) in FIELD_DOCS.iter() {
-            if let Some(example) = doc.example {
+        for (path, doc) in field_docs().iter() {
+            if let Some(example) = &doc.example {
                 // software.packages examples should use `path:`, not `name:`
-                if path.starts_with("software.packages") || *path == "software" {
+                if path.starts_with("software.packages") || path == "software" {
                     assert!(
                         !example.contains("- name:") || !example.contains("packages"),
                         "Example for {} incorrectly shows 'name:' under packages. Should use 'path:'. Example: {}",
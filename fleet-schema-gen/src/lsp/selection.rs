@@ -0,0 +1,427 @@
+//! Selection ranges ("expand selection") for Fleet GitOps YAML files.
+//!
+//! Selections grow from the word at the cursor to the SQL clause, the query
+//! string, the mapping entry, the list item, enclosing entries and items up
+//! to the top-level section, and finally the whole document. Structure
+//! follows indentation, like the rest of the LSP's YAML handling.
+//!
+//! Columns are byte offsets internally; positions from and ranges for the
+//! client are converted from and to UTF-16 code units at the edges.
+
+use super::position::{byte_offset_to_utf16, to_byte_offset};
+use tower_lsp::lsp_types::{Position, Range, SelectionRange};
+
+/// SQL keywords that start a clause, longest first.
+const SQL_CLAUSES: &[&[&str]] = &[
+    &["group", "by"],
+    &["order", "by"],
+    &["left", "join"],
+    &["inner", "join"],
+    &["cross", "join"],
+    &["select"],
+    &["from"],
+    &["join"],
+    &["where"],
+    &["having"],
+    &["limit"],
+    &["union"],
+];
+
+/// Selection range chains for each position.
+pub fn selection_ranges(source: &str, positions: &[Position]) -> Vec<SelectionRange> {
+    let lines: Vec<&str> = source.lines().collect();
+    positions.iter().map(|position| selection_range_at(&lines, *position)).collect()
+}
+
+fn selection_range_at(lines: &[&str], client_position: Position) -> SelectionRange {
+    let line_idx = client_position.line as usize;
+    let position = match lines.get(line_idx) {
+        Some(line) => Position {
+            line: client_position.line,
+            character: to_byte_offset(line, Position { line: 0, character: client_position.character }) as u32,
+        },
+        None => client_position,
+    };
+
+    // Candidates, innermost first
+    let mut candidates = Vec::new();
+    if let Some(line) = lines.get(line_idx) {
+        candidates.extend(word_range(line, position));
+        match query_region(lines, line_idx) {
+            Some((owner, region)) => {
+                candidates.extend(sql_clause_range(lines, &region, position));
+                candidates.extend(region_range(&region));
+                // SQL lines aren't YAML structure; continue from the key
+                candidates.extend(structural_ranges(lines, owner));
+            }
+            None => {
+                candidates.extend(value_range(line, line_idx));
+                candidates.extend(structural_ranges(lines, line_idx));
+            }
+        }
+    }
+    candidates.push(document_range(lines));
+
+    // Keep a strictly growing chain of ranges around the position
+    let mut chain: Vec<Range> = Vec::new();
+    for range in candidates {
+        let grows = chain.last().is_none_or(|last| range != *last && encloses(range, *last));
+        if contains(range, position) && grows {
+            chain.push(range);
+        }
+    }
+
+    chain
+        .into_iter()
+        .rev()
+        .map(|range| to_utf16(lines, range))
+        .fold(None, |parent, range| Some(SelectionRange { range, parent: parent.map(Box::new) }))
+        .unwrap_or(SelectionRange { range: Range { start: client_position, end: client_position }, parent: None })
+}
+
+/// `range` with byte columns converted to UTF-16 code units.
+fn to_utf16(lines: &[&str], range: Range) -> Range {
+    let convert = |position: Position| Position {
+        line: position.line,
+        character: lines
+            .get(position.line as usize)
+            .map_or(position.character, |line| byte_offset_to_utf16(line, position.character as usize)),
+    };
+    Range { start: convert(range.start), end: convert(range.end) }
+}
+
+/// The identifier-like word under the cursor.
+fn word_range(line: &str, position: Position) -> Option<Range> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let col = (position.character as usize).min(line.len());
+    let start = line[..col].char_indices().rfind(|&(_, c)| !is_word(c)).map_or(0, |(i, c)| i + c.len_utf8());
+    let end = line[col..].find(|c: char| !is_word(c)).map_or(line.len(), |i| col + i);
+    (start < end).then(|| line_range(position.line as usize, start, end))
+}
+
+/// The scalar value of a `key: value` line.
+fn value_range(line: &str, line_idx: usize) -> Option<Range> {
+    let key_col = key_column(line);
+    let colon = line[key_col..].find(": ").map(|i| key_col + i)?;
+    let value = &line[colon + 1..];
+    let start = colon + 1 + (value.len() - value.trim_start().len());
+    let end = line.trim_end().len();
+    let is_block = line.get(start..end).is_none_or(|value| value.starts_with(['|', '>']));
+    (start < end && !is_block).then(|| line_range(line_idx, start, end))
+}
+
+/// Text of a `query:` value: `(line, start column, end column)` per line.
+pub(super) type Region = Vec<(usize, usize, usize)>;
+
+/// The SQL of the `query:` on `line_idx`, or of the block scalar it's in,
+/// with the line of the `query:` key.
+pub(super) fn query_region(lines: &[&str], line_idx: usize) -> Option<(usize, Region)> {
+    let line = lines[line_idx];
+    if let Some((start, end)) = inline_query(line) {
+        return Some((line_idx, vec![(line_idx, start, end)]));
+    }
+
+    // Inside a block scalar: a less indented `query: |` line owns it
+    if is_blank(line) {
+        return None;
+    }
+    let mut current = line_idx;
+    let owner = loop {
+        current = (0..current)
+            .rev()
+            .find(|&i| !is_blank(lines[i]) && indent(lines[i]) < indent(lines[current]))?;
+        let value = lines[current][key_column(lines[current])..].strip_prefix("query:").map(str::trim);
+        match value {
+            Some(value) if value.starts_with(['|', '>']) => break current,
+            Some(_) => return None,
+            // SQL indented under a previous SQL line
+            None if !lines[current].contains(": ") && !lines[current].trim_end().ends_with(':') => continue,
+            None => return None,
+        }
+    };
+    let owner_line = lines[owner];
+
+    let key_col = key_column(owner_line);
+    let body: Region = lines
+        .iter()
+        .enumerate()
+        .skip(owner + 1)
+        .take_while(|(_, l)| is_blank(l) || indent(l) > key_col)
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| (i, indent(l), l.trim_end().len()))
+        .collect();
+    (!body.is_empty()).then_some((owner, body))
+}
+
+/// Column span of an inline `query: ...` value, without quotes.
+fn inline_query(line: &str) -> Option<(usize, usize)> {
+    let key_col = key_column(line);
+    let rest = line[key_col..].strip_prefix("query:")?;
+    let start = line.len() - rest.trim_start().len();
+    let end = line.trim_end().len();
+    if start >= end || line[start..].starts_with(['|', '>']) {
+        return None;
+    }
+    let quoted = line[start..end].len() >= 2
+        && ((line[start..].starts_with('"') && line[..end].ends_with('"'))
+            || (line[start..].starts_with('\'') && line[..end].ends_with('\'')));
+    Some(if quoted { (start + 1, end - 1) } else { (start, end) })
+}
+
+fn region_range(region: &Region) -> Option<Range> {
+    let (first_line, first_start, _) = *region.first()?;
+    let (last_line, _, last_end) = *region.last()?;
+    Some(Range {
+        start: Position { line: first_line as u32, character: first_start as u32 },
+        end: Position { line: last_line as u32, character: last_end as u32 },
+    })
+}
+
+/// The SQL clause (`WHERE ...`, `GROUP BY ...`) around the cursor.
+fn sql_clause_range(lines: &[&str], region: &Region, position: Position) -> Option<Range> {
+    // Flatten the region into characters with their (byte) positions
+    let mut chars: Vec<(Position, char)> = Vec::new();
+    for (line, start, end) in region {
+        if !chars.is_empty() {
+            chars.push((Position { line: *line as u32, character: 0 }, '\n'));
+        }
+        let text = lines[*line].get(*start..*end)?;
+        chars.extend(text.char_indices().map(|(offset, c)| {
+            (Position { line: *line as u32, character: (start + offset) as u32 }, c.to_ascii_lowercase())
+        }));
+    }
+    let text: Vec<char> = chars.iter().map(|(_, c)| *c).collect();
+
+    // Clause starts: keywords at word boundaries
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        if i == 0 || !is_word(text[i - 1]) {
+            if let Some(end) = SQL_CLAUSES.iter().find_map(|keyword| match_keyword(&text, i, keyword)) {
+                starts.push(i);
+                i = end;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    let cursor = chars.iter().rposition(|(p, _)| *p <= position)?;
+    let clause = starts.iter().rposition(|&start| start <= cursor)?;
+    let start = starts[clause];
+    let mut end = starts.get(clause + 1).copied().unwrap_or(text.len());
+    while end > start && text[end - 1].is_whitespace() {
+        end -= 1;
+    }
+
+    let (last, c) = chars[end - 1];
+    Some(Range {
+        start: chars[start].0,
+        end: Position { line: last.line, character: last.character + c.len_utf8() as u32 },
+    })
+}
+
+/// End of `keyword` (words separated by any whitespace) matched at `at`.
+fn match_keyword(text: &[char], at: usize, keyword: &[&str]) -> Option<usize> {
+    let mut i = at;
+    for (n, word) in keyword.iter().enumerate() {
+        if n > 0 {
+            let gap = text[i..].iter().take_while(|c| c.is_whitespace()).count();
+            if gap == 0 {
+                return None;
+            }
+            i += gap;
+        }
+        for expected in word.chars() {
+            if text.get(i) != Some(&expected) {
+                return None;
+            }
+            i += 1;
+        }
+    }
+    let at_boundary = text.get(i).is_none_or(|c| !(c.is_alphanumeric() || *c == '_'));
+    at_boundary.then_some(i)
+}
+
+/// Mapping entries and list items enclosing `line_idx`, innermost first.
+fn structural_ranges(lines: &[&str], line_idx: usize) -> Vec<Range> {
+    let mut ranges = Vec::new();
+    if is_blank(lines[line_idx]) {
+        return ranges;
+    }
+
+    let mut current = line_idx;
+    loop {
+        let line = lines[current];
+        let key_col = key_column(line);
+
+        // The entry: its key line plus everything nested under the key
+        ranges.push(Range {
+            start: Position { line: current as u32, character: key_col as u32 },
+            end: block_end(lines, current, key_col),
+        });
+        // The list item, when the line starts one
+        if line.trim_start().starts_with('-') {
+            ranges.push(Range {
+                start: Position { line: current as u32, character: indent(line) as u32 },
+                end: block_end(lines, current, indent(line)),
+            });
+        }
+
+        match (0..current).rev().find(|&i| !is_blank(lines[i]) && indent(lines[i]) < indent(line)) {
+            Some(parent) => current = parent,
+            None => break,
+        }
+    }
+    ranges
+}
+
+/// End of the block starting at `start`: following lines indented deeper
+/// than `col`, not counting trailing blank lines.
+fn block_end(lines: &[&str], start: usize, col: usize) -> Position {
+    let last = lines
+        .iter()
+        .enumerate()
+        .skip(start + 1)
+        .take_while(|(_, l)| is_blank(l) || indent(l) > col)
+        .filter(|(_, l)| !is_blank(l))
+        .map(|(i, _)| i)
+        .last()
+        .unwrap_or(start);
+    Position { line: last as u32, character: lines[last].trim_end().len() as u32 }
+}
+
+fn document_range(lines: &[&str]) -> Range {
+    let last = lines.len().saturating_sub(1);
+    Range {
+        start: Position { line: 0, character: 0 },
+        end: Position { line: last as u32, character: lines.get(last).map_or(0, |l| l.len()) as u32 },
+    }
+}
+
+fn line_range(line: usize, start: usize, end: usize) -> Range {
+    Range {
+        start: Position { line: line as u32, character: start as u32 },
+        end: Position { line: line as u32, character: end as u32 },
+    }
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Column of the key on a line, after any `- ` list markers.
+fn key_column(line: &str) -> usize {
+    let mut rest = line.trim_start();
+    while let Some(after) = rest.strip_prefix('-').filter(|r| r.is_empty() || r.starts_with(' ')) {
+        rest = after.trim_start();
+    }
+    line.len() - rest.len()
+}
+
+fn is_blank(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn contains(range: Range, position: Position) -> bool {
+    range.start <= position && position <= range.end
+}
+
+fn encloses(outer: Range, inner: Range) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(source: &str, line: u32, character: u32) -> Vec<String> {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut ranges = Vec::new();
+        let mut current = Some(selection_range_at(&lines, Position { line, character }));
+        while let Some(selection) = current {
+            let Range { start, end } = selection.range;
+            let line = lines[start.line as usize];
+            let byte = |character| to_byte_offset(line, Position { line: 0, character });
+            let text = if start.line == end.line {
+                line[byte(start.character)..byte(end.character)].to_string()
+            } else {
+                format!("{}:{}-{}:{}", start.line, start.character, end.line, end.character)
+            };
+            ranges.push(text);
+            current = selection.parent.map(|p| *p);
+        }
+        ranges
+    }
+
+    #[test]
+    fn test_inline_query_selection() {
+        let source = "policies:\n  - name: Firewall\n    query: \"SELECT 1 FROM alf WHERE global_state >= 1;\"\n    platform: darwin\n";
+        // Cursor on "global_state"
+        assert_eq!(
+            chain(source, 2, 38),
+            vec![
+                "global_state",
+                "WHERE global_state >= 1;",
+                "SELECT 1 FROM alf WHERE global_state >= 1;",
+                "query: \"SELECT 1 FROM alf WHERE global_state >= 1;\"",
+                "1:2-3:20",
+                "0:0-3:20",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_query_selection() {
+        let source = "queries:\n  - name: Uptime\n    query: |\n      SELECT total_seconds\n      FROM uptime\n      GROUP BY\n        days;\n    interval: 60\n";
+        // Cursor on "days"
+        assert_eq!(
+            chain(source, 6, 9),
+            vec!["days", "5:6-6:13", "3:6-6:13", "2:4-6:13", "1:2-7:16", "0:0-7:16"]
+        );
+    }
+
+    #[test]
+    fn test_nested_mapping_selection() {
+        let source = "controls:\n  macos_settings:\n    custom_settings:\n      - path: ../lib/a.mobileconfig\n";
+        assert_eq!(
+            chain(source, 3, 17),
+            vec![
+                "lib",
+                "../lib/a.mobileconfig",
+                "path: ../lib/a.mobileconfig",
+                "- path: ../lib/a.mobileconfig",
+                "2:4-3:35",
+                "1:2-3:35",
+                "0:0-3:35",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_selection_with_non_ascii_text() {
+        let source = "policies:\n  - name: Überprüfung 🔒 firewall\n    query: SELECT 1 FROM users WHERE description = 'Zürich 🔒' AND uid = 0;\n";
+        // UTF-16 columns: the emoji counts as two units, the umlauts as one
+        assert_eq!(chain(source, 1, 26)[..2], ["firewall", "Überprüfung 🔒 firewall"]);
+        assert_eq!(
+            chain(source, 2, 68)[..3],
+            [
+                "uid",
+                "WHERE description = 'Zürich 🔒' AND uid = 0;",
+                "SELECT 1 FROM users WHERE description = 'Zürich 🔒' AND uid = 0;",
+            ]
+        );
+        let range = selection_ranges(source, &[Position { line: 1, character: 26 }])[0].range;
+        assert_eq!((range.start.character, range.end.character), (25, 33));
+    }
+
+    #[test]
+    fn test_key_column() {
+        assert_eq!(key_column("  - name: a"), 4);
+        assert_eq!(key_column("- - a"), 4);
+        assert_eq!(key_column("    query: x"), 4);
+        assert_eq!(key_column("  -1"), 2);
+    }
+}
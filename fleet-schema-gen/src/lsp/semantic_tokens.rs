@@ -274,26 +274,26 @@ fn tokenize_sql(sql: &str, line: u32, offset: u32, tokens: &mut Vec<RawToken>) {
         "ASC", "DESC", "COUNT", "SUM", "AVG", "MIN", "MAX", "CAST", "COALESCE",
     ];
 
-    let sql_upper = sql.to_uppercase();
     let mut pos = 0;
 
     while pos < sql.len() {
         let remaining = &sql[pos..];
-        let remaining_upper = &sql_upper[pos..];
+        // Byte length of the next character
+        let char_len = remaining.chars().next().map_or(1, char::len_utf8);
 
         // Skip whitespace
         if remaining.starts_with(char::is_whitespace) {
-            pos += 1;
+            pos += char_len;
             continue;
         }
 
         // Check for SQL keywords
         let mut found_keyword = false;
         for keyword in &sql_keywords {
-            if remaining_upper.starts_with(keyword) {
+            if remaining.get(..keyword.len()).is_some_and(|word| word.eq_ignore_ascii_case(keyword)) {
                 // Make sure it's a whole word
                 let keyword_len = keyword.len();
-                let next_char = remaining.chars().nth(keyword_len);
+                let next_char = remaining[keyword_len..].chars().next();
                 if next_char.map(|c| !c.is_alphanumeric() && c != '_').unwrap_or(true) {
                     tokens.push(RawToken {
                         line,
@@ -332,9 +332,8 @@ fn tokenize_sql(sql: &str, line: u32, offset: u32, tokens: &mut Vec<RawToken>) {
         // Check for numbers
         if remaining.starts_with(|c: char| c.is_ascii_digit()) {
             let num_len = remaining
-                .chars()
-                .take_while(|c| c.is_ascii_digit() || *c == '.')
-                .count();
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(remaining.len());
             tokens.push(RawToken {
                 line,
                 start: offset + pos as u32,
@@ -349,9 +348,8 @@ fn tokenize_sql(sql: &str, line: u32, offset: u32, tokens: &mut Vec<RawToken>) {
         // Check for identifiers (potential table names)
         if remaining.starts_with(|c: char| c.is_alphabetic() || c == '_') {
             let ident_len = remaining
-                .chars()
-                .take_while(|c| c.is_alphanumeric() || *c == '_')
-                .count();
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(remaining.len());
             let ident = &remaining[..ident_len];
 
             // Check if this is an osquery table name
@@ -371,7 +369,7 @@ fn tokenize_sql(sql: &str, line: u32, offset: u32, tokens: &mut Vec<RawToken>) {
         }
 
         // Skip other characters
-        pos += 1;
+        pos += char_len;
     }
 }
 
@@ -0,0 +1,145 @@
+//! Signature help for SQL function calls in `query:` fields.
+//!
+//! The SQL of the query up to the cursor is tokenized, so parentheses and
+//! commas in strings and comments don't count, and the innermost open call
+//! of a known function (see [`crate::linter::sql_functions`]) is shown with
+//! the argument under the cursor highlighted.
+
+use tower_lsp::lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, Position, SignatureHelp,
+    SignatureInformation,
+};
+
+use super::selection::query_region;
+use crate::linter::sql::{tokenize, TokenKind};
+use crate::linter::sql_functions::{sql_function, SqlFunction};
+
+/// Characters that open or advance a signature help session.
+pub const TRIGGER_CHARACTERS: &[&str] = &["(", ","];
+
+/// The query's SQL from its start up to `position`.
+fn sql_before(source: &str, position: Position) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let line_idx = position.line as usize;
+    lines.get(line_idx)?;
+    let (_, region) = query_region(&lines, line_idx)?;
+
+    let mut sql = String::new();
+    for (line, start, end) in region {
+        if line > line_idx {
+            break;
+        }
+        let end = if line == line_idx { (position.character as usize).clamp(start, end) } else { end };
+        sql.push_str(lines[line].get(start..end)?);
+        if line == line_idx {
+            return Some(sql);
+        }
+        sql.push('\n');
+    }
+    None
+}
+
+/// The innermost unclosed call of a known function in `sql`, and the index
+/// of the argument `sql` ends in.
+fn open_call(sql: &str) -> Option<(&'static SqlFunction, usize)> {
+    let tokens: Vec<_> =
+        tokenize(sql).into_iter().filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Comment)).collect();
+    // (index of the `(`, commas so far) of each open parenthesis
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.is("(") {
+            open.push((idx, 0));
+        } else if token.is(")") {
+            open.pop();
+        } else if token.is(",") {
+            if let Some((_, commas)) = open.last_mut() {
+                *commas += 1;
+            }
+        }
+    }
+    // Inside plain parentheses (`IN (...)`, grouping) the enclosing call shows
+    open.iter().rev().find_map(|&(paren, commas)| {
+        let name = tokens.get(paren.checked_sub(1)?)?;
+        let function = matches!(name.kind, TokenKind::Identifier | TokenKind::Keyword).then(|| sql_function(name.text))??;
+        Some((function, commas))
+    })
+}
+
+/// Which parameter of `function` the `argument`-th argument fills.
+fn active_parameter(function: &SqlFunction, argument: usize) -> Option<u32> {
+    if function.params.is_empty() {
+        return None;
+    }
+    // Arguments past a trailing `...` all highlight it
+    Some(argument.min(function.params.len() - 1) as u32)
+}
+
+/// Signature help at `position`, when it is inside a function call in a query.
+pub fn signature_help(source: &str, position: Position) -> Option<SignatureHelp> {
+    let sql = sql_before(source, position)?;
+    let (function, argument) = open_call(&sql)?;
+
+    let label = function.signature();
+    // Parameters by their UTF-16 offsets in the label
+    let mut offset = function.name.len() as u32 + 1;
+    let parameters = function
+        .params
+        .iter()
+        .map(|param| {
+            let start = offset;
+            offset += param.encode_utf16().count() as u32 + 2;
+            ParameterInformation {
+                label: ParameterLabel::LabelOffsets([start, start + param.encode_utf16().count() as u32]),
+                documentation: None,
+            }
+        })
+        .collect();
+    let active = active_parameter(function, argument);
+
+    Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation: Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: function.to_markdown(),
+            })),
+            parameters: Some(parameters),
+            active_parameter: active,
+        }],
+        active_signature: Some(0),
+        active_parameter: active,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn active(source: &str, line: u32, character: u32) -> Option<(String, Option<u32>)> {
+        let help = signature_help(source, Position { line, character })?;
+        Some((help.signatures[0].label.clone(), help.active_parameter))
+    }
+
+    #[test]
+    fn test_signature_help() {
+        let source = "queries:\n  - name: q\n    query: SELECT split(path, ',', 1) FROM t\n";
+        assert_eq!(active(source, 2, 24), Some(("split(string, tokens, index)".to_string(), Some(0))));
+        // Inside the string, then past it: its comma isn't a separator
+        assert_eq!(active(source, 2, 31), Some(("split(string, tokens, index)".to_string(), Some(1))));
+        assert_eq!(active(source, 2, 35), Some(("split(string, tokens, index)".to_string(), Some(2))));
+        // After the call is closed
+        assert_eq!(active(source, 2, 37), None);
+
+        // Block scalars, nested calls, and variadic functions
+        let source = "queries:\n  - name: q\n    query: |\n      SELECT concat(a, b,\n        lower(c), d\n";
+        assert_eq!(active(source, 4, 14), Some(("lower(string)".to_string(), Some(0))));
+        assert_eq!(active(source, 4, 18), Some(("concat(X, ...)".to_string(), Some(1))));
+
+        let help = signature_help(source, Position { line: 4, character: 14 }).unwrap();
+        let parameters = help.signatures[0].parameters.as_ref().unwrap();
+        assert_eq!(parameters[0].label, ParameterLabel::LabelOffsets([6, 12]));
+
+        // Outside queries
+        assert!(signature_help("policies:\n  - name: split(a, b\n", Position { line: 1, character: 20 }).is_none());
+    }
+}
@@ -0,0 +1,333 @@
+//! Document store of the language server.
+//!
+//! Open documents are kept as text, since the editor sends each change in
+//! full. Other workspace files the server looks at (lib files referenced by
+//! an open team file) are only kept as [`FileMetadata`]: their item names
+//! and `path:` references with where they are, a content hash, and the
+//! errors shown for them. Bodies are read again when a file's size or
+//! modification time changes, and analyzed again only when its hash does,
+//! so memory stays proportional to what's open on large monorepos.
+
+use dashmap::mapref::multiple::RefMulti;
+use dashmap::mapref::one::Ref;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tower_lsp::lsp_types::{Diagnostic, Position, Range, Url};
+
+use super::position::byte_offset_to_utf16;
+use super::workspace::extract_path_references;
+
+/// A `name:` value and where it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedItem {
+    pub name: String,
+    pub range: Range,
+}
+
+/// A `path:` reference in a stored file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReference {
+    /// Line of the reference (0-indexed)
+    pub line: u32,
+    /// The path as written
+    pub path_value: String,
+    /// Resolved path, if the file exists
+    pub resolved_path: Option<PathBuf>,
+}
+
+/// What the server keeps of a workspace file that isn't open.
+#[derive(Debug, Clone)]
+pub struct FileMetadata {
+    /// Hash of the content the rest was computed from
+    pub hash: u64,
+    /// Size in bytes on disk
+    pub size: u64,
+    modified: Option<SystemTime>,
+    pub names: Vec<NamedItem>,
+    pub references: Vec<FileReference>,
+    /// Errors found in the file
+    pub errors: Vec<Diagnostic>,
+}
+
+impl FileMetadata {
+    /// Metadata of `content` read from `path`.
+    pub fn new(path: &Path, content: &str, errors: Vec<Diagnostic>) -> Self {
+        let references = extract_path_references(content, path)
+            .into_iter()
+            .map(|reference| FileReference {
+                line: reference.line as u32,
+                path_value: reference.path_value,
+                resolved_path: reference.resolved_path,
+            })
+            .collect();
+        Self {
+            hash: content_hash(content),
+            size: content.len() as u64,
+            modified: None,
+            names: named_items(content),
+            references,
+            errors,
+        }
+    }
+
+    /// Approximate heap and inline size, in bytes.
+    pub fn memory_size(&self) -> usize {
+        let names: usize = self.names.iter().map(|item| size_of::<NamedItem>() + item.name.capacity()).sum();
+        let references: usize = self
+            .references
+            .iter()
+            .map(|r| {
+                size_of::<FileReference>()
+                    + r.path_value.capacity()
+                    + r.resolved_path.as_ref().map_or(0, |p| p.as_os_str().len())
+            })
+            .sum();
+        let errors: usize = self.errors.iter().map(|e| size_of::<Diagnostic>() + e.message.capacity()).sum();
+        size_of::<Self>() + names + references + errors
+    }
+}
+
+/// Memory held by a [`DocumentStore`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StoreStats {
+    pub open_documents: usize,
+    /// Text of open documents, in bytes
+    pub open_bytes: usize,
+    pub indexed_files: usize,
+    /// Size on disk of the indexed files, none of which is held
+    pub indexed_file_bytes: u64,
+    /// Metadata kept for the indexed files, in bytes (approximate)
+    pub metadata_bytes: usize,
+}
+
+/// Open documents by URI, and metadata of other files by path.
+#[derive(Debug, Default)]
+pub struct DocumentStore {
+    open: DashMap<String, String>,
+    files: DashMap<PathBuf, Arc<FileMetadata>>,
+}
+
+impl DocumentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text of an open document.
+    pub fn open(&self, uri: String, text: String) {
+        self.open.insert(uri, text);
+    }
+
+    /// Forget a closed document.
+    pub fn close(&self, uri: &str) {
+        self.open.remove(uri);
+    }
+
+    /// Text of an open document.
+    pub fn text(&self, uri: &str) -> Option<Ref<'_, String, String>> {
+        self.open.get(uri)
+    }
+
+    /// Open documents and their text.
+    pub fn open_documents(&self) -> impl Iterator<Item = RefMulti<'_, String, String>> {
+        self.open.iter()
+    }
+
+    /// Content of a file: the open document's text, or what's on disk.
+    pub fn content(&self, uri: &Url) -> Option<String> {
+        match self.open.get(uri.as_str()) {
+            Some(text) => Some(text.clone()),
+            None => std::fs::read_to_string(uri.to_file_path().ok()?).ok(),
+        }
+    }
+
+    /// Metadata of the file at `path`, with its errors from `analyze`.
+    ///
+    /// Open documents are analyzed as they are and not stored. Files on
+    /// disk are read again only when their size or modification time
+    /// changed, and analyzed again only when their content did.
+    pub fn metadata(&self, path: &Path, analyze: impl FnOnce(&str) -> Vec<Diagnostic>) -> Option<Arc<FileMetadata>> {
+        let uri = Url::from_file_path(path).ok();
+        if let Some(text) = uri.as_ref().and_then(|uri| self.open.get(uri.as_str())) {
+            let errors = analyze(&text);
+            return Some(Arc::new(FileMetadata::new(path, &text, errors)));
+        }
+
+        let Ok(stat) = std::fs::metadata(path) else {
+            self.files.remove(path);
+            return None;
+        };
+        let modified = stat.modified().ok();
+        let cached = self.files.get(path).map(|entry| Arc::clone(&entry));
+        if let Some(cached) = &cached {
+            if cached.size == stat.len() && cached.modified.is_some() && cached.modified == modified {
+                return Some(Arc::clone(cached));
+            }
+        }
+
+        let Ok(content) = std::fs::read_to_string(path) else {
+            self.files.remove(path);
+            return None;
+        };
+        let mut metadata = match cached {
+            // Touched but unchanged: keep the analysis
+            Some(cached) if cached.hash == content_hash(&content) => (*cached).clone(),
+            _ => {
+                let errors = analyze(&content);
+                FileMetadata::new(path, &content, errors)
+            }
+        };
+        metadata.size = stat.len();
+        metadata.modified = modified;
+
+        let metadata = Arc::new(metadata);
+        self.files.insert(path.to_path_buf(), Arc::clone(&metadata));
+        Some(metadata)
+    }
+
+    /// Drop the metadata of a file that changed or was deleted.
+    pub fn invalidate(&self, path: &Path) {
+        self.files.remove(path);
+    }
+
+    /// Drop all file metadata, e.g. when the lint config changes.
+    pub fn clear(&self) {
+        self.files.clear();
+    }
+
+    pub fn stats(&self) -> StoreStats {
+        StoreStats {
+            open_documents: self.open.len(),
+            open_bytes: self.open.iter().map(|doc| doc.key().len() + doc.value().capacity()).sum(),
+            indexed_files: self.files.len(),
+            indexed_file_bytes: self.files.iter().map(|file| file.size).sum(),
+            metadata_bytes: self
+                .files
+                .iter()
+                .map(|file| file.key().as_os_str().len() + file.memory_size())
+                .sum(),
+        }
+    }
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// `name:` values of list items and documents, with the value's range
+fn named_items(content: &str) -> Vec<NamedItem> {
+    let mut items = Vec::new();
+    for (line_idx, line) in content.lines().enumerate() {
+        let rest = line.trim_start();
+        let Some(after) = rest.strip_prefix("- ").unwrap_or(rest).trim_start().strip_prefix("name:") else {
+            continue;
+        };
+        let trimmed = after.trim_start();
+        let value = trimmed.split(" #").next().unwrap_or("").trim_end();
+        let name = value.trim_matches(|c| c == '"' || c == '\'');
+        if name.is_empty() {
+            continue;
+        }
+        // `after` is a suffix of `line`
+        let start = line.len() - trimmed.len();
+        items.push(NamedItem {
+            name: name.to_string(),
+            range: Range {
+                start: Position::new(line_idx as u32, byte_offset_to_utf16(line, start)),
+                end: Position::new(line_idx as u32, byte_offset_to_utf16(line, start + value.len())),
+            },
+        });
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_named_items() {
+        let items = named_items("name: Team\npolicies:\n  - name: \"Disk\" # encrypted\n  - name:\n  - path: a.yml\n");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, "Team");
+        assert_eq!(items[0].range, Range::new(Position::new(0, 6), Position::new(0, 10)));
+        assert_eq!(items[1].name, "Disk");
+        assert_eq!(items[1].range, Range::new(Position::new(2, 10), Position::new(2, 16)));
+    }
+
+    #[test]
+    fn test_metadata_is_cached_until_content_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let lib = temp_dir.path().join("lib.yml");
+        fs::write(temp_dir.path().join("other.yml"), "").unwrap();
+        fs::write(&lib, "- name: A\n- path: other.yml\n").unwrap();
+
+        let store = DocumentStore::new();
+        let analyzed = AtomicUsize::new(0);
+        let analyze = |_: &str| {
+            analyzed.fetch_add(1, Ordering::Relaxed);
+            Vec::new()
+        };
+
+        let metadata = store.metadata(&lib, analyze).unwrap();
+        assert_eq!(metadata.names[0].name, "A");
+        assert_eq!(metadata.references[0].path_value, "other.yml");
+        assert!(metadata.references[0].resolved_path.is_some());
+        store.metadata(&lib, analyze).unwrap();
+        assert_eq!(analyzed.load(Ordering::Relaxed), 1);
+
+        // Same content written again: re-read, but not re-analyzed
+        store.invalidate(&lib);
+        fs::write(&lib, "- name: A\n- path: other.yml\n").unwrap();
+        store.metadata(&lib, analyze).unwrap();
+        assert_eq!(analyzed.load(Ordering::Relaxed), 2);
+        let cached = store.files.get(&lib).unwrap().clone();
+        store.files.insert(lib.clone(), Arc::new(FileMetadata { modified: None, ..(*cached).clone() }));
+        store.metadata(&lib, analyze).unwrap();
+        assert_eq!(analyzed.load(Ordering::Relaxed), 2);
+
+        fs::write(&lib, "- name: B\n").unwrap();
+        store.files.insert(lib.clone(), Arc::new(FileMetadata { modified: None, ..(*cached).clone() }));
+        assert_eq!(store.metadata(&lib, analyze).unwrap().names[0].name, "B");
+        assert_eq!(analyzed.load(Ordering::Relaxed), 3);
+
+        // Open documents win over the disk, and aren't stored
+        let uri = Url::from_file_path(&lib).unwrap().to_string();
+        store.open(uri.clone(), "- name: Open\n".to_string());
+        assert_eq!(store.metadata(&lib, analyze).unwrap().names[0].name, "Open");
+        assert_eq!(store.content(&Url::parse(&uri).unwrap()).unwrap(), "- name: Open\n");
+        store.close(&uri);
+        assert_eq!(store.metadata(&lib, analyze).unwrap().names[0].name, "B");
+
+        fs::remove_file(&lib).unwrap();
+        assert!(store.metadata(&lib, analyze).is_none());
+        assert_eq!(store.stats().indexed_files, 0);
+    }
+
+    #[test]
+    fn test_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        let lib = temp_dir.path().join("lib.yml");
+        let content = format!("{}- name: Last\n", "# comment\n".repeat(1000));
+        fs::write(&lib, &content).unwrap();
+
+        let store = DocumentStore::new();
+        store.open("file:///a.yml".to_string(), "abc".to_string());
+        store.metadata(&lib, |_| Vec::new()).unwrap();
+
+        let stats = store.stats();
+        assert_eq!(stats.open_documents, 1);
+        assert_eq!(stats.indexed_files, 1);
+        assert_eq!(stats.indexed_file_bytes, content.len() as u64);
+        assert!(stats.metadata_bytes > 0 && stats.metadata_bytes < content.len() / 4);
+    }
+}
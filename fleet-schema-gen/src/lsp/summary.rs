@@ -0,0 +1,111 @@
+//! `fleet/fileSummary` notification.
+//!
+//! Sent after each analysis of a document so editor extensions can show a
+//! status item such as "Fleet: 2 errors, schema v4.62" without counting
+//! diagnostics themselves. Clients opt in with the `fileSummary`
+//! initialization option.
+
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::notification::Notification;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Url};
+
+/// Initialization option that enables the notification.
+pub const FILE_SUMMARY_OPTION: &str = "fileSummary";
+
+/// The `fleet/fileSummary` notification.
+pub enum FileSummary {}
+
+impl Notification for FileSummary {
+    type Params = FileSummaryParams;
+    const METHOD: &'static str = "fleet/fileSummary";
+}
+
+/// Summary of one analysis of a document
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSummaryParams {
+    pub uri: Url,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    /// Fleet version of the schema the document was checked against
+    pub schema_version: String,
+    /// Whether that schema is the snapshot embedded in the server rather
+    /// than one generated for the workspace's `fleet_version`
+    pub bundled_schema: bool,
+    /// Platforms the document's policies and queries target; empty when it
+    /// doesn't restrict them (every platform)
+    pub platforms: Vec<String>,
+}
+
+impl FileSummaryParams {
+    pub fn new(uri: Url, content: &str, diagnostics: &[Diagnostic], schema_version: &str, bundled_schema: bool) -> Self {
+        let count = |severity| diagnostics.iter().filter(|d| d.severity == Some(severity)).count();
+        Self {
+            uri,
+            errors: count(DiagnosticSeverity::ERROR),
+            warnings: count(DiagnosticSeverity::WARNING),
+            infos: count(DiagnosticSeverity::INFORMATION),
+            schema_version: schema_version.to_string(),
+            bundled_schema,
+            platforms: effective_platforms(content),
+        }
+    }
+}
+
+/// Platforms named by `platform:` fields in `content`, sorted and deduplicated.
+/// Fleet accepts comma-separated lists (`platform: darwin,linux`).
+pub fn effective_platforms(content: &str) -> Vec<String> {
+    let mut platforms: Vec<String> = content
+        .lines()
+        .filter_map(|line| {
+            let field = line.trim_start().trim_start_matches('-').trim_start();
+            let value = field.strip_prefix("platform:")?;
+            Some(value.split(" #").next().unwrap_or("").trim().trim_matches('"').trim_matches('\'').to_string())
+        })
+        .flat_map(|value| value.split(',').map(|p| p.trim().to_string()).collect::<Vec<_>>())
+        .filter(|platform| !platform.is_empty())
+        .collect();
+    platforms.sort();
+    platforms.dedup();
+    platforms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_platforms() {
+        let content = r#"
+- name: FileVault enabled
+  platform: darwin
+  query: SELECT 1 FROM disk_encryption;
+- name: Disk encrypted
+  platform: "linux, darwin" # both
+- name: Any
+  platform:
+"#;
+        assert_eq!(effective_platforms(content), vec!["darwin", "linux"]);
+        assert!(effective_platforms("policies: []\n").is_empty());
+    }
+
+    #[test]
+    fn test_file_summary_params() {
+        let diagnostic = |severity| Diagnostic { severity: Some(severity), ..Default::default() };
+        let diagnostics = vec![
+            diagnostic(DiagnosticSeverity::ERROR),
+            diagnostic(DiagnosticSeverity::ERROR),
+            diagnostic(DiagnosticSeverity::WARNING),
+        ];
+        let uri = Url::parse("file:///repo/default.yml").unwrap();
+
+        let summary = FileSummaryParams::new(uri, "platform: windows\n", &diagnostics, "4.62.0", false);
+        assert_eq!((summary.errors, summary.warnings, summary.infos), (2, 1, 0));
+        assert_eq!(summary.platforms, vec!["windows"]);
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["schemaVersion"], "4.62.0");
+        assert_eq!(json["bundledSchema"], false);
+    }
+}
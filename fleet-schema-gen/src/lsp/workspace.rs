@@ -2,11 +2,19 @@
 //!
 //! Provides cross-file validation including:
 //! - Path reference validation (checking that referenced files exist)
+//! - Errors in referenced files, surfaced at the referencing `path:` line
 //! - Go-to-definition for path references
+//! - A cached file index for path completion
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use super::completion::{matches_context_type, path_context_at, PathContextType};
+use crate::linter::walk::{self, WalkOptions};
 use tower_lsp::lsp_types::{
-    Diagnostic, DiagnosticSeverity, GotoDefinitionResponse, Location, Position, Range, Url,
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, GotoDefinitionResponse, Location,
+    Position, Range, Url,
 };
 
 /// Check path references in a document and return diagnostics for invalid paths.
@@ -32,7 +40,7 @@ pub fn validate_path_references(
                     file_path.parent().unwrap_or(Path::new(".")).to_path_buf()
                 };
 
-                let resolved_path = base_dir.join(&path_value);
+                let resolved_path = normalize_path(&base_dir.join(&path_value));
 
                 if !resolved_path.exists() {
                     // Calculate character positions for the path value
@@ -53,6 +61,12 @@ pub fn validate_path_references(
                         severity: Some(DiagnosticSeverity::ERROR),
                         source: Some("fleet-lsp".to_string()),
                         message: format!("Referenced file not found: {}", path_value),
+                        // Lets the "Create file" code action pick a template
+                        data: Some(serde_json::json!({
+                            "missing_path": resolved_path,
+                            "path": path_value,
+                            "context": path_context_at(source, line_idx),
+                        })),
                         ..Default::default()
                     });
                 }
@@ -63,6 +77,133 @@ pub fn validate_path_references(
     diagnostics
 }
 
+/// Resolve `.` and `..` components without touching the file system, so
+/// URIs of referenced files match those of open documents, and files that
+/// don't exist yet get clean URIs.
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // Nothing above the root
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Link "Referenced file not found" diagnostics to files with the same name
+/// elsewhere in the workspace, which usually means the file was moved or
+/// the directory is misspelled.
+pub fn relate_moved_files(diagnostics: &mut [Diagnostic], index: &FileIndex) {
+    for diagnostic in diagnostics {
+        let missing = diagnostic
+            .data
+            .as_ref()
+            .and_then(|data| data.get("missing_path"))
+            .and_then(|path| path.as_str())
+            .map(PathBuf::from);
+        let Some(name) = missing.as_deref().and_then(|p| p.file_name()) else {
+            continue;
+        };
+
+        let related: Vec<DiagnosticRelatedInformation> = index
+            .files(PathContextType::Generic)
+            .iter()
+            .filter(|candidate| candidate.file_name() == Some(name))
+            .take(3)
+            .filter_map(|candidate| {
+                Some(DiagnosticRelatedInformation {
+                    location: Location { uri: Url::from_file_path(candidate).ok()?, range: Range::default() },
+                    message: format!("A file named {} exists here", name.to_string_lossy()),
+                })
+            })
+            .collect();
+        if !related.is_empty() {
+            diagnostic.related_information = Some(related);
+        }
+    }
+}
+
+/// Point at `path:` references to YAML files that have errors, so breakage
+/// in a lib file shows up in the team file that includes it. `lint` returns
+/// the error diagnostics of a referenced file; the first few are linked as
+/// related information.
+pub fn referenced_file_errors(
+    source: &str,
+    file_path: &Path,
+    lint: impl Fn(&Path) -> Vec<Diagnostic>,
+) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut diagnostics = Vec::new();
+
+    for reference in extract_path_references(source, file_path) {
+        let Some(resolved) = reference.resolved_path else {
+            continue;
+        };
+        if !resolved.extension().is_some_and(|ext| ext == "yml" || ext == "yaml") {
+            continue;
+        }
+
+        let errors = lint(&resolved);
+        if errors.is_empty() {
+            continue;
+        }
+
+        let line = lines.get(reference.line).copied().unwrap_or_default();
+        let start = line.find(&reference.path_value).unwrap_or(0) as u32;
+        let related: Vec<DiagnosticRelatedInformation> = Url::from_file_path(&resolved)
+            .map(|uri| {
+                errors
+                    .iter()
+                    .take(3)
+                    .map(|error| DiagnosticRelatedInformation {
+                        location: Location { uri: uri.clone(), range: error.range },
+                        message: error.message.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        diagnostics.push(Diagnostic {
+            range: Range {
+                start: Position { line: reference.line as u32, character: start },
+                end: Position { line: reference.line as u32, character: start + reference.path_value.len() as u32 },
+            },
+            severity: Some(DiagnosticSeverity::WARNING),
+            source: Some("fleet-lsp".to_string()),
+            message: format!(
+                "Referenced file has {} error{}",
+                errors.len(),
+                if errors.len() == 1 { "" } else { "s" }
+            ),
+            related_information: (!related.is_empty()).then_some(related),
+            ..Default::default()
+        });
+    }
+
+    diagnostics
+}
+
+/// Whether `source` (at `file_path`) has a `path:` reference to `target`.
+pub fn references_file(source: &str, file_path: &Path, target: &Path) -> bool {
+    let target = target.canonicalize().unwrap_or_else(|_| target.to_path_buf());
+    extract_path_references(source, file_path).into_iter().any(|reference| {
+        reference
+            .resolved_path
+            .is_some_and(|resolved| resolved.canonicalize().unwrap_or(resolved) == target)
+    })
+}
+
 /// Extract path value from a line like "path: lib/policies.yml"
 fn extract_path_value(line: &str) -> Option<String> {
     let value = line.strip_prefix("path:")?.trim();
@@ -108,7 +249,7 @@ pub fn get_path_definition(
         file_path.parent().unwrap_or(Path::new(".")).to_path_buf()
     };
 
-    let resolved_path = base_dir.join(&path_value);
+    let resolved_path = normalize_path(&base_dir.join(&path_value));
 
     if !resolved_path.exists() {
         return None;
@@ -134,21 +275,10 @@ pub fn get_path_definition(
 
 /// Find all files in a workspace that are Fleet GitOps YAML files.
 pub fn find_fleet_files(workspace_root: &Path) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-
-    if let Ok(entries) = std::fs::read_dir(workspace_root) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() && is_fleet_yaml(&path) {
-                files.push(path);
-            } else if path.is_dir() {
-                // Recursively scan subdirectories
-                files.extend(find_fleet_files(&path));
-            }
-        }
-    }
-
-    files
+    crate::linter::walk::files(workspace_root, &Default::default())
+        .into_iter()
+        .filter(|path| is_fleet_yaml(path))
+        .collect()
 }
 
 /// Check if a file is likely a Fleet GitOps YAML file.
@@ -201,6 +331,80 @@ fn is_fleet_yaml(path: &Path) -> bool {
     false
 }
 
+/// Directories scanned for path completion candidates.
+const INDEXED_DIRS: &[&str] = &["lib", "teams"];
+
+/// Directory levels below `lib/` and `teams/` that are indexed.
+const INDEX_MAX_DEPTH: usize = 5;
+
+/// Cached path completion candidates, keyed by context type.
+///
+/// Built on first use from `lib/` and `teams/` and dropped with
+/// [`FileIndex::invalidate`] when the client reports created or deleted
+/// files, so completion doesn't rescan the workspace on every keystroke.
+#[derive(Debug)]
+pub struct FileIndex {
+    root: PathBuf,
+    cache: RwLock<Option<HashMap<PathContextType, Arc<Vec<PathBuf>>>>>,
+}
+
+impl FileIndex {
+    /// Index for the workspace at `root` (scanned lazily).
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Workspace root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Candidate files for a context type, sorted by path.
+    pub fn files(&self, context_type: PathContextType) -> Arc<Vec<PathBuf>> {
+        if let Some(files) = self.cache.read().ok().and_then(|cache| cache.as_ref()?.get(&context_type).cloned()) {
+            return files;
+        }
+
+        let index = self.build();
+        let files = index.get(&context_type).cloned().unwrap_or_default();
+        if let Ok(mut cache) = self.cache.write() {
+            *cache = Some(index);
+        }
+        files
+    }
+
+    /// Drop the cached index; the next lookup rescans.
+    pub fn invalidate(&self) {
+        if let Ok(mut cache) = self.cache.write() {
+            *cache = None;
+        }
+    }
+
+    fn build(&self) -> HashMap<PathContextType, Arc<Vec<PathBuf>>> {
+        let options = WalkOptions {
+            max_depth: Some(INDEX_MAX_DEPTH),
+            ..Default::default()
+        };
+        let files: Vec<PathBuf> = INDEXED_DIRS
+            .iter()
+            .map(|dir| self.root.join(dir))
+            .filter(|dir| dir.is_dir())
+            .flat_map(|dir| walk::files(&dir, &options))
+            .collect();
+
+        PathContextType::ALL
+            .into_iter()
+            .map(|context_type| {
+                let matching = files.iter().filter(|path| matches_context_type(path, &context_type)).cloned().collect();
+                (context_type, Arc::new(matching))
+            })
+            .collect()
+    }
+}
+
 /// PathReference represents a reference from one file to another.
 #[derive(Debug, Clone)]
 pub struct PathReference {
@@ -224,7 +428,7 @@ pub fn extract_path_references(source: &str, file_path: &Path) -> Vec<PathRefere
 
         if trimmed.starts_with("path:") {
             if let Some(path_value) = extract_path_value(trimmed) {
-                let resolved = base_dir.join(&path_value);
+                let resolved = normalize_path(&base_dir.join(&path_value));
                 refs.push(PathReference {
                     source_file: file_path.to_path_buf(),
                     line: line_idx,
@@ -248,6 +452,13 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path(Path::new("/repo/teams/../lib/./a.yml")), PathBuf::from("/repo/lib/a.yml"));
+        assert_eq!(normalize_path(Path::new("../../lib/a.yml")), PathBuf::from("../../lib/a.yml"));
+        assert_eq!(normalize_path(Path::new("/../a.yml")), PathBuf::from("/a.yml"));
+    }
+
     #[test]
     fn test_extract_path_value() {
         assert_eq!(
@@ -285,6 +496,59 @@ mod tests {
         // Should have 1 diagnostic for the missing file
         assert_eq!(diagnostics.len(), 1);
         assert!(diagnostics[0].message.contains("missing.yml"));
+        let data = diagnostics[0].data.as_ref().unwrap();
+        assert_eq!(data["context"], "policy");
+        assert_eq!(data["path"], "lib/missing.yml");
+
+        // A file with the same name elsewhere is linked
+        fs::create_dir_all(lib_dir.join("macos")).unwrap();
+        fs::write(lib_dir.join("macos/missing.yml"), "").unwrap();
+        let mut diagnostics = diagnostics;
+        relate_moved_files(&mut diagnostics, &FileIndex::new(temp_dir.path()));
+        let related = diagnostics[0].related_information.as_ref().unwrap();
+        assert!(related[0].location.uri.path().ends_with("lib/macos/missing.yml"));
+    }
+
+    #[test]
+    fn test_referenced_file_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let lib_dir = temp_dir.path().join("lib");
+        fs::create_dir(&lib_dir).unwrap();
+        fs::write(lib_dir.join("broken.yml"), "- name: Broken").unwrap();
+        fs::write(lib_dir.join("fine.yml"), "- name: Fine").unwrap();
+        fs::write(lib_dir.join("script.sh"), "echo").unwrap();
+
+        let source = "policies:\n  - path: lib/fine.yml\n  - path: lib/broken.yml\nscripts:\n  - path: lib/script.sh\n";
+        let team_file = temp_dir.path().join("default.yml");
+
+        let error = |line: u32, message: &str| Diagnostic {
+            range: Range { start: Position { line, character: 2 }, end: Position { line, character: 6 } },
+            severity: Some(DiagnosticSeverity::ERROR),
+            message: message.to_string(),
+            ..Default::default()
+        };
+        let diagnostics = referenced_file_errors(source, &team_file, |path| {
+            assert!(!path.ends_with("script.sh"), "only YAML files are linted");
+            if path.ends_with("broken.yml") {
+                vec![error(0, "Missing required field 'query'"), error(0, "Missing required field 'platform'")]
+            } else {
+                vec![]
+            }
+        });
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.message, "Referenced file has 2 errors");
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diagnostic.range.start, Position { line: 2, character: 10 });
+        assert_eq!(diagnostic.range.end, Position { line: 2, character: 24 });
+        let related = diagnostic.related_information.as_ref().unwrap();
+        assert_eq!(related.len(), 2);
+        assert!(related[0].location.uri.path().ends_with("lib/broken.yml"));
+        assert_eq!(related[1].message, "Missing required field 'platform'");
+
+        assert!(references_file(source, &team_file, &lib_dir.join("broken.yml")));
+        assert!(!references_file(source, &team_file, &lib_dir.join("other.yml")));
     }
 
     #[test]
@@ -304,4 +568,23 @@ mod tests {
         assert_eq!(refs[1].path_value, "lib/more-policies.yml");
         assert_eq!(refs[1].line, 4);
     }
+
+    #[test]
+    fn test_file_index_caches_until_invalidated() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("lib/macos/scripts")).unwrap();
+        fs::write(root.join("lib/macos/scripts/a.sh"), "").unwrap();
+        fs::write(root.join("lib/policies.yml"), "").unwrap();
+
+        let index = FileIndex::new(root);
+        assert_eq!(index.files(PathContextType::Script).len(), 1);
+        assert_eq!(index.files(PathContextType::Policy).len(), 1);
+
+        // New files show up only after invalidation
+        fs::write(root.join("lib/macos/scripts/b.sh"), "").unwrap();
+        assert_eq!(index.files(PathContextType::Script).len(), 1);
+        index.invalidate();
+        assert_eq!(index.files(PathContextType::Script).len(), 2);
+    }
 }
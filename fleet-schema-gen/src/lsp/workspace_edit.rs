@@ -0,0 +1,469 @@
+//! Multi-file workspace edits for code actions.
+//!
+//! Code actions that touch more than one file (creating a missing file and
+//! filling it in, edits spread across the repo) are built as versioned
+//! `documentChanges`. This module adapts them to what the client supports,
+//! checks them against the documents the server has open before applying,
+//! and renders them as a unified diff for the `fleet/previewWorkspaceEdit`
+//! request so clients can show the changes before accepting them.
+
+use std::collections::BTreeMap;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use tower_lsp::lsp_types::{
+    ClientCapabilities, CodeAction, Command, CreateFile, DocumentChangeOperation, DocumentChanges,
+    OneOf, ResourceOp, ResourceOperationKind, TextDocumentEdit, TextEdit, Url, WorkspaceEdit,
+};
+
+use super::position::to_byte_offset;
+
+/// Command that applies a workspace edit on the server's side (arguments:
+/// the edit, then an optional follow-up command). Used for clients that
+/// can't create files themselves.
+pub const APPLY_EDIT_COMMAND: &str = "fleet.applyWorkspaceEdit";
+
+/// Custom request returning a workspace edit as a unified diff.
+pub const PREVIEW_EDIT_REQUEST: &str = "fleet/previewWorkspaceEdit";
+
+/// Which parts of `WorkspaceEdit` the client can apply. Until `initialize`
+/// says otherwise, assume the LSP 3.0 baseline of neither.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditSupport {
+    /// Versioned `documentChanges` instead of the plain `changes` map
+    pub document_changes: bool,
+    /// `CreateFile` resource operations
+    pub create_files: bool,
+}
+
+impl EditSupport {
+    pub fn from_capabilities(capabilities: &ClientCapabilities) -> Self {
+        let edit = capabilities.workspace.as_ref().and_then(|w| w.workspace_edit.as_ref());
+        let document_changes = edit.and_then(|e| e.document_changes).unwrap_or(false);
+        let create_files = document_changes
+            && edit
+                .and_then(|e| e.resource_operations.as_ref())
+                .is_some_and(|ops| ops.contains(&ResourceOperationKind::Create));
+        Self { document_changes, create_files }
+    }
+}
+
+/// Parameters of `fleet/previewWorkspaceEdit`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewParams {
+    pub edit: WorkspaceEdit,
+}
+
+/// Result of `fleet/previewWorkspaceEdit`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewResult {
+    /// Unified diff of every file the edit touches
+    pub diff: String,
+    /// URIs of the touched files, in diff order
+    pub files: Vec<Url>,
+}
+
+/// Text edits of `edit` grouped per file, and whether the edit creates the file.
+fn file_edits(edit: &WorkspaceEdit) -> BTreeMap<Url, (Vec<TextEdit>, bool)> {
+    let mut files: BTreeMap<Url, (Vec<TextEdit>, bool)> = BTreeMap::new();
+
+    if let Some(changes) = &edit.changes {
+        for (uri, edits) in changes {
+            files.entry(uri.clone()).or_default().0.extend(edits.iter().cloned());
+        }
+    }
+
+    let mut add_document_edit = |doc_edit: &TextDocumentEdit| {
+        let edits = doc_edit.edits.iter().map(|e| match e {
+            OneOf::Left(edit) => edit.clone(),
+            OneOf::Right(annotated) => annotated.text_edit.clone(),
+        });
+        files.entry(doc_edit.text_document.uri.clone()).or_default().0.extend(edits);
+    };
+    match &edit.document_changes {
+        Some(DocumentChanges::Edits(edits)) => edits.iter().for_each(&mut add_document_edit),
+        Some(DocumentChanges::Operations(operations)) => {
+            let mut created = Vec::new();
+            for operation in operations {
+                match operation {
+                    DocumentChangeOperation::Edit(doc_edit) => add_document_edit(doc_edit),
+                    DocumentChangeOperation::Op(ResourceOp::Create(create)) => created.push(create.uri.clone()),
+                    DocumentChangeOperation::Op(_) => {}
+                }
+            }
+            for uri in created {
+                files.entry(uri).or_default().1 = true;
+            }
+        }
+        None => {}
+    }
+
+    files
+}
+
+/// Versioned document edits whose version no longer matches the document,
+/// per `current_version` (`None` for documents that aren't open).
+pub fn stale_documents(edit: &WorkspaceEdit, current_version: impl Fn(&Url) -> Option<i32>) -> Vec<Url> {
+    let doc_edits: Vec<&TextDocumentEdit> = match &edit.document_changes {
+        Some(DocumentChanges::Edits(edits)) => edits.iter().collect(),
+        Some(DocumentChanges::Operations(operations)) => operations
+            .iter()
+            .filter_map(|op| match op {
+                DocumentChangeOperation::Edit(doc_edit) => Some(doc_edit),
+                DocumentChangeOperation::Op(_) => None,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    doc_edits
+        .into_iter()
+        .filter(|doc_edit| {
+            let uri = &doc_edit.text_document.uri;
+            match (doc_edit.text_document.version, current_version(uri)) {
+                (Some(expected), Some(current)) => expected != current,
+                _ => false,
+            }
+        })
+        .map(|doc_edit| doc_edit.text_document.uri.clone())
+        .collect()
+}
+
+/// Apply non-overlapping text edits to `content`.
+pub fn apply_text_edits(content: &str, edits: &[TextEdit]) -> String {
+    let mut ranges: Vec<(usize, usize, &str)> = edits
+        .iter()
+        .map(|edit| {
+            let start = to_byte_offset(content, edit.range.start);
+            let end = to_byte_offset(content, edit.range.end).max(start);
+            (start, end, edit.new_text.as_str())
+        })
+        .collect();
+    // Apply back to front so earlier offsets stay valid; edits inserted at
+    // the same position keep their order
+    ranges.sort_by_key(|&(start, end, _)| (start, end));
+
+    let mut result = content.to_string();
+    for (start, end, text) in ranges.into_iter().rev() {
+        result.replace_range(start..end, text);
+    }
+    result
+}
+
+/// Render `edit` as a unified diff. `read` returns the current content of a
+/// file (open document or disk); files the edit creates that don't exist yet
+/// start out empty.
+pub fn preview(edit: &WorkspaceEdit, read: impl Fn(&Url) -> Option<String>) -> PreviewResult {
+    let mut diff = String::new();
+    let mut files = Vec::new();
+
+    for (uri, (edits, created)) in file_edits(edit) {
+        let existing = read(&uri);
+        let created = created && existing.is_none();
+        let original = existing.unwrap_or_default();
+        let updated = apply_text_edits(&original, &edits);
+        if original == updated && !created {
+            continue;
+        }
+
+        let path = uri.to_file_path().map(|p| p.display().to_string()).unwrap_or_else(|_| uri.to_string());
+        let old_header = if created { "/dev/null".to_string() } else { format!("a/{}", path.trim_start_matches('/')) };
+        let new_header = format!("b/{}", path.trim_start_matches('/'));
+        diff.push_str(
+            &TextDiff::from_lines(&original, &updated)
+                .unified_diff()
+                .context_radius(3)
+                .header(&old_header, &new_header)
+                .to_string(),
+        );
+        files.push(uri);
+    }
+
+    PreviewResult { diff, files }
+}
+
+/// Whether `edit` changes nothing
+pub fn is_empty(edit: &WorkspaceEdit) -> bool {
+    let no_changes = edit.changes.as_ref().is_none_or(|changes| changes.is_empty());
+    let no_document_changes = match &edit.document_changes {
+        Some(DocumentChanges::Edits(edits)) => edits.is_empty(),
+        Some(DocumentChanges::Operations(operations)) => operations.is_empty(),
+        None => true,
+    };
+    no_changes && no_document_changes
+}
+
+/// Take the file creations out of `edit`, leaving only its text edits.
+pub fn split_file_creations(edit: WorkspaceEdit) -> (Vec<CreateFile>, WorkspaceEdit) {
+    let Some(DocumentChanges::Operations(operations)) = edit.document_changes else {
+        return (Vec::new(), edit);
+    };
+
+    let mut creations = Vec::new();
+    let mut rest = Vec::new();
+    for operation in operations {
+        match operation {
+            DocumentChangeOperation::Op(ResourceOp::Create(create)) => creations.push(create),
+            other => rest.push(other),
+        }
+    }
+
+    let edit = WorkspaceEdit {
+        document_changes: Some(DocumentChanges::Operations(rest)),
+        ..edit
+    };
+    (creations, edit)
+}
+
+/// Create the file of a `CreateFile` operation on disk, empty, honouring
+/// its `overwrite` and `ignoreIfExists` options.
+pub fn create_file(create: &CreateFile) -> io::Result<()> {
+    let path = create.uri.to_file_path().map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("not a file URI: {}", create.uri))
+    })?;
+    let options = create.options.as_ref();
+
+    if path.exists() {
+        if options.and_then(|o| o.overwrite) == Some(true) {
+            return std::fs::write(&path, "");
+        }
+        if options.and_then(|o| o.ignore_if_exists) == Some(true) {
+            return Ok(());
+        }
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", path.display()),
+        ));
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, "")
+}
+
+/// Downgrade `edit` to the `changes` map for clients without
+/// `documentChanges` support. Versions and resource operations are dropped.
+pub fn to_changes_map(edit: WorkspaceEdit) -> WorkspaceEdit {
+    if edit.document_changes.is_none() {
+        return edit;
+    }
+    let changes = file_edits(&edit)
+        .into_iter()
+        .filter(|(_, (edits, _))| !edits.is_empty())
+        .map(|(uri, (edits, _))| (uri, edits))
+        .collect();
+    WorkspaceEdit { changes: Some(changes), document_changes: None, change_annotations: edit.change_annotations }
+}
+
+/// Fit a code action's edit to what the client can apply. An edit that
+/// creates files the client can't create moves into [`APPLY_EDIT_COMMAND`],
+/// with the action's own command as its follow-up.
+pub fn adapt_for_client(mut action: CodeAction, support: EditSupport) -> CodeAction {
+    let Some(edit) = action.edit.take() else {
+        return action;
+    };
+
+    let creates_files = matches!(
+        &edit.document_changes,
+        Some(DocumentChanges::Operations(ops))
+            if ops.iter().any(|op| matches!(op, DocumentChangeOperation::Op(ResourceOp::Create(_))))
+    );
+
+    if creates_files && !support.create_files {
+        let mut arguments = vec![serde_json::json!(edit)];
+        if let Some(follow_up) = action.command.take() {
+            arguments.push(serde_json::json!(follow_up));
+        }
+        action.command = Some(Command {
+            title: action.title.clone(),
+            command: APPLY_EDIT_COMMAND.to_string(),
+            arguments: Some(arguments),
+        });
+    } else if !support.document_changes {
+        action.edit = Some(to_changes_map(edit));
+    } else {
+        action.edit = Some(edit);
+    }
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tower_lsp::lsp_types::{
+        CreateFileOptions, OptionalVersionedTextDocumentIdentifier, Position, Range,
+        WorkspaceClientCapabilities, WorkspaceEditClientCapabilities,
+    };
+
+    fn text_edit(line: u32, start: u32, end: u32, text: &str) -> TextEdit {
+        TextEdit {
+            range: Range {
+                start: Position { line, character: start },
+                end: Position { line, character: end },
+            },
+            new_text: text.to_string(),
+        }
+    }
+
+    fn doc_edit(uri: &Url, version: Option<i32>, edits: Vec<TextEdit>) -> DocumentChangeOperation {
+        DocumentChangeOperation::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri: uri.clone(), version },
+            edits: edits.into_iter().map(OneOf::Left).collect(),
+        })
+    }
+
+    fn create(uri: &Url) -> DocumentChangeOperation {
+        DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+            uri: uri.clone(),
+            options: Some(CreateFileOptions { overwrite: Some(false), ignore_if_exists: Some(true) }),
+            annotation_id: None,
+        }))
+    }
+
+    fn operations(ops: Vec<DocumentChangeOperation>) -> WorkspaceEdit {
+        WorkspaceEdit { document_changes: Some(DocumentChanges::Operations(ops)), ..Default::default() }
+    }
+
+    #[test]
+    fn test_edit_support_from_capabilities() {
+        assert_eq!(EditSupport::from_capabilities(&ClientCapabilities::default()), EditSupport::default());
+
+        let capabilities = ClientCapabilities {
+            workspace: Some(WorkspaceClientCapabilities {
+                workspace_edit: Some(WorkspaceEditClientCapabilities {
+                    document_changes: Some(true),
+                    resource_operations: Some(vec![ResourceOperationKind::Create, ResourceOperationKind::Rename]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            EditSupport::from_capabilities(&capabilities),
+            EditSupport { document_changes: true, create_files: true }
+        );
+    }
+
+    #[test]
+    fn test_apply_text_edits() {
+        let content = "name: Old\nplatform: darwin\n";
+        let edits = vec![text_edit(1, 10, 16, "windows"), text_edit(0, 6, 9, "New"), text_edit(2, 0, 0, "tags: []\n")];
+        assert_eq!(apply_text_edits(content, &edits), "name: New\nplatform: windows\ntags: []\n");
+    }
+
+    #[test]
+    fn test_stale_documents() {
+        let a = Url::parse("file:///repo/a.yml").unwrap();
+        let b = Url::parse("file:///repo/b.yml").unwrap();
+        let c = Url::parse("file:///repo/c.yml").unwrap();
+        let edit = operations(vec![
+            doc_edit(&a, Some(3), vec![]),
+            doc_edit(&b, Some(1), vec![]),
+            doc_edit(&c, None, vec![]),
+        ]);
+
+        let versions = HashMap::from([(a.clone(), 3), (b.clone(), 2), (c.clone(), 7)]);
+        assert_eq!(stale_documents(&edit, |uri| versions.get(uri).copied()), vec![b]);
+    }
+
+    #[test]
+    fn test_preview() {
+        let team = Url::parse("file:///repo/teams/a.yml").unwrap();
+        let policy = Url::parse("file:///repo/lib/p.yml").unwrap();
+        let edit = operations(vec![
+            doc_edit(&team, Some(1), vec![text_edit(1, 10, 16, "windows")]),
+            create(&policy),
+            doc_edit(&policy, None, vec![text_edit(0, 0, 0, "- name: P\n")]),
+        ]);
+
+        let result = preview(&edit, |uri| (uri == &team).then(|| "name: A\nplatform: darwin\n".to_string()));
+        assert_eq!(result.files, vec![policy, team]);
+        assert_eq!(
+            result.diff,
+            "--- /dev/null\n+++ b/repo/lib/p.yml\n@@ -0,0 +1 @@\n+- name: P\n\
+             --- a/repo/teams/a.yml\n+++ b/repo/teams/a.yml\n@@ -1,2 +1,2 @@\n name: A\n-platform: darwin\n+platform: windows\n"
+        );
+    }
+
+    #[test]
+    fn test_split_file_creations_and_changes_map() {
+        let policy = Url::parse("file:///repo/lib/p.yml").unwrap();
+        let edit = operations(vec![create(&policy), doc_edit(&policy, None, vec![text_edit(0, 0, 0, "x")])]);
+
+        let (creations, rest) = split_file_creations(edit.clone());
+        assert_eq!(creations.len(), 1);
+        assert_eq!(creations[0].uri, policy);
+        let Some(DocumentChanges::Operations(ops)) = &rest.document_changes else {
+            panic!("expected operations");
+        };
+        assert_eq!(ops.len(), 1);
+        assert!(!is_empty(&rest));
+        assert!(is_empty(&split_file_creations(operations(vec![create(&policy)])).1));
+
+        let map = to_changes_map(edit);
+        assert!(map.document_changes.is_none());
+        assert_eq!(map.changes.unwrap()[&policy], vec![text_edit(0, 0, 0, "x")]);
+    }
+
+    #[test]
+    fn test_create_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib/policies/new.yml");
+        let mut create = CreateFile {
+            uri: Url::from_file_path(&path).unwrap(),
+            options: Some(CreateFileOptions { overwrite: None, ignore_if_exists: None }),
+            annotation_id: None,
+        };
+
+        create_file(&create).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+
+        std::fs::write(&path, "- name: P\n").unwrap();
+        assert_eq!(create_file(&create).unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+
+        create.options = Some(CreateFileOptions { overwrite: None, ignore_if_exists: Some(true) });
+        create_file(&create).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "- name: P\n");
+
+        create.options = Some(CreateFileOptions { overwrite: Some(true), ignore_if_exists: Some(true) });
+        create_file(&create).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+    }
+
+    #[test]
+    fn test_adapt_for_client() {
+        let policy = Url::parse("file:///repo/lib/p.yml").unwrap();
+        let action = CodeAction {
+            title: "Create p.yml".to_string(),
+            edit: Some(operations(vec![create(&policy), doc_edit(&policy, None, vec![text_edit(0, 0, 0, "x")])])),
+            command: Some(Command { title: "Open".to_string(), command: "fleet.openFile".to_string(), arguments: None }),
+            ..Default::default()
+        };
+
+        let full = EditSupport { document_changes: true, create_files: true };
+        assert_eq!(adapt_for_client(action.clone(), full), action);
+
+        // Without create support the server applies the edit itself
+        let adapted = adapt_for_client(action.clone(), EditSupport { document_changes: true, create_files: false });
+        assert!(adapted.edit.is_none());
+        let command = adapted.command.unwrap();
+        assert_eq!(command.command, APPLY_EDIT_COMMAND);
+        let arguments = command.arguments.unwrap();
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[1]["command"], "fleet.openFile");
+
+        // Text-only edits fall back to the changes map
+        let text_only = CodeAction {
+            edit: Some(operations(vec![doc_edit(&policy, Some(2), vec![text_edit(0, 0, 0, "x")])])),
+            ..Default::default()
+        };
+        let adapted = adapt_for_client(text_only, EditSupport::default());
+        assert!(adapted.edit.unwrap().changes.is_some());
+    }
+}
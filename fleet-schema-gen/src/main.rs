@@ -5,7 +5,7 @@ mod utils;
 mod linter;
 mod lsp;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use anyhow::Result;
 use std::path::PathBuf;
 
@@ -14,9 +14,26 @@ use std::path::PathBuf;
 #[command(author = env!("CARGO_PKG_AUTHORS"))]
 #[command(version = concat!(env!("CARGO_PKG_VERSION"), "+", env!("BUILD_TIMESTAMP")))]
 #[command(about = "Generate Fleet GitOps schemas for multiple editors", long_about = None)]
+#[command(arg_required_else_help = true)]
 struct Cli {
+    /// Print the man page (roff) to stdout
+    #[arg(long)]
+    man: bool,
+
+    /// More detailed logs on stderr (-v debug, -vv trace)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Only log errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log as JSON lines on stderr (for CI and automation)
+    #[arg(long, global = true)]
+    json_logs: bool,
+
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -31,7 +48,7 @@ enum Commands {
         #[arg(short, long, default_value = "./output")]
         output: PathBuf,
 
-        /// Specific editor format (vscode, sublime, sublime-lsp, intellij, neovim, strict, all)
+        /// Specific editor format (vscode, sublime, sublime-lsp, intellij, intellij-plugin, neovim, generic-lsp, strict, lsp, docs, all)
         #[arg(short, long, default_value = "all")]
         editor: String,
 
@@ -46,7 +63,7 @@ enum Commands {
 
     /// Update schemas from specific source
     Update {
-        /// Source to update from (docs, github, openapi, local)
+        /// Source to update from (docs, github, changelog, openapi, local)
         #[arg(short, long)]
         source: String,
 
@@ -69,30 +86,101 @@ enum Commands {
         fix: bool,
 
         /// Output format (text, json)
-        #[arg(short, long, default_value = "text")]
+        #[arg(long, default_value = "text")]
         format: String,
+
+        /// Also run each query through the local osqueryi (results are host-specific)
+        #[arg(long)]
+        exec_osquery: bool,
+
+        /// Check that software package URLs are reachable (network access)
+        #[arg(long)]
+        check_urls: bool,
+
+        /// With --check-urls, report unreachable hosts as info instead of errors
+        #[arg(long, requires = "check_urls")]
+        allow_offline: bool,
+
+        /// Fleet version the repository targets (overrides fleet_version in .fleetlint.toml)
+        #[arg(long)]
+        fleet_version: Option<String>,
+
+        /// Built-in preset to apply on top of .fleetlint.toml
+        #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(linter::presets::possible_values()))]
+        preset: Option<String>,
+
+        /// Also lint files ignored by .gitignore/.ignore
+        #[arg(long)]
+        no_ignore: bool,
+    },
+
+    /// Format Fleet GitOps YAML files
+    ///
+    /// With --sort, orders policies, queries and labels alphabetically by
+    /// name. Comments above an item move with it. With --sql, lays out the
+    /// SQL of `query:` fields one clause per line with uppercase keywords.
+    Fmt {
+        /// File or directory to format
+        path: PathBuf,
+
+        /// Sort policies, queries and labels by name
+        #[arg(long)]
+        sort: bool,
+
+        /// Pretty-print the SQL of `query:` fields
+        #[arg(long)]
+        sql: bool,
+
+        /// List files that would change without writing them (exit 1 if any)
+        #[arg(long)]
+        check: bool,
     },
 
     /// Validate YAML file against generated schema
     Validate {
-        /// YAML file to validate
-        file: PathBuf,
+        /// YAML files, directories, or glob patterns to validate
+        #[arg(required = true)]
+        paths: Vec<String>,
 
-        /// Schema file to validate against
+        /// JSON Schema to validate every file against, or "bundled" for the
+        /// embedded snapshot's schema of each file's kind (default: the
+        /// generated schemas for the Fleet version, else built-in lint rules)
         #[arg(short, long)]
-        schema: Option<PathBuf>,
+        schema: Option<linter::validate::SchemaSource>,
+
+        /// Output format (text, json, sarif)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Fleet version to validate for (selects the generated schema for that version)
+        #[arg(long)]
+        fleet_version: Option<String>,
     },
 
     /// Migrate Fleet config between versions
+    #[command(args_conflicts_with_subcommands = true)]
     Migrate {
+        #[command(subcommand)]
+        command: Option<MigrateCommand>,
+
         /// Path to config directory or file
-        path: PathBuf,
+        path: Option<PathBuf>,
+
+        /// Read one YAML document from stdin and write the migrated document
+        /// to stdout (no files, backups or git); needs --from
+        #[arg(long, conflicts_with_all = ["path", "dry_run", "git"])]
+        stdin: bool,
+
+        /// With --stdin, the document's path in the repository, so only
+        /// transformations whose pattern matches it apply
+        #[arg(long, requires = "stdin")]
+        stdin_filename: Option<String>,
 
         /// Source Fleet version (auto-detected if not specified)
         #[arg(short, long)]
         from: Option<String>,
 
-        /// Target Fleet version (defaults to latest)
+        /// Target Fleet version, e.g. 4.62.0-rc1 or `latest` (defaults to the newest known migration)
         #[arg(short, long)]
         to: Option<String>,
 
@@ -100,13 +188,37 @@ enum Commands {
         #[arg(short = 'n', long)]
         dry_run: bool,
 
+        /// With --dry-run, show side-by-side instead of unified diffs
+        #[arg(short, long, requires = "dry_run")]
+        side_by_side: bool,
+
         /// Custom migrations file (default: migrations.toml)
         #[arg(short, long)]
         migrations_file: Option<PathBuf>,
 
-        /// Create Git branch and commit
+        /// Create Git branch and commit (templates: [migrate] in .fleetlint.toml)
         #[arg(short, long)]
         git: bool,
+
+        /// Migrate the other files when one fails, and report failures at the end
+        #[arg(long, conflicts_with = "fail_fast")]
+        keep_going: bool,
+
+        /// Stop at the first file that fails, writing nothing (default)
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Number of files to migrate concurrently
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+
+        /// With --git, push the branch to the configured remote
+        #[arg(long, requires = "git")]
+        push: bool,
+
+        /// With --git, push and open a pull request with gh
+        #[arg(long, requires = "git")]
+        pr: bool,
     },
 
     /// Show diff between two Fleet versions
@@ -118,7 +230,7 @@ enum Commands {
         #[arg(short, long)]
         from: String,
 
-        /// Second version
+        /// Second version (`latest` for the newest Fleet release)
         #[arg(short, long)]
         to: String,
 
@@ -127,6 +239,149 @@ enum Commands {
         side_by_side: bool,
     },
 
+    /// Report CIS benchmark coverage per team
+    ///
+    /// Uses `# cis: <id>` annotations on policies to report which CIS
+    /// macOS/Windows controls are covered by each team's policies.
+    /// With --by-platform, reports which platforms each team has a policy
+    /// for per control objective (disk encryption, firewall, ...) instead.
+    Coverage {
+        /// Path to GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Benchmark to report (cis-macos, cis-windows; default: all)
+        #[arg(short, long, conflicts_with = "by_platform")]
+        benchmark: Option<String>,
+
+        /// Report platform coverage of control objectives
+        #[arg(long)]
+        by_platform: bool,
+
+        /// Objectives for --by-platform (disk-encryption, firewall,
+        /// screen-lock, os-updates, antivirus; default: all)
+        #[arg(long, value_delimiter = ',', requires = "by_platform")]
+        objective: Vec<String>,
+
+        /// Platforms for --by-platform (default: darwin,windows,linux)
+        #[arg(long, value_delimiter = ',', requires = "by_platform")]
+        platform: Vec<String>,
+
+        /// List controls that are not covered
+        #[arg(short, long)]
+        uncovered: bool,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Run policy/query tests against local fixture data
+    ///
+    /// Finds `*.tests.yml` files, loads the referenced CSV/JSON fixtures
+    /// into an in-memory SQLite database, and checks each policy's SQL
+    /// against its expected pass/fail outcome.
+    Test {
+        /// Tests file or directory to search
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Record fixtures for `test` from the local osquery
+    Fixtures {
+        #[command(subcommand)]
+        action: FixturesAction,
+    },
+
+    /// Generate human-readable documentation from a GitOps repository
+    ///
+    /// Renders one page per team listing its policies (with queries,
+    /// platforms, and criticality), queries, and software, plus an index.
+    Docs {
+        /// Path to GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output directory
+        #[arg(short, long, default_value = "site")]
+        output: PathBuf,
+
+        /// Output format (markdown, html)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+    },
+
+    /// Export policies and queries as a flat table for auditors, or as IaC
+    ///
+    /// One row per policy/query with team, platform, criticality,
+    /// interval, query hash, and defining file. With --format terraform or
+    /// pulumi, writes fleetdm provider resources for teams instead; the
+    /// provider has no policy or query resources, so those are listed as
+    /// left out.
+    Export {
+        /// Path to GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output format (csv, xlsx, terraform, pulumi)
+        #[arg(short, long, default_value = "csv")]
+        format: String,
+
+        /// Output file (stdout by default; required for xlsx)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Report the software each team deploys (bill of deployments)
+    ///
+    /// Resolves packages, App Store apps, and Fleet-maintained apps with
+    /// version, self-service flag, and target labels.
+    SoftwareReport {
+        /// Path to GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output format (json, csv)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show the effective settings of each team
+    ///
+    /// Resolves global defaults from default.yml and team overrides,
+    /// including hosts in "No team".
+    Effective {
+        /// Path to GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Only show this team
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Compute and record the SHA-256 of a software package installer
+    ///
+    /// Downloads the installer referenced by the package definition's `url:`
+    /// and writes `hash_sha256:` into the YAML. With --verify, only checks
+    /// that the declared hash still matches (for CI).
+    Hash {
+        /// Software package definition (YAML with a url: field)
+        file: PathBuf,
+
+        /// Fail if hash_sha256 is missing or no longer matches, without modifying the file
+        #[arg(long)]
+        verify: bool,
+    },
+
     /// Start LSP server for editor integration
     ///
     /// This command starts a Language Server Protocol (LSP) server that
@@ -152,12 +407,402 @@ enum Commands {
         output: Option<PathBuf>,
 
         /// Skip interactive prompts, use detected/default values
-        #[arg(long)]
-        no_interactive: bool,
+        #[arg(short = 'y', long = "yes", visible_alias = "no-interactive")]
+        yes: bool,
 
         /// Force overwrite existing config
         #[arg(short, long)]
         force: bool,
+
+        /// Strictness level (strict, moderate, relaxed); skips the prompt
+        #[arg(long)]
+        strictness: Option<linter::init::StrictnessLevel>,
+
+        /// Print the config to stdout instead of writing a file
+        #[arg(long, conflicts_with_all = ["output", "force"])]
+        print: bool,
+    },
+
+    /// Generate CI pipelines that lint and validate the repository
+    ///
+    /// Writes a GitHub Actions workflow and/or a GitLab CI include that install a
+    /// cached fleet-schema-gen release, run lint and validate, and report pending
+    /// migrations. Trigger paths and the Fleet version come from the repository.
+    CiInit {
+        /// GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// CI system(s) to generate for (github, gitlab)
+        #[arg(long, value_delimiter = ',', default_value = "github")]
+        provider: Vec<linter::ci::CiProvider>,
+
+        /// Overwrite existing pipeline files
+        #[arg(short, long)]
+        force: bool,
+
+        /// Print the pipelines to stdout instead of writing files
+        #[arg(long, conflicts_with = "force")]
+        print: bool,
+    },
+
+    /// Manage policy packs (shared lint settings, rules, templates, lib files)
+    ///
+    /// Packs are vendored into .fleet/packs/ next to .fleetlint.toml and
+    /// apply beneath the repository's own settings.
+    Pack {
+        #[command(subcommand)]
+        action: PackAction,
+    },
+
+    /// Vendor policies and queries from Fleet's standard query library
+    ///
+    /// Items are written to lib/fleet-library/ with provenance comments, so
+    /// `library check` can report drift from upstream.
+    Library {
+        #[command(subcommand)]
+        action: LibraryAction,
+    },
+
+    /// Show or reset the local usage metrics ([metrics] enabled = true)
+    ///
+    /// Metrics stay on this machine: rule findings from lint runs and the
+    /// LSP, and completion latency.
+    Metrics {
+        #[command(subcommand)]
+        action: MetricsAction,
+    },
+
+    /// Report who owns each policy and query
+    ///
+    /// Combines CODEOWNERS, the team files referencing each item and the
+    /// last people to modify it (git blame). `lint --format json` adds the
+    /// same owner metadata to every finding.
+    Owners {
+        /// GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Show scheduled query load per team against the performance budget
+    ///
+    /// Load is tables touched x runs per hour for every scheduled query a
+    /// team's hosts run, global queries included. The budget is
+    /// `thresholds.max_schedule_load` in .fleetlint.toml.
+    Stats {
+        /// GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Estimate how many hosts changed policies and labels affect, per team
+    ///
+    /// Compares the file with its version at --base and asks the Fleet
+    /// server (hosts API) how many hosts are targeted before and after.
+    /// Example: fleet-schema-gen impact teams/workstations.yml --base origin/main
+    Impact {
+        /// Team file or policies/labels lib file
+        file: PathBuf,
+
+        /// Git ref to compare against
+        #[arg(long, default_value = "HEAD")]
+        base: String,
+
+        /// Fleet server URL (default: $FLEET_URL)
+        #[arg(long)]
+        server: Option<String>,
+
+        /// Fleet API token (default: $FLEET_API_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Run a mock Fleet API server for end-to-end tests of GitOps workflows
+    ///
+    /// Answers the config, spec (teams, policies, queries, labels) and teams
+    /// endpoints `fleetctl gitops` uses, with or without dry_run, from canned
+    /// responses kept in memory. GET /mock/requests lists the requests received.
+    /// Example: fleet-schema-gen mock-server --seed mock-fleet.yml --token test
+    MockServer {
+        /// Address to listen on (port 0 picks a free port)
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+
+        /// YAML file with the version, teams, labels and host count to start with
+        #[arg(long)]
+        seed: Option<PathBuf>,
+
+        /// Only accept this API token (default: any token)
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Diagnose the environment (config, workspace, git, osquery, network)
+    Doctor {
+        /// GitOps repository root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Skip network reachability checks
+        #[arg(long)]
+        offline: bool,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Print shell completions (bash, zsh, fish, powershell, elvish)
+    ///
+    /// Example: fleet-schema-gen completions zsh > ~/.zfunc/_fleet-schema-gen
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+
+    /// Manage schema data
+    Schema {
+        #[command(subcommand)]
+        action: SchemaAction,
+    },
+
+    /// Update fleet-schema-gen to the latest GitHub release
+    ///
+    /// Downloads are verified against the release's checksums.txt. Set
+    /// FLEET_SCHEMA_GEN_MINISIGN_PUBKEY to also require a valid minisign
+    /// signature (checked with the minisign CLI).
+    SelfUpdate {
+        /// Release channel (stable, prerelease)
+        #[arg(long, default_value = "stable")]
+        channel: String,
+
+        /// Only check whether an update is available
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PackAction {
+    /// Install a pack from a local directory or git URL
+    Add {
+        /// Pack directory or git URL
+        source: String,
+
+        /// Git branch or tag to install
+        #[arg(long = "ref")]
+        git_ref: Option<String>,
+
+        /// Directory to install into (default: the nearest directory with a .fleetlint.toml)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+
+    /// List installed packs
+    List {
+        /// Directory packs are installed in (default: the nearest directory with a .fleetlint.toml)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+
+    /// Uninstall a pack (lib files it added are kept)
+    Remove {
+        /// Pack name
+        name: String,
+
+        /// Directory packs are installed in (default: the nearest directory with a .fleetlint.toml)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum FixturesAction {
+    /// Save tables queried with the local osqueryi as JSON fixtures
+    ///
+    /// Hostnames and usernames of this host are replaced with placeholders
+    /// (host-1, user-1, ...) everywhere they appear.
+    /// Example: fleet-schema-gen fixtures record --tables processes,os_version
+    Record {
+        /// Tables to record
+        #[arg(long, value_delimiter = ',', required = true)]
+        tables: Vec<String>,
+
+        /// Fixture directory to write <table>.json files into
+        #[arg(short, long, default_value = "fixtures/recorded")]
+        output: PathBuf,
+
+        /// Record at most this many rows per table
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Keep hostnames and usernames as they are
+        #[arg(long)]
+        no_redact: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum LibraryAction {
+    /// Fetch the library and vendor the selected items (interactive without filters)
+    Sync {
+        /// Only items with this tag (repeatable)
+        #[arg(long)]
+        tag: Vec<String>,
+
+        /// Only the item with this name (repeatable)
+        #[arg(long)]
+        name: Vec<String>,
+
+        /// Only items for this platform (darwin, windows, linux, chrome)
+        #[arg(long)]
+        platform: Option<String>,
+
+        /// Only policies or queries
+        #[arg(long)]
+        kind: Option<linter::library::ItemKind>,
+
+        /// Vendor every matching item without prompting
+        #[arg(long)]
+        all: bool,
+
+        /// Lib directory to vendor into
+        #[arg(long, default_value = "lib")]
+        dir: PathBuf,
+
+        /// fleetdm/fleet branch or tag to fetch from
+        #[arg(long = "ref", default_value = "main")]
+        git_ref: String,
+    },
+
+    /// Report vendored items that changed upstream or locally
+    Check {
+        /// Lib directory items were vendored into
+        #[arg(long, default_value = "lib")]
+        dir: PathBuf,
+
+        /// fleetdm/fleet branch or tag to compare with
+        #[arg(long = "ref", default_value = "main")]
+        git_ref: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// List, prune or restore the backups taken before migrations
+    Backups {
+        #[command(subcommand)]
+        action: BackupsAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackupsAction {
+    /// List backups, newest first
+    List {
+        /// Directory the migrations ran in (holding .fleet-migrate/)
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Delete backups beyond the retention limits ([migrate.backups] in .fleetlint.toml)
+    Prune {
+        /// Directory the migrations ran in (holding .fleet-migrate/)
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+
+        /// Number of most recent backups to keep
+        #[arg(long)]
+        keep: Option<usize>,
+
+        /// Delete backups older than this many days
+        #[arg(long)]
+        max_age_days: Option<u64>,
+    },
+
+    /// Restore the files of a backup (checked against the manifest hashes)
+    Restore {
+        /// Backup ID, as shown by `migrate backups list`
+        id: String,
+
+        /// Directory the migrations ran in (holding .fleet-migrate/)
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum SchemaAction {
+    /// Package cached source data and generated schemas for air-gapped networks
+    Bundle {
+        #[command(subcommand)]
+        action: BundleAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum BundleAction {
+    /// Write the GitHub response cache and generated schemas to a bundle
+    ///
+    /// Example: fleet-schema-gen schema bundle export fleet-schemas.tar.zst
+    Export {
+        /// Bundle file (.tar.zst, .tar.gz or .tar)
+        file: PathBuf,
+
+        /// Generated schemas directory (default: from .fleetlint.toml, else ./schemas)
+        #[arg(long)]
+        versions_dir: Option<PathBuf>,
+    },
+
+    /// Verify a bundle against its manifest and unpack it
+    Import {
+        /// Bundle file (.tar.zst, .tar.gz or .tar)
+        file: PathBuf,
+
+        /// Generated schemas directory (default: from .fleetlint.toml, else ./schemas)
+        #[arg(long)]
+        versions_dir: Option<PathBuf>,
+
+        /// Only verify the bundle, don't write anything
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetricsAction {
+    /// Print rules by findings and completion latency percentiles
+    Report {
+        /// Metrics file (default: from .fleetlint.toml, else the user cache)
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Delete the recorded metrics
+    Reset {
+        /// Metrics file (default: from .fleetlint.toml, else the user cache)
+        #[arg(long)]
+        file: Option<PathBuf>,
     },
 }
 
@@ -165,13 +810,26 @@ enum Commands {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    match cli.command {
+    // The LSP `--debug` flag is equivalent to -v
+    let lsp_debug = matches!(cli.command, Some(Commands::Lsp { debug: true, .. }));
+    utils::logging::init(cli.verbose.max(lsp_debug as u8), cli.quiet, cli.json_logs);
+
+    if cli.man {
+        clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())?;
+        return Ok(());
+    }
+    let Some(command) = cli.command else {
+        Cli::command().print_help()?;
+        return Ok(());
+    };
+
+    match command {
         Commands::Generate { fleet_version, output, editor, schema_defs, source } => {
-            println!("Generating schemas for Fleet version: {}",
+            tracing::info!("Generating schemas for Fleet version: {}",
                 fleet_version.as_deref().unwrap_or("latest"));
-            println!("Output directory: {}", output.display());
-            println!("Editor format: {}", editor);
-            println!("Schema source: {}", source);
+            tracing::info!("Output directory: {}", output.display());
+            tracing::info!("Editor format: {}", editor);
+            tracing::info!("Schema source: {}", source);
 
             // Load and merge schema sources
             let schema = schema::build_schema(fleet_version, &schema_defs, &source).await?;
@@ -182,15 +840,25 @@ async fn main() -> Result<()> {
                 "sublime" => generators::sublime::generate(&schema, &output)?,
                 "sublime-lsp" => generators::sublime_lsp::generate(&output)?,
                 "intellij" => generators::intellij::generate(&schema, &output)?,
+                "intellij-plugin" => {
+                    generators::intellij::generate(&schema, &output)?;
+                    generators::intellij::generate_plugin(&output.join("plugin"))?;
+                }
                 "neovim" => generators::neovim::generate(&schema, &output)?,
+                "generic-lsp" => generators::generic_lsp::generate(&output)?,
                 "strict" => generators::strict::generate(&schema, &output)?,
+                "lsp" => generators::lsp::generate(&schema, &output)?,
+                "docs" => generators::markdown::generate(&schema, &output)?,
                 "all" => {
                     generators::vscode::generate(&schema, &output.join("vscode"))?;
                     generators::sublime::generate(&schema, &output.join("sublime"))?;
                     generators::sublime_lsp::generate(&output.join("sublime-lsp"))?;
                     generators::intellij::generate(&schema, &output.join("intellij"))?;
                     generators::neovim::generate(&schema, &output.join("neovim"))?;
+                    generators::generic_lsp::generate(&output.join("generic-lsp"))?;
                     generators::strict::generate(&schema, &output.join("strict"))?;
+                    generators::lsp::generate(&schema, &output.join("lsp"))?;
+                    generators::markdown::generate(&schema, &output.join("docs"))?;
                 }
                 _ => anyhow::bail!("Unknown editor format: {}", editor),
             }
@@ -204,6 +872,7 @@ async fn main() -> Result<()> {
             match source.as_str() {
                 "docs" => sources::docs_scraper::fetch_and_save(&output).await?,
                 "github" => sources::github::fetch_and_save(&output).await?,
+                "changelog" => sources::changelog::fetch_and_save(&output).await?,
                 "local" => println!("Local schemas already up to date"),
                 _ => anyhow::bail!("Unknown source: {}", source),
             }
@@ -211,7 +880,7 @@ async fn main() -> Result<()> {
             println!("✓ Update complete!");
         }
 
-        Commands::Lint { path, watch, fix, format } => {
+        Commands::Lint { path, watch, fix, format, exec_osquery, check_urls, allow_offline, fleet_version, preset, no_ignore } => {
             use linter::Linter;
             use colored::Colorize;
 
@@ -221,34 +890,178 @@ async fn main() -> Result<()> {
                 anyhow::bail!("Watch mode not yet implemented");
             }
 
-            if fix {
-                println!("{} Auto-fix mode not yet implemented", "⚠️ ".yellow());
+            // Honor .fleetlint.toml (disabled/opt-in rules, rule options)
+            let mut linter = Linter::from_path(&path);
+            if fleet_version.is_some() || preset.is_some() || no_ignore {
+                let mut config = linter.config().cloned().unwrap_or_default();
+                if let Some(preset) = preset {
+                    config = config.with_preset(&preset)?;
+                }
+                if let Some(version) = fleet_version {
+                    config.fleet_version = Some(version);
+                }
+                if no_ignore {
+                    config.files.gitignore = false;
+                }
+                linter.set_config(config);
+            }
+
+            let osqueryi = if exec_osquery {
+                let binary = linter::osqueryi::find_osqueryi()
+                    .ok_or_else(|| anyhow::anyhow!("--exec-osquery requires osqueryi in PATH (or set OSQUERYI)"))?;
+                println!("{} Running queries with {} (results are specific to this host)\n",
+                    "⚙️ ".cyan(),
+                    binary.display()
+                );
+                Some(binary)
+            } else {
+                None
+            };
+
+            let url_options = check_urls.then(|| {
+                let mut options = linter::urls::UrlCheckOptions::from_config(linter.config());
+                options.allow_offline = allow_offline;
+                println!("{} Checking software package URLs (up to {} at a time)\n",
+                    "🌐".cyan(),
+                    options.concurrency
+                );
+                options
+            });
+
+            if !matches!(format.as_str(), "text" | "json") {
+                anyhow::bail!("Unknown output format: {} (expected text or json)", format);
             }
+            let json = format == "json";
 
-            let linter = Linter::new();
+            // -v shows what makes files over the size thresholds large
+            let thresholds = linter.config().map(|c| c.thresholds.clone()).unwrap_or_default();
+            let print_file_stats = |file: &std::path::Path| {
+                if cli.verbose == 0 {
+                    return;
+                }
+                if let Some(stats) = linter::file_size::FileStats::for_file(file) {
+                    if !stats.exceeded(&thresholds).is_empty() {
+                        print!("\n{}", stats.render(file, &thresholds));
+                    }
+                }
+            };
+
+            if fix {
+                use anyhow::Context;
+
+                let files = if path.is_file() {
+                    vec![path.clone()]
+                } else {
+                    let options = linter::walk::WalkOptions::from_config(linter.config());
+                    linter::engine::find_yaml_files(&path, &options)?
+                };
+                let (mut fixed, mut changed) = (0, 0);
+                for file in &files {
+                    let source = std::fs::read_to_string(file)?;
+                    // Files that don't parse are reported by the lint below
+                    let Ok(result) = linter.fix_content(&source, file) else {
+                        continue;
+                    };
+                    if result.changed() {
+                        std::fs::write(file, &result.content)
+                            .with_context(|| format!("Failed to write {}", file.display()))?;
+                        fixed += result.fixed;
+                        changed += 1;
+                    }
+                }
+                if !json {
+                    println!("{} Fixed {} problem(s) in {} file(s)\n", "🔧".green(), fixed, changed);
+                }
+            }
 
             if path.is_file() {
                 // Lint single file
-                println!("{} Linting {}...\n", "🔍".blue(), path.display());
+                if !json {
+                    println!("{} Linting {}...\n", "🔍".blue(), path.display());
+                }
 
                 let source = std::fs::read_to_string(&path)?;
-                let report = linter.lint_file(&path)?;
+                let mut report = linter.lint_file(&path)?;
 
-                if format == "json" {
-                    // TODO: JSON output
-                    println!("JSON output not yet implemented");
+                if let Some(binary) = &osqueryi {
+                    let config = linter::engine::parse_fleet_config(&source, &path)?;
+                    for error in linter::osqueryi::exec_config(&config, &path, binary) {
+                        report.add(error);
+                    }
+                }
+
+                if let Some(options) = &url_options {
+                    let packages = linter::urls::collect_package_urls(&path, true);
+                    for error in linter::urls::check_package_urls(&packages, options).await {
+                        report.add(error);
+                    }
+                }
+
+                let failed = report.has_errors();
+                if json {
+                    let mut ownership = linter::owners::Ownership::load(&path);
+                    let results = vec![(path.display().to_string(), report)];
+                    println!("{}", serde_json::to_string_pretty(&linter::owners::lint_json(&results, &mut ownership)?)?);
                 } else {
                     report.print(Some(&source));
+                    print_file_stats(&path);
+                }
+
+                linter.flush_metrics();
+                if failed {
+                    std::process::exit(1);
+                }
+            } else if path.is_dir() {
+                // Lint directory
+                if !json {
+                    println!("{} Linting directory {}...\n", "🔍".blue(), path.display());
+                }
+
+                let mut results = linter.lint_directory(&path)?;
+
+                // Cross-file rules when linting a GitOps repository root
+                if let Ok(errors) = linter.lint_repo(&path) {
+                    for error in errors {
+                        if let Some((_, report)) = results.iter_mut().find(|(file_path, _)| std::path::Path::new(file_path.as_str()) == error.file) {
+                            report.add(error);
+                        }
+                    }
+                }
+
+                if let Some(binary) = &osqueryi {
+                    for (file_path, report) in results.iter_mut() {
+                        let file_path = std::path::Path::new(file_path.as_str());
+                        let source = std::fs::read_to_string(file_path)?;
+                        if let Ok(config) = linter::engine::parse_fleet_config(&source, file_path) {
+                            for error in linter::osqueryi::exec_config(&config, file_path, binary) {
+                                report.add(error);
+                            }
+                        }
+                    }
                 }
 
-                if report.has_errors() {
-                    std::process::exit(1);
+                if let Some(options) = &url_options {
+                    // Package files are linted on their own, so don't follow team `path:` refs
+                    let packages: Vec<_> = results
+                        .iter()
+                        .flat_map(|(file_path, _)| linter::urls::collect_package_urls(std::path::Path::new(file_path.as_str()), false))
+                        .collect();
+                    for error in linter::urls::check_package_urls(&packages, options).await {
+                        if let Some((_, report)) = results.iter_mut().find(|(file_path, _)| std::path::Path::new(file_path.as_str()) == error.file) {
+                            report.add(error);
+                        }
+                    }
                 }
-            } else if path.is_dir() {
-                // Lint directory
-                println!("{} Linting directory {}...\n", "🔍".blue(), path.display());
 
-                let results = linter.lint_directory(&path, None)?;
+                if json {
+                    let mut ownership = linter::owners::Ownership::load(&path);
+                    println!("{}", serde_json::to_string_pretty(&linter::owners::lint_json(&results, &mut ownership)?)?);
+                    linter.flush_metrics();
+                    if results.iter().any(|(_, report)| report.has_errors()) {
+                        std::process::exit(1);
+                    }
+                    return Ok(());
+                }
 
                 let mut total_errors = 0;
                 let mut total_warnings = 0;
@@ -263,6 +1076,7 @@ async fn main() -> Result<()> {
                         } else {
                             report.print(None);
                         }
+                        print_file_stats(std::path::Path::new(file_path.as_str()));
 
                         total_errors += report.errors.len();
                         total_warnings += report.warnings.len();
@@ -277,6 +1091,7 @@ async fn main() -> Result<()> {
                 println!("  {} warning(s)", total_warnings.to_string().yellow());
                 println!("  {} info", total_infos.to_string().blue());
 
+                linter.flush_metrics();
                 if total_errors > 0 {
                     std::process::exit(1);
                 }
@@ -285,36 +1100,252 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::Validate { file, schema } => {
-            use linter::Linter;
+        Commands::Fmt { path, sort, sql, check } => {
+            use anyhow::Context;
+            use colored::Colorize;
 
-            println!("🔍 Validating: {}", file.display());
+            if !sort && !sql {
+                anyhow::bail!("Nothing to format: pass --sort to sort items by name, --sql to format queries");
+            }
 
-            let linter = Linter::new();
-            let source = std::fs::read_to_string(&file)?;
-            let report = linter.lint_file(&file)?;
+            let files = if path.is_file() {
+                vec![path.clone()]
+            } else {
+                let linter = linter::Linter::from_path(&path);
+                let options = linter::walk::WalkOptions::from_config(linter.config());
+                linter::engine::find_yaml_files(&path, &options)?
+            };
+
+            let mut changed = Vec::new();
+            for file in &files {
+                let source = std::fs::read_to_string(file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                let mut formatted = source.clone();
+                if sql {
+                    formatted = linter::sql_format::format_queries(&formatted);
+                }
+                if sort {
+                    formatted = linter::sort::sort_items(&formatted);
+                }
+                if formatted == source {
+                    continue;
+                }
+                if !check {
+                    std::fs::write(file, &formatted).with_context(|| format!("Failed to write {}", file.display()))?;
+                }
+                changed.push(file);
+            }
+
+            if check {
+                for file in &changed {
+                    println!("{} {}", "unformatted:".yellow(), file.display());
+                }
+                if !changed.is_empty() {
+                    std::process::exit(1);
+                }
+                println!("{} {} file(s) already formatted", "✓".green(), files.len());
+            } else {
+                println!("{} Formatted {} of {} file(s)", "✓".green(), changed.len(), files.len());
+            }
+        }
 
-            report.print(Some(&source));
+        Commands::Validate { paths, schema, format, fleet_version } => {
+            use linter::validate::{expand_targets, to_sarif, validate_with_linter, SchemaSource, SchemaValidators};
+            use colored::Colorize;
 
-            if report.has_errors() {
-                std::process::exit(1);
+            if !matches!(format.as_str(), "text" | "json" | "sarif") {
+                anyhow::bail!("Unknown output format: {} (expected text, json, or sarif)", format);
+            }
+
+            let files = expand_targets(&paths)?;
+            if files.is_empty() {
+                anyhow::bail!("No YAML files matched: {}", paths.join(" "));
+            }
+
+            let mut linter = linter::Linter::from_path(&files[0]);
+            let mut config = linter.config().cloned().unwrap_or_default();
+            if fleet_version.is_some() {
+                config.fleet_version = fleet_version;
+                linter.set_config(config.clone());
             }
 
-            println!("✓ Validation complete!");
+            // Without --schema, use the generated schemas for the targeted
+            // version, falling back to the snapshot embedded in the binary
+            let schema = schema.or_else(|| {
+                let version = config.fleet_version.as_deref()?;
+                let source = SchemaSource::for_version(std::path::Path::new(&config.schema.versions_dir), version);
+                if source == SchemaSource::Bundled {
+                    tracing::warn!("No generated schema for Fleet {} in {}/, using the bundled {} schema",
+                        version,
+                        config.schema.versions_dir,
+                        linter::bundled::SNAPSHOT_VERSION
+                    );
+                }
+                Some(source)
+            });
+            if let (Some(SchemaSource::Bundled), Some(version)) = (&schema, config.fleet_version.as_deref()) {
+                if let Some(warning) = linter::bundled::version_mismatch(version) {
+                    tracing::warn!("{}", warning);
+                }
+            }
+            let validators = schema.map(SchemaValidators::new);
+
+            // Files without a schema of their kind (software, scripts) get
+            // the lint rules instead
+            let mut results = Vec::new();
+            for file in &files {
+                let validator = match &validators {
+                    Some(validators) => validators.for_file(file)?,
+                    None => None,
+                };
+                results.push(match (validator, std::fs::read_to_string(file)) {
+                    (Some(validator), Ok(source)) => validator.validate(&source, file),
+                    _ => validate_with_linter(&linter, file),
+                });
+            }
+
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&results)?),
+                "sarif" => println!("{}", serde_json::to_string_pretty(&to_sarif(&results))?),
+                _ => {
+                    for result in &results {
+                        if result.violations.is_empty() {
+                            println!("{} {}", "✓".green(), result.file);
+                            continue;
+                        }
+
+                        let marker = if result.valid { "⚠".yellow() } else { "✗".red() };
+                        println!("{} {}", marker, result.file.bold());
+                        for violation in &result.violations {
+                            let location = match violation.line {
+                                Some(line) => format!("{}:{}", result.file, line),
+                                None => result.file.clone(),
+                            };
+                            let pointer = violation.pointer.as_deref().unwrap_or("");
+                            println!("    {} {} {}", location.dimmed(), pointer.cyan(), violation.message);
+                        }
+                    }
+
+                    let invalid = results.iter().filter(|r| !r.valid).count();
+                    println!("\n{} Validated {} file(s), {} invalid",
+                        "Summary:".bold(),
+                        results.len(),
+                        invalid
+                    );
+                }
+            }
+
+            if results.iter().any(|r| !r.valid) {
+                std::process::exit(1);
+            }
         }
 
         Commands::Migrate {
+            command,
             path,
+            stdin,
+            stdin_filename,
             from,
             to,
             dry_run,
+            side_by_side,
             migrations_file,
+            keep_going,
+            fail_fast: _,
+            jobs,
             git,
+            push,
+            pr,
         } => {
-            use linter::migrate::{Migrator, Version};
+            use linter::migrate::{Backup, Migrator};
             use colored::Colorize;
+            use anyhow::Context;
+
+            if let Some(MigrateCommand::Backups { action }) = command {
+                let retention_for = |dir: &std::path::Path| {
+                    linter::FleetLintConfig::find_and_load(dir)
+                        .map(|(_, c)| c.migrate.backups)
+                        .unwrap_or_default()
+                };
+                match action {
+                    BackupsAction::List { dir, format } => {
+                        let backups = Backup::list(&dir)?;
+                        match format.as_str() {
+                            "json" => println!("{}", serde_json::to_string_pretty(&backups)?),
+                            "text" if backups.is_empty() => println!("No backups in {}", dir.join(linter::migrate::backup::BACKUPS_DIR).display()),
+                            "text" => {
+                                for backup in &backups {
+                                    println!("{}  {}  {} file(s), {} bytes",
+                                        backup.id.bold(),
+                                        backup.created.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                                        backup.files.len(),
+                                        backup.size_bytes()
+                                    );
+                                }
+                            }
+                            _ => anyhow::bail!("Unknown output format: {} (expected text or json)", format),
+                        }
+                    }
+                    BackupsAction::Prune { dir, keep, max_age_days } => {
+                        let mut retention = retention_for(&dir);
+                        if keep.is_some() {
+                            retention.keep = keep;
+                        }
+                        if max_age_days.is_some() {
+                            retention.max_age_days = max_age_days;
+                        }
+                        let deleted = Backup::prune(&dir, &retention, chrono::Utc::now())?;
+                        println!("{} Deleted {} backup(s)", "✓".green(), deleted.len());
+                        for id in deleted {
+                            println!("  {}", id);
+                        }
+                    }
+                    BackupsAction::Restore { id, dir } => {
+                        let restored = Backup::restore_id(&dir, &id)?;
+                        println!("{} Restored {} file(s) from backup {}", "✓".green(), restored.len(), id.bold());
+                        for path in restored {
+                            println!("  {}", path.display());
+                        }
+                    }
+                }
+                return Ok(());
+            }
+
+            if stdin {
+                use std::io::Read;
+
+                let from = from.ok_or_else(|| anyhow::anyhow!("--stdin needs --from: the version can't be detected from one document"))?;
+                let mut migrator = Migrator::new();
+                if let Some(migrations_path) = &migrations_file {
+                    migrator.load_migrations_from_file(migrations_path)?;
+                    for issue in migrator.validate_migrations(None) {
+                        eprintln!("{} {}", "⚠".yellow().bold(), issue);
+                    }
+                }
+                let from_version = linter::migrate::resolve_version(&from).await?;
+                let to_version = match &to {
+                    Some(v) => linter::migrate::resolve_version(v).await?,
+                    None => migrator.latest_version(),
+                };
+
+                let mut content = String::new();
+                std::io::stdin().read_to_string(&mut content).context("Failed to read stdin")?;
+                let migrated = migrator.migrate_document(&content, stdin_filename.as_deref(), &from_version, &to_version)?;
+                for skipped in &migrated.skipped {
+                    eprintln!("{} {}", "⚠".yellow().bold(), skipped);
+                }
+                print!("{}", migrated.content);
+                return Ok(());
+            }
 
+            let path = path.ok_or_else(|| anyhow::anyhow!("Missing path to migrate (or use --stdin or `migrate backups`)"))?;
+            let config = linter::FleetLintConfig::find_and_load(&path).map(|(_, c)| c).unwrap_or_default();
             let mut migrator = Migrator::new();
+            migrator.set_diff_style(linter::migrate::DiffStyle::from_flag(side_by_side));
+            migrator.set_jobs(jobs);
+            if keep_going {
+                migrator.set_failure_mode(linter::migrate::FailureMode::KeepGoing);
+            }
 
             // Load custom migrations if specified
             if let Some(migrations_path) = migrations_file {
@@ -323,12 +1354,16 @@ async fn main() -> Result<()> {
                     migrations_path.display()
                 );
                 migrator.load_migrations_from_file(&migrations_path)?;
+
+                let root = if path.is_dir() { path.as_path() } else { path.parent().unwrap_or(std::path::Path::new(".")) };
+                for issue in migrator.validate_migrations(Some(root)) {
+                    println!("{} {}", "⚠".yellow().bold(), issue);
+                }
             }
 
             // Auto-detect source version if not specified
             let from_version = if let Some(v) = from {
-                Version::parse(&v)
-                    .ok_or_else(|| anyhow::anyhow!("Invalid version format: {}", v))?
+                linter::migrate::resolve_version(&v).await?
             } else {
                 println!("{} Auto-detecting Fleet version...", "→".blue().bold());
                 migrator.detect_version(&path)?
@@ -337,8 +1372,7 @@ async fn main() -> Result<()> {
 
             // Use latest version if target not specified
             let to_version = if let Some(v) = to {
-                Version::parse(&v)
-                    .ok_or_else(|| anyhow::anyhow!("Invalid version format: {}", v))?
+                linter::migrate::resolve_version(&v).await?
             } else {
                 migrator.latest_version()
             };
@@ -369,13 +1403,21 @@ async fn main() -> Result<()> {
                 plan.estimated_changes.to_string().bold()
             );
 
-            // Execute migration
-            if git && !dry_run {
+            // Branch, commit and PR settings from .fleetlint.toml, flags on top
+            let git_migrator = if git && !dry_run {
                 use linter::migrate::git::GitMigrator;
-                use std::path::Path;
 
+                let mut git_config = config.migrate.clone();
+                git_config.push |= push;
+                git_config.pull_request |= pr;
+                Some(GitMigrator::open(&path)?.with_config(git_config))
+            } else {
+                None
+            };
+
+            // Execute migration
+            if let Some(git_migrator) = &git_migrator {
                 println!("{} Creating Git branch...", "→".blue().bold());
-                let git_migrator = GitMigrator::open(Path::new("."))?;
                 let branch_name = git_migrator.create_migration_branch(
                     &from_version.to_string(),
                     &to_version.to_string()
@@ -386,20 +1428,46 @@ async fn main() -> Result<()> {
                 );
             }
 
-            migrator.execute_migration(&plan, dry_run)?;
+            let report = migrator.execute_migration(&plan, dry_run)?;
 
-            if git && !dry_run {
-                use linter::migrate::git::GitMigrator;
-                use std::path::Path;
+            if !dry_run {
+                let pruned = Backup::prune(std::path::Path::new("."), &config.migrate.backups, chrono::Utc::now())?;
+                if !pruned.is_empty() {
+                    tracing::info!("Pruned {} old backup(s)", pruned.len());
+                }
+            }
+
+            if !report.failed.is_empty() {
+                anyhow::bail!("{} file(s) failed to migrate", report.failed.len());
+            }
+
+            if let Some(git_migrator) = &git_migrator {
+                let (from, to) = (from_version.to_string(), to_version.to_string());
+                let files = report.migrated.len();
 
                 println!("\n{} Creating commit...", "→".blue().bold());
-                let git_migrator = GitMigrator::open(Path::new("."))?;
-                git_migrator.commit_migration(
-                    &from_version.to_string(),
-                    &to_version.to_string(),
-                    plan.affected_files.len()
-                )?;
+                let affected: Vec<&std::path::Path> = report.migrated.iter().map(|f| f.as_path()).collect();
+                git_migrator.stage_files(&affected)?;
+                let message = git_migrator.migration_message(&from, &to, files)?;
+                git_migrator.commit_migration(&from, &to, files)?;
                 println!("{} Migration committed", "✓".green());
+
+                if git_migrator.should_push() {
+                    let branch = git_migrator.current_branch()?;
+                    git_migrator.push(&branch)?;
+                    println!("{} Pushed {}", "✓".green(), branch.bold());
+
+                    if git_migrator.should_open_pr() {
+                        let files = files.to_string();
+                        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+                        let (title, body) = git_migrator.pr_text(&message, &[
+                            ("from", &from), ("to", &to), ("files", &files), ("date", &date), ("branch", &branch),
+                        ]);
+                        if let Some(url) = git_migrator.create_pr(&title, &body)? {
+                            println!("{} Opened pull request: {}", "✓".green(), url);
+                        }
+                    }
+                }
             }
         }
 
@@ -409,7 +1477,7 @@ async fn main() -> Result<()> {
             to,
             side_by_side,
         } => {
-            use linter::migrate::{Migrator, Version};
+            use linter::migrate::Migrator;
             use colored::Colorize;
 
             println!("\n{} Analyzing migration changes: {} → {}",
@@ -419,10 +1487,8 @@ async fn main() -> Result<()> {
             );
 
             let mut migrator = Migrator::new();
-            let from_version = Version::parse(&from)
-                .ok_or_else(|| anyhow::anyhow!("Invalid version format: {}", from))?;
-            let to_version = Version::parse(&to)
-                .ok_or_else(|| anyhow::anyhow!("Invalid version format: {}", to))?;
+            let from_version = linter::migrate::resolve_version(&from).await?;
+            let to_version = linter::migrate::resolve_version(&to).await?;
 
             // Create migration plan
             let plan = migrator.plan_migration(&path, &from_version, &to_version)?;
@@ -553,15 +1619,241 @@ async fn main() -> Result<()> {
 
             println!("\n{} Run with {} to see the actual changes",
                 "Tip:".blue().bold(),
-                "migrate --dry-run".yellow()
+                if side_by_side { "migrate --dry-run --side-by-side" } else { "migrate --dry-run" }.yellow()
+            );
+        }
+
+        Commands::Coverage { path, benchmark, by_platform, objective, platform, uncovered, format } => {
+            use linter::benchmarks::Benchmark;
+            use linter::coverage::coverage_report;
+
+            if by_platform {
+                use linter::platform_coverage::{self, Objective, OBJECTIVES};
+
+                let objectives = objective
+                    .iter()
+                    .map(|id| {
+                        Objective::parse(id).ok_or_else(|| {
+                            let known: Vec<&str> = OBJECTIVES.iter().map(|o| o.id).collect();
+                            anyhow::anyhow!("Unknown objective: {} (expected one of {})", id, known.join(", "))
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let repo = linter::repo::load_repo(&path)?;
+                let report = platform_coverage::platform_coverage(&repo, &objectives, &platform);
+
+                match format.as_str() {
+                    "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+                    "text" => platform_coverage::print(&report),
+                    _ => anyhow::bail!("Unknown output format: {}", format),
+                }
+                return Ok(());
+            }
+
+            let benchmarks = match benchmark {
+                Some(name) => vec![Benchmark::parse(&name)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown benchmark: {} (expected cis-macos or cis-windows)", name))?],
+                None => Vec::new(),
+            };
+
+            let report = coverage_report(&path, &benchmarks)?;
+
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+                "text" => report.print(uncovered),
+                _ => anyhow::bail!("Unknown output format: {}", format),
+            }
+        }
+
+        Commands::Test { path } => {
+            use linter::harness;
+            use colored::Colorize;
+
+            let files = harness::find_tests_files(&path)?;
+            if files.is_empty() {
+                println!("{} No {} files found in {}",
+                    "⚠️ ".yellow(),
+                    harness::TESTS_FILE_SUFFIX,
+                    path.display()
+                );
+                return Ok(());
+            }
+
+            let mut outcomes = Vec::new();
+            for file in &files {
+                outcomes.extend(harness::run_tests_file(file)?);
+            }
+
+            if harness::print_outcomes(&outcomes) > 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Fixtures { action: FixturesAction::Record { tables, output, limit, no_redact } } => {
+            use linter::osqueryi::{find_osqueryi, run_query};
+            use colored::Colorize;
+
+            let osqueryi = find_osqueryi()
+                .ok_or_else(|| anyhow::anyhow!("osqueryi not found. Install osquery or set OSQUERYI to its path."))?;
+            let (recorded, redactor) =
+                linter::fixtures::record(&tables, &output, limit, !no_redact, |sql| run_query(&osqueryi, sql))?;
+
+            for table in &recorded {
+                println!("{} {} ({} row(s)) → {}", "✓".green(), table.table, table.rows, table.path.display());
+            }
+            if no_redact {
+                println!("{} Hostnames and usernames were not redacted", "⚠️ ".yellow());
+            } else {
+                println!("Redacted {} hostname(s) and {} username(s)", redactor.hostnames, redactor.usernames);
+            }
+        }
+
+        Commands::Docs { path, output, format } => {
+            use linter::docs::{write_site, DocsFormat};
+            use colored::Colorize;
+
+            let format = DocsFormat::parse(&format)
+                .ok_or_else(|| anyhow::anyhow!("Unknown docs format: {} (expected markdown or html)", format))?;
+
+            let repo = linter::repo::load_repo(&path)?;
+            let written = write_site(&repo, &output, format)?;
+
+            println!("{} Generated {} page(s) for {} team(s) in {}",
+                "✓".green(),
+                written.len(),
+                repo.teams.len(),
+                output.display()
             );
         }
 
+        Commands::Export { path, format, output } => {
+            use linter::export::{export_rows, write_csv, write_xlsx, ExportFormat};
+            use colored::Colorize;
+
+            let format = ExportFormat::parse(&format).ok_or_else(|| {
+                anyhow::anyhow!("Unknown export format: {} (expected csv, xlsx, terraform or pulumi)", format)
+            })?;
+
+            let repo = linter::repo::load_repo(&path)?;
+
+            let exported = match format {
+                ExportFormat::Terraform | ExportFormat::Pulumi => {
+                    use linter::iac::{iac_resources, render_pulumi, render_terraform};
+
+                    let export = iac_resources(&repo);
+                    let program = match format {
+                        ExportFormat::Terraform => render_terraform(&export.resources),
+                        _ => render_pulumi(&export.resources)?,
+                    };
+                    match &output {
+                        None => print!("{}", program),
+                        Some(file) => std::fs::write(file, program)?,
+                    }
+                    if !export.skipped.is_empty() {
+                        // stderr, so the program can be piped
+                        eprintln!(
+                            "{} The fleetdm provider has no policy or query resources; left out {} item(s), keep managing them with fleetctl gitops:",
+                            "⚠️ ".yellow(),
+                            export.skipped.len()
+                        );
+                        for item in &export.skipped {
+                            eprintln!("  - {} '{}' ({})", item.kind, item.name, item.team);
+                        }
+                    }
+                    format!("{} resource(s)", export.resources.len())
+                }
+                ExportFormat::Csv | ExportFormat::Xlsx => {
+                    let rows = export_rows(&repo);
+                    match (format, &output) {
+                        (ExportFormat::Xlsx, Some(file)) => write_xlsx(&rows, file)?,
+                        (ExportFormat::Xlsx, None) => anyhow::bail!("--output is required for xlsx exports"),
+                        (_, None) => write_csv(&rows, std::io::stdout())?,
+                        (_, Some(file)) => write_csv(&rows, std::fs::File::create(file)?)?,
+                    }
+                    format!("{} row(s)", rows.len())
+                }
+            };
+
+            if let Some(file) = &output {
+                println!("{} Exported {} to {}", "✓".green(), exported, file.display());
+            }
+        }
+
+        Commands::SoftwareReport { path, format, output } => {
+            use linter::software_report::software_report;
+
+            if !matches!(format.as_str(), "json" | "csv") {
+                anyhow::bail!("Unknown output format: {} (expected json or csv)", format);
+            }
+
+            let repo = linter::repo::load_repo(&path)?;
+            let report = software_report(&repo);
+
+            let mut writer: Box<dyn std::io::Write> = match &output {
+                Some(file) => Box::new(std::fs::File::create(file)?),
+                None => Box::new(std::io::stdout()),
+            };
+
+            match format.as_str() {
+                "json" => writeln!(writer, "{}", serde_json::to_string_pretty(&report)?)?,
+                _ => report.write_csv(writer)?,
+            }
+        }
+
+        Commands::Effective { path, team, format } => {
+            use linter::effective::effective_configs;
+
+            let repo = linter::repo::load_repo(&path)?;
+            let mut configs = effective_configs(&repo);
+            if let Some(team) = &team {
+                configs.retain(|c| c.team.eq_ignore_ascii_case(team));
+                if configs.is_empty() {
+                    anyhow::bail!("No team named '{}' in {}", team, path.display());
+                }
+            }
+
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&configs)?),
+                "text" => linter::effective::print(&configs),
+                _ => anyhow::bail!("Unknown output format: {}", format),
+            }
+        }
+
+        Commands::Hash { file, verify } => {
+            use linter::package_hash::{hash_package, set_hash, HashStatus};
+            use colored::Colorize;
+
+            let source = std::fs::read_to_string(&file)?;
+            println!("{} Downloading installer for {}...", "⬇️ ".cyan(), file.display());
+
+            let result = hash_package(&source, &file).await?;
+            println!("  {} {}", "URL:".bold(), result.url);
+            println!("  {} {} bytes", "Size:".bold(), result.size);
+            println!("  {} {}", "SHA-256:".bold(), result.sha256);
+
+            match (&result.status, verify) {
+                (HashStatus::Match, _) => {
+                    println!("\n{} hash_sha256 is up to date", "✓".green());
+                }
+                (HashStatus::Missing, true) => {
+                    println!("\n{} {} has no hash_sha256", "✗".red(), file.display());
+                    std::process::exit(1);
+                }
+                (HashStatus::Mismatch { declared }, true) => {
+                    println!("\n{} hash_sha256 no longer matches the installer (declared {})", "✗".red(), declared);
+                    std::process::exit(1);
+                }
+                (_, false) => {
+                    std::fs::write(&file, set_hash(&source, &result.sha256))?;
+                    println!("\n{} Updated hash_sha256 in {}", "✓".green(), file.display());
+                }
+            }
+        }
+
         Commands::Lsp { debug, stdio: _ } => {
             // Set up logging if debug mode is enabled
             if debug {
-                eprintln!("Fleet LSP server starting in debug mode...");
-                // TODO: Set up tracing/logging to stderr
+                tracing::debug!("Fleet LSP server starting in debug mode");
             }
 
             // Start the LSP server - this blocks until the client disconnects
@@ -569,11 +1861,319 @@ async fn main() -> Result<()> {
             lsp::start_server().await?;
         }
 
-        Commands::Init { output, no_interactive, force } => {
+        Commands::Init { output, yes, force, strictness, print } => {
             let current_dir = std::env::current_dir()?;
-            linter::init_config(&current_dir, output, !no_interactive, force)?;
+            linter::init_config(&current_dir, linter::init::InitOptions {
+                output,
+                interactive: !yes,
+                force,
+                strictness,
+                print,
+            })?;
+        }
+
+        Commands::CiInit { path, provider, force, print } => {
+            linter::ci::ci_init(&path, linter::ci::CiInitOptions {
+                providers: provider,
+                force,
+                print,
+            })?;
+        }
+
+        Commands::Pack { action } => {
+            use colored::Colorize;
+            use linter::packs;
+
+            let root_for = |dir: Option<PathBuf>| dir.unwrap_or_else(|| packs::default_root(std::path::Path::new(".")));
+            match action {
+                PackAction::Add { source, git_ref, dir } => {
+                    let root = root_for(dir);
+                    let installed = packs::add(&root, &source, git_ref.as_deref())?;
+                    let manifest = &installed.pack.manifest;
+                    println!(
+                        "{} Installed {} {} into {}",
+                        "✓".green(),
+                        manifest.name.bold(),
+                        manifest.version,
+                        installed.pack.dir.display()
+                    );
+                    for file in &installed.lib_files {
+                        println!("  + {}", file.display());
+                    }
+                    for file in &installed.skipped_lib_files {
+                        println!("  {} {} (already exists, kept)", "-".dimmed(), file.display());
+                    }
+                }
+                PackAction::List { dir } => {
+                    let root = root_for(dir);
+                    let installed = packs::installed(&root);
+                    if installed.is_empty() {
+                        println!("No packs installed in {}", root.join(packs::PACKS_DIR).display());
+                    }
+                    for pack in installed {
+                        let manifest = pack.manifest;
+                        println!("{} {}", manifest.name.bold(), manifest.version);
+                        if let Some(description) = manifest.description {
+                            println!("  {}", description);
+                        }
+                        if let Some(source) = manifest.source {
+                            let at = manifest.git_ref.map(|r| format!(" @ {}", r)).unwrap_or_default();
+                            println!("  {}{}", source.dimmed(), at.dimmed());
+                        }
+                    }
+                }
+                PackAction::Remove { name, dir } => {
+                    let pack = packs::remove(&root_for(dir), &name)?;
+                    println!("{} Removed {} {}", "✓".green(), pack.manifest.name.bold(), pack.manifest.version);
+                }
+            }
+        }
+
+        Commands::Library { action } => {
+            use colored::Colorize;
+            use linter::library;
+
+            match action {
+                LibraryAction::Sync { tag, name, platform, kind, all, dir, git_ref } => {
+                    let selection = library::Selection { tags: tag, names: name, platform, kind };
+                    let items = library::fetch(&git_ref).await?;
+                    let matching: Vec<&library::LibraryItem> = items.iter().filter(|i| selection.matches(i)).collect();
+
+                    let chosen: Vec<&library::LibraryItem> = if all || !selection.is_empty() {
+                        matching
+                    } else if std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+                        library::prompt(&matching)?.into_iter().map(|i| matching[i]).collect()
+                    } else {
+                        anyhow::bail!("Select items with --tag, --name or --platform, or pass --all");
+                    };
+                    if chosen.is_empty() {
+                        println!("No library items selected");
+                        return Ok(());
+                    }
+
+                    let summary = library::sync(&dir, &chosen, &git_ref)?;
+                    for name in &summary.added {
+                        println!("  {} {}", "+".green(), name);
+                    }
+                    for name in &summary.updated {
+                        println!("  {} {}", "~".yellow(), name);
+                    }
+                    println!(
+                        "{} {} added, {} updated, {} unchanged in {}",
+                        "✓".green(),
+                        summary.added.len(),
+                        summary.updated.len(),
+                        summary.unchanged.len(),
+                        summary.files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(", ")
+                    );
+                }
+                LibraryAction::Check { dir, git_ref } => {
+                    let upstream = library::fetch(&git_ref).await?;
+                    let reports = library::check(&dir, &upstream)?;
+                    if reports.is_empty() {
+                        println!("{} Vendored library items match {}", "✓".green(), git_ref);
+                        return Ok(());
+                    }
+                    for report in &reports {
+                        println!("{} {}: {} ({})", "!".yellow(), report.file.display(), report.name, report.drift);
+                    }
+                    println!("\nRun `fleet-schema-gen library sync --name <name>` to take the upstream version.");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Metrics { action } => {
+            use linter::metrics::{self, Metrics};
+
+            let path_for = |file: Option<PathBuf>| {
+                file.or_else(|| {
+                    let config = linter::FleetLintConfig::find_and_load(std::path::Path::new(".")).map(|(_, c)| c);
+                    metrics::path_for(&config.unwrap_or_default().metrics)
+                })
+                .ok_or_else(|| anyhow::anyhow!("No metrics file: pass --file or set HOME"))
+            };
+            match action {
+                MetricsAction::Report { file, format } => {
+                    let path = path_for(file)?;
+                    let metrics = Metrics::load(&path)?;
+                    match format.as_str() {
+                        "json" => println!("{}", serde_json::to_string_pretty(&metrics)?),
+                        "text" => {
+                            if metrics == Metrics::default() {
+                                println!("No metrics recorded in {} (enable with [metrics] enabled = true)", path.display());
+                            } else {
+                                print!("{}", metrics.render());
+                            }
+                        }
+                        _ => anyhow::bail!("Unknown output format: {} (expected text or json)", format),
+                    }
+                }
+                MetricsAction::Reset { file } => {
+                    let path = path_for(file)?;
+                    if path.exists() {
+                        std::fs::remove_file(&path)?;
+                    }
+                    println!("✓ Reset {}", path.display());
+                }
+            }
+        }
+
+        Commands::Owners { path, format } => {
+            use linter::owners::{render, Ownership};
+
+            let repo = linter::repo::load_repo(&path)?;
+            let items = Ownership::load(&path).items(&repo);
+
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&items)?),
+                "text" => print!("{}", render(&items)),
+                _ => anyhow::bail!("Unknown output format: {} (expected text or json)", format),
+            }
+        }
+
+        Commands::Stats { path, format } => {
+            use linter::schedule::{render, team_loads};
+
+            let config = linter::FleetLintConfig::find_and_load(&path).map(|(_, c)| c).unwrap_or_default();
+            let budget = config.thresholds.max_schedule_load;
+            let repo = linter::repo::load_repo(&path)?;
+            let loads = team_loads(&repo);
+
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                    "budget": budget,
+                    "teams": loads,
+                }))?),
+                "text" => print!("{}", render(&loads, budget)),
+                _ => anyhow::bail!("Unknown output format: {} (expected text or json)", format),
+            }
+        }
+
+        Commands::Impact { file, base, server, token, format } => {
+            use anyhow::Context;
+            use linter::impact::{self, FleetApi};
+            use std::collections::HashMap;
+
+            if !matches!(format.as_str(), "text" | "json") {
+                anyhow::bail!("Unknown output format: {} (expected text or json)", format);
+            }
+            let role = linter::file_role::FileRole::detect(&file);
+            let current = std::fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let previous = impact::read_at_ref(&file, &base)?;
+            let changes = impact::changes(previous.as_deref(), &current, role)
+                .with_context(|| format!("Failed to compare {} with {}", file.display(), base))?;
+
+            if changes.is_empty() {
+                if format == "json" {
+                    println!("[]");
+                } else {
+                    println!("No policy or label changes in {} since {}", file.display(), base);
+                }
+                return Ok(());
+            }
+
+            let api = FleetApi::from_args(server, token)?;
+            let server_teams = api.teams().await?;
+            let label_ids = api.label_ids().await?;
+            let teams = impact::file_teams(&file, &server_teams)?;
+
+            let mut counts = HashMap::new();
+            for key in impact::count_keys(&changes, &teams, &label_ids) {
+                let count = api.host_count(&key).await?;
+                counts.insert(key, count);
+            }
+            let impacts = impact::assess(&changes, &teams, &label_ids, &counts);
+
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&impacts)?),
+                _ => print!("{}", impact::render(&impacts)),
+            }
+        }
+
+        Commands::MockServer { listen, seed, token } => {
+            use linter::mock_server::{serve, MockFleet, MockSeed};
+            use anyhow::Context;
+
+            let seed = seed.map(|path| MockSeed::load(&path)).transpose()?.unwrap_or_default();
+            let listener = tokio::net::TcpListener::bind(&listen)
+                .await
+                .with_context(|| format!("Failed to listen on {}", listen))?;
+            // Printed before serving, so scripts can wait for it (and read the port)
+            println!("Mock Fleet server listening on http://{}", listener.local_addr()?);
+            serve(listener, std::sync::Arc::new(MockFleet::new(seed, token))).await?;
+        }
+
+        Commands::Doctor { path, offline, format } => {
+            use utils::doctor::{diagnose, print, Status};
+
+            let checks = diagnose(&path, offline).await;
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&checks)?),
+                "text" => print(&checks),
+                _ => anyhow::bail!("Unknown output format: {} (expected text or json)", format),
+            }
+
+            if checks.iter().any(|c| c.status == Status::Fail) {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "fleet-schema-gen", &mut std::io::stdout());
+        }
+
+        Commands::Schema { action: SchemaAction::Bundle { action } } => {
+            use utils::bundle;
+
+            let versions_dir_for = |dir: Option<PathBuf>| {
+                dir.unwrap_or_else(|| match linter::FleetLintConfig::find_and_load(std::path::Path::new(".")) {
+                    Some((config_path, config)) => config_path
+                        .parent()
+                        .unwrap_or(std::path::Path::new("."))
+                        .join(config.schema.versions_dir),
+                    None => PathBuf::from("schemas"),
+                })
+            };
+            match action {
+                BundleAction::Export { file, versions_dir } => bundle::export(&file, &versions_dir_for(versions_dir))?,
+                BundleAction::Import { file, versions_dir, check } => {
+                    bundle::import(&file, &versions_dir_for(versions_dir), check)?
+                }
+            }
+        }
+        Commands::SelfUpdate { channel, check } => {
+            utils::self_update::run(channel.parse()?, check).await?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_lint_fix_short_flag() {
+        let cli = Cli::try_parse_from(["fleet-schema-gen", "lint", "-f", "--format", "json", "."]).unwrap();
+        assert!(matches!(cli.command, Some(Commands::Lint { fix: true, ref format, .. }) if format == "json"));
+    }
+
+    #[test]
+    fn test_completions_and_man_page() {
+        let mut completions = Vec::new();
+        clap_complete::generate(clap_complete::Shell::Bash, &mut Cli::command(), "fleet-schema-gen", &mut completions);
+        assert!(String::from_utf8(completions).unwrap().contains("self-update"));
+
+        let mut man = Vec::new();
+        clap_mangen::Man::new(Cli::command()).render(&mut man).unwrap();
+        assert!(String::from_utf8(man).unwrap().contains(".TH fleet-schema-gen"));
+    }
+}
@@ -0,0 +1,102 @@
+//! Errors of [`build_schema`](super::build_schema), by the stage that failed.
+
+use crate::sources::github_api::FetchError;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Box a source's error. A bare [`FetchError`] is boxed as itself so
+/// [`SchemaBuildError::is_rate_limit`] can find it; boxing the `anyhow::Error`
+/// would hide its type.
+pub(super) fn boxed(e: anyhow::Error) -> BoxError {
+    if e.chain().nth(1).is_some() {
+        return e.into();
+    }
+    match e.downcast::<FetchError>() {
+        Ok(e) => Box::new(e),
+        Err(e) => e.into(),
+    }
+}
+
+/// Why a schema could not be built
+#[derive(Debug)]
+pub enum SchemaBuildError {
+    /// Fetching or parsing the Fleet Go source failed
+    GoSource(BoxError),
+    /// Scraping the Fleet documentation failed
+    Docs(BoxError),
+    /// Fetching the YAML examples from GitHub failed
+    Examples(BoxError),
+    /// The local YAML enhancements could not be loaded
+    Enhancements { path: PathBuf, source: BoxError },
+    /// The sources could not be merged
+    Merge(BoxError),
+}
+
+impl fmt::Display for SchemaBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaBuildError::GoSource(_) => write!(f, "Failed to parse the Fleet Go source"),
+            SchemaBuildError::Docs(_) => write!(f, "Failed to fetch the Fleet documentation"),
+            SchemaBuildError::Examples(_) => write!(f, "Failed to fetch the GitHub examples"),
+            SchemaBuildError::Enhancements { path, .. } => {
+                write!(f, "Failed to load YAML enhancements from {}", path.display())
+            }
+            SchemaBuildError::Merge(_) => write!(f, "Failed to merge schema sources"),
+        }
+    }
+}
+
+impl Error for SchemaBuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SchemaBuildError::GoSource(e)
+            | SchemaBuildError::Docs(e)
+            | SchemaBuildError::Examples(e)
+            | SchemaBuildError::Enhancements { source: e, .. }
+            | SchemaBuildError::Merge(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+impl SchemaBuildError {
+    /// Whether a GitHub rate limit caused the failure (retrying later or
+    /// setting `GITHUB_TOKEN` helps)
+    pub fn is_rate_limit(&self) -> bool {
+        let mut cause = self.source();
+        while let Some(e) = cause {
+            if e.downcast_ref::<FetchError>().is_some_and(|e| e.is_rate_limit()) {
+                return true;
+            }
+            cause = e.source();
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_rate_limit() {
+        let limited = || FetchError::RateLimited {
+            url: "https://api.github.com".to_string(),
+            retry_after: None,
+            authenticated: false,
+        };
+        let err = SchemaBuildError::Examples(boxed(anyhow::Error::new(limited())));
+        assert_eq!(err.to_string(), "Failed to fetch the GitHub examples");
+        assert!(err.is_rate_limit());
+
+        let err = SchemaBuildError::Examples(boxed(anyhow::Error::new(limited()).context("Failed to fetch examples")));
+        assert!(err.is_rate_limit());
+        assert_eq!(err.source().unwrap().to_string(), "Failed to fetch examples");
+
+        let err = SchemaBuildError::Merge(boxed(anyhow::anyhow!("conflict")));
+        assert!(!err.is_rate_limit());
+        assert_eq!(err.source().unwrap().to_string(), "conflict");
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use indexmap::IndexMap;
-use crate::schema::types::{FleetSchema, SchemaDefinition, SchemaMetadata, YamlEnhancement, AdditionalProperties};
+use crate::schema::types::{FleetSchema, SchemaDefinition, SchemaMetadata, YamlEnhancement, FieldEnhancement, AdditionalProperties};
 use crate::sources::yaml_defs;
 use chrono::Utc;
 
@@ -175,7 +175,7 @@ pub fn merge_schemas(
     enhancements: IndexMap<String, YamlEnhancement>,
     version: &str,
 ) -> Result<FleetSchema> {
-    println!("  → Merging schemas with priority: Local YAML > Docs > GitHub");
+    tracing::info!("  → Merging schemas with priority: Local YAML > Docs > GitHub");
 
     // Start with base schema from docs
     let mut base_schema = docs_schema;
@@ -223,8 +223,11 @@ fn merge_schema_definitions(base: &mut SchemaDefinition, overlay: SchemaDefiniti
         let base_props = base.properties.get_or_insert_with(IndexMap::new);
 
         for (key, value) in overlay_props {
-            if !base_props.contains_key(&key) {
-                base_props.insert(key, value);
+            match base_props.get_mut(&key) {
+                Some(existing) => merge_examples(existing, value),
+                None => {
+                    base_props.insert(key, value);
+                }
             }
         }
     }
@@ -241,6 +244,70 @@ fn merge_schema_definitions(base: &mut SchemaDefinition, overlay: SchemaDefiniti
     }
 }
 
+/// Fill `examples` and `default` missing from `base` (and its nested
+/// properties and items) from the same field in `overlay`.
+fn merge_examples(base: &mut SchemaDefinition, overlay: SchemaDefinition) {
+    if base.examples.is_none() {
+        base.examples = overlay.examples;
+    }
+    if base.default.is_none() {
+        base.default = overlay.default;
+    }
+
+    if let (Some(base_props), Some(overlay_props)) = (&mut base.properties, overlay.properties) {
+        for (key, value) in overlay_props {
+            if let Some(existing) = base_props.get_mut(&key) {
+                merge_examples(existing, value);
+            }
+        }
+    }
+    if let (Some(base_items), Some(overlay_items)) = (&mut base.items, overlay.items) {
+        merge_examples(base_items, *overlay_items);
+    }
+}
+
+/// Apply local enhancements to an already generated schema, e.g. the one
+/// an editor loaded, without regenerating it from its sources.
+pub fn apply_local_enhancements(
+    schema: &mut FleetSchema,
+    enhancements: &IndexMap<String, YamlEnhancement>,
+) -> Result<()> {
+    for root in [&mut schema.default_schema, &mut schema.team_schema] {
+        apply_enhancements(root, enhancements)?;
+
+        // List sections (`policies:`) document their fields on the items
+        for (section, enhancement) in enhancements {
+            let items = root.properties.as_mut()
+                .and_then(|p| p.get_mut(section))
+                .and_then(|prop| prop.items.as_deref_mut());
+            if let (Some(items), Some(fields)) = (items, &enhancement.fields) {
+                merge_fields(items, fields);
+            }
+        }
+    }
+
+    // Lib file schemas take the fields of their section
+    for (section, lib_schema) in [
+        ("policies", &mut schema.policy_schema),
+        ("queries", &mut schema.query_schema),
+        ("labels", &mut schema.label_schema),
+    ] {
+        if let Some(fields) = enhancements.get(section).and_then(|e| e.fields.as_ref()) {
+            merge_fields(lib_schema, fields);
+        }
+    }
+
+    Ok(())
+}
+
+fn merge_fields(schema: &mut SchemaDefinition, fields: &IndexMap<String, FieldEnhancement>) {
+    for (name, field_enhancement) in fields {
+        if let Some(prop) = schema.properties.as_mut().and_then(|p| p.get_mut(name)) {
+            yaml_defs::merge_field_enhancement(prop, field_enhancement);
+        }
+    }
+}
+
 fn apply_enhancements(
     schema: &mut SchemaDefinition,
     enhancements: &IndexMap<String, YamlEnhancement>,
@@ -255,6 +322,10 @@ fn apply_enhancements(
                     prop.default_snippets = Some(snippets.clone());
                 }
 
+                if let Some(docs) = &enhancement.docs {
+                    prop.docs_url = Some(yaml_defs::docs_url(docs));
+                }
+
                 // Apply field-level enhancements
                 if let Some(fields) = &enhancement.fields {
                     for (field_name, field_enhancement) in fields {
@@ -331,6 +402,9 @@ fn create_policy_schema(enhancements: &IndexMap<String, YamlEnhancement>) -> Sch
     // Apply top-level defaultSnippets from enhancement file
     let default_snippets = policy_enhancement
         .and_then(|e| e.default_snippets.clone());
+    let docs_url = policy_enhancement
+        .and_then(|e| e.docs.as_deref())
+        .map(yaml_defs::docs_url);
 
     SchemaDefinition {
         schema: Some("https://json-schema.org/draft-07/schema#".to_string()),
@@ -341,6 +415,7 @@ fn create_policy_schema(enhancements: &IndexMap<String, YamlEnhancement>) -> Sch
         required: Some(vec!["name".to_string(), "query".to_string()]),
         additional_properties: Some(AdditionalProperties::Boolean(false)),
         default_snippets,
+        docs_url,
         ..Default::default()
     }
 }
@@ -384,6 +459,9 @@ fn create_query_schema(enhancements: &IndexMap<String, YamlEnhancement>) -> Sche
     // Apply top-level defaultSnippets from enhancement file
     let default_snippets = query_enhancement
         .and_then(|e| e.default_snippets.clone());
+    let docs_url = query_enhancement
+        .and_then(|e| e.docs.as_deref())
+        .map(yaml_defs::docs_url);
 
     SchemaDefinition {
         schema: Some("https://json-schema.org/draft-07/schema#".to_string()),
@@ -394,6 +472,7 @@ fn create_query_schema(enhancements: &IndexMap<String, YamlEnhancement>) -> Sche
         required: Some(vec!["name".to_string(), "query".to_string()]),
         additional_properties: Some(AdditionalProperties::Boolean(false)),
         default_snippets,
+        docs_url,
         ..Default::default()
     }
 }
@@ -433,6 +512,9 @@ fn create_label_schema(enhancements: &IndexMap<String, YamlEnhancement>) -> Sche
     // Apply top-level defaultSnippets from enhancement file
     let default_snippets = label_enhancement
         .and_then(|e| e.default_snippets.clone());
+    let docs_url = label_enhancement
+        .and_then(|e| e.docs.as_deref())
+        .map(yaml_defs::docs_url);
 
     SchemaDefinition {
         schema: Some("https://json-schema.org/draft-07/schema#".to_string()),
@@ -443,6 +525,46 @@ fn create_label_schema(enhancements: &IndexMap<String, YamlEnhancement>) -> Sche
         required: Some(vec!["name".to_string(), "query".to_string()]),
         additional_properties: Some(AdditionalProperties::Boolean(false)),
         default_snippets,
+        docs_url,
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::SchemaType;
+
+    fn object(properties: Vec<(&str, SchemaDefinition)>) -> SchemaDefinition {
+        SchemaDefinition {
+            type_: Some(SchemaType::Single("object".to_string())),
+            properties: Some(properties.into_iter().map(|(k, v)| (k.to_string(), v)).collect()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_examples_fill_existing_fields() {
+        let described = SchemaDefinition { description: Some("From Go".to_string()), ..Default::default() };
+        let mut base = object(vec![("controls", object(vec![("enable_disk_encryption", described)]))]);
+
+        let observed = SchemaDefinition {
+            examples: Some(vec![serde_json::json!(true)]),
+            default: Some(serde_json::json!(true)),
+            ..Default::default()
+        };
+        let overlay = object(vec![
+            ("controls", object(vec![("enable_disk_encryption", observed)])),
+            ("software", object(vec![])),
+        ]);
+
+        merge_schema_definitions(&mut base, overlay);
+
+        let props = base.properties.unwrap();
+        let field = &props["controls"].properties.as_ref().unwrap()["enable_disk_encryption"];
+        assert_eq!(field.description.as_deref(), Some("From Go"));
+        assert_eq!(field.examples, Some(vec![serde_json::json!(true)]));
+        assert_eq!(field.default, Some(serde_json::json!(true)));
+        assert!(props.contains_key("software"));
+    }
+}
@@ -1,7 +1,10 @@
 pub mod types;
 pub mod merger;
+pub mod error;
 
-use anyhow::Result;
+pub use error::SchemaBuildError;
+
+use error::boxed;
 use std::path::Path;
 use crate::sources;
 use types::FleetSchema;
@@ -10,73 +13,99 @@ pub async fn build_schema(
     fleet_version: Option<String>,
     schema_defs_path: &Path,
     source: &str,
-) -> Result<FleetSchema> {
+) -> Result<FleetSchema, SchemaBuildError> {
     let version = fleet_version.unwrap_or_else(|| "latest".to_string());
 
-    println!("Building schema from multiple sources...");
+    tracing::info!("Building schema from multiple sources...");
 
     match source {
         "go" => {
             // Parse Fleet Go source code only
-            println!("  → Parsing Fleet Go source code...");
-            let go_data = sources::go_parser::fetch_from_fleet_repo(Some(&version)).await?;
+            tracing::info!("  → Parsing Fleet Go source code...");
+            let go_data = sources::go_parser::fetch_from_fleet_repo(Some(&version))
+                .await
+                .map_err(|e| SchemaBuildError::GoSource(boxed(e)))?;
 
             // Still load local enhancements for IDE-specific features
-            println!("  → Loading local YAML enhancements...");
-            let local_data = sources::yaml_defs::load_enhancements(schema_defs_path)?;
+            tracing::info!("  → Loading local YAML enhancements...");
+            let local_data = load_enhancements(schema_defs_path)?;
 
             // Merge Go schema with local enhancements
-            let merged = merger::merge_with_go_schema(go_data, local_data, &version)?;
+            let merged = merger::merge_with_go_schema(go_data, local_data, &version)
+                .map_err(|e| SchemaBuildError::Merge(boxed(e)))?;
 
-            println!("✓ Schema built successfully from Go source");
+            tracing::info!("✓ Schema built successfully from Go source");
             Ok(merged)
         }
         "examples" => {
             // Infer from YAML examples only
-            println!("  → Fetching from GitHub examples...");
-            let github_data = sources::github::fetch_schema(&version).await?;
+            tracing::info!("  → Fetching from GitHub examples...");
+            let github_data = sources::github::fetch_schema(&version)
+                .await
+                .map_err(|e| SchemaBuildError::Examples(boxed(e)))?;
+
+            tracing::info!("  → Loading local YAML enhancements...");
+            let local_data = load_enhancements(schema_defs_path)?;
 
-            println!("  → Loading local YAML enhancements...");
-            let local_data = sources::yaml_defs::load_enhancements(schema_defs_path)?;
+            let merged = merger::merge_with_examples(github_data, local_data, &version)
 
-            let merged = merger::merge_with_examples(github_data, local_data, &version)?;
+                .map_err(|e| SchemaBuildError::Merge(boxed(e)))?;
 
-            println!("✓ Schema built successfully from examples");
+            tracing::info!("✓ Schema built successfully from examples");
             Ok(merged)
         }
         "docs" => {
             // Scrape from Fleet docs only
-            println!("  → Fetching from Fleet documentation...");
-            let docs_data = sources::docs_scraper::fetch_schema().await?;
+            tracing::info!("  → Fetching from Fleet documentation...");
+            let docs_data = sources::docs_scraper::fetch_schema()
+                .await
+                .map_err(|e| SchemaBuildError::Docs(boxed(e)))?;
+
+            tracing::info!("  → Loading local YAML enhancements...");
+            let local_data = load_enhancements(schema_defs_path)?;
 
-            println!("  → Loading local YAML enhancements...");
-            let local_data = sources::yaml_defs::load_enhancements(schema_defs_path)?;
+            let merged = merger::merge_with_docs(docs_data, local_data, &version)
 
-            let merged = merger::merge_with_docs(docs_data, local_data, &version)?;
+                .map_err(|e| SchemaBuildError::Merge(boxed(e)))?;
 
-            println!("✓ Schema built successfully from docs");
+            tracing::info!("✓ Schema built successfully from docs");
             Ok(merged)
         }
         "hybrid" | _ => {
             // Hybrid: Go source + Examples + Docs + Local
-            println!("  → Parsing Fleet Go source code...");
-            let go_data = sources::go_parser::fetch_from_fleet_repo(Some(&version)).await?;
+            tracing::info!("  → Parsing Fleet Go source code...");
+            let go_data = sources::go_parser::fetch_from_fleet_repo(Some(&version))
+                .await
+                .map_err(|e| SchemaBuildError::GoSource(boxed(e)))?;
 
-            println!("  → Fetching from Fleet documentation...");
-            let docs_data = sources::docs_scraper::fetch_schema().await?;
+            tracing::info!("  → Fetching from Fleet documentation...");
+            let docs_data = sources::docs_scraper::fetch_schema()
+                .await
+                .map_err(|e| SchemaBuildError::Docs(boxed(e)))?;
 
-            println!("  → Fetching from GitHub examples...");
-            let github_data = sources::github::fetch_schema(&version).await?;
+            tracing::info!("  → Fetching from GitHub examples...");
+            let github_data = sources::github::fetch_schema(&version)
+                .await
+                .map_err(|e| SchemaBuildError::Examples(boxed(e)))?;
 
-            println!("  → Loading local YAML enhancements...");
-            let local_data = sources::yaml_defs::load_enhancements(schema_defs_path)?;
+            tracing::info!("  → Loading local YAML enhancements...");
+            let local_data = load_enhancements(schema_defs_path)?;
 
             // Merge with priority: Go > Docs > Examples > Local
-            println!("  → Merging schemas with priority: Go > Docs > Examples > Local");
-            let merged = merger::merge_all_sources(go_data, docs_data, github_data, local_data, &version)?;
+            tracing::info!("  → Merging schemas with priority: Go > Docs > Examples > Local");
+            let merged = merger::merge_all_sources(go_data, docs_data, github_data, local_data, &version)
+                .map_err(|e| SchemaBuildError::Merge(boxed(e)))?;
 
-            println!("✓ Schema built successfully (hybrid mode)");
+            tracing::info!("✓ Schema built successfully (hybrid mode)");
             Ok(merged)
         }
     }
 }
+
+/// Local YAML enhancements for IDE-specific features
+fn load_enhancements(
+    schema_defs_path: &Path,
+) -> Result<indexmap::IndexMap<String, types::YamlEnhancement>, SchemaBuildError> {
+    sources::yaml_defs::load_enhancements(schema_defs_path)
+        .map_err(|e| SchemaBuildError::Enhancements { path: schema_defs_path.to_path_buf(), source: boxed(e) })
+}
@@ -49,6 +49,9 @@ pub struct SchemaDefinition {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub format: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<i64>,
+
     #[serde(rename = "oneOf", skip_serializing_if = "Option::is_none")]
     pub one_of: Option<Vec<SchemaDefinition>>,
 
@@ -57,6 +60,10 @@ pub struct SchemaDefinition {
 
     #[serde(rename = "defaultSnippets", skip_serializing_if = "Option::is_none")]
     pub default_snippets: Option<Vec<DefaultSnippet>>,
+
+    /// Section of Fleet's YAML reference documenting this field
+    #[serde(rename = "x-docs-url", skip_serializing_if = "Option::is_none")]
+    pub docs_url: Option<String>,
 }
 
 /// VSCode YAML extension defaultSnippet for autocomplete
@@ -113,6 +120,11 @@ pub struct YamlEnhancement {
 
     #[serde(rename = "defaultSnippets", skip_serializing_if = "Option::is_none")]
     pub default_snippets: Option<Vec<DefaultSnippet>>,
+
+    /// Docs anchor (`#policies`) or URL for the section; fields without
+    /// their own `docs` link here.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +150,10 @@ pub struct FieldEnhancement {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sublime_hint: Option<String>,
 
+    /// Docs anchor (`#macos-settings`) or URL for the field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
+
     #[serde(rename = "defaultSnippets", skip_serializing_if = "Option::is_none")]
     pub default_snippets: Option<Vec<DefaultSnippet>>,
 }
@@ -0,0 +1,275 @@
+//! GitOps key deprecations from Fleet's CHANGELOG.
+//!
+//! Release notes announce deprecated, removed and renamed YAML keys in
+//! prose ("Deprecated `foo_bar` in GitOps files in favor of `baz`"). This
+//! source extracts the backticked keys per release and turns them into
+//! migrations: renames become `field_rename`, deprecations and removals
+//! become `field_delete` with the changelog line as the reason.
+//!
+//! `fleet-schema-gen update --source changelog` writes the result as a
+//! `migrations.toml` draft. Entries reach the `deprecated-fields` rule once
+//! they're reviewed and moved to `migrations.toml`; the heuristics are too
+//! loose to apply unreviewed.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+use crate::linter::migrate::{Migration, Transformation, Version};
+
+const CHANGELOG_URL: &str = "https://raw.githubusercontent.com/fleetdm/fleet/main/CHANGELOG.md";
+
+/// Name of the migrations draft written by `update --source changelog`.
+pub const MIGRATIONS_FILE: &str = "changelog-migrations.toml";
+
+/// Changelog entries don't say which files a key lives in.
+const ALL_YAML_FILES: &str = "**/*.yml";
+
+/// `## Fleet 4.74.0 (Sep 25, 2025)`
+static RELEASE_HEADING: Lazy<Regex> = Lazy::new(|| Regex::new(r"^#+\s*Fleet\s+v?(\d+\.\d+(?:\.\d+)?)").unwrap());
+
+static BACKTICKED: Lazy<Regex> = Lazy::new(|| Regex::new(r"`([^`]+)`").unwrap());
+
+/// `self_service`, `controls.macos_updates`: lowercase, with an underscore
+/// or a dot so command names and values don't qualify.
+static YAML_KEY: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z][a-z0-9_]*(\.[a-z][a-z0-9_]*)*$").unwrap());
+
+/// What happened to a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Deprecated,
+    Removed,
+    /// Renamed to (or deprecated in favor of) this key.
+    Renamed(String),
+}
+
+/// A GitOps key change announced in a release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChange {
+    pub version: Version,
+    /// The release before `version` in the changelog, if any.
+    pub previous: Option<Version>,
+    pub key: String,
+    pub kind: ChangeKind,
+    /// The changelog bullet, without the list marker.
+    pub line: String,
+}
+
+pub async fn fetch_changes() -> Result<Vec<KeyChange>> {
+    let changelog = super::github_api::GitHubClient::new()?.get_text(CHANGELOG_URL).await?;
+    Ok(parse(&changelog))
+}
+
+pub async fn fetch_and_save(output_dir: &Path) -> Result<()> {
+    let changes = fetch_changes().await?;
+
+    let output_path = output_dir.join(MIGRATIONS_FILE);
+    std::fs::create_dir_all(output_dir)?;
+    std::fs::write(&output_path, to_toml(&changes)?)?;
+
+    tracing::info!("  → {} GitOps key change(s) found", changes.len());
+    tracing::debug!("  ✓ Saved to: {}", output_path.display());
+
+    Ok(())
+}
+
+/// Key changes in a changelog, newest release first.
+pub fn parse(changelog: &str) -> Vec<KeyChange> {
+    // Release headings with their line index
+    let releases: Vec<(usize, Version)> = changelog
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let version = RELEASE_HEADING.captures(line)?.get(1)?.as_str();
+            Some((i, Version::parse(version)?))
+        })
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut release = None;
+    for (i, line) in changelog.lines().enumerate() {
+        if let Some(index) = releases.iter().position(|(start, _)| *start == i) {
+            release = Some((releases[index].1.clone(), releases.get(index + 1).map(|(_, v)| v.clone())));
+            continue;
+        }
+        let Some((version, previous)) = &release else {
+            continue;
+        };
+
+        let text = line.trim().trim_start_matches(['-', '*']).trim();
+        for (key, kind) in key_changes(text) {
+            changes.push(KeyChange {
+                version: version.clone(),
+                previous: previous.clone(),
+                key,
+                kind,
+                line: text.to_string(),
+            });
+        }
+    }
+    changes
+}
+
+/// Keys a single changelog line deprecates, removes or renames.
+fn key_changes(line: &str) -> Vec<(String, ChangeKind)> {
+    let lower = line.to_lowercase();
+    if !(lower.contains("gitops") || lower.contains("yaml")) {
+        return Vec::new();
+    }
+
+    let mut keys: Vec<String> = Vec::new();
+    for captures in BACKTICKED.captures_iter(line) {
+        let key = captures[1].trim().trim_end_matches(':');
+        let is_key = YAML_KEY.is_match(key)
+            && (key.contains('_') || key.contains('.'))
+            && !key.ends_with(".yml")
+            && !key.ends_with(".yaml");
+        if is_key && !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+        }
+    }
+    if keys.is_empty() {
+        return Vec::new();
+    }
+
+    let replaced = ["renamed", "in favor of", "replaced by", "instead of"]
+        .iter()
+        .any(|phrase| lower.contains(phrase));
+    if replaced && keys.len() >= 2 {
+        // "Renamed `old` to `new`", but "use `new` instead of `old`"
+        let (old, new) = if lower.contains("instead of") { (&keys[1], &keys[0]) } else { (&keys[0], &keys[1]) };
+        return vec![(old.clone(), ChangeKind::Renamed(new.clone()))];
+    }
+
+    let kind = if lower.contains("deprecat") {
+        ChangeKind::Deprecated
+    } else if lower.contains("removed") || lower.contains("no longer supported") {
+        ChangeKind::Removed
+    } else {
+        return Vec::new();
+    };
+    keys.into_iter().map(|k| (k, kind.clone())).collect()
+}
+
+/// One migration per release with key changes.
+pub fn to_migrations(changes: &[KeyChange]) -> Vec<Migration> {
+    let mut migrations: Vec<Migration> = Vec::new();
+    for change in changes {
+        let transformation = match &change.kind {
+            ChangeKind::Renamed(new_path) => Transformation::FieldRename {
+                pattern: ALL_YAML_FILES.to_string(),
+                old_path: change.key.clone(),
+                new_path: new_path.clone(),
+            },
+            ChangeKind::Deprecated | ChangeKind::Removed => Transformation::FieldDelete {
+                pattern: ALL_YAML_FILES.to_string(),
+                fields: vec![change.key.clone()],
+                reason: Some(change.line.clone()),
+            },
+        };
+
+        match migrations.iter_mut().find(|m| m.to_version == change.version) {
+            Some(migration) => migration.transformations.push(transformation),
+            None => migrations.push(Migration {
+                id: format!("changelog-{}", change.version),
+                from_version: change.previous.clone().unwrap_or_else(|| change.version.clone()),
+                to_version: change.version.clone(),
+                versions: None,
+                description: format!("GitOps key changes from the Fleet {} changelog", change.version),
+                transformations: vec![transformation],
+            }),
+        }
+    }
+    migrations
+}
+
+/// `migrations.toml` draft for the changes.
+pub fn to_toml(changes: &[KeyChange]) -> Result<String> {
+    let quote = |s: &str| toml::Value::String(s.to_string()).to_string();
+
+    let mut out = String::from(
+        "# GitOps key changes drafted from Fleet's CHANGELOG.md by\n\
+         # `fleet-schema-gen update --source changelog`.\n\
+         # Review patterns and replacements before moving entries to migrations.toml.\n",
+    );
+
+    for migration in to_migrations(changes) {
+        out.push_str(&format!(
+            "\n[[migration]]\nid = {}\nfrom_version = \"{}\"\nto_version = \"{}\"\ndescription = {}\n",
+            quote(&migration.id),
+            migration.from_version,
+            migration.to_version,
+            quote(&migration.description)
+        ));
+
+        // Every transformation kind, in the layout the loader reads
+        for transformation in &migration.transformations {
+            out.push_str("\n[[migration.transformations]]\n");
+            out.push_str(&toml::to_string(transformation)?);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "\
+# Fleet changelog
+
+## Fleet 4.75.0 (Oct 20, 2025)
+
+### IT Admins
+- Renamed `macos_setup.bootstrap` to `macos_setup.bootstrap_package` in GitOps files.
+- Deprecated `install_during_setup` in GitOps YAML in favor of `setup_experience`.
+- Added `fleetctl gitops` support for `windows_updates`.
+
+## Fleet 4.74.0 (Sep 25, 2025)
+
+- Removed support for `host_status_webhook` in GitOps configuration files.
+- Use `team_settings.host_features` instead of `team_settings.features` in team YAML files.
+- Deprecated `host_count` in the API response.
+
+## Fleet 4.73.0 (Sep 1, 2025)
+
+- Fixed a bug in `default.yml` handling in GitOps.
+";
+
+    #[test]
+    fn test_parse_changelog() {
+        let changes = parse(CHANGELOG);
+        let summary: Vec<(String, &str, ChangeKind)> =
+            changes.iter().map(|c| (c.version.to_string(), c.key.as_str(), c.kind.clone())).collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("4.75.0".to_string(), "macos_setup.bootstrap", ChangeKind::Renamed("macos_setup.bootstrap_package".to_string())),
+                ("4.75.0".to_string(), "install_during_setup", ChangeKind::Renamed("setup_experience".to_string())),
+                ("4.74.0".to_string(), "host_status_webhook", ChangeKind::Removed),
+                ("4.74.0".to_string(), "team_settings.features", ChangeKind::Renamed("team_settings.host_features".to_string())),
+            ]
+        );
+        assert_eq!(changes[0].previous, Some(Version::new(4, 74, 0)));
+        assert_eq!(changes[2].line, "Removed support for `host_status_webhook` in GitOps configuration files.");
+    }
+
+    #[test]
+    fn test_migrations_draft_loads() {
+        let changes = parse(CHANGELOG);
+        let draft = to_toml(&changes).unwrap();
+        let migrations = crate::linter::migrate::loader::load_migrations_from_str(&draft).unwrap();
+
+        assert!(crate::linter::migrate::loader::load_migrations_from_str(&to_toml(&[]).unwrap()).unwrap().is_empty());
+        assert_eq!(migrations.len(), 2);
+        assert_eq!(migrations[0].id, "changelog-4.75.0");
+        assert_eq!(migrations[0].from_version, Version::new(4, 74, 0));
+        assert_eq!(migrations[0].transformations.len(), 2);
+        assert!(matches!(
+            &migrations[1].transformations[0],
+            Transformation::FieldDelete { fields, reason: Some(reason), .. }
+                if fields == &vec!["host_status_webhook".to_string()] && reason.starts_with("Removed support")
+        ));
+    }
+}
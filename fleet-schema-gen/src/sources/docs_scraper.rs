@@ -27,7 +27,7 @@ pub async fn fetch_and_save(output_dir: &Path) -> Result<()> {
     let json = serde_json::to_string_pretty(&schema)?;
     std::fs::write(&output_path, json)?;
 
-    println!("  ✓ Saved to: {}", output_path.display());
+    tracing::debug!("  ✓ Saved to: {}", output_path.display());
 
     Ok(())
 }
@@ -1,10 +1,15 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output};
+use std::time::Duration;
+use super::github_api;
 
 const FLEET_REPO_URL: &str = "https://github.com/fleetdm/fleet.git";
 const DEFAULT_FLEET_REPO_PATH: &str = "/tmp/fleet";
 
+/// Attempts for network operations (clone, pull).
+const NETWORK_ATTEMPTS: u32 = 3;
+
 /// Manages Fleet repository cloning and updates
 pub struct FleetRepo {
     repo_path: PathBuf,
@@ -31,7 +36,7 @@ impl FleetRepo {
     /// Ensure Fleet repository is available (clone if needed, update if exists)
     pub fn ensure_repo(&self, version: Option<&str>) -> Result<()> {
         if self.repo_path.exists() {
-            println!("  → Fleet repository exists at: {}", self.repo_path.display());
+            tracing::info!("  → Fleet repository exists at: {}", self.repo_path.display());
 
             if let Some(ver) = version {
                 self.checkout_version(ver)?;
@@ -39,40 +44,52 @@ impl FleetRepo {
                 self.update_repo()?;
             }
         } else {
-            println!("  → Cloning Fleet repository...");
+            tracing::info!("  → Cloning Fleet repository...");
             self.clone_repo(version)?;
         }
 
         Ok(())
     }
 
-    /// Clone the Fleet repository
-    fn clone_repo(&self, version: Option<&str>) -> Result<()> {
+    /// A git command, authenticated with the GitHub token if one is set
+    fn git(&self) -> Command {
         let mut cmd = Command::new("git");
-        cmd.arg("clone");
-
-        // If version is specified and looks like a tag, do a shallow clone
-        if version.is_some() {
-            cmd.arg("--depth").arg("1");
-            if let Some(ver) = version {
-                if ver.starts_with('v') || ver.starts_with("fleet-v") {
-                    cmd.arg("--branch").arg(ver);
-                }
-            }
+        if let Some(token) = github_api::token() {
+            let (key, value) = github_api::git_auth_config(&token);
+            cmd.env("GIT_CONFIG_COUNT", "1")
+                .env("GIT_CONFIG_KEY_0", key)
+                .env("GIT_CONFIG_VALUE_0", value);
         }
+        cmd
+    }
 
-        cmd.arg(FLEET_REPO_URL).arg(&self.repo_path);
-
-        let output = cmd
-            .output()
-            .context("Failed to execute git clone command")?;
+    /// Run a network git command, retrying with backoff
+    fn run_with_retry(&self, operation: &str, build: impl Fn() -> Command) -> Result<Output> {
+        let mut attempt = 0;
+        loop {
+            let output = build()
+                .output()
+                .with_context(|| format!("Failed to execute {}", operation.to_lowercase()))?;
+            if output.status.success() {
+                return Ok(output);
+            }
 
-        if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Git clone failed: {}", stderr);
+            attempt += 1;
+            if attempt >= NETWORK_ATTEMPTS {
+                return Err(github_api::git_error(operation, &stderr, github_api::token().is_some()));
+            }
+            let delay = Duration::from_secs(2u64.pow(attempt));
+            tracing::warn!("  {} failed, retrying in {}s: {}", operation, delay.as_secs(), stderr.trim());
+            std::thread::sleep(delay);
         }
+    }
+
+    /// Clone the Fleet repository
+    fn clone_repo(&self, version: Option<&str>) -> Result<()> {
+        self.run_with_retry("Git clone", || self.clone_command(version))?;
 
-        println!("  ✓ Cloned Fleet repository to {}", self.repo_path.display());
+        tracing::debug!("  ✓ Cloned Fleet repository to {}", self.repo_path.display());
 
         // If version was specified but not as a branch, checkout after cloning
         if let Some(ver) = version {
@@ -84,24 +101,40 @@ impl FleetRepo {
         Ok(())
     }
 
-    /// Update existing repository
-    fn update_repo(&self) -> Result<()> {
-        println!("  → Updating Fleet repository...");
+    fn clone_command(&self, version: Option<&str>) -> Command {
+        let mut cmd = self.git();
+        cmd.arg("clone");
 
-        let output = Command::new("git")
-            .arg("-C")
-            .arg(&self.repo_path)
-            .arg("pull")
-            .arg("--quiet")
-            .output()
-            .context("Failed to execute git pull")?;
+        // If version is specified and looks like a tag, do a shallow clone
+        if version.is_some() {
+            cmd.arg("--depth").arg("1");
+            if let Some(ver) = version {
+                if ver.starts_with('v') || ver.starts_with("fleet-v") {
+                    cmd.arg("--branch").arg(ver);
+                }
+            }
+        }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            eprintln!("  ⚠ Git pull failed: {}", stderr);
-            eprintln!("  → Continuing with current repository state");
-        } else {
-            println!("  ✓ Updated Fleet repository");
+        cmd.arg(FLEET_REPO_URL).arg(&self.repo_path);
+        cmd
+    }
+
+    /// Update existing repository
+    fn update_repo(&self) -> Result<()> {
+        tracing::info!("  → Updating Fleet repository...");
+
+        let pull = self.run_with_retry("Git pull", || {
+            let mut cmd = self.git();
+            cmd.arg("-C").arg(&self.repo_path).arg("pull").arg("--quiet");
+            cmd
+        });
+
+        match pull {
+            Ok(_) => tracing::debug!("  ✓ Updated Fleet repository"),
+            Err(e) => {
+                tracing::warn!("  {}", e);
+                tracing::warn!("  → Continuing with current repository state");
+            }
         }
 
         Ok(())
@@ -115,7 +148,7 @@ impl FleetRepo {
             return Ok(());
         }
 
-        println!("  → Checking out Fleet version: {}", version);
+        tracing::info!("  → Checking out Fleet version: {}", version);
 
         let output = Command::new("git")
             .arg("-C")
@@ -130,7 +163,7 @@ impl FleetRepo {
             anyhow::bail!("Git checkout failed: {}", stderr);
         }
 
-        println!("  ✓ Checked out version: {}", version);
+        tracing::debug!("  ✓ Checked out version: {}", version);
         Ok(())
     }
 
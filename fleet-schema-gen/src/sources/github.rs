@@ -1,7 +1,9 @@
 use anyhow::Result;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use crate::schema::types::SchemaDefinition;
+use super::github_api::GitHubClient;
 
 #[derive(Debug, Deserialize, Serialize)]
 struct GitHubRelease {
@@ -14,20 +16,36 @@ struct GitHubRelease {
 const FLEET_REPO: &str = "fleetdm/fleet";
 const FLEET_GITOPS_REPO: &str = "fleetdm/fleet-gitops";
 
+/// Fleet's own GitOps configuration, in the main repository.
+const IT_AND_SECURITY_DIR: &str = "it-and-security";
+
+/// Distinct values kept as a field's `examples`.
+const MAX_EXAMPLES: usize = 3;
+
+/// Scalar values seen per field path (`controls.macos_updates.deadline`,
+/// `labels_include_any[]`), in file order.
+type Observations = IndexMap<String, Vec<serde_json::Value>>;
+
 pub async fn fetch_schema(version: &str) -> Result<SchemaDefinition> {
-    println!("  → Fetching Fleet version: {}", version);
+    tracing::info!("  → Fetching Fleet version: {}", version);
+
+    let client = GitHubClient::new()?;
+    if !client.is_authenticated() {
+        tracing::debug!("  No GitHub token set; requests are subject to anonymous rate limits");
+    }
 
     // Get latest release if version is "latest"
     let release_version = if version == "latest" {
-        get_latest_release().await?
+        get_latest_release(&client).await?
     } else {
         version.to_string()
     };
 
-    println!("  → Using Fleet version: {}", release_version);
+    tracing::info!("  → Using Fleet version: {}", release_version);
 
-    // Fetch example YAML files from fleet-gitops repo
-    let examples = fetch_gitops_examples(&release_version).await?;
+    // Fetch example YAML files from fleet-gitops and Fleet's it-and-security
+    let mut examples = fetch_gitops_examples(&client, &release_version).await?;
+    examples.extend(fetch_it_and_security_examples(&client, &release_version).await);
 
     // Parse examples to infer schema
     let schema = infer_schema_from_examples(examples)?;
@@ -44,27 +62,25 @@ pub async fn fetch_and_save(output_dir: &Path) -> Result<()> {
     let json = serde_json::to_string_pretty(&schema)?;
     std::fs::write(&output_path, json)?;
 
-    println!("  ✓ Saved to: {}", output_path.display());
+    tracing::debug!("  ✓ Saved to: {}", output_path.display());
 
     Ok(())
 }
 
-async fn get_latest_release() -> Result<String> {
-    let url = format!("https://api.github.com/repos/{}/releases/latest", FLEET_REPO);
+/// Tag of the latest Fleet release, e.g. `fleet-v4.62.0`.
+pub async fn latest_release_tag() -> Result<String> {
+    get_latest_release(&GitHubClient::new()?).await
+}
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "fleet-schema-gen")
-        .send()
-        .await?;
+async fn get_latest_release(client: &GitHubClient) -> Result<String> {
+    let url = format!("https://api.github.com/repos/{}/releases/latest", FLEET_REPO);
 
-    let release: GitHubRelease = response.json().await?;
+    let release: GitHubRelease = client.get_json(&url).await?;
 
     Ok(release.tag_name)
 }
 
-async fn fetch_gitops_examples(_version: &str) -> Result<Vec<String>> {
+async fn fetch_gitops_examples(client: &GitHubClient, _version: &str) -> Result<Vec<String>> {
     // Fetch comprehensive example files from fleet-gitops repository
     // These paths are from the actual fleet-gitops repo structure
     let example_files = vec![
@@ -98,57 +114,90 @@ async fn fetch_gitops_examples(_version: &str) -> Result<Vec<String>> {
     let mut successful = 0;
 
     for file in example_files {
-        match fetch_file_from_repo(FLEET_GITOPS_REPO, file, "main").await {
+        match fetch_file_from_repo(client, FLEET_GITOPS_REPO, file, "main").await {
             Ok(content) => {
                 examples.push(content);
                 successful += 1;
             }
             Err(e) => {
-                eprintln!("  ⚠ Could not fetch {}: {}", file, e);
+                tracing::warn!("  Could not fetch {}: {}", file, e);
             }
         }
     }
 
-    println!("  → Fetched {} example file(s)", successful);
+    tracing::info!("  → Fetched {} example file(s)", successful);
 
     Ok(examples)
 }
 
-async fn fetch_file_from_repo(repo: &str, path: &str, branch: &str) -> Result<String> {
+/// Fleet's own configuration at `version` (falling back to `main`), which
+/// uses more of the format than the fleet-gitops starter repository.
+async fn fetch_it_and_security_examples(client: &GitHubClient, version: &str) -> Vec<String> {
+    let example_files = [
+        "default.yml",
+        "teams/workstations.yml",
+        "teams/workstations-canary.yml",
+        "teams/servers.yml",
+        "teams/compliance-exclusions.yml",
+        "teams/ios-ipados.yml",
+        "lib/agent-options.yml",
+    ];
+
+    let mut examples = Vec::new();
+    for file in example_files {
+        let path = format!("{}/{}", IT_AND_SECURITY_DIR, file);
+        let content = match fetch_file_from_repo(client, FLEET_REPO, &path, version).await {
+            Ok(content) => Ok(content),
+            Err(e) if version != "main" && !e.is_rate_limit() => fetch_file_from_repo(client, FLEET_REPO, &path, "main").await,
+            Err(e) => Err(e),
+        };
+        match content {
+            Ok(content) => examples.push(content),
+            Err(e) => tracing::warn!("  Could not fetch {}: {}", path, e),
+        }
+    }
+
+    tracing::info!("  → Fetched {} it-and-security file(s)", examples.len());
+    examples
+}
+
+async fn fetch_file_from_repo(
+    client: &GitHubClient,
+    repo: &str,
+    path: &str,
+    branch: &str,
+) -> std::result::Result<String, super::github_api::FetchError> {
     let url = format!(
         "https://raw.githubusercontent.com/{}/{}/{}",
         repo, branch, path
     );
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "fleet-schema-gen")
-        .send()
-        .await?;
-
-    if response.status().is_success() {
-        Ok(response.text().await?)
-    } else {
-        anyhow::bail!("Failed to fetch file: {} (status: {})", path, response.status())
-    }
+    client.get_text(&url).await
 }
 
 fn infer_schema_from_examples(examples: Vec<String>) -> Result<SchemaDefinition> {
-    use indexmap::IndexMap;
     use crate::schema::types::{SchemaProperty, SchemaType};
 
     let mut all_properties: IndexMap<String, SchemaProperty> = IndexMap::new();
+    let mut observations = Observations::new();
 
     // Parse each example YAML and extract properties
     for example in examples {
         if let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(&example) {
-            if let serde_yaml::Value::Mapping(map) = yaml {
-                extract_properties(&map, &mut all_properties, "");
+            if let serde_yaml::Value::Mapping(map) = &yaml {
+                extract_properties(map, &mut all_properties, "");
+                observe(&yaml, "", &mut observations);
             }
         }
     }
 
+    // Values seen in real repositories become examples. They are never
+    // defaults: repositories agreeing on a value says nothing about what
+    // Fleet does without it, which only the Go source or the docs know
+    for (name, property) in all_properties.iter_mut() {
+        apply_observations(property, name, &observations);
+    }
+
     let schema = SchemaDefinition {
         schema: Some("https://json-schema.org/draft-07/schema#".to_string()),
         title: Some("Fleet Configuration (Inferred)".to_string()),
@@ -188,7 +237,6 @@ fn extract_properties(
 
 fn infer_property_from_value(value: &serde_yaml::Value) -> crate::schema::types::SchemaProperty {
     use crate::schema::types::{SchemaProperty, SchemaType};
-    use indexmap::IndexMap;
 
     match value {
         serde_yaml::Value::String(_) => SchemaProperty {
@@ -244,17 +292,108 @@ fn infer_property_from_value(value: &serde_yaml::Value) -> crate::schema::types:
     }
 }
 
+/// Record every scalar value in `value` under its field path.
+fn observe(value: &serde_yaml::Value, path: &str, observations: &mut Observations) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, value) in map {
+                if let serde_yaml::Value::String(key) = key {
+                    let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    observe(value, &path, observations);
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(seq) => {
+            let path = format!("{}[]", path);
+            for item in seq {
+                observe(item, &path, observations);
+            }
+        }
+        serde_yaml::Value::String(s) if s.contains('\n') || s.len() > 100 => {
+            // SQL, scripts and certificates make poor completion previews
+        }
+        serde_yaml::Value::String(_) | serde_yaml::Value::Bool(_) | serde_yaml::Value::Number(_) => {
+            if let Ok(json) = serde_json::to_value(value) {
+                observations.entry(path.to_string()).or_default().push(json);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Fill `examples` of the property at `path` and its children from
+/// observed values.
+fn apply_observations(property: &mut SchemaDefinition, path: &str, observations: &Observations) {
+    if let Some(values) = observations.get(path) {
+        let mut distinct: Vec<serde_json::Value> = Vec::new();
+        for value in values {
+            if !distinct.contains(value) {
+                distinct.push(value.clone());
+            }
+        }
+        distinct.truncate(MAX_EXAMPLES);
+        property.examples = Some(distinct);
+    }
+
+    if let Some(props) = &mut property.properties {
+        for (name, child) in props.iter_mut() {
+            apply_observations(child, &format!("{}.{}", path, name), observations);
+        }
+    }
+    if let Some(items) = &mut property.items {
+        apply_observations(items, &format!("{}[]", path), observations);
+    }
+}
+
 pub async fn list_releases() -> Result<Vec<GitHubRelease>> {
     let url = format!("https://api.github.com/repos/{}/releases", FLEET_REPO);
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "fleet-schema-gen")
-        .send()
-        .await?;
-
-    let releases: Vec<GitHubRelease> = response.json().await?;
+    let releases: Vec<GitHubRelease> = GitHubClient::new()?.get_json(&url).await?;
 
     Ok(releases)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_from_repos() {
+        let files = vec![
+            "controls:\n  macos_updates:\n    deadline: \"2025-01-31\"\n    minimum_version: \"15.1\"\n  enable_disk_encryption: true\nlabels_include_any:\n  - Macs\n".to_string(),
+            "controls:\n  macos_updates:\n    deadline: \"2025-02-28\"\n    minimum_version: \"15.2\"\n  enable_disk_encryption: true\n".to_string(),
+            "controls:\n  enable_disk_encryption: true\nagent_options:\n  path: ../lib/agent-options.yml\nqueries:\n  - query: |\n      SELECT 1;\n      SELECT 2;\n".to_string(),
+        ];
+        let schema = infer_schema_from_examples(files).unwrap();
+        let props = schema.properties.unwrap();
+
+        let controls = props["controls"].properties.as_ref().unwrap();
+        let updates = controls["macos_updates"].properties.as_ref().unwrap();
+        assert_eq!(
+            updates["deadline"].examples,
+            Some(vec![serde_json::json!("2025-01-31"), serde_json::json!("2025-02-28")])
+        );
+        assert_eq!(updates["deadline"].default, None);
+
+        // Even when every file agrees, the value is an example and not a default
+        assert_eq!(controls["enable_disk_encryption"].default, None);
+        assert_eq!(controls["enable_disk_encryption"].examples, Some(vec![serde_json::json!(true)]));
+
+        let agent_options = props["agent_options"].properties.as_ref().unwrap();
+        assert_eq!(agent_options["path"].examples, Some(vec![serde_json::json!("../lib/agent-options.yml")]));
+        assert_eq!(agent_options["path"].default, None);
+
+        // List items and multi-line values
+        assert_eq!(props["labels_include_any"].items.as_ref().unwrap().examples, Some(vec![serde_json::json!("Macs")]));
+        assert_eq!(props["queries"].items.as_ref().unwrap().properties.as_ref().unwrap()["query"].examples, None);
+    }
+
+    #[test]
+    fn test_examples_are_capped() {
+        let files = (1..=5).map(|i| format!("org_settings:\n  server_settings:\n    query_report_cap: {}\n", i)).collect();
+        let schema = infer_schema_from_examples(files).unwrap();
+        let settings = &schema.properties.unwrap()["org_settings"];
+        let cap = &settings.properties.as_ref().unwrap()["server_settings"].properties.as_ref().unwrap()["query_report_cap"];
+        assert_eq!(cap.examples.as_ref().unwrap().len(), MAX_EXAMPLES);
+    }
+}
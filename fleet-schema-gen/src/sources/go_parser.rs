@@ -19,19 +19,54 @@ pub struct GoStruct {
 /// Represents a field in a Go struct
 #[derive(Debug, Clone)]
 pub struct GoField {
+    /// Field name, or the type name for embedded fields
     pub name: String,
     pub go_type: String,
     pub json_tag: Option<String>,
     pub yaml_tag: Option<String>,
-    pub omitempty: bool,
     pub doc_comment: Option<String>,
+    /// Anonymous field (`BaseItem`, `*fleet.Features`)
+    pub embedded: bool,
+    /// Fields of an inline `struct { ... }` type
+    pub inline_fields: Option<Vec<GoField>>,
 }
 
+impl GoField {
+    /// Key in YAML: the `json` tag name (Fleet converts GitOps YAML to JSON
+    /// before unmarshaling), then the `yaml` tag, then the field name.
+    /// `None` for fields excluded with `json:"-"`.
+    pub fn property_name(&self) -> Option<String> {
+        let tag = [&self.json_tag, &self.yaml_tag]
+            .into_iter()
+            .flatten()
+            .find(|tag| !tag.is_empty());
+        match tag {
+            Some(tag) if tag == "-" => None,
+            Some(tag) => Some(tag.clone()),
+            None => Some(self.name.to_lowercase()),
+        }
+    }
+
+    /// Embedded fields without a tag name have their fields promoted.
+    fn is_promoted(&self) -> bool {
+        self.embedded && self.json_tag.as_deref().is_none_or(str::is_empty)
+    }
+}
+
+/// Sections typed `map[string]interface{}` in the GitOps spec that Fleet
+/// unmarshals into a known struct.
+const UNTYPED_SECTIONS: &[(&str, &str)] = &[("org_settings", "AppConfig")];
+
+/// Nesting limit for self-referencing types.
+const MAX_DEPTH: usize = 12;
+
 /// Main Go parser for Fleet source code
 pub struct FleetGoParser {
     parser: Parser,
     /// Maps struct names to their definitions
     pub struct_cache: HashMap<String, GoStruct>,
+    /// Named non-struct types and what they stand for (`type Mode string`)
+    pub type_aliases: HashMap<String, String>,
 }
 
 impl FleetGoParser {
@@ -45,16 +80,18 @@ impl FleetGoParser {
         Ok(Self {
             parser,
             struct_cache: HashMap::new(),
+            type_aliases: HashMap::new(),
         })
     }
 
     /// Parse Fleet repository and extract schema definitions
     pub fn parse_fleet_repo(&mut self, fleet_repo_path: &Path) -> Result<SchemaDefinition> {
-        println!("  → Parsing Fleet Go source code...");
+        tracing::info!("  → Parsing Fleet Go source code...");
 
         // Key files containing GitOps struct definitions
         let files_to_parse = vec![
             "pkg/spec/gitops.go",
+            "server/fleet/app.go",                 // AppConfig, MDM (org_settings)
             "server/fleet/teams.go",
             "server/fleet/policies.go",
             "server/fleet/queries.go",
@@ -71,11 +108,11 @@ impl FleetGoParser {
                 self.parse_file(&full_path)
                     .with_context(|| format!("Failed to parse {}", file_path))?;
             } else {
-                eprintln!("  ⚠ File not found: {}", file_path);
+                tracing::warn!("  File not found: {}", file_path);
             }
         }
 
-        println!("  → Parsed {} struct definitions", self.struct_cache.len());
+        tracing::info!("  → Parsed {} struct definitions", self.struct_cache.len());
 
         // Build schema from parsed structs
         self.build_team_schema()
@@ -84,15 +121,20 @@ impl FleetGoParser {
     /// Parse a single Go file
     pub fn parse_file(&mut self, file_path: &Path) -> Result<()> {
         let source = fs::read_to_string(file_path)?;
+        self.parse_source(&source)
+    }
+
+    /// Parse Go source text
+    pub fn parse_source(&mut self, source: &str) -> Result<()> {
         let tree = self
             .parser
-            .parse(&source, None)
+            .parse(source, None)
             .ok_or_else(|| anyhow!("Failed to parse Go file"))?;
 
         let root_node = tree.root_node();
 
         // Find all struct definitions
-        self.extract_structs(&root_node, &source)?;
+        self.extract_structs(&root_node, source)?;
 
         Ok(())
     }
@@ -104,9 +146,8 @@ impl FleetGoParser {
         // Look for type declarations
         for child in node.children(&mut cursor) {
             if child.kind() == "type_declaration" {
-                if let Some(go_struct) = self.parse_type_declaration(&child, source)? {
-                    self.struct_cache.insert(go_struct.name.clone(), go_struct);
-                }
+                self.parse_type_declaration(&child, source)?;
+                continue;
             }
 
             // Recursively process children
@@ -116,45 +157,42 @@ impl FleetGoParser {
         Ok(())
     }
 
-    /// Parse a type declaration node
-    fn parse_type_declaration(&self, node: &Node, source: &str) -> Result<Option<GoStruct>> {
+    /// Parse a type declaration node, which may declare several types
+    /// (`type ( A struct{...}; B string )`)
+    fn parse_type_declaration(&mut self, node: &Node, source: &str) -> Result<()> {
         let mut cursor = node.walk();
-        let children: Vec<_> = node.children(&mut cursor).collect();
-
-        // Find the type_spec node
-        let type_spec = children
-            .iter()
-            .find(|n| n.kind() == "type_spec")
-            .ok_or_else(|| anyhow!("No type_spec found"))?;
+        let specs: Vec<_> = node.children(&mut cursor).filter(|n| n.kind() == "type_spec").collect();
 
-        // Get struct name
-        let mut spec_cursor = type_spec.walk();
-        let spec_children: Vec<_> = type_spec.children(&mut spec_cursor).collect();
-
-        let name_node = spec_children
-            .iter()
-            .find(|n| n.kind() == "type_identifier")
-            .ok_or_else(|| anyhow!("No type_identifier found"))?;
-
-        let struct_name = name_node.utf8_text(source.as_bytes())?;
-
-        // Check if it's a struct type
-        let struct_type = spec_children
-            .iter()
-            .find(|n| n.kind() == "struct_type");
-
-        if let Some(struct_node) = struct_type {
-            let fields = self.parse_struct_fields(struct_node, source)?;
-            let doc_comment = self.extract_doc_comment(node, source);
-
-            return Ok(Some(GoStruct {
-                name: struct_name.to_string(),
-                fields,
-                doc_comment,
-            }));
+        for type_spec in specs {
+            let Some(name_node) = type_spec.child_by_field_name("name") else {
+                continue;
+            };
+            let Some(type_node) = type_spec.child_by_field_name("type") else {
+                continue;
+            };
+            let type_name = name_node.utf8_text(source.as_bytes())?.to_string();
+
+            if type_node.kind() == "struct_type" {
+                let fields = self.parse_struct_fields(&type_node, source)?;
+                // Single specs carry the comment on the declaration, grouped ones on the spec
+                let doc_comment = self
+                    .extract_doc_comment(&type_spec, source)
+                    .or_else(|| self.extract_doc_comment(node, source));
+
+                self.struct_cache.insert(
+                    type_name.clone(),
+                    GoStruct {
+                        name: type_name,
+                        fields,
+                        doc_comment,
+                    },
+                );
+            } else {
+                self.type_aliases.insert(type_name, type_node.utf8_text(source.as_bytes())?.to_string());
+            }
         }
 
-        Ok(None)
+        Ok(())
     }
 
     /// Parse struct fields
@@ -167,9 +205,7 @@ impl FleetGoParser {
                 let mut field_cursor = child.walk();
                 for field_decl in child.children(&mut field_cursor) {
                     if field_decl.kind() == "field_declaration" {
-                        if let Some(field) = self.parse_field(&field_decl, source)? {
-                            fields.push(field);
-                        }
+                        fields.extend(self.parse_field(&field_decl, source)?);
                     }
                 }
             }
@@ -178,94 +214,87 @@ impl FleetGoParser {
         Ok(fields)
     }
 
-    /// Parse a single field declaration
-    fn parse_field(&self, field_node: &Node, source: &str) -> Result<Option<GoField>> {
+    /// Parse a field declaration (`A, B string`, `BaseItem`, `X struct{...}`)
+    fn parse_field(&self, field_node: &Node, source: &str) -> Result<Vec<GoField>> {
         let mut cursor = field_node.walk();
         let children: Vec<_> = field_node.children(&mut cursor).collect();
 
-        // Get field name
-        let name_node = children.iter().find(|n| n.kind() == "field_identifier");
-        if name_node.is_none() {
-            // Anonymous field (embedded struct)
-            return Ok(None);
-        }
-        let field_name = name_node.unwrap().utf8_text(source.as_bytes())?;
-
-        // Get field type
-        let type_node = children
+        let names: Vec<&str> = children
             .iter()
-            .find(|n| {
-                matches!(
-                    n.kind(),
-                    "type_identifier"
-                        | "pointer_type"
-                        | "slice_type"
-                        | "map_type"
-                        | "qualified_type"
-                        | "interface_type"
-                        | "struct_type"
-                )
-            });
-
-        // If we can't find the type, skip this field
-        let type_node = match type_node {
-            Some(node) => node,
-            None => {
-                eprintln!("  ⚠ Could not parse type for field: {}", field_name);
-                return Ok(None);
+            .filter(|n| n.kind() == "field_identifier")
+            .map(|n| n.utf8_text(source.as_bytes()))
+            .collect::<std::result::Result<_, _>>()?;
+
+        let Some(type_node) = field_node.child_by_field_name("type") else {
+            tracing::warn!("  Could not parse type for field: {}", names.join(", "));
+            return Ok(Vec::new());
+        };
+        let go_type = type_node.utf8_text(source.as_bytes())?.to_string();
+
+        // Inline struct types, possibly behind a pointer
+        let mut struct_node = type_node;
+        while struct_node.kind() == "pointer_type" {
+            match struct_node.named_child(0) {
+                Some(inner) => struct_node = inner,
+                None => break,
             }
+        }
+        let inline_fields = if struct_node.kind() == "struct_type" {
+            Some(self.parse_struct_fields(&struct_node, source)?)
+        } else {
+            None
         };
 
-        let go_type = type_node.utf8_text(source.as_bytes())?;
-
         // Get struct tags
-        let tag_node = children.iter().find(|n| n.kind() == "raw_string_literal");
-        let (json_tag, yaml_tag, omitempty) = if let Some(tag) = tag_node {
-            let tag_str = tag.utf8_text(source.as_bytes())?;
-            self.parse_struct_tags(tag_str)?
-        } else {
-            (None, None, false)
+        let (json_tag, yaml_tag) = match field_node.child_by_field_name("tag") {
+            Some(tag) => self.parse_struct_tags(tag.utf8_text(source.as_bytes())?)?,
+            None => (None, None),
         };
 
         // Get doc comment
         let doc_comment = self.extract_doc_comment(field_node, source);
 
-        Ok(Some(GoField {
-            name: field_name.to_string(),
-            go_type: go_type.to_string(),
-            json_tag,
-            yaml_tag,
-            omitempty,
-            doc_comment,
-        }))
+        let field = |name: String, embedded: bool| GoField {
+            name,
+            go_type: go_type.clone(),
+            json_tag: json_tag.clone(),
+            yaml_tag: yaml_tag.clone(),
+            doc_comment: doc_comment.clone(),
+            embedded,
+            inline_fields: inline_fields.clone(),
+        };
+
+        if names.is_empty() {
+            // Embedded field: named after its type
+            return Ok(vec![field(base_type_name(&go_type).to_string(), true)]);
+        }
+        Ok(names.into_iter().map(|name| field(name.to_string(), false)).collect())
     }
 
-    /// Parse struct tags (e.g., json:"name,omitempty" yaml:"name")
-    fn parse_struct_tags(&self, tag_str: &str) -> Result<(Option<String>, Option<String>, bool)> {
-        let tag_re = Regex::new(r#"(?:json|yaml):"([^"]+)""#)?;
+    /// Parse the `json` and `yaml` names of struct tags (e.g.,
+    /// json:"name,omitempty" yaml:"other").
+    ///
+    /// Options are ignored: `omitempty` only affects marshaling, and Fleet
+    /// accepts every field as optional when reading GitOps files.
+    fn parse_struct_tags(&self, tag_str: &str) -> Result<(Option<String>, Option<String>)> {
+        let tag_re = Regex::new(r#"(\w+):"([^"]*)""#)?;
 
         let mut json_tag = None;
         let mut yaml_tag = None;
-        let mut omitempty = false;
 
         for cap in tag_re.captures_iter(tag_str) {
-            let tag_content = cap.get(1).map(|m| m.as_str()).unwrap_or("");
-            let parts: Vec<&str> = tag_content.split(',').collect();
-
-            if tag_str.contains("json:") && json_tag.is_none() {
-                json_tag = Some(parts[0].to_string());
-                if parts.contains(&"omitempty") {
-                    omitempty = true;
-                }
-            } else if tag_str.contains("yaml:") && yaml_tag.is_none() {
-                yaml_tag = Some(parts[0].to_string());
-                if parts.contains(&"omitempty") {
-                    omitempty = true;
-                }
+            let name = cap[2].split(',').next().unwrap_or("").to_string();
+            let slot = match &cap[1] {
+                "json" => &mut json_tag,
+                "yaml" => &mut yaml_tag,
+                _ => continue,
+            };
+            if slot.is_none() {
+                *slot = Some(name);
             }
         }
 
-        Ok((json_tag, yaml_tag, omitempty))
+        Ok((json_tag, yaml_tag))
     }
 
     /// Extract doc comment from preceding comment nodes
@@ -295,23 +324,15 @@ impl FleetGoParser {
             .get("GitOps")
             .ok_or_else(|| anyhow!("GitOps struct not found"))?;
 
-        let mut properties = IndexMap::new();
-
-        for field in &gitops_struct.fields {
-            // Use json_tag if available, otherwise use field name
-            let property_name = field
-                .json_tag
-                .as_ref()
-                .unwrap_or(&field.name)
-                .to_lowercase();
+        let mut properties = self.expand_struct_properties(gitops_struct, 0)?;
 
-            // Skip fields with "-" tag (means don't serialize)
-            if property_name == "-" {
-                continue;
+        // Untyped sections Fleet unmarshals into a known struct
+        for (section, type_name) in UNTYPED_SECTIONS {
+            if let (Some(prop), Some(typed)) = (properties.get_mut(*section), self.struct_schema(type_name)?) {
+                prop.type_ = typed.type_;
+                prop.properties = typed.properties;
+                prop.additional_properties = None;
             }
-
-            let schema_prop = self.convert_go_type_to_schema(&field.go_type, &field)?;
-            properties.insert(property_name, schema_prop);
         }
 
         Ok(SchemaDefinition {
@@ -325,41 +346,83 @@ impl FleetGoParser {
         })
     }
 
-    /// Convert Go type to JSON Schema property
-    fn convert_go_type_to_schema(&self, go_type: &str, field: &GoField) -> Result<SchemaProperty> {
-        let mut prop = SchemaProperty {
-            description: field.doc_comment.clone(),
-            ..Default::default()
+    /// JSON Schema for the struct named `name`, if it was parsed
+    pub fn struct_schema(&self, name: &str) -> Result<Option<SchemaProperty>> {
+        let Some(go_struct) = self.struct_cache.get(name) else {
+            return Ok(None);
         };
+        Ok(Some(SchemaProperty {
+            type_: Some(SchemaType::Single("object".to_string())),
+            description: go_struct.doc_comment.clone(),
+            properties: Some(self.expand_struct_properties(go_struct, 0)?),
+            ..Default::default()
+        }))
+    }
 
-        // Handle pointer types
-        let clean_type = go_type.trim_start_matches('*');
+    /// Convert Go type to JSON Schema property
+    fn convert_go_type_to_schema(&self, go_type: &str, field: &GoField, depth: usize) -> Result<SchemaProperty> {
+        let mut prop = self.type_schema(go_type, field.inline_fields.as_deref(), depth)?;
+        prop.description = field.doc_comment.clone();
+        Ok(prop)
+    }
+
+    /// JSON Schema for a Go type expression
+    fn type_schema(&self, go_type: &str, inline_fields: Option<&[GoField]>, depth: usize) -> Result<SchemaProperty> {
+        let mut prop = SchemaProperty::default();
+        let go_type = go_type.trim();
+
+        // Pointers are optional: `null` is accepted as well
+        if let Some(pointee) = go_type.strip_prefix('*') {
+            let mut prop = self.type_schema(pointee, inline_fields, depth)?;
+            if let Some(SchemaType::Single(t)) = &prop.type_ {
+                prop.type_ = Some(SchemaType::Multiple(vec![t.clone(), "null".to_string()]));
+            }
+            return Ok(prop);
+        }
+
+        // optjson wrappers distinguish "unset" from the zero value
+        let (base, type_args) = split_generic(go_type);
+        let go_type = match (base_type_name(base), type_args) {
+            ("String", None) if base.starts_with("optjson.") => "string",
+            ("Bool", None) if base.starts_with("optjson.") => "bool",
+            ("Int", None) if base.starts_with("optjson.") => "int",
+            ("Slice", Some(item)) if base.starts_with("optjson.") => return self.array_schema(item, depth),
+            ("Any", Some(item)) if base.starts_with("optjson.") => item,
+            _ => go_type,
+        };
 
         // Map Go types to JSON Schema types
-        match clean_type {
+        match go_type {
             "string" => {
                 prop.type_ = Some(SchemaType::Single("string".to_string()));
             }
             "bool" => {
                 prop.type_ = Some(SchemaType::Single("boolean".to_string()));
             }
-            "int" | "int32" | "int64" | "uint" | "uint32" | "uint64" => {
+            "int" | "int8" | "int16" | "int32" | "int64" | "uint" | "uint8" | "uint16" | "uint32" | "uint64" => {
                 prop.type_ = Some(SchemaType::Single("integer".to_string()));
             }
             "float32" | "float64" => {
                 prop.type_ = Some(SchemaType::Single("number".to_string()));
             }
             t if t.starts_with("[]") => {
-                // Array type
-                prop.type_ = Some(SchemaType::Single("array".to_string()));
-                // TODO: Parse array item type and set items
+                return self.array_schema(&t[2..], depth);
             }
             t if t.starts_with("map[") => {
-                // Map type - represents as object
+                // Map type - represents as object with typed values
                 prop.type_ = Some(SchemaType::Single("object".to_string()));
-                prop.additional_properties = Some(AdditionalProperties::Boolean(true));
+                let value_type = map_value_type(t);
+                let values = match value_type {
+                    Some(v) if depth < MAX_DEPTH => self.type_schema(v, None, depth + 1)?,
+                    _ => SchemaProperty::default(),
+                };
+                prop.additional_properties = Some(if values.type_.is_none() && values.properties.is_none() {
+                    AdditionalProperties::Boolean(true)
+                } else {
+                    AdditionalProperties::Schema(Box::new(values))
+                });
             }
-            "interface{}" | "interface {}" => {
+            "interface{}" | "interface {}" | "any" => {
                 // Any type - don't constrain
                 prop.type_ = None;
             }
@@ -367,16 +430,29 @@ impl FleetGoParser {
                 // Raw JSON - could be anything
                 prop.type_ = None;
             }
-            _ => {
-                // Custom struct type - make it an object
+            t if t.starts_with("struct") => {
                 prop.type_ = Some(SchemaType::Single("object".to_string()));
-
-                // If we have this struct in cache, expand its properties
-                if let Some(nested_struct) = self.struct_cache.get(clean_type) {
-                    let nested_props = self.expand_struct_properties(nested_struct)?;
-                    if !nested_props.is_empty() {
-                        prop.properties = Some(nested_props);
+                if let Some(fields) = inline_fields {
+                    prop.properties = Some(self.fields_to_properties(fields, depth + 1)?);
+                }
+            }
+            t => {
+                let name = base_type_name(t);
+                if let Some(nested_struct) = self.struct_cache.get(name) {
+                    // Custom struct type - expand its properties
+                    prop.type_ = Some(SchemaType::Single("object".to_string()));
+                    if depth < MAX_DEPTH {
+                        let nested_props = self.expand_struct_properties(nested_struct, depth + 1)?;
+                        if !nested_props.is_empty() {
+                            prop.properties = Some(nested_props);
+                        }
                     }
+                } else if let Some(underlying) = self.type_aliases.get(name).filter(|_| depth < MAX_DEPTH) {
+                    // Named type (`type MacOSMigrationMode string`)
+                    return self.type_schema(underlying, None, depth + 1);
+                } else {
+                    // Unknown type (not parsed) - assume an object
+                    prop.type_ = Some(SchemaType::Single("object".to_string()));
                 }
             }
         }
@@ -384,22 +460,44 @@ impl FleetGoParser {
         Ok(prop)
     }
 
+    fn array_schema(&self, item_type: &str, depth: usize) -> Result<SchemaProperty> {
+        let items = if depth < MAX_DEPTH {
+            self.type_schema(item_type, None, depth + 1)?
+        } else {
+            SchemaProperty::default()
+        };
+        Ok(SchemaProperty {
+            type_: Some(SchemaType::Single("array".to_string())),
+            items: (items.type_.is_some() || items.properties.is_some()).then(|| Box::new(items)),
+            ..Default::default()
+        })
+    }
+
     /// Expand struct properties recursively
-    fn expand_struct_properties(&self, go_struct: &GoStruct) -> Result<IndexMap<String, SchemaProperty>> {
-        let mut properties = IndexMap::new();
+    fn expand_struct_properties(&self, go_struct: &GoStruct, depth: usize) -> Result<IndexMap<String, SchemaProperty>> {
+        self.fields_to_properties(&go_struct.fields, depth)
+    }
 
-        for field in &go_struct.fields {
-            let property_name = field
-                .json_tag
-                .as_ref()
-                .unwrap_or(&field.name)
-                .to_lowercase();
+    fn fields_to_properties(&self, fields: &[GoField], depth: usize) -> Result<IndexMap<String, SchemaProperty>> {
+        let mut properties = IndexMap::new();
 
-            if property_name == "-" {
+        for field in fields {
+            // Embedded structs without a tag name contribute their fields
+            if field.is_promoted() {
+                if let Some(embedded) = self.struct_cache.get(&field.name).filter(|_| depth < MAX_DEPTH) {
+                    for (name, prop) in self.expand_struct_properties(embedded, depth + 1)? {
+                        // Fields declared on the outer struct win
+                        properties.entry(name).or_insert(prop);
+                    }
+                }
                 continue;
             }
 
-            let schema_prop = self.convert_go_type_to_schema(&field.go_type, field)?;
+            let Some(property_name) = field.property_name() else {
+                continue;
+            };
+
+            let schema_prop = self.convert_go_type_to_schema(&field.go_type, field, depth)?;
             properties.insert(property_name, schema_prop);
         }
 
@@ -407,11 +505,44 @@ impl FleetGoParser {
     }
 }
 
+/// `Foo` for `Foo`, `*Foo`, `fleet.Foo` and `optjson.Slice[Foo]`.
+fn base_type_name(go_type: &str) -> &str {
+    let go_type = split_generic(go_type.trim_start_matches('*')).0;
+    go_type.rsplit('.').next().unwrap_or(go_type)
+}
+
+/// `("optjson.Slice", Some("Foo"))` for `optjson.Slice[Foo]`.
+fn split_generic(go_type: &str) -> (&str, Option<&str>) {
+    match (go_type.find('['), go_type.strip_suffix(']')) {
+        (Some(open), Some(inner)) if open > 0 => (&go_type[..open], Some(inner[open + 1..].trim())),
+        _ => (go_type, None),
+    }
+}
+
+/// `V` in `map[K]V`, respecting nested brackets in `K`.
+fn map_value_type(go_type: &str) -> Option<&str> {
+    let rest = go_type.strip_prefix("map[")?;
+    let mut depth = 1;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(rest[i + 1..].trim());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Fetch Fleet repository from GitHub and parse schemas
 pub async fn fetch_from_fleet_repo(version: Option<&str>) -> Result<SchemaDefinition> {
     use crate::sources::fleet_repo::FleetRepo;
 
-    println!("  → Preparing Fleet repository...");
+    tracing::info!("  → Preparing Fleet repository...");
 
     // Use FleetRepo to manage cloning/updating
     let fleet_repo = FleetRepo::new();
@@ -421,8 +552,143 @@ pub async fn fetch_from_fleet_repo(version: Option<&str>) -> Result<SchemaDefini
     let actual_version = fleet_repo.get_current_tag()?
         .unwrap_or_else(|| fleet_repo.get_current_version().unwrap_or_else(|_| "unknown".to_string()));
 
-    println!("  → Using Fleet version: {}", actual_version);
+    tracing::info!("  → Using Fleet version: {}", actual_version);
 
     let mut parser = FleetGoParser::new()?;
     parser.parse_fleet_repo(fleet_repo.path())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_parser() -> FleetGoParser {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/go");
+        let mut parser = FleetGoParser::new().unwrap();
+        parser.parse_file(&dir.join("pkg_spec_gitops.go")).unwrap();
+        parser.parse_file(&dir.join("server_fleet_app.go")).unwrap();
+        parser
+    }
+
+    fn props(schema: &SchemaProperty) -> &IndexMap<String, SchemaProperty> {
+        schema.properties.as_ref().expect("object with properties")
+    }
+
+    fn single(type_: &str) -> Option<SchemaType> {
+        Some(SchemaType::Single(type_.to_string()))
+    }
+
+    #[test]
+    fn test_struct_tags() {
+        let parser = FleetGoParser::new().unwrap();
+        let tags = |s| parser.parse_struct_tags(s).unwrap();
+
+        assert_eq!(tags(r#"`json:"name,omitempty"`"#), (Some("name".to_string()), None));
+        assert_eq!(
+            tags(r#"`json:"self_service,omitempty" yaml:"self_service"`"#),
+            (Some("self_service".to_string()), Some("self_service".to_string()))
+        );
+        // Options on either tag are dropped, other keys ignored
+        assert_eq!(
+            tags(r#"`db:"id" yaml:"team_id,omitempty" json:"team_id"`"#),
+            (Some("team_id".to_string()), Some("team_id".to_string()))
+        );
+        assert_eq!(tags(r#"`json:"-"`"#), (Some("-".to_string()), None));
+    }
+
+    #[test]
+    fn test_nested_org_settings() {
+        let parser = fixture_parser();
+        let schema = parser.build_team_schema().unwrap();
+        let root = schema.properties.as_ref().unwrap();
+
+        // json:"-" is skipped, pointers accept null
+        assert!(!root.contains_key("teamname") && !root.contains_key("-"));
+        assert_eq!(
+            root["team_id"].type_,
+            Some(SchemaType::Multiple(vec!["integer".to_string(), "null".to_string()]))
+        );
+
+        // org_settings is untyped in the spec but resolved through AppConfig
+        let mdm = &props(&root["org_settings"])["mdm"];
+        let macos_settings = &props(mdm)["macos_settings"];
+        assert_eq!(macos_settings.type_, single("object"));
+        assert_eq!(macos_settings.description, None);
+
+        let custom_settings = &props(macos_settings)["custom_settings"];
+        assert_eq!(custom_settings.type_, single("array"));
+        let profile = custom_settings.items.as_ref().unwrap();
+        assert_eq!(
+            props(profile).keys().collect::<Vec<_>>(),
+            vec!["path", "labels", "labels_include_all", "labels_exclude_any"]
+        );
+        assert_eq!(props(profile)["labels"].items.as_ref().unwrap().type_, single("string"));
+        assert_eq!(props(macos_settings)["enable_disk_encryption"].description.as_deref(), Some("Deprecated: use mdm.enable_disk_encryption."));
+
+        // optjson wrappers map to their value type
+        assert_eq!(props(mdm)["enable_disk_encryption"].type_, single("boolean"));
+        assert_eq!(props(&props(mdm)["macos_updates"])["deadline"].type_, single("string"));
+        assert_eq!(props(&props(mdm)["windows_updates"])["deadline_days"].type_, single("integer"));
+        let windows_profiles = &props(&props(mdm)["windows_settings"])["custom_settings"];
+        assert!(props(windows_profiles.items.as_ref().unwrap()).contains_key("labels_include_all"));
+        let setup_software = &props(&props(mdm)["macos_setup"])["software"];
+        assert!(props(setup_software.items.as_ref().unwrap()).contains_key("app_store_id"));
+
+        // Named string types resolve to their underlying type
+        assert_eq!(props(&props(mdm)["macos_migration"])["mode"].type_, single("string"));
+    }
+
+    #[test]
+    fn test_embedded_structs() {
+        let parser = fixture_parser();
+        let app_config = parser.struct_schema("AppConfig").unwrap().unwrap();
+
+        // Promoted fields, with the outer struct's field taking precedence
+        let sso = props(&props(&app_config)["sso_settings"]).clone();
+        assert_eq!(
+            sso.keys().collect::<Vec<_>>(),
+            vec!["entity_id", "issuer_uri", "idp_name", "enable_sso", "enable_jit_provisioning"]
+        );
+        assert_eq!(sso["enable_sso"].type_, single("boolean"));
+
+        // Inline anonymous struct with an embedded struct
+        let end_user_auth = &props(&props(&app_config)["mdm"])["end_user_authentication"];
+        assert_eq!(
+            props(end_user_auth).keys().collect::<Vec<_>>(),
+            vec!["entity_id", "issuer_uri", "idp_name", "enable_sso", "enabled"]
+        );
+
+        // Embedded in the GitOps spec: controls gets BaseItem's path
+        let schema = parser.build_team_schema().unwrap();
+        let root = schema.properties.as_ref().unwrap();
+        let controls = props(&root["controls"]);
+        assert!(controls.contains_key("path") && controls.contains_key("macos_updates"));
+        assert!(!controls.contains_key("defined"));
+        assert_eq!(props(controls["scripts"].items.as_ref().unwrap()).keys().collect::<Vec<_>>(), vec!["path", "paths"]);
+
+        // Grouped type declarations; yaml tag agrees with json
+        let packages = &props(&root["software"])["packages"];
+        assert!(props(packages.items.as_ref().unwrap()).contains_key("self_service"));
+        assert_eq!(root["software"].description.as_deref(), Some("Software packages and app store apps"));
+    }
+
+    #[test]
+    fn test_map_values() {
+        let parser = fixture_parser();
+        let features = parser.struct_schema("Features").unwrap().unwrap();
+        let overrides = &props(&features)["detail_query_overrides"];
+        assert!(matches!(
+            &overrides.additional_properties,
+            Some(AdditionalProperties::Schema(values))
+                if values.type_ == Some(SchemaType::Multiple(vec!["string".to_string(), "null".to_string()]))
+        ));
+
+        let calendar = parser.struct_schema("GoogleCalendarIntegration").unwrap().unwrap();
+        assert!(matches!(&props(&calendar)["api_key_json"].additional_properties, Some(AdditionalProperties::Schema(_))));
+
+        assert_eq!(map_value_type("map[string]interface{}"), Some("interface{}"));
+        assert_eq!(map_value_type("map[[2]string][]int"), Some("[]int"));
+        assert_eq!(split_generic("optjson.Slice[*MacOSSetupSoftware]"), ("optjson.Slice", Some("*MacOSSetupSoftware")));
+        assert_eq!(base_type_name("*fleet.MacOSSetup"), "MacOSSetup");
+    }
+}
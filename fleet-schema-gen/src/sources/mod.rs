@@ -1,5 +1,7 @@
 pub mod docs_scraper;
 pub mod github;
+pub mod github_api;
 pub mod yaml_defs;
 pub mod go_parser;
 pub mod fleet_repo;
+pub mod changelog;
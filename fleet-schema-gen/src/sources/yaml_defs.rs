@@ -5,16 +5,21 @@ use indexmap::IndexMap;
 use crate::schema::types::{YamlEnhancement, FieldEnhancement};
 
 pub fn load_enhancements(schema_defs_path: &Path) -> Result<IndexMap<String, YamlEnhancement>> {
-    let mut enhancements = IndexMap::new();
-
     // Look for enhancement files in the schema-defs directory
     if !schema_defs_path.exists() {
-        println!("  ⚠ Schema definitions directory not found: {}", schema_defs_path.display());
-        println!("    Creating directory with default enhancements...");
+        tracing::warn!("  Schema definitions directory not found: {}", schema_defs_path.display());
+        tracing::info!("    Creating directory with default enhancements...");
         fs::create_dir_all(schema_defs_path)?;
         create_default_enhancements(schema_defs_path)?;
     }
 
+    read_enhancements(schema_defs_path)
+}
+
+/// Enhancements in an existing schema-defs directory, keyed by file stem.
+pub fn read_enhancements(schema_defs_path: &Path) -> Result<IndexMap<String, YamlEnhancement>> {
+    let mut enhancements = IndexMap::new();
+
     // Load all .yml files from the directory
     for entry in fs::read_dir(schema_defs_path)? {
         let entry = entry?;
@@ -27,7 +32,7 @@ pub fn load_enhancements(schema_defs_path: &Path) -> Result<IndexMap<String, Yam
                 .and_then(|s| s.to_str())
                 .unwrap_or("unknown");
 
-            println!("  → Loading: {}", path.display());
+            tracing::info!("  → Loading: {}", path.display());
 
             let content = fs::read_to_string(&path)?;
             let enhancement: YamlEnhancement = serde_yaml::from_str(&content)?;
@@ -44,6 +49,8 @@ fn create_default_enhancements(schema_defs_path: &Path) -> Result<()> {
 
     // policies.yml
     let policies_content = r#"
+docs: '#policies'
+
 fields:
   name:
     description: "Human-readable policy name shown in Fleet UI"
@@ -100,6 +107,8 @@ fields:
 
     // queries.yml
     let queries_content = r#"
+docs: '#queries'
+
 fields:
   name:
     description: "Unique query name"
@@ -158,6 +167,8 @@ fields:
 
     // labels.yml
     let labels_content = r#"
+docs: '#labels'
+
 fields:
   name:
     description: "Label name"
@@ -198,11 +209,23 @@ last_updated: "2024-01-01"
 
     fs::write(schema_defs_path.join("version.yml"), version_content)?;
 
-    println!("  ✓ Created default enhancement files");
+    tracing::debug!("  ✓ Created default enhancement files");
 
     Ok(())
 }
 
+/// Fleet's YAML reference, which `docs` anchors in schema defs point into.
+pub const FLEET_YAML_DOCS: &str = "https://fleetdm.com/docs/configuration/yaml-files";
+
+/// Full URL for a `docs` entry: an anchor on the YAML reference or a URL.
+pub fn docs_url(docs: &str) -> String {
+    if docs.starts_with('#') {
+        format!("{}{}", FLEET_YAML_DOCS, docs)
+    } else {
+        docs.to_string()
+    }
+}
+
 pub fn merge_field_enhancement(
     field: &mut crate::schema::types::SchemaProperty,
     enhancement: &FieldEnhancement,
@@ -230,4 +253,8 @@ pub fn merge_field_enhancement(
     if let Some(snippets) = &enhancement.default_snippets {
         field.default_snippets = Some(snippets.clone());
     }
+
+    if let Some(docs) = &enhancement.docs {
+        field.docs_url = Some(docs_url(docs));
+    }
 }
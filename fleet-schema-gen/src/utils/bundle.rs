@@ -0,0 +1,389 @@
+//! `fleet-schema-gen schema bundle`: move schema data into isolated networks.
+//!
+//! A bundle is a tar archive (plain, gzip or zstd, by file extension) with
+//! the cached GitHub responses `generate` fetches its sources from, the
+//! generated schemas of the versions directory, and a `manifest.json` with
+//! the SHA-256 of every file. `import` checks the manifest before writing
+//! anything, so a truncated or corrupted bundle is rejected as a whole. The
+//! manifest isn't signed and doesn't protect against deliberate changes.
+//!
+//! Only the GitHub API cache is bundled: offline, `generate --source
+//! examples` works from it, but the Fleet repository clone and docs pages
+//! that `--source hybrid` also reads are not included.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::verify::sha256_hex;
+use crate::linter::walk::tree_files;
+
+/// Bundle format version, bumped on incompatible layout changes.
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Prefix of cached GitHub responses in the archive.
+const CACHE_PREFIX: &str = "github-cache";
+
+/// Prefix of generated schemas in the archive.
+const SCHEMAS_PREFIX: &str = "schemas";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    pub created_at: String,
+    /// fleet-schema-gen version that wrote the bundle
+    pub tool_version: String,
+    /// Fleet versions with generated schemas in the bundle
+    pub fleet_versions: Vec<String>,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path inside the archive
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Archive compression, from the bundle's file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    /// Through the `zstd` CLI
+    Zstd,
+}
+
+impl Compression {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Ok(Compression::Zstd)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(Compression::Gzip)
+        } else if name.ends_with(".tar") {
+            Ok(Compression::None)
+        } else {
+            anyhow::bail!("Unknown bundle format: {} (expected .tar.zst, .tar.gz or .tar)", path.display())
+        }
+    }
+}
+
+/// Files under `dir` (recursively) as archive paths under `prefix`.
+fn collect_files(dir: &Path, prefix: &str, files: &mut BTreeMap<String, Vec<u8>>) -> Result<()> {
+    for path in tree_files(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        if let Ok(relative) = path.strip_prefix(dir) {
+            let name = format!("{}/{}", prefix, relative.to_string_lossy().replace('\\', "/"));
+            files.insert(name, fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?);
+        }
+    }
+    Ok(())
+}
+
+/// Build the manifest for archive `files`.
+pub fn manifest_for(files: &BTreeMap<String, Vec<u8>>) -> Manifest {
+    let fleet_versions = files
+        .keys()
+        .filter_map(|path| path.strip_prefix(&format!("{}/", SCHEMAS_PREFIX))?.split('/').next())
+        .filter(|version| version.starts_with(|c: char| c.is_ascii_digit() || c == 'v'))
+        .map(str::to_string)
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    Manifest {
+        format: FORMAT_VERSION,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        fleet_versions,
+        files: files
+            .iter()
+            .map(|(path, content)| ManifestEntry {
+                path: path.clone(),
+                size: content.len() as u64,
+                sha256: sha256_hex(content),
+            })
+            .collect(),
+    }
+}
+
+/// Check archive `files` against `manifest`: same set of files, sizes and hashes.
+pub fn verify_manifest(manifest: &Manifest, files: &BTreeMap<String, Vec<u8>>) -> Result<()> {
+    if manifest.format != FORMAT_VERSION {
+        anyhow::bail!(
+            "Bundle format {} is not supported by this version (expected {})",
+            manifest.format,
+            FORMAT_VERSION
+        );
+    }
+
+    for entry in &manifest.files {
+        let content = files
+            .get(&entry.path)
+            .with_context(|| format!("Bundle is missing {}", entry.path))?;
+        if content.len() as u64 != entry.size || sha256_hex(content) != entry.sha256 {
+            anyhow::bail!("Bundle file {} does not match its manifest entry", entry.path);
+        }
+    }
+    if let Some(extra) = files.keys().find(|path| !manifest.files.iter().any(|e| &e.path == *path)) {
+        anyhow::bail!("Bundle file {} is not listed in its manifest", extra);
+    }
+    Ok(())
+}
+
+/// Where an archive path is written on import, or `None` for paths outside
+/// the bundle layout (and anything trying to escape it with `..`).
+fn destination(path: &str, cache_dir: &Path, versions_dir: &Path) -> Option<PathBuf> {
+    let (prefix, rest) = path.split_once('/')?;
+    let relative = Path::new(rest);
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    match prefix {
+        CACHE_PREFIX => Some(cache_dir.join(relative)),
+        SCHEMAS_PREFIX => Some(versions_dir.join(relative)),
+        _ => None,
+    }
+}
+
+fn write_archive<W: Write>(writer: W, files: &BTreeMap<String, Vec<u8>>, manifest: &Manifest) -> Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    let mut append = |path: &str, content: &[u8]| -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content)?;
+        Ok(())
+    };
+
+    append(MANIFEST_FILE, &serde_json::to_vec_pretty(manifest)?)?;
+    for (path, content) in files {
+        append(path, content)?;
+    }
+    Ok(builder.into_inner()?)
+}
+
+fn read_archive<R: Read>(reader: R) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut files = BTreeMap::new();
+    for entry in archive.entries().context("Invalid bundle archive")? {
+        let mut entry = entry.context("Invalid bundle archive")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.to_string_lossy().replace('\\', "/");
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        files.insert(path, content);
+    }
+    Ok(files)
+}
+
+/// Run `zstd` with `args`, feeding it `input`.
+fn zstd(args: &[&str], input: &[u8]) -> Result<Vec<u8>> {
+    let mut child = Command::new("zstd")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run zstd (install it, or use a .tar.gz bundle)")?;
+
+    let mut stdin = child.stdin.take().context("zstd has no stdin")?;
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output()?;
+    writer.join().map_err(|_| anyhow::anyhow!("Failed to write to zstd"))??;
+
+    if !output.status.success() {
+        anyhow::bail!("zstd failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(output.stdout)
+}
+
+/// Encode archive `files` with `manifest` in `compression`.
+pub fn encode(files: &BTreeMap<String, Vec<u8>>, manifest: &Manifest, compression: Compression) -> Result<Vec<u8>> {
+    match compression {
+        Compression::None => write_archive(Vec::new(), files, manifest),
+        Compression::Gzip => {
+            let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            Ok(write_archive(encoder, files, manifest)?.finish()?)
+        }
+        Compression::Zstd => zstd(&["-q", "-19", "-c"], &write_archive(Vec::new(), files, manifest)?),
+    }
+}
+
+/// Decode a bundle into its manifest and files.
+pub fn decode(bytes: &[u8], compression: Compression) -> Result<(Manifest, BTreeMap<String, Vec<u8>>)> {
+    let mut files = match compression {
+        Compression::None => read_archive(bytes)?,
+        Compression::Gzip => read_archive(flate2::read::GzDecoder::new(bytes))?,
+        Compression::Zstd => read_archive(zstd(&["-q", "-d", "-c"], bytes)?.as_slice())?,
+    };
+    let manifest = files.remove(MANIFEST_FILE).context("Bundle has no manifest.json")?;
+    let manifest: Manifest = serde_json::from_slice(&manifest).context("Invalid manifest.json")?;
+    Ok((manifest, files))
+}
+
+/// Write a bundle of the GitHub cache and the generated schemas in `versions_dir`.
+pub fn export(file: &Path, versions_dir: &Path) -> Result<()> {
+    let compression = Compression::from_path(file)?;
+
+    let mut files = BTreeMap::new();
+    if let Some(cache_dir) = crate::sources::github_api::default_cache_dir() {
+        collect_files(&cache_dir, CACHE_PREFIX, &mut files)?;
+    }
+    let cached = files.len();
+    collect_files(versions_dir, SCHEMAS_PREFIX, &mut files)?;
+    if files.is_empty() {
+        anyhow::bail!(
+            "Nothing to bundle: no cached GitHub responses and no schemas in {} (run `generate` first)",
+            versions_dir.display()
+        );
+    }
+
+    let manifest = manifest_for(&files);
+    fs::write(file, encode(&files, &manifest, compression)?)
+        .with_context(|| format!("Failed to write {}", file.display()))?;
+
+    println!(
+        "{} Wrote {} ({} cached responses, {} schema files{})",
+        "✓".green(),
+        file.display(),
+        cached,
+        files.len() - cached,
+        if manifest.fleet_versions.is_empty() {
+            String::new()
+        } else {
+            format!(" for Fleet {}", manifest.fleet_versions.join(", "))
+        }
+    );
+    Ok(())
+}
+
+/// Verify a bundle and, unless `check_only`, unpack it into the GitHub
+/// cache and `versions_dir`.
+pub fn import(file: &Path, versions_dir: &Path, check_only: bool) -> Result<()> {
+    let compression = Compression::from_path(file)?;
+    let bytes = fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let (manifest, files) = decode(&bytes, compression)?;
+    verify_manifest(&manifest, &files)?;
+    println!(
+        "{} {} verified: {} files, written by fleet-schema-gen {} at {}",
+        "✓".green(),
+        file.display(),
+        manifest.files.len(),
+        manifest.tool_version,
+        manifest.created_at
+    );
+    if check_only {
+        return Ok(());
+    }
+
+    let cache_dir = crate::sources::github_api::default_cache_dir()
+        .context("No cache directory (set XDG_CACHE_HOME or HOME)")?;
+    let targets: Vec<(PathBuf, &Vec<u8>)> = files
+        .iter()
+        .map(|(path, content)| {
+            destination(path, &cache_dir, versions_dir)
+                .map(|target| (target, content))
+                .with_context(|| format!("Unexpected path in bundle: {}", path))
+        })
+        .collect::<Result<_>>()?;
+
+    for (target, content) in &targets {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, content).with_context(|| format!("Failed to write {}", target.display()))?;
+    }
+
+    println!(
+        "{} Imported {} files into {} and {}",
+        "✓".green(),
+        targets.len(),
+        cache_dir.display(),
+        versions_dir.display()
+    );
+    if !manifest.fleet_versions.is_empty() {
+        println!("  Schemas for Fleet {}", manifest.fleet_versions.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_files() -> BTreeMap<String, Vec<u8>> {
+        BTreeMap::from([
+            ("github-cache/0123abcd.json".to_string(), b"{\"etag\":\"x\",\"body\":\"y\"}".to_vec()),
+            ("schemas/4.62.0/fleet-schema.json".to_string(), b"{}".to_vec()),
+            ("schemas/4.62.0/lsp/fleet-schema.json".to_string(), b"{}".to_vec()),
+        ])
+    }
+
+    #[test]
+    fn test_compression_from_path() {
+        assert_eq!(Compression::from_path(Path::new("b.tar.zst")).unwrap(), Compression::Zstd);
+        assert_eq!(Compression::from_path(Path::new("b.tgz")).unwrap(), Compression::Gzip);
+        assert_eq!(Compression::from_path(Path::new("b.tar")).unwrap(), Compression::None);
+        assert!(Compression::from_path(Path::new("b.zip")).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_and_manifest() {
+        let files = sample_files();
+        let manifest = manifest_for(&files);
+        assert_eq!(manifest.fleet_versions, vec!["4.62.0"]);
+
+        for compression in [Compression::None, Compression::Gzip] {
+            let bytes = encode(&files, &manifest, compression).unwrap();
+            let (decoded_manifest, decoded) = decode(&bytes, compression).unwrap();
+            assert_eq!(decoded_manifest, manifest);
+            assert_eq!(decoded, files);
+            verify_manifest(&decoded_manifest, &decoded).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_verify_manifest_rejects_changes() {
+        let files = sample_files();
+        let manifest = manifest_for(&files);
+
+        let mut altered = files.clone();
+        altered.insert("schemas/4.62.0/fleet-schema.json".to_string(), b"{\"x\":1}".to_vec());
+        assert!(verify_manifest(&manifest, &altered).unwrap_err().to_string().contains("does not match"));
+
+        let mut missing = files.clone();
+        missing.remove("github-cache/0123abcd.json");
+        assert!(verify_manifest(&manifest, &missing).unwrap_err().to_string().contains("missing"));
+
+        let mut extra = files.clone();
+        extra.insert("schemas/evil.json".to_string(), Vec::new());
+        assert!(verify_manifest(&manifest, &extra).unwrap_err().to_string().contains("not listed"));
+    }
+
+    #[test]
+    fn test_destination() {
+        let cache = Path::new("/cache");
+        let versions = Path::new("/repo/schemas");
+        assert_eq!(
+            destination("schemas/4.62.0/lsp/fleet-schema.json", cache, versions),
+            Some(PathBuf::from("/repo/schemas/4.62.0/lsp/fleet-schema.json"))
+        );
+        assert_eq!(destination("github-cache/ab.json", cache, versions), Some(PathBuf::from("/cache/ab.json")));
+        assert_eq!(destination("schemas/../../etc/passwd", cache, versions), None);
+        assert_eq!(destination("other/file", cache, versions), None);
+    }
+}
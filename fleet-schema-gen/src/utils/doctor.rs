@@ -0,0 +1,360 @@
+//! `fleet-schema-gen doctor`: diagnose the local environment.
+//!
+//! Each check reports a status and, when something is off, the command or
+//! setting that fixes it, so bug reports can start from the doctor output.
+
+use crate::linter::config::{FleetLintConfig, CONFIG_FILE_NAME};
+use crate::linter::engine::find_yaml_files;
+use crate::linter::walk::WalkOptions;
+use crate::linter::{Linter, RuleSet};
+use crate::lsp::diagnostics::file_error_diagnostics;
+use crate::lsp::store::DocumentStore;
+use colored::Colorize;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// A single diagnostic.
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// Suggested fix when the status isn't `ok`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Ok, detail: detail.into(), fix: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Warn, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Fail, detail: detail.into(), fix: Some(fix.into()) }
+    }
+}
+
+/// Sources fetched by `generate` and `self-update`.
+const NETWORK_SOURCES: &[(&str, &str)] = &[
+    ("GitHub API", "https://api.github.com"),
+    ("GitHub raw content", "https://raw.githubusercontent.com"),
+    ("Fleet docs", "https://fleetdm.com/docs/configuration/yaml-files"),
+];
+
+/// Run every check for the workspace at `root`.
+pub async fn diagnose(root: &Path, offline: bool) -> Vec<Check> {
+    let mut checks = vec![
+        Check::ok(
+            "binary",
+            format!(
+                "fleet-schema-gen {}+{} ({})",
+                env!("CARGO_PKG_VERSION"),
+                env!("BUILD_TIMESTAMP"),
+                std::env::current_exe().map(|p| p.display().to_string()).unwrap_or_default()
+            ),
+        ),
+        Check::ok(
+            "schema snapshot",
+            format!("bundled schema for Fleet {}", crate::linter::bundled::SNAPSHOT_VERSION),
+        ),
+    ];
+
+    checks.extend(check_config(root));
+    checks.extend(check_workspace(root));
+    checks.push(check_document_store(root));
+    checks.push(check_git(root));
+    checks.push(check_osquery());
+
+    if offline {
+        checks.push(Check::ok("network", "skipped (--offline)"));
+    } else {
+        checks.extend(check_network().await);
+    }
+
+    checks
+}
+
+/// Config discovery, parse errors, and unknown rule names.
+pub fn check_config(root: &Path) -> Vec<Check> {
+    let (paths, config) = match FleetLintConfig::discover(root) {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            return vec![Check::warn(
+                "config",
+                format!("no {} found in {} or its parents", CONFIG_FILE_NAME, root.display()),
+                "Run `fleet-schema-gen init` to create one",
+            )];
+        }
+        Err(e) => {
+            return vec![Check::fail(
+                "config",
+                e.to_string(),
+                "Fix the error; until then the linter ignores the file and uses defaults",
+            )];
+        }
+    };
+    let path = paths.last().cloned().unwrap_or_default();
+    let used: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+
+    let mut checks = vec![Check::ok("config", format!("using {}", used.join(" + ")))];
+
+    let rules = RuleSet::default_rules();
+    let known: Vec<&str> = rules
+        .rules()
+        .iter()
+        .map(|r| r.name())
+        .chain(rules.workspace_rules().iter().map(|r| r.name()))
+        .collect();
+    let unknown: Vec<&str> = config
+        .rules
+        .disabled
+        .iter()
+        .chain(&config.rules.warn)
+        .chain(&config.rules.enabled)
+        .map(|r| r.as_str())
+        .filter(|r| !known.contains(r))
+        .collect();
+    if !unknown.is_empty() {
+        checks.push(Check::warn(
+            "config rules",
+            format!("unknown rule name(s): {}", unknown.join(", ")),
+            format!("Use one of: {}", known.join(", ")),
+        ));
+    }
+
+    if let Some(version) = &config.fleet_version {
+        let dir = path.parent().unwrap_or(root).join(&config.schema.versions_dir);
+        if crate::linter::validate::versioned_schema(&dir, version, crate::linter::validate::SchemaKind::Default).is_none() {
+            checks.push(Check::warn(
+                "versioned schema",
+                format!("no generated schema for Fleet {} in {}", version, dir.display()),
+                format!(
+                    "Run `fleet-schema-gen generate --fleet-version {} --editor strict --output {}/{}` (validate uses the bundled schema meanwhile)",
+                    version, config.schema.versions_dir, version
+                ),
+            ));
+        }
+    }
+
+    checks
+}
+
+/// GitOps layout and unresolved `path:` references.
+pub fn check_workspace(root: &Path) -> Vec<Check> {
+    let detected = crate::linter::init::detect_workspace(root);
+    if !root.join("default.yml").is_file() && !detected.has_teams_dir {
+        return vec![Check::warn(
+            "workspace",
+            format!("{} has no default.yml or teams/ directory", root.display()),
+            "Run doctor from the root of your Fleet GitOps repository",
+        )];
+    }
+
+    let mut checks = vec![Check::ok(
+        "workspace",
+        format!(
+            "{} YAML file(s), {} team(s){}",
+            detected.yaml_file_count,
+            detected.team_count,
+            if detected.has_lib_dir { ", lib/" } else { "" }
+        ),
+    )];
+
+    match crate::linter::repo::load_repo(root) {
+        Ok(repo) => {
+            let unresolved: Vec<String> = repo
+                .teams
+                .iter()
+                .flat_map(|team| team.unresolved.iter().map(move |path| format!("{} ({})", path, team.name)))
+                .collect();
+            if !unresolved.is_empty() {
+                checks.push(Check::fail(
+                    "path references",
+                    format!("{} unresolved: {}", unresolved.len(), unresolved.join(", ")),
+                    "Fix the paths (they are relative to the file that references them)",
+                ));
+            }
+        }
+        Err(e) => checks.push(Check::fail(
+            "path references",
+            format!("failed to load the repository: {}", e),
+            "Run `fleet-schema-gen lint .` for details",
+        )),
+    }
+
+    checks
+}
+
+/// Memory the language server needs for the workspace's files.
+pub fn check_document_store(root: &Path) -> Check {
+    let files = find_yaml_files(root, &WalkOptions::default()).unwrap_or_default();
+    let linter = Linter::new();
+    let store = DocumentStore::new();
+    for file in &files {
+        store.metadata(file, |content| file_error_diagnostics(&linter, content, file));
+    }
+
+    let stats = store.stats();
+    Check::ok(
+        "document store",
+        format!(
+            "{} YAML file(s), {} on disk, indexed by the language server in ~{}",
+            stats.indexed_files,
+            format_bytes(stats.indexed_file_bytes),
+            format_bytes(stats.metadata_bytes as u64)
+        ),
+    )
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// git is needed by `migrate` (branches, backups).
+fn check_git(root: &Path) -> Check {
+    let version = Command::new("git").arg("--version").output().ok();
+    let Some(output) = version.filter(|o| o.status.success()) else {
+        return Check::warn("git", "git not found", "Install git to use `migrate --git`");
+    };
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let in_repo = Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(root)
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if in_repo {
+        Check::ok("git", version)
+    } else {
+        Check::warn("git", format!("{}, but {} is not a git repository", version, root.display()), "Run `git init`; migrations create branches and commits")
+    }
+}
+
+/// osqueryi is optional (`lint --exec-osquery`).
+fn check_osquery() -> Check {
+    let Some(binary) = crate::linter::osqueryi::find_osqueryi() else {
+        return Check::warn(
+            "osquery",
+            "osqueryi not found (only needed for --exec-osquery)",
+            "Install osquery or set OSQUERYI to the osqueryi binary",
+        );
+    };
+
+    let version = Command::new(&binary)
+        .arg("--version")
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+    Check::ok("osquery", format!("{} {}", binary.display(), version).trim().to_string())
+}
+
+async fn check_network() -> Vec<Check> {
+    let client = match reqwest::Client::builder()
+        .user_agent("fleet-schema-gen")
+        .timeout(Duration::from_secs(5))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return vec![Check::fail("network", e.to_string(), "Check TLS/proxy configuration")],
+    };
+
+    let mut checks = Vec::new();
+    for (label, url) in NETWORK_SOURCES {
+        let check = match client.head(*url).send().await {
+            Ok(response) => Check::ok("network", format!("{} reachable ({})", label, response.status())),
+            Err(e) => Check::warn(
+                "network",
+                format!("{} unreachable: {}", label, e),
+                "Schema generation and self-update need network access; lint and validate work offline",
+            ),
+        };
+        checks.push(check);
+    }
+    checks
+}
+
+/// Print checks as text.
+pub fn print(checks: &[Check]) {
+    for check in checks {
+        let marker = match check.status {
+            Status::Ok => "✓".green(),
+            Status::Warn => "⚠".yellow(),
+            Status::Fail => "✗".red(),
+        };
+        println!("{} {:<17} {}", marker, check.name.bold(), check.detail);
+        if let Some(fix) = &check.fix {
+            println!("  {:<17} {} {}", "", "→".dimmed(), fix.dimmed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_check_config_reports_unknown_rules() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "[rules]\ndisabled = [\"query-syntax\", \"querysyntax\"]\n",
+        )
+        .unwrap();
+
+        let checks = check_config(temp_dir.path());
+        assert_eq!(checks[0].status, Status::Ok);
+        assert_eq!(checks[1].status, Status::Warn);
+        assert_eq!(checks[1].detail, "unknown rule name(s): querysyntax");
+
+        fs::write(temp_dir.path().join(CONFIG_FILE_NAME), "[rules\n").unwrap();
+        assert_eq!(check_config(temp_dir.path())[0].status, Status::Fail);
+    }
+
+    #[test]
+    fn test_check_document_store() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("lib")).unwrap();
+        fs::write(temp_dir.path().join("lib/policies.yml"), "- name: A\n  query: SELECT 1;\n").unwrap();
+
+        let check = check_document_store(temp_dir.path());
+        assert_eq!(check.status, Status::Ok);
+        assert!(check.detail.starts_with("1 YAML file(s), 29 B on disk"), "{}", check.detail);
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1_048_576), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_check_workspace_reports_unresolved_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        assert_eq!(check_workspace(root)[0].status, Status::Warn);
+
+        fs::create_dir_all(root.join("teams")).unwrap();
+        fs::write(root.join("teams/a.yml"), "name: A\npolicies:\n  - path: ../lib/missing.yml\n").unwrap();
+
+        let checks = check_workspace(root);
+        assert_eq!(checks[0].status, Status::Ok);
+        assert_eq!(checks[1].status, Status::Fail);
+        assert!(checks[1].detail.contains("../lib/missing.yml (A)"));
+    }
+}
@@ -0,0 +1,71 @@
+//! Logging setup for the CLI (`-v`/`-vv`, `--quiet`, `--json-logs`).
+//!
+//! Progress and diagnostics go through `tracing` to stderr, so stdout only
+//! carries command output (lint reports, JSON, SARIF). `RUST_LOG` overrides
+//! the level chosen by the flags.
+
+use colored::Colorize;
+use std::fmt;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Level for our own events given the CLI flags.
+pub fn level(verbose: u8, quiet: bool) -> Level {
+    match (quiet, verbose) {
+        (true, _) => Level::ERROR,
+        (false, 0) => Level::INFO,
+        (false, 1) => Level::DEBUG,
+        (false, _) => Level::TRACE,
+    }
+}
+
+/// Install the global subscriber.
+pub fn init(verbose: u8, quiet: bool, json: bool) {
+    let default = format!("warn,fleet_schema_gen={}", level(verbose, quiet).as_str().to_lowercase());
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter).with_writer(std::io::stderr);
+
+    // Ignore errors from a subscriber installed twice (tests)
+    let _ = if json {
+        builder.json().flatten_event(true).try_init()
+    } else if verbose > 0 {
+        builder.with_target(true).try_init()
+    } else {
+        builder.event_format(Plain).try_init()
+    };
+}
+
+/// Default text format: the message only, with a marker for warnings and errors.
+struct Plain;
+
+impl<S, N> FormatEvent<S, N> for Plain
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        match *event.metadata().level() {
+            Level::ERROR => write!(writer, "{} ", "✗".red())?,
+            Level::WARN => write!(writer, "{} ", "⚠".yellow())?,
+            _ => {}
+        }
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level() {
+        assert_eq!(level(0, false), Level::INFO);
+        assert_eq!(level(1, false), Level::DEBUG);
+        assert_eq!(level(3, false), Level::TRACE);
+        assert_eq!(level(2, true), Level::ERROR);
+    }
+}
@@ -1,2 +1,7 @@
 pub mod http;
 pub mod markdown;
+pub mod self_update;
+pub mod doctor;
+pub mod logging;
+pub mod verify;
+pub mod bundle;
@@ -0,0 +1,288 @@
+//! `fleet-schema-gen self-update`: replace the running binary with the
+//! latest GitHub release.
+//!
+//! Release archives are named `fleet-schema-gen-<version>-<platform>.tar.gz`
+//! and verified against the release's `checksums.txt` (or the `.sha256`
+//! file next to them in older releases) and, when a public key is
+//! configured, its minisign signature; see [`super::verify`].
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Deserialize;
+use std::io::Read;
+use std::path::Path;
+
+use super::verify::{self, CHECKSUMS_FILE, PUBLIC_KEY_ENV, SIGNATURE_FILE};
+
+/// Repository the release workflow publishes to.
+const RELEASES_REPO: &str = "headmin/fleet-editor-extensions";
+
+const BINARY_NAME: &str = "fleet-schema-gen";
+
+/// Which releases to consider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Stable,
+    Prerelease,
+}
+
+impl std::str::FromStr for Channel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "stable" => Ok(Channel::Stable),
+            "prerelease" => Ok(Channel::Prerelease),
+            _ => anyhow::bail!("Unknown channel: {} (expected stable or prerelease)", s),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+impl Release {
+    /// Version without the leading `v`.
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|a| a.name == name)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+/// Release platform of this build (`darwin-arm64`, `linux-x64`, ...).
+pub fn platform() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "aarch64") => Some("darwin-arm64"),
+        ("macos", "x86_64") => Some("darwin-x64"),
+        ("linux", "aarch64") => Some("linux-arm64"),
+        ("linux", "x86_64") => Some("linux-x64"),
+        _ => None,
+    }
+}
+
+/// Archive name for a version and platform.
+pub fn asset_name(version: &str, platform: &str) -> String {
+    format!("{}-{}-{}.tar.gz", BINARY_NAME, version, platform)
+}
+
+/// Newest release on a channel (GitHub lists releases newest first).
+pub fn select_release(releases: &[Release], channel: Channel) -> Option<&Release> {
+    releases
+        .iter()
+        .filter(|r| !r.draft)
+        .find(|r| channel == Channel::Prerelease || !r.prerelease)
+}
+
+/// Whether `candidate` is newer than `current` (semver, including prereleases).
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    match (semver::Version::parse(candidate), semver::Version::parse(current)) {
+        (Ok(candidate), Ok(current)) => candidate > current,
+        _ => candidate != current,
+    }
+}
+
+/// Extract the binary from a release archive.
+pub fn extract_binary(archive: &[u8]) -> Result<Vec<u8>> {
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    for entry in tar.entries().context("Invalid release archive")? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if path.file_name().and_then(|n| n.to_str()) == Some(BINARY_NAME) {
+            let mut binary = Vec::new();
+            entry.read_to_end(&mut binary)?;
+            return Ok(binary);
+        }
+    }
+    anyhow::bail!("Release archive does not contain {}", BINARY_NAME)
+}
+
+/// Atomically replace `target` with `binary`.
+///
+/// The new binary is written next to the target and renamed over it, so a
+/// failed update never leaves a partial file behind.
+pub fn replace_binary(target: &Path, binary: &[u8]) -> Result<()> {
+    let dir = target.parent().context("Binary has no parent directory")?;
+    let staged = dir.join(format!(".{}.new", BINARY_NAME));
+
+    std::fs::write(&staged, binary)
+        .with_context(|| format!("Failed to write {} (is the directory writable?)", staged.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    if let Err(e) = verify_signature(&staged) {
+        let _ = std::fs::remove_file(&staged);
+        return Err(e);
+    }
+
+    std::fs::rename(&staged, target).with_context(|| format!("Failed to replace {}", target.display()))
+}
+
+/// Check the code signature of macOS release binaries.
+fn verify_signature(binary: &Path) -> Result<()> {
+    if !cfg!(target_os = "macos") {
+        return Ok(());
+    }
+
+    let status = std::process::Command::new("codesign")
+        .args(["--verify", "--strict"])
+        .arg(binary)
+        .status()
+        .context("Failed to run codesign")?;
+    if !status.success() {
+        anyhow::bail!("Code signature verification failed for the downloaded binary");
+    }
+    Ok(())
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?
+        .error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Check for a newer release and install it.
+pub async fn run(channel: Channel, check_only: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let platform = platform().context("No release builds for this platform; build from source with cargo install")?;
+    let client = super::http::create_client()?;
+
+    let releases: Vec<Release> = client
+        .get(format!("https://api.github.com/repos/{}/releases", RELEASES_REPO))
+        .send()
+        .await
+        .context("Failed to reach GitHub")?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let release = select_release(&releases, channel).context("No releases found")?;
+    let version = release.version();
+    if !is_newer(version, current) {
+        println!("{} fleet-schema-gen {} is up to date", "✓".green(), current);
+        return Ok(());
+    }
+
+    println!("{} fleet-schema-gen {} is available (current: {})", "⬆".cyan(), version.bold(), current);
+    if check_only {
+        return Ok(());
+    }
+
+    let name = asset_name(version, platform);
+    let archive_asset = release
+        .asset(&name)
+        .with_context(|| format!("Release {} has no {}", release.tag_name, name))?;
+    let checksum_asset = release
+        .asset(CHECKSUMS_FILE)
+        .or_else(|| release.asset(&format!("{}.sha256", name)))
+        .with_context(|| format!("Release {} has no checksum for {}", release.tag_name, name))?;
+    let checksums = String::from_utf8(download(&client, &checksum_asset.browser_download_url).await?)?;
+
+    // The signature covers checksums.txt, which in turn covers the archive
+    match (verify::public_key(), release.asset(SIGNATURE_FILE)) {
+        (Some(key), Some(signature_asset)) if checksum_asset.name == CHECKSUMS_FILE => {
+            let signature = download(&client, &signature_asset.browser_download_url).await?;
+            verify::verify_checksums_signature(&checksums, &signature, &key)?;
+            println!("  {} Signature verified", "✓".green());
+        }
+        (Some(_), _) => {
+            anyhow::bail!(
+                "Release {} is not signed, but {} is set; refusing to install it",
+                release.tag_name,
+                PUBLIC_KEY_ENV
+            );
+        }
+        (None, Some(_)) => {
+            println!("  {} Signature not checked (set {} to verify it)", "!".yellow(), PUBLIC_KEY_ENV);
+        }
+        (None, None) => {}
+    }
+
+    let archive = download(&client, &archive_asset.browser_download_url).await?;
+    verify::verify_checksum(&archive, &checksums, &name)?;
+    println!("  {} SHA-256 verified", "✓".green());
+
+    let binary = extract_binary(&archive)?;
+    let target = std::env::current_exe()?.canonicalize()?;
+    replace_binary(&target, &binary)?;
+
+    println!("{} Updated {} to {}", "✓".green(), target.display(), version);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            prerelease,
+            draft: false,
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_select_release() {
+        let releases = vec![release("v0.3.0-beta.1", true), release("v0.2.0", false)];
+        assert_eq!(select_release(&releases, Channel::Stable).unwrap().version(), "0.2.0");
+        assert_eq!(select_release(&releases, Channel::Prerelease).unwrap().version(), "0.3.0-beta.1");
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.1.0"));
+        assert!(is_newer("0.2.0-beta.2", "0.2.0-beta.1"));
+        assert!(!is_newer("0.2.0-beta.1", "0.2.0"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+    }
+
+    #[test]
+    fn test_extract_and_replace_binary() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        let contents = b"#!/bin/sh\necho new\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, BINARY_NAME, &contents[..]).unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let binary = extract_binary(&archive).unwrap();
+        assert_eq!(binary, contents);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let target = temp_dir.path().join(BINARY_NAME);
+        std::fs::write(&target, "old").unwrap();
+        if !cfg!(target_os = "macos") {
+            replace_binary(&target, &binary).unwrap();
+            assert_eq!(std::fs::read(&target).unwrap(), contents);
+            assert!(!temp_dir.path().join(".fleet-schema-gen.new").exists());
+        }
+    }
+}
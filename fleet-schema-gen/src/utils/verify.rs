@@ -0,0 +1,124 @@
+//! Verification of downloaded release artifacts.
+//!
+//! Releases publish a `checksums.txt` (`shasum -a 256` lines for every
+//! archive) and, when the release was signed, `checksums.txt.minisig`.
+//! Older releases only have a `<archive>.sha256` next to each archive.
+//! Signatures are checked with the `minisign` CLI against the public key in
+//! `FLEET_SCHEMA_GEN_MINISIGN_PUBKEY`.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Release-wide checksum file.
+pub const CHECKSUMS_FILE: &str = "checksums.txt";
+
+/// Signature of [`CHECKSUMS_FILE`].
+pub const SIGNATURE_FILE: &str = "checksums.txt.minisig";
+
+/// Environment variable with the minisign public key releases are signed with.
+pub const PUBLIC_KEY_ENV: &str = "FLEET_SCHEMA_GEN_MINISIGN_PUBKEY";
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Extract the hash from a `shasum -a 256` line for `asset`. A single hash
+/// without a file name (a bare `.sha256` file) applies to any asset.
+pub fn parse_checksum(text: &str, asset: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let file = parts.next().map(|f| f.trim_start_matches('*'));
+        (file.is_none() || file == Some(asset)).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+/// Check `data` against the entry for `asset` in `checksums`.
+pub fn verify_checksum(data: &[u8], checksums: &str, asset: &str) -> Result<()> {
+    let expected = parse_checksum(checksums, asset).with_context(|| format!("No checksum for {}", asset))?;
+    let actual = sha256_hex(data);
+    if actual != expected {
+        anyhow::bail!("Checksum mismatch for {}: expected {}, got {}", asset, expected, actual);
+    }
+    Ok(())
+}
+
+/// The configured minisign public key, if any.
+pub fn public_key() -> Option<String> {
+    std::env::var(PUBLIC_KEY_ENV).ok().filter(|key| !key.trim().is_empty())
+}
+
+/// Verify the minisign `signature` of `file` with `public_key` (the base64
+/// key line of a `minisign.pub`).
+pub fn verify_signature(file: &Path, signature: &Path, public_key: &str) -> Result<()> {
+    let output = std::process::Command::new("minisign")
+        .arg("-V")
+        .arg("-q")
+        .args(["-P", public_key.trim()])
+        .arg("-m")
+        .arg(file)
+        .arg("-x")
+        .arg(signature)
+        .output()
+        .context("Failed to run minisign (install it to verify release signatures)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Signature verification failed for {}: {}",
+            file.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Verify the signature of `checksums` (the contents of [`CHECKSUMS_FILE`]).
+pub fn verify_checksums_signature(checksums: &str, signature: &[u8], public_key: &str) -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("fleet-schema-gen-verify-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let file = dir.join(CHECKSUMS_FILE);
+    let sig = dir.join(SIGNATURE_FILE);
+    let result = std::fs::write(&file, checksums)
+        .and_then(|_| std::fs::write(&sig, signature))
+        .map_err(anyhow::Error::from)
+        .and_then(|_| verify_signature(&file, &sig, public_key));
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_checksum() {
+        let name = "fleet-schema-gen-0.2.0-linux-x64.tar.gz";
+        assert_eq!(
+            parse_checksum(&format!("ABC123  {}\n", name), name).as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(parse_checksum("abc123\n", name).as_deref(), Some("abc123"));
+        assert_eq!(parse_checksum("abc123  other.tar.gz\n", name), None);
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let name = "fleet-schema-gen-0.2.0-linux-x64.tar.gz";
+        let data = b"archive";
+        let checksums = format!("{}  other.zip\n{} *{}\n", sha256_hex(b"other"), sha256_hex(data), name);
+
+        verify_checksum(data, &checksums, name).unwrap();
+
+        let err = verify_checksum(b"tampered", &checksums, name).unwrap_err();
+        assert!(err.to_string().starts_with("Checksum mismatch"));
+
+        let err = verify_checksum(data, &checksums, "fleet-schema-gen-0.2.0-darwin-arm64.tar.gz").unwrap_err();
+        assert!(err.to_string().starts_with("No checksum"));
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    }
+}
@@ -0,0 +1,69 @@
+package spec
+
+import (
+	"encoding/json"
+
+	"github.com/fleetdm/fleet/v4/server/fleet"
+)
+
+type BaseItem struct {
+	Path *string `json:"path"`
+	Paths *string `json:"paths"`
+}
+
+type GitOpsControls struct {
+	BaseItem
+	MacOSUpdates   interface{} `json:"macos_updates"`
+	MacOSSettings  interface{} `json:"macos_settings"`
+	MacOSSetup     *fleet.MacOSSetup `json:"macos_setup"`
+	EnableDiskEncryption interface{} `json:"enable_disk_encryption"`
+	Scripts        []BaseItem `json:"scripts"`
+	Defined        bool       `json:"-"`
+}
+
+type Policy struct {
+	BaseItem
+	GitOpsPolicySpec
+}
+
+type GitOpsPolicySpec struct {
+	fleet.PolicySpec
+	RunScript        *PolicyRunScript        `json:"run_script"`
+	InstallSoftware  *PolicyInstallSoftware  `json:"install_software"`
+}
+
+type PolicyRunScript struct {
+	Path string `json:"path"`
+}
+
+type PolicyInstallSoftware struct {
+	PackagePath string `json:"package_path"`
+	AppStoreID  string `json:"app_store_id"`
+}
+
+type (
+	SoftwarePackage struct {
+		fleet.SoftwarePackageSpec
+		SelfService bool `json:"self_service,omitempty" yaml:"self_service"`
+	}
+
+	GitOpsSoftware struct {
+		Packages     []*SoftwarePackage            `json:"packages,omitempty"`
+		AppStoreApps []*fleet.TeamSpecAppStoreApp  `json:"app_store_apps,omitempty"`
+		FleetMaintainedApps []*fleet.MaintainedAppSpec `json:"fleet_maintained_apps,omitempty"`
+	}
+)
+
+type GitOps struct {
+	TeamID       *uint                  `json:"team_id"`
+	TeamName     *string                `json:"-"`
+	TeamSettings map[string]interface{} `json:"team_settings"`
+	OrgSettings  map[string]interface{} `json:"org_settings"`
+	AgentOptions *json.RawMessage       `json:"agent_options,omitempty"`
+	Controls     GitOpsControls         `json:"controls"`
+	Policies     []*Policy              `json:"policies"`
+	Queries      []*fleet.QuerySpec     `json:"queries"`
+	Labels       []*fleet.LabelSpec     `json:"labels"`
+	// Software packages and app store apps
+	Software     GitOpsSoftware         `json:"software"`
+}
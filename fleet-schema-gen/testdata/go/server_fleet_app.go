@@ -0,0 +1,149 @@
+package fleet
+
+import (
+	"github.com/fleetdm/fleet/v4/pkg/optjson"
+)
+
+// AppConfig holds server configuration that can be changed via the API.
+type AppConfig struct {
+	OrgInfo        OrgInfo        `json:"org_info"`
+	ServerSettings ServerSettings `json:"server_settings"`
+	// Features allows to globally enable or disable features
+	Features Features `json:"features"`
+	MDM      MDM      `json:"mdm"`
+	Scripts  optjson.Slice[string] `json:"scripts"`
+	Integrations Integrations `json:"integrations"`
+	// deprecated and ignored
+	SSOSettings *SSOSettings `json:"sso_settings,omitempty"`
+	HostExpirySettings HostExpirySettings `json:"host_expiry_settings"`
+	AgentOptions *json.RawMessage `json:"agent_options,omitempty"`
+}
+
+type OrgInfo struct {
+	OrgName    string `json:"org_name"`
+	OrgLogoURL string `json:"org_logo_url"`
+	ContactURL string `json:"contact_url"`
+}
+
+type ServerSettings struct {
+	ServerURL           string `json:"server_url"`
+	LiveQueryDisabled   bool   `json:"live_query_disabled"`
+	QueryReportCap      int    `json:"query_report_cap"`
+	ScriptsDisabled     bool   `json:"scripts_disabled"`
+	AIFeaturesDisabled  bool   `json:"ai_features_disabled"`
+}
+
+type SSOSettings struct {
+	SSOProviderSettings
+
+	EnableSSO bool `json:"enable_sso"`
+	EnableJITProvisioning bool `json:"enable_jit_provisioning"`
+}
+
+type SSOProviderSettings struct {
+	EntityID    string `json:"entity_id"`
+	IssuerURI   string `json:"issuer_uri"`
+	IDPName     string `json:"idp_name"`
+	// EnableSSO on the outer struct takes precedence
+	EnableSSO   string `json:"enable_sso"`
+}
+
+type Features struct {
+	EnableHostUsers         bool               `json:"enable_host_users"`
+	EnableSoftwareInventory bool               `json:"enable_software_inventory"`
+	AdditionalQueries       *json.RawMessage   `json:"additional_queries,omitempty"`
+	DetailQueryOverrides    map[string]*string `json:"detail_query_overrides,omitempty"`
+}
+
+type HostExpirySettings struct {
+	HostExpiryEnabled bool `json:"host_expiry_enabled"`
+	HostExpiryWindow  int  `json:"host_expiry_window"`
+}
+
+// MDM is part of AppConfig and defines the mdm settings.
+type MDM struct {
+	AppleBMDefaultTeam string `json:"apple_bm_default_team"`
+	EnableDiskEncryption optjson.Bool `json:"enable_disk_encryption"`
+
+	MacOSUpdates  AppleOSUpdateSettings `json:"macos_updates"`
+	IOSUpdates    AppleOSUpdateSettings `json:"ios_updates"`
+	WindowsUpdates WindowsUpdates       `json:"windows_updates"`
+
+	MacOSSettings   MacOSSettings   `json:"macos_settings"`
+	MacOSSetup      MacOSSetup      `json:"macos_setup"`
+	MacOSMigration  MacOSMigration  `json:"macos_migration"`
+	WindowsSettings WindowsSettings `json:"windows_settings"`
+
+	EndUserAuthentication struct {
+		SSOProviderSettings
+		Enabled bool `json:"enabled"`
+	} `json:"end_user_authentication"`
+}
+
+type AppleOSUpdateSettings struct {
+	MinimumVersion optjson.String `json:"minimum_version"`
+	Deadline       optjson.String `json:"deadline"`
+}
+
+type WindowsUpdates struct {
+	DeadlineDays    optjson.Int `json:"deadline_days"`
+	GracePeriodDays optjson.Int `json:"grace_period_days"`
+}
+
+// MacOSSettings contains settings specific to macOS.
+type MacOSSettings struct {
+	// CustomSettings is a slice of configuration profile file paths.
+	CustomSettings []MDMProfileSpec `json:"custom_settings"`
+	// Deprecated: use mdm.enable_disk_encryption.
+	DeprecatedEnableDiskEncryption *bool `json:"enable_disk_encryption,omitempty"`
+}
+
+type MDMProfileSpec struct {
+	Path             string   `json:"path,omitempty"`
+	Labels           []string `json:"labels,omitempty"`
+	LabelsIncludeAll []string `json:"labels_include_all,omitempty"`
+	LabelsExcludeAny []string `json:"labels_exclude_any,omitempty"`
+}
+
+type MacOSSetup struct {
+	BootstrapPackage            optjson.String `json:"bootstrap_package"`
+	EnableEndUserAuthentication bool           `json:"enable_end_user_authentication"`
+	MacOSSetupAssistant         optjson.String `json:"macos_setup_assistant"`
+	EnableReleaseDeviceManually optjson.Bool   `json:"enable_release_device_manually"`
+	Script                      optjson.String `json:"script"`
+	Software                    optjson.Slice[*MacOSSetupSoftware] `json:"software"`
+}
+
+type MacOSSetupSoftware struct {
+	AppStoreID  string `json:"app_store_id"`
+	PackagePath string `json:"package_path"`
+}
+
+type MacOSMigrationMode string
+
+type MacOSMigration struct {
+	Enable     bool               `json:"enable"`
+	Mode       MacOSMigrationMode `json:"mode"`
+	WebhookURL string             `json:"webhook_url"`
+}
+
+type WindowsSettings struct {
+	CustomSettings optjson.Slice[MDMProfileSpec] `json:"custom_settings"`
+}
+
+type Integrations struct {
+	Jira           []*JiraIntegration     `json:"jira"`
+	GoogleCalendar []*GoogleCalendarIntegration `json:"google_calendar"`
+}
+
+type JiraIntegration struct {
+	URL        string `json:"url"`
+	Username   string `json:"username"`
+	APIToken   string `json:"api_token"`
+	ProjectKey string `json:"project_key"`
+}
+
+type GoogleCalendarIntegration struct {
+	Domain     string            `json:"domain"`
+	ApiKey     map[string]string `json:"api_key_json"`
+}
@@ -105,6 +105,7 @@ OPTIONS:
     -t, --tag TAG       Release tag (default: v{extension_version})
     --quick             Skip cargo clean (faster, may use stale build)
     --skip-vsix         Skip VSIX build (standalone LSP only)
+    --minisign KEY      Sign the release's checksums.txt with this minisign secret key
     --dry-run           Show what would be done without executing
     -h, --help          Show this help
 
@@ -186,6 +187,7 @@ TAG_NAME=""
 QUICK_BUILD=false
 SKIP_VSIX=false
 DRY_RUN=false
+MINISIGN_KEY=""
 
 while [[ $# -gt 0 ]]; do
     case $1 in
@@ -195,6 +197,7 @@ while [[ $# -gt 0 ]]; do
         -t|--tag) TAG_NAME="$2"; shift 2 ;;
         --quick) QUICK_BUILD=true; shift ;;
         --skip-vsix) SKIP_VSIX=true; shift ;;
+        --minisign) MINISIGN_KEY="$2"; shift 2 ;;
         --dry-run) DRY_RUN=true; shift ;;
         -h|--help) show_usage; exit 0 ;;
         *) log_error "Unknown option: $1"; show_usage; exit 1 ;;
@@ -248,7 +251,7 @@ fi
 log_section "Step 1: Setup"
 
 mkdir -p "$DIST_DIR"
-rm -f "$DIST_DIR"/*.tar.gz "$DIST_DIR"/*.vsix "$DIST_DIR"/*.sha256
+rm -f "$DIST_DIR"/*.tar.gz "$DIST_DIR"/*.vsix "$DIST_DIR"/*.sha256 "$DIST_DIR"/checksums.txt*
 
 if [ "$CODESIGN" = true ] && [ "$OS" = "darwin" ]; then
     load_credentials
@@ -512,6 +515,24 @@ tar xzf ${ARCHIVE_NAME} && sudo mv $BINARY_NAME /usr/local/bin/
     $GH_CLI release upload "$TAG_NAME" "$DIST_DIR/$SUBLIME_ZIP" --clobber
     $GH_CLI release upload "$TAG_NAME" "$DIST_DIR/${SUBLIME_ZIP}.sha256" --clobber
 
+    # Merge this platform's checksums into the release-wide checksums.txt,
+    # which self-update and the Zed extension verify downloads against
+    log_step "Updating checksums.txt..."
+    CHECKSUMS_DIR="$DIST_DIR/release-checksums"
+    rm -rf "$CHECKSUMS_DIR"
+    mkdir -p "$CHECKSUMS_DIR"
+    $GH_CLI release download "$TAG_NAME" --pattern checksums.txt --dir "$CHECKSUMS_DIR" 2>/dev/null || touch "$CHECKSUMS_DIR/checksums.txt"
+    # Entries for this build replace those of earlier uploads
+    cat "$DIST_DIR"/*.sha256 "$CHECKSUMS_DIR/checksums.txt" | awk '!seen[$2]++' | sort -k2 > "$DIST_DIR/checksums.txt"
+    rm -rf "$CHECKSUMS_DIR"
+    $GH_CLI release upload "$TAG_NAME" "$DIST_DIR/checksums.txt" --clobber
+
+    if [ -n "$MINISIGN_KEY" ]; then
+        log_step "Signing checksums.txt..."
+        minisign -S -s "$MINISIGN_KEY" -m "$DIST_DIR/checksums.txt" -x "$DIST_DIR/checksums.txt.minisig"
+        $GH_CLI release upload "$TAG_NAME" "$DIST_DIR/checksums.txt.minisig" --clobber
+    fi
+
     log_info "Release URL:"
     $GH_CLI release view "$TAG_NAME" --json url -q .url
 
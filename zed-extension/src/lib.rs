@@ -5,8 +5,17 @@
 //!
 //! The extension will automatically download the LSP binary from GitHub releases
 //! if it's not found in PATH or common installation locations.
-
+//!
+//! Progress and problems are reported through Zed's language server
+//! installation status (the activity indicator in the status bar), which is
+//! also where an outdated `fleet-schema-gen` on PATH is pointed out. Zed
+//! doesn't pass custom notifications such as `fleet/fileSummary` to
+//! extensions; the server reports its schema version in its log and warns
+//! about a stale bundled schema with a notification instead.
+
+use sha2::{Digest, Sha256};
 use std::fs;
+use std::io;
 use zed::LanguageServerId;
 use zed_extension_api::{self as zed, settings::LspSettings, Result};
 
@@ -14,6 +23,9 @@ use zed_extension_api::{self as zed, settings::LspSettings, Result};
 struct FleetExtension {
     /// Cached path to the fleet-schema-gen binary.
     cached_binary_path: Option<String>,
+    /// Whether a binary found on the system was already compared with the
+    /// latest release this session.
+    checked_for_update: bool,
 }
 
 /// Binary name.
@@ -64,8 +76,73 @@ impl FleetExtension {
         Some(format!("{}-{}-{}.tar.gz", BINARY_NAME, version, platform))
     }
 
+    /// Version printed by `fleet-schema-gen --version` (e.g.
+    /// "fleet-schema-gen 0.3.1+20250101" -> "0.3.1").
+    fn installed_version(binary_path: &str) -> Option<String> {
+        let output = zed::process::Command::new(binary_path).arg("--version").output().ok()?;
+        let stdout = String::from_utf8(output.stdout).ok()?;
+        let version = stdout.split_whitespace().nth(1)?;
+        Some(version.split('+').next().unwrap_or(version).to_string())
+    }
+
+    /// Whether `latest` is a newer dotted version than `installed`.
+    fn is_newer(latest: &str, installed: &str) -> bool {
+        let parse = |version: &str| -> Vec<u64> {
+            version
+                .trim_start_matches('v')
+                .split(['.', '-'])
+                .map_while(|part| part.parse().ok())
+                .collect()
+        };
+        parse(latest) > parse(installed)
+    }
+
+    /// Compare a binary found on the system with the latest release, once
+    /// per session, and prompt to update it through the installation status.
+    fn check_for_update(&mut self, language_server_id: &LanguageServerId, binary_path: &str) {
+        if self.checked_for_update {
+            return;
+        }
+        self.checked_for_update = true;
+
+        let Some(installed) = Self::installed_version(binary_path) else {
+            return;
+        };
+        zed::set_language_server_installation_status(
+            language_server_id,
+            &zed::LanguageServerInstallationStatus::CheckingForUpdate,
+        );
+        let latest = zed::latest_github_release(
+            GITHUB_REPO,
+            zed::GithubReleaseOptions {
+                require_assets: true,
+                pre_release: true,
+            },
+        );
+
+        // Failed is the only status that carries a message
+        let status = match latest {
+            Ok(release) if Self::is_newer(&release.version, &installed) => {
+                zed::LanguageServerInstallationStatus::Failed(format!(
+                    "{} {} is installed at {}; {} is available. Update it, or remove it to let the extension download the latest release.",
+                    BINARY_NAME,
+                    installed,
+                    binary_path,
+                    release.version.trim_start_matches('v')
+                ))
+            }
+            _ => zed::LanguageServerInstallationStatus::None,
+        };
+        zed::set_language_server_installation_status(language_server_id, &status);
+    }
+
     /// Download and install the binary from GitHub releases.
-    fn download_binary(&self) -> Result<String> {
+    fn download_binary(&self, language_server_id: &LanguageServerId) -> Result<String> {
+        zed::set_language_server_installation_status(
+            language_server_id,
+            &zed::LanguageServerInstallationStatus::CheckingForUpdate,
+        );
+
         // Get the latest release from GitHub
         let release = zed::latest_github_release(
             GITHUB_REPO,
@@ -105,21 +182,50 @@ impl FleetExtension {
             return Ok(binary_path);
         }
 
-        // Download the archive
+        // The release-wide checksums.txt, or the .sha256 next to the
+        // archive in older releases
+        let checksum_asset = release
+            .assets
+            .iter()
+            .find(|a| a.name == CHECKSUMS_FILE)
+            .or_else(|| {
+                release
+                    .assets
+                    .iter()
+                    .find(|a| a.name == format!("{}.sha256", asset_name))
+            })
+            .ok_or_else(|| format!("Release {} publishes no checksum for {}", release.version, asset_name))?;
+
+        // Download the archive and its checksum; nothing is extracted until
+        // the archive matches
+        zed::set_language_server_installation_status(
+            language_server_id,
+            &zed::LanguageServerInstallationStatus::Downloading,
+        );
+        let checksum_path = format!("{}.checksums", binary_path);
         let archive_path = format!("{}.tar.gz", binary_path);
+        zed::download_file(
+            &checksum_asset.download_url,
+            &checksum_path,
+            zed::DownloadedFileType::Uncompressed,
+        )
+        .map_err(|e| format!("Failed to download {}: {}", checksum_asset.name, e))?;
         zed::download_file(
             &asset.download_url,
             &archive_path,
-            zed::DownloadedFileType::GzipTar,
+            zed::DownloadedFileType::Uncompressed,
         )
         .map_err(|e| format!("Failed to download {}: {}", asset_name, e))?;
 
-        // The archive extracts to the binary name
-        let extracted_binary = BINARY_NAME.to_string();
+        let checksums = fs::read_to_string(&checksum_path);
+        let archive = fs::read(&archive_path);
+        let _ = fs::remove_file(&checksum_path);
+        let _ = fs::remove_file(&archive_path);
+        let checksums = checksums.map_err(|e| format!("Failed to read {}: {}", checksum_asset.name, e))?;
+        let archive = archive.map_err(|e| format!("Failed to read {}: {}", asset_name, e))?;
 
-        // Rename to versioned path
-        fs::rename(&extracted_binary, &binary_path)
-            .map_err(|e| format!("Failed to rename binary: {}", e))?;
+        verify_checksum(&archive, &checksums, &asset_name)?;
+        extract_binary(&archive, &binary_path)?;
 
         // Make executable
         zed::make_file_executable(&binary_path)?;
@@ -134,11 +240,19 @@ impl FleetExtension {
             }
         }
 
+        zed::set_language_server_installation_status(
+            language_server_id,
+            &zed::LanguageServerInstallationStatus::None,
+        );
         Ok(binary_path)
     }
 
     /// Get the binary path, trying multiple methods.
-    fn get_binary_path(&mut self, worktree: &zed::Worktree) -> Result<String> {
+    fn get_binary_path(
+        &mut self,
+        language_server_id: &LanguageServerId,
+        worktree: &zed::Worktree,
+    ) -> Result<String> {
         // 1. Return cached path if available and still exists
         if let Some(ref path) = self.cached_binary_path {
             if fs::metadata(path).is_ok() {
@@ -148,36 +262,91 @@ impl FleetExtension {
 
         // 2. Try worktree.which() (searches PATH)
         if let Some(path) = worktree.which(BINARY_NAME) {
+            self.check_for_update(language_server_id, &path);
             self.cached_binary_path = Some(path.clone());
             return Ok(path);
         }
 
         // 3. Try common installation paths
         if let Some(path) = self.find_binary_in_common_paths() {
+            self.check_for_update(language_server_id, &path);
             self.cached_binary_path = Some(path.clone());
             return Ok(path);
         }
 
         // 4. Auto-download from GitHub releases
-        let path = self.download_binary()?;
+        let path = self.download_binary(language_server_id)?;
         self.cached_binary_path = Some(path.clone());
         Ok(path)
     }
 }
 
+/// Release-wide checksum file (`shasum -a 256` lines).
+const CHECKSUMS_FILE: &str = "checksums.txt";
+
+/// Check `archive` against the SHA-256 for `asset_name` in `checksums`. A
+/// bare hash without a file name (a `.sha256` file) applies to any asset.
+fn verify_checksum(archive: &[u8], checksums: &str, asset_name: &str) -> Result<()> {
+    let expected = checksums
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let file = parts.next().map(|f| f.trim_start_matches('*'));
+            (file.is_none() || file == Some(asset_name)).then(|| hash.to_ascii_lowercase())
+        })
+        .ok_or_else(|| format!("No checksum for {}", asset_name))?;
+
+    let actual: String = Sha256::digest(archive).iter().map(|b| format!("{:02x}", b)).collect();
+    if actual != expected {
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}. The download was discarded.",
+            asset_name, expected, actual
+        ));
+    }
+    Ok(())
+}
+
+/// Write the binary from a verified release archive to `destination`.
+fn extract_binary(archive: &[u8], destination: &str) -> Result<()> {
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    let entries = tar.entries().map_err(|e| format!("Invalid release archive: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Invalid release archive: {}", e))?;
+        let is_binary = entry
+            .path()
+            .ok()
+            .and_then(|path| path.file_name().map(|name| name == BINARY_NAME))
+            .unwrap_or(false);
+        if is_binary {
+            let mut file = fs::File::create(destination)
+                .map_err(|e| format!("Failed to write {}: {}", destination, e))?;
+            io::copy(&mut entry, &mut file).map_err(|e| format!("Failed to write {}: {}", destination, e))?;
+            return Ok(());
+        }
+    }
+    Err(format!("Release archive does not contain {}", BINARY_NAME))
+}
+
 impl zed::Extension for FleetExtension {
     fn new() -> Self {
         Self {
             cached_binary_path: None,
+            checked_for_update: false,
         }
     }
 
     fn language_server_command(
         &mut self,
-        _language_server_id: &LanguageServerId,
+        language_server_id: &LanguageServerId,
         worktree: &zed::Worktree,
     ) -> Result<zed::Command> {
-        let binary_path = self.get_binary_path(worktree)?;
+        let binary_path = self.get_binary_path(language_server_id, worktree).inspect_err(|e| {
+            zed::set_language_server_installation_status(
+                language_server_id,
+                &zed::LanguageServerInstallationStatus::Failed(e.clone()),
+            );
+        })?;
 
         Ok(zed::Command {
             command: binary_path,